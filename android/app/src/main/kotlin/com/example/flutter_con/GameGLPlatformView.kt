@@ -53,6 +53,8 @@ class GameGLSurfaceView(
 
     init {
         setEGLContextClientVersion(2)
+        // RGBA8, 16-bit depth, 8-bit stencil for the engine's clip masks
+        setEGLConfigChooser(8, 8, 8, 8, 16, 8)
         setRenderer(renderer)
         renderMode = RENDERMODE_CONTINUOUSLY
     }
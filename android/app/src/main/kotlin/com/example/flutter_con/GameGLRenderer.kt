@@ -24,6 +24,9 @@ class GameGLRenderer : GLSurfaceView.Renderer {
     override fun onSurfaceCreated(gl: GL10?, config: EGLConfig?) {
         // Initialize Rust game engine
         gameHandle = GameNative.gameInit(width, height)
+        if (gameHandle != 0L) {
+            GameNative.gameWarmup(gameHandle)
+        }
     }
 
     override fun onSurfaceChanged(gl: GL10?, width: Int, height: Int) {
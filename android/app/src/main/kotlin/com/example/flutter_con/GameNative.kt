@@ -1,6 +1,14 @@
 package com.example.flutter_con
 
 object GameNative {
+    fun interface FrameListener {
+        fun onFrame(pixels: java.nio.ByteBuffer, width: Int, height: Int, timestampNs: Long)
+    }
+
+    fun interface LogListener {
+        fun onLog(level: Int, target: String, message: String)
+    }
+
     init {
         System.loadLibrary("game_engine")
     }
@@ -9,10 +17,294 @@ object GameNative {
     external fun gameInit(width: Int, height: Int): Long
     external fun gameResize(handle: Long, width: Int, height: Int)
     external fun gameUpdate(handle: Long)
-    external fun gameRender(handle: Long)
+    // False if nothing was drawn (on-demand rendering with an unchanged scene)
+    external fun gameRender(handle: Long): Boolean
+    // With on-demand rendering, use RENDERMODE_WHEN_DIRTY and requestRender() while gameNeedsRedraw is true
+    external fun gameSetRenderOnDemand(handle: Long, enabled: Boolean)
+    external fun gameNeedsRedraw(handle: Long): Boolean
+    // Choreographer frameTimeNanos; false means the frame was skipped and must not be presented
+    external fun gameRenderWithTimestamp(handle: Long, vsyncNs: Long): Boolean
+    external fun gameSetTargetFps(handle: Long, fps: Int): Boolean
+    // Self-driven loop: call on a HandlerThread owning the EGL context; the engine updates, renders and
+    // swaps on each Choreographer frame. Not for GLSurfaceView, whose GL thread has no Looper
+    external fun gameStartLoop(handle: Long): Boolean
+    external fun gameStopLoop(handle: Long): Boolean
+    @Deprecated("Use gameSetPlayerDirection with gameGetPlayerNode")
     external fun gameSetDirection(handle: Long, direction: Int)
+    external fun gameSetPlayerDirection(handle: Long, player: Long, direction: Int): Boolean
     external fun gameSetMode(handle: Long, mode: Int)
+    // Game states: "menu", "playing", "paused", "game_over" or added ones; changes arrive as
+    // EVENT_STATE_CHANGED. False for unknown states or moves the current one doesn't allow
+    external fun gameTransition(handle: Long, name: String): Boolean
+    // STATE_* or an added state's id
+    external fun gameGetState(handle: Long): Int
+    external fun gameGetStateName(handle: Long, state: Int): String?
+    // STATE_INVALID for an empty or taken name
+    external fun gameAddState(handle: Long, name: String, simulates: Boolean): Int
+    external fun gameAllowTransition(handle: Long, from: Int, to: Int): Boolean
+    // hook = address of a native GameStateHook, 0 to remove
+    external fun gameSetStateHook(handle: Long, state: Int, hook: Long, userData: Long): Boolean
+    external fun gameStartDaily(handle: Long, dateSeed: Int)
+    external fun gameExportChallengeCode(handle: Long): String?
+    external fun gameImportChallengeCode(handle: Long, code: String): Boolean
+    @Deprecated("Use gameTouchPlayer with gameGetPlayerNode")
     external fun gameTouch(handle: Long, x: Float, y: Float, action: Int)
+    external fun gameTouchPlayer(handle: Long, player: Long, x: Float, y: Float, action: Int): Boolean
+    // Which gestures the engine claims (TOUCH_ROUTING_*); false for unknown policies
+    external fun gameSetTouchRouting(handle: Long, policy: Int): Boolean
+    // Whether the engine claimed the gesture of the last touch; unclaimed ones are left to the host
+    external fun gameTouchClaimed(handle: Long): Boolean
+    // How quickly a player let go mid-drag slows down (per second; default 4); 0 = no throwing
+    external fun gameSetThrowFriction(handle: Long, friction: Float): Boolean
+    external fun gameSpawnSprite(handle: Long, x: Float, y: Float, w: Float, h: Float, rgba: Int): Long
+    external fun gameLoadPrefabs(handle: Long, json: String): Boolean
+    external fun gameSpawnPrefab(handle: Long, name: String, x: Float, y: Float): Long
+    external fun gameSetSpritePosition(handle: Long, id: Long, x: Float, y: Float): Boolean
+    external fun gameSetSpriteVelocity(handle: Long, id: Long, vx: Float, vy: Float): Boolean
+    // Walks around obstacles; EVENT_PATH_FINISHED reports arrival or interruption
+    external fun gameMoveEntityTo(handle: Long, entity: Long, x: Float, y: Float): Boolean
+    external fun gameRemoveSprite(handle: Long, id: Long): Boolean
+    external fun gameGetSpriteCount(handle: Long): Int
+    // params: [burst, rate, duration, lifetimeMin, lifetimeMax, angle, spread, speedMin, speedMax,
+    //          speedEnd, gravityX, gravityY, sizeStart, sizeEnd]; colors 0xRRGGBBAA
+    external fun gameSpawnEmitter(handle: Long, x: Float, y: Float, params: FloatArray, colorStart: Int, colorEnd: Int): Long
+    external fun gameMoveEmitter(handle: Long, id: Long, x: Float, y: Float): Boolean
+    external fun gameStopEmitter(handle: Long, id: Long): Boolean
+    // Same params as gameSpawnEmitter; null turns bounce particles off
+    external fun gameSetBounceParticles(handle: Long, params: FloatArray?, colorStart: Int, colorEnd: Int): Boolean
+    external fun gameGetParticleCount(handle: Long): Int
+    // Ease a sprite's position, size or color (0xRRGGBBAA), or a node's transform, with EASING_*
+    // Returns the tween id (0 on failure); EVENT_TWEEN_FINISHED reports how it ended
+    external fun gameAnimatePosition(handle: Long, sprite: Long, x: Float, y: Float, durationMs: Int, easing: Int): Long
+    external fun gameAnimateSize(handle: Long, sprite: Long, w: Float, h: Float, durationMs: Int, easing: Int): Long
+    external fun gameAnimateColor(handle: Long, sprite: Long, rgba: Int, durationMs: Int, easing: Int): Long
+    external fun gameAnimateNode(
+        handle: Long, node: Long, x: Float, y: Float, rotation: Float, scaleX: Float, scaleY: Float,
+        durationMs: Int, easing: Int,
+    ): Long
+    external fun gameCancelTween(handle: Long, tween: Long): Boolean
+    // shape 0 = box (width x height), 1 = circle (width across); returns the collider id (0 on failure)
+    // Overlaps arrive as EVENT_COLLISION_ENTER / EXIT with both collider ids
+    external fun gameAddSpriteCollider(
+        handle: Long, sprite: Long, shape: Int, width: Float, height: Float, offsetX: Float, offsetY: Float,
+    ): Long
+    external fun gameAddNodeCollider(
+        handle: Long, node: Long, shape: Int, width: Float, height: Float, offsetX: Float, offsetY: Float,
+    ): Long
+    external fun gameRemoveCollider(handle: Long, collider: Long): Boolean
+    external fun gameCollidersTouching(handle: Long, a: Long, b: Long): Boolean
+    // [kind (COLLIDER_OWNER_*), id] of the topmost sprite or node whose collider covers a screen point
+    // (in points); null if none does
+    external fun gameHitTest(handle: Long, x: Float, y: Float): LongArray?
+    // GLSL in the engine's dialect (see game_api.h); null vertSrc uses the built-in quad
+    external fun gameRegisterShader(handle: Long, vertSrc: String?, fragSrc: String, name: String): Boolean
+    // null shader draws the sprite as a colored quad again
+    external fun gameSetSpriteMaterial(handle: Long, id: Long, shader: String?): Boolean
+    // 1-4 values: float to vec4
+    external fun gameSetSpriteUniform(handle: Long, id: Long, name: String, values: FloatArray): Boolean
+    external fun gameSetInputBufferMs(handle: Long, ms: Int)
+    // [kind, id, x, y, value, other]
+    external fun gamePollEvent(handle: Long): DoubleArray?
+    external fun gameSetPhysics(handle: Long, gravityX: Float, gravityY: Float, restitution: Float, damping: Float)
+    // Gameplay tunables (PARAM_*); false for unknown keys or out-of-range values
+    external fun gameSetParam(handle: Long, key: Int, value: Float): Boolean
+    // NaN for unknown keys
+    external fun gameGetParam(handle: Long, key: Int): Float
+    // Scoreboard stats (STAT_*); NaN for unknown keys. Changes arrive as EVENT_STAT_UPDATED
+    external fun gameGetStat(handle: Long, key: Int): Float
+    // Game time between EVENT_STAT_UPDATED reports (0 = off, the default)
+    external fun gameSetStatsInterval(handle: Long, intervalMs: Int)
+    // Zero the stats; the run's score is kept
+    external fun gameResetStats(handle: Long)
+    external fun gameDebugSetPlayerPosition(handle: Long, x: Float, y: Float): Boolean
+    // Hex challenge for this instance; gameEnableDevMode takes its signature by the dev key, in hex
+    external fun gameGetDevChallenge(handle: Long): String?
+    external fun gameEnableDevMode(handle: Long, token: String): Boolean
+    external fun gameGetRunSummary(handle: Long): LongArray?
+    external fun gameSetTickRate(handle: Long, hz: Int)
+    // Call on the GL thread; returns whether the simulation thread runs
+    external fun gameSetSimulationThread(handle: Long, enabled: Boolean): Boolean
+    external fun gameCaptureRegion(handle: Long, x: Float, y: Float, w: Float, h: Float, scale: Float): ByteArray?
+    external fun gameRenderShareCard(handle: Long, templateId: Int): ByteArray?
+    external fun gameCreateNode(handle: Long, w: Float, h: Float, rgba: Int): Long
+    external fun gameCreateTextNode(handle: Long, text: String, size: Float, rgba: Int, align: Int): Long
+    external fun gameSetNodeText(handle: Long, node: Long, text: String): Boolean
+    external fun gameGetPlayerNode(handle: Long): Long
+    // Local multiplayer: extra players (0 past 7), steered like the primary player until bound
+    external fun gameAddPlayer(handle: Long, x: Float, y: Float, rgba: Int): Long
+    external fun gameRemovePlayer(handle: Long, player: Long): Boolean
+    external fun gameBindPlayerGamepad(handle: Long, player: Long, pad: Int): Boolean
+    // Region in points; the player heads for a finger down in it
+    external fun gameBindPlayerTouchRegion(handle: Long, player: Long, x: Float, y: Float, w: Float, h: Float): Boolean
+    external fun gameUnbindPlayer(handle: Long, player: Long): Boolean
+    external fun gameSetPlayerColor(handle: Long, player: Long, rgba: Int): Boolean
+    // null draws the built-in player image again
+    external fun gameSetPlayerTexture(handle: Long, player: Long, name: String?): Boolean
+    external fun gameGetNodePosition(handle: Long, node: Long): FloatArray?
+    external fun gameSetNodeTransform(handle: Long, id: Long, x: Float, y: Float, rotation: Float, scaleX: Float, scaleY: Float): Boolean
+    external fun gameSetNodeZ(handle: Long, id: Long, z: Int): Boolean
+    external fun gameAttachNode(handle: Long, child: Long, parent: Long): Boolean
+    external fun gameDetachNode(handle: Long, id: Long): Boolean
+    external fun gameDestroyNode(handle: Long, id: Long): Boolean
+    // Host draw list (DRAW_* commands, layout in game_api.h); a list of just the version byte clears it
+    external fun gameSubmitDrawList(handle: Long, data: ByteArray): Boolean
+    // Font (TTF/OTF bytes) for draw-list text; the first is primary, later ones are fallbacks
+    external fun gameRegisterFont(handle: Long, data: ByteArray): Boolean
+    external fun gameSetTextDirection(handle: Long, direction: Int): Boolean
+    // Free glyph atlas pages (all of them unless keepVisible), e.g. from onTrimMemory; returns pages freed
+    external fun gameTrimGlyphAtlas(handle: Long, keepVisible: Boolean): Int
+    // Free rebuildable memory (TRIM_MEMORY_*) from onTrimMemory, on the GL thread; false for unknown levels
+    external fun gameTrimMemory(handle: Long, level: Int): Boolean
+    // [pages, maxPages, glyphs, occupancy, textureBytes, rasterized, evicted]
+    external fun gameGetGlyphAtlasStats(handle: Long): DoubleArray?
+    // Drop picture `id`'s cached raster (DRAW_PICTURE); false if it wasn't cached
+    external fun gameInvalidatePicture(handle: Long, id: Int): Boolean
+    external fun gameLoadAtlas(handle: Long, json: String, png: ByteArray): Long
+    external fun gameSetNodeAnimation(handle: Long, node: Long, atlas: Long, name: String?, fps: Float, mode: Int): Boolean
+    // Tiled (TMX/JSON) or LDtk map at world (x, y); tilesets use textures named after the image file stem
+    external fun gameLoadTilemap(handle: Long, data: ByteArray, x: Float, y: Float, scale: Float): Long
+    external fun gameRemoveTilemap(handle: Long, map: Long): Boolean
+    external fun gameSetTilemapLayerVisible(handle: Long, map: Long, name: String, visible: Boolean): Boolean
+    external fun gameTilemapIsSolid(handle: Long, x: Float, y: Float): Boolean
+    external fun gameShowQr(handle: Long, data: String, size: Float, ecLevel: Int, quietZone: Int): Boolean
+    external fun gameHideQr(handle: Long)
+    // Widgets above the game, anchored with TEXT_ALIGN_* bits; taps arrive as EVENT_BUTTON_PRESSED
+    external fun gameUiCreateLabel(handle: Long, text: String, size: Float, rgba: Int, anchor: Int, x: Float, y: Float): Long
+    external fun gameUiCreateButton(handle: Long, text: String, size: Float, textRgba: Int, fillRgba: Int, anchor: Int, x: Float, y: Float, width: Float, height: Float): Long
+    external fun gameUiCreateProgressBar(handle: Long, rgba: Int, anchor: Int, x: Float, y: Float, width: Float, height: Float): Long
+    external fun gameUiSetText(handle: Long, widget: Long, text: String): Boolean
+    external fun gameUiSetProgress(handle: Long, widget: Long, value: Float): Boolean
+    external fun gameUiSetVisible(handle: Long, widget: Long, visible: Boolean): Boolean
+    external fun gameUiRemove(handle: Long, widget: Long): Boolean
+    external fun gameLoadTextureFromBytes(handle: Long, data: ByteArray, name: String): Boolean
+    external fun gameLoadTextureFromPath(handle: Long, path: String): Boolean
+    external fun gameLoadTextureAsync(handle: Long, data: ByteArray, name: String): Long
+    external fun gameLoadTextureFromPathAsync(handle: Long, path: String): Long
+    external fun gameNegotiate(requestedFeatures: Long): Long
+    external fun gameGetFeatures(handle: Long): Long
+    // [fps, cpuMs, gpuMs, frameMs, frameMsP95, frameCount, culledSprites, culledNodes, culledParticles,
+    //  gpuClearMs, gpuSceneMs, gpuUiMs]
+    external fun gameGetFrameStats(handle: Long): FloatArray?
+    // [targetFps, refreshHz, rendered, skippedPaced, skippedUnchanged]
+    external fun gameGetPacingStats(handle: Long): DoubleArray?
+    // PowerManager thermal status mapped to THERMAL_*; hotter states draw fewer particles, frames and effects
+    external fun gameSetThermalState(handle: Long, state: Int): Boolean
+    // PowerManager.isPowerSaveMode
+    external fun gameSetLowPowerMode(handle: Long, enabled: Boolean)
+    external fun gameSetPowerScaling(handle: Long, enabled: Boolean)
+    // [rendererMs, engineMs, warmupMs, firstFrameMs]
+    external fun gameGetInitTimings(handle: Long): FloatArray?
+    // [published, delivered, dropped, coalesced, maxLatencyMs] for outbound events, then for input
+    external fun gameGetEventStats(handle: Long): DoubleArray?
+    external fun gameSetDebugOverlay(handle: Long, enabled: Boolean): Boolean
+    // All zero turns post-processing off; false if a value is out of range
+    external fun gameSetPostFx(
+        handle: Long,
+        bloomIntensity: Float,
+        bloomThreshold: Float,
+        bloomSpread: Float,
+        vignetteStrength: Float,
+        vignetteRadius: Float,
+        lutIntensity: Float,
+    ): Boolean
+    // PNG of N tiles of N x N pixels (red across, green down, blue per tile); null removes it
+    external fun gameSetColorLut(handle: Long, png: ByteArray?): Boolean
+    external fun gameSetClearColor(handle: Long, r: Float, g: Float, b: Float, a: Float)
+    external fun gameSetBackgroundGradient(handle: Long, topRgba: Int, bottomRgba: Int)
+    external fun gameClearBackgroundGradient(handle: Long)
+    // Why this thread's last call was dropped before reaching the GL thread (CALL_*)
+    external fun gameLastCallError(): Int
+    external fun gameLastStringError(): Int
+    external fun gameSetScaleFactor(handle: Long, scale: Float)
+    // WindowInsets (system bars | display cutout) in pixels
+    external fun gameSetSafeAreaInsets(handle: Long, top: Float, bottom: Float, left: Float, right: Float): Boolean
+    // From onConfigurationChanged, before the surface is resized
+    external fun gameSetOrientation(handle: Long, orientation: Int): Boolean
+    external fun gameSetRotationPolicy(handle: Long, policy: Int): Boolean
+    // Camera onto the world (see game_api.h); zoom 0.05..20, rotation in radians clockwise
+    external fun gameSetCamera(handle: Long, x: Float, y: Float, zoom: Float, rotation: Float): Boolean
+    // Ease the camera toward the player, rate per second (~5); 0 stops following
+    external fun gameSetCameraFollow(handle: Long, rate: Float): Boolean
+    external fun gameResetCamera(handle: Long)
+    // World size in points (0 x 0 = screen size)
+    external fun gameSetWorldSize(handle: Long, width: Float, height: Float): Boolean
+    // [x, y] world point under a screen point (in points, not pixels); null for a bad handle
+    external fun gameScreenToWorld(handle: Long, x: Float, y: Float): FloatArray?
+    // [x, y] world point under a point of screen space SCREEN_SPACE_*; null for a bad handle or space
+    external fun gameScreenToWorldIn(handle: Long, x: Float, y: Float, space: Int): FloatArray?
+    // [x, y] where a world point showed in the last frame, in screen space SCREEN_SPACE_* (for placing
+    // views over entities); null for a bad handle or space
+    external fun gameWorldToScreen(handle: Long, x: Float, y: Float, space: Int): FloatArray?
+    // Level of detail (0, 0 = full detail); false if a value is negative or not finite
+    external fun gameSetLod(handle: Long, detailMinPx: Float, simplifyBelowZoom: Float): Boolean
+    // Batched sprite and image-node drawing (on by default); off draws each through egui
+    external fun gameSetSpriteBatching(handle: Long, enabled: Boolean)
+    external fun gameKeyEvent(handle: Long, keycode: Int, action: Int, modifiers: Int): Boolean
+    external fun gameTextInput(handle: Long, text: String): Boolean
+    external fun gamePollStateDiff(handle: Long): ByteArray?
+    external fun gameResetStateDiff(handle: Long)
+    external fun gameSaveState(handle: Long): ByteArray?
+    external fun gameRestoreState(handle: Long, data: ByteArray): Boolean
+    external fun gameStartRecording(handle: Long): Boolean
+    external fun gameStopRecording(handle: Long): ByteArray?
+    external fun gameStartReplay(handle: Long, data: ByteArray): Boolean
+    external fun gameFinishReplay(handle: Long)
+    external fun gameStopReplay(handle: Long)
+    external fun gameIsReplaying(handle: Long): Boolean
+    external fun gameTagMask(handle: Long, name: String): Long
+    external fun gameSetSpriteTags(handle: Long, id: Long, tags: Long): Boolean
+    external fun gameSetNodeTags(handle: Long, id: Long, tags: Long): Boolean
+    external fun gameCountEntitiesWithTag(handle: Long, tags: Long): Int
+    external fun gameDespawnGroup(handle: Long, tags: Long): Int
+    external fun gameGamepadEvent(handle: Long, padId: Int, control: Int, value: Float): Boolean
+    external fun gameGamepadDisconnected(handle: Long, padId: Int)
+    external fun gameSetGamepadDeadZone(handle: Long, deadZone: Float)
+    external fun gameSensorEvent(handle: Long, sensorType: Int, x: Float, y: Float, z: Float, timestamp: Long): Boolean
+    external fun gameGetTilt(handle: Long): FloatArray?
+    external fun gameSetTiltGravity(handle: Long, strength: Float)
+    external fun gameRequestHaptic(handle: Long, pattern: Int): Boolean
+    external fun gameSetHapticsEnabled(handle: Long, enabled: Boolean): Boolean
+    external fun gameLoadQuirks(json: String): Boolean
+    external fun gameGetQuirks(handle: Long): Int
+    external fun gameSetQuirks(handle: Long, quirks: Int): Boolean
+    external fun gameLoadSound(handle: Long, data: ByteArray): Long
+    external fun gameUnloadSound(handle: Long, id: Long): Boolean
+    external fun gamePlaySound(handle: Long, id: Long, volume: Float): Boolean
+    external fun gamePlayMusic(handle: Long, id: Long): Boolean
+    external fun gameStopMusic(handle: Long)
+    external fun gameSetVolume(handle: Long, channel: Int, volume: Float): Boolean
+    external fun gameSetBounceSound(handle: Long, id: Long): Boolean
+    external fun gameSetPalette(handle: Long, palette: Int): Boolean
+    external fun gameSetAnnouncementsEnabled(handle: Long, enabled: Boolean)
+    external fun gameSetAnnouncementText(handle: Long, key: String, text: String): Boolean
+    // Forward to View.announceForAccessibility
+    external fun gamePollAnnouncement(handle: Long): String?
+    // JSON draw list of the current frame, for golden-list tests
+    external fun gameDumpDisplayList(handle: Long): String?
+    // Slow-frame logging (0 disables) and the recent-frame timeline as Chrome trace JSON
+    external fun gameSetSpikeThreshold(handle: Long, thresholdMs: Float, logIntervalMs: Int)
+    external fun gameExportTimeline(handle: Long): String?
+    external fun gameSetAssistMode(handle: Long, enabled: Boolean)
+    external fun gameTriggerGpuCapture(handle: Long): Boolean
+    // glFinish + glGetError every frame (debug default) instead of fences and periodic error checks
+    external fun gameSetGpuValidation(handle: Long, enabled: Boolean): Boolean
+    // Gameplay recording: listener.onFrame(pixels: ByteBuffer, width: Int, height: Int, timestampNs: Long)
+    // runs on the GL thread; pixels are RGBA, top row first, and only valid during the call
+    external fun gameStartVideoCapture(handle: Long, listener: FrameListener, ringSize: Int): Boolean
+    external fun gameStopVideoCapture(handle: Long)
+    // hook: address of a GameRenderHook from another native library (0 removes), called on the GL thread
+    // at stage (RENDER_STAGE_*) with userData; the engine restores its GL state afterwards
+    external fun gameSetCustomRenderHook(handle: Long, hook: Long, userData: Long, stage: Int): Boolean
+    // priority is a nice value (Process.THREAD_PRIORITY_*); affinityMask bit n allows CPU n, 0 = any
+    external fun gameSetThreadHints(handle: Long, affinityMask: Long, priority: Int): Boolean
+    // Engine logs (all instances) up to maxLevel (LOG_*), on whichever thread logged; null or LOG_OFF stops
+    // Call on the GL thread right after gameInit so the first frame doesn't hitch
+    external fun gameWarmup(handle: Long): Boolean
+    external fun gameSetLogCallback(listener: LogListener?, maxLevel: Int)
+    // STATUS_POISONED after a caught panic: other calls do nothing until gameDestroy
+    external fun gameGetStatus(handle: Long): Int
+    // JSON {"message":..,"backtrace":..} of the panic that poisoned the instance
+    external fun gameLastCrash(handle: Long): String?
     external fun gameDestroy(handle: Long)
 
     // Direction constants matching Rust enum
@@ -27,6 +319,249 @@ object GameNative {
     const val TOUCH_UP = 1
     const val TOUCH_MOVE = 2
 
+    // Key action and modifier constants matching Rust keyboard module
+    const val KEY_DOWN = 0
+    const val KEY_UP = 1
+    const val KEY_REPEAT = 2
+    const val KEY_MOD_SHIFT = 1 shl 0
+    const val KEY_MOD_CTRL = 1 shl 1
+    const val KEY_MOD_ALT = 1 shl 2
+    const val KEY_MOD_META = 1 shl 3
+
+    // Gamepad control constants matching Rust gamepad module
+    const val PAD_DPAD_UP = 0
+    const val PAD_DPAD_DOWN = 1
+    const val PAD_DPAD_LEFT = 2
+    const val PAD_DPAD_RIGHT = 3
+    const val PAD_SOUTH = 4
+    const val PAD_EAST = 5
+    const val PAD_WEST = 6
+    const val PAD_NORTH = 7
+    const val PAD_START = 8
+    const val PAD_SELECT = 9
+    const val PAD_LEFT_SHOULDER = 10
+    const val PAD_RIGHT_SHOULDER = 11
+    const val PAD_LEFT_STICK_X = 32
+    const val PAD_LEFT_STICK_Y = 33
+    const val PAD_RIGHT_STICK_X = 34
+    const val PAD_RIGHT_STICK_Y = 35
+    const val PAD_LEFT_TRIGGER = 36
+    const val PAD_RIGHT_TRIGGER = 37
+
+    // Sensor type constants matching Rust sensors module
+    const val SENSOR_ACCELEROMETER = 0
+    const val SENSOR_GYROSCOPE = 1
+
+    // Orientations and rotation policies matching Rust orientation module
+    const val ORIENTATION_PORTRAIT = 0
+    const val ORIENTATION_LANDSCAPE = 1
+    const val ROTATION_REMAP_PROPORTIONAL = 0
+    const val ROTATION_REMAP_CLAMP = 1
+
+    // Thermal states matching Rust power module
+    const val THERMAL_NOMINAL = 0
+    const val THERMAL_FAIR = 1
+    const val THERMAL_SERIOUS = 2
+    const val THERMAL_CRITICAL = 3
+
+    // Trim levels matching Rust engine module (not Android's ComponentCallbacks2 values)
+    const val TRIM_MEMORY_MODERATE = 0
+    const val TRIM_MEMORY_BACKGROUND = 1
+
+    // Touch routing policies matching Rust input routing module
+    const val TOUCH_ROUTING_ALL = 0
+    const val TOUCH_ROUTING_ENTITIES = 1
+
+    // Screen coordinate spaces matching Rust engine module
+    const val SCREEN_SPACE_POINTS = 0
+    const val SCREEN_SPACE_PIXELS = 1
+    const val SCREEN_SPACE_SAFE_AREA = 2
+
+    // Text alignment bits matching Rust text module (one horizontal or'd with one vertical)
+    const val TEXT_ALIGN_LEFT = 0
+    const val TEXT_ALIGN_CENTER = 1
+    const val TEXT_ALIGN_RIGHT = 2
+    const val TEXT_ALIGN_TOP = 0
+    const val TEXT_ALIGN_MIDDLE = 4
+    const val TEXT_ALIGN_BOTTOM = 8
+
+    // Paragraph directions matching Rust text module
+    const val TEXT_DIRECTION_AUTO = 0
+    const val TEXT_DIRECTION_LTR = 1
+    const val TEXT_DIRECTION_RTL = 2
+
+    // Event kind constants matching Rust events module
+    const val EVENT_BOUNCE = 1
+    const val EVENT_ANIMATION_FINISHED = 2
+    const val EVENT_TEXTURE_READY = 3
+    const val EVENT_TEXTURE_FAILED = 4
+    const val EVENT_HAPTIC = 5
+    const val EVENT_REPLAY_FINISHED = 6
+    const val EVENT_FIRST_MEANINGFUL_FRAME = 7
+    const val EVENT_ENGINE_CRASHED = 8
+    const val EVENT_TWEEN_FINISHED = 9
+    const val EVENT_COLLISION_ENTER = 10
+    const val EVENT_COLLISION_EXIT = 11
+    const val EVENT_PATH_FINISHED = 12
+    const val EVENT_BUTTON_PRESSED = 13
+    const val EVENT_STATE_CHANGED = 14
+    const val EVENT_STAT_UPDATED = 15
+
+    // Built-in game states matching Rust state_machine module
+    const val STATE_MENU = 0
+    const val STATE_PLAYING = 1
+    const val STATE_PAUSED = 2
+    const val STATE_GAME_OVER = 3
+    const val STATE_INVALID = -1
+
+    // Haptic patterns matching Rust haptics module
+    const val HAPTIC_LIGHT = 0
+    const val HAPTIC_MEDIUM = 1
+    const val HAPTIC_HEAVY = 2
+    const val HAPTIC_SUCCESS = 3
+    const val HAPTIC_WARNING = 4
+    const val HAPTIC_ERROR = 5
+    const val HAPTIC_SELECTION = 6
+
+    // Volume channels matching Rust audio module
+    const val VOLUME_MASTER = 0
+    const val VOLUME_EFFECTS = 1
+    const val VOLUME_MUSIC = 2
+
+    // Color palettes matching Rust palette module
+    const val PALETTE_STANDARD = 0
+    const val PALETTE_DEUTERANOPIA = 1
+    const val PALETTE_PROTANOPIA = 2
+    const val PALETTE_TRITANOPIA = 3
+
+    // Easing curves matching Rust tween module
+    const val EASING_LINEAR = 0
+    const val EASING_EASE_IN = 1
+    const val EASING_EASE_OUT = 2
+    const val EASING_EASE_IN_OUT = 3
+    const val EASING_ELASTIC = 4
+    const val EASING_BOUNCE = 5
+
+    // Collider shapes matching Rust collision module
+    const val COLLIDER_BOX = 0
+    const val COLLIDER_CIRCLE = 1
+
+    // Collider owner kinds matching Rust collision module
+    const val COLLIDER_OWNER_SPRITE = 0
+    const val COLLIDER_OWNER_NODE = 1
+
+    // Animation loop modes matching Rust atlas module
+    const val LOOP_MODE_LOOP = 0
+    const val LOOP_MODE_ONCE = 1
+    const val LOOP_MODE_PING_PONG = 2
+
+    // Share card templates matching Rust share module
+    const val SHARE_TEMPLATE_SQUARE = 0
+    const val SHARE_TEMPLATE_STORY = 1
+
+    // QR error-correction levels matching Rust qr module
+    const val QR_EC_LOW = 0
+    const val QR_EC_MEDIUM = 1
+    const val QR_EC_QUARTILE = 2
+    const val QR_EC_HIGH = 3
+    const val QR_DEFAULT_QUIET_ZONE = 4
+
+    // Feature bits for gameNegotiate matching Rust capabilities module
+    const val FEATURE_PHYSICS = 1L shl 0
+    const val FEATURE_ASYNC_ASSETS = 1L shl 1
+    const val FEATURE_AUDIO = 1L shl 2
+    const val FEATURE_NETWORK = 1L shl 3
+    const val FEATURE_DEBUG_SERVER = 1L shl 4
+    const val FEATURE_HAPTICS = 1L shl 5
+
+    // Driver workaround bits matching Rust quirks module
+    const val QUIRK_SKIP_GL_FINISH = 1 shl 0
+    const val QUIRK_FORCE_GLES2 = 1 shl 1
+
+    // Dropped call reasons (gameLastCallError)
+    const val CALL_OK = 0
+    const val CALL_ERR_INBOX_FULL = 1
+    const val CALL_ERR_TIMED_OUT = 2
+
+    // String rejection reasons (gameLastStringError)
+    const val STRING_OK = 0
+    const val STRING_ERR_NULL = 1
+    const val STRING_ERR_INVALID_UTF8 = 2
+    const val STRING_ERR_INTERIOR_NUL = 3
+
+    // Log levels (gameSetLogCallback)
+    const val LOG_OFF = 0
+    const val LOG_ERROR = 1
+    const val LOG_WARN = 2
+    const val LOG_INFO = 3
+    const val LOG_DEBUG = 4
+    const val LOG_TRACE = 5
+
+    // Draw list commands and layers matching Rust draw_list module
+    const val DRAW_LIST_VERSION = 1
+    const val DRAW_RECT = 1
+    const val DRAW_IMAGE = 2
+    const val DRAW_PATH = 3
+    const val DRAW_TEXT = 4
+    const val DRAW_TRANSFORM = 5
+    const val DRAW_LAYER = 6
+    const val DRAW_VECTOR = 7
+    const val DRAW_FILL_NON_ZERO = 0
+    const val DRAW_FILL_EVEN_ODD = 1
+    const val DRAW_CAP_BUTT = 0
+    const val DRAW_CAP_ROUND = 1
+    const val DRAW_CAP_SQUARE = 2
+    const val DRAW_JOIN_MITER = 0
+    const val DRAW_JOIN_ROUND = 1
+    const val DRAW_JOIN_BEVEL = 2
+    const val DRAW_VERB_MOVE = 0
+    const val DRAW_VERB_LINE = 1
+    const val DRAW_VERB_QUAD = 2
+    const val DRAW_VERB_CUBIC = 3
+    const val DRAW_VERB_CLOSE = 4
+    const val DRAW_PAINT = 8
+    const val DRAW_PAINT_FILL = 0
+    const val DRAW_PAINT_STROKE = 1
+    const val DRAW_PAINT_SOLID = 0
+    const val DRAW_PAINT_LINEAR = 1
+    const val DRAW_PAINT_RADIAL = 2
+    const val DRAW_PAINT_TEXTURE = 3
+    const val DRAW_CLIP = 9
+    const val DRAW_CLIP_POP = 10
+    const val DRAW_CLIP_RECT = 0
+    const val DRAW_CLIP_ROUNDED_RECT = 1
+    const val DRAW_CLIP_PATH = 2
+    const val DRAW_PICTURE = 11
+    const val DRAW_PICTURE_END = 12
+    const val DRAW_LAYER_BELOW = 0
+    const val DRAW_LAYER_ABOVE = 1
+
+    // Instance status (gameGetStatus)
+    const val STATUS_OK = 0
+    const val STATUS_POISONED = 1
+    const val STATUS_INVALID = 2
+
+    // Render hook stages (gameSetCustomRenderHook)
+    const val RENDER_STAGE_BEFORE_WORLD = 0
+    const val RENDER_STAGE_AFTER_WORLD = 1
+    const val RENDER_STAGE_AFTER_HUD = 2
+
+    // Gameplay parameter keys matching Rust params module
+    const val PARAM_SPEED = 0
+    const val PARAM_AUTO_VELOCITY_X = 1
+    const val PARAM_AUTO_VELOCITY_Y = 2
+    const val PARAM_FRICTION = 3
+    const val PARAM_RESTITUTION = 4
+    const val PARAM_PLAYER_SIZE = 5
+
+    // Scoreboard stat keys matching Rust scoreboard module
+    const val STAT_SCORE = 0
+    const val STAT_BOUNCES = 1
+    const val STAT_DISTANCE = 2
+    const val STAT_AUTO_TIME = 3
+    const val STAT_MANUAL_TIME = 4
+    const val STAT_THROWS = 5
+
     // Game mode constants matching Rust enum
     const val MODE_MANUAL = 0
     const val MODE_AUTO = 1
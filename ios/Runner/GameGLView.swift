@@ -44,6 +44,9 @@ class GameGLView: GLKView {
 
         if !isInitialized && width > 0 && height > 0 {
             gameHandle = game_init(width, height)
+            if let handle = gameHandle {
+                _ = game_warmup(handle)
+            }
             isInitialized = true
             startRenderLoop()
         } else if let handle = gameHandle {
@@ -1,2 +1,2 @@
 #import "GeneratedPluginRegistrant.h"
-#import "game_engine.h"
+#import "../../rust/include/game_api.h"
@@ -0,0 +1,69 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Turn exports.txt into a linker version script so the shared library exports exactly
+/// the listed symbols (no Rust runtime or dependency symbols that could clash with
+/// other native plugins loaded into the same process)
+fn main() {
+    println!("cargo:rerun-if-changed=exports.txt");
+
+    let exports = fs::read_to_string("exports.txt").expect("read exports.txt");
+    let symbols: Vec<&str> = exports
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    #[cfg(feature = "bindings")]
+    generate_header();
+
+    // Version scripts are an ELF linker feature; iOS links the staticlib into the app instead
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if target_os != "android" && target_os != "linux" {
+        return;
+    }
+
+    // The JNI wrappers are only compiled for Android; a global entry for an undefined symbol
+    // fails the link with lld
+    let jni = target_os == "android";
+    let mut script = String::from("{\n  global:\n");
+    for symbol in symbols.iter().filter(|s| jni || !s.starts_with("Java_")) {
+        script.push_str(&format!("    {};\n", symbol));
+    }
+    script.push_str("  local:\n    *;\n};\n");
+
+    let path = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR")).join("exports.map");
+    fs::write(&path, script).expect("write version script");
+    println!("cargo:rustc-cdylib-link-arg=-Wl,--version-script={}", path.display());
+}
+
+/// Run cbindgen over the sources to write include/game_api.h (see cbindgen.toml)
+#[cfg(feature = "bindings")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src");
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"));
+    let mut config = cbindgen::Config::from_file(manifest_dir.join("cbindgen.toml")).expect("read cbindgen.toml");
+
+    // Constants come from src/ffi/constants.rs alone, not every `pub const` in the crate, and go first
+    let mut constants = config.clone();
+    constants.export.item_types = vec![cbindgen::ItemType::Constants];
+    constants.header = None;
+    constants.include_guard = None;
+    constants.sys_includes.clear();
+    constants.cpp_compat = false;
+    let mut defines = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(constants)
+        .with_src(manifest_dir.join("src").join("ffi").join("constants.rs"))
+        .generate()
+        .expect("generate C API constants")
+        .write(&mut defines);
+    config.after_includes = Some(format!("\n{}", String::from_utf8(defines).expect("constants are UTF-8")));
+
+    cbindgen::generate_with_config(&manifest_dir, config)
+        .expect("generate C bindings")
+        .write_to_file(manifest_dir.join("include").join("game_api.h"));
+}
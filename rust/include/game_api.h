@@ -0,0 +1,1752 @@
+// Generated by cbindgen from rust/src; do not edit. Regenerate with `cargo build --features bindings`
+
+#ifndef game_api_h
+#define game_api_h
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Event kinds (GameEventRecord::kind)
+// id = surface (0=left, 1=right, 2=top, 3=bottom, 4=obstacle), (x, y) = contact point, value = impact speed
+#define GAME_EVENT_BOUNCE 1
+
+// id = node whose once animation ended
+#define GAME_EVENT_ANIMATION_FINISHED 2
+
+// id = load id, (x, y) = texture size
+#define GAME_EVENT_TEXTURE_READY 3
+
+// id = load id
+#define GAME_EVENT_TEXTURE_FAILED 4
+
+// id = GAME_HAPTIC_* pattern
+#define GAME_EVENT_HAPTIC 5
+
+// No fields; host input is accepted again
+#define GAME_EVENT_REPLAY_FINISHED 6
+
+// No fields; the startup splash is gone and the scene was rendered
+#define GAME_EVENT_FIRST_MEANINGFUL_FRAME 7
+
+// No fields; a call panicked and the instance is poisoned (game_last_crash)
+#define GAME_EVENT_ENGINE_CRASHED 8
+
+// id = tween, value = 1 if it reached its end, 0 if cancelled, replaced or its entity was removed
+#define GAME_EVENT_TWEEN_FINISHED 9
+
+// id and other = colliders that started overlapping (lower slot first), (x, y) = middle of the overlap,
+// value = its depth
+#define GAME_EVENT_COLLISION_ENTER 10
+
+// id and other = colliders that stopped overlapping or were removed
+#define GAME_EVENT_COLLISION_EXIT 11
+
+// id = sprite, (x, y) = where it stopped, value = 1 if it reached the end of its path, 0 if given
+// another, moved by the host or removed
+#define GAME_EVENT_PATH_FINISHED 12
+
+// id = UI button tapped (the touch went down and came up on it)
+#define GAME_EVENT_BUTTON_PRESSED 13
+
+// id = game state entered, other = state left
+#define GAME_EVENT_STATE_CHANGED 14
+
+// id = GAME_STAT_*, value = its new value
+#define GAME_EVENT_STAT_UPDATED 15
+
+// Share card templates: 1080x1080
+#define GAME_SHARE_TEMPLATE_SQUARE 0
+
+// 1080x1920
+#define GAME_SHARE_TEMPLATE_STORY 1
+
+// Run summary flags (RunSummary::flags)
+#define GAME_RUN_FLAG_DEV_MODE (1 << 0)
+
+#define GAME_RUN_FLAG_TAMPER_ATTEMPT (1 << 1)
+
+#define GAME_RUN_FLAG_TUNED (1 << 2)
+
+#define GAME_RUN_FLAG_STATE_MUTATED (1 << 3)
+
+// Resumed with game_restore_state
+#define GAME_RUN_FLAG_RESTORED (1 << 4)
+
+// Optional subsystems for game_negotiate
+#define GAME_FEATURE_PHYSICS (1 << 0)
+
+#define GAME_FEATURE_ASYNC_ASSETS (1 << 1)
+
+#define GAME_FEATURE_AUDIO (1 << 2)
+
+#define GAME_FEATURE_NETWORK (1 << 3)
+
+#define GAME_FEATURE_DEBUG_SERVER (1 << 4)
+
+#define GAME_FEATURE_HAPTICS (1 << 5)
+
+// Driver workarounds (game_get_quirks bits): no glFinish per frame; gpu_ms reads ~0
+#define GAME_QUIRK_SKIP_GL_FINISH (1 << 0)
+
+// GLSL ES 1.00 shaders; read at game_init only
+#define GAME_QUIRK_FORCE_GLES2 (1 << 1)
+
+// Reasons a call from off the render thread is dropped (game_last_call_error)
+#define GAME_CALL_OK 0
+
+#define GAME_CALL_ERR_INBOX_FULL 1
+
+#define GAME_CALL_ERR_TIMED_OUT 2
+
+// Reasons a string argument is rejected (game_last_string_error)
+#define GAME_STRING_OK 0
+
+#define GAME_STRING_ERR_NULL 1
+
+#define GAME_STRING_ERR_INVALID_UTF8 2
+
+#define GAME_STRING_ERR_INTERIOR_NUL 3
+
+// Log levels for game_set_log_callback
+#define GAME_LOG_OFF 0
+
+#define GAME_LOG_ERROR 1
+
+#define GAME_LOG_WARN 2
+
+#define GAME_LOG_INFO 3
+
+#define GAME_LOG_DEBUG 4
+
+#define GAME_LOG_TRACE 5
+
+// Device orientation
+#define GAME_ORIENTATION_PORTRAIT 0
+
+#define GAME_ORIENTATION_LANDSCAPE 1
+
+// What a rotation does to entity positions: same place relative to the world's edges (default)
+#define GAME_ROTATION_REMAP_PROPORTIONAL 0
+
+// Same coordinates; the player is kept inside
+#define GAME_ROTATION_REMAP_CLAMP 1
+
+// Screen coordinate spaces for game_screen_to_world_in and game_world_to_screen: points from the
+// surface's top left (logical pixels over the view)
+#define GAME_SCREEN_SPACE_POINTS 0
+
+// Physical pixels from the surface's top left, like touches
+#define GAME_SCREEN_SPACE_PIXELS 1
+
+// Points from the safe area's top left (widgets inside the insets)
+#define GAME_SCREEN_SPACE_SAFE_AREA 2
+
+// Thermal states, as ProcessInfo.ThermalState
+#define GAME_THERMAL_NOMINAL 0
+
+// Fewer particles, paced frames capped at 60
+#define GAME_THERMAL_FAIR 1
+
+// Half the particles, 30 fps, no post-processing
+#define GAME_THERMAL_SERIOUS 2
+
+// A quarter of the particles, 30 fps, no post-processing
+#define GAME_THERMAL_CRITICAL 3
+
+// Render hook stages: after the clear, under obstacles, sprites and the scene
+#define GAME_RENDER_STAGE_BEFORE_WORLD 0
+
+// Over the scene and particles, under the HUD
+#define GAME_RENDER_STAGE_AFTER_WORLD 1
+
+// Over the HUD, before post effects and the debug overlay
+#define GAME_RENDER_STAGE_AFTER_HUD 2
+
+// State diff encoding (game_poll_state_diff): version byte, then tagged little-endian records
+#define GAME_STATE_DIFF_VERSION 1
+
+// float, points
+#define GAME_STATE_PLAYER_X 1
+
+// float, points
+#define GAME_STATE_PLAYER_Y 2
+
+// uint32_t, 0xRRGGBBAA
+#define GAME_STATE_PLAYER_TINT 3
+
+// uint8_t, 0=manual, 1=auto
+#define GAME_STATE_MODE 4
+
+// uint8_t, 0 or 1
+#define GAME_STATE_DRAGGING 5
+
+// uint32_t
+#define GAME_STATE_SCORE 6
+
+// uint32_t, 0 = free play
+#define GAME_STATE_LEVEL_SEED 7
+
+// uint64_t id, float x, y, w, h, uint32_t rgba
+#define GAME_STATE_SPRITE 16
+
+// uint64_t id
+#define GAME_STATE_SPRITE_REMOVED 17
+
+// Game states; games start in GAME_STATE_PLAYING. Menu, paused and game over hold the world still
+#define GAME_STATE_MENU 0
+
+#define GAME_STATE_PLAYING 1
+
+#define GAME_STATE_PAUSED 2
+
+#define GAME_STATE_GAME_OVER 3
+
+#define GAME_INVALID_STATE 4294967295
+
+// Gameplay tunables for game_set_param / game_get_param (default, accepted range)
+// Manual mode player speed, points/s (300, >= 0)
+#define GAME_PARAM_SPEED 0
+
+// Auto mode starting velocity outside levels, points/s (250)
+#define GAME_PARAM_AUTO_VELOCITY_X 1
+
+// (200)
+#define GAME_PARAM_AUTO_VELOCITY_Y 2
+
+// Thrown player slowdown per second (4, >= 0; 0 = no throwing)
+#define GAME_PARAM_FRICTION 3
+
+// Speed kept bouncing off walls in Auto mode (1, 0..1)
+#define GAME_PARAM_RESTITUTION 4
+
+// Side of the player's box, points (200, >= 1)
+#define GAME_PARAM_PLAYER_SIZE 5
+
+// Scoreboard stats for game_get_stat and GAME_EVENT_STAT_UPDATED
+// Bounces this run (reset when a level starts)
+#define GAME_STAT_SCORE 0
+
+// Bounces since the engine started or game_reset_stats
+#define GAME_STAT_BOUNCES 1
+
+// Points the player traveled, dragged and thrown included
+#define GAME_STAT_DISTANCE 2
+
+// Seconds of game time in Auto mode
+#define GAME_STAT_AUTO_TIME 3
+
+// Seconds of game time in Manual mode
+#define GAME_STAT_MANUAL_TIME 4
+
+// Drags released fast enough to throw the player
+#define GAME_STAT_THROWS 5
+
+// Easing curves for game_animate_*
+#define GAME_EASING_LINEAR 0
+
+#define GAME_EASING_EASE_IN 1
+
+#define GAME_EASING_EASE_OUT 2
+
+#define GAME_EASING_EASE_IN_OUT 3
+
+// Overshoots and springs back
+#define GAME_EASING_ELASTIC 4
+
+// Bounces to rest on the end value
+#define GAME_EASING_BOUNCE 5
+
+// Collider shapes: width x height, axis-aligned
+#define GAME_COLLIDER_BOX 0
+
+// Width across; height is ignored
+#define GAME_COLLIDER_CIRCLE 1
+
+// What a collider is attached to
+#define GAME_COLLIDER_OWNER_SPRITE 0
+
+#define GAME_COLLIDER_OWNER_NODE 1
+
+// Text alignment: one horizontal value | one vertical value
+#define GAME_TEXT_ALIGN_LEFT 0
+
+#define GAME_TEXT_ALIGN_CENTER 1
+
+#define GAME_TEXT_ALIGN_RIGHT 2
+
+#define GAME_TEXT_ALIGN_TOP 0
+
+#define GAME_TEXT_ALIGN_MIDDLE 4
+
+#define GAME_TEXT_ALIGN_BOTTOM 8
+
+// Host draw list format (game_submit_draw_list): version byte, then commands, each a tag byte and
+// little-endian fields. Coordinates are points, colors 0xRRGGBBAA (uint32), strings a uint32 byte
+// length then UTF-8
+#define GAME_DRAW_LIST_VERSION 1
+
+// x, y, w, h f32, fill u32, stroke width f32, stroke u32
+#define GAME_DRAW_RECT 1
+
+// Texture name, x, y, w, h f32, u0, v0, u1, v1 f32, tint u32
+#define GAME_DRAW_IMAGE 2
+
+// Closed u8, count u32, count x (x, y f32), fill u32, stroke width f32, stroke u32
+#define GAME_DRAW_PATH 3
+
+// x, y f32 (top left), size f32, color u32, text
+#define GAME_DRAW_TEXT 4
+
+// a, b, c, d, tx, ty f32 for the following commands (text: position only)
+#define GAME_DRAW_TRANSFORM 5
+
+// u8 layer (GAME_DRAW_LAYER_*) for the following commands
+#define GAME_DRAW_LAYER 6
+
+// Vector path, filled then stroked: fill u32, fill rule u8, stroke width f32, stroke u32, cap u8, join u8,
+// dash count u8 (<= 16), dash/gap lengths f32, dash offset f32, verb count u32, then per verb a
+// GAME_DRAW_VERB_* byte and its points as x, y f32 pairs (move 1, line 1, quad 2, cubic 3, close 0)
+#define GAME_DRAW_VECTOR 7
+
+#define GAME_DRAW_FILL_NON_ZERO 0
+
+#define GAME_DRAW_FILL_EVEN_ODD 1
+
+#define GAME_DRAW_CAP_BUTT 0
+
+#define GAME_DRAW_CAP_ROUND 1
+
+#define GAME_DRAW_CAP_SQUARE 2
+
+#define GAME_DRAW_JOIN_MITER 0
+
+#define GAME_DRAW_JOIN_ROUND 1
+
+#define GAME_DRAW_JOIN_BEVEL 2
+
+#define GAME_DRAW_VERB_MOVE 0
+
+#define GAME_DRAW_VERB_LINE 1
+
+#define GAME_DRAW_VERB_QUAD 2
+
+#define GAME_DRAW_VERB_CUBIC 3
+
+#define GAME_DRAW_VERB_CLOSE 4
+
+// Paint replacing the fill or stroke color of the following vector commands: target u8, kind u8, then
+// solid: nothing (back to the command's color); linear: x0, y0, x1, y1 f32, stops; radial: cx, cy, radius f32,
+// stops; texture: texture name, x, y, w, h f32 (where the whole image lands), tint u32. Stops are a u8 count
+// (1-16) then offset f32 (ascending) and color u32 each. Coordinates are the path's, before the transform
+// Tessellated vector commands are cached, so resubmitting unchanged shapes is cheap
+#define GAME_DRAW_PAINT 8
+
+#define GAME_DRAW_PAINT_FILL 0
+
+#define GAME_DRAW_PAINT_STROKE 1
+
+#define GAME_DRAW_PAINT_SOLID 0
+
+#define GAME_DRAW_PAINT_LINEAR 1
+
+#define GAME_DRAW_PAINT_RADIAL 2
+
+#define GAME_DRAW_PAINT_TEXTURE 3
+
+// Clip the following commands of the same layer until GAME_DRAW_CLIP_POP: kind u8, then rect: x, y, w, h f32;
+// rounded rect: x, y, w, h, radius f32; path: fill rule u8, verb count u32 and verbs as in GAME_DRAW_VECTOR.
+// Clips nest up to 16 deep per layer and follow the transform; shapes other than rects need a stencil buffer
+#define GAME_DRAW_CLIP 9
+
+#define GAME_DRAW_CLIP_POP 10
+
+#define GAME_DRAW_CLIP_RECT 0
+
+#define GAME_DRAW_CLIP_ROUNDED_RECT 1
+
+#define GAME_DRAW_CLIP_PATH 2
+
+// Picture: id u32, x, y, w, h f32 (moved by the transform), then commands up to GAME_DRAW_PICTURE_END.
+// They're rasterized once into a texture over those bounds and it's drawn instead until their bytes change or
+// game_invalidate_picture(id). No nesting or layer changes inside; clips opened inside must be popped inside
+#define GAME_DRAW_PICTURE 11
+
+#define GAME_DRAW_PICTURE_END 12
+
+// Beneath the level obstacles
+#define GAME_DRAW_LAYER_BELOW 0
+
+// Above the scene nodes, beneath the HUD (default)
+#define GAME_DRAW_LAYER_ABOVE 1
+
+// Paragraph direction for text: each line's first strong character
+#define GAME_TEXT_DIRECTION_AUTO 0
+
+#define GAME_TEXT_DIRECTION_LTR 1
+
+#define GAME_TEXT_DIRECTION_RTL 2
+
+// How much game_trim_memory frees: memory warning while on screen, keep what the frame draws
+#define GAME_TRIM_MEMORY_MODERATE 0
+
+// Entering the background: drop everything that can be rebuilt
+#define GAME_TRIM_MEMORY_BACKGROUND 1
+
+// Compressed texture formats (game_get_compressed_texture_formats bits)
+#define GAME_TEXTURE_FORMAT_ETC2 (1 << 0)
+
+#define GAME_TEXTURE_FORMAT_ASTC (1 << 1)
+
+// Animation loop modes
+#define GAME_LOOP_MODE_LOOP 0
+
+#define GAME_LOOP_MODE_ONCE 1
+
+#define GAME_LOOP_MODE_PING_PONG 2
+
+// QR error-correction levels (~7%, 15%, 25%, 30% recoverable)
+#define GAME_QR_EC_LOW 0
+
+#define GAME_QR_EC_MEDIUM 1
+
+#define GAME_QR_EC_QUARTILE 2
+
+#define GAME_QR_EC_HIGH 3
+
+// Recommended quiet zone for game_show_qr, in modules (the engine takes any)
+#define GAME_QR_DEFAULT_QUIET_ZONE 4
+
+// Which touch gestures the engine claims, from the next touch down on: every gesture (the default)
+#define GAME_TOUCH_ROUTING_ALL 0
+
+// Gestures going down on a UI button, the player or an entity with a collider
+#define GAME_TOUCH_ROUTING_ENTITIES 1
+
+// Key actions and modifier bits for game_key_event
+#define GAME_KEY_DOWN 0
+
+#define GAME_KEY_UP 1
+
+#define GAME_KEY_REPEAT 2
+
+#define GAME_KEY_MOD_SHIFT (1 << 0)
+
+#define GAME_KEY_MOD_CTRL (1 << 1)
+
+#define GAME_KEY_MOD_ALT (1 << 2)
+
+// Command on Apple keyboards
+#define GAME_KEY_MOD_META (1 << 3)
+
+// Gamepad controls for game_gamepad_event
+#define GAME_PAD_DPAD_UP 0
+
+#define GAME_PAD_DPAD_DOWN 1
+
+#define GAME_PAD_DPAD_LEFT 2
+
+#define GAME_PAD_DPAD_RIGHT 3
+
+// A / Cross
+#define GAME_PAD_SOUTH 4
+
+#define GAME_PAD_EAST 5
+
+#define GAME_PAD_WEST 6
+
+#define GAME_PAD_NORTH 7
+
+#define GAME_PAD_START 8
+
+#define GAME_PAD_SELECT 9
+
+#define GAME_PAD_LEFT_SHOULDER 10
+
+#define GAME_PAD_RIGHT_SHOULDER 11
+
+// -1..1
+#define GAME_PAD_LEFT_STICK_X 32
+
+// -1..1, +y down (negate GCController yAxis)
+#define GAME_PAD_LEFT_STICK_Y 33
+
+#define GAME_PAD_RIGHT_STICK_X 34
+
+#define GAME_PAD_RIGHT_STICK_Y 35
+
+// 0..1
+#define GAME_PAD_LEFT_TRIGGER 36
+
+#define GAME_PAD_RIGHT_TRIGGER 37
+
+// Motion sensors for game_sensor_event
+// Axes follow Android's device frame (x right, y up, z out of the screen; flat face-up reads +z)
+// CoreMotion reports acceleration in g with the opposite sign: pass -9.81 * userAcceleration+gravity
+// m/s^2
+#define GAME_SENSOR_ACCELEROMETER 0
+
+// rad/s
+#define GAME_SENSOR_GYROSCOPE 1
+
+// Haptic patterns for GAME_EVENT_HAPTIC and game_request_haptic: UIImpactFeedbackGenerator styles
+#define GAME_HAPTIC_LIGHT 0
+
+#define GAME_HAPTIC_MEDIUM 1
+
+#define GAME_HAPTIC_HEAVY 2
+
+// UINotificationFeedbackGenerator types
+#define GAME_HAPTIC_SUCCESS 3
+
+#define GAME_HAPTIC_WARNING 4
+
+#define GAME_HAPTIC_ERROR 5
+
+// UISelectionFeedbackGenerator
+#define GAME_HAPTIC_SELECTION 6
+
+// No sound (needs GAME_FEATURE_AUDIO, built with the `audio` cargo feature)
+#define GAME_INVALID_SOUND 0
+
+// Volume channels for game_set_volume
+#define GAME_VOLUME_MASTER 0
+
+#define GAME_VOLUME_EFFECTS 1
+
+#define GAME_VOLUME_MUSIC 2
+
+// Color palettes for game_set_palette (bounce tints, drag highlight, status text)
+#define GAME_PALETTE_STANDARD 0
+
+#define GAME_PALETTE_DEUTERANOPIA 1
+
+#define GAME_PALETTE_PROTANOPIA 2
+
+#define GAME_PALETTE_TRITANOPIA 3
+
+// Instance status (game_get_status)
+#define GAME_STATUS_OK 0
+
+// A call panicked; other calls return defaults until game_destroy
+#define GAME_STATUS_POISONED 1
+
+// Null handle
+#define GAME_STATUS_INVALID 2
+
+
+// What the engine gives up to draw less power
+typedef struct Degradation Degradation;
+
+// What a GameHandle points at: the engine, confined to the thread that created it (the one owning
+// the GL context), and an inbox for calls made on other threads
+// Hosts calling from a platform thread (touch handlers, Flutter method channels) get their call run
+// on the render thread at its next engine call instead of racing it. Calls returning nothing are
+// queued without waiting for that; hosts rendering on demand request a frame after making one
+typedef struct GameInstance GameInstance;
+
+// Opaque handle for FFI
+typedef GameInstance *GameHandle;
+
+// Level-of-detail rules for dense scenes, evaluated for each entity every frame from how large it's
+// drawn. All zero is off: everything is drawn in full detail
+typedef struct {
+  // Entities drawn smaller than this many screen pixels (their larger side) lose outlines and custom
+  // shaders, and particles this small aren't drawn
+  float detail_min_px;
+  // Below this camera zoom every sprite and obstacle is a plain square-cornered rect without custom
+  // shaders
+  float simplify_below_zoom;
+} LodSettings;
+
+// Pacing counters for game_get_pacing_stats
+typedef struct {
+  // Frame rate cap, including any power scaling limit (0 = every vsync)
+  uint32_t target_fps;
+  // Display refresh rate estimated from vsync timestamps (0 until known)
+  float refresh_hz;
+  // Frames drawn by game_render_with_timestamp
+  uint64_t rendered;
+  // Vsyncs skipped to hold the cap, or because they were already rendered
+  uint64_t skipped_paced;
+  // Vsyncs skipped because the frame would have looked the same as the one on screen
+  uint64_t skipped_unchanged;
+} PacingStats;
+
+// Effects applied to the scene and HUD before presenting (game_set_post_fx)
+// All zero is off: frames are painted straight to the surface with no extra passes
+typedef struct {
+  // How much of the blurred highlights is added back (0 = no bloom)
+  float bloom_intensity;
+  // Brightness (brightest channel, 0-1) above which pixels glow
+  float bloom_threshold;
+  // Blur width; 1 spreads highlights about 8 pixels
+  float bloom_spread;
+  // Darkening at the corners (0 = no vignette, 1 = black)
+  float vignette_strength;
+  // Where darkening starts, from the center (0) to the corners (1)
+  float vignette_radius;
+  // Blend toward the color LUT's colors (0 = off; needs game_set_color_lut)
+  float lut_intensity;
+} PostFxSettings;
+
+// Entities left out of a frame because they were off screen (with a margin) or, for particles, too
+// small to see (LodSettings::detail_min_px), by kind
+typedef struct {
+  uint32_t sprites;
+  // Scene nodes, including the player
+  uint32_t nodes;
+  uint32_t particles;
+} CullStats;
+
+// GPU time of a frame's passes in milliseconds, measured with timer queries
+// (EXT_disjoint_timer_query on GLES). With cpu_ms, shows whether frames are CPU or GPU bound
+typedef struct {
+  float clear_ms;
+  // The world, host draw lists and everything else beneath the HUD
+  float scene_ms;
+  // egui's HUD and the debug overlay
+  float ui_ms;
+} GpuPassStats;
+
+// Frame timing summary over the recent window, exported to the host
+typedef struct {
+  // Frames per second from the average frame interval
+  float fps;
+  // Average CPU time spent in game_render, in milliseconds
+  float cpu_ms;
+  // Average time waiting for the GPU to finish the frame, in milliseconds
+  float gpu_ms;
+  // Average interval between frames, in milliseconds
+  float frame_ms;
+  // 95th percentile frame interval, in milliseconds
+  float frame_ms_p95;
+  // Frames rendered since init
+  uint64_t frame_count;
+  // Entities the last frame skipped for lying outside the camera's view
+  CullStats culled;
+  // Average GPU time per pass from timer queries; all zero where the GPU has none
+  GpuPassStats gpu_passes;
+} FrameStats;
+
+// Where startup time went, in milliseconds, for game_get_init_timings
+typedef struct {
+  // Renderer creation: driver queries, quirk detection, egui painter (0 without a renderer)
+  float renderer_ms;
+  // Engine construction after the renderer
+  float engine_ms;
+  // game_warmup (0 if it wasn't called)
+  float warmup_ms;
+  // From engine construction to the first frame showing the scene (0 until then)
+  float first_frame_ms;
+} InitTimings;
+
+// Counters for one event path, exported to the host
+typedef struct {
+  // Events that entered the path
+  uint64_t published;
+  // Events that reached their consumer (host poll or a rendered frame)
+  uint64_t delivered;
+  // Events discarded because a queue was full
+  uint64_t dropped;
+  // Events superseded by a newer one before delivery (e.g. touch moves within a frame)
+  uint64_t coalesced;
+  // Longest time an event waited for delivery since init, in milliseconds
+  float max_latency_ms;
+} EventPathStats;
+
+// Both event paths, for game_get_event_stats and the debug overlay
+typedef struct {
+  // Engine to host (game_poll_event)
+  EventPathStats outbound;
+  // Host to engine (touch, direction, keys, text, gamepad, sensors)
+  EventPathStats input;
+} EventStats;
+
+// Bytes handed to the host; release with game_free_buffer
+// `data` is null (and `len` 0) on failure
+typedef struct {
+  uint8_t *data;
+  size_t len;
+} GameBuffer;
+
+// Id handed across FFI for arena-stored objects
+// Low 32 bits: slot index + 1 (so 0 is never valid), high 32 bits: slot generation
+typedef uint64_t Id;
+
+// Scene node id handed across FFI
+typedef Id NodeId;
+
+// A game state: the built-in ones below, then game_add_state's in order
+typedef uint32_t StateId;
+
+// Flat C representation of an event
+// Field meaning depends on kind:
+// - EVENT_BOUNCE: id = Surface, (x, y) = contact point, value = impact speed
+// - EVENT_ANIMATION_FINISHED: id = node
+// - EVENT_TEXTURE_READY: id = load id, (x, y) = texture size
+// - EVENT_TEXTURE_FAILED: id = load id
+// - EVENT_HAPTIC: id = HapticPattern
+// - EVENT_REPLAY_FINISHED: no fields
+// - EVENT_FIRST_MEANINGFUL_FRAME: no fields
+// - EVENT_ENGINE_CRASHED: no fields (message and backtrace via game_last_crash)
+// - EVENT_TWEEN_FINISHED: id = tween, value = 1 if it reached its end value, 0 if interrupted
+// - EVENT_COLLISION_ENTER: id and other = the colliders (lower slot first), (x, y) = middle of the
+//   overlap, value = its depth
+// - EVENT_COLLISION_EXIT: id and other = the colliders
+// - EVENT_PATH_FINISHED: id = sprite, (x, y) = where it stopped, value = 1 if it reached the end, 0 if
+//   interrupted
+// - EVENT_BUTTON_PRESSED: id = widget
+// - EVENT_STATE_CHANGED: id = state entered, other = state left
+// - EVENT_STAT_UPDATED: id = Stat, value = its new value
+typedef struct {
+  uint32_t kind;
+  uint64_t id;
+  float x;
+  float y;
+  float value;
+  // Second entity of two-entity events
+  uint64_t other;
+} GameEventRecord;
+
+// Sprite id handed across FFI
+typedef Id SpriteId;
+
+// Emitter id handed across FFI
+typedef Id EmitterId;
+
+// How an emitter throws particles and how they change over their lifetime
+// Angles are radians with y down (0 = right, π/2 = down)
+typedef struct {
+  // Particles emitted at once when the emitter starts
+  uint32_t burst;
+  // Particles per second emitted after that (0 for a burst only)
+  float rate;
+  // Seconds a continuous emitter runs (0 = until stopped)
+  float duration;
+  // Seconds each particle lives, picked between the two
+  float lifetime_min;
+  float lifetime_max;
+  // Direction particles are thrown and the cone around it (TAU = all around)
+  float angle;
+  float spread;
+  // Speed at birth in points per second, picked between the two
+  float speed_min;
+  float speed_max;
+  // Speed at death as a fraction of the birth speed (1 = constant, 0 = comes to rest)
+  float speed_end;
+  // Acceleration in points per second squared (e.g. gravity)
+  float gravity_x;
+  float gravity_y;
+  // Edge of the particle's square in points, at birth and at death
+  float size_start;
+  float size_end;
+  // 0xRRGGBBAA at birth and at death
+  uint32_t color_start;
+  uint32_t color_end;
+} EmitterConfig;
+
+// Tween id handed across FFI
+typedef Id TweenId;
+
+// Collider id handed across FFI
+typedef Id ColliderId;
+
+// Summary of the current run, exported for leaderboard validation
+typedef struct {
+  // Level seed (0 when no level is active)
+  uint32_t seed;
+  // RUN_FLAG_* bits
+  uint32_t flags;
+  // Number of rejected mutation attempts
+  uint32_t rejected_mutations;
+  // Milliseconds since the run started
+  uint64_t elapsed_ms;
+} RunSummary;
+
+// Glyph atlas occupancy for game_get_glyph_atlas_stats
+typedef struct {
+  // Texture pages allocated (PAGE_SIZE square each)
+  uint32_t pages;
+  // Most pages before cold ones are evicted
+  uint32_t max_pages;
+  // Glyph bitmaps cached
+  uint32_t glyphs;
+  // Fraction of the pages' pixels handed out to glyphs (0 to 1)
+  float occupancy;
+  // Texture memory held by the pages
+  uint64_t texture_bytes;
+  // Glyphs rasterized since init; evicted ones count again when they're drawn again
+  uint64_t rasterized;
+  // Glyphs dropped to make room for others, or by game_trim_glyph_atlas
+  uint64_t evicted;
+} GlyphAtlasStats;
+
+// Atlas id handed across FFI
+typedef Id AtlasId;
+
+// Tilemap id handed across FFI
+typedef Id TilemapId;
+
+// Id of an in-engine UI widget (game_ui_*)
+typedef Id WidgetId;
+
+// Sound id handed across FFI
+typedef Id SoundId;
+
+// Host allocation function: returns `size` bytes or null
+typedef uint8_t *(*GameAllocFn)(size_t size);
+
+// Host free function for memory returned by the matching AllocFn
+typedef void (*GameFreeFn)(uint8_t *ptr, size_t size);
+
+// Receives each recorded frame: tightly packed RGBA8, top row first, `width * 4` bytes per row
+// `pixels` is only valid during the call; copy it (or feed the encoder) before returning
+typedef void (*GameFrameCallback)(void *user_data, const uint8_t *pixels, uint32_t width, uint32_t height, uint64_t timestamp_ns);
+
+// Receives forwarded log records: `level` is GAME_LOG_* (1 = error .. 5 = trace), `target` the Rust
+// module path; both strings are NUL-terminated UTF-8 and only valid during the call
+// Called on whichever thread logged (render thread, task threads, audio), possibly concurrently
+typedef void (*GameLogCallback)(void *user_data, int32_t level, const char *target, const char *message);
+
+// Host GL drawing injected into a frame: called on the render thread with the engine's target bound
+// (the surface, or the offscreen scene when post effects are on) and its viewport covering it
+// `stage` is RENDER_STAGE_*, `width` / `height` the target's size in pixels
+typedef void (*GameRenderHook)(void *user_data, uint32_t stage, uint32_t width, uint32_t height);
+
+// Host code run on the render thread as `state` is left (`entering` false) or entered, before the
+// change is reported as GAME_EVENT_STATE_CHANGED
+typedef void (*GameStateHook)(void *user_data, StateId state, bool entering);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Declare which optional subsystems (GAME_FEATURE_* bits) the host intends to use
+// Returns the subset this build and device actually provide; only those are initialized
+// Applies to instances created by later game_init calls; without it everything available is granted
+uint64_t game_negotiate(uint64_t requested_features);
+
+// Load a driver quirk database (JSON, see quirks::load_rules) replacing any loaded before
+// Rules are matched against the GL vendor/renderer/version by later game_init calls
+// Returns false if the JSON is invalid; the previous database stays in effect
+bool game_load_quirks(const char *json);
+
+// Driver workarounds in effect for this instance (GAME_QUIRK_* bits)
+uint32_t game_get_quirks(GameHandle handle);
+
+// Replace this instance's workarounds (GAME_QUIRK_* bits; unknown bits are ignored)
+// Returns false for a headless instance
+bool game_set_quirks(GameHandle handle, uint32_t quirks);
+
+// Features granted to this instance (GAME_FEATURE_* bits)
+uint64_t game_get_features(GameHandle handle);
+
+// Why the calling thread's last call from off the render thread was dropped (0 = it wasn't)
+// 1 = the render thread's inbox was full, 2 = it didn't run a call returning a value within 250 ms
+// Calls returning nothing are only queued for the render thread, so they can only fail with 1
+int32_t game_last_call_error(void);
+
+// Why the calling thread's last string argument was rejected (0 = it was accepted)
+// 1 = null, 2 = invalid UTF-8, 3 = interior NUL (StringError)
+int32_t game_last_string_error(void);
+
+// Forward engine log records (level, target, message) to `callback` as well as logcat / os_log
+// `max_level` is GAME_LOG_ERROR (1) .. GAME_LOG_TRACE (5); a null callback or 0 stops forwarding
+// Process-wide, for every instance; may be called before game_init
+// `user_data` must stay valid until the callback is replaced, and a replaced callback may still be
+// running on another thread when this returns
+void game_set_log_callback(GameLogCallback callback, void *user_data, uint32_t max_level);
+
+// Initialize the game engine
+// Called from GLSurfaceView.onSurfaceCreated() on Android
+// Called from GLKView.setup() on iOS
+// Returns null on failure
+GameHandle game_init(uint32_t width, uint32_t height);
+
+// Initialize an engine rendering into its own off-screen `width` x `height` surface
+// For desktop CI and golden-image tests: render with game_render, read back with game_capture_region
+// The GL context stays current on the calling thread; make every later call from it
+// Needs the `headless` cargo feature and a libEGL at runtime (Mesa's surfaceless platform needs no display)
+// Returns null on failure
+GameHandle game_init_headless(uint32_t width, uint32_t height);
+
+// Handle surface size changes
+// Called from GLSurfaceView.onSurfaceChanged()
+void game_resize(GameHandle handle, uint32_t width, uint32_t height);
+
+// Set physical pixels per logical point (devicePixelRatio / UIScreen.scale)
+// Game coordinates, sizes and capture regions are in points; touches stay in physical pixels
+// The player keeps its place on screen; ignored unless finite and positive
+void game_set_scale_factor(GameHandle handle, float scale);
+
+// Point the camera at world point (x, y) (points) with `zoom` (1 = 1:1, 2 = twice as big) turned
+// `rotation` radians clockwise. Obstacles, sprites, scene nodes and particles are drawn through it and
+// touches are mapped back into the world; the background, host draw lists and HUD stay put
+// Returns false (camera unchanged) for non-finite values or a zoom outside 0.05..=20
+bool game_set_camera(GameHandle handle, float x, float y, float zoom, float rotation);
+
+// Smoothly follow the player: the camera center closes `rate` of the distance per second
+// (exponentially; around 5 feels natural). 0 stops following; false for negative or non-finite rates
+bool game_set_camera_follow(GameHandle handle, float rate);
+
+// Back to the default view (world origin at the screen's top left, 1:1) and stop following
+void game_reset_camera(GameHandle handle);
+
+// Physical pixels covered by notches, home indicators and system bars at each screen edge (e.g. the
+// window's safe-area or system-bar insets). UI widgets and the QR code are laid out clear of them, and
+// the player is kept out from under them. Returns false (insets unchanged) for negative or NaN values
+bool game_set_safe_area_insets(GameHandle handle, float top, float bottom, float left, float right);
+
+// Report the device orientation (GAME_ORIENTATION_*), before the resize a rotation brings
+// When that resize arrives the player, sprites and their paths are moved per the rotation policy
+// (game_set_rotation_policy) rather than only clamped into the new bounds. False for unknown values
+bool game_set_orientation(GameHandle handle, uint32_t orientation);
+
+// How rotations move entities (GAME_ROTATION_REMAP_*): proportionally to the world's new size
+// (the default), or not at all beyond keeping the player inside. False for unknown values
+bool game_set_rotation_policy(GameHandle handle, uint32_t policy);
+
+// Make the world `width` x `height` points instead of the screen's size: the player bounces off its
+// edges and levels are laid out over it. 0 x 0 goes back to the screen. False for other sizes <= 0
+bool game_set_world_size(GameHandle handle, float width, float height);
+
+// Write the world point under screen point (x, y) (points, not pixels) as the last frame showed it
+// Returns false for a null handle or output pointer
+bool game_screen_to_world(GameHandle handle, float x, float y, float *out_x, float *out_y);
+
+// Write the world point under point (x, y) of screen space `space` (GAME_SCREEN_SPACE_*) as the
+// last frame showed it. Returns false for a null handle or output pointer, or an unknown space
+bool game_screen_to_world_in(GameHandle handle, float x, float y, uint32_t space, float *out_x, float *out_y);
+
+// Write where world point (x, y) showed in the last frame, in screen space `space`
+// (GAME_SCREEN_SPACE_*), through the camera and the scale factor: for placing host widgets such as
+// tooltips over entities. Returns false for a null handle or output pointer, or an unknown space
+bool game_world_to_screen(GameHandle handle, float x, float y, uint32_t space, float *out_x, float *out_y);
+
+// Drop fine detail from entities drawn small and from wide views to keep dense scenes smooth (see
+// LodSettings); NULL turns it off. Returns false (settings unchanged) if a value is negative or not finite
+bool game_set_lod(GameHandle handle, const LodSettings *settings);
+
+// Current level-of-detail rules; false for a null handle or output pointer
+bool game_get_lod(GameHandle handle, LodSettings *out);
+
+// Draw runs of sprites and image nodes sharing a texture as one instanced draw each (on by default),
+// or each as its own egui mesh, to rule the batcher out when something draws wrong
+void game_set_sprite_batching(GameHandle handle, bool enabled);
+
+// Update game state
+// Called each frame before render
+// Runs zero or more fixed-rate ticks (see game_set_tick_rate)
+// Optimized: minimal allocations, no logging in hot path
+void game_update(GameHandle handle);
+
+// Render the game using egui
+// Called from GLSurfaceView.onDrawFrame()
+// Returns true if a frame was drawn; false for a zero-sized surface, or when rendering on demand
+// and nothing changed (the framebuffer is untouched then and need not be presented)
+bool game_render(GameHandle handle);
+
+// Render on demand: game_render and game_render_with_timestamp only draw when game_needs_redraw
+// is true, so a static scene (Manual mode, no touch) costs no GPU work. Off by default
+void game_set_render_on_demand(GameHandle handle, bool enabled);
+
+// Whether the next frame would differ from the last one drawn: the simulation or an animation is
+// running, or a command changed the scene. Hosts rendering on demand request frames while this is
+// true (check after game_update and after sending input or commands) and stop when it turns false
+bool game_needs_redraw(GameHandle handle);
+
+// Render for the vsync at `vsync_ns` (Choreographer frameTimeNanos, CADisplayLink timestamp in ns)
+// Holds the game_set_target_fps cap and skips frames identical to the one on screen, to save power
+// Returns true if a frame was drawn; on false the framebuffer is untouched and must not be presented
+bool game_render_with_timestamp(GameHandle handle, uint64_t vsync_ns);
+
+// Drive update and render from the display's vsync instead of per-frame host calls
+// Call on the render thread, with the GL context current. On Android it needs a Looper on that thread
+// (a HandlerThread owning the EGL context, not GLSurfaceView's GL thread): AChoreographer calls back
+// each vsync and the engine presents to the current EGL surface itself. On iOS, register a CADisplayLink
+// that calls game_loop_frame. Don't call game_update or game_render while the loop runs
+// Returns false if it was already running or no Choreographer is available
+bool game_start_loop(GameHandle handle);
+
+// Stop the loop game_start_loop started; false if it wasn't running
+bool game_stop_loop(GameHandle handle);
+
+// A loop frame for the display link vsync at `vsync_ns` (CADisplayLink targetTimestamp in ns): update,
+// then render per game_render_with_timestamp. Returns true if a frame was drawn and should be presented;
+// false when the loop isn't running
+bool game_loop_frame(GameHandle handle, uint64_t vsync_ns);
+
+// Cap game_render_with_timestamp to `fps` frames per second: 30, 60 or 120 (0, the default,
+// renders on every vsync). Frames land on the vsync grid, e.g. every other vsync for 30 on 60 Hz
+// Returns false (and keeps the current cap) for other values
+bool game_set_target_fps(GameHandle handle, uint32_t fps);
+
+// Frame pacing counters: cap, estimated refresh rate, frames drawn and vsyncs skipped
+// Returns false for a null handle or output pointer
+bool game_get_pacing_stats(GameHandle handle, PacingStats *out);
+
+// Report the device's thermal state (GAME_THERMAL_*: ProcessInfo.thermalState, or Android's
+// THERMAL_STATUS_* mapped onto it). While power scaling is on, hotter states emit fewer particles,
+// then also cap paced frames and skip post-processing. False for unknown states
+bool game_set_thermal_state(GameHandle handle, uint32_t state);
+
+// Report battery saver (Android) or Low Power Mode (iOS); it's treated like a fair thermal state
+void game_set_low_power_mode(GameHandle handle, bool enabled);
+
+// Turn off the engine's own scaling for heat and battery (on by default), e.g. when the host
+// lowers its frame rate and effects itself; the reported states are kept for turning it back on
+void game_set_power_scaling(GameHandle handle, bool enabled);
+
+// Do the first frame's one-time GPU work up front: upload the font atlas and player texture,
+// compile egui's shaders and prime the tessellator, so the first game_render doesn't hitch
+// Call on the render thread right after game_init; blocks briefly for the startup textures to decode
+// Returns false for a headless instance or null handle
+bool game_warmup(GameHandle handle);
+
+// Show or hide the on-screen debug overlay (FPS, entity count, draw calls, touches, last GL error)
+// Returns false if this build has no overlay (enable the `debug-overlay` cargo feature)
+bool game_set_debug_overlay(GameHandle handle, bool enabled);
+
+// Post-process presented frames with bloom, a vignette and color grading; NULL turns it all off
+// Captures and share cards stay unprocessed. Returns false (settings unchanged) if a value is out of range
+bool game_set_post_fx(GameHandle handle, const PostFxSettings *settings);
+
+// Current post-processing settings; false for a null handle or output pointer
+bool game_get_post_fx(GameHandle handle, PostFxSettings *out);
+
+// Color grading table for game_set_post_fx's lut_intensity: a PNG of N tiles of N x N pixels
+// side by side (red across each tile, green down, blue from tile to tile), N from 2 to 64
+// NULL removes it. Returns false (LUT unchanged) if the PNG can't be decoded or has another shape
+bool game_set_color_lut(GameHandle handle, const uint8_t *png, size_t len);
+
+// Wrap the next rendered frame in a KHR_debug group ("game_engine/game_trigger_gpu_capture")
+// so it can be found in an Android GPU Inspector or RenderDoc capture; under RenderDoc the
+// frame is also captured programmatically. AGI captures must still be started from AGI
+// Returns true if RenderDoc will capture the frame, false otherwise (or for a headless instance)
+bool game_trigger_gpu_capture(GameHandle handle);
+
+// End each frame with glFinish and glGetError (exact GPU times in frame stats, errors logged on the
+// frame that made them), or with a fence the next frame waits on and an error check every 60 frames,
+// which doesn't drain the GPU pipeline. On by default in debug builds, off in release
+// Returns false for a headless instance or null handle
+bool game_set_gpu_validation(GameHandle handle, bool enabled);
+
+// Record gameplay: every rendered frame (without the debug overlay) goes to `callback` as RGBA8,
+// top row first, a few frames after it was drawn (read back through a ring of `ring_size` GPU buffers;
+// 0 picks the default). Called on the render thread inside game_render; copy the pixels before returning
+// `user_data` must stay valid until game_stop_video_capture or game_destroy
+// Replaces a running recording; false without a renderer or on GL older than ES 3
+bool game_start_video_capture(GameHandle handle, GameFrameCallback callback, void *user_data, uint32_t ring_size);
+
+// Stop recording; frames still in flight are delivered before this returns
+// Call on the render thread
+void game_stop_video_capture(GameHandle handle);
+
+// Let the host draw into presented frames with its own GL calls (another native renderer sharing
+// the context): `hook` is called on the render thread at `stage`, one of RENDER_STAGE_BEFORE_WORLD (after the
+// clear), RENDER_STAGE_AFTER_WORLD (before the HUD) or RENDER_STAGE_AFTER_HUD (before post effects), with
+// the engine's target bound. Framebuffer, viewport, scissor, blend, depth/cull/stencil tests, color mask,
+// program, texture and buffer bindings are restored after it returns. NULL removes the stage's hook
+// `user_data` must stay valid until the hook is replaced or game_destroy; false for an unknown stage
+bool game_set_custom_render_hook(GameHandle handle, GameRenderHook hook, void *user_data, uint32_t stage);
+
+// Scheduling hints for the engine's internal threads (background tasks and any engine-driven loop),
+// e.g. to keep them on the big cores: `affinity_mask` bit n allows CPU n (0 = any), `priority` is a
+// nice value from -20 (most urgent) to 19 as in Process.setThreadPriority (0 = unchanged)
+// iOS has no affinity; there the priority picks a QoS class for threads started afterwards
+// Threads the host owns (the GL thread calling game_render) are left alone
+// Returns false if a running thread couldn't take the hints
+bool game_set_thread_hints(GameHandle handle, uint64_t affinity_mask, int32_t priority);
+
+// Frame timing over the last ~120 frames (FPS, CPU/GPU time, p95 frame interval, GPU time per pass)
+// Returns false for a null handle or output pointer
+bool game_get_frame_stats(GameHandle handle, FrameStats *out);
+
+// Startup cost breakdown: renderer and engine creation, game_warmup, time to the first scene frame
+// Returns false for a null handle or output pointer
+bool game_get_init_timings(GameHandle handle, InitTimings *out);
+
+// Counters for both event paths: outbound events (game_poll_event) and host input
+// Counts published, delivered, dropped and coalesced events plus the worst delivery latency
+// Returns false for a null handle or output pointer
+bool game_get_event_stats(GameHandle handle, EventStats *out);
+
+// Capture a screen rect (x, y, w, h in points) scaled by `scale` as RGBA8 pixels, top row first
+// The scene is re-rendered into a temporary framebuffer, so the crop stays sharp at any scale
+// Output size is (round(w * scale), round(h * scale)); pass out = null to query the size
+// Returns the number of bytes required, or -1 on failure; nothing is written if capacity is too small
+// Must be called on the GL thread
+int64_t game_capture_region(GameHandle handle, float x, float y, float w, float h, float scale, uint8_t *out, size_t capacity);
+
+// Install host allocation functions for buffers returned by the engine (e.g. share cards)
+// Must be called before the first buffer is returned; returns false otherwise or if either is null
+// Without an allocator, buffers come from the engine's allocator and must go back via game_free_buffer
+bool game_set_allocator(GameAllocFn alloc, GameFreeFn free);
+
+// Release a buffer returned by the engine (null buffers are ignored)
+// Calls the host free function if one was installed
+void game_free_buffer(GameBuffer buffer);
+
+// Compose a share card for `template_id` (SHARE_TEMPLATE_*) as PNG bytes
+// The card shows the screen around the player, the run's score and the logo
+// Returns an empty buffer on failure; release the result with game_free_buffer
+// Must be called on the GL thread
+GameBuffer game_render_share_card(GameHandle handle, uint32_t template_id);
+
+// Deprecated: game_set_player_direction with game_get_player_node
+// Steers the primary player; warns once through the log
+void game_set_direction(GameHandle handle, int32_t direction);
+
+// Handle direction input from Flutter for `player` (game_get_player_node) in Manual mode
+// Returns false if the node isn't a player
+// No logging in hot path for performance
+bool game_set_player_direction(GameHandle handle, NodeId player, int32_t direction);
+
+// Set game mode (Manual=0, Auto=1)
+void game_set_mode(GameHandle handle, int32_t mode);
+
+// Go to game state `name`: "menu", "playing" (the state games start in), "paused", "game_over" or
+// one added with game_add_state. Menu, paused and game over hold the world still, leaving touches to
+// UI buttons. Hooks of the state left and entered run, then GAME_EVENT_STATE_CHANGED is reported
+// False for unknown states and moves the current state doesn't allow
+bool game_transition(GameHandle handle, const char *name);
+
+// Current game state (GAME_STATE_* or a game_add_state id)
+StateId game_get_state(GameHandle handle);
+
+// Write state `state`'s name (NUL-terminated) into `out`
+// Returns the name's length, or -1 for unknown states or a buffer too small
+int32_t game_get_state_name(GameHandle handle, StateId state, char *out, size_t capacity);
+
+// Add game state `name`, the world moving on in it if `simulates`; no transition leads to or from it
+// until game_allow_transition. Returns its id, or GAME_INVALID_STATE for an empty or taken name
+StateId game_add_state(GameHandle handle, const char *name, bool simulates);
+
+// Let the game go from state `from` to state `to`; false for unknown states
+bool game_allow_transition(GameHandle handle, StateId from, StateId to);
+
+// Call `hook` on the render thread as `state` is left (`entering` false) and entered, before the
+// change event; NULL removes it. `user_data` must stay valid until the hook is replaced or
+// game_destroy; false for an unknown state
+bool game_set_state_hook(GameHandle handle, StateId state, GameStateHook hook, void *user_data);
+
+// Set the color the frame is cleared to before drawing (components 0.0..=1.0)
+void game_set_clear_color(GameHandle handle, float r, float g, float b, float a);
+
+// Cover the clear color with a vertical gradient (packed 0xRRGGBBAA, top to bottom)
+void game_set_background_gradient(GameHandle handle, uint32_t top_rgba, uint32_t bottom_rgba);
+
+// Remove the background gradient, leaving the flat clear color
+void game_clear_background_gradient(GameHandle handle);
+
+// Set the simulation tick rate in Hz (0 = variable timestep)
+// A fixed rate makes movement and physics identical across devices
+void game_set_tick_rate(GameHandle handle, uint32_t hz);
+
+// Run game logic on its own thread, stepping at the tick rate (60 Hz at a variable timestep), so slow
+// ticks and slow frames don't hold each other up. game_render then draws the simulation's snapshots,
+// interpolated a step behind, game_update does nothing, and every other call runs on the simulation
+// thread between steps. The engine's own GL work (custom shaders, cached pictures, post effects, render
+// hooks, captures, the debug overlay) waits until it stops
+// Call on the render thread; returns whether the simulation thread runs afterwards
+bool game_set_simulation_thread(GameHandle handle, bool enabled);
+
+// Pop the next outbound event into `out`
+// Returns false when the queue is empty
+// Still works on a poisoned instance, to deliver EVENT_ENGINE_CRASHED
+bool game_poll_event(GameHandle handle, GameEventRecord *out);
+
+// Engine state changed since the last poll, for hosts mirroring it in Dart
+// Encoding: version byte, then tagged little-endian records (GAME_STATE_* in game_api.h)
+// Returns an empty buffer when nothing changed; release the result with game_free_buffer
+GameBuffer game_poll_state_diff(GameHandle handle);
+
+// Make the next game_poll_state_diff carry every field (e.g. after the host rebuilt its mirror)
+void game_reset_state_diff(GameHandle handle);
+
+// Serialize player, mode, level, score and sprites so the host can persist them
+// across process death; release the result with game_free_buffer
+GameBuffer game_save_state(GameHandle handle);
+
+// Resume from game_save_state bytes; sprites keep their ids
+// Returns false (leaving the game untouched) for corrupt or incompatible data
+// Textures, atlases, sounds and scene nodes are not saved; reload them as at startup
+// Load the prefabs the save's sprites were spawned from first; it fails without them
+bool game_restore_state(GameHandle handle, const uint8_t *data, size_t len);
+
+// Start recording host input (direction, mode, touch, controller, sensor, level starts)
+// with tick indices, from a snapshot of the current state
+// Returns false in variable timestep mode (tick rate 0) or while a replay is playing
+bool game_start_recording(GameHandle handle);
+
+// Finish the recording started by game_start_recording and return it for game_start_replay
+// Empty when no recording was running; release with game_free_buffer
+GameBuffer game_stop_recording(GameHandle handle);
+
+// Restore a recording's starting state and replay its input at its fixed tick rate
+// Host input is ignored until GAME_EVENT_REPLAY_FINISHED or game_stop_replay
+// Returns false (leaving the game untouched) for corrupt or incompatible data
+bool game_start_replay(GameHandle handle, const uint8_t *data, size_t len);
+
+// Run the rest of the replay immediately instead of in real time (regression tests)
+void game_finish_replay(GameHandle handle);
+
+// Abandon a replay; the game continues from where it stopped
+void game_stop_replay(GameHandle handle);
+
+bool game_is_replaying(GameHandle handle);
+
+// Configure the Auto mode physics world
+// gravity in pixels/s^2, restitution 0..1 (1 = perfectly elastic; sets GAME_PARAM_RESTITUTION), damping per second
+// Requires the `physics` feature; ignored otherwise
+void game_set_physics(GameHandle handle, float gravity_x, float gravity_y, float restitution, float damping);
+
+// Tune gameplay without rebuilding: `key` is a GAME_PARAM_* (player speed, Auto mode starting
+// velocity, throw friction, wall restitution, player size), from the next tick on
+// False for unknown keys and values outside the key's range (game_get_param gives the current one)
+bool game_set_param(GameHandle handle, uint32_t key, float value);
+
+// Current value of a GAME_PARAM_* (its default until set); NaN for unknown keys
+float game_get_param(GameHandle handle, uint32_t key);
+
+// Current value of a GAME_STAT_* for scoreboards (bounces, distance traveled, time per mode,
+// throws); NaN for unknown keys. Changes also arrive as GAME_EVENT_STAT_UPDATED
+float game_get_stat(GameHandle handle, uint32_t key);
+
+// Report stats that changed as GAME_EVENT_STAT_UPDATED every `interval_ms` of game time
+// (e.g. 1000 for a scoreboard); 0, the default, stops the reports
+void game_set_stats_interval(GameHandle handle, uint32_t interval_ms);
+
+// Zero the stats (a new session); the current run's score is kept
+void game_reset_stats(GameHandle handle);
+
+// Set the input buffer window in milliseconds (0 disables buffering)
+// Direction taps and near-miss grabs within the window are applied on the next tick
+void game_set_input_buffer_ms(GameHandle handle, uint32_t ms);
+
+// Start the daily challenge for a date (e.g. 20261016 for yyyymmdd)
+// Everyone passing the same date_seed gets the same level and bounce colors
+void game_start_daily(GameHandle handle, uint32_t date_seed);
+
+// Write the active level's challenge code (NUL-terminated) into `out`
+// Returns the code length, or -1 if no level is active or the buffer is too small
+int32_t game_export_challenge_code(GameHandle handle, char *out, size_t capacity);
+
+// Start the level described by a challenge code shared by another player
+// Returns false if the code is malformed
+bool game_import_challenge_code(GameHandle handle, const char *code);
+
+// Spawn a sprite centered at (x, y) with size (w, h) and color 0xRRGGBBAA
+// Returns the sprite id, or 0 on failure
+SpriteId game_spawn_sprite(GameHandle handle, float x, float y, float w, float h, uint32_t rgba);
+
+// Add sprite prefabs from a JSON object of name to definition, replacing ones of the same name:
+// {"spike": {"size": [32, 32], "color": "#ff4040", "velocity": [0, 0], "tags": ["hazard"],
+//            "shader": "glow", "collider": {"shape": "circle", "size": [24, 24], "offset": [0, 4]}}}
+// Only size is required. Returns false (loading none) if any definition is invalid
+bool game_load_prefabs(GameHandle handle, const char *json);
+
+// Spawn prefab `name` centered at (x, y); saves store it by name and only what's changed since
+// Returns the sprite id, or 0 if there's no such prefab
+SpriteId game_spawn_prefab(GameHandle handle, const char *name, float x, float y);
+
+// Move a sprite to (x, y)
+// Returns false if the id is unknown or already removed
+bool game_set_sprite_position(GameHandle handle, SpriteId id, float x, float y);
+
+// Set a sprite's velocity in pixels per second (integrated in game_update)
+bool game_set_sprite_velocity(GameHandle handle, SpriteId id, float vx, float vy);
+
+// Send a sprite to (x, y) along a path around level obstacles and solid tiles, moving at a steady
+// speed like the player in Auto mode (its current speed, or 200 points per second if it's still)
+// EVENT_PATH_FINISHED reports when it arrives or is interrupted (another path, a position or velocity
+// set by the host, removal). Returns false for unknown sprites or if there's no way there
+bool game_move_entity_to(GameHandle handle, SpriteId entity, float x, float y);
+
+// Remove a sprite
+// Returns false if the id is unknown or already removed
+bool game_remove_sprite(GameHandle handle, SpriteId id);
+
+// Number of live sprites
+uint32_t game_get_sprite_count(GameHandle handle);
+
+// Start a particle emitter at (x, y): its burst is thrown at once, then `rate` particles per second
+// for `duration` seconds (0 = until game_stop_emitter). Particles are drawn above the scene
+// Returns the emitter id, or 0 if `config` is NULL or invalid or there are too many emitters
+EmitterId game_spawn_emitter(GameHandle handle, float x, float y, const EmitterConfig *config);
+
+// Move a continuous emitter to (x, y), e.g. to follow something
+// Returns false if the id is unknown or the emitter has finished
+bool game_move_emitter(GameHandle handle, EmitterId id, float x, float y);
+
+// Stop an emitter; particles it already threw live out their lifetime
+// Returns false if the id is unknown or the emitter has finished
+bool game_stop_emitter(GameHandle handle, EmitterId id);
+
+// Spray `config`'s burst each time the player bounces in Auto mode, its angle relative to the surface
+// normal (0 throws straight away from the wall); NULL turns it off. Returns false if `config` is invalid
+bool game_set_bounce_particles(GameHandle handle, const EmitterConfig *config);
+
+// Number of live particles
+uint32_t game_get_particle_count(GameHandle handle);
+
+// Ease a sprite from where it is to (x, y) over `duration_ms`, with `easing` GAME_EASING_*
+// Returns the tween id, reported by EVENT_TWEEN_FINISHED when it ends; 0 for unknown sprites or easings
+// A running tween on the same property ends early (reported as interrupted)
+TweenId game_animate_position(GameHandle handle, SpriteId sprite, float x, float y, uint32_t duration_ms, uint32_t easing);
+
+// Ease a sprite's size to w x h (see game_animate_position)
+TweenId game_animate_size(GameHandle handle, SpriteId sprite, float w, float h, uint32_t duration_ms, uint32_t easing);
+
+// Ease a sprite's color to `rgba` (0xRRGGBBAA; see game_animate_position)
+TweenId game_animate_color(GameHandle handle, SpriteId sprite, uint32_t rgba, uint32_t duration_ms, uint32_t easing);
+
+// Ease a scene node's local transform to the one given (as in game_set_node_transform; see
+// game_animate_position). The player node's translation stays driven by the game
+// Player nodes are rejected unless dev mode is enabled
+TweenId game_animate_node(GameHandle handle, NodeId node, float x, float y, float rotation, float scale_x, float scale_y, uint32_t duration_ms, uint32_t easing);
+
+// Stop a tween where it is; EVENT_TWEEN_FINISHED still follows (as interrupted)
+// Returns false if it already ended
+bool game_cancel_tween(GameHandle handle, TweenId tween);
+
+// Attach a collider to a sprite, centered on it plus (offset_x, offset_y)
+// shape GAME_COLLIDER_BOX is `width` x `height`; GAME_COLLIDER_CIRCLE is `width` across
+// Overlaps with other colliders are reported as EVENT_COLLISION_ENTER / EVENT_COLLISION_EXIT
+// Returns the collider id, or 0 for unknown sprites or shapes, or bad sizes; removed with the sprite
+ColliderId game_add_sprite_collider(GameHandle handle, SpriteId sprite, uint32_t shape, float width, float height, float offset_x, float offset_y);
+
+// Attach a collider to a scene node at its world origin plus (offset_x, offset_y); use
+// game_get_player_node for the player (see game_add_sprite_collider)
+ColliderId game_add_node_collider(GameHandle handle, NodeId node, uint32_t shape, float width, float height, float offset_x, float offset_y);
+
+// Remove a collider; EVENT_COLLISION_EXIT follows for each collider it was overlapping
+// Returns false if it's unknown or its entity is gone
+bool game_remove_collider(GameHandle handle, ColliderId collider);
+
+// Whether two colliders overlapped at the end of the last tick
+bool game_colliders_touching(GameHandle handle, ColliderId a, ColliderId b);
+
+// The topmost sprite or node whose collider covers screen point (x, y) (points, like
+// game_screen_to_world), so host gesture handlers can tell touches meant for the game from the rest
+// Writes its kind (GAME_COLLIDER_OWNER_*) to `out_kind` unless NULL
+// Returns its id, or 0 if no collider is there
+uint64_t game_hit_test(GameHandle handle, float x, float y, uint32_t *out_kind);
+
+// Compile GLSL as shader `name` for game_set_sprite_material, replacing any shader of that name
+// Sources skip the #version line and use ATTRIBUTE, VARYING, TEXTURE and FRAG_COLOR; the vertex
+// shader gets `a_pos` (0 to 1 across the sprite), NULL `vert_src` passes it on as `v_uv`
+// The fragment shader writes premultiplied color and may read u_color (the sprite's) and u_size (pixels)
+// Returns false if compiling fails (the log has why) or a string is invalid
+bool game_register_shader(GameHandle handle, const char *vert_src, const char *frag_src, const char *name);
+
+// Draw a sprite with a shader from game_register_shader (its uniforms start unset), or as a
+// colored quad again with NULL; returns false if the sprite or shader is unknown
+bool game_set_sprite_material(GameHandle handle, SpriteId id, const char *shader);
+
+// Set uniform `name` (float, vec2, vec3 or vec4 for 1-4 `count` values) of a sprite's material
+// Returns false if the sprite has no material, the name is u_color or u_size, or the values are invalid
+bool game_set_sprite_uniform(GameHandle handle, SpriteId id, const char *name, const float *values, uint32_t count);
+
+// Bit mask for a tag name ("enemy", "pickup", "ui"), registering the name on first use
+// Combine masks with | to require several tags; returns 0 on failure or once 64 tags exist
+uint64_t game_tag_mask(GameHandle handle, const char *name);
+
+// Replace a sprite's tags (mask from game_tag_mask; 0 clears them)
+bool game_set_sprite_tags(GameHandle handle, SpriteId id, uint64_t tags);
+
+// Replace a scene node's tags (mask from game_tag_mask; 0 clears them)
+bool game_set_node_tags(GameHandle handle, NodeId id, uint64_t tags);
+
+// Number of sprites and nodes carrying every tag in `tags`
+uint32_t game_count_entities_with_tag(GameHandle handle, uint64_t tags);
+
+// Remove every sprite and node carrying every tag in `tags` (nodes take their children along)
+// The player node is never removed; returns the number of tagged entities removed
+uint32_t game_despawn_group(GameHandle handle, uint64_t tags);
+
+// Challenge a dev-mode token must sign, as hex text (release with game_free_buffer)
+// Fresh for every engine instance, so a token only unlocks the instance it was signed for
+GameBuffer game_get_dev_challenge(GameHandle handle);
+
+// Enable dev mode with `token`: the hex ed25519 signature of "game_engine dev mode v1\0" followed by
+// the raw game_get_dev_challenge bytes, made with the key whose public half is GAME_DEV_PUBLIC_KEY
+// Debug builds start in dev mode; returns whether dev mode is now enabled
+bool game_enable_dev_mode(GameHandle handle, const char *token);
+
+// Debug: teleport the player (rejected unless dev mode is enabled)
+bool game_debug_set_player_position(GameHandle handle, float x, float y);
+
+// Write the current run summary (seed, RUN_FLAG_* bits) into `out`
+bool game_get_run_summary(GameHandle handle, RunSummary *out);
+
+// Create a scene node at the root (w, h = 0 creates an empty group)
+// Rect nodes draw a solid 0xRRGGBBAA quad centered on the node origin
+// Returns the node id, or 0 on failure
+NodeId game_create_node(GameHandle handle, float w, float h, uint32_t rgba);
+
+// Create a scene node at the root drawing `text` (UTF-8, lines split at '\n') `size` points per em in
+// 0xRRGGBBAA, shaped with the registered fonts (game_register_font). `align` (GAME_TEXT_ALIGN_* bits)
+// picks the corner or edge of the text's block placed on the node origin; it moves, turns and scales
+// with the node. Returns the node id, or 0 for a bad size, alignment or string
+NodeId game_create_text_node(GameHandle handle, const char *text, float size, uint32_t rgba, uint32_t align);
+
+// Replace a text node's text (UTF-8), e.g. to update a score; it's laid out again only when it changes
+// Returns false if the node doesn't draw text or the string is invalid
+bool game_set_node_text(GameHandle handle, NodeId node, const char *text);
+
+// Id of the player's scene node (attach children to it to build composite players)
+NodeId game_get_player_node(GameHandle handle);
+
+// Add a player for local multiplayer at world point (x, y), drawn as the player image tinted `rgba`
+// (0xRRGGBBAA). It moves in Manual mode like the primary player, steered by game_set_player_direction
+// and game_touch_player until bound to a controller (game_bind_player_*)
+// Returns its node id, or 0 once there are 7 besides the primary player
+NodeId game_add_player(GameHandle handle, float x, float y, uint32_t rgba);
+
+// Remove a player added with game_add_player; false for other nodes
+bool game_remove_player(GameHandle handle, NodeId player);
+
+// Steer an added player with controller `pad` (see game_gamepad_event), which stops steering the
+// primary player. False for other nodes, unknown pads and pads bound to another player
+bool game_bind_player_gamepad(GameHandle handle, NodeId player, uint32_t pad);
+
+// Steer an added player with touches in a screen region (points from the surface's top left): it
+// heads for the finger while one is down there, so each half of a tablet can steer its own player
+// False for other nodes or a region that isn't a positive finite size
+bool game_bind_player_touch_region(GameHandle handle, NodeId player, float x, float y, float w, float h);
+
+// Leave an added player to game_set_player_direction and game_touch_player only, releasing its
+// gamepad or touch region; false for other nodes
+bool game_unbind_player(GameHandle handle, NodeId player);
+
+// Tint a player 0xRRGGBBAA: an added one, or the primary one until its next bounce
+// False for nodes that aren't players
+bool game_set_player_color(GameHandle handle, NodeId player, uint32_t rgba);
+
+// Draw an added player with texture `name` (game_load_texture_*; drawn once loaded), or the built-in
+// player image for null. False for other nodes or a bad string
+bool game_set_player_texture(GameHandle handle, NodeId player, const char *name);
+
+// Write a node's world position (the player node's is the player's center) to out_x/out_y
+// Returns false for unknown nodes or null pointers
+bool game_get_node_position(GameHandle handle, NodeId node, float *out_x, float *out_y);
+
+// Set a node's local transform (translation in pixels, rotation in radians, scale)
+// The player node's translation is driven by the game and ignored here
+// Player nodes are rejected unless dev mode is enabled
+bool game_set_node_transform(GameHandle handle, NodeId id, float x, float y, float rotation, float scale_x, float scale_y);
+
+// Set a node's z-order (higher draws on top; ties keep tree order)
+bool game_set_node_z(GameHandle handle, NodeId id, int32_t z);
+
+// Attach a node under a parent (its transform becomes relative to the parent)
+// Returns false for unknown ids, cycles, or attaching the player under another node
+bool game_attach_node(GameHandle handle, NodeId child, NodeId parent);
+
+// Detach a node from its parent, making it a root
+bool game_detach_node(GameHandle handle, NodeId id);
+
+// Destroy a node and its descendants (the player node cannot be destroyed)
+bool game_destroy_node(GameHandle handle, NodeId id);
+
+// Replace the host draw list: rects, images, paths, vector art and text drawn every frame beneath the obstacles
+// or above the scene (GAME_DRAW_* commands in game_api.h, see draw_list module)
+// Submit a new list whenever the content changes; a list of just the version byte clears it
+// Returns false (keeping the previous list) for malformed data
+bool game_submit_draw_list(GameHandle handle, const uint8_t *data, size_t len);
+
+// Add a TrueType/OpenType font (copied) to the fallback chain for draw-list text and text nodes: the first
+// registered is primary, later ones supply characters it lacks. Text is shaped (ligatures, Arabic joining,
+// bidi) only once a font is registered, in lists submitted afterwards and in every text node. Returns false if the font can't be parsed
+bool game_register_font(GameHandle handle, const uint8_t *data, size_t len);
+
+// Paragraph direction for draw-list text and text nodes (GAME_TEXT_DIRECTION_*): AUTO takes each line's
+// from its first strong character; LTR and RTL force it, to match the host's Directionality so mixed-script
+// text reads the way Flutter shows it. Returns false for unknown directions
+bool game_set_text_direction(GameHandle handle, uint32_t direction);
+
+// Free glyph atlas pages (draw-list text) the current list's text doesn't use, or every page if `keep_visible`
+// is false, e.g. on a low-memory warning or before the GL context is torn down in the background. Text is
+// rasterized again as it's next drawn. Returns how many pages were freed (0 for a null handle)
+uint32_t game_trim_glyph_atlas(GameHandle handle, bool keep_visible);
+
+// Free memory the engine can rebuild (GAME_TRIM_MEMORY_*), from Android's onTrimMemory or an iOS
+// memory warning: glyph pages, tessellated paths and, when backgrounded, cached pictures,
+// post-processing targets and shaders. Call on the render thread; everything is made again lazily
+// as the next frame needs it. False for unknown levels
+bool game_trim_memory(GameHandle handle, uint32_t level);
+
+// Glyph atlas occupancy: pages, cached glyphs, fraction filled, texture memory, rasterizations and evictions
+// Returns false for a null handle or output pointer
+bool game_get_glyph_atlas_stats(GameHandle handle, GlyphAtlasStats *out);
+
+// Drop the cached raster of draw-list picture `id` (GAME_DRAW_PICTURE), so it's redrawn from its commands
+// on the next frame; needed when something the commands don't show changes, like a reloaded texture
+// Returns false if the picture wasn't cached
+bool game_invalidate_picture(GameHandle handle, uint32_t id);
+
+// Load a PNG from memory into the asset cache under `name`, replacing any texture of that name
+// SVG is rasterized at the scale factor (game_set_scale_factor) and redrawn when it changes
+// Loading "player" reskins the player; "logo" is used on share cards
+bool game_load_texture_from_bytes(GameHandle handle, const uint8_t *data, size_t len, const char *name);
+
+// Load a PNG or SVG file into the asset cache, named after the file stem ("dl/player.png" -> "player")
+bool game_load_texture_from_path(GameHandle handle, const char *path);
+
+// Decode a PNG or SVG on the background thread and add it to the asset cache as `name`
+// The bytes are copied, so the caller may free them immediately
+// Completion is reported by EVENT_TEXTURE_READY / EVENT_TEXTURE_FAILED with the returned load id
+// Returns the load id, or 0 on failure
+uint64_t game_load_texture_async(GameHandle handle, const uint8_t *data, size_t len, const char *name);
+
+// Read and decode a PNG or SVG file on the background thread (named after the file stem)
+// Completion is reported like game_load_texture_async; returns the load id, or 0 on failure
+uint64_t game_load_texture_from_path_async(GameHandle handle, const char *path);
+
+// Load a KTX2 texture as `name`, uploading ETC2 or ASTC blocks (and mip levels) as they are
+// If the GPU can't sample its format (game_get_compressed_texture_formats), the PNG or SVG
+// `fallback` bytes are loaded instead; fallback may be NULL for uncompressed KTX2
+bool game_load_texture_ktx2(GameHandle handle, const uint8_t *data, size_t len, const uint8_t *fallback, size_t fallback_len, const char *name);
+
+// Compressed texture formats the GPU can sample: GAME_TEXTURE_FORMAT_* bits (0 before the
+// renderer exists)
+uint32_t game_get_compressed_texture_formats(GameHandle handle);
+
+// Generate mipmaps for PNG and SVG textures loaded from now on (off by default), so textures
+// drawn well below their size don't shimmer; costs a third more texture memory
+void game_set_texture_mipmaps(GameHandle handle, bool enabled);
+
+// Load a sprite sheet from atlas JSON (TexturePacker hash/array or Aseprite) and PNG bytes
+// Numbered frames ("run_01.png", ...) form animations by name stem; Aseprite frameTags win
+// Returns the atlas id, or 0 on failure
+AtlasId game_load_atlas(GameHandle handle, const char *json, const uint8_t *png, size_t png_len);
+
+// Load an animated GIF, WebP or APNG as an atlas with one animation called `name`, played with
+// game_set_node_animation (fps 0 keeps each frame's own delay). Returns the atlas id, or
+// INVALID_ATLAS if it can't be decoded or doesn't fit a sprite sheet
+AtlasId game_load_animated_texture(GameHandle handle, const uint8_t *data, size_t len, const char *name);
+
+// Play an atlas animation (or a single named frame) on a node, including the player node
+// fps > 0 overrides per-frame durations; mode: 0=loop, 1=once, 2=ping-pong
+// Once animations report EVENT_ANIMATION_FINISHED; name = null clears the animation
+bool game_set_node_animation(GameHandle handle, NodeId node, AtlasId atlas, const char *name, float fps, int32_t mode);
+
+// Load a Tiled (TMX or JSON) or LDtk map, its top left at world point (x, y), one map pixel = scale
+// points. Tilesets are drawn from textures named after their image's file stem; layers with a
+// "collision" property or name, and LDtk IntGrid layers, block the player
+// Returns the tilemap id, or INVALID_TILEMAP if the map can't be read
+TilemapId game_load_tilemap(GameHandle handle, const uint8_t *data, size_t len, float x, float y, float scale);
+
+// Remove a tilemap; returns false for unknown ids
+bool game_remove_tilemap(GameHandle handle, TilemapId map);
+
+// Show or hide a tilemap's layers named `name`; hidden collision layers still collide
+// Returns false for unknown maps or layer names
+bool game_set_tilemap_layer_visible(GameHandle handle, TilemapId map, const char *name, bool visible);
+
+// Whether any tilemap has a solid collision cell at world point (x, y)
+bool game_tilemap_is_solid(GameHandle handle, float x, float y);
+
+// Show a QR code (e.g. a challenge code) centered above the scene
+// size = edge length in pixels including the quiet zone; ec_level: 0=L, 1=M, 2=Q, 3=H
+// quiet_zone = light border in modules (4 recommended)
+// Returns false if the data doesn't fit a QR code at that level
+bool game_show_qr(GameHandle handle, const char *data, float size, int32_t ec_level, uint32_t quiet_zone);
+
+// Hide the QR code shown by game_show_qr
+void game_hide_qr(GameHandle handle);
+
+// Add a UI label drawn above the game: `text` (UTF-8, lines split at '\n') `size` points per em in
+// 0xRRGGBBAA. `anchor` (GAME_TEXT_ALIGN_* bits) picks the screen corner, edge or middle it's placed at
+// and the same point of the text's block; (x, y) moves it that many points in from those edges
+// (right and down from a middle). Returns the widget id, or 0 for a bad size, anchor or string
+WidgetId game_ui_create_label(GameHandle handle, const char *text, float size, uint32_t rgba, uint32_t anchor, float x, float y);
+
+// Add a UI button of width x height points (0 fits the text) filled with `fill_rgba`, its `text`
+// drawn `size` points per em in `text_rgba`; anchored like game_ui_create_label
+// Taps arrive as GAME_EVENT_BUTTON_PRESSED with the widget id; touches that go down on a button
+// don't reach the game. Returns the widget id, or 0 for a bad size, anchor or string
+WidgetId game_ui_create_button(GameHandle handle, const char *text, float size, uint32_t text_rgba, uint32_t fill_rgba, uint32_t anchor, float x, float y, float width, float height);
+
+// Add an empty UI progress bar of width x height points in 0xRRGGBBAA (its track is a faded copy);
+// anchored like game_ui_create_label. Returns the widget id, or 0 for a bad size or anchor
+WidgetId game_ui_create_progress_bar(GameHandle handle, uint32_t rgba, uint32_t anchor, float x, float y, float width, float height);
+
+// Replace a UI label's or button's text (UTF-8); false for other widgets or an invalid string
+bool game_ui_set_text(GameHandle handle, WidgetId widget, const char *text);
+
+// Fill a UI progress bar `value` of the way (clamped to 0..1); false for other widgets
+bool game_ui_set_progress(GameHandle handle, WidgetId widget, float value);
+
+// Show or hide a UI widget; hidden buttons take no touches. False if the id is unknown
+bool game_ui_set_visible(GameHandle handle, WidgetId widget, bool visible);
+
+// Remove a UI widget; false if the id is unknown
+bool game_ui_remove(GameHandle handle, WidgetId widget);
+
+// Deprecated: game_touch_player with game_get_player_node
+// Touches for the primary player; warns once through the log
+void game_touch(GameHandle handle, float x, float y, int32_t action);
+
+// Handle touch events (x, y in physical pixels); touching `player` (game_get_player_node) grabs it,
+// and an added player (game_add_player) heads for the finger. Returns false if the node isn't a player
+// Optimized: no logging in hot path, minimal branching
+bool game_touch_player(GameHandle handle, NodeId player, float x, float y, int32_t action);
+
+// Which touch gestures the engine claims (GAME_TOUCH_ROUTING_*), from the next touch down on: all
+// of them (the default), or only those going down on a UI button, the player or an entity with a
+// collider. Gestures it doesn't claim are ignored, for the host to give to its own handlers (e.g. a
+// scrollable around the game view). False for unknown policies
+bool game_set_touch_routing(GameHandle handle, uint32_t policy);
+
+// Whether the engine claimed the gesture the last game_touch_player belongs to; call after each
+// touch down to decide whether the host's gesture recognizers should yield
+bool game_touch_claimed(GameHandle handle);
+
+// Letting go of the player mid-drag throws it at the finger's speed; `friction` is how quickly it
+// slows down (per second, exponentially: 4, the default, leaves 2% of the speed after a second)
+// 0 drops released players where they are. Returns false for negative or non-finite values
+bool game_set_throw_friction(GameHandle handle, float friction);
+
+// Handle a key event from a hardware keyboard or forwarded by Flutter
+// keycode = USB HID usage (PhysicalKeyboardKey.usbHidUsage); action: 0=down, 1=up, 2=repeat
+// modifiers = GAME_KEY_MOD_* bits; returns false if the key is not recognized
+bool game_key_event(GameHandle handle, uint32_t keycode, int32_t action, uint32_t modifiers);
+
+// Handle committed text input (UTF-8), e.g. from the soft keyboard or an IME
+// Delivered to egui text fields on the next frame
+bool game_text_input(GameHandle handle, const char *utf8);
+
+// Handle a game controller button or axis (pad_id 0..3)
+// control = GAME_PAD_* value; buttons: value > 0.5 = pressed; sticks -1..1 (+y down); triggers 0..1
+// The left stick and d-pad move the player in Manual mode; returns false for unknown pads or controls
+bool game_gamepad_event(GameHandle handle, uint32_t pad_id, int32_t control, float value);
+
+// Forget a disconnected controller so a held stick stops steering
+void game_gamepad_disconnected(GameHandle handle, uint32_t pad_id);
+
+// Set the analog stick dead zone (fraction of full deflection, default 0.2)
+void game_set_gamepad_dead_zone(GameHandle handle, float dead_zone);
+
+// Forward a motion sensor sample (sensor_type: 0=accelerometer m/s^2, 1=gyroscope rad/s)
+// Axes follow Android's device frame (x right, y up, z out of the screen; flat face-up reads +z)
+// timestamp in nanoseconds (SensorEvent.timestamp); returns false for unknown sensor types
+bool game_sensor_event(GameHandle handle, int32_t sensor_type, float x, float y, float z, uint64_t timestamp);
+
+// Write the fused device tilt (direction a ball would roll, x right, y down, length 0..1)
+// Returns false for a null handle or output pointer
+bool game_get_tilt(GameHandle handle, float *out_x, float *out_y);
+
+// Let device tilt accelerate the player in Auto mode (px/s^2 at full tilt, 0 = off)
+void game_set_tilt_gravity(GameHandle handle, float strength);
+
+// Queue haptic feedback; the host receives it as an EVENT_HAPTIC event after the next tick
+// Returns false for unknown patterns or while haptics are disabled or not negotiated
+bool game_request_haptic(GameHandle handle, uint32_t pattern);
+
+// Turn haptic events on or off (e.g. a user setting)
+// Returns false if HAPTICS was not negotiated
+bool game_set_haptics_enabled(GameHandle handle, bool enabled);
+
+// Decode a WAV file (PCM 8/16-bit or float, mono or stereo) for playback
+// Returns the sound id, or INVALID_SOUND if the data is invalid or AUDIO was not negotiated
+SoundId game_load_sound(GameHandle handle, const uint8_t *data, size_t len);
+
+// Free a loaded sound; returns false for unknown ids
+bool game_unload_sound(GameHandle handle, SoundId id);
+
+// Play a sound effect once at `volume` (0..1); returns false for unknown ids
+bool game_play_sound(GameHandle handle, SoundId id, float volume);
+
+// Loop a sound as background music, replacing the current track
+bool game_play_music(GameHandle handle, SoundId id);
+
+void game_stop_music(GameHandle handle);
+
+// Set a volume (0..1) for GAME_VOLUME_MASTER, _EFFECTS or _MUSIC
+// Returns false for unknown channels
+bool game_set_volume(GameHandle handle, uint32_t channel, float volume);
+
+// Sound played on every bounce, louder for harder impacts (INVALID_SOUND = none)
+bool game_set_bounce_sound(GameHandle handle, SoundId id);
+
+// Pick the colors used for bounce tints, the drag highlight and status text
+// GAME_PALETTE_STANDARD, _DEUTERANOPIA, _PROTANOPIA or _TRITANOPIA; false for unknown values
+bool game_set_palette(GameHandle handle, uint32_t palette);
+
+// One-handed assist mode (a user setting, off by default): in Manual mode a tap anywhere
+// smoothly pulls the player toward it, and Auto mode runs at half speed
+void game_set_assist_mode(GameHandle handle, bool enabled);
+
+// Turn screen-reader announcements on or off (off by default)
+void game_set_announcements_enabled(GameHandle handle, bool enabled);
+
+// Localize an announcement: key is one of "bounce", "surface.left", "surface.right",
+// "surface.top", "surface.bottom", "surface.obstacle" or "score"
+// "{surface}" and "{score}" in the text are filled in; returns false for unknown keys
+bool game_set_announcement_text(GameHandle handle, const char *key, const char *text);
+
+// Next screen-reader announcement as UTF-8 text (no NUL), for a live region
+// Returns an empty buffer when none is pending; release with game_free_buffer
+GameBuffer game_poll_announcement(GameHandle handle);
+
+// What the current frame draws, as UTF-8 JSON (no NUL), before tessellation
+// Layers in draw order with rects, node transforms, textures and tints; for golden-list tests and debugging
+// Release with game_free_buffer
+GameBuffer game_dump_display_list(GameHandle handle);
+
+// Log frames slower than `threshold_ms` (frame interval or time in game_render) with their phase
+// timings and engine context, at most once per `log_interval_ms`; 0 disables detection
+// Defaults: 50 ms, one entry per 5 s. Spikes are marked in game_export_timeline either way
+void game_set_spike_threshold(GameHandle handle, float threshold_ms, uint32_t log_interval_ms);
+
+// The last ~300 rendered frames as Chrome trace event JSON (open in Perfetto / chrome://tracing):
+// per-frame phase slices with queue depths and draw counts, plus a "spike" marker on slow frames
+// Release with game_free_buffer
+GameBuffer game_export_timeline(GameHandle handle);
+
+// GAME_STATUS_*: whether calls on `handle` will do anything
+int32_t game_get_status(GameHandle handle);
+
+// The panic that poisoned this instance as JSON: `{"message":..,"backtrace":..}`
+// The message includes the source location; empty when the instance isn't poisoned
+// Release with game_free_buffer
+GameBuffer game_last_crash(GameHandle handle);
+
+// Deprecated: game_get_node_position with game_get_player_node
+// Primary player X position; warns once through the log
+float game_get_player_x(GameHandle handle);
+
+// Deprecated: game_get_node_position with game_get_player_node
+// Primary player Y position; warns once through the log
+float game_get_player_y(GameHandle handle);
+
+// Clean up resources
+// Safe to call with a null handle; must be called on the thread that created the engine
+void game_destroy(GameHandle handle);
+
+extern void *dlopen(const char *filename, int32_t flags);
+
+extern void *dlsym(void *handle, const char *symbol);
+
+extern void *eglGetCurrentDisplay(void);
+
+extern void *eglGetCurrentSurface(int32_t readdraw);
+
+extern uint32_t eglSwapBuffers(void *display, void *surface);
+
+extern int32_t gettid(void);
+
+extern int32_t sched_setaffinity(int32_t pid, size_t size, const uint64_t *mask);
+
+extern int32_t setpriority(int32_t which, uint32_t who, int32_t prio);
+
+extern int32_t pthread_set_qos_class_self_np(uint32_t qos_class, int32_t relative_priority);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* game_api_h */
@@ -0,0 +1,124 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::events::Surface;
+
+/// Shortest gap between announcements, so screen readers aren't flooded by Auto mode bounces
+const MIN_INTERVAL: Duration = Duration::from_millis(1500);
+
+/// Score is announced each time it passes a multiple of this
+const SCORE_STEP: u32 = 5;
+
+/// Announcements kept for the host; older ones are dropped
+const MAX_PENDING: usize = 8;
+
+/// Message keys and their English defaults
+/// `{surface}` and `{score}` are replaced when the message is built
+const DEFAULT_STRINGS: &[(&str, &str)] = &[
+    ("bounce", "Player bounced off {surface}"),
+    ("surface.left", "left wall"),
+    ("surface.right", "right wall"),
+    ("surface.top", "top wall"),
+    ("surface.bottom", "bottom wall"),
+    ("surface.obstacle", "obstacle"),
+    ("score", "Score {score}"),
+];
+
+/// Announcement texts, overridable per key by the host (localization)
+#[derive(Default)]
+pub struct StringTable {
+    overrides: HashMap<String, String>,
+}
+
+impl StringTable {
+    /// Replace the text for a known key; false for unknown keys
+    pub fn set(&mut self, key: &str, text: &str) -> bool {
+        if !DEFAULT_STRINGS.iter().any(|(k, _)| *k == key) {
+            return false;
+        }
+        self.overrides.insert(key.to_string(), text.to_string());
+        true
+    }
+
+    pub fn get(&self, key: &str) -> &str {
+        if let Some(text) = self.overrides.get(key) {
+            return text;
+        }
+        DEFAULT_STRINGS.iter().find(|(k, _)| *k == key).map_or("", |(_, text)| text)
+    }
+}
+
+fn surface_key(surface: Surface) -> &'static str {
+    match surface {
+        Surface::Left => "surface.left",
+        Surface::Right => "surface.right",
+        Surface::Top => "surface.top",
+        Surface::Bottom => "surface.bottom",
+        Surface::Obstacle => "surface.obstacle",
+    }
+}
+
+/// Builds short, rate-limited screen-reader messages from game events
+/// Off until the host enables it (e.g. when TalkBack or VoiceOver is running)
+#[derive(Default)]
+pub struct Announcer {
+    enabled: bool,
+    pub strings: StringTable,
+    /// Latest bounce since the last flush
+    bounce: Option<Surface>,
+    last_score: u32,
+    last_announced: Option<Instant>,
+    pending: VecDeque<String>,
+}
+
+impl Announcer {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pending.clear();
+            self.bounce = None;
+        }
+    }
+
+    pub fn bounce(&mut self, surface: Surface) {
+        if self.enabled {
+            self.bounce = Some(surface);
+        }
+    }
+
+    /// Turn this tick's events into at most one announcement
+    /// A score milestone wins over a bounce; a bounce inside the quiet interval is dropped
+    pub fn flush(&mut self, score: u32, now: Instant) {
+        let bounce = self.bounce.take();
+        if score < self.last_score {
+            // New level or restore
+            self.last_score = score;
+        }
+        if !self.enabled {
+            return;
+        }
+        if self.last_announced.is_some_and(|last| now.saturating_duration_since(last) < MIN_INTERVAL) {
+            return;
+        }
+
+        let message = if score / SCORE_STEP > self.last_score / SCORE_STEP {
+            self.last_score = score;
+            self.strings.get("score").replace("{score}", &score.to_string())
+        } else if let Some(surface) = bounce {
+            self.strings.get("bounce").replace("{surface}", self.strings.get(surface_key(surface)))
+        } else {
+            return;
+        };
+
+        if self.pending.len() >= MAX_PENDING {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(message);
+        self.last_announced = Some(now);
+    }
+
+    /// Next announcement for the host's live region
+    pub fn poll(&mut self) -> Option<String> {
+        self.pending.pop_front()
+    }
+}
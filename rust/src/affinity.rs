@@ -0,0 +1,202 @@
+use std::cell::{Cell, UnsafeCell};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use crate::ring_queue::RingQueue;
+use crate::GameEngine;
+
+/// Longest a call from another thread that returns a value waits for the render thread (e.g. while
+/// the app is paused) before it is dropped and returns its default
+pub const CALL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Calls an instance's inbox holds; waiting callers finding it full retry until CALL_TIMEOUT, posted
+/// ones are dropped (backpressure on a flood of platform-thread calls rather than unbounded memory)
+const INBOX_CAPACITY: usize = 256;
+
+/// How long a caller facing a full inbox waits before trying again
+const FULL_INBOX_RETRY: Duration = Duration::from_micros(200);
+
+/// Erased `&mut dyn FnMut` living on the calling thread's stack; the caller stays blocked until
+/// the call has run or been taken back, so the pointer outlives every use
+struct PendingCall(*mut (dyn FnMut(&mut GameEngine) + 'static));
+
+// Only dereferenced on the render thread while the calling thread waits for it
+unsafe impl Send for PendingCall {}
+
+/// CallState::status values; QUEUED moves to RUNNING (the owner got to it first) or ABANDONED
+/// (the caller timed out first), RUNNING to DONE
+const QUEUED: u8 = 0;
+const RUNNING: u8 = 1;
+const DONE: u8 = 2;
+const ABANDONED: u8 = 3;
+
+/// Shared by a call and its caller, which is parked until the status leaves QUEUED / RUNNING
+struct CallState {
+    status: AtomicU8,
+    caller: Thread,
+}
+
+enum Call {
+    /// A caller blocked in call_from_other_thread for the result
+    Waiting { run: PendingCall, state: Arc<CallState> },
+    /// A call nobody waits for (post)
+    Posted(Box<dyn FnOnce(&mut GameEngine) + Send>),
+}
+
+/// Why the calling thread's last call from off the render thread was dropped
+/// Values are part of the C API (game_last_call_error); never renumber
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum CallError {
+    None = 0,
+    /// The inbox was full: the render thread has fallen behind, or stopped making engine calls
+    InboxFull = 1,
+    /// The render thread didn't get to a call returning a value within CALL_TIMEOUT
+    TimedOut = 2,
+}
+
+thread_local! {
+    /// Outcome of the calling thread's last call from off the render thread (like errno)
+    static LAST_ERROR: Cell<CallError> = const { Cell::new(CallError::None) };
+}
+
+/// Error from the calling thread's last call from off the render thread (CallError::None if it was
+/// queued, and run if it returns a value)
+pub fn last_error() -> CallError {
+    LAST_ERROR.with(Cell::get)
+}
+
+fn record(error: CallError) {
+    LAST_ERROR.with(|last| last.set(error));
+}
+
+/// Process-unique number of the calling thread (ThreadId has no stable integer form to store atomically)
+fn thread_number() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static NUMBER: u64 = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+    NUMBER.with(|number| *number)
+}
+
+/// What a GameHandle points at: the engine, confined to the thread that created it (the one owning
+/// the GL context), and an inbox for calls made on other threads
+/// Hosts calling from a platform thread (touch handlers, Flutter method channels) get their call run
+/// on the render thread at its next engine call instead of racing it. Calls returning nothing are
+/// queued without waiting for that; hosts rendering on demand request a frame after making one
+pub struct Instance {
+    /// thread_number of the owner: the creating thread, or the simulation thread while it runs
+    owner: AtomicU64,
+    /// Calls queued by other threads; only read on the owner thread
+    inbox: RingQueue<Call>,
+    engine: UnsafeCell<GameEngine>,
+}
+
+impl Instance {
+    /// Box `engine` as a handle owned by the calling thread
+    pub fn into_handle(engine: GameEngine) -> *mut Instance {
+        let inbox = RingQueue::new(INBOX_CAPACITY);
+        Box::into_raw(Box::new(Instance { owner: AtomicU64::new(thread_number()), inbox, engine: UnsafeCell::new(engine) }))
+    }
+
+    pub fn is_owner_thread(&self) -> bool {
+        thread_number() == self.owner.load(Ordering::Acquire)
+    }
+
+    /// Make the calling thread the owner; calls from the previous owner are queued like any other
+    /// thread's from now on (see simulation module)
+    /// # Safety
+    /// The previous owner holds no reference to the engine and has handed it over (joined, or waiting
+    /// for the calling thread), so its writes are visible here
+    pub unsafe fn claim(&self) {
+        self.owner.store(thread_number(), Ordering::Release);
+    }
+
+    /// The engine behind `handle`, after running calls other threads queued for it
+    /// # Safety
+    /// `handle` is live; only on the owner thread, with no other reference to the engine alive
+    pub unsafe fn engine<'a>(handle: *const Instance) -> &'a mut GameEngine {
+        let instance = &*handle;
+        debug_assert!(instance.is_owner_thread(), "engine used off its render thread");
+        let engine = &mut *instance.engine.get();
+        while let Some(call) = instance.inbox.pop() {
+            match call {
+                Call::Waiting { run: PendingCall(f), state } => {
+                    if state.status.compare_exchange(QUEUED, RUNNING, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                        (*f)(engine);
+                        state.status.store(DONE, Ordering::Release);
+                        state.caller.unpark();
+                    }
+                }
+                Call::Posted(f) => f(engine),
+            }
+        }
+        engine
+    }
+
+    /// Queue `f` for the owner thread's next engine call and return at once
+    /// False, with CallError::InboxFull recorded, if the inbox is full; `f` is then never run
+    pub fn post(&self, f: impl FnOnce(&mut GameEngine) + Send + 'static) -> bool {
+        debug_assert!(!self.is_owner_thread());
+        if self.inbox.push(Call::Posted(Box::new(f))).is_err() {
+            log::warn!("Call from another thread dropped: the render thread's inbox is full");
+            record(CallError::InboxFull);
+            return false;
+        }
+        record(CallError::None);
+        true
+    }
+
+    /// Run `f` on the owner thread at its next engine call and wait for the result
+    /// None if the owner didn't get to it within CALL_TIMEOUT (recorded as a CallError); `f` is then never run
+    pub fn call_from_other_thread<T>(&self, f: impl FnOnce(&mut GameEngine) -> T) -> Option<T> {
+        debug_assert!(!self.is_owner_thread());
+        let mut f = Some(f);
+        let mut result = None;
+        let mut run = |engine: &mut GameEngine| result = f.take().map(|f| f(engine));
+        let run: &mut (dyn FnMut(&mut GameEngine) + '_) = &mut run;
+        // SAFETY: lifetime erased for the inbox; this function doesn't return while the owner may use it
+        let run: &mut (dyn FnMut(&mut GameEngine) + 'static) = unsafe { std::mem::transmute(run) };
+        let state = Arc::new(CallState { status: AtomicU8::new(QUEUED), caller: thread::current() });
+        let deadline = Instant::now() + CALL_TIMEOUT;
+        let mut call = Call::Waiting { run: PendingCall(run), state: state.clone() };
+        while let Err(rejected) = self.inbox.push(call) {
+            if Instant::now() >= deadline {
+                log::warn!("Call from another thread dropped: the render thread's inbox stayed full for {:?}", CALL_TIMEOUT);
+                record(CallError::InboxFull);
+                return None;
+            }
+            call = rejected;
+            thread::sleep(FULL_INBOX_RETRY);
+        }
+        loop {
+            match state.status.load(Ordering::Acquire) {
+                DONE => break,
+                RUNNING => thread::park(),
+                _ => {
+                    let now = Instant::now();
+                    if now < deadline {
+                        thread::park_timeout(deadline - now);
+                    } else if state.status.compare_exchange(QUEUED, ABANDONED, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+                        log::warn!("Call from another thread dropped: the render thread didn't pick it up in {:?}", CALL_TIMEOUT);
+                        record(CallError::TimedOut);
+                        return None;
+                    }
+                }
+            }
+        }
+        record(CallError::None);
+        result
+    }
+
+    /// Free the handle and the engine
+    /// # Safety
+    /// `handle` came from into_handle and no call on it is in progress; only on the owner thread
+    pub unsafe fn destroy(handle: *mut Instance) {
+        // Run calls already queued so their callers don't wait out the timeout
+        Instance::engine(handle);
+        drop(Box::from_raw(handle));
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::Cursor;
+
+use egui::{Color32, ColorImage};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, ImageDecoder, ImageFormat};
+
+/// Most frames of one animation; longer ones are rejected
+pub const MAX_FRAMES: usize = 512;
+
+/// Longest side of the sprite sheet frames are packed into
+pub const MAX_SHEET_SIDE: usize = 4096;
+
+/// Delay used for frames that ask for none (or less than 20 ms), as browsers do
+const DEFAULT_DELAY: f32 = 0.1;
+
+/// One decoded frame, composited onto the full canvas
+pub struct Frame {
+    pub image: ColorImage,
+    /// Seconds the frame is shown for
+    pub delay: f32,
+}
+
+/// Decode a GIF, WebP or (A)PNG into its frames; still images give a single frame
+pub fn decode(bytes: &[u8]) -> Result<Vec<Frame>, String> {
+    match image::guess_format(bytes).map_err(|e| e.to_string())? {
+        ImageFormat::Gif => {
+            let decoder = GifDecoder::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+            let size = decoder.dimensions();
+            decode_frames(decoder, size)
+        }
+        ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+            if !decoder.has_animation() {
+                return still(bytes);
+            }
+            let size = decoder.dimensions();
+            decode_frames(decoder, size)
+        }
+        _ => decode_png(bytes),
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Vec<Frame>, String> {
+    let decoder = PngDecoder::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+    if !decoder.is_apng().map_err(|e| e.to_string())? {
+        return still(bytes);
+    }
+    let size = decoder.dimensions();
+    decode_frames(decoder.apng().map_err(|e| e.to_string())?, size)
+}
+
+fn still(bytes: &[u8]) -> Result<Vec<Frame>, String> {
+    let image = crate::assets::decode_image(bytes)?;
+    Ok(vec![Frame { image, delay: DEFAULT_DELAY }])
+}
+
+/// Frames of an animation whose canvas is `width` x `height`, composited by the decoder
+/// Canvases and frame counts the sprite sheet can't hold are turned away before their pixels are decoded
+fn decode_frames<'a>(decoder: impl AnimationDecoder<'a>, (width, height): (u32, u32)) -> Result<Vec<Frame>, String> {
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 || w > MAX_SHEET_SIDE || h > MAX_SHEET_SIDE {
+        return Err(format!("Animation of {}x{} doesn't fit a {} pixel sheet", w, h, MAX_SHEET_SIDE));
+    }
+    let limit = MAX_FRAMES.min((MAX_SHEET_SIDE / w) * (MAX_SHEET_SIDE / h));
+    let mut frames = Vec::new();
+    for frame in decoder.into_frames() {
+        if frames.len() == limit {
+            return Err(format!("Animation has more than {} frames of {}x{}", limit, w, h));
+        }
+        let frame = frame.map_err(|e| e.to_string())?;
+        let (numerator, denominator) = frame.delay().numer_denom_ms();
+        let delay = numerator as f32 / denominator.max(1) as f32 / 1000.0;
+        let rgba = frame.into_buffer();
+        let size = [rgba.width() as usize, rgba.height() as usize];
+        frames.push(Frame {
+            image: ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()),
+            delay: if delay < 0.02 { DEFAULT_DELAY } else { delay },
+        });
+    }
+    if frames.is_empty() {
+        return Err("Animation has no frames".to_string());
+    }
+    Ok(frames)
+}
+
+/// Frames of equal size packed row by row into one sheet, and the number of columns
+pub fn sheet(frames: &[Frame]) -> Result<(ColorImage, usize), String> {
+    let [w, h] = frames.first().ok_or("Animation has no frames")?.image.size;
+    let columns = ((frames.len() as f32).sqrt().ceil() as usize).clamp(1, (MAX_SHEET_SIDE / w).max(1));
+    let rows = frames.len().div_ceil(columns);
+    if columns * w > MAX_SHEET_SIDE || rows * h > MAX_SHEET_SIDE {
+        return Err(format!("{} frames of {}x{} don't fit a {} pixel sheet", frames.len(), w, h, MAX_SHEET_SIDE));
+    }
+    let mut image = ColorImage::new([columns * w, rows * h], Color32::TRANSPARENT);
+    for (i, frame) in frames.iter().enumerate() {
+        if frame.image.size != [w, h] {
+            return Err("Animation frames differ in size".to_string());
+        }
+        let (x0, y0) = (i % columns * w, i / columns * h);
+        for (row, pixels) in frame.image.pixels.chunks_exact(w).enumerate() {
+            let start = (y0 + row) * image.size[0] + x0;
+            image.pixels[start..start + w].copy_from_slice(pixels);
+        }
+    }
+    Ok((image, columns))
+}
@@ -0,0 +1,154 @@
+/// Id handed across FFI for arena-stored objects
+/// Low 32 bits: slot index + 1 (so 0 is never valid), high 32 bits: slot generation
+pub type Id = u64;
+
+/// Invalid id returned on failure
+pub const INVALID_ID: Id = 0;
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Highest slot index accepted when rebuilding an arena from saved ids
+const MAX_RESTORED_INDEX: usize = 1 << 20;
+
+/// Generational arena
+/// Removed slots are reused; stale ids are rejected by the generation check
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    count: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            count: 0,
+        }
+    }
+}
+
+fn make_id(index: u32, generation: u32) -> Id {
+    ((generation as u64) << 32) | (index as u64 + 1)
+}
+
+fn split_id(id: Id) -> Option<(usize, u32)> {
+    let low = (id & 0xFFFF_FFFF) as u32;
+    if low == 0 {
+        return None;
+    }
+    Some(((low - 1) as usize, (id >> 32) as u32))
+}
+
+impl<T> Arena<T> {
+    /// Rebuild an arena holding `entries` under their original ids (restoring saved state)
+    /// None if an id is invalid, repeated or implausibly large
+    pub fn from_entries(entries: impl IntoIterator<Item = (Id, T)>) -> Option<Self> {
+        let mut arena = Self::default();
+        for (id, value) in entries {
+            let (index, generation) = split_id(id)?;
+            if index > MAX_RESTORED_INDEX {
+                return None;
+            }
+            if index >= arena.slots.len() {
+                arena.slots.resize_with(index + 1, || Slot { generation: 0, value: None });
+            }
+            let slot = &mut arena.slots[index];
+            if slot.value.is_some() {
+                return None;
+            }
+            slot.generation = generation;
+            slot.value = Some(value);
+            arena.count += 1;
+        }
+        arena.free = (0..arena.slots.len() as u32)
+            .rev()
+            .filter(|&index| arena.slots[index as usize].value.is_none())
+            .collect();
+        Some(arena)
+    }
+
+    pub fn insert(&mut self, value: T) -> Id {
+        self.count += 1;
+
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(value);
+            return make_id(index, slot.generation);
+        }
+
+        let index = self.slots.len() as u32;
+        self.slots.push(Slot {
+            generation: 0,
+            value: Some(value),
+        });
+        make_id(index, 0)
+    }
+
+    pub fn remove(&mut self, id: Id) -> Option<T> {
+        let (index, generation) = split_id(id)?;
+        let slot = self.slots.get_mut(index)?;
+        if slot.generation != generation {
+            return None;
+        }
+
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(index as u32);
+        self.count -= 1;
+        Some(value)
+    }
+
+    pub fn get(&self, id: Id) -> Option<&T> {
+        let (index, generation) = split_id(id)?;
+        let slot = self.slots.get(index)?;
+        if slot.generation != generation {
+            return None;
+        }
+        slot.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        let (index, generation) = split_id(id)?;
+        let slot = self.slots.get_mut(index)?;
+        if slot.generation != generation {
+            return None;
+        }
+        slot.value.as_mut()
+    }
+
+    pub fn contains(&self, id: Id) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Iterate live values in slot order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|s| s.value.as_ref())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|s| s.value.as_mut())
+    }
+
+    /// Iterate live values with their ids
+    pub fn entries(&self) -> impl Iterator<Item = (Id, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let generation = slot.generation;
+            slot.value.as_ref().map(|value| (make_id(index as u32, generation), value))
+        })
+    }
+
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = (Id, &mut T)> {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            let generation = slot.generation;
+            slot.value.as_mut().map(|value| (make_id(index as u32, generation), value))
+        })
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use egui::Vec2;
+
+use crate::ffi::strings::{self, NameId};
+use crate::svg;
+use crate::tasks::{CancellationToken, TaskGroup};
+
+/// Name of the player texture; loading a texture under this name reskins the player
+pub const PLAYER_TEXTURE: &str = "player";
+
+/// Name of the share-card logo (falls back to the player texture)
+pub const LOGO_TEXTURE: &str = "logo";
+
+/// Interned ids of the names above, for per-frame lookups
+pub static PLAYER_TEXTURE_ID: LazyLock<NameId> = LazyLock::new(|| strings::intern(PLAYER_TEXTURE));
+pub static LOGO_TEXTURE_ID: LazyLock<NameId> = LazyLock::new(|| strings::intern(LOGO_TEXTURE));
+
+/// Id of an async load, reported back in TextureReady / TextureFailed events
+pub type LoadId = u64;
+
+/// Decoded pixels uploaded per frame; at least one texture is always uploaded
+pub const UPLOAD_BUDGET_BYTES: usize = 4 * 1024 * 1024;
+
+/// How often the decode worker checks for cancellation while idle
+const WORKER_POLL: Duration = Duration::from_millis(50);
+
+/// Uploaded texture plus its source size (in pixels as rasterized, for SVG)
+pub struct Texture {
+    pub id: egui::TextureId,
+    pub size: Vec2,
+    /// Keeps egui-managed pixels alive (dropping it frees the GPU texture); None for textures
+    /// uploaded straight to GL (KTX2), which the renderer frees once they're released
+    handle: Option<egui::TextureHandle>,
+    /// Vector source, redrawn when the scale factor changes
+    svg: Option<Arc<svg::Document>>,
+}
+
+/// Decode PNG bytes into an egui image
+pub fn decode_image(bytes: &[u8]) -> Result<egui::ColorImage, String> {
+    let rgba = image::load_from_memory(bytes).map_err(|e| e.to_string())?.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()))
+}
+
+/// Decode PNG or SVG bytes; SVG is rasterized at `scale` pixels per point and kept for redrawing
+fn decode(bytes: &[u8], scale: f32) -> Result<(egui::ColorImage, Option<Arc<svg::Document>>), String> {
+    if !svg::is_svg(bytes) {
+        return Ok((decode_image(bytes)?, None));
+    }
+    let document = svg::parse(bytes)?;
+    Ok((document.rasterize(scale), Some(Arc::new(document))))
+}
+
+/// Textures handed to the engine at runtime, keyed by interned name
+/// Loading an existing name replaces it (the old texture is freed once unused)
+pub struct AssetCache {
+    textures: HashMap<NameId, Texture>,
+    /// Pixels per point SVG textures are rasterized at
+    scale: f32,
+    /// Whether textures loaded from now on get mipmaps
+    mipmaps: bool,
+    /// Replaced native textures waiting for the renderer to free them
+    released: Vec<egui::TextureId>,
+    /// Started on the first async load
+    loader: Option<AsyncLoader>,
+    next_load_id: LoadId,
+    /// Queued loads not yet handed back by upload_decoded
+    in_flight: usize,
+}
+
+impl Default for AssetCache {
+    fn default() -> Self {
+        AssetCache {
+            textures: HashMap::new(),
+            scale: 1.0,
+            mipmaps: false,
+            released: Vec::new(),
+            loader: None,
+            next_load_id: 0,
+            in_flight: 0,
+        }
+    }
+}
+
+enum Source {
+    Bytes(Vec<u8>),
+    Path(PathBuf),
+}
+
+struct Request {
+    id: LoadId,
+    name: String,
+    source: Source,
+    scale: f32,
+}
+
+struct Decoded {
+    id: LoadId,
+    name: String,
+    image: Result<(egui::ColorImage, Option<Arc<svg::Document>>), String>,
+}
+
+/// Channels to the background decode thread
+struct AsyncLoader {
+    requests: Sender<Request>,
+    results: Receiver<Decoded>,
+}
+
+/// Decode requests until cancelled or the cache is dropped
+fn decode_worker(requests: Receiver<Request>, results: Sender<Decoded>, token: CancellationToken) {
+    while !token.is_cancelled() {
+        let request = match requests.recv_timeout(WORKER_POLL) {
+            Ok(request) => request,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        let image = match request.source {
+            Source::Bytes(bytes) => decode(&bytes, request.scale),
+            Source::Path(path) => std::fs::read(&path)
+                .map_err(|e| format!("{}: {}", path.display(), e))
+                .and_then(|bytes| decode(&bytes, request.scale)),
+        };
+        let decoded = Decoded {
+            id: request.id,
+            name: request.name,
+            image,
+        };
+        if results.send(decoded).is_err() {
+            return;
+        }
+    }
+}
+
+/// Texture name for a file path ("sprites/hero.png" -> "hero")
+pub fn name_from_path(path: &Path) -> Result<String, String> {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("No file name in {}", path.display()))
+}
+
+impl AssetCache {
+    /// Load PNG or SVG bytes (SVG is drawn at the current scale factor)
+    pub fn load_from_bytes(&mut self, ctx: &egui::Context, name: &str, bytes: &[u8]) -> Result<(), String> {
+        let (image, svg) = decode(bytes, self.scale)?;
+        log::info!("Texture '{}' loaded: {}x{}", name, image.width(), image.height());
+        self.insert_image(ctx, name, image, svg);
+        Ok(())
+    }
+
+    /// Upload decoded pixels as texture `name`
+    pub fn insert_image(&mut self, ctx: &egui::Context, name: &str, image: egui::ColorImage, svg: Option<Arc<svg::Document>>) {
+        let size = Vec2::new(image.width() as f32, image.height() as f32);
+        let handle = ctx.load_texture(name, image, self.options());
+        self.insert(name, Texture { id: handle.id(), size, handle: Some(handle), svg });
+    }
+
+    /// Add a texture the renderer uploaded itself (registered with the painter) as `name`
+    /// It's released for the renderer to free when replaced
+    pub fn insert_native(&mut self, name: &str, id: egui::TextureId, size: Vec2) {
+        self.insert(name, Texture { id, size, handle: None, svg: None });
+    }
+
+    fn insert(&mut self, name: &str, texture: Texture) {
+        if let Some(old) = self.textures.insert(strings::intern(name), texture) {
+            if old.handle.is_none() {
+                self.released.push(old.id);
+            }
+        }
+    }
+
+    /// Native textures no longer in the cache; the renderer frees them with the painter
+    pub fn take_released(&mut self) -> Vec<egui::TextureId> {
+        std::mem::take(&mut self.released)
+    }
+
+    /// Give textures loaded from now on mipmaps, so they stay smooth drawn well below their size
+    /// Costs a third more texture memory; already loaded textures keep their setting
+    pub fn set_mipmaps(&mut self, enabled: bool) {
+        self.mipmaps = enabled;
+    }
+
+    fn options(&self) -> egui::TextureOptions {
+        let mipmap_mode = self.mipmaps.then_some(egui::TextureFilter::Linear);
+        egui::TextureOptions { mipmap_mode, ..egui::TextureOptions::LINEAR }
+    }
+
+    /// Rasterize SVG textures at `scale` pixels per point from now on, redrawing loaded ones
+    /// Handles are updated in place, so sprites and nodes drawing them pick up the new pixels
+    pub fn set_scale(&mut self, scale: f32) {
+        if scale == self.scale {
+            return;
+        }
+        self.scale = scale;
+        let options = self.options();
+        for texture in self.textures.values_mut() {
+            let (Some(svg), Some(handle)) = (&texture.svg, &mut texture.handle) else {
+                continue;
+            };
+            let image = svg.rasterize(scale);
+            texture.size = Vec2::new(image.width() as f32, image.height() as f32);
+            handle.set(image, options);
+        }
+    }
+
+    /// Load a file, naming the texture after the file stem ("sprites/hero.png" -> "hero")
+    pub fn load_from_path(&mut self, ctx: &egui::Context, path: &Path) -> Result<String, String> {
+        let name = name_from_path(path)?;
+        let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.load_from_bytes(ctx, &name, &bytes)?;
+        Ok(name)
+    }
+
+    pub fn get(&self, name: NameId) -> Option<&Texture> {
+        self.textures.get(&name)
+    }
+
+    /// Async loads still decoding or waiting for upload
+    pub fn pending_loads(&self) -> usize {
+        self.in_flight
+    }
+
+    /// Queue PNG or SVG bytes for decoding on the worker thread
+    pub fn load_bytes_async(&mut self, tasks: &mut TaskGroup, name: &str, bytes: Vec<u8>) -> Result<LoadId, String> {
+        self.queue(tasks, name.to_string(), Source::Bytes(bytes))
+    }
+
+    /// Queue a PNG or SVG file for reading and decoding on the worker thread (named after the file stem)
+    pub fn load_path_async(&mut self, tasks: &mut TaskGroup, path: &Path) -> Result<LoadId, String> {
+        let name = name_from_path(path)?;
+        self.queue(tasks, name, Source::Path(path.to_path_buf()))
+    }
+
+    fn queue(&mut self, tasks: &mut TaskGroup, name: String, source: Source) -> Result<LoadId, String> {
+        if self.loader.is_none() {
+            let (request_tx, request_rx) = mpsc::channel();
+            let (result_tx, result_rx) = mpsc::channel();
+            tasks
+                .spawn("asset-decode", move |token| decode_worker(request_rx, result_tx, token))
+                .map_err(|e| format!("Failed to start decode thread: {}", e))?;
+            self.loader = Some(AsyncLoader {
+                requests: request_tx,
+                results: result_rx,
+            });
+        }
+
+        self.next_load_id += 1;
+        let id = self.next_load_id;
+        let loader = self.loader.as_ref().ok_or("Decode thread unavailable")?;
+        loader
+            .requests
+            .send(Request { id, name, source, scale: self.scale })
+            .map_err(|_| "Decode thread stopped".to_string())?;
+        self.in_flight += 1;
+        Ok(id)
+    }
+
+    /// Upload decoded textures within the per-frame budget (render thread)
+    /// `on_done` receives each load's id and its size, or the decode error
+    pub fn upload_decoded(&mut self, ctx: &egui::Context, mut on_done: impl FnMut(LoadId, Result<Vec2, String>)) {
+        let mut uploaded = 0;
+        while uploaded < UPLOAD_BUDGET_BYTES {
+            let Some(decoded) = self.loader.as_ref().and_then(|loader| loader.results.try_recv().ok()) else {
+                break;
+            };
+            self.in_flight = self.in_flight.saturating_sub(1);
+            match decoded.image {
+                Ok((mut image, svg)) => {
+                    // The scale may have changed while it was decoding
+                    if let Some(svg) = svg.as_ref().filter(|svg| image.size != svg.pixel_size(self.scale)) {
+                        image = svg.rasterize(self.scale);
+                    }
+                    uploaded += image.pixels.len() * 4;
+                    let size = Vec2::new(image.width() as f32, image.height() as f32);
+                    log::info!("Texture '{}' loaded async: {}x{}", decoded.name, size.x, size.y);
+                    self.insert_image(ctx, &decoded.name, image, svg);
+                    on_done(decoded.id, Ok(size));
+                }
+                Err(e) => on_done(decoded.id, Err(format!("'{}': {}", decoded.name, e))),
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use egui::{Pos2, Rect, Vec2};
+
+use crate::arena::{Id, INVALID_ID};
+use crate::json::{self, Value};
+
+/// Atlas id handed across FFI
+pub type AtlasId = Id;
+
+/// Invalid atlas id returned on failure
+pub const INVALID_ATLAS: AtlasId = INVALID_ID;
+
+/// Frame duration used when neither the sheet nor the caller specifies one
+const DEFAULT_FRAME_SECONDS: f32 = 0.1;
+
+/// Shortest frame duration, so a zero duration can't stall the update loop
+const MIN_FRAME_SECONDS: f32 = 0.001;
+
+/// One packed sub-image
+pub struct Frame {
+    /// Normalized texture coordinates
+    pub uv: Rect,
+    /// Size in source pixels
+    pub size: Vec2,
+    /// Per-frame duration in seconds (Aseprite exports), if present
+    pub duration: Option<f32>,
+}
+
+/// Sprite sheet: one texture plus named frames and animations
+/// Loaded from TexturePacker / Aseprite JSON (hash or array form)
+pub struct Atlas {
+    pub texture: egui::TextureHandle,
+    frames: Vec<Frame>,
+    frame_names: HashMap<String, usize>,
+    /// Animation name -> frame indices in playback order
+    animations: HashMap<String, Vec<usize>>,
+}
+
+impl Atlas {
+    /// Parse the atlas JSON against a decoded sheet of `image_size` pixels
+    pub fn parse(text: &str, image_size: [usize; 2], texture: egui::TextureHandle) -> Result<Atlas, String> {
+        let root = json::parse(text)?;
+        let entries: Vec<(String, &Value)> = match root.get("frames") {
+            // Hash form: { "name.png": { "frame": ... } }
+            Some(Value::Object(members)) => members.iter().map(|(name, v)| (name.clone(), v)).collect(),
+            // Array form: [ { "filename": "name.png", "frame": ... } ]
+            Some(Value::Array(items)) => items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let name = v.get("filename").and_then(Value::as_str).map(str::to_string);
+                    (name.unwrap_or_else(|| i.to_string()), v)
+                })
+                .collect(),
+            _ => return Err("Atlas JSON has no frames".to_string()),
+        };
+
+        let (image_w, image_h) = (image_size[0] as f32, image_size[1] as f32);
+        let mut frames = Vec::with_capacity(entries.len());
+        let mut frame_names = HashMap::new();
+        for (name, entry) in &entries {
+            if entry.get("rotated").and_then(Value::as_bool) == Some(true) {
+                return Err(format!("Frame '{}' is rotated; pack the atlas without rotation", name));
+            }
+            let rect = entry.get("frame").ok_or_else(|| format!("Frame '{}' has no rect", name))?;
+            let field = |key: &str| {
+                rect.get(key)
+                    .and_then(Value::as_f32)
+                    .ok_or_else(|| format!("Frame '{}' is missing '{}'", name, key))
+            };
+            let (x, y, w, h) = (field("x")?, field("y")?, field("w")?, field("h")?);
+            if w <= 0.0 || h <= 0.0 || x < 0.0 || y < 0.0 || x + w > image_w || y + h > image_h {
+                return Err(format!("Frame '{}' lies outside the {}x{} sheet", name, image_w, image_h));
+            }
+
+            frame_names.insert(name.clone(), frames.len());
+            frames.push(Frame {
+                uv: Rect::from_min_size(Pos2::new(x / image_w, y / image_h), Vec2::new(w / image_w, h / image_h)),
+                size: Vec2::new(w, h),
+                duration: entry.get("duration").and_then(Value::as_f32).map(|ms| ms / 1000.0),
+            });
+        }
+
+        // Group numbered frames ("run_01.png", "run_02.png") into animations by stem
+        let mut animations: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, (name, _)) in entries.iter().enumerate() {
+            animations.entry(animation_stem(name).to_string()).or_default().push(index);
+        }
+
+        // Explicit Aseprite tags win over inferred groups
+        let tags = root.get("meta").and_then(|m| m.get("frameTags")).and_then(Value::as_array);
+        for tag in tags.unwrap_or_default() {
+            let name = tag.get("name").and_then(Value::as_str);
+            let from = tag.get("from").and_then(Value::as_f64);
+            let to = tag.get("to").and_then(Value::as_f64);
+            let (Some(name), Some(from), Some(to)) = (name, from, to) else {
+                continue;
+            };
+            let (from, to) = (from as usize, to as usize);
+            if from > to || to >= frames.len() {
+                log::warn!("Atlas tag '{}' has an invalid range {}..={}", name, from, to);
+                continue;
+            }
+            let mut indices: Vec<usize> = (from..=to).collect();
+            if tag.get("direction").and_then(Value::as_str) == Some("reverse") {
+                indices.reverse();
+            }
+            animations.insert(name.to_string(), indices);
+        }
+
+        Ok(Atlas {
+            texture,
+            frames,
+            frame_names,
+            animations,
+        })
+    }
+
+    /// A sheet of equal frames packed row by row, `columns` wide, played in order as animation
+    /// `name` (each frame shown for its delay in seconds); frames are also named "name_0", "name_1"..
+    pub fn from_grid(name: &str, image_size: [usize; 2], columns: usize, frame_size: [usize; 2], delays: &[f32], texture: egui::TextureHandle) -> Atlas {
+        let sheet = Vec2::new(image_size[0] as f32, image_size[1] as f32);
+        let size = Vec2::new(frame_size[0] as f32, frame_size[1] as f32);
+        let frames: Vec<Frame> = delays
+            .iter()
+            .enumerate()
+            .map(|(i, &delay)| {
+                let min = Pos2::new((i % columns) as f32 * size.x, (i / columns) as f32 * size.y);
+                Frame {
+                    uv: Rect::from_min_size((min.to_vec2() / sheet).to_pos2(), size / sheet),
+                    size,
+                    duration: Some(delay),
+                }
+            })
+            .collect();
+        Atlas {
+            texture,
+            frame_names: (0..frames.len()).map(|i| (format!("{}_{}", name, i), i)).collect(),
+            animations: HashMap::from([(name.to_string(), (0..frames.len()).collect())]),
+            frames,
+        }
+    }
+
+    pub fn frame(&self, index: usize) -> Option<&Frame> {
+        self.frames.get(index)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Frames of an animation, or a single named frame for static sprites
+    fn sequence(&self, name: &str) -> Option<Vec<usize>> {
+        self.animations
+            .get(name)
+            .cloned()
+            .or_else(|| self.frame_names.get(name).map(|&i| vec![i]))
+    }
+}
+
+/// "run_01.png" -> "run", "idle 3" -> "idle", "jump" -> "jump"
+fn animation_stem(name: &str) -> &str {
+    let name = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let trimmed = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let trimmed = trimmed.trim_end_matches(['_', '-', ' ']);
+    if trimmed.is_empty() {
+        name
+    } else {
+        trimmed
+    }
+}
+
+/// How playback continues after the last frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum LoopMode {
+    Loop = 0,
+    /// Stop on the last frame and report completion
+    Once = 1,
+    /// Play forward then backward
+    PingPong = 2,
+}
+
+impl From<i32> for LoopMode {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => LoopMode::Once,
+            2 => LoopMode::PingPong,
+            _ => LoopMode::Loop,
+        }
+    }
+}
+
+/// Flipbook playback of an atlas animation
+#[derive(Clone, Debug)]
+pub struct AnimatedSprite {
+    pub atlas: AtlasId,
+    /// (atlas frame index, duration in seconds) per playback step
+    steps: Vec<(usize, f32)>,
+    mode: LoopMode,
+    step: usize,
+    elapsed: f32,
+    finished: bool,
+}
+
+impl AnimatedSprite {
+    /// Play `name` from an atlas; fps > 0 overrides per-frame durations
+    pub fn new(atlas_id: AtlasId, atlas: &Atlas, name: &str, fps: f32, mode: LoopMode) -> Option<AnimatedSprite> {
+        let mut frames = atlas.sequence(name)?;
+        if mode == LoopMode::PingPong && frames.len() > 2 {
+            let back: Vec<usize> = frames[1..frames.len() - 1].iter().rev().copied().collect();
+            frames.extend(back);
+        }
+
+        let steps = frames
+            .into_iter()
+            .map(|index| {
+                let duration = if fps > 0.0 {
+                    1.0 / fps
+                } else {
+                    atlas.frames[index].duration.unwrap_or(DEFAULT_FRAME_SECONDS)
+                };
+                (index, duration.max(MIN_FRAME_SECONDS))
+            })
+            .collect();
+
+        Some(AnimatedSprite {
+            atlas: atlas_id,
+            steps,
+            mode,
+            step: 0,
+            elapsed: 0.0,
+            finished: false,
+        })
+    }
+
+    /// Advance playback; returns true on the update a Once animation finishes
+    pub fn update(&mut self, delta: f32) -> bool {
+        if self.finished {
+            return false;
+        }
+        self.elapsed += delta;
+        while self.elapsed >= self.steps[self.step].1 {
+            self.elapsed -= self.steps[self.step].1;
+            if self.step + 1 < self.steps.len() {
+                self.step += 1;
+            } else if self.mode == LoopMode::Once {
+                self.finished = true;
+                return true;
+            } else {
+                self.step = 0;
+            }
+        }
+        false
+    }
+
+    /// Atlas frame index to draw
+    pub fn frame(&self) -> usize {
+        self.steps[self.step].0
+    }
+
+    /// Whether the drawn frame will still change
+    pub fn is_playing(&self) -> bool {
+        !self.finished && self.steps.len() > 1
+    }
+}
@@ -0,0 +1,369 @@
+use std::sync::{Arc, Mutex};
+
+use crate::arena::{Arena, Id, INVALID_ID};
+
+/// Sound id handed across FFI
+pub type SoundId = Id;
+
+/// Invalid sound id returned on failure
+pub const INVALID_SOUND: SoundId = INVALID_ID;
+
+/// Effects playing at once; the oldest is cut off when a new one starts
+const MAX_VOICES: usize = 16;
+
+/// Mixer rate until an output device reports its own
+const DEFAULT_OUTPUT_RATE: u32 = 48_000;
+
+/// Impact speed (points/s) at which the bounce sound plays at full volume
+const LOUD_BOUNCE_SPEED: f32 = 600.0;
+
+/// Volume controls for game_set_volume
+/// Values are part of the C API; never renumber
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u32)]
+pub enum Channel {
+    Master = 0,
+    Effects = 1,
+    Music = 2,
+}
+
+impl Channel {
+    pub fn from_raw(value: u32) -> Option<Channel> {
+        match value {
+            0 => Some(Channel::Master),
+            1 => Some(Channel::Effects),
+            2 => Some(Channel::Music),
+            _ => None,
+        }
+    }
+}
+
+/// Decoded sound as stereo frames
+pub struct Sound {
+    frames: Vec<[f32; 2]>,
+    sample_rate: u32,
+}
+
+impl Sound {
+    /// Decode a RIFF WAVE file: PCM 8/16-bit or 32-bit float, mono or stereo
+    pub fn decode_wav(bytes: &[u8]) -> Result<Sound, String> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err("Not a WAV file".to_string());
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut pos = 12;
+        while pos + 8 <= bytes.len() {
+            let id = &bytes[pos..pos + 4];
+            let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+            let body = &bytes[pos + 8..(pos + 8).saturating_add(size).min(bytes.len())];
+            match id {
+                b"fmt " if body.len() >= 16 => format = Some(body),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+            // Chunks are padded to an even size
+            pos = pos.saturating_add(8 + size + (size & 1));
+        }
+
+        let format = format.ok_or("WAV file has no fmt chunk")?;
+        let data = data.ok_or("WAV file has no data chunk")?;
+        let u16_at = |offset: usize| u16::from_le_bytes([format[offset], format[offset + 1]]);
+        let (encoding, channels, bits) = (u16_at(0), u16_at(2) as usize, u16_at(14));
+        let sample_rate = u32::from_le_bytes(format[4..8].try_into().unwrap());
+        if !(1..=2).contains(&channels) || sample_rate == 0 {
+            return Err(format!("Unsupported WAV layout: {} channels at {} Hz", channels, sample_rate));
+        }
+
+        let sample: fn(&[u8]) -> f32 = match (encoding, bits) {
+            (1, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+            (1, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            (3, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            _ => return Err(format!("Unsupported WAV encoding {} with {} bits", encoding, bits)),
+        };
+        let frame_size = channels * bits as usize / 8;
+        let frames = data
+            .chunks_exact(frame_size)
+            .map(|frame| {
+                let left = sample(frame);
+                let right = if channels == 2 { sample(&frame[frame_size / 2..]) } else { left };
+                [left, right]
+            })
+            .collect();
+
+        Ok(Sound { frames, sample_rate })
+    }
+}
+
+/// One playing sound
+struct Voice {
+    sound: Arc<Sound>,
+    /// Position in source frames
+    position: f64,
+    volume: f32,
+    looping: bool,
+}
+
+impl Voice {
+    fn new(sound: Arc<Sound>, volume: f32, looping: bool) -> Voice {
+        Voice { sound, position: 0.0, volume, looping }
+    }
+
+    /// Next frame at the output rate, or None once a non-looping sound ends
+    fn next_frame(&mut self, output_rate: u32) -> Option<[f32; 2]> {
+        let frames = &self.sound.frames;
+        if self.position as usize >= frames.len() {
+            if !self.looping || frames.is_empty() {
+                return None;
+            }
+            self.position %= frames.len() as f64;
+        }
+        let frame = frames[self.position as usize];
+        self.position += self.sound.sample_rate as f64 / output_rate as f64;
+        Some(frame)
+    }
+}
+
+/// Sums playing voices into the output buffer; shared with the audio callback
+pub struct Mixer {
+    effects: Vec<Voice>,
+    music: Option<Voice>,
+    /// Indexed by Channel
+    volumes: [f32; 3],
+    output_rate: u32,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Mixer {
+            effects: Vec::new(),
+            music: None,
+            volumes: [1.0; 3],
+            output_rate: DEFAULT_OUTPUT_RATE,
+        }
+    }
+}
+
+impl Mixer {
+    /// Fill interleaved `out` with `channels` samples per frame
+    /// Only the output stream calls this, so builds without `audio` never mix
+    #[cfg_attr(not(feature = "audio"), allow(dead_code))]
+    pub fn mix(&mut self, out: &mut [f32], channels: usize) {
+        out.fill(0.0);
+        if channels == 0 {
+            return;
+        }
+
+        let master = self.volumes[Channel::Master as usize];
+        let effects_gain = master * self.volumes[Channel::Effects as usize];
+        let music_gain = master * self.volumes[Channel::Music as usize];
+        let output_rate = self.output_rate;
+
+        let voices = self
+            .effects
+            .iter_mut()
+            .map(|voice| (voice, effects_gain))
+            .chain(self.music.iter_mut().map(|voice| (voice, music_gain)));
+        for (voice, gain) in voices {
+            let gain = gain * voice.volume;
+            for frame in out.chunks_exact_mut(channels) {
+                let Some([left, right]) = voice.next_frame(output_rate) else {
+                    break;
+                };
+                if channels == 1 {
+                    frame[0] += (left + right) * 0.5 * gain;
+                } else {
+                    frame[0] += left * gain;
+                    frame[1] += right * gain;
+                }
+            }
+        }
+
+        let sound_count = |voice: &Voice| voice.sound.frames.len() as f64;
+        self.effects.retain(|voice| voice.position < sound_count(voice));
+        for sample in out.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+    }
+
+    fn play(&mut self, voice: Voice) {
+        if self.effects.len() >= MAX_VOICES {
+            self.effects.remove(0);
+        }
+        self.effects.push(voice);
+    }
+}
+
+/// Loaded sounds plus the mixer and (with the `audio` feature) the output stream
+pub struct Audio {
+    enabled: bool,
+    sounds: Arena<Arc<Sound>>,
+    mixer: Arc<Mutex<Mixer>>,
+    /// Played on each bounce, louder for harder impacts
+    bounce_sound: Option<SoundId>,
+    #[cfg(feature = "audio")]
+    _stream: Option<cpal::Stream>,
+}
+
+impl Audio {
+    /// Open the default output device if `enabled` (AUDIO granted)
+    /// A missing device is logged and leaves the engine silent
+    pub fn new(enabled: bool) -> Audio {
+        let mixer = Arc::new(Mutex::new(Mixer::default()));
+
+        #[cfg(feature = "audio")]
+        let stream = if enabled {
+            match output::open(mixer.clone()) {
+                Ok(stream) => Some(stream),
+                Err(e) => {
+                    log::warn!("Audio output unavailable: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Audio {
+            enabled,
+            sounds: Arena::default(),
+            mixer,
+            bounce_sound: None,
+            #[cfg(feature = "audio")]
+            _stream: stream,
+        }
+    }
+
+    fn mixer(&self) -> std::sync::MutexGuard<'_, Mixer> {
+        self.mixer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Decode a WAV file and keep it for playback
+    pub fn load(&mut self, bytes: &[u8]) -> Result<SoundId, String> {
+        if !self.enabled {
+            return Err("audio was not negotiated".to_string());
+        }
+        let sound = Sound::decode_wav(bytes)?;
+        Ok(self.sounds.insert(Arc::new(sound)))
+    }
+
+    /// Free a sound; voices already playing it finish normally
+    pub fn unload(&mut self, id: SoundId) -> bool {
+        if self.bounce_sound == Some(id) {
+            self.bounce_sound = None;
+        }
+        self.sounds.remove(id).is_some()
+    }
+
+    /// Start a one-shot effect at `volume` (0..1)
+    pub fn play(&mut self, id: SoundId, volume: f32) -> bool {
+        let Some(sound) = self.sounds.get(id).cloned() else {
+            return false;
+        };
+        let volume = if volume.is_finite() { volume.clamp(0.0, 1.0) } else { 1.0 };
+        self.mixer().play(Voice::new(sound, volume, false));
+        true
+    }
+
+    /// Loop `id` as background music, replacing the current track
+    pub fn play_music(&mut self, id: SoundId) -> bool {
+        let Some(sound) = self.sounds.get(id).cloned() else {
+            return false;
+        };
+        self.mixer().music = Some(Voice::new(sound, 1.0, true));
+        true
+    }
+
+    pub fn stop_music(&mut self) {
+        self.mixer().music = None;
+    }
+
+    pub fn set_volume(&mut self, channel: Channel, volume: f32) {
+        if volume.is_finite() {
+            self.mixer().volumes[channel as usize] = volume.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Sound played on bounces; INVALID_SOUND turns it off
+    pub fn set_bounce_sound(&mut self, id: SoundId) -> bool {
+        if id == INVALID_SOUND {
+            self.bounce_sound = None;
+            return true;
+        }
+        if !self.sounds.contains(id) {
+            return false;
+        }
+        self.bounce_sound = Some(id);
+        true
+    }
+
+    /// Play the bounce sound for an impact at `speed`
+    pub fn bounce(&mut self, speed: f32) {
+        if let Some(id) = self.bounce_sound {
+            self.play(id, (speed / LOUD_BOUNCE_SPEED).clamp(0.2, 1.0));
+        }
+    }
+
+    /// Render `frames` of output without a device (tests)
+    #[cfg(test)]
+    pub fn render(&self, frames: usize, channels: usize) -> Vec<f32> {
+        let mut out = vec![0.0; frames * channels];
+        self.mixer().mix(&mut out, channels);
+        out
+    }
+}
+
+#[cfg(feature = "audio")]
+mod output {
+    use std::sync::{Arc, Mutex};
+
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+    use super::Mixer;
+
+    /// Start a stream on the default output device pulling from `mixer`
+    pub fn open(mixer: Arc<Mutex<Mixer>>) -> Result<cpal::Stream, String> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or("No audio output device")?;
+        let supported = device.default_output_config().map_err(|e| e.to_string())?;
+        let format = supported.sample_format();
+        let config: cpal::StreamConfig = supported.into();
+        mixer.lock().unwrap_or_else(|e| e.into_inner()).output_rate = config.sample_rate.0;
+
+        let stream = match format {
+            cpal::SampleFormat::F32 => build::<f32>(&device, &config, mixer),
+            cpal::SampleFormat::I16 => build::<i16>(&device, &config, mixer),
+            cpal::SampleFormat::U16 => build::<u16>(&device, &config, mixer),
+            other => return Err(format!("Unsupported output sample format {:?}", other)),
+        }
+        .map_err(|e| e.to_string())?;
+        stream.play().map_err(|e| e.to_string())?;
+        Ok(stream)
+    }
+
+    fn build<T>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        mixer: Arc<Mutex<Mixer>>,
+    ) -> Result<cpal::Stream, cpal::BuildStreamError>
+    where
+        T: cpal::SizedSample + cpal::FromSample<f32>,
+    {
+        let channels = config.channels as usize;
+        let mut scratch = Vec::new();
+        device.build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                scratch.resize(data.len(), 0.0);
+                mixer.lock().unwrap_or_else(|e| e.into_inner()).mix(&mut scratch, channels);
+                for (out, sample) in data.iter_mut().zip(&scratch) {
+                    *out = T::from_sample(*sample);
+                }
+            },
+            |e| log::warn!("Audio stream error: {}", e),
+            None,
+        )
+    }
+}
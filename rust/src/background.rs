@@ -0,0 +1,38 @@
+use egui::epaint::{Mesh, Vertex, WHITE_UV};
+use egui::{Color32, Rect, Shape};
+
+/// Default clear color (RGBA)
+pub const DEFAULT_CLEAR_COLOR: [f32; 4] = [0.1, 0.1, 0.15, 1.0];
+
+/// What the scene is drawn over: a flat clear color, optionally covered by a vertical gradient
+/// Lets the host match its light/dark theme
+pub struct Background {
+    pub clear_color: [f32; 4],
+    /// Top and bottom colors
+    pub gradient: Option<(Color32, Color32)>,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self {
+            clear_color: DEFAULT_CLEAR_COLOR,
+            gradient: None,
+        }
+    }
+}
+
+/// Rect shaded from `top` to `bottom`
+pub fn vertical_gradient(rect: Rect, top: Color32, bottom: Color32) -> Shape {
+    let mut mesh = Mesh::default();
+    for (pos, color) in [
+        (rect.left_top(), top),
+        (rect.right_top(), top),
+        (rect.right_bottom(), bottom),
+        (rect.left_bottom(), bottom),
+    ] {
+        mesh.vertices.push(Vertex { pos, uv: WHITE_UV, color });
+    }
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(0, 2, 3);
+    Shape::mesh(mesh)
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use flutter_rust_bridge::frb;
+
+use crate::events::{GameEvent, SubscriptionId, Surface};
+use crate::frb_generated::StreamSink;
+use crate::haptics::HapticPattern;
+use crate::policy::RunSummary;
+use crate::scoreboard::Stat;
+use crate::stats::{CullStats, FrameStats, GpuPassStats};
+use crate::{call_engine, GameEngine};
+
+/// Dart-side state of one engine, touched by bridge calls (FRB worker threads) and service
+#[derive(Default)]
+struct Channels {
+    events: Option<StreamSink<GameEvent>>,
+    /// The engine's event subscription feeding `events`; service makes it once the stream opens and
+    /// drops it once the stream closes
+    subscription: Option<SubscriptionId>,
+    frame_stats: Option<FrameStatsStream>,
+}
+
+struct FrameStatsStream {
+    sink: StreamSink<FrameStats>,
+    interval: Duration,
+    last_sent: Option<Instant>,
+}
+
+/// Keyed by handle address; only engines Dart has subscribed to or queried have an entry
+fn channels() -> &'static Mutex<HashMap<usize, Channels>> {
+    static CHANNELS: OnceLock<Mutex<HashMap<usize, Channels>>> = OnceLock::new();
+    CHANNELS.get_or_init(Default::default)
+}
+
+fn with_channels<T>(handle: usize, f: impl FnOnce(&mut Channels) -> T) -> T {
+    let mut channels = channels().lock().unwrap_or_else(|e| e.into_inner());
+    f(channels.entry(handle).or_default())
+}
+
+/// Called by game_render after each frame, and after each step while the simulation thread runs:
+/// feed the streams
+pub(crate) fn service(handle: usize, engine: &mut GameEngine) {
+    let mut channels = channels().lock().unwrap_or_else(|e| e.into_inner());
+    let Some(channels) = channels.get_mut(&handle) else {
+        return;
+    };
+    match (&channels.events, channels.subscription) {
+        (Some(_), None) => channels.subscription = Some(engine.subscribe_events()),
+        (None, Some(subscription)) => {
+            engine.unsubscribe_events(subscription);
+            channels.subscription = None;
+        }
+        _ => {}
+    }
+    if let (Some(sink), Some(subscription)) = (&channels.events, channels.subscription) {
+        while let Some(event) = engine.poll_subscribed_event(subscription) {
+            if sink.add(event).is_err() {
+                engine.unsubscribe_events(subscription);
+                channels.events = None;
+                channels.subscription = None;
+                break;
+            }
+        }
+    }
+    if let Some(stream) = &mut channels.frame_stats {
+        let now = Instant::now();
+        if stream.last_sent.is_none_or(|last| now.duration_since(last) >= stream.interval) {
+            stream.last_sent = Some(now);
+            if stream.sink.add(engine.frame_stats()).is_err() {
+                channels.frame_stats = None;
+            }
+        }
+    }
+}
+
+/// Called by game_destroy; the streams stop
+pub(crate) fn forget(handle: usize) {
+    channels().lock().unwrap_or_else(|e| e.into_inner()).remove(&handle);
+}
+
+// Dart API. `handle` is the GameHandle's address (`Pointer.address`); the engine is still created,
+// sized and rendered through the C API, which stays the fast path for per-frame calls. Requests run
+// on the render thread like C API calls from another thread (see affinity), and return None if it
+// didn't get to them in time
+
+/// Engine events from the next rendered frame (or simulation step) on, delivered after each one
+/// The stream has a subscription of its own: game_poll_event still returns every event
+pub fn event_stream(handle: usize, sink: StreamSink<GameEvent>) {
+    with_channels(handle, |channels| channels.events = Some(sink));
+}
+
+/// Frame timing, at most every `interval_ms` (0 = every frame)
+pub fn frame_stats_stream(handle: usize, interval_ms: u32, sink: StreamSink<FrameStats>) {
+    let stream = FrameStatsStream { sink, interval: Duration::from_millis(interval_ms as u64), last_sent: None };
+    with_channels(handle, |channels| channels.frame_stats = Some(stream));
+}
+
+/// Close both streams
+pub fn close_streams(handle: usize) {
+    with_channels(handle, |channels| {
+        channels.events = None;
+        channels.frame_stats = None;
+    });
+}
+
+/// The next frame's display list as JSON (see game_dump_display_list)
+pub fn display_list(handle: usize) -> Option<String> {
+    call_engine(handle, |engine| engine.display_list().to_json())
+}
+
+/// Summary of the current run (see game_get_run_summary)
+pub fn run_summary(handle: usize) -> Option<RunSummary> {
+    call_engine(handle, |engine| engine.run_summary())
+}
+
+/// Serialized game state (see game_save_state)
+pub fn save_state(handle: usize) -> Option<Vec<u8>> {
+    call_engine(handle, |engine| engine.save_state())
+}
+
+/// Restore state from save_state; false if the data is invalid or the call was dropped
+pub fn restore_state(handle: usize, data: Vec<u8>) -> bool {
+    call_engine(handle, |engine| match engine.restore_state(&data) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("restore_state: {}", e);
+            false
+        }
+    })
+    .unwrap_or(false)
+}
+
+// Existing types exposed to Dart as-is
+
+#[frb(mirror(GameEvent))]
+pub enum _GameEvent {
+    Bounce { surface: Surface, x: f32, y: f32, speed: f32 },
+    AnimationFinished { node: u64 },
+    TextureReady { load: u64, width: f32, height: f32 },
+    TextureFailed { load: u64 },
+    Haptic { pattern: HapticPattern },
+    ReplayFinished,
+    FirstMeaningfulFrame,
+    EngineCrashed { message: String, backtrace: String },
+    TweenFinished { tween: u64, completed: bool },
+    CollisionEnter { a: u64, b: u64, x: f32, y: f32, depth: f32 },
+    CollisionExit { a: u64, b: u64 },
+    PathFinished { sprite: u64, x: f32, y: f32, arrived: bool },
+    ButtonPressed { widget: u64 },
+    StateChanged { from: u32, to: u32 },
+    StatUpdated { stat: Stat, value: f32 },
+}
+
+#[frb(mirror(Surface))]
+pub enum _Surface {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Obstacle,
+}
+
+#[frb(mirror(Stat))]
+pub enum _Stat {
+    Score,
+    Bounces,
+    Distance,
+    AutoTime,
+    ManualTime,
+    Throws,
+}
+
+#[frb(mirror(HapticPattern))]
+pub enum _HapticPattern {
+    Light,
+    Medium,
+    Heavy,
+    Success,
+    Warning,
+    Error,
+    Selection,
+}
+
+#[frb(mirror(FrameStats))]
+pub struct _FrameStats {
+    pub fps: f32,
+    pub cpu_ms: f32,
+    pub gpu_ms: f32,
+    pub frame_ms: f32,
+    pub frame_ms_p95: f32,
+    pub frame_count: u64,
+    pub culled: CullStats,
+    pub gpu_passes: GpuPassStats,
+}
+
+#[frb(mirror(GpuPassStats))]
+pub struct _GpuPassStats {
+    pub clear_ms: f32,
+    pub scene_ms: f32,
+    pub ui_ms: f32,
+}
+
+#[frb(mirror(CullStats))]
+pub struct _CullStats {
+    pub sprites: u32,
+    pub nodes: u32,
+    pub particles: u32,
+}
+
+#[frb(mirror(RunSummary))]
+pub struct _RunSummary {
+    pub seed: u32,
+    pub flags: u32,
+    pub rejected_mutations: u32,
+    pub elapsed_ms: u64,
+}
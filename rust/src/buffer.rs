@@ -0,0 +1,83 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Host allocation function: returns `size` bytes or null
+pub type AllocFn = unsafe extern "C" fn(size: usize) -> *mut u8;
+/// Host free function for memory returned by the matching AllocFn
+pub type FreeFn = unsafe extern "C" fn(ptr: *mut u8, size: usize);
+
+/// Bytes handed to the host; release with game_free_buffer
+/// `data` is null (and `len` 0) on failure
+#[repr(C)]
+#[derive(Debug)]
+pub struct GameBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl GameBuffer {
+    pub fn empty() -> Self {
+        Self {
+            data: std::ptr::null_mut(),
+            len: 0,
+        }
+    }
+}
+
+struct HostAllocator {
+    alloc: AllocFn,
+    free: FreeFn,
+}
+
+/// Installed at most once, before the first buffer is handed out,
+/// so every buffer is freed by the allocator that produced it
+static HOST_ALLOCATOR: OnceLock<HostAllocator> = OnceLock::new();
+static BUFFER_ISSUED: AtomicBool = AtomicBool::new(false);
+
+/// Route returned buffers through host functions
+/// Fails if an allocator is already set or a buffer was already returned
+pub fn set_allocator(alloc: AllocFn, free: FreeFn) -> bool {
+    if BUFFER_ISSUED.load(Ordering::Acquire) {
+        return false;
+    }
+    HOST_ALLOCATOR.set(HostAllocator { alloc, free }).is_ok()
+}
+
+/// Copy bytes into a buffer owned by the host
+pub fn into_buffer(bytes: Vec<u8>) -> GameBuffer {
+    BUFFER_ISSUED.store(true, Ordering::Release);
+    if bytes.is_empty() {
+        return GameBuffer::empty();
+    }
+
+    match HOST_ALLOCATOR.get() {
+        Some(host) => {
+            let data = unsafe { (host.alloc)(bytes.len()) };
+            if data.is_null() {
+                log::error!("Host allocator returned null for {} bytes", bytes.len());
+                return GameBuffer::empty();
+            }
+            unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), data, bytes.len()) };
+            GameBuffer { data, len: bytes.len() }
+        }
+        None => {
+            let len = bytes.len();
+            let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+            GameBuffer { data, len }
+        }
+    }
+}
+
+/// Release a buffer returned by into_buffer
+///
+/// # Safety
+/// `buffer` must come from into_buffer and not have been freed already
+pub unsafe fn free_buffer(buffer: GameBuffer) {
+    if buffer.data.is_null() {
+        return;
+    }
+    match HOST_ALLOCATOR.get() {
+        Some(host) => (host.free)(buffer.data, buffer.len),
+        None => drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(buffer.data, buffer.len))),
+    }
+}
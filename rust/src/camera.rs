@@ -0,0 +1,100 @@
+use egui::{Pos2, Vec2};
+
+use crate::scene::{Affine, Transform};
+
+/// Smallest and largest zoom game_set_camera accepts
+pub const MIN_ZOOM: f32 = 0.05;
+pub const MAX_ZOOM: f32 = 20.0;
+
+/// 2D view onto the world: which world point sits at the screen center, how far in it's zoomed and how
+/// it's turned. The default shows the world's origin at the top left of the screen at 1:1, as before
+/// cameras existed; the HUD and host draw lists stay in screen points
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    /// World point at the screen center; None until the host or follow moves the camera (screen center)
+    center: Option<Pos2>,
+    /// Center at the previous tick (for render interpolation)
+    prev_center: Option<Pos2>,
+    zoom: f32,
+    /// Radians the camera is turned clockwise (the world appears turned the other way)
+    rotation: f32,
+    /// How fast the center closes on the player (exponential rate per second); 0 = doesn't follow
+    follow: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera { center: None, prev_center: None, zoom: 1.0, rotation: 0.0, follow: 0.0 }
+    }
+}
+
+impl Camera {
+    /// Jump to `center` (world points) with `zoom` and `rotation`; following keeps easing from there
+    pub fn set(&mut self, center: Pos2, zoom: f32, rotation: f32) -> Result<(), String> {
+        if !(center.x.is_finite() && center.y.is_finite() && rotation.is_finite()) {
+            return Err("Camera values must be finite".into());
+        }
+        if !(MIN_ZOOM..=MAX_ZOOM).contains(&zoom) {
+            return Err(format!("Zoom {} outside {}..={}", zoom, MIN_ZOOM, MAX_ZOOM));
+        }
+        self.center = Some(center);
+        self.prev_center = Some(center);
+        self.zoom = zoom;
+        self.rotation = rotation;
+        Ok(())
+    }
+
+    /// Ease toward the player at `rate` per second (higher is snappier); 0 stops following
+    pub fn set_follow(&mut self, rate: f32) -> Result<(), String> {
+        if !(rate.is_finite() && rate >= 0.0) {
+            return Err(format!("Invalid follow rate {}", rate));
+        }
+        self.follow = rate;
+        Ok(())
+    }
+
+    /// Back to the default view, not following
+    pub fn reset(&mut self) {
+        *self = Camera::default();
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn store_previous_position(&mut self) {
+        self.prev_center = self.center;
+    }
+
+    /// Move toward `target` for `delta` seconds of following; `screen` places a camera that hasn't
+    /// moved yet
+    pub fn update(&mut self, delta: f32, target: Pos2, screen: Vec2) {
+        if self.follow == 0.0 {
+            return;
+        }
+        let center = self.center.unwrap_or((screen / 2.0).to_pos2());
+        let t = 1.0 - (-self.follow * delta).exp();
+        self.center = Some(center + (target - center) * t);
+        if self.prev_center.is_none() {
+            self.prev_center = Some(center);
+        }
+    }
+
+    /// Still easing toward `target` (more than a hundredth of a point away)
+    pub fn is_following(&self, target: Pos2, screen: Vec2) -> bool {
+        let center = self.center.unwrap_or((screen / 2.0).to_pos2());
+        self.follow > 0.0 && (target - center).length_sq() > 1e-4
+    }
+
+    /// World-to-screen transform for a `screen`-sized view, the center `alpha` of the way from the
+    /// previous tick
+    pub fn transform(&self, screen: Vec2, alpha: f32) -> Affine {
+        let half = screen / 2.0;
+        let center = match (self.prev_center, self.center) {
+            (Some(prev), Some(center)) => prev.lerp(center, alpha),
+            (_, center) => center.unwrap_or(half.to_pos2()),
+        };
+        let view = Affine::from_transform(&Transform { translation: half, rotation: -self.rotation, scale: Vec2::splat(self.zoom) });
+        view.then(&Affine::from_transform(&Transform { translation: -center.to_vec2(), ..Default::default() }))
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+bitflags::bitflags! {
+    /// Optional subsystems the host can ask for in game_negotiate
+    /// Bit values are part of the C API; never renumber
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Features: u64 {
+        /// Rigid-body physics for Auto mode (needs the `physics` cargo feature)
+        const PHYSICS = 1 << 0;
+        /// Background texture decoding (game_load_texture_async)
+        const ASYNC_ASSETS = 1 << 1;
+        /// Sound effects and music (needs the `audio` cargo feature)
+        const AUDIO = 1 << 2;
+        const NETWORK = 1 << 3;
+        const DEBUG_SERVER = 1 << 4;
+        const HAPTICS = 1 << 5;
+    }
+}
+
+/// Last negotiated request; everything available until the host negotiates
+static REQUESTED: AtomicU64 = AtomicU64::new(u64::MAX);
+
+impl Features {
+    /// What this build (and device) can provide
+    pub fn available() -> Features {
+        // Haptics are only requests on the event channel; the host drives the hardware
+        let mut features = Features::ASYNC_ASSETS | Features::HAPTICS;
+        if cfg!(feature = "physics") {
+            features |= Features::PHYSICS;
+        }
+        if cfg!(feature = "audio") {
+            features |= Features::AUDIO;
+        }
+        features
+    }
+}
+
+/// Record the host's request and return what it will actually get
+/// Applies to instances created afterwards
+pub fn negotiate(requested: u64) -> Features {
+    REQUESTED.store(requested, Ordering::Release);
+    Features::from_bits_truncate(requested) & Features::available()
+}
+
+/// Features for a new instance
+pub fn granted() -> Features {
+    Features::from_bits_truncate(REQUESTED.load(Ordering::Acquire)) & Features::available()
+}
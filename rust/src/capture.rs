@@ -0,0 +1,90 @@
+use egui::{Rect, Vec2};
+
+use crate::gl_debug;
+use crate::render_target::RenderTarget;
+
+/// Largest capture edge in pixels
+pub const MAX_CAPTURE_SIZE: u32 = 4096;
+
+/// RGBA8 pixels, top row first
+pub struct Capture {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Output size in pixels for a region captured at a scale
+pub fn output_size(region: Rect, scale: f32) -> Option<(u32, u32)> {
+    let width = (region.width() * scale).round();
+    let height = (region.height() * scale).round();
+    if !(width >= 1.0 && height >= 1.0) {
+        return None;
+    }
+    let (width, height) = (width as u32, height as u32);
+    if width > MAX_CAPTURE_SIZE || height > MAX_CAPTURE_SIZE {
+        return None;
+    }
+    Some((width, height))
+}
+
+/// Re-render an egui frame into a temporary FBO, cropped to `region` and scaled by `scale`
+/// Restores the caller's framebuffer binding and viewport afterwards
+pub fn render_region(
+    gl: &glow::Context,
+    painter: &mut egui_glow::Painter,
+    ctx: &egui::Context,
+    mut output: egui::FullOutput,
+    region: Rect,
+    scale: f32,
+    clear_color: [f32; 4],
+) -> Result<Capture, String> {
+    let (width, height) = output_size(region, scale).ok_or("Invalid capture size")?;
+
+    // Move the region's top-left corner to the origin
+    let offset = -region.min.to_vec2();
+    for clipped in &mut output.shapes {
+        clipped.clip_rect = clipped.clip_rect.translate(offset);
+        clipped.shape.translate(offset);
+    }
+    let primitives = ctx.tessellate(output.shapes, scale);
+
+    unsafe {
+        let target = RenderTarget::new(gl, width, height)?;
+        gl_debug::push_group(gl, "game_engine/capture");
+        let pixels = target.draw(gl, clear_color, || {
+            // Textures created by this frame must still be uploaded or they'd be lost
+            painter.paint_and_update_textures([width, height], scale, &primitives, &output.textures_delta);
+            target.read_pixels(gl)
+        });
+        gl_debug::pop_group(gl);
+        target.destroy(gl);
+
+        Ok(Capture { width, height, pixels: flip_rows(&pixels, width as usize * 4) })
+    }
+}
+
+impl Capture {
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        use image::ImageEncoder;
+
+        let mut png = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png)
+            .write_image(&self.pixels, self.width, self.height, image::ExtendedColorType::Rgba8)
+            .map_err(|e| e.to_string())?;
+        Ok(png)
+    }
+}
+
+/// GL reads bottom row first; flip to top row first
+fn flip_rows(pixels: &[u8], row_bytes: usize) -> Vec<u8> {
+    let mut flipped = Vec::with_capacity(pixels.len());
+    for row in pixels.chunks_exact(row_bytes).rev() {
+        flipped.extend_from_slice(row);
+    }
+    flipped
+}
+
+/// Rect from FFI arguments
+pub fn region(x: f32, y: f32, w: f32, h: f32) -> Rect {
+    Rect::from_min_size(egui::Pos2::new(x, y), Vec2::new(w, h))
+}
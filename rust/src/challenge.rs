@@ -0,0 +1,72 @@
+/// Crockford base32 alphabet (no I, L, O, U to avoid misreading)
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Length of a challenge code without the separator
+const CODE_CHARS: usize = 8;
+
+/// Derive the level seed for a given date seed (e.g. 20261016 for yyyymmdd)
+/// Mixed so consecutive dates produce unrelated levels
+pub fn daily_level_seed(date_seed: u32) -> u32 {
+    let mut z = date_seed as u64 ^ 0xD1B5_4A32_D192_ED03;
+    z = (z ^ (z >> 33)).wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    z = (z ^ (z >> 33)).wrapping_mul(0xC4CE_B9FE_1A85_EC53);
+    (z ^ (z >> 33)) as u32
+}
+
+/// 8-bit checksum over the seed bytes to catch typos
+fn checksum(seed: u32) -> u8 {
+    seed.to_le_bytes()
+        .iter()
+        .fold(0x5Au8, |acc, b| acc.rotate_left(3) ^ b)
+}
+
+/// Encode a level seed as a short shareable code, e.g. "7QK2-M9XD"
+pub fn encode(seed: u32) -> String {
+    // 32 seed bits + 8 checksum bits = 40 bits = 8 base32 chars
+    let bits = ((seed as u64) << 8) | checksum(seed) as u64;
+
+    let mut code = String::with_capacity(CODE_CHARS + 1);
+    for i in 0..CODE_CHARS {
+        if i == CODE_CHARS / 2 {
+            code.push('-');
+        }
+        let shift = (CODE_CHARS - 1 - i) * 5;
+        code.push(ALPHABET[((bits >> shift) & 0x1F) as usize] as char);
+    }
+    code
+}
+
+/// Decode a challenge code back into a level seed
+/// Case-insensitive, ignores separators, maps ambiguous characters (O->0, I/L->1)
+/// Returns None if the code is malformed or the checksum does not match
+pub fn decode(code: &str) -> Option<u32> {
+    let mut bits: u64 = 0;
+    let mut count = 0;
+
+    for c in code.chars() {
+        if c == '-' || c == ' ' {
+            continue;
+        }
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            other => other,
+        };
+        let value = ALPHABET.iter().position(|&a| a as char == c)?;
+        bits = (bits << 5) | value as u64;
+        count += 1;
+        if count > CODE_CHARS {
+            return None;
+        }
+    }
+
+    if count != CODE_CHARS {
+        return None;
+    }
+
+    let seed = (bits >> 8) as u32;
+    if (bits & 0xFF) as u8 != checksum(seed) {
+        return None;
+    }
+    Some(seed)
+}
@@ -0,0 +1,264 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use egui::epaint::Mesh;
+use egui::{PaintCallback, Painter, Pos2, Rect, Shape, Vec2};
+use glow::HasContext;
+
+use crate::scene::Affine;
+use crate::shader::{self, Quad, Resource};
+use crate::vector::{self, FillRule, Path, VectorPath};
+
+/// Deepest nesting of clips in one stack (mask clips use one stencil value per level)
+pub const MAX_CLIP_DEPTH: usize = 16;
+
+/// GL_STENCIL_BITS (GLES; glow only names the desktop core enums)
+const STENCIL_BITS: u32 = 0x0D57;
+
+/// Set once the missing stencil buffer has been logged
+static WARNED_NO_STENCIL: AtomicBool = AtomicBool::new(false);
+
+/// Cubic control point distance for a quarter circle of radius 1
+const KAPPA: f32 = 0.552_284_8;
+
+/// What content is clipped to, in screen points
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClipShape {
+    /// Axis-aligned: narrows the painter's clip rect (scissor), no stencil needed
+    Rect(Rect),
+    /// Any other outline, filled into the stencil buffer
+    Mask(Arc<Mask>),
+}
+
+/// Outline of a mask clip
+pub struct Mask {
+    mesh: Arc<Mesh>,
+    /// Content is also scissored to these
+    pub bounds: Rect,
+    /// Stencil callbacks made for this mask, reused every frame: egui compares callbacks by pointer,
+    /// so fresh ones would make identical frames look changed (see pacing module)
+    callbacks: Mutex<Vec<(StencilKey, Arc<egui_glow::CallbackFn>)>>,
+}
+
+/// Push or pop, at which depth, for which screen
+type StencilKey = (bool, u8, [u32; 4]);
+
+impl PartialEq for Mask {
+    fn eq(&self, other: &Self) -> bool {
+        self.bounds == other.bounds && self.mesh == other.mesh
+    }
+}
+
+impl std::fmt::Debug for Mask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mask").field("bounds", &self.bounds).field("triangles", &(self.mesh.indices.len() / 3)).finish()
+    }
+}
+
+impl ClipShape {
+    /// `rect` under `transform`, with corners rounded by `radius` (before the transform)
+    /// Stays a scissor rect when unrounded and the transform keeps it axis-aligned
+    pub fn rounded_rect(transform: &Affine, rect: Rect, radius: f32) -> Result<ClipShape, String> {
+        let [a, b, c, d, ..] = transform.to_array();
+        if radius <= 0.0 && b == 0.0 && c == 0.0 && a > 0.0 && d > 0.0 {
+            return Ok(ClipShape::Rect(Rect::from_two_pos(transform.apply(rect.min), transform.apply(rect.max))));
+        }
+        let r = radius.clamp(0.0, rect.width().min(rect.height()) / 2.0);
+        let k = r * (1.0 - KAPPA);
+        let p = |x: f32, y: f32| transform.apply(Pos2::new(x, y));
+        let (l, t, rt, bm) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+        let mut path = VectorPath::default();
+        path.move_to(p(l + r, t));
+        path.line_to(p(rt - r, t));
+        path.cubic_to(p(rt - k, t), p(rt, t + k), p(rt, t + r));
+        path.line_to(p(rt, bm - r));
+        path.cubic_to(p(rt, bm - k), p(rt - k, bm), p(rt - r, bm));
+        path.line_to(p(l + r, bm));
+        path.cubic_to(p(l + k, bm), p(l, bm - k), p(l, bm - r));
+        path.line_to(p(l, t + r));
+        path.cubic_to(p(l, t + k), p(l + k, t), p(l + r, t));
+        path.close();
+        ClipShape::path(&path.build(), FillRule::NonZero)
+    }
+
+    /// The inside of `path` (already in screen points) under `rule`
+    pub fn path(path: &Path, rule: FillRule) -> Result<ClipShape, String> {
+        let mesh = vector::fill(path, rule, egui::Color32::WHITE)?;
+        let bounds = vector::bounds(&mesh).unwrap_or(Rect::NOTHING);
+        Ok(ClipShape::Mask(Arc::new(Mask { mesh: Arc::new(mesh), bounds, callbacks: Mutex::default() })))
+    }
+
+    pub fn bounds(&self) -> Rect {
+        match self {
+            ClipShape::Rect(rect) => *rect,
+            ClipShape::Mask(mask) => mask.bounds,
+        }
+    }
+}
+
+/// Nested clips while painting, like a canvas save/clip/restore
+/// Rects narrow the painter's clip rect; masks add stencil callbacks around the content, so they
+/// need a stencil buffer (without one, masked content is only clipped to the mask's bounds)
+pub struct ClipStack {
+    base: Painter,
+    /// Current painter (clip rect) and, for mask clips, the mask to undo on pop
+    stack: Vec<(Painter, Option<Arc<Mask>>)>,
+    depth: u8,
+}
+
+impl ClipStack {
+    pub fn new(painter: &Painter) -> ClipStack {
+        ClipStack { base: painter.clone(), stack: Vec::new(), depth: 0 }
+    }
+
+    /// Paint clipped content with this
+    pub fn painter(&self) -> &Painter {
+        self.stack.last().map_or(&self.base, |(painter, _)| painter)
+    }
+
+    pub fn push(&mut self, clip: &ClipShape) {
+        let current = self.painter();
+        let painter = current.with_clip_rect(current.clip_rect().intersect(clip.bounds()));
+        let mask = match clip {
+            ClipShape::Rect(_) => None,
+            ClipShape::Mask(mask) => {
+                self.depth += 1;
+                self.callback(mask, true);
+                Some(mask.clone())
+            }
+        };
+        self.stack.push((painter, mask));
+    }
+
+    /// Undo the last push (no-op on an empty stack)
+    pub fn pop(&mut self) {
+        if let Some((_, Some(mask))) = self.stack.pop() {
+            self.callback(&mask, false);
+            self.depth -= 1;
+        }
+    }
+
+    /// Pop everything still pushed, leaving the stencil as it was
+    pub fn finish(mut self) {
+        while !self.stack.is_empty() {
+            self.pop();
+        }
+    }
+
+    /// Stencil updates cover the whole screen so egui never culls them
+    fn callback(&self, mask: &Mask, push: bool) {
+        let screen = self.base.ctx().screen_rect();
+        let key = (push, self.depth, [screen.min.x, screen.min.y, screen.max.x, screen.max.y].map(f32::to_bits));
+        let mut callbacks = mask.callbacks.lock().unwrap_or_else(|e| e.into_inner());
+        let callback = match callbacks.iter().find(|(k, _)| *k == key) {
+            Some((_, callback)) => callback.clone(),
+            None => {
+                let (mesh, depth) = (mask.mesh.clone(), self.depth);
+                let stencil = if push { Stencil::Push { mesh, depth } } else { Stencil::Pop { mesh, depth } };
+                // Masks map `screen` onto the callback's viewport, which captures move along with the content
+                let callback = egui_glow::CallbackFn::new(move |_, painter| unsafe { stencil.apply(painter.gl(), screen) });
+                let callback = Arc::new(callback);
+                callbacks.push((key, callback.clone()));
+                callback
+            }
+        };
+        self.base.with_clip_rect(screen).add(Shape::Callback(PaintCallback { rect: screen, callback }));
+    }
+}
+
+/// Shapes a mask clip adds around its content (push and pop callbacks)
+pub const MASK_SHAPES: usize = 2;
+
+/// Stencil update between two egui primitives
+enum Stencil {
+    /// Raise pixels inside `mesh` from depth - 1 to `depth`, and draw only where the stencil is `depth`
+    Push { mesh: Arc<Mesh>, depth: u8 },
+    /// Lower them back, and draw where the stencil is depth - 1
+    Pop { mesh: Arc<Mesh>, depth: u8 },
+}
+
+impl Stencil {
+    unsafe fn apply(&self, gl: &Arc<glow::Context>, screen: Rect) {
+        // (mask, stencil value it applies to, operation, value content draws at afterwards)
+        let (mesh, test, op, after) = match self {
+            Stencil::Push { mesh, depth } => (mesh, depth - 1, glow::INCR, *depth),
+            Stencil::Pop { mesh, depth } => (mesh, *depth, glow::DECR, depth - 1),
+        };
+        // Checked per frame: the target may be the surface or an offscreen capture
+        if gl.get_parameter_i32(STENCIL_BITS) == 0 {
+            if !WARNED_NO_STENCIL.swap(true, Ordering::Relaxed) {
+                log::warn!("No stencil buffer: clip masks fall back to their bounds");
+            }
+            return;
+        }
+        if !shader::with(gl, |gl| MaskProgram::new(gl), |program| program.draw(gl, mesh, screen, test, op)) {
+            return;
+        }
+        if after == 0 {
+            gl.disable(glow::STENCIL_TEST);
+        } else {
+            gl.stencil_func(glow::EQUAL, after as i32, 0xff);
+        }
+        gl.stencil_op(glow::KEEP, glow::KEEP, glow::KEEP);
+    }
+}
+
+/// Shader drawing mask triangles into the stencil buffer, one per GL context
+struct MaskProgram {
+    program: glow::Program,
+    buffer: glow::Buffer,
+    index_buffer: glow::Buffer,
+    /// Its vertex array binds `buffer` instead of the quad's own
+    quad: Quad,
+    u_viewport: Option<glow::UniformLocation>,
+}
+
+const MASK_VERTEX: &str = "ATTRIBUTE vec2 a_pos;\nuniform vec4 u_viewport;\n\
+    void main() {\n  vec2 ndc = (a_pos - u_viewport.xy) / u_viewport.zw * 2.0 - 1.0;\n  \
+    gl_Position = vec4(ndc.x, -ndc.y, 0.0, 1.0);\n}\n";
+
+const MASK_FRAGMENT: &str = "void main() { FRAG_COLOR = vec4(1.0); }\n";
+
+impl MaskProgram {
+    unsafe fn new(gl: &glow::Context) -> Result<MaskProgram, String> {
+        let program = shader::compile(gl, MASK_VERTEX, MASK_FRAGMENT)?;
+        Ok(MaskProgram {
+            program,
+            buffer: gl.create_buffer()?,
+            index_buffer: gl.create_buffer()?,
+            quad: Quad::new(gl)?,
+            u_viewport: gl.get_uniform_location(program, "u_viewport"),
+        })
+    }
+
+    /// Apply `op` to the stencil where `mesh` (points within `viewport`) covers it and the stencil equals `test`
+    unsafe fn draw(&self, gl: &glow::Context, mesh: &Mesh, viewport: Rect, test: u8, op: u32) {
+        let positions: Vec<f32> = mesh.vertices.iter().flat_map(|v| [v.pos.x, v.pos.y]).collect();
+        gl.enable(glow::STENCIL_TEST);
+        gl.stencil_mask(0xff);
+        gl.stencil_func(glow::EQUAL, test as i32, 0xff);
+        gl.stencil_op(glow::KEEP, glow::KEEP, op);
+        gl.color_mask(false, false, false, false);
+        gl.disable(glow::BLEND);
+
+        gl.use_program(Some(self.program));
+        let Vec2 { x: width, y: height } = viewport.size();
+        gl.uniform_4_f32(self.u_viewport.as_ref(), viewport.min.x, viewport.min.y, width, height);
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.buffer));
+        gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, shader::bytes_of(&positions), glow::STREAM_DRAW);
+        self.quad.bind(gl, self.buffer);
+        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.index_buffer));
+        gl.buffer_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, shader::bytes_of(&mesh.indices), glow::STREAM_DRAW);
+        gl.draw_elements(glow::TRIANGLES, mesh.indices.len() as i32, glow::UNSIGNED_INT, 0);
+        self.quad.unbind(gl);
+    }
+}
+
+impl Resource for MaskProgram {
+    unsafe fn delete(&self, gl: &glow::Context) {
+        gl.delete_program(self.program);
+        gl.delete_buffer(self.buffer);
+        gl.delete_buffer(self.index_buffer);
+        self.quad.delete(gl);
+    }
+}
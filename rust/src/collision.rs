@@ -0,0 +1,286 @@
+use std::collections::BTreeSet;
+
+use egui::{Pos2, Rect, Vec2};
+
+use crate::arena::{Arena, Id, INVALID_ID};
+use crate::scene::NodeId;
+use crate::spatial::SpatialHash;
+use crate::sprites::SpriteId;
+
+/// Collider id handed across FFI
+pub type ColliderId = Id;
+
+/// Invalid collider id returned on failure
+pub const INVALID_COLLIDER: ColliderId = INVALID_ID;
+
+/// Most registered colliders
+pub const MAX_COLLIDERS: usize = 4096;
+
+/// Broad-phase cell side in world points
+const CELL: f32 = 128.0;
+
+/// Collider outline in world points, centered on its owner's position plus an offset
+/// Shapes don't rotate or scale with their owner
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    /// Axis-aligned box of this size
+    Box(Vec2),
+    /// Circle of this radius
+    Circle(f32),
+}
+
+/// Shape kinds across FFI
+pub const SHAPE_BOX: u32 = 0;
+pub const SHAPE_CIRCLE: u32 = 1;
+
+impl Shape {
+    /// Shape from its FFI kind: a `width` x `height` box, or a circle `width` across (height ignored)
+    pub fn from_raw(kind: u32, width: f32, height: f32) -> Option<Shape> {
+        match kind {
+            SHAPE_BOX => Some(Shape::Box(Vec2::new(width, height))),
+            SHAPE_CIRCLE => Some(Shape::Circle(width / 2.0)),
+            _ => None,
+        }
+    }
+
+    pub fn validate(self) -> Result<(), String> {
+        let valid = match self {
+            Shape::Box(size) => size.x > 0.0 && size.y > 0.0 && size.is_finite(),
+            Shape::Circle(radius) => radius > 0.0 && radius.is_finite(),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("Invalid collider shape {:?}", self))
+        }
+    }
+
+    fn bounds(self, center: Pos2) -> Rect {
+        match self {
+            Shape::Box(size) => Rect::from_center_size(center, size),
+            Shape::Circle(radius) => Rect::from_center_size(center, Vec2::splat(radius * 2.0)),
+        }
+    }
+
+    fn contains(self, center: Pos2, point: Pos2) -> bool {
+        match self {
+            Shape::Box(size) => Rect::from_center_size(center, size).contains(point),
+            Shape::Circle(radius) => center.distance_sq(point) <= radius * radius,
+        }
+    }
+}
+
+/// The entity a collider follows
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Owner {
+    /// Centered on the sprite
+    Sprite(SpriteId),
+    /// At the node's world origin (the player node follows the player)
+    Node(NodeId),
+}
+
+/// Owner kinds across FFI
+pub const OWNER_SPRITE: u32 = 0;
+pub const OWNER_NODE: u32 = 1;
+
+impl Owner {
+    /// FFI kind and entity id
+    pub fn to_raw(self) -> (u32, u64) {
+        match self {
+            Owner::Sprite(id) => (OWNER_SPRITE, id),
+            Owner::Node(id) => (OWNER_NODE, id),
+        }
+    }
+}
+
+struct Collider {
+    owner: Owner,
+    shape: Shape,
+    offset: Vec2,
+    /// Center at the last step; None until the first
+    center: Option<Pos2>,
+}
+
+/// A change in which colliders overlap; `a` is the collider registered in the lower slot
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Contact {
+    /// Started overlapping: `point` is the middle of the overlap, `depth` how far they'd have to move
+    /// apart to only touch
+    Enter { a: ColliderId, b: ColliderId, point: Pos2, depth: f32 },
+    /// Stopped overlapping, or one of them was removed
+    Exit { a: ColliderId, b: ColliderId },
+}
+
+/// Colliders attached to sprites and nodes, checked for overlaps once per tick
+/// A spatial hash finds candidate pairs; only changes are reported, as enter and exit contacts
+pub struct Colliders {
+    colliders: Arena<Collider>,
+    index: SpatialHash,
+    /// Overlapping pairs (lower slot first) after the last step
+    touching: BTreeSet<(ColliderId, ColliderId)>,
+    /// Scratch for step, kept so steady-state ticks don't allocate: candidates near a collider, and
+    /// the overlapping pairs found (sorted by pair once complete)
+    nearby: Vec<ColliderId>,
+    overlaps: Vec<((ColliderId, ColliderId), (Pos2, f32))>,
+}
+
+impl Default for Colliders {
+    fn default() -> Self {
+        Colliders {
+            colliders: Arena::default(),
+            index: SpatialHash::new(CELL),
+            touching: BTreeSet::new(),
+            nearby: Vec::new(),
+            overlaps: Vec::new(),
+        }
+    }
+}
+
+/// Pair key for `a` and `b` with the lower slot first
+fn pair(a: ColliderId, b: ColliderId) -> (ColliderId, ColliderId) {
+    if (a as u32) < (b as u32) {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+impl Colliders {
+    /// Register a collider on `owner`; it's checked from the next step on
+    pub fn add(&mut self, owner: Owner, shape: Shape, offset: Vec2) -> Result<ColliderId, String> {
+        shape.validate()?;
+        if !offset.is_finite() {
+            return Err(format!("Invalid collider offset {:?}", offset));
+        }
+        if self.colliders.count() == MAX_COLLIDERS {
+            return Err(format!("At most {} colliders", MAX_COLLIDERS));
+        }
+        Ok(self.colliders.insert(Collider { owner, shape, offset, center: None }))
+    }
+
+    /// Unregister a collider, ending its contacts now; false if it's unknown
+    pub fn remove(&mut self, id: ColliderId, contacts: &mut Vec<Contact>) -> bool {
+        if self.colliders.remove(id).is_none() {
+            return false;
+        }
+        self.index.remove(id);
+        self.end_contacts(|a, b| a == id || b == id, contacts);
+        true
+    }
+
+    /// Drop the colliders whose owner `keep` rejects, ending their contacts now
+    pub fn retain(&mut self, mut keep: impl FnMut(Owner) -> bool, contacts: &mut Vec<Contact>) {
+        let gone: Vec<ColliderId> = self.colliders.entries().filter(|(_, c)| !keep(c.owner)).map(|(id, _)| id).collect();
+        for id in gone {
+            self.remove(id, contacts);
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.colliders.count()
+    }
+
+    /// Owners of the colliders covering `point` at the last step, each once, in no particular order
+    pub fn owners_at(&self, point: Pos2, out: &mut Vec<Owner>) {
+        out.clear();
+        let mut nearby = Vec::new();
+        self.index.query(Rect::from_min_max(point, point), &mut nearby);
+        for collider in nearby.into_iter().filter_map(|id| self.colliders.get(id)) {
+            if collider.center.is_some_and(|center| collider.shape.contains(center, point)) && !out.contains(&collider.owner) {
+                out.push(collider.owner);
+            }
+        }
+    }
+
+    /// Whether `a` and `b` overlapped at the last step
+    pub fn touching(&self, a: ColliderId, b: ColliderId) -> bool {
+        self.touching.contains(&pair(a, b))
+    }
+
+    fn end_contacts(&mut self, mut ends: impl FnMut(ColliderId, ColliderId) -> bool, contacts: &mut Vec<Contact>) {
+        self.touching.retain(|&(a, b)| {
+            let ended = ends(a, b);
+            if ended {
+                contacts.push(Contact::Exit { a, b });
+            }
+            !ended
+        });
+    }
+
+    /// Move colliders to where `locate` puts their owners and report overlaps that started or ended
+    /// Colliders whose owner is gone (`locate` returns None) are dropped, ending their contacts
+    pub fn step(&mut self, mut locate: impl FnMut(Owner) -> Option<Pos2>, contacts: &mut Vec<Contact>) {
+        let mut gone = Vec::new();
+        for (id, collider) in self.colliders.entries_mut() {
+            collider.center = locate(collider.owner).map(|position| position + collider.offset);
+            match collider.center {
+                Some(center) => self.index.update(id, collider.shape.bounds(center)),
+                None => gone.push(id),
+            }
+        }
+        for &id in &gone {
+            self.remove(id, contacts);
+        }
+
+        let (mut nearby, mut now) = (std::mem::take(&mut self.nearby), std::mem::take(&mut self.overlaps));
+        now.clear();
+        for (a, first) in self.colliders.entries() {
+            let Some(center_a) = first.center else {
+                continue;
+            };
+            self.index.query(first.shape.bounds(center_a), &mut nearby);
+            // Each pair once: from its lower slot
+            for &b in nearby.iter().filter(|&&b| (b as u32) > (a as u32)) {
+                let Some((second, center_b)) = self.colliders.get(b).and_then(|c| Some((c, c.center?))) else {
+                    continue;
+                };
+                if let Some((point, depth)) = overlap(first.shape, center_a, second.shape, center_b) {
+                    now.push(((a, b), (point, depth)));
+                }
+            }
+        }
+        now.sort_unstable_by_key(|&(key, _)| key);
+
+        self.end_contacts(|a, b| now.binary_search_by_key(&(a, b), |&(key, _)| key).is_err(), contacts);
+        for &(key, (point, depth)) in &now {
+            if self.touching.insert(key) {
+                contacts.push(Contact::Enter { a: key.0, b: key.1, point, depth });
+            }
+        }
+        (self.nearby, self.overlaps) = (nearby, now);
+    }
+}
+
+/// Middle of the overlap and its depth, if the shapes overlap (touching edges don't)
+fn overlap(a: Shape, center_a: Pos2, b: Shape, center_b: Pos2) -> Option<(Pos2, f32)> {
+    match (a, b) {
+        (Shape::Box(_), Shape::Box(_)) => {
+            let shared = a.bounds(center_a).intersect(b.bounds(center_b));
+            let depth = shared.width().min(shared.height());
+            shared.is_positive().then(|| (shared.center(), depth))
+        }
+        (Shape::Circle(radius_a), Shape::Circle(radius_b)) => {
+            let between = center_b - center_a;
+            let distance = between.length();
+            let depth = radius_a + radius_b - distance;
+            let direction = if distance > 0.0 { between / distance } else { Vec2::X };
+            (depth > 0.0).then(|| (center_a + direction * (radius_a - depth / 2.0), depth))
+        }
+        (Shape::Box(size), Shape::Circle(radius)) => box_circle(center_a, size, center_b, radius),
+        (Shape::Circle(radius), Shape::Box(size)) => box_circle(center_b, size, center_a, radius),
+    }
+}
+
+fn box_circle(center: Pos2, size: Vec2, circle: Pos2, radius: f32) -> Option<(Pos2, f32)> {
+    let rect = Rect::from_center_size(center, size);
+    let closest = rect.clamp(circle);
+    let distance = closest.distance(circle);
+    let depth = if rect.contains(circle) {
+        // Center inside the box: out through the nearest side
+        let inside = [circle.x - rect.min.x, rect.max.x - circle.x, circle.y - rect.min.y, rect.max.y - circle.y];
+        radius + inside.into_iter().fold(f32::INFINITY, f32::min)
+    } else {
+        radius - distance
+    };
+    (depth > 0.0).then(|| (closest.lerp(circle, 0.5), depth))
+}
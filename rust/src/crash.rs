@@ -0,0 +1,64 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic;
+use std::sync::Once;
+
+use crate::json::{object, string};
+
+/// A panic caught at the FFI boundary
+#[derive(Clone, Debug)]
+pub struct Crash {
+    /// Panic message with its source location
+    pub message: String,
+    /// Symbolicated where the binary has symbols (debug builds, unstripped libraries), addresses otherwise
+    pub backtrace: String,
+}
+
+impl Crash {
+    /// `{"message":..,"backtrace":..}` for game_last_crash
+    pub fn to_json(&self) -> String {
+        object([("message", string(&self.message)), ("backtrace", string(&self.backtrace))]).to_string()
+    }
+}
+
+thread_local! {
+    /// Filled by the panic hook, taken by catch_panic! on the same thread once the panic unwinds
+    static LAST_PANIC: RefCell<Option<Crash>> = const { RefCell::new(None) };
+}
+
+/// Chain a hook that records each panic's message, location and backtrace for the FFI layer
+/// The previous hook (stderr on desktop) still runs; later calls do nothing
+pub fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let location = info.location().map_or_else(String::new, |l| format!(" at {}:{}", l.file(), l.line()));
+            let crash = Crash {
+                message: format!("{}{}", payload_message(info.payload()), location),
+                backtrace: format!("{:?}", backtrace::Backtrace::new()),
+            };
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(crash));
+            previous(info);
+        }));
+    });
+}
+
+/// Details of the panic that unwound to catch_panic! with `payload`
+/// Falls back to the payload alone if the hook wasn't installed or the panic came from another thread
+pub fn take(payload: &(dyn Any + Send)) -> Crash {
+    LAST_PANIC.with(|last| last.borrow_mut().take()).unwrap_or_else(|| Crash {
+        message: payload_message(payload),
+        backtrace: String::new(),
+    })
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Unknown panic".to_string()
+    }
+}
@@ -0,0 +1,141 @@
+use egui::epaint::ClippedShape;
+use egui::text::Fonts;
+use egui::{Align2, Color32, FontId, Pos2, Rect, Rounding, Shape, Stroke, Vec2};
+
+use crate::palette::Palette;
+use crate::stats::{EventStats, FrameStats};
+use crate::text::GlyphAtlasStats;
+
+/// Whether this build can draw the overlay (text needs the `debug-overlay` feature's fonts)
+pub const AVAILABLE: bool = cfg!(feature = "debug-overlay");
+
+const PANEL_COLOR: Color32 = Color32::from_black_alpha(180);
+const TOUCH_RADIUS: f32 = 24.0;
+const MARGIN: f32 = 8.0;
+const FONT_SIZE: f32 = 14.0;
+
+/// Per-frame numbers shown by the overlay
+pub struct DebugInfo {
+    pub stats: FrameStats,
+    pub events: EventStats,
+    pub entities: usize,
+    pub glyphs: GlyphAtlasStats,
+    /// Text colors follow the player's palette
+    pub palette: Palette,
+}
+
+/// On-screen diagnostics drawn above everything (game_set_debug_overlay)
+/// Bookkeeping runs even while hidden so the first shown frame is accurate
+#[derive(Default)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+    /// Last touch position and whether the finger is still down
+    touch: Option<(Pos2, bool)>,
+    /// Clipped primitives painted last frame
+    draw_calls: usize,
+    /// Most recent non-zero glGetError value
+    last_gl_error: Option<u32>,
+}
+
+impl DebugOverlay {
+    pub fn record_touch(&mut self, pos: Pos2, down: bool) {
+        self.touch = Some((pos, down));
+    }
+
+    pub fn record_frame(&mut self, draw_calls: usize, gl_error: u32) {
+        self.draw_calls = draw_calls;
+        if gl_error != glow::NO_ERROR {
+            self.last_gl_error = Some(gl_error);
+        }
+    }
+
+    /// Overlay shapes for the current frame, to paint after the scene
+    pub fn shapes(&self, fonts: &Fonts, screen: Rect, info: &DebugInfo) -> Vec<ClippedShape> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let (text_color, error_color) = info.palette.status_colors();
+        let stats = &info.stats;
+        let mut lines = vec![
+            (format!("FPS {:.1}  frame {:.2} ms (p95 {:.2})", stats.fps, stats.frame_ms, stats.frame_ms_p95), text_color),
+            (format!("CPU {:.2} ms  GPU {:.2} ms", stats.cpu_ms, stats.gpu_ms), text_color),
+            (format!("entities {}  draw calls {}", info.entities, self.draw_calls), text_color),
+        ];
+        let gpu = &stats.gpu_passes;
+        if gpu.clear_ms + gpu.scene_ms + gpu.ui_ms > 0.0 {
+            lines.push((
+                format!("GPU clear {:.2}  scene {:.2}  ui {:.2} ms", gpu.clear_ms, gpu.scene_ms, gpu.ui_ms),
+                text_color,
+            ));
+        }
+        let culled = &stats.culled;
+        if culled.sprites + culled.nodes + culled.particles > 0 {
+            lines.push((
+                format!("culled sprites {}  nodes {}  particles {}", culled.sprites, culled.nodes, culled.particles),
+                text_color,
+            ));
+        }
+        let glyphs = &info.glyphs;
+        if glyphs.pages > 0 {
+            lines.push((
+                format!(
+                    "glyphs {}  pages {}/{} ({:.0}% full)  evicted {}",
+                    glyphs.glyphs,
+                    glyphs.pages,
+                    glyphs.max_pages,
+                    glyphs.occupancy * 100.0,
+                    glyphs.evicted
+                ),
+                text_color,
+            ));
+        }
+        for (name, path) in [("events", &info.events.outbound), ("input", &info.events.input)] {
+            let color = if path.dropped > 0 { error_color } else { text_color };
+            lines.push((
+                format!(
+                    "{} {}/{} drop {} coal {}  max {:.1} ms",
+                    name, path.delivered, path.published, path.dropped, path.coalesced, path.max_latency_ms
+                ),
+                color,
+            ));
+        }
+        match self.touch {
+            Some((pos, down)) => lines.push((
+                format!("touch {:.0}, {:.0} ({})", pos.x, pos.y, if down { "down" } else { "up" }),
+                text_color,
+            )),
+            None => lines.push(("touch none".to_string(), text_color)),
+        }
+        match self.last_gl_error {
+            Some(error) => lines.push((format!("last GL error {:#x}", error), error_color)),
+            None => lines.push(("no GL errors".to_string(), text_color)),
+        }
+
+        let font = FontId::monospace(FONT_SIZE);
+        let mut text = Vec::with_capacity(lines.len());
+        let mut cursor = screen.min + Vec2::splat(MARGIN * 2.0);
+        let mut panel = Rect::NOTHING;
+        for (line, color) in lines {
+            let shape = Shape::text(fonts, cursor, Align2::LEFT_TOP, line, font.clone(), color);
+            panel = panel.union(shape.visual_bounding_rect());
+            cursor.y += FONT_SIZE * 1.3;
+            text.push(shape);
+        }
+
+        let mut shapes = vec![Shape::rect_filled(panel.expand(MARGIN), Rounding::same(4.0), PANEL_COLOR)];
+        shapes.extend(text);
+        // Crosshair under an active touch
+        if let Some((pos, true)) = self.touch {
+            let (x, y) = (Vec2::X * TOUCH_RADIUS, Vec2::Y * TOUCH_RADIUS);
+            shapes.push(Shape::circle_stroke(pos, TOUCH_RADIUS, Stroke::new(2.0, text_color)));
+            shapes.push(Shape::line_segment([pos - x, pos + x], Stroke::new(1.0, text_color)));
+            shapes.push(Shape::line_segment([pos - y, pos + y], Stroke::new(1.0, text_color)));
+        }
+
+        shapes
+            .into_iter()
+            .map(|shape| ClippedShape { clip_rect: screen, shape })
+            .collect()
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::HashMap;
+
+use egui::epaint::tessellator::path;
+use egui::epaint::Mesh;
+use egui::{Align, Color32, FontId, Painter, Pos2, Rect, Rounding, Shape, Stroke, TextureId, Vec2};
+
+use crate::atlas::AtlasId;
+use crate::clip::ClipShape;
+use crate::draw_list::{self, DrawCommand, DRAW_LAYER_ABOVE, DRAW_LAYER_BELOW};
+use crate::ffi::strings::{self, NameId};
+use crate::json::{number, numbers, object, string, Value};
+use crate::material::MaterialDraw;
+use crate::scene::{self, Affine, NodeId};
+use crate::sprite_batch::SpriteBatch;
+use crate::sprites::SpriteId;
+use crate::stats::CullStats;
+use crate::text::Label;
+use crate::tilemap::TileDraw;
+use crate::ui::{WidgetDraw, WidgetKind};
+use crate::vector;
+
+/// Where a textured node's pixels come from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextureSource {
+    /// The player image asset
+    Player,
+    /// A loaded texture by name (extra players' own images)
+    Asset(NameId),
+    /// Current flipbook frame of an atlas
+    Atlas { atlas: AtlasId, frame: usize },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeVisual {
+    Rect { fill: Color32, stroke: Stroke },
+    Image { texture: TextureId, source: TextureSource, uv: Rect, tint: Color32 },
+    /// Glyphs in node-local points; `size` is the bounds of the text, not centered on the origin
+    Text(Label),
+}
+
+/// A scene node resolved for this frame
+#[derive(Clone, PartialEq)]
+pub struct NodeDraw {
+    pub node: NodeId,
+    pub z: i32,
+    pub world: Affine,
+    /// Local size, centered on the node origin
+    pub size: Vec2,
+    pub visual: NodeVisual,
+}
+
+/// Everything a frame draws, in draw order, before tessellation (points)
+/// Painted by the renderer and dumped as JSON for golden-list tests and host-side debugging
+/// Two equal lists draw the same pixels (on-demand rendering compares them to skip frames)
+#[derive(Clone, PartialEq)]
+pub struct DisplayList {
+    pub screen: Rect,
+    /// Screen less the safe-area insets (game_set_safe_area_insets); the HUD is laid out in it
+    pub safe_area: Rect,
+    /// World-to-screen transform (game_set_camera) for obstacles, sprites, nodes and particles
+    /// The background, host draw commands and HUD are in screen points
+    pub camera: Affine,
+    pub clear_color: [f32; 4],
+    /// Top and bottom colors
+    pub gradient: Option<(Color32, Color32)>,
+    /// Tilemap chunks in view, bottom layer first
+    pub tiles: Vec<TileDraw>,
+    pub obstacles: Vec<(Rect, Color32)>,
+    /// With the custom shader drawing it, if the host gave it one
+    pub sprites: Vec<(SpriteId, Rect, Color32, Option<MaterialDraw>)>,
+    /// Sorted by z
+    pub nodes: Vec<NodeDraw>,
+    /// Every particle in view, batched into one untextured mesh
+    pub particles: Mesh,
+    /// Sprites, nodes and particles left out for lying outside the camera's view (plus CULL_MARGIN)
+    pub culled: CullStats,
+    /// Zoomed out past LodSettings::simplify_below_zoom: sprites and obstacles drop their rounded corners
+    pub simplified: bool,
+    /// Sprites and image nodes are drawn in batches rather than as egui meshes (game_set_sprite_batching)
+    pub batching: bool,
+    /// Host draw commands (game_submit_draw_list), indexed by DRAW_LAYER_*
+    pub host: [Vec<DrawCommand>; 2],
+    /// In-engine UI widgets (game_ui_*), drawn on the HUD layer beneath the QR overlay
+    pub ui: Vec<WidgetDraw>,
+    /// Bounds of the QR overlay, drawn by the HUD
+    pub qr: Option<Rect>,
+    /// Startup splash drawn instead of the layers while assets load
+    pub splash: bool,
+}
+
+impl DisplayList {
+    /// `next` as seen `alpha` of the way to it from this list (0 = this one), for frames drawn between
+    /// simulation snapshots: the camera and the sprites and nodes in both lists move, the rest is `next`'s
+    pub fn interpolate(&self, next: &DisplayList, alpha: f32) -> DisplayList {
+        let mut list = next.clone();
+        list.camera = self.camera.lerp(&next.camera, alpha);
+        let sprites: HashMap<SpriteId, Rect> = self.sprites.iter().map(|&(id, rect, ..)| (id, rect)).collect();
+        for (id, rect, ..) in &mut list.sprites {
+            if let Some(previous) = sprites.get(id) {
+                *rect = previous.lerp_towards(rect, alpha);
+            }
+        }
+        let nodes: HashMap<NodeId, Affine> = self.nodes.iter().map(|node| (node.node, node.world)).collect();
+        for node in &mut list.nodes {
+            if let Some(previous) = nodes.get(&node.node) {
+                node.world = previous.lerp(&node.world, alpha);
+            }
+        }
+        list
+    }
+
+    /// Paint the scene layers (everything but the HUD)
+    pub fn paint(&self, painter: &Painter) {
+        if let Some((top, bottom)) = self.gradient {
+            painter.add(crate::background::vertical_gradient(self.screen, top, bottom));
+        }
+
+        draw_list::paint_layer(&self.host[DRAW_LAYER_BELOW as usize], painter);
+
+        let camera = &self.camera;
+
+        // Tilemaps beneath everything else in the world
+        for draw in &self.tiles {
+            let mut mesh = (*draw.mesh).clone();
+            mesh.texture_id = draw.texture;
+            if *camera != Affine::IDENTITY {
+                for vertex in &mut mesh.vertices {
+                    vertex.pos = camera.apply(vertex.pos);
+                }
+            }
+            painter.add(Shape::mesh(mesh));
+        }
+        let (obstacle_rounding, sprite_rounding) = if self.simplified { (0.0, 0.0) } else { (6.0, 4.0) };
+
+        // Level obstacles (drawn beneath the player)
+        for &(rect, color) in &self.obstacles {
+            painter.add(rounded_rect(camera, rect, obstacle_rounding, color));
+        }
+
+        // Host-driven sprites, then image nodes, batched in runs (see sprite_batch) when `batching`
+        // Custom shaders fill an axis-aligned rect, so under a turned camera they cover the sprite's bounds
+        let mut batch = SpriteBatch::new(self.screen);
+        for (_, rect, color, material) in &self.sprites {
+            if let Some(material) = material {
+                batch.flush(painter);
+                if material.paint(painter, camera.bounds(*rect)) {
+                    continue;
+                }
+            }
+            if self.batching {
+                batch.push_rect(painter, camera, *rect, sprite_rounding, *color);
+            } else {
+                painter.add(rounded_rect(camera, *rect, sprite_rounding, *color));
+            }
+        }
+
+        for node in &self.nodes {
+            let world = camera.then(&node.world);
+            if let (true, &NodeVisual::Image { texture, uv, tint, .. }) = (self.batching, &node.visual) {
+                batch.push_image(painter, world, node.size, texture, uv, tint);
+                continue;
+            }
+            batch.flush(painter);
+            match &node.visual {
+                &NodeVisual::Rect { fill, stroke } => {
+                    painter.add(scene::rect_shape(&world, node.size, fill, stroke));
+                }
+                &NodeVisual::Image { texture, uv, tint, .. } => {
+                    painter.add(scene::image_shape(&world, node.size, texture, uv, tint));
+                }
+                NodeVisual::Text(Label { glyphs: Some(glyphs), .. }) => {
+                    for glyph_mesh in glyphs.iter() {
+                        let mut mesh = glyph_mesh.clone();
+                        for vertex in &mut mesh.vertices {
+                            vertex.pos = world.apply(vertex.pos);
+                        }
+                        painter.add(Shape::mesh(mesh));
+                    }
+                }
+                NodeVisual::Text(label) => {
+                    // No fonts: egui lays it out upright at the origin, scaled with the node
+                    let font = FontId::proportional(label.size * world.area_scale().sqrt());
+                    painter.text(world.apply(Pos2::ZERO), label.align, &label.text, font, label.color);
+                }
+            }
+        }
+
+        batch.flush(painter);
+
+        if !self.particles.is_empty() {
+            let mut particles = self.particles.clone();
+            if *camera != Affine::IDENTITY {
+                for vertex in &mut particles.vertices {
+                    vertex.pos = camera.apply(vertex.pos);
+                }
+            }
+            painter.add(Shape::mesh(particles));
+        }
+
+        draw_list::paint_layer(&self.host[DRAW_LAYER_ABOVE as usize], painter);
+    }
+
+    /// JSON document with one entry per layer:
+    /// `{"screen":[w,h],"safe_area":[x,y,w,h],"camera":[..],"culled":{..},"simplified":false,"splash":false,"background":{..},"layers":[{"name":..,"items":[..]},..]}`
+    /// Rects are `[x,y,w,h]`, transforms `[a,b,c,d,tx,ty]` and colors `"#rrggbbaa"` (unmultiplied)
+    /// World layers (tiles, obstacles, sprites, scene, particles) are in world points, drawn through `camera`
+    pub fn to_json(&self) -> String {
+        let obstacles = self
+            .obstacles
+            .iter()
+            .map(|&(rect, color)| object([("kind", string("rect")), ("rect", rect_value(rect)), ("color", color_value(color))]))
+            .collect();
+        let sprites = self
+            .sprites
+            .iter()
+            .map(|(id, rect, color, material)| {
+                let mut sprite = object([
+                    ("kind", string("rect")),
+                    ("id", Value::Number(*id as f64)),
+                    ("rect", rect_value(*rect)),
+                    ("color", color_value(*color)),
+                ]);
+                if let (Value::Object(members), Some(draw)) = (&mut sprite, material) {
+                    let uniforms = draw.material.uniforms.iter().map(|(name, values)| (name.clone(), numbers(values)));
+                    let material = object([
+                        ("shader", string(&draw.material.shader)),
+                        ("uniforms", Value::Object(uniforms.collect())),
+                    ]);
+                    members.push(("material".to_string(), material));
+                }
+                sprite
+            })
+            .collect();
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|draw| {
+                object([
+                    ("kind", string("tiles")),
+                    ("map", Value::Number(draw.map as f64)),
+                    ("layer", Value::Number(draw.layer as f64)),
+                    ("rect", rect_value(draw.bounds)),
+                    ("count", Value::Number((draw.mesh.vertices.len() / 4) as f64)),
+                ])
+            })
+            .collect();
+        let nodes = self.nodes.iter().map(node_value).collect();
+        // Particles are summarized: there can be thousands and they change every tick
+        let particles = if self.particles.is_empty() {
+            Vec::new()
+        } else {
+            vec![object([
+                ("kind", string("particles")),
+                ("count", Value::Number((self.particles.vertices.len() / 4) as f64)),
+                ("bounds", rect_value(self.particles.calc_bounds())),
+            ])]
+        };
+        let [host_below, host_above] = self.host.each_ref().map(|layer| layer.iter().map(host_value).collect());
+        let hud = self
+            .ui
+            .iter()
+            .map(widget_value)
+            .chain(self.qr.iter().map(|&rect| object([("kind", string("qr")), ("rect", rect_value(rect))])))
+            .collect();
+
+        let gradient = match self.gradient {
+            Some((top, bottom)) => object([("top", color_value(top)), ("bottom", color_value(bottom))]),
+            None => Value::Null,
+        };
+        let background = object([("clear_color", numbers(&self.clear_color)), ("gradient", gradient)]);
+        let layers = [
+            ("host_below", host_below),
+            ("tiles", tiles),
+            ("obstacles", obstacles),
+            ("sprites", sprites),
+            ("scene", nodes),
+            ("particles", particles),
+            ("host_above", host_above),
+            ("hud", hud),
+        ]
+            .into_iter()
+            .map(|(name, items)| object([("name", string(name)), ("items", Value::Array(items))]))
+            .collect();
+
+        object([
+            ("screen", numbers(&[self.screen.width(), self.screen.height()])),
+            ("safe_area", rect_value(self.safe_area)),
+            ("camera", numbers(&self.camera.to_array())),
+            ("culled", object([
+                ("sprites", Value::Number(self.culled.sprites as f64)),
+                ("nodes", Value::Number(self.culled.nodes as f64)),
+                ("particles", Value::Number(self.culled.particles as f64)),
+            ])),
+            ("simplified", Value::Bool(self.simplified)),
+            ("splash", Value::Bool(self.splash)),
+            ("background", background),
+            ("layers", Value::Array(layers)),
+        ])
+        .to_string()
+    }
+}
+
+/// Filled rect with rounded corners, through the camera
+/// Cameras that don't turn keep it a rect shape (the common case, and cheaper to tessellate)
+pub fn rounded_rect(camera: &Affine, rect: Rect, rounding: f32, color: Color32) -> Shape {
+    if let Some(scale) = camera.axis_scale() {
+        return Shape::rect_filled(camera.bounds(rect), Rounding::same(rounding * scale.min_elem()), color);
+    }
+    let mut points: Vec<Pos2> = Vec::new();
+    path::rounded_rectangle(&mut points, rect, Rounding::same(rounding));
+    Shape::convex_polygon(points.into_iter().map(|p| camera.apply(p)).collect(), color, Stroke::NONE)
+}
+
+fn node_value(node: &NodeDraw) -> Value {
+    let mut members = vec![
+        ("node".to_string(), Value::Number(node.node as f64)),
+        ("z".to_string(), Value::Number(node.z as f64)),
+        ("transform".to_string(), numbers(&node.world.to_array())),
+        ("size".to_string(), numbers(&[node.size.x, node.size.y])),
+    ];
+    match &node.visual {
+        &NodeVisual::Rect { fill, stroke } => {
+            members.insert(0, ("kind".to_string(), string("rect")));
+            members.push(("color".to_string(), color_value(fill)));
+            if let Some(stroke) = stroke_value(stroke) {
+                members.push(("stroke".to_string(), stroke));
+            }
+        }
+        &NodeVisual::Image { source, uv, tint, .. } => {
+            members.insert(0, ("kind".to_string(), string("image")));
+            let texture = match source {
+                TextureSource::Player => object([("asset", string("player"))]),
+                TextureSource::Asset(name) => object([("asset", string(&strings::name(name)))]),
+                TextureSource::Atlas { atlas, frame } => {
+                    object([("atlas", Value::Number(atlas as f64)), ("frame", Value::Number(frame as f64))])
+                }
+            };
+            members.push(("texture".to_string(), texture));
+            members.push(("uv".to_string(), rect_value(uv)));
+            members.push(("tint".to_string(), color_value(tint)));
+        }
+        NodeVisual::Text(label) => {
+            members.insert(0, ("kind".to_string(), string("text")));
+            members.push(("text".to_string(), string(&label.text)));
+            members.push(("font_size".to_string(), number(label.size)));
+            members.push(("color".to_string(), color_value(label.color)));
+            // Fractions of the text's bounds on the node origin: [0, 0] is top left, [1, 1] bottom right
+            members.push(("align".to_string(), numbers(&label.align.0.map(Align::to_factor))));
+            if let Some(glyphs) = &label.glyphs {
+                let quads = glyphs.iter().map(|mesh| mesh.vertices.len() / 4).sum::<usize>();
+                members.push(("glyphs".to_string(), number(quads as f32)));
+            }
+        }
+    }
+    Value::Object(members)
+}
+
+fn widget_value(draw: &WidgetDraw) -> Value {
+    let mut members = vec![
+        ("widget".to_string(), Value::Number(draw.widget as f64)),
+        ("rect".to_string(), rect_value(draw.rect)),
+    ];
+    let (kind, label) = match &draw.kind {
+        WidgetKind::Label(label) => ("label", Some(label)),
+        WidgetKind::Button { label, fill } => {
+            members.push(("fill".to_string(), color_value(*fill)));
+            ("button", Some(label))
+        }
+        &WidgetKind::Progress { value, color } => {
+            members.push(("value".to_string(), number(value)));
+            members.push(("color".to_string(), color_value(color)));
+            ("progress", None)
+        }
+    };
+    members.insert(0, ("kind".to_string(), string(kind)));
+    if let Some(label) = label {
+        members.push(("text".to_string(), string(&label.text)));
+        members.push(("font_size".to_string(), number(label.size)));
+        members.push(("text_color".to_string(), color_value(label.color)));
+    }
+    Value::Object(members)
+}
+
+fn host_value(command: &DrawCommand) -> Value {
+    match command {
+        DrawCommand::Rect { world, size, fill, stroke } => {
+            let mut members = vec![
+                ("kind".to_string(), string("rect")),
+                ("transform".to_string(), numbers(&world.to_array())),
+                ("size".to_string(), numbers(&[size.x, size.y])),
+                ("color".to_string(), color_value(*fill)),
+            ];
+            if let Some(stroke) = stroke_value(*stroke) {
+                members.push(("stroke".to_string(), stroke));
+            }
+            Value::Object(members)
+        }
+        DrawCommand::Image { name, world, size, uv, tint, .. } => object([
+            ("kind", string("image")),
+            ("transform", numbers(&world.to_array())),
+            ("size", numbers(&[size.x, size.y])),
+            ("texture", object([("asset", string(name))])),
+            ("uv", rect_value(*uv)),
+            ("tint", color_value(*tint)),
+        ]),
+        DrawCommand::Path { points, closed, fill, stroke } => {
+            let points: Vec<f32> = points.iter().flat_map(|p| [p.x, p.y]).collect();
+            let mut members = vec![
+                ("kind".to_string(), string("path")),
+                ("points".to_string(), numbers(&points)),
+                ("closed".to_string(), Value::Bool(*closed)),
+                ("color".to_string(), color_value(*fill)),
+            ];
+            if let Some(stroke) = stroke_value(*stroke) {
+                members.push(("stroke".to_string(), stroke));
+            }
+            Value::Object(members)
+        }
+        DrawCommand::Text { pos, size, color, text, glyphs } => {
+            let mut members = vec![
+                ("kind".to_string(), string("text")),
+                ("pos".to_string(), numbers(&[pos.x, pos.y])),
+                ("size".to_string(), number(*size)),
+                ("color".to_string(), color_value(*color)),
+                ("text".to_string(), string(text)),
+            ];
+            // Shaped glyphs drawn (not characters: ligatures merge them, Indic scripts may add some)
+            if let Some(glyphs) = glyphs {
+                let quads = glyphs.iter().map(|mesh| mesh.vertices.len() / 4).sum::<usize>();
+                members.push(("glyphs".to_string(), number(quads as f32)));
+            }
+            Value::Object(members)
+        }
+        DrawCommand::Vector { parts } => object([
+            ("kind", string("vector")),
+            (
+                "parts",
+                Value::Array(
+                    parts
+                        .iter()
+                        .map(|part| {
+                            object([
+                                ("bounds", vector::bounds(&part.mesh).map_or(Value::Null, rect_value)),
+                                ("vertices", Value::Number(part.mesh.vertices.len() as f64)),
+                                ("triangles", Value::Number((part.mesh.indices.len() / 3) as f64)),
+                                ("textured", Value::Bool(part.texture.is_some())),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+        ]),
+        DrawCommand::Clip(shape) => object([
+            ("kind", string("clip")),
+            ("shape", string(if matches!(shape, ClipShape::Rect(_)) { "rect" } else { "mask" })),
+            ("bounds", rect_value(shape.bounds())),
+        ]),
+        DrawCommand::PopClip => object([("kind", string("pop_clip"))]),
+        DrawCommand::Picture { id, bounds, commands, cached, .. } => object([
+            ("kind", string("picture")),
+            ("id", Value::Number(*id as f64)),
+            ("bounds", rect_value(*bounds)),
+            ("cached", Value::Bool(cached.is_some())),
+            ("items", Value::Array(commands.iter().map(host_value).collect())),
+        ]),
+    }
+}
+
+fn stroke_value(stroke: Stroke) -> Option<Value> {
+    (stroke.width > 0.0).then(|| object([("width", number(stroke.width)), ("color", color_value(stroke.color))]))
+}
+
+fn rect_value(rect: Rect) -> Value {
+    numbers(&[rect.min.x, rect.min.y, rect.width(), rect.height()])
+}
+
+fn color_value(color: Color32) -> Value {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    Value::String(format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a))
+}
@@ -0,0 +1,656 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use egui::epaint::Mesh;
+use egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Shape, Stroke, TextureId, Vec2};
+
+use crate::assets::AssetCache;
+use crate::clip::{self, ClipShape, ClipStack};
+use crate::picture::{self, CachedPicture};
+use crate::ffi::strings::{self, NameId};
+use crate::save::Reader;
+use crate::scene::{self, Affine};
+use crate::sprites;
+use crate::vector::{self, FillRule, LineCap, LineJoin, Paint, Path, StrokeStyle, VectorPath};
+
+/// Format version, first byte of every draw list
+pub const DRAW_LIST_VERSION: u8 = 1;
+
+/// Command tags; values are part of the C API, never renumber
+/// Coordinates are points in scene space, colors packed 0xRRGGBBAA, strings a u32 byte length then UTF-8
+/// Rect: x f32, y f32, w f32, h f32, fill u32, stroke width f32, stroke u32
+pub const DRAW_RECT: u8 = 1;
+/// Image: texture name (as loaded with game_load_texture_*), x, y, w, h f32, uv u0, v0, u1, v1 f32, tint u32
+pub const DRAW_IMAGE: u8 = 2;
+/// Path: closed u8, point count u32, x y f32 per point, fill u32, stroke width f32, stroke u32
+/// Fill applies to closed convex paths only
+pub const DRAW_PATH: u8 = 3;
+/// Text: x f32, y f32 (top left), size f32, color u32, text string
+/// Shaped when the list is submitted with the fonts registered by then (see text module), at the scale
+/// factor in effect; without fonts it's laid out by egui (no ligatures, joining or bidi)
+pub const DRAW_TEXT: u8 = 4;
+/// Transform for the commands after it: a, b, c, d, tx, ty f32 (column-major, as in game_dump_display_list)
+/// Text only has its position transformed
+pub const DRAW_TRANSFORM: u8 = 5;
+/// Layer for the commands after it: u8 DRAW_LAYER_BELOW or DRAW_LAYER_ABOVE (the default)
+pub const DRAW_LAYER: u8 = 6;
+/// Vector path, filled then stroked (see vector module): fill u32, fill rule u8 (DRAW_FILL_*),
+/// stroke width f32, stroke u32, cap u8 (DRAW_CAP_*), join u8 (DRAW_JOIN_*), dash count u8 (at most 16),
+/// that many dash/gap lengths f32, dash offset f32, verb count u32, then per verb a DRAW_VERB_* byte and
+/// its points as x y f32 pairs (move 1, line 1, quad 2, cubic 3, close 0)
+pub const DRAW_VECTOR: u8 = 7;
+/// Paint replacing the fill or stroke color of the DRAW_VECTOR commands after it (a transparent fill or
+/// zero-width stroke still draws nothing): target u8 (DRAW_PAINT_FILL/STROKE), kind u8 (DRAW_PAINT_*), then
+/// solid: nothing (back to the command's own color);
+/// linear: x0 y0 x1 y1 f32, stop count u8 (1 to 16), then per stop offset f32 (ascending) and color u32;
+/// radial: cx cy radius f32, stops as for linear;
+/// texture: texture name, x y w h f32 (where the whole image lands), tint u32.
+/// Coordinates are the path's own, before the transform
+pub const DRAW_PAINT: u8 = 8;
+/// Clip the commands after it, in the same layer, until the matching DRAW_CLIP_POP (or the end of the
+/// list): kind u8 (DRAW_CLIP_*), then rect: x y w h f32; rounded rect: x y w h radius f32;
+/// path: fill rule u8 (DRAW_FILL_*), verb count u32 and verbs as in DRAW_VECTOR.
+/// Clips nest (at most 16 deep per layer) and are moved by the transform in effect
+pub const DRAW_CLIP: u8 = 9;
+/// End the innermost DRAW_CLIP of the current layer
+pub const DRAW_CLIP_POP: u8 = 10;
+/// Picture: the commands up to DRAW_PICTURE_END are rasterized once into a texture covering
+/// x y w h f32 (moved by the transform, then their bounding box) and that texture is drawn until the
+/// content changes or game_invalidate_picture(id) is called: id u32, x y w h f32.
+/// Pictures don't nest or change layer, and clips opened inside must be popped inside
+pub const DRAW_PICTURE: u8 = 11;
+pub const DRAW_PICTURE_END: u8 = 12;
+
+pub const DRAW_FILL_NON_ZERO: u8 = 0;
+pub const DRAW_FILL_EVEN_ODD: u8 = 1;
+pub const DRAW_CAP_BUTT: u8 = 0;
+pub const DRAW_CAP_ROUND: u8 = 1;
+pub const DRAW_CAP_SQUARE: u8 = 2;
+pub const DRAW_JOIN_MITER: u8 = 0;
+pub const DRAW_JOIN_ROUND: u8 = 1;
+pub const DRAW_JOIN_BEVEL: u8 = 2;
+pub const DRAW_VERB_MOVE: u8 = 0;
+pub const DRAW_VERB_LINE: u8 = 1;
+pub const DRAW_VERB_QUAD: u8 = 2;
+pub const DRAW_VERB_CUBIC: u8 = 3;
+pub const DRAW_VERB_CLOSE: u8 = 4;
+pub const DRAW_PAINT_FILL: u8 = 0;
+pub const DRAW_PAINT_STROKE: u8 = 1;
+pub const DRAW_PAINT_SOLID: u8 = 0;
+pub const DRAW_PAINT_LINEAR: u8 = 1;
+pub const DRAW_PAINT_RADIAL: u8 = 2;
+pub const DRAW_PAINT_TEXTURE: u8 = 3;
+pub const DRAW_CLIP_RECT: u8 = 0;
+pub const DRAW_CLIP_ROUNDED_RECT: u8 = 1;
+pub const DRAW_CLIP_PATH: u8 = 2;
+
+/// Beneath the level obstacles, above the background
+pub const DRAW_LAYER_BELOW: u8 = 0;
+/// Above the scene nodes, beneath the HUD
+pub const DRAW_LAYER_ABOVE: u8 = 1;
+
+/// Submissions a cached vector mesh survives without being used
+const CACHE_SUBMISSIONS: u64 = 4;
+
+/// Most vector commands kept tessellated
+const MAX_CACHED: usize = 1024;
+
+/// Lays out text (text, top left, size, color) as a mesh per glyph atlas page, None to leave it to egui
+pub type ShapeText<'a> = dyn FnMut(&str, Pos2, f32, Color32) -> Option<Vec<Mesh>> + 'a;
+
+/// One host draw command, with the transform in effect already applied
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    /// Centered on the local origin of `world`
+    Rect { world: Affine, size: Vec2, fill: Color32, stroke: Stroke },
+    /// `texture` is resolved from `name` each frame; unloaded textures are skipped
+    Image { name: String, id: NameId, texture: Option<TextureId>, world: Affine, size: Vec2, uv: Rect, tint: Color32 },
+    Path { points: Vec<Pos2>, closed: bool, fill: Color32, stroke: Stroke },
+    /// `glyphs` is the shaped text, a mesh per glyph atlas page (None without fonts, drawn with egui's layout)
+    Text { pos: Pos2, size: f32, color: Color32, text: String, glyphs: Option<Vec<Mesh>> },
+    /// Tessellated when the list is submitted (or taken from the VectorCache)
+    Vector { parts: Vec<VectorPart> },
+    /// Clip the commands up to the matching PopClip
+    Clip(ClipShape),
+    PopClip,
+    /// `cached` is the raster of `commands`, looked up by id and `key` (a hash of the content) each frame
+    Picture { id: u32, key: u64, bounds: Rect, commands: Vec<DrawCommand>, cached: Option<CachedPicture> },
+}
+
+/// The fill or stroke of a DRAW_VECTOR command
+#[derive(Clone, Debug, PartialEq)]
+pub struct VectorPart {
+    pub mesh: Mesh,
+    /// Texture paint, looked up by name each frame like images; unloaded textures are skipped
+    pub texture: Option<NameId>,
+}
+
+impl DrawCommand {
+    pub fn paint(&self, painter: &Painter) {
+        match self {
+            DrawCommand::Rect { world, size, fill, stroke } => {
+                painter.add(scene::rect_shape(world, *size, *fill, *stroke));
+            }
+            DrawCommand::Image { texture: Some(texture), world, size, uv, tint, .. } => {
+                painter.add(scene::image_shape(world, *size, *texture, *uv, *tint));
+            }
+            DrawCommand::Image { texture: None, .. } => {}
+            DrawCommand::Path { points, closed: true, fill, stroke } => {
+                painter.add(Shape::convex_polygon(points.clone(), *fill, *stroke));
+            }
+            DrawCommand::Path { points, closed: false, stroke, .. } => {
+                painter.add(Shape::line(points.clone(), *stroke));
+            }
+            DrawCommand::Text { glyphs: Some(glyphs), .. } => {
+                for mesh in glyphs {
+                    painter.add(Shape::mesh(mesh.clone()));
+                }
+            }
+            DrawCommand::Text { pos, size, color, text, glyphs: None } => {
+                painter.text(*pos, Align2::LEFT_TOP, text, FontId::proportional(*size), *color);
+            }
+            DrawCommand::Vector { parts } => {
+                for part in parts {
+                    painter.add(Shape::mesh(part.mesh.clone()));
+                }
+            }
+            // Applied by paint_layer
+            DrawCommand::Clip(_) | DrawCommand::PopClip | DrawCommand::Picture { .. } => {}
+        }
+    }
+}
+
+/// Paint a layer's commands with their clips
+pub fn paint_layer(commands: &[DrawCommand], painter: &Painter) {
+    let mut clips = ClipStack::new(painter);
+    for command in commands {
+        match command {
+            DrawCommand::Clip(shape) => clips.push(shape),
+            DrawCommand::PopClip => clips.pop(),
+            DrawCommand::Picture { cached: Some(cached), bounds, .. } => {
+                clips.painter().add(cached.shape(*bounds));
+            }
+            // Not cached yet: marked for the renderer to rasterize
+            DrawCommand::Picture { id, key, bounds, commands, cached: None } => {
+                picture::begin(clips.painter(), *id, *key, *bounds);
+                paint_layer(commands, &clips.painter().with_clip_rect(*bounds));
+                picture::end(clips.painter());
+            }
+            command => command.paint(clips.painter()),
+        }
+    }
+    clips.finish();
+}
+
+/// Host-provided content drawn every frame until replaced (game_submit_draw_list)
+#[derive(Clone, Debug, Default)]
+pub struct HostDrawList {
+    /// Indexed by DRAW_LAYER_*
+    pub layers: [Vec<DrawCommand>; 2],
+}
+
+impl HostDrawList {
+    /// Decode a submitted list; vector commands seen in recent submissions come from `cache`, and
+    /// text is laid out by `shape_text`
+    pub fn decode(
+        bytes: &[u8],
+        cache: &mut VectorCache,
+        shape_text: &mut ShapeText<'_>,
+    ) -> Result<HostDrawList, String> {
+        cache.begin_submission();
+        let mut reader = Reader::new(bytes);
+        let version = reader.u8()?;
+        if version != DRAW_LIST_VERSION {
+            return Err(format!("Unsupported draw list version {}", version));
+        }
+
+        let mut list = HostDrawList::default();
+        let mut transform = Affine::IDENTITY;
+        let mut layer = DRAW_LAYER_ABOVE;
+        // Current fill and stroke paints, indexed by DRAW_PAINT_FILL/STROKE
+        let mut paints: [Option<ActivePaint>; 2] = [None, None];
+        // Open clips per layer
+        let mut clip_depth = [0; 2];
+        let mut open_picture: Option<OpenPicture> = None;
+        while !reader.is_at_end() {
+            let start = reader.position();
+            let tag = reader.u8()?;
+            let command = match tag {
+                DRAW_RECT => {
+                    let rect = read_rect(&mut reader)?;
+                    let (fill, stroke) = (color(&mut reader)?, read_stroke(&mut reader)?);
+                    DrawCommand::Rect { world: centered(&transform, rect), size: rect.size(), fill, stroke }
+                }
+                DRAW_IMAGE => {
+                    let name = read_string(&mut reader)?;
+                    let rect = read_rect(&mut reader)?;
+                    let uv = Rect::from_min_max(
+                        Pos2::new(reader.f32()?, reader.f32()?),
+                        Pos2::new(reader.f32()?, reader.f32()?),
+                    );
+                    let tint = color(&mut reader)?;
+                    let id = strings::intern(&name);
+                    let world = centered(&transform, rect);
+                    DrawCommand::Image { name, id, texture: None, world, size: rect.size(), uv, tint }
+                }
+                DRAW_PATH => {
+                    let closed = reader.u8()? != 0;
+                    let count = reader.count()?;
+                    let mut points = Vec::with_capacity(count.min(1024) as usize);
+                    for _ in 0..count {
+                        points.push(transform.apply(Pos2::new(reader.f32()?, reader.f32()?)));
+                    }
+                    let (fill, stroke) = (color(&mut reader)?, read_stroke(&mut reader)?);
+                    DrawCommand::Path { points, closed, fill, stroke }
+                }
+                DRAW_TEXT => {
+                    let pos = transform.apply(Pos2::new(reader.f32()?, reader.f32()?));
+                    let (size, color) = (reader.f32()?, color(&mut reader)?);
+                    if !(size > 0.0 && size.is_finite()) {
+                        return Err(format!("Invalid text size {}", size));
+                    }
+                    let text = read_string(&mut reader)?;
+                    let glyphs = shape_text(&text, pos, size, color);
+                    DrawCommand::Text { pos, size, color, text, glyphs }
+                }
+                DRAW_VECTOR => {
+                    let body = read_vector(&mut reader, &transform)?;
+                    let mut key = DefaultHasher::new();
+                    bytes[start..reader.position()].hash(&mut key);
+                    transform.to_array().map(f32::to_bits).hash(&mut key);
+                    paints.iter().for_each(|paint| paint.as_ref().map(|p| p.bytes).hash(&mut key));
+                    let parts = cache.get_or_insert(key.finish(), || body.tessellate(&transform, &paints))?;
+                    DrawCommand::Vector { parts }
+                }
+                DRAW_PAINT => {
+                    let target = reader.u8()?;
+                    if target > DRAW_PAINT_STROKE {
+                        return Err(format!("Unknown paint target {}", target));
+                    }
+                    let paint = read_paint(&mut reader)?;
+                    let command = &bytes[start..reader.position()];
+                    paints[target as usize] = paint.map(|(paint, texture)| ActivePaint { paint, texture, bytes: command });
+                    continue;
+                }
+                DRAW_CLIP => {
+                    if clip_depth[layer as usize] == clip::MAX_CLIP_DEPTH {
+                        return Err(format!("Clips nested deeper than {}", clip::MAX_CLIP_DEPTH));
+                    }
+                    clip_depth[layer as usize] += 1;
+                    DrawCommand::Clip(read_clip(&mut reader, &transform)?)
+                }
+                DRAW_CLIP_POP => {
+                    if clip_depth[layer as usize] == 0 {
+                        return Err("Clip pop without a clip".into());
+                    }
+                    if open_picture.as_ref().is_some_and(|p| p.clip_depth == clip_depth[layer as usize]) {
+                        return Err("Clip pop of a clip opened outside the picture".into());
+                    }
+                    clip_depth[layer as usize] -= 1;
+                    DrawCommand::PopClip
+                }
+                DRAW_PICTURE => {
+                    if open_picture.is_some() {
+                        return Err("Nested picture".into());
+                    }
+                    let id = reader.u32()?;
+                    let rect = read_rect(&mut reader)?;
+                    let corners = [rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom()];
+                    let bounds = Rect::from_points(&corners.map(|p| transform.apply(p)));
+                    // Content depends on the state it starts with as well as its own bytes
+                    let mut key = DefaultHasher::new();
+                    transform.to_array().map(f32::to_bits).hash(&mut key);
+                    paints.iter().for_each(|paint| paint.as_ref().map(|p| p.bytes).hash(&mut key));
+                    [bounds.min, bounds.max].map(|p| [p.x.to_bits(), p.y.to_bits()]).hash(&mut key);
+                    let clip_depth = clip_depth[layer as usize];
+                    let start = reader.position();
+                    open_picture = Some(OpenPicture { id, bounds, key, start, clip_depth, commands: Vec::new() });
+                    continue;
+                }
+                DRAW_PICTURE_END => {
+                    let Some(open) = open_picture.take() else {
+                        return Err("Picture end without a picture".into());
+                    };
+                    if open.clip_depth != clip_depth[layer as usize] {
+                        return Err("Picture ends with clips still open".into());
+                    }
+                    let mut key = open.key;
+                    bytes[open.start..start].hash(&mut key);
+                    let (id, bounds, commands) = (open.id, open.bounds, open.commands);
+                    DrawCommand::Picture { id, key: key.finish(), bounds, commands, cached: None }
+                }
+                DRAW_TRANSFORM => {
+                    let mut values = [0.0; 6];
+                    for value in &mut values {
+                        *value = reader.f32()?;
+                    }
+                    transform = Affine::from_array(values);
+                    continue;
+                }
+                DRAW_LAYER => {
+                    if open_picture.is_some() {
+                        return Err("Layer change inside a picture".into());
+                    }
+                    layer = reader.u8()?;
+                    if layer > DRAW_LAYER_ABOVE {
+                        return Err(format!("Unknown draw layer {}", layer));
+                    }
+                    continue;
+                }
+                _ => return Err(format!("Unknown draw command {}", tag)),
+            };
+            match &mut open_picture {
+                Some(open) => open.commands.push(command),
+                None => list.layers[layer as usize].push(command),
+            }
+        }
+        if open_picture.is_some() {
+            return Err("Picture without an end".into());
+        }
+        Ok(list)
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(Vec::len).sum()
+    }
+
+    /// Lay out every text command again (e.g. after the glyph atlas evicted some of its glyphs)
+    pub fn reshape_text(&mut self, shape_text: &mut ShapeText<'_>) {
+        self.layers.iter_mut().for_each(|layer| reshape_commands(layer, shape_text));
+    }
+
+    /// Ids of the pictures in the list
+    pub fn picture_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.layers.iter().flatten().filter_map(|command| match command {
+            DrawCommand::Picture { id, .. } => Some(*id),
+            _ => None,
+        })
+    }
+
+    /// Copy for this frame's display list, with image and paint textures looked up by name and
+    /// cached pictures by id, key and bounds
+    pub fn resolve(&self, assets: &AssetCache, pictures: &impl Fn(u32, u64, Rect) -> Option<CachedPicture>) -> [Vec<DrawCommand>; 2] {
+        self.layers.clone().map(|mut layer| {
+            resolve_commands(&mut layer, assets, pictures);
+            layer
+        })
+    }
+}
+
+fn reshape_commands(commands: &mut [DrawCommand], shape_text: &mut ShapeText<'_>) {
+    for command in commands {
+        match command {
+            DrawCommand::Text { pos, size, color, text, glyphs } => *glyphs = shape_text(text, *pos, *size, *color),
+            DrawCommand::Picture { commands, .. } => reshape_commands(commands, shape_text),
+            _ => {}
+        }
+    }
+}
+
+fn resolve_commands(commands: &mut [DrawCommand], assets: &AssetCache, pictures: &impl Fn(u32, u64, Rect) -> Option<CachedPicture>) {
+    for command in commands {
+        match command {
+            DrawCommand::Image { id, texture, .. } => *texture = assets.get(*id).map(|t| t.id),
+            DrawCommand::Vector { parts } => parts.retain_mut(|part| match part.texture {
+                Some(id) => assets.get(id).map(|t| part.mesh.texture_id = t.id).is_some(),
+                None => true,
+            }),
+            DrawCommand::Picture { id, key, bounds, commands, cached } => {
+                *cached = pictures(*id, *key, *bounds);
+                // Content is only drawn while the picture isn't cached
+                if cached.is_none() {
+                    resolve_commands(commands, assets, pictures);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Tessellated DRAW_VECTOR commands by a hash of their bytes, transform and paints, so lists
+/// resubmitted every frame only tessellate the shapes that changed
+#[derive(Default)]
+pub struct VectorCache {
+    /// Parts and the submission that last used them
+    entries: HashMap<u64, (Vec<VectorPart>, u64)>,
+    submission: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl VectorCache {
+    /// Start decoding a new list; entries unused for a few submissions are dropped
+    fn begin_submission(&mut self) {
+        self.submission += 1;
+        let oldest = self.submission.saturating_sub(CACHE_SUBMISSIONS);
+        self.entries.retain(|_, (_, used)| *used >= oldest);
+    }
+
+    fn get_or_insert(
+        &mut self,
+        key: u64,
+        tessellate: impl FnOnce() -> Result<Vec<VectorPart>, String>,
+    ) -> Result<Vec<VectorPart>, String> {
+        if let Some((parts, used)) = self.entries.get_mut(&key) {
+            *used = self.submission;
+            self.hits += 1;
+            return Ok(parts.clone());
+        }
+        self.misses += 1;
+        let parts = tessellate()?;
+        if self.entries.len() < MAX_CACHED {
+            self.entries.insert(key, (parts.clone(), self.submission));
+        }
+        Ok(parts)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Drop every entry; paths are tessellated again when next submitted. Returns how many were dropped
+    pub fn clear(&mut self) -> usize {
+        let dropped = self.entries.len();
+        self.entries = HashMap::new();
+        dropped
+    }
+}
+
+/// `rect` as a world transform on its center, so it can be drawn like a scene node
+fn centered(transform: &Affine, rect: Rect) -> Affine {
+    let center = rect.center();
+    transform.then(&Affine::from_array([1.0, 0.0, 0.0, 1.0, center.x, center.y]))
+}
+
+fn read_rect(reader: &mut Reader) -> Result<Rect, String> {
+    let min = Pos2::new(reader.f32()?, reader.f32()?);
+    let size = Vec2::new(reader.f32()?, reader.f32()?);
+    Ok(Rect::from_min_size(min, size))
+}
+
+fn read_stroke(reader: &mut Reader) -> Result<Stroke, String> {
+    let width = reader.f32()?;
+    Ok(Stroke::new(width, color(reader)?))
+}
+
+fn color(reader: &mut Reader) -> Result<Color32, String> {
+    Ok(sprites::color_from_rgba(reader.u32()?))
+}
+
+fn read_string(reader: &mut Reader) -> Result<String, String> {
+    let len = reader.count()? as usize;
+    let text = std::str::from_utf8(reader.take(len)?).map_err(|_| "String is not UTF-8")?;
+    Ok(text.to_string())
+}
+
+/// DRAW_PAINT body after the target; None for DRAW_PAINT_SOLID
+fn read_paint(reader: &mut Reader) -> Result<Option<(Paint, Option<NameId>)>, String> {
+    let point = |reader: &mut Reader| -> Result<Pos2, String> { Ok(Pos2::new(reader.f32()?, reader.f32()?)) };
+    let stops = |reader: &mut Reader| -> Result<Vec<(f32, Color32)>, String> {
+        let count = reader.u8()? as usize;
+        if count == 0 || count > vector::MAX_STOPS {
+            return Err(format!("Gradient of {} stops", count));
+        }
+        let mut stops: Vec<(f32, Color32)> = Vec::with_capacity(count);
+        for _ in 0..count {
+            let stop = (reader.f32()?, color(reader)?);
+            if !stop.0.is_finite() || stops.last().is_some_and(|last| last.0 > stop.0) {
+                return Err("Gradient stops must ascend".to_string());
+            }
+            stops.push(stop);
+        }
+        Ok(stops)
+    };
+    let paint = match reader.u8()? {
+        DRAW_PAINT_SOLID => return Ok(None),
+        DRAW_PAINT_LINEAR => {
+            let (start, end) = (point(reader)?, point(reader)?);
+            Paint::Linear { start, end, stops: stops(reader)? }
+        }
+        DRAW_PAINT_RADIAL => {
+            let (center, radius) = (point(reader)?, reader.f32()?);
+            Paint::Radial { center, radius, stops: stops(reader)? }
+        }
+        DRAW_PAINT_TEXTURE => {
+            let id = strings::intern(&read_string(reader)?);
+            let rect = read_rect(reader)?;
+            if !(rect.width() > 0.0 && rect.height() > 0.0) {
+                return Err(format!("Empty texture paint rect {:?}", rect));
+            }
+            return Ok(Some((Paint::Texture { rect, tint: color(reader)? }, Some(id))));
+        }
+        kind => return Err(format!("Unknown paint kind {}", kind)),
+    };
+    Ok(Some((paint, None)))
+}
+
+/// A DRAW_PICTURE whose end hasn't been read yet
+struct OpenPicture {
+    id: u32,
+    bounds: Rect,
+    /// Hash of the state the picture starts with; its bytes are added at the end
+    key: DefaultHasher,
+    /// Offset of its first command
+    start: usize,
+    /// Open clips of the layer when it began
+    clip_depth: usize,
+    commands: Vec<DrawCommand>,
+}
+
+/// A DRAW_PAINT in effect
+struct ActivePaint<'a> {
+    paint: Paint,
+    texture: Option<NameId>,
+    /// The command that set it, hashed into cache keys
+    bytes: &'a [u8],
+}
+
+/// A decoded DRAW_VECTOR command, not yet tessellated
+struct VectorBody {
+    path: Path,
+    rule: FillRule,
+    fill: Color32,
+    stroke: StrokeStyle,
+}
+
+impl VectorBody {
+    /// Fill then stroke (stroke width is not transformed), each with its paint
+    fn tessellate(&self, transform: &Affine, paints: &[Option<ActivePaint>; 2]) -> Result<Vec<VectorPart>, String> {
+        let path = &self.path;
+        let mut parts = Vec::new();
+        if self.fill.a() > 0 {
+            parts.push(vector::fill(path, self.rule, self.fill)?);
+        }
+        let fill_count = parts.len();
+        if self.stroke.width > 0.0 && self.stroke.color.a() > 0 {
+            parts.push(vector::stroke(path, &self.stroke)?);
+        }
+        Ok(parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut mesh)| {
+                let target = if i < fill_count { DRAW_PAINT_FILL } else { DRAW_PAINT_STROKE };
+                let texture = paints[target as usize].as_ref().and_then(|active| {
+                    vector::apply_paint(&mut mesh, &active.paint, transform);
+                    active.texture
+                });
+                VectorPart { mesh, texture }
+            })
+            .collect())
+    }
+}
+
+/// Body of a DRAW_VECTOR command, its points moved by `transform`
+fn read_vector(reader: &mut Reader, transform: &Affine) -> Result<VectorBody, String> {
+    let fill = color(reader)?;
+    let rule = read_fill_rule(reader)?;
+    let mut stroke = StrokeStyle::solid(reader.f32()?, color(reader)?);
+    stroke.cap = match reader.u8()? {
+        DRAW_CAP_BUTT => LineCap::Butt,
+        DRAW_CAP_ROUND => LineCap::Round,
+        DRAW_CAP_SQUARE => LineCap::Square,
+        cap => return Err(format!("Unknown line cap {}", cap)),
+    };
+    stroke.join = match reader.u8()? {
+        DRAW_JOIN_MITER => LineJoin::Miter,
+        DRAW_JOIN_ROUND => LineJoin::Round,
+        DRAW_JOIN_BEVEL => LineJoin::Bevel,
+        join => return Err(format!("Unknown line join {}", join)),
+    };
+    let dash_count = reader.u8()? as usize;
+    if dash_count > vector::MAX_DASHES {
+        return Err(format!("Dash pattern of {} entries", dash_count));
+    }
+    for _ in 0..dash_count {
+        stroke.dashes.push(reader.f32()?);
+    }
+    stroke.dash_offset = reader.f32()?;
+    Ok(VectorBody { path: read_verbs(reader, transform)?, rule, fill, stroke })
+}
+
+/// Body of a DRAW_CLIP command, its shape moved by `transform`
+fn read_clip(reader: &mut Reader, transform: &Affine) -> Result<ClipShape, String> {
+    match reader.u8()? {
+        DRAW_CLIP_RECT => ClipShape::rounded_rect(transform, read_rect(reader)?, 0.0),
+        DRAW_CLIP_ROUNDED_RECT => {
+            let rect = read_rect(reader)?;
+            ClipShape::rounded_rect(transform, rect, reader.f32()?)
+        }
+        DRAW_CLIP_PATH => {
+            let rule = read_fill_rule(reader)?;
+            ClipShape::path(&read_verbs(reader, transform)?, rule)
+        }
+        kind => Err(format!("Unknown clip kind {}", kind)),
+    }
+}
+
+fn read_fill_rule(reader: &mut Reader) -> Result<FillRule, String> {
+    match reader.u8()? {
+        DRAW_FILL_NON_ZERO => Ok(FillRule::NonZero),
+        DRAW_FILL_EVEN_ODD => Ok(FillRule::EvenOdd),
+        rule => Err(format!("Unknown fill rule {}", rule)),
+    }
+}
+
+/// Verb count then verbs, as in DRAW_VECTOR
+fn read_verbs(reader: &mut Reader, transform: &Affine) -> Result<Path, String> {
+    let mut path = VectorPath::default();
+    let point = |reader: &mut Reader| -> Result<Pos2, String> { Ok(transform.apply(Pos2::new(reader.f32()?, reader.f32()?))) };
+    for _ in 0..reader.count()? {
+        match reader.u8()? {
+            DRAW_VERB_MOVE => path.move_to(point(reader)?),
+            DRAW_VERB_LINE => path.line_to(point(reader)?),
+            DRAW_VERB_QUAD => {
+                let ctrl = point(reader)?;
+                path.quad_to(ctrl, point(reader)?);
+            }
+            DRAW_VERB_CUBIC => {
+                let (ctrl1, ctrl2) = (point(reader)?, point(reader)?);
+                path.cubic_to(ctrl1, ctrl2, point(reader)?);
+            }
+            DRAW_VERB_CLOSE => path.close(),
+            verb => return Err(format!("Unknown path verb {}", verb)),
+        }
+    }
+    Ok(path.build())
+}
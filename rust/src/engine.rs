@@ -0,0 +1,7302 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::c_void;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use egui::{Align2, Color32, Margin, Pos2, Rect, Stroke, Vec2};
+use glow::HasContext;
+
+use crate::a11y::Announcer;
+use crate::animated;
+use crate::arena::Arena;
+use crate::assets::{self, AssetCache, LoadId};
+use crate::atlas::{AnimatedSprite, Atlas, AtlasId, LoopMode, INVALID_ATLAS};
+use crate::audio::{Audio, Channel, SoundId};
+use crate::background::Background;
+use crate::camera::Camera;
+use crate::capabilities::{self, Features};
+use crate::capture::{self, Capture};
+use crate::challenge;
+use crate::collision::{self, ColliderId, Colliders, Contact, INVALID_COLLIDER};
+use crate::crash::Crash;
+use crate::debug_overlay::{self, DebugInfo, DebugOverlay};
+use crate::display_list::{DisplayList, NodeDraw, NodeVisual, TextureSource};
+use crate::draw_list::{HostDrawList, VectorCache};
+use crate::events::{EventQueue, GameEvent, SubscriptionId, Surface};
+use crate::ffi::strings;
+use crate::gl_debug;
+use crate::gpu_capture::GpuCapture;
+use crate::gpu_sync::GpuSync;
+use crate::gpu_timer::{self, GpuTimer};
+use crate::haptics::{HapticPattern, Haptics};
+#[cfg(feature = "headless")]
+use crate::headless::HeadlessContext;
+use crate::hud::Hud;
+use crate::input::gamepad::{Control, Gamepads, MAX_PADS};
+use crate::input::keyboard::{KeyAction, KeyModifiers, KeyboardInput};
+use crate::input::routing::{TouchRouter, TouchRouting};
+use crate::input::sensors::{SensorType, Sensors};
+use crate::input_buffer::InputBuffer;
+use crate::ktx2;
+use crate::legacy::{Deprecations, LegacyCall};
+use crate::lod::LodSettings;
+use crate::nav::{NavGrid, Route};
+use crate::orientation::{Orientation, OrientationTracker, Remap, RotationPolicy};
+use crate::material::{Material, ShaderRegistry};
+#[cfg(feature = "physics")]
+use crate::physics;
+use crate::pacing::{FrameKey, FramePacer, PacingStats};
+use crate::palette::Palette;
+use crate::params::{Param, Params};
+use crate::particles::{EmitterConfig, EmitterId, ParticleSystem, INVALID_EMITTER};
+use crate::picture::{self, PictureCache};
+use crate::policy::{Mutation, Policy, RunSummary};
+use crate::players::{self, Controller, Players};
+use crate::pool::{FrameScratch, ListBuffers, TickScratch};
+use crate::postfx::{ColorLut, PostFx, PostFxSettings};
+use crate::power::{Degradation, PowerState, ThermalState};
+use crate::procgen::{self, Level};
+use crate::qr;
+use crate::replay::{GameCommand, Playback, Recorder, Recording};
+use crate::quirks::{self, DriverInfo, Quirks};
+use crate::render_hook::{self, RenderHook, RenderHooks};
+use crate::rng::Rng;
+use crate::prefab::{Prefab, Prefabs};
+use crate::save::{self, SavedSprite, SavedState};
+use crate::scene::{NodeContent, NodeId, Scene, Transform, INVALID_NODE};
+use crate::scoreboard::{Scoreboard, Stat};
+use crate::shader;
+use crate::share;
+use crate::simulation;
+use crate::spatial::SpatialHash;
+use crate::spikes::{FrameContext, FrameProfile, SpikeDetector};
+use crate::splash::Splash;
+use crate::sprites::{Sprite, SpriteArena, SpriteId, INVALID_SPRITE};
+use crate::state_diff::{Snapshot, SpriteState, StateDiffer};
+use crate::state_machine::{StateHook, StateId, StateMachine, INVALID_STATE};
+use crate::stats::{self, CullStats, EventStats, FrameStats, FrameTimer, InitTimings, InputMetrics};
+use crate::tags::{self, TagMask, TagRegistry};
+use crate::tasks::{CancellationToken, TaskGroup};
+use crate::tessellation::TessellationCache;
+use crate::text::{GlyphAtlasStats, Label, TextDirection, TextShaper};
+use crate::thread_hints::ThreadHints;
+use crate::throw::{DragTracker, Throw};
+use crate::tilemap::{TileDraw, Tilemap, TilemapId, INVALID_TILEMAP};
+use crate::tween::{self, Easing, TweenId, Tweens, INVALID_TWEEN};
+use crate::ui::{self, Ui, UiTouch, WidgetId};
+use crate::video_capture::{FrameCallback, VideoCapture};
+
+/// Direction enum for player movement
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum Direction {
+    #[default]
+    None = 0,
+    Up = 1,
+    Down = 2,
+    Left = 3,
+    Right = 4,
+}
+
+/// Game mode enum
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum GameMode {
+    #[default]
+    Manual = 0,
+    Auto = 1,
+}
+
+impl From<i32> for Direction {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Direction::Up,
+            2 => Direction::Down,
+            3 => Direction::Left,
+            4 => Direction::Right,
+            _ => Direction::None,
+        }
+    }
+}
+
+impl From<i32> for GameMode {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => GameMode::Auto,
+            _ => GameMode::Manual,
+        }
+    }
+}
+
+/// Touch action enum
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum TouchAction {
+    Down = 0,
+    Up = 1,
+    Move = 2,
+}
+
+impl From<i32> for TouchAction {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => TouchAction::Down,
+            1 => TouchAction::Up,
+            2 => TouchAction::Move,
+            _ => TouchAction::Down,
+        }
+    }
+}
+
+/// How much game_trim_memory frees; never renumber
+pub const TRIM_MEMORY_MODERATE: u32 = 0;
+pub const TRIM_MEMORY_BACKGROUND: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrimLevel {
+    /// Still on screen but memory is tight (onTrimMemory RUNNING_*, an iOS memory warning): keep
+    /// what this frame draws
+    Moderate,
+    /// Hidden or about to be (UI_HIDDEN and up, entering the background): drop everything that can
+    /// be made again
+    Background,
+}
+
+impl TrimLevel {
+    pub fn from_raw(raw: u32) -> Option<TrimLevel> {
+        match raw {
+            TRIM_MEMORY_MODERATE => Some(TrimLevel::Moderate),
+            TRIM_MEMORY_BACKGROUND => Some(TrimLevel::Background),
+            _ => None,
+        }
+    }
+}
+
+/// Screen coordinates game_world_to_screen and game_screen_to_world_in work in; never renumber
+pub const SCREEN_SPACE_POINTS: u32 = 0;
+pub const SCREEN_SPACE_PIXELS: u32 = 1;
+pub const SCREEN_SPACE_SAFE_AREA: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScreenSpace {
+    /// Points from the surface's top left: logical pixels for a host view covering the surface
+    Points,
+    /// Physical pixels from the surface's top left, as touches are reported
+    Pixels,
+    /// Points from the safe area's top left, for host widgets laid out inside the safe-area insets
+    SafeArea,
+}
+
+impl ScreenSpace {
+    pub fn from_raw(raw: u32) -> Option<ScreenSpace> {
+        match raw {
+            SCREEN_SPACE_POINTS => Some(ScreenSpace::Points),
+            SCREEN_SPACE_PIXELS => Some(ScreenSpace::Pixels),
+            SCREEN_SPACE_SAFE_AREA => Some(ScreenSpace::SafeArea),
+            _ => None,
+        }
+    }
+}
+
+/// Maximum time shutdown waits for background tasks
+const TASK_SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Default simulation rate for the fixed timestep
+const DEFAULT_TICK_RATE: u32 = 60;
+
+/// Assist mode: how fast the player closes on a tapped point (exponential rate per second)
+const ASSIST_PULL_RATE: f32 = 6.0;
+
+/// Assist mode: Auto mode speed multiplier
+const ASSIST_AUTO_SPEED: f32 = 0.5;
+
+/// Longest game_warmup waits for the startup textures to decode
+const WARMUP_LOAD_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Screen points past each edge that entities still count as visible, so sprite shaders, particle
+/// sprays and anything else drawn beyond an entity's bounds doesn't pop at the edges
+const CULL_MARGIN: f32 = 64.0;
+
+/// Side of the sprite index's cells in world points (a few typical sprites across)
+const SPRITE_CELL: f32 = 128.0;
+
+/// Side of pathfinding grid cells in world points
+const NAV_CELL: f32 = 16.0;
+
+/// Speed of sprites sent along a path while standing still (points per second); moving ones keep theirs
+const PATH_SPEED: f32 = 200.0;
+
+/// Built-in player image, used until the host loads its own "player" texture
+const PLAYER_IMAGE_BYTES: &[u8] = include_bytes!("../assets/player.png");
+
+/// GL context plus the egui painter drawing into it
+pub struct Renderer {
+    gl: Arc<glow::Context>,
+    painter: egui_glow::Painter,
+    // Driver workarounds (see quirks module)
+    quirks: Quirks,
+    // Frame picked by game_trigger_gpu_capture
+    gpu_capture: GpuCapture,
+    // glFinish or a fence at the end of each frame, and GL error checks (game_set_gpu_validation)
+    gpu_sync: GpuSync,
+    // Timer queries around the clear, scene and UI passes, for frame stats
+    gpu_timer: GpuTimer,
+    // Gameplay recording started by game_start_video_capture
+    video_capture: Option<VideoCapture>,
+    // Rasterized DRAW_PICTURE groups of the host draw list
+    pictures: PictureCache,
+    // Compressed texture formats the GPU samples (ktx2::FORMAT_* bits)
+    compressed_formats: u32,
+    // Time Renderer::new took, for game_get_init_timings
+    created_in: Duration,
+    // Thread the GL context is current on; every GL call must come from it
+    gl_thread: ThreadId,
+    // Off-screen context of a headless instance; dropped after the painter is destroyed
+    #[cfg(feature = "headless")]
+    _headless: Option<HeadlessContext>,
+}
+
+impl Renderer {
+    pub fn new(gl: Arc<glow::Context>) -> Result<Renderer, String> {
+        let start = Instant::now();
+        let driver = unsafe {
+            DriverInfo {
+                vendor: gl.get_parameter_string(glow::VENDOR),
+                renderer: gl.get_parameter_string(glow::RENDERER),
+                version: gl.get_parameter_string(glow::VERSION),
+            }
+        };
+        let quirks = quirks::detect(&driver);
+        log::info!("GL {} / {} / {}, quirks {:?}", driver.vendor, driver.renderer, driver.version, quirks);
+        let compressed_formats = ktx2::supported_formats(&gl);
+
+        // egui_glow painter for OpenGL ES
+        let shader_version = quirks.contains(Quirks::FORCE_GLES2).then_some(egui_glow::ShaderVersion::Es100);
+        let painter = egui_glow::Painter::new(gl.clone(), "", shader_version, false).map_err(|e| e.to_string())?;
+        Ok(Renderer {
+            gpu_sync: GpuSync::new(&gl),
+            gpu_timer: GpuTimer::new(&gl),
+            gl,
+            painter,
+            quirks,
+            gpu_capture: GpuCapture::default(),
+            video_capture: None,
+            pictures: PictureCache::default(),
+            compressed_formats,
+            created_in: start.elapsed(),
+            gl_thread: thread::current().id(),
+            #[cfg(feature = "headless")]
+            _headless: None,
+        })
+    }
+
+    /// Renderer on its own off-screen `width` x `height` context (see headless module)
+    #[cfg(feature = "headless")]
+    pub fn headless(width: u32, height: u32) -> Result<Renderer, String> {
+        let (context, gl) = HeadlessContext::create(width, height)?;
+        let mut renderer = Renderer::new(Arc::new(gl))?;
+        renderer._headless = Some(context);
+        Ok(renderer)
+    }
+
+    fn assert_gl_thread(&self) {
+        debug_assert_eq!(thread::current().id(), self.gl_thread, "GL used off the thread that created the renderer");
+    }
+
+    /// Draw a simulation thread's snapshot (see simulation module): the scene layers, UI and HUD of
+    /// `list` over its clear color. Pictures are drawn uncached, and the engine's own GL passes (post
+    /// effects, render hooks, recording, the debug overlay) wait for the engine to get the renderer back
+    pub fn draw_snapshot(&mut self, ctx: &egui::Context, list: &DisplayList, hud: &Hud, size: [u32; 2], pixels_per_point: f32) {
+        self.assert_gl_thread();
+        let gl = &self.gl;
+        unsafe {
+            let [r, g, b, a] = list.clear_color;
+            gl.viewport(0, 0, size[0] as i32, size[1] as i32);
+            gl.clear_color(r, g, b, a);
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::STENCIL_BUFFER_BIT);
+        }
+        let mut raw_input = egui::RawInput { screen_rect: Some(list.screen), ..Default::default() };
+        raw_input.viewports.entry(egui::ViewportId::ROOT).or_default().native_pixels_per_point = Some(pixels_per_point);
+        // The splash is the engine's; snapshots taken while it shows draw just the background
+        let output = ctx.run(raw_input, |ctx| {
+            if list.splash {
+                return;
+            }
+            list.paint(&ctx.layer_painter(egui::LayerId::background()));
+            let hud_painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("hud")));
+            ui::paint(&list.ui, &hud_painter);
+            hud.draw(&hud_painter, list.safe_area);
+        });
+        let primitives = ctx.tessellate(picture::flatten(output.shapes), output.pixels_per_point);
+        self.painter.paint_and_update_textures(size, output.pixels_per_point, &primitives, &output.textures_delta);
+    }
+}
+
+/// One game instance: simulation, scene and (optionally) the GL renderer
+/// The C API in lib.rs is a thin layer over these methods
+pub struct GameEngine {
+    // None when running without a GL context (tests); rendering is skipped
+    renderer: Option<Renderer>,
+
+    // Surface size in physical pixels
+    width: u32,
+    height: u32,
+    // Physical pixels per logical point; game coordinates are in points
+    scale_factor: f32,
+
+    // egui
+    egui_ctx: egui::Context,
+
+    // Player state
+    player_x: f32,
+    player_y: f32,
+    current_direction: Direction,
+
+    // Touch state
+    is_player_touched: bool,
+    drag_offset_x: f32,
+    drag_offset_y: f32,
+
+    // Inputs that arrived between ticks (direction taps, near-miss grabs)
+    input_buffer: InputBuffer,
+
+    // Assist mode (game_set_assist_mode): a tap pulls the player toward it, Auto mode is slower
+    assist: bool,
+    assist_target: Option<Pos2>,
+
+    // Key and text events for egui, consumed by the next render
+    keyboard: KeyboardInput,
+    // Which touch gestures are the engine's rather than the host's (game_set_touch_routing)
+    touch_router: TouchRouter,
+    // Recent drag positions, and the glide of a player let go mid-drag (game_set_throw_friction)
+    drag: DragTracker,
+    throw: Throw,
+    // Tunables hosts set at runtime (game_set_param)
+    params: Params,
+    // Players besides the primary one (add_player)
+    players: Players,
+    // Menu, playing, paused, game over and host states (transition)
+    states: StateMachine,
+
+    // Controller state, polled each tick in Manual mode
+    gamepads: Gamepads,
+
+    // Device tilt from forwarded motion sensors, and how hard it pulls in Auto mode (px/s^2)
+    sensors: Sensors,
+    tilt_gravity: f32,
+
+    // Game mode
+    game_mode: GameMode,
+    velocity_x: f32,
+    velocity_y: f32,
+
+    // Runtime-loaded textures by name (includes the player texture)
+    assets: AssetCache,
+
+    // Clear color and optional gradient behind the scene (matches the host theme)
+    background: Background,
+
+    // Player tint color (changes on bounce)
+    player_tint: Color32,
+    // Colors for bounce tints and highlights (game_set_palette)
+    palette: Palette,
+
+    // Random source for bounce colors (seeded for daily challenges)
+    rng: Rng,
+
+    // Active procedurally generated level (None = free play)
+    level: Option<Level>,
+
+    // Bounces this run (shown on share cards)
+    score: u32,
+    // Play statistics for host scoreboards (game_get_stat)
+    scoreboard: Scoreboard,
+
+    // Host-driven sprites (drawn beneath the player)
+    sprites: SpriteArena,
+    // Templates sprites are spawned from by name (game_load_prefabs)
+    prefabs: Prefabs,
+    // Single-player calls the host has used, warned about once each
+    deprecations: Deprecations,
+    // Paths sprites are following (game_move_entity_to), in id order so ticks are deterministic
+    routes: BTreeMap<SpriteId, Route>,
+    // Where each sprite was over its last tick, for culling the ones the camera can't see
+    sprite_index: SpatialHash,
+    // Emitters and their particles (drawn above the scene)
+    particles: ParticleSystem,
+    // Eased sprite and node property animations (game_animate_*)
+    tweens: Tweens,
+    // Sprite and node colliders, checked for overlaps after each tick (game_add_*_collider)
+    colliders: Colliders,
+    // Which details small entities and wide views drop (game_set_lod)
+    lod: LodSettings,
+    // Draw sprites and image nodes in batches instead of one egui mesh each (game_set_sprite_batching)
+    sprite_batching: bool,
+    // Host GLSL sprites can be drawn with (game_register_shader)
+    shaders: ShaderRegistry,
+
+    // Node hierarchy drawn above sprites (the player is a node)
+    scene: Scene,
+    // Host-provided draw commands (game_submit_draw_list), and their recently tessellated paths
+    host_draws: HostDrawList,
+    vector_cache: VectorCache,
+    // Fonts (game_register_font) and glyph atlas for draw-list text, and the atlas generation
+    // host_draws' text was laid out in (laid out again before drawing once it moves)
+    text: TextShaper,
+    host_text_generation: u64,
+
+    // Screen-space overlay above the scene (QR codes)
+    hud: Hud,
+    // Labels, buttons and progress bars anchored to the screen (game_ui_*), drawn with the HUD
+    ui: Ui,
+
+    // Tag names registered by the host (bit masks on sprites and nodes)
+    tags: TagRegistry,
+
+    // Sprite sheets referenced by node animations
+    atlases: Arena<Atlas>,
+    // Tiled / LDtk maps drawn beneath entities; their collision layers block the player
+    tilemaps: Arena<Tilemap>,
+
+    // Outbound events polled by the host
+    events: EventQueue,
+
+    // Haptic requests, flushed to events after each tick
+    haptics: Haptics,
+
+    // Loaded sounds and the mixer feeding the audio output
+    audio: Audio,
+
+    // Screen-reader messages for the host's live region
+    announcer: Announcer,
+
+    // Last state sent to a host-side mirror (game_poll_state_diff)
+    state_differ: StateDiffer,
+
+    // Anti-tamper policy for direct state mutation
+    policy: Policy,
+
+    // Rigid-body world driving Auto mode
+    #[cfg(feature = "physics")]
+    physics: physics::PhysicsWorld,
+
+    // Fixed timestep (ticks per second, 0 = variable timestep)
+    tick_rate: u32,
+    accumulator: f32,
+    // Ticks run since creation (replay timestamps)
+    tick_count: u64,
+
+    // Input log being recorded (game_start_recording)
+    recorder: Option<Recorder>,
+    // Recording being played back; host input is ignored meanwhile
+    playback: Option<Playback>,
+
+    // Render interpolation between the previous and current tick
+    prev_player: Pos2,
+    interpolation_alpha: f32,
+
+    // View onto the world (game_set_camera) and the world's size when larger than the screen
+    camera: Camera,
+    world_size: Option<Vec2>,
+    // Screen edges covered by notches, home indicators and system bars (physical pixels)
+    safe_area_insets: Margin,
+    // Host orientation (game_set_orientation); rotations remap positions when the resize arrives
+    orientation: OrientationTracker,
+
+    // Optional subsystems granted at init (see game_negotiate)
+    features: Features,
+
+    // Background threads owned by this instance (cancelled and joined on drop)
+    tasks: TaskGroup,
+    // Placeholder shown until the startup textures are uploaded
+    splash: Splash,
+
+    // Startup cost breakdown for game_get_init_timings
+    init_timings: InitTimings,
+    created: Instant,
+
+    // Render timing for game_get_frame_stats
+    frame_timer: FrameTimer,
+    // Slow-frame logging and the game_export_timeline ring
+    spikes: SpikeDetector,
+    // Frame rate cap and unchanged-frame skipping for game_render_with_timestamp
+    pacer: FramePacer,
+    // Thermal state and battery saver the host reports, and what's scaled down for them
+    power: PowerState,
+    // Render only when needed (game_set_render_on_demand); the display list last run,
+    // cleared by changes it doesn't capture (HUD, overlay, surface)
+    render_on_demand: bool,
+    drawn_list: Option<DisplayList>,
+    // Scene and HUD primitives of the last frame, redrawn while the display list stays the same
+    tessellation: TessellationCache,
+    // Buffers ticks and display lists are built in, reused so steady-state frames don't allocate
+    tick_scratch: TickScratch,
+    frame_scratch: RefCell<FrameScratch>,
+
+    // Host input counters for game_get_event_stats
+    input_metrics: InputMetrics,
+
+    // On-screen diagnostics (game_set_debug_overlay)
+    debug_overlay: DebugOverlay,
+    // Bloom, vignette and color grading of presented frames (game_set_post_fx)
+    post_fx: PostFx,
+    // Host GL drawing between the passes of presented frames (game_set_custom_render_hook)
+    render_hooks: RenderHooks,
+
+    // Set when a call panicked; the FFI layer refuses further calls (see game_get_status)
+    crash: Option<Crash>,
+    refused_calls: u64,
+
+    // Time tracking
+    last_frame_time: Instant,
+}
+
+impl GameEngine {
+    /// Create an instance for a `width` x `height` pixel surface
+    /// Without a renderer the game still simulates, but render and capture do nothing
+    pub fn new(renderer: Option<Renderer>, width: u32, height: u32) -> GameEngine {
+        let created = Instant::now();
+        if let Some(renderer) = &renderer {
+            // Set initial viewport
+            unsafe {
+                renderer.gl.viewport(0, 0, width as i32, height as i32);
+            }
+        }
+
+        // Create egui context
+        let egui_ctx = egui::Context::default();
+
+        // Built-in player texture (the host may replace it at runtime), decoded off-thread behind the splash
+        let mut assets = AssetCache::default();
+        let mut tasks = TaskGroup::default();
+        let startup = match assets.load_bytes_async(&mut tasks, assets::PLAYER_TEXTURE, PLAYER_IMAGE_BYTES.to_vec()) {
+            Ok(load) => vec![load],
+            Err(e) => {
+                log::warn!("{}; decoding the player image inline", e);
+                if let Err(e) = assets.load_from_bytes(&egui_ctx, assets::PLAYER_TEXTURE, PLAYER_IMAGE_BYTES) {
+                    log::error!("Failed to load player image: {}", e);
+                }
+                Vec::new()
+            }
+        };
+        let splash = Splash::new(&egui_ctx, startup);
+
+        let features = capabilities::granted();
+        let init_timings = InitTimings {
+            renderer_ms: renderer.as_ref().map_or(0.0, |r| stats::to_ms(r.created_in)),
+            engine_ms: stats::to_ms(created.elapsed()),
+            ..Default::default()
+        };
+
+        GameEngine {
+            renderer,
+            width,
+            height,
+            scale_factor: 1.0,
+            egui_ctx,
+            player_x: width as f32 / 2.0,
+            player_y: height as f32 / 2.0,
+            current_direction: Direction::None,
+            is_player_touched: false,
+            drag_offset_x: 0.0,
+            drag_offset_y: 0.0,
+            input_buffer: InputBuffer::default(),
+            assist: false,
+            assist_target: None,
+            keyboard: KeyboardInput::default(),
+            touch_router: TouchRouter::default(),
+            drag: DragTracker::default(),
+            throw: Throw::default(),
+            params: Params::default(),
+            players: Players::default(),
+            states: StateMachine::default(),
+            gamepads: Gamepads::default(),
+            sensors: Sensors::default(),
+            tilt_gravity: 0.0,
+            game_mode: GameMode::Manual,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            assets,
+            background: Background::default(),
+            player_tint: Color32::WHITE,
+            palette: Palette::default(),
+            rng: Rng::from_time(),
+            level: None,
+            score: 0,
+            scoreboard: Scoreboard::new(Pos2::new(width as f32 / 2.0, height as f32 / 2.0)),
+            sprites: SpriteArena::default(),
+            prefabs: Prefabs::default(),
+            deprecations: Deprecations::default(),
+            routes: BTreeMap::new(),
+            sprite_index: SpatialHash::new(SPRITE_CELL),
+            particles: ParticleSystem::default(),
+            tweens: Tweens::default(),
+            colliders: Colliders::default(),
+            lod: LodSettings::default(),
+            sprite_batching: true,
+            shaders: ShaderRegistry::default(),
+            scene: Scene::default(),
+            host_draws: HostDrawList::default(),
+            vector_cache: VectorCache::default(),
+            text: TextShaper::new(),
+            host_text_generation: 0,
+            hud: Hud::default(),
+            ui: Ui::default(),
+            tags: TagRegistry::default(),
+            atlases: Arena::default(),
+            tilemaps: Arena::default(),
+            events: EventQueue::default(),
+            haptics: Haptics::new(features.contains(Features::HAPTICS)),
+            audio: Audio::new(features.contains(Features::AUDIO)),
+            announcer: Announcer::default(),
+            state_differ: StateDiffer::default(),
+            policy: Policy::default(),
+            #[cfg(feature = "physics")]
+            physics: physics::PhysicsWorld::default(),
+            tick_rate: DEFAULT_TICK_RATE,
+            accumulator: 0.0,
+            tick_count: 0,
+            recorder: None,
+            playback: None,
+            prev_player: Pos2::new(width as f32 / 2.0, height as f32 / 2.0),
+            interpolation_alpha: 1.0,
+            camera: Camera::default(),
+            world_size: None,
+            safe_area_insets: Margin::ZERO,
+            orientation: OrientationTracker::default(),
+            features,
+            tasks,
+            splash,
+            init_timings,
+            created,
+            frame_timer: FrameTimer::default(),
+            spikes: SpikeDetector::default(),
+            pacer: FramePacer::default(),
+            power: PowerState::default(),
+            render_on_demand: false,
+            drawn_list: None,
+            tessellation: TessellationCache::default(),
+            tick_scratch: TickScratch::default(),
+            frame_scratch: RefCell::default(),
+            input_metrics: InputMetrics::default(),
+            debug_overlay: DebugOverlay::default(),
+            post_fx: PostFx::default(),
+            render_hooks: RenderHooks::default(),
+            crash: None,
+            refused_calls: 0,
+            last_frame_time: Instant::now(),
+        }
+    }
+
+    /// Optional subsystems granted to this instance
+    pub fn features(&self) -> Features {
+        self.features
+    }
+
+    /// Screen size in points (game and egui coordinates)
+    fn screen_size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32) / self.scale_factor
+    }
+
+    /// Area the player moves in and levels are laid out over (points): the screen unless the host
+    /// set a larger world (game_set_world_size)
+    fn world_size(&self) -> Vec2 {
+        self.world_size.unwrap_or_else(|| self.screen_size())
+    }
+
+    /// Safe-area insets in points
+    fn safe_area_insets(&self) -> Margin {
+        self.safe_area_insets / self.scale_factor
+    }
+
+    /// Screen area clear of notches and system bars (points); the HUD and UI widgets are laid out in it
+    fn safe_area(&self) -> Rect {
+        Rect::from_min_size(Pos2::ZERO, self.screen_size()) - self.safe_area_insets()
+    }
+
+    /// Area the player is kept in (points): the world less the safe-area insets, never smaller than
+    /// the player
+    fn player_area(&self) -> Rect {
+        let area = Rect::from_min_size(Pos2::ZERO, self.world_size()) - self.safe_area_insets();
+        Rect::from_center_size(area.center(), area.size().max(Vec2::splat(self.player_size())))
+    }
+
+    /// Pull the player back inside player_area after it shrank
+    fn keep_player_inside(&mut self) {
+        let area = self.player_area().shrink(self.player_size() / 2.0);
+        self.player_x = self.player_x.clamp(area.min.x, area.max.x);
+        self.player_y = self.player_y.clamp(area.min.y, area.max.y);
+        self.snap_interpolation();
+    }
+
+    /// Note the host's orientation; a change remaps positions (per the rotation policy) when the
+    /// resize that follows it arrives, instead of only clamping the player into the new bounds
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        if self.orientation.set(orientation, self.world_size()) {
+            log::info!("Orientation changed to {:?}", orientation);
+        }
+    }
+
+    pub fn set_rotation_policy(&mut self, policy: RotationPolicy) {
+        self.orientation.policy = policy;
+    }
+
+    /// Move the player, sprites and the paths they follow to the same place relative to the resized
+    /// world's edges
+    fn remap_positions(&mut self, remap: Remap) {
+        let player = remap.apply(Pos2::new(self.player_x, self.player_y));
+        (self.player_x, self.player_y) = (player.x, player.y);
+        self.assist_target = self.assist_target.map(|target| remap.apply(target));
+        for sprite in self.sprites.iter_mut() {
+            sprite.position = remap.apply(sprite.position);
+        }
+        for route in self.routes.values_mut() {
+            route.map_points(|point| remap.apply(point));
+        }
+    }
+
+    /// Handle surface size changes (physical pixels)
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.assert_gl_thread();
+        // Place player on first resize (when dimensions were 0)
+        let first_size = self.width == 0 || self.height == 0;
+        self.width = width;
+        self.height = height;
+        // The surface may be new, with nothing on it yet
+        self.request_redraw();
+        if first_size {
+            let start = self.level.as_ref().map_or(Pos2::new(0.5, 0.5), |l| l.player_start);
+            let world = self.world_size();
+            self.player_x = start.x * world.x;
+            self.player_y = start.y * world.y;
+            self.snap_interpolation();
+        } else if width > 0 && height > 0 {
+            // A rotation keeps entities where they were relative to the world's edges
+            if let Some(remap) = self.orientation.finish(self.world_size()) {
+                self.remap_positions(remap);
+            }
+            self.keep_player_inside();
+        }
+
+        if let Some(renderer) = &self.renderer {
+            unsafe {
+                renderer.gl.viewport(0, 0, width as i32, height as i32);
+            }
+        }
+    }
+
+    /// Set physical pixels per point; the player keeps its place on screen
+    /// Returns false (and changes nothing) unless `scale` is finite and positive
+    pub fn set_scale_factor(&mut self, scale: f32) -> bool {
+        if !(scale.is_finite() && scale > 0.0) {
+            return false;
+        }
+        let ratio = self.scale_factor / scale;
+        self.scale_factor = scale;
+        self.player_x *= ratio;
+        self.player_y *= ratio;
+        self.snap_interpolation();
+        // Glyphs and SVG textures were rasterized for the old scale
+        self.text.invalidate();
+        self.assets.set_scale(scale);
+        true
+    }
+
+    /// Start a level from a seed: reseed RNG, reset player, apply level velocity
+    fn start_level(&mut self, seed: u32) {
+        let level = procgen::generate(seed);
+
+        self.rng = Rng::new(seed as u64);
+        self.policy.start_run();
+        self.score = 0;
+        let world = self.world_size();
+        self.player_x = level.player_start.x * world.x;
+        self.player_y = level.player_start.y * world.y;
+        self.player_tint = Color32::WHITE;
+        self.current_direction = Direction::None;
+        self.is_player_touched = false;
+        self.throw.stop();
+        self.assist_target = None;
+        self.snap_interpolation();
+        if self.game_mode == GameMode::Auto {
+            self.velocity_x = level.auto_velocity.x;
+            self.velocity_y = level.auto_velocity.y;
+        }
+
+        log::info!(
+            "Level started: seed={} code={} obstacles={}",
+            seed,
+            challenge::encode(seed),
+            level.obstacles.len()
+        );
+        self.level = Some(level);
+    }
+
+    /// Check if a point is within the player box
+    fn is_on_player(&self, x: f32, y: f32) -> bool {
+        let half = self.player_size() / 2.0;
+        x >= self.player_x - half
+            && x <= self.player_x + half
+            && y >= self.player_y - half
+            && y <= self.player_y + half
+    }
+
+    /// Start dragging the player from a touch point
+    fn grab_player(&mut self, x: f32, y: f32) {
+        self.is_player_touched = true;
+        self.throw.stop();
+        self.drag_offset_x = self.player_x - x;
+        self.drag_offset_y = self.player_y - y;
+    }
+
+    /// Push the player out of level obstacles
+    /// In Auto mode the velocity is reflected and the tint changes, like a wall bounce
+    fn resolve_obstacles(&mut self) {
+        let Vec2 { x: width, y: height } = self.world_size();
+        let mut rects = std::mem::take(&mut self.tick_scratch.obstacles);
+        if let Some(level) = &self.level {
+            rects.extend(level.obstacles.iter().map(|o| o.screen_rect(width, height)));
+        }
+        let player = Rect::from_center_size(Pos2::new(self.player_x, self.player_y), Vec2::splat(self.player_size()));
+        for map in self.tilemaps.iter() {
+            map.solid_rects(player, &mut rects);
+        }
+
+        let half = self.player_size() / 2.0;
+
+        for &rect in &rects {
+            let overlap_x = (self.player_x + half).min(rect.max.x) - (self.player_x - half).max(rect.min.x);
+            let overlap_y = (self.player_y + half).min(rect.max.y) - (self.player_y - half).max(rect.min.y);
+            if overlap_x <= 0.0 || overlap_y <= 0.0 {
+                continue;
+            }
+
+            // Separate along the axis of least penetration
+            let speed = if overlap_x < overlap_y {
+                let sign = if self.player_x < rect.center().x { -1.0 } else { 1.0 };
+                self.player_x += sign * overlap_x;
+                let speed = self.velocity_x.abs();
+                self.velocity_x = sign * speed;
+                speed
+            } else {
+                let sign = if self.player_y < rect.center().y { -1.0 } else { 1.0 };
+                self.player_y += sign * overlap_y;
+                let speed = self.velocity_y.abs();
+                self.velocity_y = sign * speed;
+                speed
+            };
+
+            if self.game_mode == GameMode::Auto {
+                let point = rect.clamp(Pos2::new(self.player_x, self.player_y));
+                self.events.push(GameEvent::Bounce { surface: Surface::Obstacle, x: point.x, y: point.y, speed });
+                self.haptics.impact(speed);
+                self.audio.bounce(speed);
+                self.announcer.bounce(Surface::Obstacle);
+                self.particles.bounce(point, Pos2::new(self.player_x, self.player_y));
+                self.player_tint = self.palette.bounce_color(&mut self.rng, self.player_tint);
+                self.score += 1;
+                self.scoreboard.bounce();
+            }
+        }
+        rects.clear();
+        self.tick_scratch.obstacles = rects;
+    }
+
+    /// Advance the player as a rigid body (Auto mode with the physics feature)
+    /// Contacts change the tint and are reported as Bounce events
+    #[cfg(feature = "physics")]
+    fn step_physics(&mut self, delta: f32) {
+        self.physics.restitution = self.params.get(Param::Restitution);
+        let Vec2 { x: width, y: height } = self.world_size();
+        let mut body = physics::RigidBody {
+            position: Pos2::new(self.player_x, self.player_y),
+            velocity: Vec2::new(self.velocity_x, self.velocity_y),
+            half_size: Vec2::splat(self.player_size() / 2.0),
+        };
+
+        let bounds = self.player_area();
+        let mut obstacles = std::mem::take(&mut self.tick_scratch.obstacles);
+        if let Some(level) = &self.level {
+            obstacles.extend(level.obstacles.iter().map(|o| o.screen_rect(width, height)));
+        }
+        // Solid tiles the player could reach this step
+        let reach = Rect::from_center_size(body.position, body.half_size * 2.0).expand(body.velocity.length() * delta);
+        for map in self.tilemaps.iter() {
+            map.solid_rects(reach, &mut obstacles);
+        }
+
+        let mut contacts = std::mem::take(&mut self.tick_scratch.physics_contacts);
+        self.physics.step(&mut body, bounds, &obstacles, delta, &mut contacts);
+        obstacles.clear();
+        self.tick_scratch.obstacles = obstacles;
+
+        self.player_x = body.position.x;
+        self.player_y = body.position.y;
+        self.velocity_x = body.velocity.x;
+        self.velocity_y = body.velocity.y;
+
+        for contact in contacts.drain(..) {
+            self.player_tint = self.palette.bounce_color(&mut self.rng, self.player_tint);
+            self.score += 1;
+            self.scoreboard.bounce();
+            self.events.push(GameEvent::Bounce {
+                surface: contact.surface,
+                x: contact.point.x,
+                y: contact.point.y,
+                speed: contact.speed,
+            });
+            self.haptics.impact(contact.speed);
+            self.audio.bounce(contact.speed);
+            self.announcer.bounce(contact.surface);
+            self.particles.bounce(contact.point, body.position);
+        }
+        self.tick_scratch.physics_contacts = contacts;
+    }
+
+    /// Advance game logic by one step of `delta` seconds
+    fn tick(&mut self, delta: f32, now: Instant) {
+        // Replayed input is applied just before the tick it arrived ahead of
+        while let Some(command) = self.playback.as_mut().and_then(|p| p.due(self.tick_count)) {
+            self.apply(command);
+        }
+
+        self.steer_routes(delta);
+        self.sprites.update(delta);
+        self.particles.update(delta);
+        // Tweened properties override velocities
+        let mut ended = std::mem::take(&mut self.tick_scratch.ended_tweens);
+        let (sprites, scene) = (&mut self.sprites, &mut self.scene);
+        self.tweens.update(delta, |target, value| target.set(sprites, scene, value), &mut ended);
+        self.report_tweens(&mut ended);
+        self.tick_scratch.ended_tweens = ended;
+        self.index_sprites();
+
+        let finished = &mut self.tick_scratch.finished_animations;
+        self.scene.update_animations(delta, finished);
+        for node in finished.drain(..) {
+            self.events.push(GameEvent::AnimationFinished { node });
+        }
+
+        let area = self.player_area();
+        // Where the player's center may go
+        let (min, max) = (area.min + Vec2::splat(self.player_size() / 2.0), area.max - Vec2::splat(self.player_size() / 2.0));
+        let auto_delta = if self.assist { delta * ASSIST_AUTO_SPEED } else { delta };
+
+        // Device tilt pulls the player in Auto mode (off until set_tilt_gravity)
+        if self.game_mode == GameMode::Auto && self.tilt_gravity != 0.0 {
+            let pull = self.sensors.tilt() * self.tilt_gravity * delta;
+            self.velocity_x += pull.x;
+            self.velocity_y += pull.y;
+        }
+
+        match self.game_mode {
+            GameMode::Manual => {
+                // Move player based on direction (held, or a tap buffered since last tick)
+                // A controller in use overrides it, with analog speed from the stick
+                let speed = self.params.get(Param::Speed) * delta;
+                let direction = self.input_buffer.take_direction(self.current_direction, now);
+                let pad = self.gamepads.movement();
+                if pad != Vec2::ZERO {
+                    self.player_x += pad.x * speed;
+                    self.player_y += pad.y * speed;
+                } else {
+                    match direction {
+                        Direction::Up => self.player_y -= speed,
+                        Direction::Down => self.player_y += speed,
+                        Direction::Left => self.player_x -= speed,
+                        Direction::Right => self.player_x += speed,
+                        Direction::None => {}
+                    }
+                }
+
+                // Assist mode: ease toward the last touch point instead of requiring a drag
+                if let Some(target) = self.assist_target {
+                    let t = 1.0 - (-ASSIST_PULL_RATE * delta).exp();
+                    self.player_x += (target.x - self.player_x) * t;
+                    self.player_y += (target.y - self.player_y) * t;
+                    if (target - Pos2::new(self.player_x, self.player_y)).length() < 1.0 {
+                        self.assist_target = None;
+                    }
+                }
+
+                // Clamp to bounds
+                self.player_x = self.player_x.clamp(min.x, max.x);
+                self.player_y = self.player_y.clamp(min.y, max.y);
+
+                self.players.step(delta, self.params.get(Param::Speed), &self.gamepads, min, max);
+            }
+            #[cfg(feature = "physics")]
+            GameMode::Auto if self.features.contains(Features::PHYSICS) => self.step_physics(auto_delta),
+            GameMode::Auto => {
+                // Velocity-based movement
+                self.player_x += self.velocity_x * auto_delta;
+                self.player_y += self.velocity_y * auto_delta;
+
+                // Bounce off walls and change color on each bounce
+                if self.player_x <= min.x || self.player_x >= max.x {
+                    let (surface, x) = if self.player_x <= min.x {
+                        (Surface::Left, area.min.x)
+                    } else {
+                        (Surface::Right, area.max.x)
+                    };
+                    self.events.push(GameEvent::Bounce { surface, x, y: self.player_y, speed: self.velocity_x.abs() });
+                    self.haptics.impact(self.velocity_x.abs());
+                    self.audio.bounce(self.velocity_x.abs());
+                    self.announcer.bounce(surface);
+                    self.velocity_x = -self.velocity_x * self.params.get(Param::Restitution);
+                    self.player_x = self.player_x.clamp(min.x, max.x);
+                    self.particles.bounce(Pos2::new(x, self.player_y), Pos2::new(self.player_x, self.player_y));
+                    self.player_tint = self.palette.bounce_color(&mut self.rng, self.player_tint);
+                    self.score += 1;
+                    self.scoreboard.bounce();
+                }
+                if self.player_y <= min.y || self.player_y >= max.y {
+                    let (surface, y) = if self.player_y <= min.y {
+                        (Surface::Top, area.min.y)
+                    } else {
+                        (Surface::Bottom, area.max.y)
+                    };
+                    self.events.push(GameEvent::Bounce { surface, x: self.player_x, y, speed: self.velocity_y.abs() });
+                    self.haptics.impact(self.velocity_y.abs());
+                    self.audio.bounce(self.velocity_y.abs());
+                    self.announcer.bounce(surface);
+                    self.velocity_y = -self.velocity_y * self.params.get(Param::Restitution);
+                    self.player_y = self.player_y.clamp(min.y, max.y);
+                    self.particles.bounce(Pos2::new(self.player_x, y), Pos2::new(self.player_x, self.player_y));
+                    self.player_tint = self.palette.bounce_color(&mut self.rng, self.player_tint);
+                    self.score += 1;
+                    self.scoreboard.bounce();
+                }
+            }
+        }
+
+        // A thrown player glides to a stop, halted along an axis by the wall it reaches
+        if self.throw.is_moving() && !self.is_player_touched {
+            let step = self.throw.step(delta, self.params.get(Param::Friction));
+            let moved = Pos2::new(self.player_x, self.player_y) + step;
+            let clamped = moved.clamp(min, max);
+            self.throw.stop_axis(clamped.x != moved.x, clamped.y != moved.y);
+            (self.player_x, self.player_y) = (clamped.x, clamped.y);
+        }
+
+        self.resolve_obstacles();
+        self.step_colliders();
+        let screen = self.screen_size();
+        self.camera.update(delta, Pos2::new(self.player_x, self.player_y), screen);
+        self.haptics.flush(&mut self.events);
+        self.announcer.flush(self.score, now);
+        self.scoreboard.tick(delta, self.game_mode, Pos2::new(self.player_x, self.player_y), self.score);
+        self.scoreboard.flush(&mut self.events);
+
+        // Retry a grab that missed the player slightly before this tick
+        if !self.is_player_touched {
+            if let Some(grab) = self.input_buffer.pending_grab(now) {
+                if self.is_on_player(grab.x, grab.y) {
+                    self.grab_player(grab.x, grab.y);
+                    self.input_buffer.cancel_grab();
+                }
+            }
+        }
+
+        self.tick_count += 1;
+        if self.playback.as_ref().is_some_and(|p| p.is_finished(self.tick_count)) {
+            self.playback = None;
+            self.events.push(GameEvent::ReplayFinished);
+            log::info!("Replay finished");
+        }
+    }
+
+    /// Bring the sprite index up to date: each sprite under the span it covers between ticks
+    fn index_sprites(&mut self) {
+        for (id, sprite) in self.sprites.entries() {
+            self.sprite_index.update(id, sprite.swept_rect());
+        }
+        let sprites = &self.sprites;
+        self.sprite_index.retain(|id| sprites.contains(id));
+    }
+
+    /// Remember positions before a tick so render can interpolate between ticks
+    fn store_previous_positions(&mut self) {
+        self.prev_player = Pos2::new(self.player_x, self.player_y);
+        self.players.store_previous_positions();
+        for sprite in self.sprites.iter_mut() {
+            sprite.prev_position = sprite.position;
+        }
+        self.particles.store_previous_positions();
+        self.camera.store_previous_position();
+    }
+
+    /// Reset interpolation so the next render shows current positions (after teleports)
+    fn snap_interpolation(&mut self) {
+        self.store_previous_positions();
+        self.scoreboard.place(Pos2::new(self.player_x, self.player_y));
+        self.interpolation_alpha = 1.0;
+    }
+
+    /// Advance the simulation to `now`
+    /// Runs zero or more fixed-rate ticks (see set_tick_rate)
+    pub fn update(&mut self, now: Instant) {
+        let _span = tracing::info_span!("update").entered();
+        // Calculate delta time with frame cap to prevent huge jumps
+        let delta = now.saturating_duration_since(self.last_frame_time).as_secs_f32();
+        self.last_frame_time = now;
+
+        // Cap delta time to prevent physics explosions after pause
+        let delta = delta.min(0.1); // Max 100ms per frame
+
+        // Paused and menu states hold the world still
+        if !self.states.simulates() {
+            return;
+        }
+
+        if self.tick_rate == 0 {
+            // Variable timestep (legacy behavior)
+            self.snap_interpolation();
+            self.tick(delta, now);
+            return;
+        }
+
+        // Fixed timestep: run whole ticks, keep the remainder for render interpolation
+        let dt = 1.0 / self.tick_rate as f32;
+        self.accumulator += delta;
+        while self.accumulator >= dt {
+            self.store_previous_positions();
+            self.tick(dt, now);
+            self.accumulator -= dt;
+        }
+        self.interpolation_alpha = self.accumulator / dt;
+    }
+
+    /// What this frame draws, resolved from the current state before tessellation, with moving
+    /// things interpolated between the last two ticks
+    pub fn display_list(&self) -> DisplayList {
+        self.display_list_at(self.interpolation_alpha)
+    }
+
+    /// Hand back a display list done with, for the next one to be built in its vectors
+    pub fn recycle_display_list(&self, list: DisplayList) {
+        self.frame_scratch.borrow_mut().recycle_list(list);
+    }
+
+    /// The display list `alpha` of the way from the previous tick to the current one
+    fn display_list_at(&self, alpha: f32) -> DisplayList {
+        let screen_rect = Rect::from_min_size(Pos2::ZERO, self.screen_size());
+
+        // Interpolate between the last two ticks (dragging follows the finger directly)
+        let current = Pos2::new(self.player_x, self.player_y);
+        // A player at rest is drawn exactly where it is: lerp between equal points can land a hair off,
+        // and the changed list would defeat render-on-demand
+        let player_pos = if self.is_player_touched || self.prev_player == current {
+            current
+        } else {
+            self.prev_player.lerp(current, alpha)
+        };
+
+        let player_size = self.player_size();
+        let player_texture = self.assets.get(*assets::PLAYER_TEXTURE_ID).map(|t| (t, TextureSource::Player));
+        let Vec2 { x: width, y: height } = self.world_size();
+        let level_obstacles = self.level.as_ref().map(|l| l.obstacles.as_slice()).unwrap_or_default();
+
+        // Image, render size and tint of a player node: extra players have their own image (the
+        // built-in one until set) and color, highlighted like the primary player while touched
+        let player_look = |id: NodeId| {
+            let (texture, touched, color) = match self.players.get(id) {
+                Some(player) => {
+                    let own = player.texture.and_then(|name| Some((self.assets.get(name)?, TextureSource::Asset(name))));
+                    (own.or(player_texture), player.touch_target.is_some(), player.tint)
+                }
+                // player_tint changes on bounce
+                None => (player_texture, self.is_player_touched, self.player_tint),
+            };
+            // Default to square if the texture failed to load
+            let texture_size = texture.map_or(Vec2::splat(player_size), |(t, _)| t.size);
+
+            // Calculate render size maintaining aspect ratio
+            // Scale so the larger dimension fits within player_size
+            let aspect = texture_size.x / texture_size.y;
+            let (render_w, render_h) = if aspect >= 1.0 {
+                // Wider than tall: width = player_size, height = player_size / aspect
+                (player_size, player_size / aspect)
+            } else {
+                // Taller than wide: height = player_size, width = player_size * aspect
+                (player_size * aspect, player_size)
+            };
+
+            // Apply tint: palette highlight when dragging
+            let tint = if touched { self.palette.highlight() } else { color };
+            (texture.map(|(t, source)| (t.id, source)), Vec2::new(render_w, render_h), tint)
+        };
+
+        // World region the frame shows, widened by the cull margin; entities outside it are skipped
+        let camera = self.camera.transform(screen_rect.size(), alpha);
+        let view = camera.inverse().map_or(Rect::EVERYTHING, |inverse| inverse.bounds(screen_rect.expand(CULL_MARGIN)));
+        let mut culled = CullStats::default();
+        // Level of detail: how many screen pixels a world point covers, and whether the view is zoomed out
+        // far enough to draw everything plainly
+        let pixels_per_point = self.camera.zoom() * self.scale_factor;
+        let simplified = self.lod.simplified(self.camera.zoom());
+
+        // Built in the vectors of a list already drawn, if one was handed back
+        let mut scratch = self.frame_scratch.borrow_mut();
+        let ListBuffers { mut tiles, mut obstacles, mut sprites, mut nodes, mut particles } = scratch.list_buffers();
+
+        // Tile chunks in view whose tileset texture has loaded
+        for (map_id, map) in self.tilemaps.entries() {
+            for (index, layer) in map.layers.iter().enumerate().filter(|(_, layer)| layer.visible) {
+                for chunk in layer.chunks.iter().filter(|chunk| view.intersects(chunk.bounds)) {
+                    if let Some(texture) = self.assets.get(map.texture(chunk.tileset)) {
+                        let mesh = chunk.mesh.clone();
+                        tiles.push(TileDraw { map: map_id, layer: index, texture: texture.id, bounds: chunk.bounds, mesh });
+                    }
+                }
+            }
+        }
+
+        let mut visible = std::mem::take(&mut scratch.visible);
+        self.sprite_index.query(view, &mut visible);
+        for &id in &visible {
+            let Some(sprite) = self.sprites.get(id) else {
+                continue;
+            };
+            let rect = sprite.interpolated_rect(alpha);
+            if view.intersects(rect) {
+                let detailed = !simplified && self.lod.detailed(rect.size().max_elem() * pixels_per_point);
+                let material = sprite.material.as_ref().filter(|_| detailed).map(|m| self.shaders.resolve(m, sprite.color));
+                sprites.push((id, rect, sprite.color, material));
+            }
+        }
+        culled.sprites = (self.sprites.count() - sprites.len()) as u32;
+        scratch.visible = visible;
+
+        // Scene graph (player and host-built nodes) in z order
+        let mut items = scratch.items();
+        self.scene.collect(self.players.interpolated((self.scene.player(), player_pos), alpha), &mut scratch.stack, &mut items);
+
+        // Local bounds of the visuals drawn centered on the node origin (all but text)
+        let centered = |size: Vec2| Rect::from_center_size(Pos2::ZERO, size);
+        for item in &items {
+            // Current flipbook frame, if the node is animated
+            let frame = item.animation.and_then(|animation| {
+                let atlas = self.atlases.get(animation.atlas)?;
+                let index = animation.frame();
+                let frame = atlas.frame(index)?;
+                let source = TextureSource::Atlas { atlas: animation.atlas, frame: index };
+                Some((atlas.texture.id(), source, frame))
+            });
+
+            let (local, mut visual) = match (item.content, frame) {
+                (NodeContent::Group, Some((texture, source, frame))) => {
+                    let visual = NodeVisual::Image { texture, source, uv: frame.uv, tint: Color32::WHITE };
+                    (centered(frame.size), visual)
+                }
+                (NodeContent::Group, None) => continue,
+                (NodeContent::Rect { size, color }, Some((texture, source, frame))) => {
+                    (centered(*size), NodeVisual::Image { texture, source, uv: frame.uv, tint: *color })
+                }
+                (NodeContent::Rect { size, color }, None) => {
+                    (centered(*size), NodeVisual::Rect { fill: *color, stroke: Stroke::NONE })
+                }
+                (NodeContent::Player, Some((texture, source, frame))) => {
+                    let (_, size, tint) = player_look(item.id);
+                    (centered(size), NodeVisual::Image { texture, source, uv: frame.uv, tint })
+                }
+                (NodeContent::Player, None) => {
+                    // Draw player image or fallback to box
+                    let (texture, size, tint) = player_look(item.id);
+                    let visual = match texture {
+                        Some((texture, source)) => NodeVisual::Image {
+                            texture,
+                            source,
+                            uv: Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                            tint,
+                        },
+                        // Fallback: draw colored box if texture failed to load
+                        None => NodeVisual::Rect { fill: tint, stroke: Stroke::new(2.0, Color32::WHITE) },
+                    };
+                    (centered(size), visual)
+                }
+                // Text ignores flipbooks
+                (NodeContent::Text(label), _) => (label.bounds(), NodeVisual::Text((**label).clone())),
+            };
+            let bounds = item.world.bounds(local);
+            if !view.intersects(bounds) {
+                culled.nodes += 1;
+                continue;
+            }
+            if let NodeVisual::Rect { stroke, .. } = &mut visual {
+                if !self.lod.detailed(bounds.size().max_elem() * pixels_per_point) {
+                    *stroke = Stroke::NONE;
+                }
+            }
+            nodes.push(NodeDraw { node: item.id, z: item.z, world: item.world, size: local.size(), visual });
+        }
+        scratch.recycle_items(items);
+        drop(scratch);
+        culled.particles = self.particles.mesh(alpha, view, self.lod.detail_min_px / pixels_per_point, &mut particles);
+        obstacles.extend(level_obstacles.iter().map(|o| (o.screen_rect(width, height), o.color)));
+
+        DisplayList {
+            screen: screen_rect,
+            camera,
+            clear_color: self.background.clear_color,
+            gradient: self.background.gradient,
+            tiles,
+            obstacles,
+            sprites,
+            nodes,
+            particles,
+            culled,
+            simplified,
+            batching: self.sprite_batching,
+            host: self.host_draws.resolve(&self.assets, &|id, key, bounds| {
+                let size = picture::pixel_size(bounds, self.scale_factor)?;
+                self.renderer.as_ref()?.pictures.get(id, key, size)
+            }),
+            safe_area: self.safe_area(),
+            ui: self.ui.resolve(self.safe_area()),
+            qr: self.hud.qr_bounds(self.safe_area()),
+            splash: self.splash.is_showing(),
+        }
+    }
+
+    /// Hand the renderer over to a simulation thread's render side (see simulation module)
+    /// The engine goes on without one, like a headless instance, until restore_renderer
+    pub fn take_renderer(&mut self) -> Option<Renderer> {
+        self.assert_gl_thread();
+        self.tessellation.clear();
+        self.renderer.take()
+    }
+
+    pub fn restore_renderer(&mut self, renderer: Renderer) {
+        renderer.assert_gl_thread();
+        self.renderer = Some(renderer);
+        // What's on screen was drawn from a snapshot
+        self.request_redraw();
+    }
+
+    /// Advance to `now` on the simulation thread, and snapshot the state for the render thread
+    /// Does the per-frame work render would (texture uploads, text layout, the splash) first
+    pub fn step_simulation(&mut self, now: Instant) -> simulation::Snapshot {
+        self.update(now);
+        self.upload_decoded();
+        self.reshape_stale_text();
+        self.splash.update(now);
+        simulation::Snapshot {
+            list: self.display_list_at(1.0),
+            hud: self.hud.clone(),
+            size: [self.width, self.height],
+            pixels_per_point: self.scale_factor,
+            stepped_at: now,
+        }
+    }
+
+    pub fn egui_context(&self) -> egui::Context {
+        self.egui_ctx.clone()
+    }
+
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    /// Debug builds check GL work stays on the renderer's thread (the host's GL thread)
+    fn assert_gl_thread(&self) {
+        if let Some(renderer) = &self.renderer {
+            renderer.assert_gl_thread();
+        }
+    }
+
+    /// Run an egui frame drawing the scene in screen coordinates
+    /// `raw_input` carries any host input for this frame; screen and scale are filled in here
+    /// Pictures that aren't cached yet are drawn directly
+    fn run_scene(&self, raw_input: egui::RawInput) -> egui::FullOutput {
+        let mut output = self.run_list(&self.display_list(), raw_input);
+        output.shapes = picture::flatten(output.shapes);
+        output
+    }
+
+    fn run_list(&self, list: &DisplayList, mut raw_input: egui::RawInput) -> egui::FullOutput {
+        let screen_rect = list.screen;
+        let hud = &self.hud;
+
+        // Run egui frame (screen_rect in points; egui scales to pixels)
+        raw_input.screen_rect = Some(screen_rect);
+        raw_input.viewports.entry(egui::ViewportId::ROOT).or_default().native_pixels_per_point = Some(self.scale_factor);
+
+        self.egui_ctx.run(raw_input, |ctx| {
+            let painter = ctx.layer_painter(egui::LayerId::background());
+            if list.splash {
+                self.splash.draw(&painter, screen_rect, Instant::now());
+                return;
+            }
+            list.paint(&painter);
+
+            // HUD above everything else
+            let hud_painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("hud")));
+            ui::paint(&list.ui, &hud_painter);
+            hud.draw(&hud_painter, list.safe_area);
+        })
+    }
+
+    /// Draw a frame into the current framebuffer
+    /// False if nothing was drawn: a zero-sized surface, or nothing changed in on-demand mode
+    /// Optimized: pre-computed colors, minimal allocations
+    pub fn render(&mut self) -> bool {
+        // Frames drawn here aren't paced, so the next paced one can't assume what's on screen
+        self.pacer.invalidate();
+        self.draw_frame(None)
+    }
+
+    /// Only draw frames when needs_redraw (off by default: every render draws)
+    pub fn set_render_on_demand(&mut self, enabled: bool) {
+        self.render_on_demand = enabled;
+    }
+
+    /// Whether the next frame would differ from the last one drawn: something is animating, or a
+    /// command changed the scene since. Hosts rendering on demand stop requesting frames while false
+    pub fn needs_redraw(&self) -> bool {
+        if self.is_animating() || self.text_is_stale() {
+            return true;
+        }
+        let list = self.display_list();
+        let changed = self.drawn_list.as_ref() != Some(&list);
+        self.recycle_display_list(list);
+        changed
+    }
+
+    /// Something on screen changes without further host calls: the simulation, node animations, moving
+    /// sprites, paths, particles, tweens, a following camera, texture loads, replay, recording, the debug
+    /// overlay or host render hooks
+    fn is_animating(&self) -> bool {
+        let moving = match self.game_mode {
+            GameMode::Auto => true,
+            GameMode::Manual => {
+                self.current_direction != Direction::None
+                    || self.input_buffer.has_pending()
+                    || self.assist_target.is_some()
+                    || self.throw.is_moving()
+                    || self.gamepads.movement() != Vec2::ZERO
+                    || self.players.is_moving(&self.gamepads)
+            }
+        };
+        moving
+            || self.splash.is_showing()
+            || self.assets.pending_loads() > 0
+            || self.scene.is_animating()
+            || self.sprites.iter().any(|s| s.velocity != Vec2::ZERO)
+            || !self.routes.is_empty()
+            || self.particles.is_active()
+            || self.tweens.is_active()
+            || self.camera.is_following(Pos2::new(self.player_x, self.player_y), self.screen_size())
+            || self.playback.is_some()
+            || self.debug_overlay.enabled
+            || self.renderer.as_ref().is_some_and(|r| r.video_capture.is_some())
+            || !self.render_hooks.is_empty()
+    }
+
+    /// Draw the next frame even if the display list is unchanged
+    fn request_redraw(&mut self) {
+        if let Some(list) = self.drawn_list.take() {
+            self.recycle_display_list(list);
+        }
+        self.pacer.invalidate();
+    }
+
+    /// Draw a frame for the vsync at `vsync_ns` (host presentation timestamp, any monotonic clock)
+    /// False if the vsync was skipped: over the frame rate cap, already rendered, or the frame would
+    /// look the same as the one on screen. The framebuffer is untouched then and must not be presented
+    pub fn render_at(&mut self, vsync_ns: u64) -> bool {
+        self.pacer.begin(vsync_ns) && self.draw_frame(Some(vsync_ns))
+    }
+
+    /// Cap game_render_with_timestamp to `fps` (30, 60 or 120; 0 renders every vsync)
+    pub fn set_target_fps(&mut self, fps: u32) -> bool {
+        self.pacer.set_target_fps(fps)
+    }
+
+    pub fn pacing_stats(&self) -> PacingStats {
+        self.pacer.stats()
+    }
+
+    /// Note the device's thermal state; the hotter it runs, the fewer particles, paced frames and
+    /// post-processing passes the engine draws (see Degradation) while power scaling is on
+    pub fn set_thermal_state(&mut self, state: ThermalState) {
+        self.power.thermal = state;
+        self.apply_power();
+    }
+
+    /// Note battery saver or Low Power Mode; it degrades like a fair thermal state
+    pub fn set_low_power_mode(&mut self, enabled: bool) {
+        self.power.low_power = enabled;
+        self.apply_power();
+    }
+
+    /// Turn automatic scaling for heat and battery off (the host manages it) or back on
+    pub fn set_power_scaling(&mut self, enabled: bool) {
+        self.power.scaling = enabled;
+        self.apply_power();
+    }
+
+    pub fn power_degradation(&self) -> Degradation {
+        self.power.degradation()
+    }
+
+    fn apply_power(&mut self) {
+        let degradation = self.power.degradation();
+        log::info!(
+            "Power scaling ({:?}{}): {:?}",
+            self.power.thermal,
+            if self.power.low_power { ", low power" } else { "" },
+            degradation
+        );
+        self.pacer.set_fps_limit(degradation.fps_limit);
+        self.post_fx.suspended = !degradation.post_fx;
+        self.particles.set_budget(degradation.particles);
+        self.request_redraw();
+    }
+
+    /// Render a frame; with `vsync_ns`, skip it (returning false) if it matches the last paced frame
+    fn draw_frame(&mut self, vsync_ns: Option<u64>) -> bool {
+        self.assert_gl_thread();
+        // Skip render if dimensions are zero
+        if self.width == 0 || self.height == 0 {
+            return false;
+        }
+
+        let frame_start = Instant::now();
+        let mut profile = FrameProfile::new(frame_start);
+
+        self.upload_decoded();
+        self.reshape_stale_text();
+        self.splash.update(frame_start);
+        let list = self.display_list();
+        let culled = list.culled;
+        let unchanged = self.drawn_list.as_ref() == Some(&list);
+        if self.render_on_demand && !self.is_animating() && unchanged {
+            self.recycle_display_list(list);
+            return false;
+        }
+        // The splash animates on its own, so its frames always differ
+        let unchanged = unchanged && !list.splash;
+        profile.mark("assets");
+
+        // Keyboard input only goes to the displayed frame, never to captures
+        let mut raw_input = egui::RawInput::default();
+        self.keyboard.drain_into(&mut raw_input);
+        let mut full_output = tracing::info_span!("scene").in_scope(|| self.run_list(&list, raw_input));
+        let ui_shapes = if list.splash { 0 } else { ui::shape_count(&list.ui) };
+        if let Some(drawn) = self.drawn_list.replace(list) {
+            self.recycle_display_list(drawn);
+        }
+        self.input_metrics.frame_presented(frame_start);
+        profile.mark("scene");
+        // HUD shapes follow the scene's (foreground layer); the overlay is appended after both
+        let overlay_start = full_output.shapes.len();
+        let hud_start = overlay_start.saturating_sub(self.hud.shape_count() + ui_shapes);
+
+        // Debug overlay goes on top of the scene only, never into captures
+        if self.debug_overlay.enabled {
+            let info = DebugInfo {
+                stats: self.frame_timer.stats(),
+                events: self.event_stats(),
+                entities: self.sprites.count() + self.scene.count(),
+                glyphs: self.text.atlas_stats(),
+                palette: self.palette,
+            };
+            let screen = Rect::from_min_size(Pos2::ZERO, self.screen_size());
+            let overlay = &self.debug_overlay;
+            let shapes = self.egui_ctx.fonts(|fonts| overlay.shapes(fonts, screen, &info));
+            full_output.shapes.extend(shapes);
+        }
+
+        if let Some(vsync_ns) = vsync_ns {
+            // Texture changes, recording and host hooks need the frame drawn even if its shapes are the same
+            if !full_output.textures_delta.is_empty()
+                || self.renderer.as_ref().is_some_and(|r| r.video_capture.is_some())
+                || !self.render_hooks.is_empty()
+            {
+                self.pacer.invalidate();
+            }
+            let key = FrameKey {
+                size: [self.width, self.height],
+                clear_color: self.background.clear_color,
+                shapes: &full_output.shapes,
+            };
+            if !self.pacer.changed(vsync_ns, key) {
+                return false;
+            }
+        }
+
+        // Clear background
+        if let Some(Renderer { gl, gpu_capture, gpu_timer, .. }) = &mut self.renderer {
+            gpu_capture.begin_frame(gl);
+            if let Some(passes) = unsafe { gpu_timer.begin_frame(gl) } {
+                self.frame_timer.record_gpu_passes(passes);
+            }
+            gl_debug::group(gl, "game_engine/clear", || unsafe {
+                gpu_timer.time(gl, gpu_timer::Pass::Clear, || {
+                    let [r, g, b, a] = self.background.clear_color;
+                    gl.clear_color(r, g, b, a);
+                    gl.clear(glow::COLOR_BUFFER_BIT | glow::STENCIL_BUFFER_BIT);
+                })
+            });
+        }
+
+        let pixels_per_point = full_output.pixels_per_point;
+        let mut scene_shapes = full_output.shapes;
+        let overlay_shapes = scene_shapes.split_off(overlay_start);
+        let hud_shapes = scene_shapes.split_off(hud_start);
+        let textures = &full_output.textures_delta;
+        if let Some(Renderer { gl, painter, pictures, .. }) = self.renderer.as_mut() {
+            gl_debug::group(gl, "game_engine/upload_textures", || {
+                for (id, image_delta) in &textures.set {
+                    painter.set_texture(*id, image_delta);
+                }
+            });
+            profile.mark("upload_textures");
+            // Pictures seen for the first time (or changed) are rasterized now and drawn from the cache
+            let ctx = &self.egui_ctx;
+            scene_shapes = gl_debug::group(gl, "game_engine/pictures", || {
+                picture::composite(scene_shapes, |marker, shapes| {
+                    pictures
+                        .rasterize(gl, painter, ctx, marker, shapes, pixels_per_point)
+                        .map_err(|e| log::warn!("Picture {} drawn uncached: {}", marker.id, e))
+                        .ok()
+                })
+            });
+        } else {
+            scene_shapes = picture::flatten(scene_shapes);
+        }
+
+        // Tessellate each pass separately so GPU profiles can attribute its draws; the scene and HUD are
+        // reused from the last frame if it showed the same list with the same textures
+        let ctx = &self.egui_ctx;
+        let tessellate_span = tracing::info_span!("tessellate").entered();
+        let [scene, hud] = self.tessellation.get_or_tessellate(unchanged && textures.is_empty(), pixels_per_point, || {
+            [scene_shapes, hud_shapes].map(|shapes| ctx.tessellate(shapes, pixels_per_point))
+        });
+        let overlay = ctx.tessellate(overlay_shapes, pixels_per_point);
+        drop(tessellate_span);
+        let passes = [
+            ("game_engine/scene", &scene[..]),
+            ("game_engine/hud", &hud[..]),
+            ("game_engine/debug_overlay", &overlay[..]),
+        ];
+        profile.mark("tessellate");
+
+        let Some(renderer) = self.renderer.as_mut() else {
+            return true;
+        };
+        let Renderer { gl, painter, video_capture, gpu_timer, .. } = renderer;
+        let paint_span = tracing::info_span!("paint").entered();
+        // Scene and HUD go offscreen when effects are on, and through them onto the surface
+        let clear_color = self.background.clear_color;
+        let post_fx = unsafe { self.post_fx.begin(gl, [self.width, self.height], clear_color) };
+        let size = [self.width, self.height];
+        let mut draw_calls = 0;
+        let mut vertices = 0;
+        unsafe { self.render_hooks.run(gl, render_hook::RENDER_STAGE_BEFORE_WORLD, size) };
+        for (i, &(label, primitives)) in passes.iter().enumerate() {
+            // Recorded frames leave out the debug overlay (the last pass), like captures
+            if i == passes.len() - 1 {
+                unsafe { self.render_hooks.run(gl, render_hook::RENDER_STAGE_AFTER_HUD, size) };
+                if post_fx {
+                    gl_debug::group(gl, "game_engine/post_fx", || unsafe { self.post_fx.finish(gl) });
+                }
+                if let Some(video) = video_capture {
+                    gl_debug::group(gl, "game_engine/video_capture", || video.capture_frame(gl, [self.width, self.height]));
+                }
+            }
+            if !primitives.is_empty() {
+                let pass = if i == 0 { gpu_timer::Pass::Scene } else { gpu_timer::Pass::Ui };
+                gl_debug::group(gl, label, || unsafe {
+                    gpu_timer.time(gl, pass, || painter.paint_primitives([self.width, self.height], pixels_per_point, primitives))
+                });
+                draw_calls += primitives.len();
+                vertices += primitives.iter().map(|p| match &p.primitive {
+                    egui::epaint::Primitive::Mesh(mesh) => mesh.vertices.len(),
+                    egui::epaint::Primitive::Callback(_) => 0,
+                }).sum::<usize>();
+            }
+            if i == 0 {
+                unsafe { self.render_hooks.run(gl, render_hook::RENDER_STAGE_AFTER_WORLD, size) };
+            }
+        }
+        for &id in textures.free.iter().chain(&self.assets.take_released()) {
+            painter.free_texture(id);
+        }
+        drop(paint_span);
+        profile.mark("paint");
+        let cpu_time = frame_start.elapsed();
+
+        // Finish (validating) or fence the frame; the wait is the GPU's share of the frame
+        let finish = !renderer.quirks.contains(Quirks::SKIP_GL_FINISH);
+        let end =
+            tracing::info_span!("gpu_wait").in_scope(|| unsafe { renderer.gpu_sync.end_frame(&renderer.gl, finish) });
+        if end.error != glow::NO_ERROR {
+            log::warn!("GL error after frame: {:#x}", end.error);
+        }
+        self.debug_overlay.record_frame(draw_calls, end.error);
+        renderer.gpu_capture.end_frame(&renderer.gl);
+        profile.mark("gpu_wait");
+        self.frame_timer.record(frame_start, cpu_time, end.gpu_wait, culled);
+        let context = FrameContext {
+            queued_events: self.events.queued(),
+            pending_loads: self.assets.pending_loads(),
+            entities: self.sprites.count() + self.scene.count(),
+            draw_calls,
+            vertices,
+            texture_uploads: textures.set.len(),
+            texture_upload_bytes: textures.set.iter().map(|(_, delta)| delta.image.width() * delta.image.height() * 4).sum(),
+            texture_frees: textures.free.len(),
+        };
+        self.spikes.record(profile, context);
+        if self.splash.frame_presented() {
+            self.init_timings.first_frame_ms = stats::to_ms(self.created.elapsed());
+            self.events.push(GameEvent::FirstMeaningfulFrame);
+        }
+        true
+    }
+
+    /// Hand finished background decodes to egui; they upload with the next frame's textures
+    /// Startup loads are the engine's own and end the splash instead of notifying the host
+    fn upload_decoded(&mut self) {
+        let events = &mut self.events;
+        let splash = &mut self.splash;
+        self.assets.upload_decoded(&self.egui_ctx, |load, result| match result {
+            Ok(_) if splash.load_done(load) => {}
+            Err(e) if splash.load_done(load) => log::error!("Failed to load startup texture: {}", e),
+            Ok(size) => events.push(GameEvent::TextureReady { load, width: size.x, height: size.y }),
+            Err(e) => {
+                log::error!("Async texture load {} failed: {}", load, e);
+                events.push(GameEvent::TextureFailed { load });
+            }
+        });
+    }
+
+    /// Do the first frame's one-time GPU work now, so the first game_render doesn't hitch:
+    /// waits (briefly) for the startup textures, then draws the scene once, which uploads the font atlas
+    /// and player texture, makes the driver compile egui's shaders and primes the tessellator,
+    /// and clears the result away. Call on the render thread right after init
+    /// Returns false without a renderer
+    pub fn warmup(&mut self) -> bool {
+        self.assert_gl_thread();
+        if self.renderer.is_none() {
+            return false;
+        }
+        let start = Instant::now();
+        while self.splash.is_showing() && start.elapsed() < WARMUP_LOAD_TIMEOUT {
+            self.upload_decoded();
+            if self.splash.is_showing() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        self.reshape_stale_text();
+        let output = self.run_scene(egui::RawInput::default());
+        let primitives = self.egui_ctx.tessellate(output.shapes, output.pixels_per_point);
+        let Some(renderer) = self.renderer.as_mut() else {
+            return false;
+        };
+        let Renderer { gl, painter, .. } = renderer;
+        let [r, g, b, a] = self.background.clear_color;
+        gl_debug::group(gl, "game_engine/warmup", || {
+            for (id, image_delta) in &output.textures_delta.set {
+                painter.set_texture(*id, image_delta);
+            }
+            if self.width > 0 && self.height > 0 {
+                painter.paint_primitives([self.width, self.height], output.pixels_per_point, &primitives);
+            }
+            for &id in &output.textures_delta.free {
+                painter.free_texture(id);
+            }
+            unsafe {
+                gl.clear_color(r, g, b, a);
+                gl.clear(glow::COLOR_BUFFER_BIT | glow::STENCIL_BUFFER_BIT);
+                gl.finish();
+            }
+        });
+
+        self.init_timings.warmup_ms = stats::to_ms(start.elapsed());
+        log::info!("Warm-up took {:.1} ms", self.init_timings.warmup_ms);
+        true
+    }
+
+    /// Hand every rendered frame (without the debug overlay) to `callback` on the render thread
+    /// Frames arrive a few renders late through a ring of `ring_size` GPU buffers (see video_capture module)
+    /// `user_data` must stay valid until stop_video_capture or the engine is dropped
+    /// Replaces a running recording; false without a renderer or on GL older than ES 3
+    pub fn start_video_capture(&mut self, callback: FrameCallback, user_data: *mut c_void, ring_size: usize) -> bool {
+        self.assert_gl_thread();
+        self.stop_video_capture();
+        let Some(renderer) = self.renderer.as_mut() else {
+            return false;
+        };
+        if !VideoCapture::is_supported(&renderer.gl) {
+            log::warn!("Video capture needs GL ES 3");
+            return false;
+        }
+        renderer.video_capture = Some(VideoCapture::new(callback, user_data, ring_size));
+        true
+    }
+
+    /// Stop recording, delivering frames still in flight first
+    pub fn stop_video_capture(&mut self) {
+        self.assert_gl_thread();
+        if let Some(renderer) = self.renderer.as_mut() {
+            if let Some(mut video) = renderer.video_capture.take() {
+                video.finish(&renderer.gl);
+            }
+        }
+    }
+
+    /// Call `hook` at `stage` (RENDER_STAGE_*) of every presented frame so the host can draw into it with
+    /// its own GL calls; the engine's GL state is saved before and restored after. None removes it
+    /// While any hook is installed every render draws (the engine can't tell when the host's drawing changes)
+    /// `user_data` must stay valid until the hook is replaced or the engine is dropped
+    /// False for an unknown stage
+    pub fn set_custom_render_hook(&mut self, stage: u32, hook: Option<RenderHook>, user_data: *mut c_void) -> bool {
+        if !self.render_hooks.set(stage, hook, user_data) {
+            log::warn!("Unknown render hook stage {}", stage);
+            return false;
+        }
+        self.request_redraw();
+        true
+    }
+
+    /// Run `task` on a thread of the engine's own: scheduled per set_thread_hints, and cancelled and
+    /// joined (for at most TASK_SHUTDOWN_TIMEOUT) when the engine is dropped
+    pub fn spawn_task<F>(&mut self, name: &'static str, task: F) -> std::io::Result<()>
+    where
+        F: FnOnce(CancellationToken) + Send + 'static,
+    {
+        self.tasks.spawn(name, task)
+    }
+
+    /// Scheduling hints for the engine's own threads (see TaskGroup::set_hints)
+    pub fn set_thread_hints(&mut self, hints: ThreadHints) -> bool {
+        log::info!("Thread hints: affinity {:#x}, priority {}", hints.affinity_mask, hints.priority);
+        self.tasks.set_hints(hints)
+    }
+
+    /// Driver workarounds in effect (empty without a renderer)
+    pub fn quirks(&self) -> Quirks {
+        self.renderer.as_ref().map_or(Quirks::empty(), |renderer| renderer.quirks)
+    }
+
+    /// Override the detected workarounds; false without a renderer
+    /// FORCE_GLES2 only takes effect when the renderer is created
+    pub fn set_quirks(&mut self, quirks: Quirks) -> bool {
+        let Some(renderer) = self.renderer.as_mut() else {
+            return false;
+        };
+        renderer.quirks = quirks;
+        true
+    }
+
+    /// End every frame with glFinish and glGetError (on by default in debug builds) for exact GPU
+    /// times and errors caught on the frame that made them, or fence frames and check errors now
+    /// and then, which keeps the GPU pipeline full. False without a renderer
+    pub fn set_gpu_validation(&mut self, enabled: bool) -> bool {
+        self.assert_gl_thread();
+        let Some(renderer) = self.renderer.as_mut() else {
+            return false;
+        };
+        renderer.gpu_sync.validation = enabled;
+        true
+    }
+
+    /// Wrap the next rendered frame in a debug group and, under RenderDoc, capture it
+    /// Returns None without a renderer, otherwise whether RenderDoc will capture the frame
+    pub fn trigger_gpu_capture(&mut self) -> Option<bool> {
+        self.renderer.as_mut().map(|renderer| renderer.gpu_capture.request())
+    }
+
+    /// Show or hide the debug overlay; false if this build has no overlay
+    pub fn set_debug_overlay(&mut self, enabled: bool) -> bool {
+        if enabled && !debug_overlay::AVAILABLE {
+            return false;
+        }
+        self.debug_overlay.enabled = enabled;
+        self.request_redraw();
+        true
+    }
+
+    /// Set the effects presented frames go through; false (and unchanged) if a value is out of range
+    pub fn set_post_fx(&mut self, settings: PostFxSettings) -> bool {
+        if let Err(e) = settings.validate() {
+            log::warn!("Post-processing settings rejected: {}", e);
+            return false;
+        }
+        self.post_fx.settings = settings;
+        self.request_redraw();
+        true
+    }
+
+    pub fn post_fx(&self) -> PostFxSettings {
+        self.post_fx.settings
+    }
+
+    /// Drop fine detail from entities drawn small and from wide views (see LodSettings)
+    pub fn set_lod(&mut self, settings: LodSettings) -> bool {
+        if let Err(e) = settings.validate() {
+            log::warn!("LOD settings rejected: {}", e);
+            return false;
+        }
+        self.lod = settings;
+        true
+    }
+
+    pub fn lod(&self) -> LodSettings {
+        self.lod
+    }
+
+    /// Draw runs of sprites and image nodes as one instanced draw each (the default), or each as its
+    /// own egui mesh, e.g. to rule the batcher out when something draws wrong
+    pub fn set_sprite_batching(&mut self, enabled: bool) {
+        self.sprite_batching = enabled;
+    }
+
+    /// Grade colors through a LUT PNG (see ColorLut), or remove it with None
+    /// False (and the old LUT kept) if the image can't be decoded or has the wrong shape
+    pub fn set_color_lut(&mut self, png: Option<&[u8]>) -> bool {
+        let lut = match png.map(ColorLut::decode).transpose() {
+            Ok(lut) => lut,
+            Err(e) => {
+                log::warn!("Color LUT rejected: {}", e);
+                return false;
+            }
+        };
+        self.post_fx.set_lut(lut);
+        self.request_redraw();
+        true
+    }
+
+    /// Frame timing over the last ~120 frames
+    pub fn frame_stats(&self) -> FrameStats {
+        self.frame_timer.stats()
+    }
+
+    /// Log frames slower than `threshold` (zero disables), at most one entry per `log_interval`
+    pub fn set_spike_threshold(&mut self, threshold: Duration, log_interval: Duration) {
+        self.spikes.configure(threshold, log_interval);
+    }
+
+    /// Recent frames with their phases and spikes, as Chrome trace JSON (see spikes module)
+    pub fn export_timeline(&self) -> String {
+        self.spikes.timeline_json()
+    }
+
+    /// Startup cost breakdown (renderer, engine, warm-up, first scene frame)
+    pub fn init_timings(&self) -> InitTimings {
+        self.init_timings
+    }
+
+    /// Delivery counters for outbound events and host input
+    pub fn event_stats(&self) -> EventStats {
+        let mut input = self.input_metrics.stats();
+        input.dropped += self.keyboard.dropped();
+        EventStats { outbound: self.events.stats(), input }
+    }
+
+    /// Re-render the scene cropped to `region` (points) at `scale` output pixels per point
+    pub fn capture_region(&mut self, region: Rect, scale: f32) -> Result<Capture, String> {
+        self.assert_gl_thread();
+        self.reshape_stale_text();
+        let full_output = self.run_scene(egui::RawInput::default());
+        let renderer = self.renderer.as_mut().ok_or("No renderer")?;
+        capture::render_region(
+            &renderer.gl,
+            &mut renderer.painter,
+            &self.egui_ctx,
+            full_output,
+            region,
+            scale,
+            self.background.clear_color,
+        )
+    }
+
+    /// Compose a share card for `template_id` (SHARE_TEMPLATE_*) as PNG bytes
+    pub fn render_share_card(&mut self, template_id: u32) -> Result<Vec<u8>, String> {
+        self.assert_gl_thread();
+        let layout =
+            share::Layout::for_template(template_id).ok_or_else(|| format!("unknown template {}", template_id))?;
+        if self.width == 0 || self.height == 0 {
+            return Err("surface has no size yet".to_string());
+        }
+
+        let screen = self.screen_size();
+        let player = self.camera.transform(screen, self.interpolation_alpha).apply(Pos2::new(self.player_x, self.player_y));
+        let crop = share::crop_around(player, screen, layout.shot.size());
+        // The player sprite doubles as the logo mark unless the host loaded a "logo" texture
+        let logo = self
+            .assets
+            .get(*assets::LOGO_TEXTURE_ID)
+            .or_else(|| self.assets.get(*assets::PLAYER_TEXTURE_ID))
+            .map(|t| (t.id, t.size));
+
+        self.reshape_stale_text();
+        let card = share::compose(&layout, self.run_scene(egui::RawInput::default()), crop, self.score, logo);
+        let renderer = self.renderer.as_mut().ok_or("No renderer")?;
+        capture::render_region(
+            &renderer.gl,
+            &mut renderer.painter,
+            &self.egui_ctx,
+            card,
+            layout.card_rect(),
+            1.0,
+            share::BACKGROUND,
+        )
+        .and_then(|capture| capture.encode_png())
+    }
+
+    /// Run host input through the recorder; ignored while a replay is playing
+    /// Returns whether the command was accepted
+    fn command(&mut self, command: GameCommand) -> bool {
+        if self.playback.is_some() {
+            return false;
+        }
+        let accepted = self.apply(command);
+        if accepted {
+            if let Some(recorder) = &mut self.recorder {
+                recorder.push(self.tick_count, command);
+            }
+        }
+        accepted
+    }
+
+    /// Apply input to the simulation (live, or from a replay)
+    fn apply(&mut self, command: GameCommand) -> bool {
+        match command {
+            GameCommand::SetDirection(direction) => {
+                self.current_direction = direction;
+                self.input_buffer.push_direction(direction);
+            }
+            GameCommand::SetMode(mode) => {
+                // Initialize velocity when switching to auto mode
+                if mode == GameMode::Auto && self.game_mode != GameMode::Auto {
+                    let (vx, vy) = self
+                        .level
+                        .as_ref()
+                        .map_or((self.params.get(Param::AutoVelocityX), self.params.get(Param::AutoVelocityY)), |l| {
+                            (l.auto_velocity.x, l.auto_velocity.y)
+                        });
+                    self.velocity_x = vx;
+                    self.velocity_y = vy;
+                }
+
+                self.game_mode = mode;
+                self.assist_target = None;
+                log::info!("Game mode set to {:?}", mode);
+            }
+            GameCommand::Touch { x, y, action } => self.apply_touch(x, y, action),
+            GameCommand::Throw { vx, vy } => {
+                self.throw.start(Vec2::new(vx, vy), self.params.get(Param::Friction));
+                if self.throw.is_moving() {
+                    self.scoreboard.throw();
+                }
+            }
+            GameCommand::Gamepad { pad, control, value } => return self.gamepads.event(pad, control, value),
+            GameCommand::GamepadDisconnected { pad } => self.gamepads.disconnect(pad),
+            GameCommand::Sensor { sensor, sample, timestamp } => self.sensors.event(sensor, sample, timestamp),
+            GameCommand::StartLevel { seed } => self.start_level(seed),
+        }
+        true
+    }
+
+    /// Handle direction input (held direction plus a buffered tap)
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.input_metrics.received(Instant::now());
+        self.command(GameCommand::SetDirection(direction));
+    }
+
+    pub fn set_mode(&mut self, mode: GameMode) {
+        self.command(GameCommand::SetMode(mode));
+    }
+
+    /// Set the color frames are cleared to (components clamped to 0.0..=1.0)
+    pub fn set_clear_color(&mut self, rgba: [f32; 4]) {
+        self.background.clear_color = rgba.map(|c| c.clamp(0.0, 1.0));
+    }
+
+    /// Cover the clear color with a vertical gradient (top, bottom), or remove it
+    pub fn set_background_gradient(&mut self, gradient: Option<(Color32, Color32)>) {
+        self.background.gradient = gradient;
+    }
+
+    /// Set the simulation tick rate in Hz (0 = variable timestep, capped at 1000)
+    /// Ignored while a replay is playing
+    pub fn set_tick_rate(&mut self, hz: u32) {
+        if self.playback.is_some() {
+            log::warn!("Tick rate is fixed while a replay is playing");
+            return;
+        }
+        self.tick_rate = hz.min(1000);
+        self.accumulator = 0.0;
+        self.snap_interpolation();
+        log::info!("Tick rate set to {} Hz", self.tick_rate);
+    }
+
+    /// Pop the next outbound event
+    pub fn poll_event(&mut self) -> Option<GameEvent> {
+        self.events.pop()
+    }
+
+    /// Receive every event from now on through poll_subscribed_event, as well as poll_event
+    pub fn subscribe_events(&mut self) -> SubscriptionId {
+        self.events.subscribe()
+    }
+
+    pub fn unsubscribe_events(&mut self, id: SubscriptionId) {
+        self.events.unsubscribe(id);
+    }
+
+    pub fn poll_subscribed_event(&mut self, id: SubscriptionId) -> Option<GameEvent> {
+        self.events.pop_subscribed(id)
+    }
+
+    /// Record a panic that escaped a call and tell the host with EVENT_ENGINE_CRASHED
+    /// Game state may be half-updated, so the FFI layer refuses further calls from here on
+    pub fn poison(&mut self, crash: Crash) {
+        if self.crash.is_some() {
+            return;
+        }
+        self.events.push(GameEvent::EngineCrashed { message: crash.message.clone(), backtrace: crash.backtrace.clone() });
+        self.crash = Some(crash);
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.crash.is_some()
+    }
+
+    /// The panic that poisoned this instance
+    pub fn last_crash(&self) -> Option<&Crash> {
+        self.crash.as_ref()
+    }
+
+    /// Count a call refused because the instance is poisoned; logs the first and then every power of two
+    pub fn refuse_call(&mut self) {
+        self.refused_calls += 1;
+        if self.refused_calls.is_power_of_two() {
+            log::warn!("Engine poisoned by an earlier panic, {} calls refused so far", self.refused_calls);
+        }
+    }
+
+    /// Mirrored state changed since the last call, in the state_diff encoding
+    /// Empty when nothing changed
+    pub fn poll_state_diff(&mut self) -> Vec<u8> {
+        let snapshot = Snapshot {
+            player: Pos2::new(self.player_x, self.player_y),
+            tint: self.player_tint,
+            mode: self.game_mode as u8,
+            dragging: self.is_player_touched,
+            score: self.score,
+            level_seed: self.level.as_ref().map_or(0, |l| l.seed),
+            sprites: self
+                .sprites
+                .entries()
+                .map(|(id, sprite)| {
+                    let state = SpriteState { position: sprite.position, size: sprite.size, color: sprite.color };
+                    (id, state)
+                })
+                .collect(),
+        };
+        self.state_differ.diff(snapshot)
+    }
+
+    /// Make the next state diff carry every field
+    pub fn reset_state_diff(&mut self) {
+        self.state_differ.reset();
+    }
+
+    /// Snapshot of the game for persisting across process death (save module encoding)
+    pub fn save_state(&self) -> Vec<u8> {
+        SavedState {
+            mode: self.game_mode as u8,
+            player: Pos2::new(self.player_x, self.player_y),
+            velocity: Vec2::new(self.velocity_x, self.velocity_y),
+            tint: self.player_tint,
+            score: self.score,
+            level_seed: self.level.as_ref().map_or(0, |l| l.seed),
+            rng_state: self.rng.state(),
+            run: self.policy.saved_run(),
+            tag_names: self.tags.names().to_vec(),
+            sprites: self.sprites.entries().map(|(id, sprite)| self.saved_sprite(id, sprite)).collect(),
+        }
+        .encode()
+    }
+
+    /// A sprite as saved: prefab instances keep only what differs from their prefab
+    fn saved_sprite(&self, id: SpriteId, sprite: &Sprite) -> SavedSprite {
+        let prefab = sprite.prefab.as_deref().and_then(|name| self.prefabs.get(name));
+        let overrides = match prefab {
+            Some((_, prefab)) => {
+                let mut overrides = 0;
+                if sprite.size != prefab.size {
+                    overrides |= save::OVERRIDE_SIZE;
+                }
+                if sprite.color != prefab.color {
+                    overrides |= save::OVERRIDE_COLOR;
+                }
+                if sprite.tags != self.tags.find(&prefab.tags) {
+                    overrides |= save::OVERRIDE_TAGS;
+                }
+                overrides
+            }
+            None => save::OVERRIDE_ALL,
+        };
+        SavedSprite { id, sprite: sprite.clone(), overrides }
+    }
+
+    /// Resume from save_state bytes; on error nothing changes
+    /// Sprites keep their ids; the run is flagged as restored in the run summary
+    /// Prefab instances take what they didn't save from their prefab, which must be loaded, and get
+    /// its shader and collider back; other sprite colliders are removed
+    pub fn restore_state(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let saved = SavedState::decode(bytes)?;
+        if saved.mode > GameMode::Auto as u8 {
+            return Err(format!("Unknown game mode {}", saved.mode));
+        }
+        let mut tags = TagRegistry::from_names(saved.tag_names);
+        let mut instances = Vec::new();
+        let mut entries = Vec::with_capacity(saved.sprites.len());
+        for SavedSprite { id, mut sprite, overrides } in saved.sprites {
+            if let Some(name) = sprite.prefab.as_deref() {
+                let (_, prefab) = self.prefabs.get(name).ok_or_else(|| format!("Save uses prefab '{}', which isn't loaded", name))?;
+                if overrides & save::OVERRIDE_SIZE == 0 {
+                    sprite.size = prefab.size;
+                }
+                if overrides & save::OVERRIDE_COLOR == 0 {
+                    sprite.color = prefab.color;
+                }
+                if overrides & save::OVERRIDE_TAGS == 0 {
+                    sprite.tags = prefab.tags.iter().filter_map(|tag| tags.mask(tag)).fold(0, |mask, bit| mask | bit);
+                }
+                instances.push((id, prefab.clone()));
+            }
+            entries.push((id, sprite));
+        }
+        let sprites = SpriteArena::from_entries(entries).ok_or("Saved sprite ids are invalid")?;
+
+        self.level = (saved.level_seed != 0).then(|| procgen::generate(saved.level_seed));
+        self.rng = Rng::new(saved.rng_state);
+        self.policy.resume_run(saved.run);
+        self.game_mode = GameMode::from(saved.mode as i32);
+        self.player_x = saved.player.x;
+        self.player_y = saved.player.y;
+        self.velocity_x = saved.velocity.x;
+        self.velocity_y = saved.velocity.y;
+        self.player_tint = self.palette.constrain(saved.tint);
+        self.score = saved.score;
+        self.tags = tags;
+        self.sprites = sprites;
+        self.sprite_index.clear();
+        self.index_sprites();
+        self.routes.clear();
+        let mut contacts = Vec::new();
+        self.colliders.retain(|owner| !matches!(owner, collision::Owner::Sprite(_)), &mut contacts);
+        self.report_contacts(&mut contacts);
+        for (id, prefab) in instances {
+            self.attach_prefab(id, &prefab);
+        }
+
+        // Input in flight belongs to the old session, as does a recording or replay
+        self.current_direction = Direction::None;
+        self.is_player_touched = false;
+        self.assist_target = None;
+        self.input_buffer.clear();
+        self.recorder = None;
+        self.playback = None;
+        self.accumulator = 0.0;
+        self.snap_interpolation();
+        self.state_differ.reset();
+        log::info!("Restored state: mode={:?} score={} sprites={}", self.game_mode, self.score, self.sprites.count());
+        Ok(())
+    }
+
+    /// Start logging host input for a replay; false in variable timestep or during a replay
+    /// Controller and tilt state held when recording starts is not captured
+    pub fn start_recording(&mut self) -> bool {
+        if self.tick_rate == 0 || self.playback.is_some() {
+            log::warn!("Recording needs a fixed tick rate and no replay playing");
+            return false;
+        }
+        let snapshot = self.save_state();
+        self.recorder = Some(Recorder::new(self.tick_count, self.tick_rate, self.screen_size(), snapshot));
+        true
+    }
+
+    /// Finish the recording and encode it (replay module), or None if none was running
+    pub fn stop_recording(&mut self) -> Option<Vec<u8>> {
+        let recording = self.recorder.take()?.finish(self.tick_count);
+        log::info!("Recorded {} commands over {} ticks", recording.commands.len(), recording.ticks);
+        Some(recording.encode())
+    }
+
+    /// Restore a recording's starting state and replay its input at its tick rate
+    /// Host input is ignored until GameEvent::ReplayFinished (or stop_replay)
+    pub fn start_replay(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let recording = Recording::decode(bytes)?;
+        if recording.tick_rate == 0 {
+            return Err("Replay has no tick rate".to_string());
+        }
+        self.restore_state(&recording.snapshot)?;
+        self.set_tick_rate(recording.tick_rate);
+
+        let screen = self.screen_size();
+        if recording.screen != screen {
+            log::warn!(
+                "Replay recorded on a {}x{} screen, playing on {}x{}; it may diverge",
+                recording.screen.x,
+                recording.screen.y,
+                screen.x,
+                screen.y
+            );
+        }
+        for pad in 0..MAX_PADS as u32 {
+            self.gamepads.disconnect(pad);
+        }
+        self.sensors = Sensors::default();
+
+        log::info!("Replaying {} commands over {} ticks", recording.commands.len(), recording.ticks);
+        self.playback = Some(Playback::new(self.tick_count, recording));
+        Ok(())
+    }
+
+    /// Run the rest of the replay now, without waiting for wall-clock time (regression tests)
+    pub fn finish_replay(&mut self) {
+        if self.playback.is_none() {
+            return;
+        }
+        let dt = Duration::from_secs_f64(1.0 / self.tick_rate as f64);
+        let mut now = Instant::now();
+        while self.playback.is_some() {
+            self.store_previous_positions();
+            self.tick(dt.as_secs_f32(), now);
+            now += dt;
+        }
+        self.snap_interpolation();
+        self.last_frame_time = Instant::now();
+    }
+
+    /// Abandon the replay; the game continues from where it stopped
+    pub fn stop_replay(&mut self) {
+        if self.playback.take().is_some() {
+            log::info!("Replay stopped");
+        }
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// Configure the Auto mode physics world
+    /// Returns false if physics was not negotiated or is not in this build
+    pub fn set_physics(&mut self, gravity: Vec2, restitution: f32, damping: f32) -> bool {
+        if self.level.is_some() {
+            self.policy.allow(Mutation::Tuning);
+        }
+
+        if !self.features.contains(Features::PHYSICS) {
+            return false;
+        }
+
+        #[cfg(feature = "physics")]
+        {
+            self.physics.gravity = gravity;
+            // The world bounces with the tuned restitution, shared with the hand-rolled Auto mode
+            self.params.set(Param::Restitution, restitution.clamp(0.0, 1.0));
+            self.physics.linear_damping = damping.max(0.0);
+            log::info!(
+                "Physics set: gravity=({}, {}) restitution={} damping={}",
+                gravity.x,
+                gravity.y,
+                restitution,
+                damping
+            );
+        }
+
+        #[cfg(not(feature = "physics"))]
+        let _ = (gravity, restitution, damping);
+
+        true
+    }
+
+    /// Set the input buffer window in milliseconds (0 disables buffering)
+    pub fn set_input_buffer_ms(&mut self, ms: u32) {
+        self.input_buffer.set_window_ms(ms);
+    }
+
+    /// Start the daily challenge for a date (e.g. 20261016 for yyyymmdd)
+    pub fn start_daily(&mut self, date_seed: u32) {
+        self.command(GameCommand::StartLevel { seed: challenge::daily_level_seed(date_seed) });
+    }
+
+    /// Challenge code of the active level
+    pub fn challenge_code(&self) -> Option<String> {
+        self.level.as_ref().map(|level| challenge::encode(level.seed))
+    }
+
+    /// Start the level described by a challenge code
+    /// False if the code is malformed or a replay is playing
+    pub fn import_challenge_code(&mut self, code: &str) -> bool {
+        match challenge::decode(code) {
+            Some(seed) => self.command(GameCommand::StartLevel { seed }),
+            None => {
+                log::warn!("Invalid challenge code: {}", code);
+                false
+            }
+        }
+    }
+
+    /// Spawn a sprite centered at `position`
+    pub fn spawn_sprite(&mut self, position: Pos2, size: Vec2, color: Color32) -> SpriteId {
+        let id = self.sprites.insert(Sprite {
+            position,
+            prev_position: position,
+            size,
+            velocity: Vec2::ZERO,
+            color,
+            tags: 0,
+            material: None,
+            prefab: None,
+        });
+        self.sprite_index.update(id, Rect::from_center_size(position, size));
+        id
+    }
+
+    /// Add prefab definitions from JSON (see prefab module), replacing ones of the same name
+    /// Returns how many were loaded; on error none are
+    pub fn load_prefabs(&mut self, json: &str) -> Result<usize, String> {
+        let count = self.prefabs.load(json)?;
+        log::info!("Loaded {} prefabs ({} defined)", count, self.prefabs.count());
+        Ok(count)
+    }
+
+    /// Spawn an instance of prefab `name` centered at `position`; INVALID_SPRITE if there's no such prefab
+    pub fn spawn_prefab(&mut self, name: &str, position: Pos2) -> SpriteId {
+        let Some((name, prefab)) = self.prefabs.get(name) else {
+            log::warn!("Unknown prefab '{}'", name);
+            return INVALID_SPRITE;
+        };
+        let (name, prefab) = (name.clone(), prefab.clone());
+        let tags = prefab.tags.iter().filter_map(|tag| self.tags.mask(tag)).fold(0, |mask, bit| mask | bit);
+        let id = self.sprites.insert(Sprite {
+            position,
+            prev_position: position,
+            size: prefab.size,
+            velocity: prefab.velocity,
+            color: prefab.color,
+            tags,
+            material: None,
+            prefab: Some(name),
+        });
+        self.sprite_index.update(id, Rect::from_center_size(position, prefab.size));
+        self.attach_prefab(id, &prefab);
+        id
+    }
+
+    /// Give a prefab instance its prefab's shader (if registered) and collider
+    fn attach_prefab(&mut self, id: SpriteId, prefab: &Prefab) {
+        if let Some(shader) = prefab.shader.as_deref() {
+            if !self.set_sprite_material(id, Some(shader)) {
+                log::warn!("Prefab shader '{}' isn't registered", shader);
+            }
+        }
+        if let Some((shape, offset)) = prefab.collider {
+            self.add_collider(collision::Owner::Sprite(id), shape, offset);
+        }
+    }
+
+    /// Move a sprite without interpolating from its old position
+    pub fn set_sprite_position(&mut self, id: SpriteId, position: Pos2) -> bool {
+        if !self.allow_write(tween::Target::SpritePosition(id)) {
+            return false;
+        }
+        self.end_route(id);
+        match self.sprites.get_mut(id) {
+            Some(sprite) => {
+                sprite.position = position;
+                sprite.prev_position = position;
+                self.sprite_index.update(id, sprite.swept_rect());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_sprite_velocity(&mut self, id: SpriteId, velocity: Vec2) -> bool {
+        self.end_route(id);
+        match self.sprites.get_mut(id) {
+            Some(sprite) => {
+                sprite.velocity = velocity;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove_sprite(&mut self, id: SpriteId) -> bool {
+        self.end_route(id);
+        self.sprite_index.remove(id);
+        self.sprites.remove(id).is_some()
+    }
+
+    pub fn sprite_count(&self) -> usize {
+        self.sprites.count()
+    }
+
+    /// Send a sprite to `target` around level obstacles and solid tiles, at its current speed (or
+    /// PATH_SPEED if it's still). It ends with EVENT_PATH_FINISHED; false if the sprite is unknown or
+    /// there's no way there inside the world for something its size
+    pub fn move_entity_to(&mut self, id: SpriteId, target: Pos2) -> bool {
+        let Some(sprite) = self.sprites.get(id) else {
+            return false;
+        };
+        let (start, size, speed) = (sprite.position, sprite.size, sprite.velocity.length());
+        let bounds = Rect::from_min_size(Pos2::ZERO, self.world_size());
+        let mut grid = NavGrid::new(bounds, NAV_CELL);
+        // Obstacles grown by the sprite's half size keep its edges clear, not just its center
+        let clearance = size / 2.0;
+        let Vec2 { x: width, y: height } = bounds.size();
+        for obstacle in self.level.iter().flat_map(|level| &level.obstacles) {
+            grid.block(obstacle.screen_rect(width, height).expand2(clearance));
+        }
+        let mut solid = Vec::new();
+        for map in self.tilemaps.iter() {
+            map.solid_rects(bounds, &mut solid);
+        }
+        for rect in solid {
+            grid.block(rect.expand2(clearance));
+        }
+        let Some(waypoints) = grid.find_path(start, target) else {
+            log::debug!("No path for sprite {} from {:?} to {:?}", id, start, target);
+            return false;
+        };
+        self.end_route(id);
+        let speed = if speed > 0.0 { speed } else { PATH_SPEED };
+        self.routes.insert(id, Route::new(waypoints, speed));
+        true
+    }
+
+    /// Stop a sprite following its path, reported as interrupted
+    fn end_route(&mut self, id: SpriteId) {
+        if self.routes.remove(&id).is_some() {
+            let position = self.sprites.get(id).map_or(Pos2::ZERO, |sprite| sprite.position);
+            self.events.push(GameEvent::PathFinished { sprite: id, x: position.x, y: position.y, arrived: false });
+        }
+    }
+
+    /// Point path-following sprites at their next waypoint; those at the end stop
+    fn steer_routes(&mut self, delta: f32) {
+        let (sprites, events) = (&mut self.sprites, &mut self.events);
+        self.routes.retain(|&id, route| {
+            let Some(sprite) = sprites.get_mut(id) else {
+                events.push(GameEvent::PathFinished { sprite: id, x: 0.0, y: 0.0, arrived: false });
+                return false;
+            };
+            match route.steer(sprite.position, delta) {
+                Some(velocity) => {
+                    sprite.velocity = velocity;
+                    true
+                }
+                None => {
+                    sprite.velocity = Vec2::ZERO;
+                    let Pos2 { x, y } = sprite.position;
+                    events.push(GameEvent::PathFinished { sprite: id, x, y, arrived: true });
+                    false
+                }
+            }
+        });
+    }
+
+    /// Start a particle emitter at `position` (see particles::EmitterConfig)
+    /// INVALID_EMITTER if the config is invalid or there are too many emitters
+    pub fn spawn_emitter(&mut self, position: Pos2, config: EmitterConfig) -> EmitterId {
+        self.particles.spawn(position, config).unwrap_or_else(|e| {
+            log::warn!("Emitter rejected: {}", e);
+            INVALID_EMITTER
+        })
+    }
+
+    pub fn move_emitter(&mut self, id: EmitterId, position: Pos2) -> bool {
+        self.particles.move_emitter(id, position)
+    }
+
+    /// Stop an emitter; the particles it threw fade out over their lifetime
+    pub fn stop_emitter(&mut self, id: EmitterId) -> bool {
+        self.particles.stop(id)
+    }
+
+    /// Throw `config`'s burst away from the surface each time the player bounces (None turns it off)
+    /// False (and the old setting kept) if the config is invalid
+    pub fn set_bounce_particles(&mut self, config: Option<EmitterConfig>) -> bool {
+        self.particles.set_bounce(config).map_err(|e| log::warn!("Bounce particles rejected: {}", e)).is_ok()
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.particle_count()
+    }
+
+    /// Ease `target` from its current value to `to` over `duration` seconds on the game tick
+    /// A tween already driving the target ends (reported as interrupted); its entity going away ends
+    /// this one the same way. Direct sets of the property are overridden until it finishes
+    /// INVALID_TWEEN for unknown entities, a `to` of the wrong kind, a bad duration or a player node
+    /// while dev mode is off
+    pub fn animate(&mut self, target: tween::Target, to: tween::Value, duration: f32, easing: Easing) -> TweenId {
+        if !self.allow_write(target) {
+            return INVALID_TWEEN;
+        }
+        let Some(from) = target.get(&self.sprites, &mut self.scene) else {
+            log::warn!("Tween target {:?} not found", target);
+            return INVALID_TWEEN;
+        };
+        if std::mem::discriminant(&from) != std::mem::discriminant(&to) {
+            log::warn!("Tween of {:?} to {:?} rejected", target, to);
+            return INVALID_TWEEN;
+        }
+        let mut ended = Vec::new();
+        let id = self.tweens.start(target, from, to, duration, easing, &mut ended).unwrap_or_else(|e| {
+            log::warn!("Tween rejected: {}", e);
+            INVALID_TWEEN
+        });
+        self.report_tweens(&mut ended);
+        id
+    }
+
+    /// Stop a tween where it is (reported as interrupted); false if it already finished
+    pub fn cancel_tween(&mut self, id: TweenId) -> bool {
+        let mut ended = Vec::new();
+        let cancelled = self.tweens.cancel(id, &mut ended);
+        self.report_tweens(&mut ended);
+        cancelled
+    }
+
+    pub fn tween_count(&self) -> usize {
+        self.tweens.count()
+    }
+
+    fn report_tweens(&mut self, ended: &mut Vec<tween::Finished>) {
+        for end in ended.drain(..) {
+            self.events.push(GameEvent::TweenFinished { tween: end.tween, completed: end.completed });
+        }
+    }
+
+    /// Attach a collider to a sprite or node; its overlaps with other colliders are reported as
+    /// CollisionEnter and CollisionExit events from the next tick on
+    /// INVALID_COLLIDER for unknown entities, bad shapes or offsets, or past MAX_COLLIDERS
+    pub fn add_collider(&mut self, owner: collision::Owner, shape: collision::Shape, offset: Vec2) -> ColliderId {
+        let exists = match owner {
+            collision::Owner::Sprite(id) => self.sprites.contains(id),
+            collision::Owner::Node(id) => self.scene.get_mut(id).is_some(),
+        };
+        if !exists {
+            log::warn!("Collider owner {:?} not found", owner);
+            return INVALID_COLLIDER;
+        }
+        self.colliders.add(owner, shape, offset).unwrap_or_else(|e| {
+            log::warn!("Collider rejected: {}", e);
+            INVALID_COLLIDER
+        })
+    }
+
+    /// Remove a collider, reporting CollisionExit for what it was touching; false if it's unknown
+    /// Colliders also go away with their entity
+    pub fn remove_collider(&mut self, id: ColliderId) -> bool {
+        let mut contacts = Vec::new();
+        let removed = self.colliders.remove(id, &mut contacts);
+        self.report_contacts(&mut contacts);
+        removed
+    }
+
+    /// Whether two colliders overlapped at the end of the last tick
+    pub fn colliders_touching(&self, a: ColliderId, b: ColliderId) -> bool {
+        self.colliders.touching(a, b)
+    }
+
+    pub fn collider_count(&self) -> usize {
+        self.colliders.count()
+    }
+
+    /// The topmost sprite or node whose collider covers screen point `screen` (points), with colliders
+    /// where the last tick left them: nodes are drawn above sprites, nodes by z, sprites in spawn order
+    pub fn hit_test(&self, screen: Pos2) -> Option<collision::Owner> {
+        let mut hits = Vec::new();
+        self.colliders.owners_at(self.screen_to_world(screen), &mut hits);
+        if hits.is_empty() {
+            return None;
+        }
+        let (mut stack, mut items) = (Vec::new(), Vec::new());
+        let positions = self.players.positions((self.scene.player(), Pos2::new(self.player_x, self.player_y)));
+        self.scene.collect(positions, &mut stack, &mut items);
+        let node = items.iter().rev().map(|item| collision::Owner::Node(item.id)).find(|owner| hits.contains(owner));
+        // Low 32 bits of an id are its slot, which is sprite draw order
+        node.or_else(|| {
+            hits.into_iter()
+                .filter(|owner| matches!(owner, collision::Owner::Sprite(_)))
+                .max_by_key(|owner| owner.to_raw().1 as u32)
+        })
+    }
+
+    fn step_colliders(&mut self) {
+        let (sprites, scene, players) = (&self.sprites, &self.scene, &self.players);
+        let player = (scene.player(), Pos2::new(self.player_x, self.player_y));
+        let mut contacts = std::mem::take(&mut self.tick_scratch.contacts);
+        self.colliders.step(
+            |owner| match owner {
+                collision::Owner::Sprite(id) => sprites.get(id).map(|sprite| sprite.position),
+                collision::Owner::Node(id) => scene.world_origin(id, players.positions(player)),
+            },
+            &mut contacts,
+        );
+        self.report_contacts(&mut contacts);
+        self.tick_scratch.contacts = contacts;
+    }
+
+    fn report_contacts(&mut self, contacts: &mut Vec<Contact>) {
+        for contact in contacts.drain(..) {
+            self.events.push(match contact {
+                Contact::Enter { a, b, point, depth } => GameEvent::CollisionEnter { a, b, x: point.x, y: point.y, depth },
+                Contact::Exit { a, b } => GameEvent::CollisionExit { a, b },
+            });
+        }
+    }
+
+    /// Compile host GLSL as shader `name` for sprite materials (see material module), replacing
+    /// any shader of that name; `vertex` None uses the built-in quad
+    pub fn register_shader(&mut self, name: &str, vertex: Option<&str>, fragment: &str) -> Result<(), String> {
+        self.assert_gl_thread();
+        let gl = self.renderer.as_ref().map(|r| &*r.gl);
+        self.shaders.register(gl, name, vertex, fragment)?;
+        self.request_redraw();
+        Ok(())
+    }
+
+    /// Draw a sprite with registered shader `shader` (uniforms start unset), or as a colored
+    /// quad again with None; false if the sprite or shader is unknown
+    pub fn set_sprite_material(&mut self, id: SpriteId, shader: Option<&str>) -> bool {
+        if shader.is_some_and(|name| self.shaders.get(name).is_none()) {
+            return false;
+        }
+        match self.sprites.get_mut(id) {
+            Some(sprite) => {
+                sprite.material = shader.map(|name| Arc::new(Material::new(name)));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set a uniform (float to vec4) of a sprite's material
+    pub fn set_sprite_uniform(&mut self, id: SpriteId, name: &str, values: &[f32]) -> Result<(), String> {
+        let sprite = self.sprites.get_mut(id).ok_or("Unknown sprite")?;
+        let material = sprite.material.as_mut().ok_or("Sprite has no material")?;
+        // Shared with display lists already built, which keep the old values
+        Arc::make_mut(material).set_uniform(name, values)
+    }
+
+    /// Bit mask for a tag name, registering it on first use; None once 64 tags exist
+    pub fn tag_mask(&mut self, name: &str) -> Option<TagMask> {
+        self.tags.mask(name)
+    }
+
+    /// Replace a sprite's tags
+    pub fn set_sprite_tags(&mut self, id: SpriteId, mask: TagMask) -> bool {
+        match self.sprites.get_mut(id) {
+            Some(sprite) => {
+                sprite.tags = mask;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replace a node's tags
+    pub fn set_node_tags(&mut self, id: NodeId, mask: TagMask) -> bool {
+        match self.scene.get_mut(id) {
+            Some(node) => {
+                node.tags = mask;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sprites and nodes carrying every bit of `mask`
+    pub fn count_tagged(&self, mask: TagMask) -> usize {
+        let sprites = self.sprites.iter().filter(|s| tags::matches(s.tags, mask)).count();
+        sprites + self.scene.count_tagged(mask)
+    }
+
+    /// Remove sprites and nodes carrying every bit of `mask` (never the player node)
+    /// Returns how many were removed
+    pub fn despawn_group(&mut self, mask: TagMask) -> usize {
+        let sprites = self.sprites.remove_tagged(mask);
+        self.index_sprites();
+        let nodes = self.scene.destroy_tagged(mask);
+        self.forget_destroyed_players();
+        sprites + nodes
+    }
+
+    /// Challenge a dev-mode token must sign (hex); fresh for every engine instance
+    pub fn dev_challenge(&self) -> String {
+        self.policy.dev_challenge()
+    }
+
+    /// Enable dev mode with a signature of dev_challenge by the build-time GAME_DEV_PUBLIC_KEY;
+    /// returns whether dev mode is on
+    pub fn enable_dev_mode(&mut self, token: &str) -> bool {
+        self.policy.enable_dev_mode(token)
+    }
+
+    /// Gate for host setters: player state (the player node and added players) goes through the
+    /// policy, sprites belong to the host and are always writable
+    fn allow_write(&mut self, target: tween::Target) -> bool {
+        let player = match target {
+            tween::Target::NodeTransform(id) => id == self.scene.player() || self.players.get(id).is_some(),
+            tween::Target::SpritePosition(_) | tween::Target::SpriteSize(_) | tween::Target::SpriteColor(_) => false,
+        };
+        !player || self.policy.allow(Mutation::PlayerState)
+    }
+
+    /// Teleport the player (rejected unless dev mode is enabled)
+    pub fn debug_set_player_position(&mut self, position: Pos2) -> bool {
+        if !self.policy.allow(Mutation::PlayerState) {
+            return false;
+        }
+        self.player_x = position.x;
+        self.player_y = position.y;
+        self.snap_interpolation();
+        true
+    }
+
+    /// Current run summary for leaderboard validation
+    pub fn run_summary(&self) -> RunSummary {
+        let seed = self.level.as_ref().map_or(0, |l| l.seed);
+        self.policy.summary(seed)
+    }
+
+    /// Create a root node (a zero size creates an empty group)
+    pub fn create_node(&mut self, size: Vec2, color: Color32) -> NodeId {
+        let content = if size.x > 0.0 && size.y > 0.0 {
+            NodeContent::Rect { size, color }
+        } else {
+            NodeContent::Group
+        };
+        self.scene.create(content)
+    }
+
+    /// Create a root node drawing `text` (lines split at '\n') `size` points per em, with the `align`
+    /// corner or edge of its block on the node origin. Shaped with the registered fonts like draw-list
+    /// text, and laid out again only when the text changes
+    pub fn create_text_node(&mut self, text: &str, size: f32, color: Color32, align: Align2) -> Result<NodeId, String> {
+        if !(size > 0.0 && size.is_finite()) {
+            return Err(format!("Invalid text size {}", size));
+        }
+        Ok(self.scene.create(NodeContent::Text(Box::new(Label::new(text, size, color, align)))))
+    }
+
+    /// Replace a text node's text (e.g. a score); false if the node doesn't draw text
+    pub fn set_node_text(&mut self, id: NodeId, text: &str) -> bool {
+        match self.scene.get_mut(id).map(|node| &mut node.content) {
+            Some(NodeContent::Text(label)) => {
+                if label.text != text {
+                    label.text = text.to_string();
+                    label.shaped_at = None;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn player_node(&self) -> NodeId {
+        self.scene.player()
+    }
+
+    /// The player legacy single-player calls act on, after warning (once) that `call` is deprecated
+    pub fn legacy_player(&mut self, call: LegacyCall) -> NodeId {
+        self.deprecations.warn(call);
+        self.scene.player()
+    }
+
+    /// Steer `player` in Manual mode; false if the node isn't a player
+    pub fn set_player_direction(&mut self, player: NodeId, direction: Direction) -> bool {
+        if player != self.scene.player() {
+            let Some(player) = self.players.get_mut(player) else {
+                return false;
+            };
+            player.direction = direction;
+            return true;
+        }
+        self.set_direction(direction);
+        true
+    }
+
+    /// Touch at screen point (x, y) in physical pixels, grabbing `player` if it's there (an extra
+    /// player heads for the finger instead); false if the node isn't a player
+    pub fn touch_player(&mut self, player: NodeId, x: f32, y: f32, action: TouchAction) -> bool {
+        if player != self.scene.player() {
+            let world = self.screen_to_world(Pos2::new(x, y) / self.scale_factor);
+            let Some(player) = self.players.get_mut(player) else {
+                return false;
+            };
+            player.touch_target = (action != TouchAction::Up).then_some(world);
+            return true;
+        }
+        self.touch(x, y, action);
+        true
+    }
+
+    /// Add a player at world point `position`, drawn as the player image tinted `tint`, for local
+    /// multiplayer: it moves in Manual mode at the player speed, steered by set_player_direction
+    /// until bound to a controller. INVALID_NODE past MAX_EXTRA_PLAYERS
+    pub fn add_player(&mut self, position: Pos2, tint: Color32) -> NodeId {
+        let node = self.scene.create(NodeContent::Player);
+        if !self.players.add(node, position, tint) {
+            log::warn!("Player not added: there are {} besides the primary one already", players::MAX_EXTRA_PLAYERS);
+            self.scene.destroy(node);
+            return INVALID_NODE;
+        }
+        node
+    }
+
+    /// Remove a player added with add_player, with its node; false for other nodes
+    pub fn remove_player(&mut self, player: NodeId) -> bool {
+        if self.players.get(player).is_none() {
+            return false;
+        }
+        self.destroy_node(player)
+    }
+
+    /// Steer an added player with `controller` as well as direction calls; false for other nodes,
+    /// unknown pads and pads already steering another player
+    pub fn set_player_controller(&mut self, player: NodeId, controller: Controller) -> bool {
+        if let Controller::Gamepad(pad) = controller {
+            let taken = self.players.iter().any(|p| p.node != player && p.controller == controller);
+            if taken || pad as usize >= MAX_PADS {
+                return false;
+            }
+        }
+        let Some(entry) = self.players.get_mut(player) else {
+            return false;
+        };
+        if let Controller::Gamepad(pad) = entry.controller {
+            self.gamepads.set_bound(pad, false);
+        }
+        entry.controller = controller;
+        entry.touch_target = None;
+        if let Controller::Gamepad(pad) = controller {
+            self.gamepads.set_bound(pad, true);
+        }
+        true
+    }
+
+    /// Color a player: an added player's tint, or the primary player's until its next bounce
+    pub fn set_player_color(&mut self, player: NodeId, color: Color32) -> bool {
+        if player == self.scene.player() {
+            self.player_tint = color;
+            return true;
+        }
+        let Some(player) = self.players.get_mut(player) else {
+            return false;
+        };
+        player.tint = color;
+        true
+    }
+
+    /// Draw an added player with loaded texture `name` (shown once loaded), or the built-in player
+    /// image with None; false for other nodes
+    pub fn set_player_texture(&mut self, player: NodeId, name: Option<&str>) -> bool {
+        let Some(player) = self.players.get_mut(player) else {
+            return false;
+        };
+        player.texture = name.map(strings::intern);
+        true
+    }
+
+    /// World position of a node's origin (a player's center for player nodes)
+    pub fn node_position(&self, id: NodeId) -> Option<Pos2> {
+        self.scene.world_origin(id, self.players.positions((self.scene.player(), self.player_position())))
+    }
+
+    /// Set a node's local transform (the player node's translation is driven by the game)
+    /// Player nodes are rejected unless dev mode is enabled
+    pub fn set_node_transform(&mut self, id: NodeId, transform: Transform) -> bool {
+        if !self.allow_write(tween::Target::NodeTransform(id)) {
+            return false;
+        }
+        match self.scene.get_mut(id) {
+            Some(node) => {
+                node.local = transform;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set a node's z-order (higher draws on top; ties keep tree order)
+    pub fn set_node_z(&mut self, id: NodeId, z: i32) -> bool {
+        match self.scene.get_mut(id) {
+            Some(node) => {
+                node.z = z;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attach a node under `parent`, or make it a root with None
+    /// Added players stay roots: the game drives their position in the world
+    pub fn attach_node(&mut self, child: NodeId, parent: Option<NodeId>) -> bool {
+        if self.players.get(child).is_some() {
+            return false;
+        }
+        self.scene.attach(child, parent)
+    }
+
+    /// Destroy a node and its descendants (the player node cannot be destroyed; added players go
+    /// with theirs)
+    pub fn destroy_node(&mut self, id: NodeId) -> bool {
+        let destroyed = self.scene.destroy(id);
+        if destroyed {
+            self.forget_destroyed_players();
+        }
+        destroyed
+    }
+
+    fn forget_destroyed_players(&mut self) {
+        let (scene, gamepads) = (&self.scene, &mut self.gamepads);
+        self.players.retain(|player| {
+            let exists = scene.contains(player.node);
+            if let (false, Controller::Gamepad(pad)) = (exists, player.controller) {
+                gamepads.set_bound(pad, false);
+            }
+            exists
+        });
+    }
+
+    /// Replace the host draw list (draw_list module format), drawn every frame from now on
+    /// Invalid data is rejected whole and the previous list stays
+    pub fn submit_draw_list(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.text.begin_pass();
+        let (ctx, text, scale) = (&self.egui_ctx, &mut self.text, self.scale_factor);
+        let mut shape_text = |s: &str, pos, size, color| text.layout(ctx, s, pos, Align2::LEFT_TOP, size, color, scale);
+        // On failure the old list stays, and is laid out again if this one evicted its glyphs
+        self.host_draws = HostDrawList::decode(bytes, &mut self.vector_cache, &mut shape_text)?;
+        self.host_text_generation = self.text.generation();
+        if let Some(Renderer { painter, pictures, .. }) = self.renderer.as_mut() {
+            // Pictures the new list doesn't use are gone for good
+            let ids: Vec<u32> = self.host_draws.picture_ids().collect();
+            pictures.retain(painter, |id| ids.contains(&id));
+        }
+        log::debug!(
+            "Host draw list: {} commands, {} cached paths ({} hits, {} misses)",
+            self.host_draws.len(),
+            self.vector_cache.len(),
+            self.vector_cache.hits,
+            self.vector_cache.misses
+        );
+        Ok(())
+    }
+
+    /// Add a TrueType/OpenType font to the fallback chain for draw-list text and text nodes (the first
+    /// registered is primary); lists submitted from now on, and every text node, are shaped with it
+    pub fn register_font(&mut self, data: &[u8]) -> Result<(), String> {
+        self.text.add_font(data.to_vec())?;
+        for label in self.scene.labels_mut().chain(self.ui.labels_mut()) {
+            label.shaped_at = None;
+        }
+        log::info!("Registered font {} ({} bytes)", self.text.font_count(), data.len());
+        Ok(())
+    }
+
+    /// Set the paragraph direction text is reordered in (the host's, so mixed-script text reads the way
+    /// its own widgets show it); everything is laid out again if it changed
+    pub fn set_text_direction(&mut self, direction: TextDirection) {
+        self.text.set_direction(direction);
+    }
+
+    /// Whether the draw list's text, a text node or a UI widget's text needs laying out before it's drawn
+    fn text_is_stale(&self) -> bool {
+        let generation = self.text.generation();
+        self.host_text_generation != generation
+            || self.scene.labels().chain(self.ui.labels()).any(|label| label.shaped_at != Some(generation))
+    }
+
+    /// Lay out the draw list's, text nodes' and widgets' text again if one of them changed or the glyph atlas
+    /// moved on since (evicted glyphs, trims, scale changes), rasterizing the glyphs they lost
+    /// Everything is laid out in one pass, so no text evicts glyphs another one draws
+    fn reshape_stale_text(&mut self) {
+        if !self.text_is_stale() {
+            return;
+        }
+        self.text.begin_pass();
+        let (ctx, text, scale) = (&self.egui_ctx, &mut self.text, self.scale_factor);
+        self.host_draws
+            .reshape_text(&mut |s: &str, pos, size, color| text.layout(ctx, s, pos, Align2::LEFT_TOP, size, color, scale));
+        for label in self.scene.labels_mut().chain(self.ui.labels_mut()) {
+            label.layout(text, ctx, scale);
+        }
+        let generation = self.text.generation();
+        self.host_text_generation = generation;
+        for label in self.scene.labels_mut().chain(self.ui.labels_mut()) {
+            label.shaped_at = Some(generation);
+        }
+    }
+
+    /// Free glyph atlas pages the draw list's text doesn't use, or all of them unless `keep_visible`
+    /// (e.g. before the GL context goes away in the background); text is rasterized again when next
+    /// drawn. Returns how many pages were freed
+    pub fn trim_glyph_atlas(&mut self, keep_visible: bool) -> usize {
+        let freed = self.text.trim(keep_visible);
+        if freed > 0 {
+            log::info!("Glyph atlas trimmed: {} pages freed", freed);
+            self.request_redraw();
+        }
+        freed
+    }
+
+    /// Free memory that's rebuilt on demand, for onTrimMemory and iOS memory warnings
+    /// Moderate drops glyph pages this frame doesn't use and tessellated draw-list paths; Background
+    /// also drops every glyph page, cached picture, post-processing target, built-in shader and the last
+    /// frame's tessellation, and compacts the particle pool, sprite index and event queue. What the next frame needs is made
+    /// again as it's drawn
+    pub fn trim_memory(&mut self, level: TrimLevel) {
+        self.assert_gl_thread();
+        let background = level == TrimLevel::Background;
+        let pages = self.text.trim(!background);
+        let paths = self.vector_cache.clear();
+        let mut pictures = 0;
+        if background {
+            if let Some(renderer) = self.renderer.as_mut() {
+                pictures = renderer.pictures.clear(&mut renderer.painter);
+                unsafe { self.post_fx.destroy(&renderer.gl) };
+                shader::release(&renderer.gl);
+            }
+            self.tessellation.clear();
+            self.particles.shrink_to_fit();
+            self.sprite_index.shrink_to_fit();
+            self.events.shrink_to_fit();
+        }
+        log::info!(
+            "Memory trimmed ({:?}): {} glyph pages, {} paths, {} pictures freed",
+            level,
+            pages,
+            paths,
+            pictures
+        );
+        self.request_redraw();
+    }
+
+    pub fn glyph_atlas_stats(&self) -> GlyphAtlasStats {
+        self.text.atlas_stats()
+    }
+
+    /// Drop the cached raster of draw-list picture `id` so it's drawn afresh (e.g. after a texture
+    /// it shows was reloaded); false if it wasn't cached
+    pub fn invalidate_picture(&mut self, id: u32) -> bool {
+        self.assert_gl_thread();
+        let Some(Renderer { painter, pictures, .. }) = self.renderer.as_mut() else {
+            return false;
+        };
+        let dropped = pictures.invalidate(painter, id);
+        if dropped {
+            self.request_redraw();
+        }
+        dropped
+    }
+
+    /// Decode PNG or SVG bytes into the asset cache under `name`; SVG is drawn at the scale factor
+    pub fn load_texture_from_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<(), String> {
+        self.assets.load_from_bytes(&self.egui_ctx, name, bytes)
+    }
+
+    /// Load a PNG or SVG file, named after its file stem
+    pub fn load_texture_from_path(&mut self, path: &Path) -> Result<String, String> {
+        self.assets.load_from_path(&self.egui_ctx, path)
+    }
+
+    /// Load a KTX2 texture under `name`, uploading ETC2/ASTC data as is with its mip levels
+    /// Uncompressed KTX2 always loads; if the GPU can't sample the compressed format (see
+    /// compressed_texture_formats), or there's no GPU, `fallback` PNG or SVG bytes are loaded instead
+    pub fn load_texture_ktx2(&mut self, name: &str, bytes: &[u8], fallback: Option<&[u8]>) -> Result<(), String> {
+        let ktx = ktx2::parse(bytes)?;
+        let size = Vec2::new(ktx.size[0] as f32, ktx.size[1] as f32);
+        match self.renderer.as_mut() {
+            Some(renderer) if renderer.compressed_formats & ktx.format.capability() == ktx.format.capability() => {
+                renderer.assert_gl_thread();
+                let texture = unsafe { ktx.upload(&renderer.gl)? };
+                let id = renderer.painter.register_native_texture(texture);
+                self.assets.insert_native(name, id, size);
+            }
+            _ => match (ktx.to_image(), fallback) {
+                (Some(image), _) => self.assets.insert_image(&self.egui_ctx, name, image, None),
+                (None, Some(fallback)) => {
+                    log::info!("Texture '{}': {:?} isn't supported here, loading the fallback", name, ktx.format);
+                    return self.assets.load_from_bytes(&self.egui_ctx, name, fallback);
+                }
+                (None, None) => return Err(format!("{:?} textures aren't supported here and there's no fallback", ktx.format)),
+            },
+        }
+        log::info!("Texture '{}' loaded: {}x{} {:?}, {} levels", name, size.x, size.y, ktx.format, ktx.level_count());
+        Ok(())
+    }
+
+    /// ktx2::FORMAT_* bits for the compressed formats the GPU can sample; 0 without one
+    pub fn compressed_texture_formats(&self) -> u32 {
+        self.renderer.as_ref().map_or(0, |renderer| renderer.compressed_formats)
+    }
+
+    /// Give textures loaded from now on mipmaps (KTX2 files bring their own)
+    pub fn set_texture_mipmaps(&mut self, enabled: bool) {
+        self.assets.set_mipmaps(enabled);
+    }
+
+    /// Queue PNG or SVG bytes for background decoding (needs Features::ASYNC_ASSETS)
+    pub fn load_texture_async(&mut self, name: &str, bytes: Vec<u8>) -> Result<LoadId, String> {
+        if !self.features.contains(Features::ASYNC_ASSETS) {
+            return Err("async assets were not negotiated".to_string());
+        }
+        self.assets.load_bytes_async(&mut self.tasks, name, bytes)
+    }
+
+    /// Queue a PNG or SVG file for background reading and decoding (needs Features::ASYNC_ASSETS)
+    pub fn load_texture_from_path_async(&mut self, path: &Path) -> Result<LoadId, String> {
+        if !self.features.contains(Features::ASYNC_ASSETS) {
+            return Err("async assets were not negotiated".to_string());
+        }
+        self.assets.load_path_async(&mut self.tasks, path)
+    }
+
+    /// Load a Tiled (TMX or JSON) or LDtk map with its top left at world point `origin`, one map pixel
+    /// `scale` world points. Tiles show once the host loads each tileset image as a texture named after
+    /// its file stem; returns INVALID_TILEMAP if the file can't be used
+    /// Objects and entities naming a loaded prefab spawn it at their center; the sprites outlive the map
+    pub fn load_tilemap(&mut self, bytes: &[u8], origin: Pos2, scale: f32) -> TilemapId {
+        if !(origin.x.is_finite() && origin.y.is_finite() && scale.is_finite() && scale > 0.0) {
+            log::warn!("Invalid tilemap placement {:?} x{}", origin, scale);
+            return INVALID_TILEMAP;
+        }
+        match Tilemap::parse(bytes, origin, scale) {
+            Ok(map) => {
+                log::info!("Tilemap loaded: {} layers, {} tiles", map.layers.len(), map.tile_count());
+                for (prefab, position) in map.spawns() {
+                    if self.prefabs.get(prefab).is_some() {
+                        self.spawn_prefab(prefab, *position);
+                    } else {
+                        log::debug!("Tilemap object '{}' isn't a loaded prefab", prefab);
+                    }
+                }
+                self.tilemaps.insert(map)
+            }
+            Err(e) => {
+                log::error!("Failed to load tilemap: {}", e);
+                INVALID_TILEMAP
+            }
+        }
+    }
+
+    pub fn remove_tilemap(&mut self, id: TilemapId) -> bool {
+        self.tilemaps.remove(id).is_some()
+    }
+
+    /// Show or hide a map's layers named `name` (collision layers keep colliding); false if it has none
+    pub fn set_tilemap_layer_visible(&mut self, id: TilemapId, name: &str, visible: bool) -> bool {
+        self.tilemaps.get_mut(id).is_some_and(|map| map.set_layer_visible(name, visible))
+    }
+
+    /// Whether any map's collision layers are solid at world point `point`
+    pub fn is_tile_solid(&self, point: Pos2) -> bool {
+        self.tilemaps.iter().any(|map| map.is_solid(point))
+    }
+
+    /// Load a sprite sheet from atlas JSON and PNG bytes; returns INVALID_ATLAS on failure
+    pub fn load_atlas(&mut self, json: &str, png: &[u8]) -> AtlasId {
+        let color_image = match assets::decode_image(png) {
+            Ok(image) => image,
+            Err(e) => {
+                log::error!("Failed to decode atlas image: {}", e);
+                return INVALID_ATLAS;
+            }
+        };
+        let size = color_image.size;
+        let texture = self.egui_ctx.load_texture("atlas", color_image, egui::TextureOptions::LINEAR);
+
+        match Atlas::parse(json, size, texture) {
+            Ok(atlas) => {
+                log::info!("Atlas loaded: {}x{}, {} frames", size[0], size[1], atlas.frame_count());
+                self.atlases.insert(atlas)
+            }
+            Err(e) => {
+                log::error!("Failed to parse atlas: {}", e);
+                INVALID_ATLAS
+            }
+        }
+    }
+
+    /// Load an animated GIF, WebP or APNG as an atlas holding one animation called `name`, the frames
+    /// played at their own delays (a still image is a one-frame animation); INVALID_ATLAS on failure
+    pub fn load_animated_texture(&mut self, name: &str, bytes: &[u8]) -> AtlasId {
+        let frames = match animated::decode(bytes) {
+            Ok(frames) => frames,
+            Err(e) => {
+                log::error!("Failed to decode animation '{}': {}", name, e);
+                return INVALID_ATLAS;
+            }
+        };
+        let (sheet, columns) = match animated::sheet(&frames) {
+            Ok(sheet) => sheet,
+            Err(e) => {
+                log::error!("Failed to pack animation '{}': {}", name, e);
+                return INVALID_ATLAS;
+            }
+        };
+        let size = sheet.size;
+        let delays: Vec<f32> = frames.iter().map(|frame| frame.delay).collect();
+        let texture = self.egui_ctx.load_texture(name, sheet, egui::TextureOptions::LINEAR);
+        let atlas = Atlas::from_grid(name, size, columns, frames[0].image.size, &delays, texture);
+        log::info!("Animation '{}' loaded: {} frames of {:?}", name, delays.len(), frames[0].image.size);
+        self.atlases.insert(atlas)
+    }
+
+    /// Play an atlas animation (or single frame) on a node; name = None clears it
+    pub fn set_node_animation(&mut self, node: NodeId, atlas: AtlasId, name: Option<&str>, fps: f32, mode: LoopMode) -> bool {
+        let animation = match name {
+            None => None,
+            Some(name) => {
+                let Some(atlas_ref) = self.atlases.get(atlas) else {
+                    return false;
+                };
+                match AnimatedSprite::new(atlas, atlas_ref, name, fps, mode) {
+                    Some(animation) => Some(animation),
+                    None => {
+                        log::warn!("Atlas has no animation or frame named '{}'", name);
+                        return false;
+                    }
+                }
+            }
+        };
+
+        match self.scene.get_mut(node) {
+            Some(node) => {
+                node.animation = animation;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Show a QR code centered above the scene; false if the data doesn't fit
+    pub fn show_qr(&mut self, data: &[u8], size: f32, ec_level: qr::EcLevel, quiet_zone: u32) -> bool {
+        self.request_redraw();
+        self.hud.show_qr(data, size, ec_level, quiet_zone)
+    }
+
+    pub fn hide_qr(&mut self) {
+        self.request_redraw();
+        self.hud.hide_qr();
+    }
+
+    /// Add a label showing `text` (lines split at '\n') `size` points per em, its `anchor` corner or edge
+    /// placed `offset` points in from the same corner or edge of the screen
+    pub fn create_ui_label(&mut self, text: &str, size: f32, color: Color32, anchor: Align2, offset: Vec2) -> Result<WidgetId, String> {
+        self.ui.create_label(Label::new(text, size, color, anchor), anchor, offset)
+    }
+
+    /// Add a button of `size` points (a zero width or height fits the text), anchored like a label
+    /// Taps arrive as EVENT_BUTTON_PRESSED; touches going down on it never reach the game
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_ui_button(
+        &mut self,
+        text: &str,
+        text_size: f32,
+        text_color: Color32,
+        fill: Color32,
+        anchor: Align2,
+        offset: Vec2,
+        size: Vec2,
+    ) -> Result<WidgetId, String> {
+        let label = Label::new(text, text_size, text_color, Align2::CENTER_CENTER);
+        self.ui.create_button(label, fill, anchor, offset, size)
+    }
+
+    /// Add an empty progress bar of `size` points, anchored like a label
+    pub fn create_ui_progress_bar(&mut self, color: Color32, anchor: Align2, offset: Vec2, size: Vec2) -> Result<WidgetId, String> {
+        self.ui.create_progress_bar(color, anchor, offset, size)
+    }
+
+    /// Replace a label's or button's text; false for other widgets
+    pub fn set_ui_text(&mut self, widget: WidgetId, text: &str) -> bool {
+        self.ui.set_text(widget, text)
+    }
+
+    /// Fill a progress bar `value` (0 to 1) of the way; false for other widgets
+    pub fn set_ui_progress(&mut self, widget: WidgetId, value: f32) -> bool {
+        self.ui.set_progress(widget, value)
+    }
+
+    pub fn set_ui_visible(&mut self, widget: WidgetId, visible: bool) -> bool {
+        self.ui.set_visible(widget, visible)
+    }
+
+    pub fn remove_ui_widget(&mut self, widget: WidgetId) -> bool {
+        self.ui.remove(widget)
+    }
+
+    /// Center the view on world point `center` at `zoom` (1 = one world point per screen point), turned
+    /// `rotation` radians clockwise. Jumps there; a following camera keeps easing from the new center
+    /// False (camera unchanged) for non-finite values or a zoom outside MIN_ZOOM..=MAX_ZOOM
+    pub fn set_camera(&mut self, center: Pos2, zoom: f32, rotation: f32) -> bool {
+        self.camera.set(center, zoom, rotation).map_err(|e| log::warn!("Camera rejected: {}", e)).is_ok()
+    }
+
+    /// Ease the camera center toward the player at `rate` per second (e.g. 5; higher is snappier)
+    /// 0 stops following and leaves the camera where it is
+    pub fn set_camera_follow(&mut self, rate: f32) -> bool {
+        self.camera.set_follow(rate).map_err(|e| log::warn!("Camera follow rejected: {}", e)).is_ok()
+    }
+
+    /// Back to the screen-sized view of the world's top left, not following
+    pub fn reset_camera(&mut self) {
+        self.camera.reset();
+    }
+
+    /// Let the player roam a `size` world (points) instead of the screen; levels stretch over it
+    /// None goes back to the screen's size. The player is kept inside the new bounds
+    pub fn set_world_size(&mut self, size: Option<Vec2>) -> bool {
+        if size.is_some_and(|size| !(size.x.is_finite() && size.y.is_finite() && size.x > 0.0 && size.y > 0.0)) {
+            log::warn!("Invalid world size {:?}", size);
+            return false;
+        }
+        self.world_size = size;
+        self.keep_player_inside();
+        true
+    }
+
+    /// Keep the HUD, UI widgets and player clear of notches, home indicators and system bars: `insets`
+    /// are physical pixels covered at each screen edge. The player is pulled back inside at once
+    /// False (insets unchanged) for negative or non-finite values
+    pub fn set_safe_area_insets(&mut self, insets: Margin) -> bool {
+        let sides = [insets.left, insets.right, insets.top, insets.bottom];
+        if !sides.iter().all(|side| side.is_finite() && *side >= 0.0) {
+            log::warn!("Invalid safe area insets {:?}", insets);
+            return false;
+        }
+        self.safe_area_insets = insets;
+        self.keep_player_inside();
+        true
+    }
+
+    /// World point under screen point `screen` (points), as the last frame showed it
+    pub fn screen_to_world(&self, screen: Pos2) -> Pos2 {
+        let camera = self.camera.transform(self.screen_size(), self.interpolation_alpha);
+        camera.inverse().map_or(screen, |inverse| inverse.apply(screen))
+    }
+
+    /// World point under `point` of `space`, as the last frame showed it
+    pub fn screen_space_to_world(&self, point: Pos2, space: ScreenSpace) -> Pos2 {
+        let (origin, scale) = self.screen_space(space);
+        self.screen_to_world(origin + point.to_vec2() / scale)
+    }
+
+    /// Where world point `world` showed in the last frame, in `space`
+    pub fn world_to_screen(&self, world: Pos2, space: ScreenSpace) -> Pos2 {
+        let camera = self.camera.transform(self.screen_size(), self.interpolation_alpha);
+        let (origin, scale) = self.screen_space(space);
+        ((camera.apply(world) - origin) * scale).to_pos2()
+    }
+
+    /// Screen point (in points) at the origin of `space`, and its units per point
+    fn screen_space(&self, space: ScreenSpace) -> (Pos2, f32) {
+        match space {
+            ScreenSpace::Points => (Pos2::ZERO, 1.0),
+            ScreenSpace::Pixels => (Pos2::ZERO, self.scale_factor),
+            ScreenSpace::SafeArea => (self.safe_area().min, 1.0),
+        }
+    }
+
+    /// Handle a touch at (x, y) in physical pixels
+    /// Optimized: no logging in hot path, minimal branching
+    pub fn touch(&mut self, x: f32, y: f32, action: TouchAction) {
+        self.touch_at(x, y, action, Instant::now());
+    }
+
+    /// Handle a touch that arrived at `now`
+    fn touch_at(&mut self, x: f32, y: f32, action: TouchAction, now: Instant) {
+        // Hosts report physical pixels; the game works in points
+        let (x, y) = (x / self.scale_factor, y / self.scale_factor);
+        match action {
+            TouchAction::Move => self.input_metrics.received_move(now),
+            _ => self.input_metrics.received(now),
+        }
+        self.debug_overlay.record_touch(Pos2::new(x, y), !matches!(action, TouchAction::Up));
+        // Touches that go down on a UI button are the UI's until lifted
+        match self.ui.touch(self.safe_area(), Pos2::new(x, y), action) {
+            // Only UI buttons (such as resume) work while the world is held still
+            UiTouch::Missed if !self.states.simulates() => return,
+            UiTouch::Missed => {}
+            UiTouch::Consumed => {
+                self.touch_router.route(action, true);
+                return;
+            }
+            UiTouch::Pressed(widget) => {
+                self.touch_router.route(action, true);
+                self.events.push(GameEvent::ButtonPressed { widget });
+                return;
+            }
+        }
+        // Recorded in world points so replays don't depend on where the camera was
+        let world = self.screen_to_world(Pos2::new(x, y));
+        // A finger in an added player's touch region steers that player (unless it's dragging the
+        // primary one there)
+        if !self.is_player_touched && self.players.touch(Pos2::new(x, y), world, action) {
+            self.touch_router.route(action, true);
+            return;
+        }
+        let on_entity = action == TouchAction::Down
+            && self.touch_router.policy() == TouchRouting::Entities
+            && (self.is_on_player(world.x, world.y) || self.hit_test(Pos2::new(x, y)).is_some());
+        // Gestures left to the host aren't the game's to act on
+        if !self.touch_router.route(action, on_entity) {
+            return;
+        }
+        // Letting go of a dragged player throws it at the finger's speed; the velocity is measured here
+        // and recorded, so replays throw alike whatever their touch timing
+        let throw = match action {
+            TouchAction::Down => {
+                self.drag.start(now, world);
+                None
+            }
+            TouchAction::Move => {
+                self.drag.push(now, world);
+                None
+            }
+            TouchAction::Up => {
+                let velocity = self.drag.release(now, world);
+                self.is_player_touched.then_some(velocity)
+            }
+        };
+        self.command(GameCommand::Touch { x: world.x, y: world.y, action });
+        if let Some(velocity) = throw {
+            self.command(GameCommand::Throw { vx: velocity.x, vy: velocity.y });
+        }
+    }
+
+    /// How quickly a thrown player slows down (Param::Friction)
+    pub fn set_throw_friction(&mut self, friction: f32) -> bool {
+        self.set_param(Param::Friction, friction)
+    }
+
+    /// Tune gameplay; takes effect from the next tick. Changing it mid-level marks the run as tuned
+    /// False (unchanged) for values outside the parameter's range (Param::spec)
+    pub fn set_param(&mut self, param: Param, value: f32) -> bool {
+        if !self.params.set(param, value) {
+            log::warn!("Parameter {:?} rejected: {} is outside {:?}", param, value, param.spec().range);
+            return false;
+        }
+        if self.level.is_some() {
+            self.policy.allow(Mutation::Tuning);
+        }
+        if param == Param::Friction && value == 0.0 {
+            self.throw.stop();
+        }
+        true
+    }
+
+    pub fn param(&self, param: Param) -> f32 {
+        self.params.get(param)
+    }
+
+    fn player_size(&self) -> f32 {
+        self.params.get(Param::PlayerSize)
+    }
+
+    /// Current value of a play statistic
+    pub fn stat(&self, stat: Stat) -> f32 {
+        match stat {
+            Stat::Score => self.score as f32,
+            _ => self.scoreboard.get(stat),
+        }
+    }
+
+    /// Report changed stats as StatUpdated events every `ms` of game time; 0 stops the reports
+    pub fn set_stats_interval(&mut self, ms: u32) {
+        self.scoreboard.set_interval(ms as f32 / 1000.0);
+    }
+
+    /// Start counting again from zero; the run's score is left alone
+    pub fn reset_stats(&mut self) {
+        self.scoreboard.reset();
+    }
+
+    /// Go to game state `name` (menu, playing, paused, game_over or one added with add_state) if the
+    /// current one allows it: the exit hook of the state left and the enter hook of the one entered
+    /// run, then GameEvent::StateChanged is reported. False for unknown states and disallowed moves
+    pub fn transition(&mut self, name: &str) -> bool {
+        let transition = match self.states.id(name) {
+            Some(to) => self.states.transition(to),
+            None => Err(format!("unknown state \"{}\"", name)),
+        };
+        let transition = match transition {
+            Ok(transition) => transition,
+            Err(e) => {
+                log::warn!("Transition to {} rejected: {}", name, e);
+                return false;
+            }
+        };
+        // SAFETY: on the render thread; hosts keep user_data valid while the hook is installed
+        unsafe { self.states.run_hooks(transition) };
+        self.events.push(GameEvent::StateChanged { from: transition.from, to: transition.to });
+        log::info!("Game state: {} -> {}", self.states.name(transition.from).unwrap_or_default(), name);
+        true
+    }
+
+    /// Current game state
+    pub fn state(&self) -> StateId {
+        self.states.current()
+    }
+
+    pub fn state_name(&self, state: StateId) -> Option<&str> {
+        self.states.name(state)
+    }
+
+    /// Add a game state, the world moving on in it if `simulates`; INVALID_STATE for empty or taken
+    /// names, or too many states. Reach it through allow_transition
+    pub fn add_state(&mut self, name: &str, simulates: bool) -> StateId {
+        self.states.add(name, simulates).unwrap_or_else(|e| {
+            log::warn!("State not added: {}", e);
+            INVALID_STATE
+        })
+    }
+
+    /// Let the game go from state `from` to `to`; false for unknown states
+    pub fn allow_transition(&mut self, from: StateId, to: StateId) -> bool {
+        self.states.allow(from, to)
+    }
+
+    /// Call `hook` on the render thread as `state` is entered and left; None removes it
+    /// `user_data` must stay valid until the hook is replaced or the engine is dropped
+    /// False for an unknown state
+    pub fn set_state_hook(&mut self, state: StateId, hook: Option<StateHook>, user_data: *mut c_void) -> bool {
+        self.states.set_hook(state, hook, user_data)
+    }
+
+    /// Claim touches per `policy`, from the next gesture on
+    pub fn set_touch_routing(&mut self, policy: TouchRouting) {
+        self.touch_router.set_policy(policy);
+    }
+
+    /// Whether the engine claims the gesture the last touch belongs to; hosts leave gestures it
+    /// doesn't claim to their own handlers (see TouchRouting)
+    pub fn touch_claimed(&self) -> bool {
+        self.touch_router.claimed()
+    }
+
+    /// Touch at world point (x, y)
+    fn apply_touch(&mut self, x: f32, y: f32, action: TouchAction) {
+        match action {
+            TouchAction::Down => {
+                if self.is_on_player(x, y) {
+                    self.grab_player(x, y);
+                } else if self.assist {
+                    self.assist_target = Some(Pos2::new(x, y));
+                } else {
+                    self.input_buffer.push_grab(x, y);
+                }
+            }
+            TouchAction::Up => {
+                self.is_player_touched = false;
+                self.input_buffer.cancel_grab();
+            }
+            TouchAction::Move => {
+                if self.is_player_touched {
+                    self.player_x = x + self.drag_offset_x;
+                    self.player_y = y + self.drag_offset_y;
+
+                    // Clamp to world bounds
+                    let area = self.player_area().shrink(self.player_size() / 2.0);
+                    self.player_x = self.player_x.clamp(area.min.x, area.max.x);
+                    self.player_y = self.player_y.clamp(area.min.y, area.max.y);
+                } else if self.assist_target.is_some() {
+                    self.assist_target = Some(Pos2::new(x, y));
+                } else {
+                    self.input_buffer.move_grab(x, y);
+                }
+            }
+        }
+    }
+
+    /// Forward a hardware or host-forwarded key (USB HID usage) to egui
+    /// Returns false for keys egui has no equivalent for
+    pub fn key_event(&mut self, usage: u32, action: KeyAction, modifiers: KeyModifiers) -> bool {
+        let accepted = self.keyboard.key(usage, action, modifiers);
+        if accepted {
+            self.input_metrics.received(Instant::now());
+            // egui only sees keys in a rendered frame
+            self.request_redraw();
+        }
+        accepted
+    }
+
+    /// Forward committed text (typed characters, IME or soft keyboard output) to egui
+    pub fn text_input(&mut self, text: &str) {
+        self.input_metrics.received(Instant::now());
+        self.keyboard.text(text);
+        self.request_redraw();
+    }
+
+    /// Record a controller button or axis; false for unknown pads
+    pub fn gamepad_event(&mut self, pad: u32, control: Control, value: f32) -> bool {
+        let accepted = self.command(GameCommand::Gamepad { pad, control, value });
+        if accepted {
+            self.input_metrics.received(Instant::now());
+        }
+        accepted
+    }
+
+    /// Forget a disconnected controller's state
+    pub fn gamepad_disconnected(&mut self, pad: u32) {
+        self.command(GameCommand::GamepadDisconnected { pad });
+    }
+
+    /// Set the analog stick dead zone (fraction of full deflection, clamped to 0.0..=0.95)
+    pub fn set_gamepad_dead_zone(&mut self, dead_zone: f32) {
+        self.gamepads.set_dead_zone(dead_zone);
+    }
+
+    /// Feed a motion sensor sample (`timestamp` in nanoseconds)
+    pub fn sensor_event(&mut self, sensor: SensorType, sample: [f32; 3], timestamp: u64) {
+        self.input_metrics.received(Instant::now());
+        self.command(GameCommand::Sensor { sensor, sample, timestamp });
+    }
+
+    /// Screen-space device tilt (x right, y down), length 0..=1
+    pub fn tilt(&self) -> Vec2 {
+        self.sensors.tilt()
+    }
+
+    /// Acceleration in px/s^2 applied along the tilt in Auto mode (0 = tilt ignored)
+    pub fn set_tilt_gravity(&mut self, strength: f32) {
+        self.tilt_gravity = if strength.is_finite() { strength } else { 0.0 };
+    }
+
+    /// Queue haptic feedback for the host; false while haptics are disabled or not negotiated
+    pub fn request_haptic(&mut self, pattern: HapticPattern) -> bool {
+        self.haptics.request(pattern)
+    }
+
+    /// Turn haptic requests on or off (e.g. a user setting); no effect unless HAPTICS was granted
+    pub fn set_haptics_enabled(&mut self, enabled: bool) -> bool {
+        if !self.features.contains(Features::HAPTICS) {
+            return false;
+        }
+        self.haptics.set_enabled(enabled);
+        true
+    }
+
+    /// Decode a WAV file for playback; fails unless AUDIO was granted
+    pub fn load_sound(&mut self, bytes: &[u8]) -> Result<SoundId, String> {
+        self.audio.load(bytes)
+    }
+
+    pub fn unload_sound(&mut self, id: SoundId) -> bool {
+        self.audio.unload(id)
+    }
+
+    pub fn play_sound(&mut self, id: SoundId, volume: f32) -> bool {
+        self.audio.play(id, volume)
+    }
+
+    pub fn play_music(&mut self, id: SoundId) -> bool {
+        self.audio.play_music(id)
+    }
+
+    pub fn stop_music(&mut self) {
+        self.audio.stop_music();
+    }
+
+    pub fn set_volume(&mut self, channel: Channel, volume: f32) {
+        self.audio.set_volume(channel, volume);
+    }
+
+    pub fn set_bounce_sound(&mut self, id: SoundId) -> bool {
+        self.audio.set_bounce_sound(id)
+    }
+
+    /// Switch bounce tints and highlights to a color-blind friendly set
+    /// The current tint is replaced if the new palette doesn't contain it
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+        self.player_tint = palette.constrain(self.player_tint);
+    }
+
+    /// One-handed assist mode: in Manual mode a tap anywhere pulls the player toward it
+    /// (dragging the player still works), and Auto mode runs at ASSIST_AUTO_SPEED
+    pub fn set_assist_mode(&mut self, enabled: bool) {
+        self.assist = enabled;
+        if !enabled {
+            self.assist_target = None;
+        }
+    }
+
+    /// Turn screen-reader announcements on or off (e.g. while TalkBack/VoiceOver runs)
+    pub fn set_announcements_enabled(&mut self, enabled: bool) {
+        self.announcer.set_enabled(enabled);
+    }
+
+    /// Replace an announcement text (a11y module keys); false for unknown keys
+    pub fn set_announcement_text(&mut self, key: &str, text: &str) -> bool {
+        self.announcer.strings.set(key, text)
+    }
+
+    /// Next screen-reader announcement
+    pub fn poll_announcement(&mut self) -> Option<String> {
+        self.announcer.poll()
+    }
+
+    /// Player center in points
+    pub fn player_position(&self) -> Pos2 {
+        Pos2::new(self.player_x, self.player_y)
+    }
+}
+
+impl Drop for GameEngine {
+    fn drop(&mut self) {
+        self.assert_gl_thread();
+        // Stop background tasks before freeing the state they may reference
+        self.tasks.shutdown(TASK_SHUTDOWN_TIMEOUT);
+
+        if let Some(renderer) = &mut self.renderer {
+            unsafe {
+                self.post_fx.destroy(&renderer.gl);
+                self.shaders.destroy(&renderer.gl);
+                renderer.gpu_sync.release(&renderer.gl);
+                renderer.gpu_timer.release(&renderer.gl);
+            }
+            shader::release(&renderer.gl);
+            renderer.painter.destroy();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::affinity::{CallError, Instance};
+
+    /// Engine without a GL context, advanced with explicit timestamps
+    fn headless(width: u32, height: u32) -> GameEngine {
+        GameEngine::new(None, width, height)
+    }
+
+    fn advance(engine: &mut GameEngine, ms: u64) {
+        let now = engine.last_frame_time + Duration::from_millis(ms);
+        engine.update(now);
+    }
+
+    #[test]
+    fn touch_drags_player_in_points() {
+        let mut engine = headless(800, 600);
+        assert!(engine.set_scale_factor(2.0));
+        assert_eq!(engine.player_position(), Pos2::new(200.0, 150.0));
+
+        engine.touch(400.0, 300.0, TouchAction::Down);
+        engine.touch(500.0, 300.0, TouchAction::Move);
+        engine.touch(500.0, 300.0, TouchAction::Up);
+        assert_eq!(engine.player_position(), Pos2::new(250.0, 150.0));
+    }
+
+    #[test]
+    fn letting_go_mid_drag_throws_the_player_until_friction_stops_it() {
+        let mut engine = headless(400, 300);
+        assert!(!engine.set_throw_friction(-1.0));
+        assert!(!engine.set_throw_friction(f32::NAN));
+        assert!(engine.set_throw_friction(20.0));
+        // Drag the player `by` points to the right over `ms`, in five moves
+        let drag = |engine: &mut GameEngine, by: f32, ms: u64| {
+            let from = engine.player_position();
+            let start = Instant::now();
+            engine.touch_at(from.x, from.y, TouchAction::Down, start);
+            for step in 1..=5 {
+                let x = from.x + by * step as f32 / 5.0;
+                engine.touch_at(x, from.y, TouchAction::Move, start + Duration::from_millis(ms * step / 5));
+            }
+            engine.touch_at(from.x + by, from.y, TouchAction::Up, start + Duration::from_millis(ms));
+        };
+
+        // 25 points in 50 ms: 500 points/s, gliding 500 / 20 = 25 points further as it slows
+        drag(&mut engine, 25.0, 50);
+        assert_eq!(engine.player_position(), Pos2::new(225.0, 150.0));
+        assert!(engine.throw.is_moving());
+        for _ in 0..60 {
+            advance(&mut engine, 16);
+        }
+        let x = engine.player_position().x;
+        assert!((248.0..=250.0).contains(&x), "{}", x);
+        assert!(!engine.throw.is_moving());
+
+        // A wall stops it dead: the player's center can't pass 300 on a 400 point screen
+        assert!(engine.set_throw_friction(1.0));
+        drag(&mut engine, 25.0, 50);
+        for _ in 0..30 {
+            advance(&mut engine, 16);
+        }
+        assert_eq!(engine.player_position(), Pos2::new(300.0, 150.0));
+        assert!(!engine.throw.is_moving());
+
+        // Too slow, or throwing turned off: dropped where released
+        drag(&mut engine, -2.0, 100);
+        assert!(!engine.throw.is_moving());
+        assert!(engine.set_throw_friction(0.0));
+        drag(&mut engine, -48.0, 50);
+        advance(&mut engine, 16);
+        assert_eq!(engine.player_position(), Pos2::new(250.0, 150.0));
+    }
+
+    #[test]
+    fn added_players_follow_their_own_controllers_and_colors() {
+        let mut engine = headless(400, 300);
+        assert!(engine.set_param(Param::PlayerSize, 50.0));
+        let primary = engine.player_node();
+        let left = engine.add_player(Pos2::new(100.0, 150.0), Color32::RED);
+        let right = engine.add_player(Pos2::new(300.0, 150.0), Color32::BLUE);
+        let extra: Vec<NodeId> = (0..5).map(|_| engine.add_player(Pos2::new(200.0, 50.0), Color32::GREEN)).collect();
+        assert!(!extra.contains(&INVALID_NODE));
+        assert_eq!(engine.add_player(Pos2::ZERO, Color32::GREEN), INVALID_NODE);
+        for player in extra {
+            assert!(engine.remove_player(player));
+        }
+        assert!(!engine.remove_player(primary));
+
+        // The left half of the screen steers one player, a controller the other
+        assert!(engine.set_player_controller(left, Controller::TouchRegion(Rect::from_min_size(Pos2::ZERO, Vec2::new(200.0, 300.0)))));
+        assert!(engine.set_player_controller(right, Controller::Gamepad(1)));
+        assert!(!engine.set_player_controller(left, Controller::Gamepad(1)));
+        assert!(!engine.set_player_controller(primary, Controller::Gamepad(0)));
+
+        engine.touch(50.0, 50.0, TouchAction::Down);
+        assert!(engine.gamepad_event(1, Control::DpadDown, 1.0));
+        for _ in 0..5 {
+            advance(&mut engine, 100);
+        }
+        assert_eq!(engine.node_position(left), Some(Pos2::new(50.0, 50.0)));
+        assert_eq!(engine.node_position(right), Some(Pos2::new(300.0, 275.0)));
+        assert_eq!(engine.player_position(), Pos2::new(200.0, 150.0));
+        assert!(engine.is_animating());
+        engine.touch(50.0, 50.0, TouchAction::Up);
+        assert!(engine.gamepad_event(1, Control::DpadDown, 0.0));
+
+        // Each is drawn with its own color, and a name to draw it with its own image
+        assert!(engine.set_player_color(right, Color32::YELLOW));
+        assert!(engine.set_player_texture(right, Some("blue_player")));
+        assert!(!engine.set_player_texture(primary, Some("blue_player")));
+        let list = engine.display_list();
+        let tint = |node| match list.nodes.iter().find(|n| n.node == node).map(|n| &n.visual) {
+            Some(NodeVisual::Image { tint, .. }) => Some(*tint),
+            Some(NodeVisual::Rect { fill, .. }) => Some(*fill),
+            _ => None,
+        };
+        assert_eq!(tint(left), Some(Color32::RED));
+        assert_eq!(tint(right), Some(Color32::YELLOW));
+
+        // Directions steer them too; removed, a player's controller goes back to the primary one
+        assert!(engine.set_player_direction(left, Direction::Right));
+        advance(&mut engine, 100);
+        assert_eq!(engine.node_position(left), Some(Pos2::new(80.0, 50.0)));
+        assert!(engine.remove_player(right));
+        assert_eq!(engine.node_position(right), None);
+        assert!(engine.gamepad_event(1, Control::DpadLeft, 1.0));
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position(), Pos2::new(170.0, 150.0));
+    }
+
+    #[test]
+    fn legacy_calls_act_on_the_player_node() {
+        let mut engine = headless(400, 300);
+        let player = engine.legacy_player(LegacyCall::SetDirection);
+        assert_eq!(player, engine.player_node());
+        let other = engine.create_node(Vec2::splat(10.0), Color32::RED);
+        assert!(!engine.set_player_direction(other, Direction::Right));
+        assert!(!engine.touch_player(other, 200.0, 150.0, TouchAction::Down));
+
+        assert!(engine.touch_player(player, 200.0, 150.0, TouchAction::Down));
+        assert!(engine.touch_player(player, 250.0, 150.0, TouchAction::Move));
+        assert!(engine.touch_player(player, 250.0, 150.0, TouchAction::Up));
+        assert!(engine.set_player_direction(player, Direction::Down));
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position(), Pos2::new(250.0, 180.0));
+        assert_eq!(engine.node_position(player), Some(engine.player_position()));
+
+        // Other nodes report their world position too
+        assert!(engine.attach_node(other, Some(player)));
+        assert!(engine.set_node_transform(other, Transform { translation: Vec2::new(0.0, -20.0), ..Default::default() }));
+        assert_eq!(engine.node_position(other), Some(Pos2::new(250.0, 160.0)));
+        assert_eq!(engine.node_position(crate::scene::INVALID_NODE), None);
+    }
+
+    #[test]
+    fn invalid_scale_factor_is_rejected() {
+        let mut engine = headless(800, 600);
+        assert!(!engine.set_scale_factor(0.0));
+        assert!(!engine.set_scale_factor(f32::NAN));
+        assert_eq!(engine.player_position(), Pos2::new(400.0, 300.0));
+    }
+
+    #[test]
+    fn fixed_timestep_carries_remainder() {
+        let mut engine = headless(1000, 1000);
+        engine.set_tick_rate(10);
+        engine.set_direction(Direction::Right);
+
+        advance(&mut engine, 50);
+        assert_eq!(engine.player_position().x, 500.0);
+
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position().x, 530.0);
+    }
+
+    #[test]
+    fn auto_mode_reports_wall_bounces() {
+        let mut engine = headless(250, 250);
+        engine.set_mode(GameMode::Auto);
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+
+        assert!(matches!(engine.poll_event(), Some(GameEvent::Bounce { .. })));
+        assert!(engine.score > 0);
+    }
+
+    unsafe extern "C" fn count_state_hook(user_data: *mut c_void, state: StateId, entering: bool) {
+        let calls = &mut *(user_data as *mut Vec<(StateId, bool)>);
+        calls.push((state, entering));
+    }
+
+    #[test]
+    fn paused_states_hold_the_world_and_transitions_follow_the_allowed_moves() {
+        use crate::state_machine::{STATE_GAME_OVER, STATE_MENU, STATE_PAUSED, STATE_PLAYING};
+
+        let mut engine = headless(400, 300);
+        assert_eq!(engine.state(), STATE_PLAYING);
+        let mut calls: Vec<(StateId, bool)> = Vec::new();
+        let user_data = &mut calls as *mut _ as *mut c_void;
+        assert!(engine.set_state_hook(STATE_PLAYING, Some(count_state_hook), user_data));
+        assert!(engine.set_state_hook(STATE_PAUSED, Some(count_state_hook), user_data));
+
+        // Paused: the player stays put however long the direction is held
+        engine.set_direction(Direction::Right);
+        assert!(engine.transition("paused"));
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position(), Pos2::new(200.0, 150.0));
+        assert!(std::iter::from_fn(|| engine.poll_event())
+            .any(|e| matches!(e, GameEvent::StateChanged { from: STATE_PLAYING, to: STATE_PAUSED })));
+
+        // Game over is only reached from playing; unknown states never
+        assert!(engine.transition("menu"));
+        assert!(!engine.transition("game_over"));
+        assert!(!engine.transition("credits"));
+        assert_eq!(engine.state(), STATE_MENU);
+        assert_eq!(calls, vec![(STATE_PLAYING, false), (STATE_PAUSED, true), (STATE_PAUSED, false)]);
+
+        // Added states are reachable once allowed, and keep the world moving if they simulate
+        let bonus = engine.add_state("bonus", true);
+        assert_eq!(engine.add_state("bonus", false), INVALID_STATE);
+        assert_eq!(engine.add_state("", false), INVALID_STATE);
+        assert!(!engine.transition("bonus"));
+        assert!(engine.allow_transition(STATE_MENU, bonus));
+        assert!(!engine.allow_transition(STATE_MENU, 99));
+        assert!(engine.transition("bonus"));
+        assert_eq!(engine.state_name(bonus), Some("bonus"));
+        advance(&mut engine, 100);
+        assert!(engine.player_position().x > 200.0);
+        assert_ne!(engine.state(), STATE_GAME_OVER);
+    }
+
+    #[test]
+    fn stats_count_travel_time_and_bounces_and_report_changes_each_interval() {
+        let mut engine = headless(250, 250);
+        engine.set_stats_interval(1000);
+        engine.set_direction(Direction::Right);
+        advance(&mut engine, 100);
+        let traveled = engine.player_position().x - 125.0;
+        assert!(traveled > 0.0);
+        assert!((engine.stat(Stat::Distance) - traveled).abs() < 0.01);
+        assert!((engine.stat(Stat::ManualTime) - 0.1).abs() < 0.01);
+
+        engine.set_direction(Direction::None);
+        engine.set_mode(GameMode::Auto);
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+        assert!(engine.score > 0);
+        assert_eq!(engine.stat(Stat::Bounces), engine.score as f32);
+        assert!(engine.stat(Stat::AutoTime) > 0.9);
+
+        // A second of game time has passed: the changed stats were reported
+        let updates: Vec<Stat> = std::iter::from_fn(|| engine.poll_event())
+            .filter_map(|e| match e {
+                GameEvent::StatUpdated { stat, .. } => Some(stat),
+                _ => None,
+            })
+            .collect();
+        assert!(updates.contains(&Stat::Bounces) && updates.contains(&Stat::AutoTime));
+        assert!(!updates.contains(&Stat::Throws));
+
+        // Resetting starts a new session but leaves the run's score; reports can be turned off
+        engine.reset_stats();
+        engine.set_stats_interval(0);
+        assert_eq!(engine.stat(Stat::Bounces), 0.0);
+        assert_eq!(engine.stat(Stat::Score), engine.score as f32);
+        for _ in 0..20 {
+            advance(&mut engine, 100);
+        }
+        assert!(!std::iter::from_fn(|| engine.poll_event()).any(|e| matches!(e, GameEvent::StatUpdated { .. })));
+    }
+
+    #[test]
+    fn params_tune_speed_size_and_bounces_within_their_ranges() {
+        let mut engine = headless(1000, 1000);
+        assert_eq!(engine.param(Param::Speed), 300.0);
+        assert!(!engine.set_param(Param::Speed, -1.0));
+        assert!(!engine.set_param(Param::Restitution, 1.5));
+        assert!(!engine.set_param(Param::PlayerSize, f32::NAN));
+        assert_eq!(engine.param(Param::Speed), 300.0);
+
+        // Twice the speed: 60 points in a 10 Hz tick
+        engine.set_tick_rate(10);
+        assert!(engine.set_param(Param::Speed, 600.0));
+        engine.set_direction(Direction::Right);
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position().x, 560.0);
+
+        // A bigger player stops further from the wall
+        assert!(engine.set_param(Param::PlayerSize, 400.0));
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+        assert_eq!(engine.player_position().x, 800.0);
+
+        // Auto mode starts at the tuned velocity and loses half its speed to each wall
+        let mut engine = headless(400, 300);
+        assert!(engine.set_param(Param::AutoVelocityX, 100.0));
+        assert!(engine.set_param(Param::AutoVelocityY, 0.0));
+        assert!(engine.set_param(Param::Restitution, 0.5));
+        assert!(engine.set_param(Param::PlayerSize, 50.0));
+        engine.set_mode(GameMode::Auto);
+        for _ in 0..30 {
+            advance(&mut engine, 100);
+        }
+        assert!(matches!(engine.poll_event(), Some(GameEvent::Bounce { surface: Surface::Right, .. })));
+        assert_eq!((engine.velocity_x, engine.velocity_y), (-50.0, 0.0));
+    }
+
+    #[test]
+    fn emitters_throw_particles_that_age_out_and_bounces_spray() {
+        use crate::particles::EmitterConfig;
+
+        let config = EmitterConfig {
+            burst: 10,
+            rate: 100.0,
+            duration: 0.5,
+            lifetime_min: 0.2,
+            lifetime_max: 0.2,
+            spread: std::f32::consts::TAU,
+            speed_min: 50.0,
+            speed_max: 50.0,
+            size_start: 4.0,
+            size_end: 1.0,
+            color_start: 0xffffffff,
+            color_end: 0xffffff00,
+            ..Default::default()
+        };
+        let mut engine = headless(400, 300);
+        assert_eq!(engine.spawn_emitter(Pos2::ZERO, EmitterConfig { lifetime_min: 0.0, ..config }), INVALID_EMITTER);
+        assert_eq!(engine.spawn_emitter(Pos2::ZERO, EmitterConfig { burst: 0, rate: 0.0, ..config }), INVALID_EMITTER);
+
+        // The burst is thrown at once, batched into one mesh
+        let id = engine.spawn_emitter(Pos2::new(200.0, 150.0), config);
+        assert_ne!(id, INVALID_EMITTER);
+        assert_eq!(engine.particle_count(), 10);
+        assert!(engine.needs_redraw());
+        let list = engine.display_list();
+        assert_eq!(list.particles.vertices.len(), 40);
+        assert!(list.to_json().contains("\"kind\":\"particles\",\"count\":10"));
+
+        // 100 per second for half a second, each living 0.2 s
+        advance(&mut engine, 100);
+        assert!((15..=25).contains(&engine.particle_count()), "{}", engine.particle_count());
+        assert!(engine.move_emitter(id, Pos2::new(100.0, 100.0)));
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+        assert_eq!(engine.particle_count(), 0);
+        assert!(!engine.stop_emitter(id), "timed emitters finish by themselves");
+        assert!(!engine.particles.is_active());
+
+        // Bounces spray away from the wall
+        assert!(!engine.set_bounce_particles(Some(EmitterConfig { speed_min: 2.0, speed_max: 1.0, ..config })));
+        let spray = EmitterConfig { burst: 8, rate: 0.0, lifetime_min: 5.0, lifetime_max: 5.0, spread: 0.5, ..config };
+        assert!(engine.set_bounce_particles(Some(spray)));
+        engine.set_mode(GameMode::Auto);
+        while engine.poll_event().is_none() {
+            advance(&mut engine, 16);
+        }
+        assert_eq!(engine.particle_count(), 8);
+        advance(&mut engine, 100);
+        let bounds = engine.display_list().particles.calc_bounds();
+        assert!(Rect::from_min_size(Pos2::ZERO, engine.screen_size()).contains_rect(bounds), "{:?}", bounds);
+    }
+
+    #[test]
+    fn tweens_ease_properties_and_report_how_they_ended() {
+        use crate::events::EVENT_TWEEN_FINISHED;
+        use crate::tween::{Target, Value};
+
+        for easing in (0..6).map(|raw| Easing::from_raw(raw).unwrap()) {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{:?}", easing);
+        }
+        assert_eq!(Easing::from_raw(6), None);
+        assert!(Easing::EaseIn.apply(0.5) < 0.5 && Easing::EaseOut.apply(0.5) > 0.5);
+        assert!((0..100).any(|i| Easing::Elastic.apply(i as f32 / 100.0) > 1.0), "elastic overshoots");
+        assert!((0..=100).all(|i| (0.0..=1.0).contains(&Easing::Bounce.apply(i as f32 / 100.0))));
+
+        let mut engine = headless(400, 300);
+        let sprite = engine.spawn_sprite(Pos2::new(100.0, 100.0), Vec2::splat(10.0), Color32::WHITE);
+        let to = Value::Vec2(Vec2::new(200.0, 100.0));
+        assert_eq!(engine.animate(Target::SpritePosition(INVALID_SPRITE), to, 1.0, Easing::Linear), INVALID_TWEEN);
+        assert_eq!(engine.animate(Target::SpritePosition(sprite), Value::Color(Color32::RED), 1.0, Easing::Linear), INVALID_TWEEN);
+        assert_eq!(engine.animate(Target::SpritePosition(sprite), to, f32::NAN, Easing::Linear), INVALID_TWEEN);
+
+        // Halfway there after half the duration, then done with one event
+        let moving = engine.animate(Target::SpritePosition(sprite), to, 1.0, Easing::Linear);
+        assert_ne!(moving, INVALID_TWEEN);
+        assert!(engine.needs_redraw());
+        for _ in 0..5 {
+            advance(&mut engine, 100);
+        }
+        let x = engine.sprites.get(sprite).unwrap().position.x;
+        assert!((145.0..=155.0).contains(&x), "{}", x);
+        assert!(engine.poll_event().is_none());
+        for _ in 0..6 {
+            advance(&mut engine, 100);
+        }
+        assert_eq!(engine.sprites.get(sprite).unwrap().position, Pos2::new(200.0, 100.0));
+        let record = engine.poll_event().unwrap().to_record();
+        assert_eq!((record.kind, record.id, record.value), (EVENT_TWEEN_FINISHED, moving, 1.0));
+        assert_eq!(engine.tween_count(), 0);
+
+        // A second tween on the same property interrupts the first; other properties run alongside
+        let red = Value::Color(Color32::RED);
+        let first = engine.animate(Target::SpriteColor(sprite), red, 1.0, Easing::EaseIn);
+        let second = engine.animate(Target::SpriteColor(sprite), red, 0.0, Easing::Bounce);
+        let growing = engine.animate(Target::SpriteSize(sprite), Value::Vec2(Vec2::splat(40.0)), 1.0, Easing::Elastic);
+        let record = engine.poll_event().unwrap().to_record();
+        assert_eq!((record.id, record.value), (first, 0.0));
+        advance(&mut engine, 20);
+        assert_eq!(engine.sprites.get(sprite).unwrap().color, Color32::RED);
+        assert_eq!(engine.poll_event().unwrap().to_record().id, second);
+
+        // Removing the entity ends its tweens; cancelling reports them too
+        let node = engine.create_node(Vec2::splat(10.0), Color32::WHITE);
+        let transform = Transform { rotation: 1.0, ..Default::default() };
+        let turning = engine.animate(Target::NodeTransform(node), Value::Transform(transform), 1.0, Easing::EaseInOut);
+        assert!(engine.remove_sprite(sprite));
+        advance(&mut engine, 20);
+        let record = engine.poll_event().unwrap().to_record();
+        assert_eq!((record.id, record.value), (growing, 0.0));
+        assert!(engine.cancel_tween(turning));
+        assert!(!engine.cancel_tween(turning));
+        assert_eq!(engine.poll_event().unwrap().to_record().id, turning);
+        assert!(engine.poll_event().is_none());
+        assert!(!engine.tweens.is_active());
+    }
+
+    #[test]
+    fn camera_zooms_follows_the_player_and_maps_touches_to_the_world() {
+        use crate::json::{self, Value};
+        use crate::scene::Affine;
+
+        let mut engine = headless(400, 300);
+        assert_eq!(engine.display_list().camera, Affine::IDENTITY);
+        assert!(!engine.set_camera(Pos2::new(200.0, 150.0), 0.0, 0.0));
+        assert!(!engine.set_camera(Pos2::new(f32::NAN, 150.0), 1.0, 0.0));
+        assert!(!engine.set_camera_follow(-1.0));
+
+        // Zoomed 2x on the player: screen distances are half as far in the world
+        assert!(engine.set_camera(Pos2::new(200.0, 150.0), 2.0, 0.0));
+        assert_eq!(engine.screen_to_world(Pos2::ZERO), Pos2::new(100.0, 75.0));
+        engine.touch(200.0, 150.0, TouchAction::Down);
+        engine.touch(300.0, 150.0, TouchAction::Move);
+        engine.touch(300.0, 150.0, TouchAction::Up);
+        assert_eq!(engine.player_position(), Pos2::new(250.0, 150.0));
+        let list = json::parse(&engine.display_list().to_json()).unwrap();
+        let camera = list.get("camera").and_then(Value::as_array).unwrap();
+        assert_eq!(camera.iter().filter_map(Value::as_f64).collect::<Vec<_>>(), [2.0, 0.0, 0.0, 2.0, -200.0, -150.0]);
+
+        // A bigger world lets the player leave the screen; the following camera catches up
+        assert!(!engine.set_world_size(Some(Vec2::new(0.0, 300.0))));
+        assert!(engine.set_world_size(Some(Vec2::new(2000.0, 300.0))));
+        assert!(engine.set_camera(Pos2::new(250.0, 150.0), 1.0, 0.0));
+        assert!(engine.set_camera_follow(5.0));
+        engine.touch(200.0, 150.0, TouchAction::Down);
+        engine.touch(390.0, 150.0, TouchAction::Move);
+        engine.touch(390.0, 150.0, TouchAction::Up);
+        assert_eq!(engine.player_position().x, 440.0);
+        assert!(engine.is_animating());
+        for _ in 0..30 {
+            advance(&mut engine, 100);
+        }
+        let center = engine.screen_to_world(Pos2::new(200.0, 150.0));
+        assert!((center.x - 440.0).abs() < 1.0, "{:?}", center);
+
+        // Back to the screen: the player is pulled inside and the view is 1:1 again
+        assert!(engine.set_world_size(None));
+        assert!(engine.player_position().x <= 400.0);
+        engine.reset_camera();
+        assert_eq!(engine.display_list().camera, Affine::IDENTITY);
+    }
+
+    #[test]
+    fn world_points_convert_to_each_screen_space_and_back() {
+        let mut engine = headless(400, 300);
+        engine.set_scale_factor(2.0);
+        assert!(engine.set_safe_area_insets(Margin { left: 20.0, right: 0.0, top: 40.0, bottom: 0.0 }));
+        assert!(engine.set_camera(Pos2::new(100.0, 75.0), 2.0, 0.0));
+
+        // The camera's center is the middle of the 200 x 150 point screen
+        let world = Pos2::new(110.0, 80.0);
+        assert_eq!(engine.world_to_screen(world, ScreenSpace::Points), Pos2::new(120.0, 85.0));
+        assert_eq!(engine.world_to_screen(world, ScreenSpace::Pixels), Pos2::new(240.0, 170.0));
+        // Insets are physical pixels: the safe area starts 10 x 20 points in
+        assert_eq!(engine.world_to_screen(world, ScreenSpace::SafeArea), Pos2::new(110.0, 65.0));
+        for space in [ScreenSpace::Points, ScreenSpace::Pixels, ScreenSpace::SafeArea] {
+            assert_eq!(engine.screen_space_to_world(engine.world_to_screen(world, space), space), world);
+        }
+        assert_eq!(ScreenSpace::from_raw(3), None);
+    }
+
+    #[test]
+    fn entities_outside_the_view_are_culled_and_counted() {
+        use crate::particles::EmitterConfig;
+
+        let mut engine = headless(400, 300);
+        let near = engine.spawn_sprite(Pos2::new(100.0, 100.0), Vec2::splat(10.0), Color32::WHITE);
+        let far = engine.spawn_sprite(Pos2::new(2000.0, 100.0), Vec2::splat(10.0), Color32::WHITE);
+        // Off screen but within the margin
+        let edge = engine.spawn_sprite(Pos2::new(460.0, 100.0), Vec2::splat(10.0), Color32::WHITE);
+        let node = engine.create_node(Vec2::splat(30.0), Color32::WHITE);
+        assert!(engine.set_node_transform(node, Transform { translation: Vec2::new(2000.0, 0.0), ..Default::default() }));
+        let burst = EmitterConfig { burst: 5, lifetime_min: 5.0, lifetime_max: 5.0, size_start: 4.0, size_end: 4.0, ..Default::default() };
+        assert_ne!(engine.spawn_emitter(Pos2::new(2000.0, 150.0), burst), INVALID_EMITTER);
+
+        let list = engine.display_list();
+        let ids: Vec<_> = list.sprites.iter().map(|s| s.0).collect();
+        assert_eq!(ids, [near, edge]);
+        assert_eq!(list.nodes.len(), 1);
+        assert!(list.particles.is_empty());
+        assert_eq!(list.culled, CullStats { sprites: 1, nodes: 1, particles: 5 });
+        assert!(list.to_json().contains(r#""culled":{"sprites":1,"nodes":1,"particles":5}"#));
+
+        // Looking at the far side swaps what's culled
+        assert!(engine.set_camera(Pos2::new(2000.0, 150.0), 1.0, 0.0));
+        let list = engine.display_list();
+        assert_eq!(list.sprites.iter().map(|s| s.0).collect::<Vec<_>>(), [far]);
+        assert_eq!(list.nodes[0].node, node);
+        assert_eq!(list.culled, CullStats { sprites: 2, nodes: 1, particles: 0 });
+
+        // Sprites show up as soon as they move into view, whether teleported or moving on the tick
+        assert!(engine.set_sprite_position(near, Pos2::new(1900.0, 100.0)));
+        assert!(engine.set_sprite_velocity(edge, Vec2::new(8000.0, 0.0)));
+        advance(&mut engine, 100);
+        advance(&mut engine, 100);
+        let ids: Vec<_> = engine.display_list().sprites.iter().map(|s| s.0).collect();
+        assert_eq!(ids, [near, far, edge]);
+        assert!(engine.remove_sprite(far));
+        assert_eq!(engine.display_list().sprites.len(), 2);
+        assert_eq!(engine.display_list().culled.sprites, 0);
+    }
+
+    #[test]
+    fn lod_drops_detail_from_small_sprites_particles_and_wide_views() {
+        use crate::particles::EmitterConfig;
+
+        let mut engine = headless(400, 300);
+        engine.register_shader("glow", None, "void main() { FRAG_COLOR = vec4(1.0); }\n").unwrap();
+        let big = engine.spawn_sprite(Pos2::new(100.0, 100.0), Vec2::splat(40.0), Color32::RED);
+        let small = engine.spawn_sprite(Pos2::new(200.0, 100.0), Vec2::splat(4.0), Color32::RED);
+        assert!(engine.set_sprite_material(big, Some("glow")) && engine.set_sprite_material(small, Some("glow")));
+        let burst = EmitterConfig { burst: 5, lifetime_min: 5.0, lifetime_max: 5.0, size_start: 4.0, size_end: 4.0, ..Default::default() };
+        assert_ne!(engine.spawn_emitter(Pos2::new(200.0, 150.0), burst), INVALID_EMITTER);
+        let shaded = |engine: &GameEngine| engine.display_list().sprites.iter().map(|s| s.3.is_some()).collect::<Vec<_>>();
+        assert_eq!(shaded(&engine), [true, true]);
+
+        assert!(!engine.set_lod(LodSettings { detail_min_px: -1.0, ..Default::default() }));
+        assert!(!engine.set_lod(LodSettings { simplify_below_zoom: f32::NAN, ..Default::default() }));
+        assert_eq!(engine.lod(), LodSettings::default());
+
+        // At 2x the small sprite and particles are 8 pixels: just big enough
+        assert!(engine.set_scale_factor(2.0));
+        assert!(engine.set_lod(LodSettings { detail_min_px: 8.0, simplify_below_zoom: 0.5 }));
+        assert_eq!(shaded(&engine), [true, true]);
+        assert_eq!(engine.display_list().culled.particles, 0);
+        assert!(engine.set_scale_factor(1.0));
+        assert_eq!(shaded(&engine), [true, false]);
+        let list = engine.display_list();
+        assert!(list.particles.is_empty() && !list.simplified);
+        assert_eq!(list.culled.particles, 5);
+
+        // Zoomed out past the threshold everything is plain
+        assert!(engine.set_camera(Pos2::new(200.0, 150.0), 0.25, 0.0));
+        assert_eq!(shaded(&engine), [false, false]);
+        let list = engine.display_list();
+        assert!(list.simplified);
+        assert!(list.to_json().contains(r#""simplified":true"#));
+    }
+
+    #[test]
+    fn hit_test_finds_the_topmost_entity_under_a_point_by_its_colliders() {
+        use crate::collision::{Owner, Shape};
+
+        let mut engine = headless(400, 300);
+        let a = engine.spawn_sprite(Pos2::new(100.0, 100.0), Vec2::splat(20.0), Color32::RED);
+        let b = engine.spawn_sprite(Pos2::new(108.0, 100.0), Vec2::splat(20.0), Color32::RED);
+        engine.add_collider(Owner::Sprite(a), Shape::Box(Vec2::splat(20.0)), Vec2::ZERO);
+        engine.add_collider(Owner::Sprite(b), Shape::Circle(10.0), Vec2::ZERO);
+        engine.add_collider(Owner::Node(engine.player_node()), Shape::Box(Vec2::splat(40.0)), Vec2::ZERO);
+        // Colliders are placed by the first tick
+        assert_eq!(engine.hit_test(Pos2::new(100.0, 100.0)), None);
+        advance(&mut engine, 20);
+
+        // Both sprites cover (104, 100): the later one is drawn on top; only the box reaches (91, 91)
+        assert_eq!(engine.hit_test(Pos2::new(104.0, 100.0)), Some(Owner::Sprite(b)));
+        assert_eq!(engine.hit_test(Pos2::new(91.0, 91.0)), Some(Owner::Sprite(a)));
+        assert_eq!(engine.hit_test(Pos2::new(117.0, 91.0)), None);
+        assert_eq!(engine.hit_test(Pos2::new(200.0, 150.0)), Some(Owner::Node(engine.player_node())));
+        assert_eq!(engine.hit_test(Pos2::new(300.0, 250.0)), None);
+
+        // Nodes are drawn above sprites
+        let node = engine.create_node(Vec2::splat(10.0), Color32::WHITE);
+        assert!(engine.set_node_transform(node, Transform { translation: Vec2::new(104.0, 100.0), ..Default::default() }));
+        engine.add_collider(Owner::Node(node), Shape::Box(Vec2::splat(10.0)), Vec2::ZERO);
+        advance(&mut engine, 20);
+        assert_eq!(engine.hit_test(Pos2::new(104.0, 100.0)), Some(Owner::Node(node)));
+
+        // Points are on screen: zoomed in 2x on the node, the screen's center is over it
+        assert!(engine.set_camera(Pos2::new(104.0, 100.0), 2.0, 0.0));
+        assert_eq!(engine.hit_test(Pos2::new(200.0, 150.0)), Some(Owner::Node(node)));
+        assert_eq!(engine.hit_test(Pos2::new(200.0, 150.0)).map(Owner::to_raw), Some((collision::OWNER_NODE, node)));
+    }
+
+    #[test]
+    fn touch_routing_claims_only_gestures_starting_on_entities() {
+        use crate::collision::{Owner, Shape};
+
+        let mut engine = headless(400, 300);
+        assert!(engine.touch_claimed());
+        engine.set_touch_routing(TouchRouting::Entities);
+        let sprite = engine.spawn_sprite(Pos2::new(30.0, 280.0), Vec2::splat(20.0), Color32::RED);
+        engine.add_collider(Owner::Sprite(sprite), Shape::Box(Vec2::splat(20.0)), Vec2::ZERO);
+        advance(&mut engine, 20);
+
+        // Off every entity (the player is 200 across at the center): left to the host, and not acted on
+        let start = engine.player_position();
+        engine.touch(380.0, 20.0, TouchAction::Down);
+        assert!(!engine.touch_claimed());
+        engine.touch(390.0, 20.0, TouchAction::Move);
+        engine.touch(390.0, 20.0, TouchAction::Up);
+        assert!(!engine.touch_claimed());
+        advance(&mut engine, 20);
+        assert_eq!(engine.player_position(), start);
+
+        // On the player: the whole drag is the game's
+        engine.touch(200.0, 150.0, TouchAction::Down);
+        assert!(engine.touch_claimed());
+        engine.touch(220.0, 150.0, TouchAction::Move);
+        engine.touch(220.0, 150.0, TouchAction::Up);
+        assert!(engine.touch_claimed());
+        assert_eq!(engine.player_position(), start + Vec2::new(20.0, 0.0));
+
+        // On a sprite's collider
+        engine.touch(30.0, 280.0, TouchAction::Down);
+        assert!(engine.touch_claimed());
+        engine.touch(30.0, 280.0, TouchAction::Up);
+
+        // Claiming everything again takes effect from the next gesture
+        engine.touch(380.0, 20.0, TouchAction::Down);
+        engine.set_touch_routing(TouchRouting::All);
+        engine.touch(380.0, 20.0, TouchAction::Up);
+        assert!(!engine.touch_claimed());
+        engine.touch(380.0, 20.0, TouchAction::Down);
+        assert!(engine.touch_claimed());
+    }
+
+    #[test]
+    fn colliders_report_each_pair_entering_and_leaving_once() {
+        use crate::collision::{Owner, Shape};
+        use crate::events::{EVENT_COLLISION_ENTER, EVENT_COLLISION_EXIT};
+
+        let mut engine = headless(400, 300);
+        let contacts = |engine: &mut GameEngine| {
+            std::iter::from_fn(|| engine.poll_event())
+                .map(|event| event.to_record())
+                .filter(|r| r.kind == EVENT_COLLISION_ENTER || r.kind == EVENT_COLLISION_EXIT)
+                .map(|r| (r.kind, r.id, r.other, r.x, r.y, r.value))
+                .collect::<Vec<_>>()
+        };
+        let a = engine.spawn_sprite(Pos2::new(100.0, 100.0), Vec2::splat(20.0), Color32::RED);
+        let b = engine.spawn_sprite(Pos2::new(200.0, 100.0), Vec2::splat(20.0), Color32::RED);
+        let box_a = engine.add_collider(Owner::Sprite(a), Shape::Box(Vec2::splat(20.0)), Vec2::ZERO);
+        let circle_b = engine.add_collider(Owner::Sprite(b), Shape::Circle(10.0), Vec2::ZERO);
+        // The player is 200 across at (200, 150); its collider is a smaller box
+        let player = engine.add_collider(Owner::Node(engine.player_node()), Shape::Box(Vec2::splat(40.0)), Vec2::ZERO);
+        assert!(![box_a, circle_b, player].contains(&INVALID_COLLIDER));
+        assert_eq!(engine.add_collider(Owner::Sprite(INVALID_SPRITE), Shape::Circle(1.0), Vec2::ZERO), INVALID_COLLIDER);
+        assert_eq!(engine.add_collider(Owner::Sprite(a), Shape::Box(Vec2::new(0.0, 5.0)), Vec2::ZERO), INVALID_COLLIDER);
+        assert_eq!(engine.add_collider(Owner::Sprite(a), Shape::Circle(f32::NAN), Vec2::ZERO), INVALID_COLLIDER);
+        assert_eq!(engine.add_collider(Owner::Sprite(a), Shape::Circle(1.0), Vec2::new(f32::INFINITY, 0.0)), INVALID_COLLIDER);
+        assert_eq!(engine.collider_count(), 3);
+        advance(&mut engine, 20);
+        assert!(contacts(&mut engine).is_empty());
+
+        // The circle's center on the box's right edge: 10 deep
+        assert!(engine.set_sprite_position(b, Pos2::new(110.0, 100.0)));
+        advance(&mut engine, 20);
+        assert_eq!(contacts(&mut engine), [(EVENT_COLLISION_ENTER, box_a, circle_b, 110.0, 100.0, 10.0)]);
+        assert!(engine.colliders_touching(circle_b, box_a));
+        advance(&mut engine, 20);
+        assert!(contacts(&mut engine).is_empty());
+        assert!(engine.set_sprite_position(b, Pos2::new(121.0, 100.0)));
+        advance(&mut engine, 20);
+        assert_eq!(contacts(&mut engine), [(EVENT_COLLISION_EXIT, box_a, circle_b, 0.0, 0.0, 0.0)]);
+        assert!(!engine.colliders_touching(box_a, circle_b));
+
+        // A child node's collider sits at its world origin, (150, 150) + (45, 0), inside the player's box
+        let parent = engine.create_node(Vec2::splat(10.0), Color32::WHITE);
+        let child = engine.create_node(Vec2::splat(10.0), Color32::WHITE);
+        assert!(engine.set_node_transform(parent, Transform { translation: Vec2::new(150.0, 150.0), ..Default::default() }));
+        assert!(engine.set_node_transform(child, Transform { translation: Vec2::new(45.0, 0.0), ..Default::default() }));
+        assert!(engine.attach_node(child, Some(parent)));
+        let on_child = engine.add_collider(Owner::Node(child), Shape::Circle(5.0), Vec2::ZERO);
+        advance(&mut engine, 20);
+        assert_eq!(contacts(&mut engine), [(EVENT_COLLISION_ENTER, player, on_child, 195.0, 150.0, 20.0)]);
+
+        // Destroying the parent takes the child and its collider with it
+        assert!(engine.destroy_node(parent));
+        advance(&mut engine, 20);
+        assert_eq!(contacts(&mut engine), [(EVENT_COLLISION_EXIT, player, on_child, 0.0, 0.0, 0.0)]);
+        assert_eq!(engine.collider_count(), 3);
+        assert!(!engine.remove_collider(on_child));
+
+        // Removing a collider ends its contacts right away
+        assert!(engine.set_sprite_position(a, Pos2::new(200.0, 150.0)));
+        advance(&mut engine, 20);
+        assert_eq!(contacts(&mut engine).len(), 1);
+        assert!(engine.remove_collider(box_a));
+        assert_eq!(contacts(&mut engine), [(EVENT_COLLISION_EXIT, box_a, player, 0.0, 0.0, 0.0)]);
+    }
+
+    /// 4x2 cells of 16 pixels: a ground layer with an X-flipped tile and a collision layer solid in the
+    /// bottom-right cell
+    const TILED_JSON: &str = r#"{
+        "orientation": "orthogonal", "tilewidth": 16, "tileheight": 16, "width": 4, "height": 2,
+        "tilesets": [{"firstgid": 1, "image": "tiles/cave.png", "imagewidth": 64, "imageheight": 32,
+            "tilewidth": 16, "tileheight": 16, "columns": 4, "tilecount": 8}],
+        "layers": [
+            {"type": "tilelayer", "name": "ground", "width": 4, "height": 2, "data": [1, 2, 0, 0, 2147483651, 0, 0, 0]},
+            {"type": "group", "name": "walls", "visible": false, "layers": [
+                {"type": "tilelayer", "name": "collision", "width": 4, "height": 2, "data": [0, 0, 0, 0, 0, 0, 0, 5]}
+            ]},
+            {"type": "objectgroup", "name": "spawns", "objects": []}
+        ]
+    }"#;
+
+    #[test]
+    fn svg_textures_rasterize_at_the_scale_factor_and_redraw_when_it_changes() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10" viewBox="0 0 40 20">
+            <defs><linearGradient id="fade"><stop offset="0" stop-color="#fff"/><stop offset="1" stop-color="#000"/></linearGradient></defs>
+            <rect width="20" height="20" fill="#f00"/>
+            <g transform="translate(20 0)" style="opacity: 0.5">
+                <circle cx="10" cy="10" r="8" fill="rgb(0, 0, 255)"/>
+                <path d="M0 0h2v2H0z" fill="url(#fade)" stroke="none"/>
+            </g>
+        </svg>"##;
+        let document = crate::svg::parse(svg).unwrap();
+        assert_eq!(document.size, Vec2::new(20.0, 10.0));
+        let image = document.rasterize(2.0);
+        assert_eq!(image.size, [40, 20]);
+        let pixel = |x: usize, y: usize| image.pixels[y * 40 + x].to_array();
+        assert_eq!(pixel(5, 5), [255, 0, 0, 255]);
+        // Group opacity halves the circle; premultiplied, so blue and alpha both halve
+        assert_eq!(pixel(30, 10), [0, 0, 128, 128]);
+        assert_eq!(pixel(39, 0), [0, 0, 0, 0]);
+        // The rect's right edge falls between pixels 19 and 20
+        assert_eq!(pixel(19, 10)[3], 255);
+        assert_eq!(pixel(20, 10)[3], 0);
+        // The gradient square fades from white on its left to black on its right
+        let (left, right) = (pixel(20, 0), pixel(21, 0));
+        assert_eq!((left[3], right[3]), (128, 128));
+        assert!(left[0] > right[0] && right[0] > 0);
+
+        let mut engine = headless(400, 300);
+        assert!(engine.set_scale_factor(2.0));
+        engine.load_texture_from_bytes("badge", svg).unwrap();
+        let badge = crate::ffi::strings::intern("badge");
+        assert_eq!(engine.assets.get(badge).unwrap().size, Vec2::new(40.0, 20.0));
+        assert!(engine.set_scale_factor(3.0));
+        assert_eq!(engine.assets.get(badge).unwrap().size, Vec2::new(60.0, 30.0));
+        assert!(engine.load_texture_from_bytes("broken", b"<svg><rect></svg>").is_err());
+    }
+
+    #[test]
+    fn animated_gifs_load_as_atlases_that_play_at_their_frame_delays() {
+        // 2x1 red and blue for 50 ms, then a 1x1 red patch over the blue for 100 ms
+        #[rustfmt::skip]
+        let gif: &[u8] = &[
+            b'G', b'I', b'F', b'8', b'9', b'a', 2, 0, 1, 0, 0x80, 0, 0, 0xff, 0, 0, 0, 0, 0xff,
+            0x21, 0xf9, 4, 0, 5, 0, 0, 0,
+            0x2c, 0, 0, 0, 0, 2, 0, 1, 0, 0, 2, 2, 0x44, 0x0a, 0,
+            0x21, 0xf9, 4, 0, 10, 0, 0, 0,
+            0x2c, 1, 0, 0, 0, 1, 0, 1, 0, 0, 2, 2, 0x44, 0x01, 0,
+            0x3b,
+        ];
+        let frames = animated::decode(gif).unwrap();
+        assert_eq!(frames.iter().map(|f| f.delay).collect::<Vec<_>>(), [0.05, 0.1]);
+        assert_eq!(frames[0].image.pixels, [Color32::RED, Color32::BLUE]);
+        assert_eq!(frames[1].image.pixels, [Color32::RED, Color32::RED]);
+        assert!(animated::decode(b"RIFF\0\0\0\0WEBPVP8X").is_err());
+        // A canvas wider than the sprite sheet is turned away before any frame is decoded
+        let mut wide = gif.to_vec();
+        wide[6..8].copy_from_slice(&5000u16.to_le_bytes());
+        assert!(animated::decode(&wide).is_err_and(|e| e.contains("doesn't fit")));
+        // A still WebP is a one-frame animation
+        let mut webp = Vec::new();
+        image::codecs::webp::WebPEncoder::new_lossless(&mut webp)
+            .encode(&[255, 0, 0, 255], 1, 1, image::ExtendedColorType::Rgba8)
+            .unwrap();
+        assert_eq!(animated::decode(&webp).unwrap()[0].image.pixels, [Color32::RED]);
+
+        let mut engine = headless(400, 300);
+        let atlas = engine.load_animated_texture("blink", gif);
+        assert_ne!(atlas, INVALID_ATLAS);
+        let sheet = engine.atlases.get(atlas).unwrap();
+        assert_eq!(sheet.frame_count(), 2);
+        assert_eq!(sheet.texture.size(), [4, 1]);
+        assert_eq!(sheet.frame(1).unwrap().uv, Rect::from_min_max(Pos2::new(0.5, 0.0), Pos2::new(1.0, 1.0)));
+
+        let node = engine.create_node(Vec2::splat(20.0), Color32::WHITE);
+        assert!(engine.set_node_animation(node, atlas, Some("blink"), 0.0, LoopMode::Loop));
+        let frame = |engine: &mut GameEngine| engine.scene.get_mut(node).and_then(|n| n.animation.as_ref()).map(AnimatedSprite::frame);
+        assert_eq!(frame(&mut engine), Some(0));
+        advance(&mut engine, 60);
+        assert_eq!(frame(&mut engine), Some(1));
+        advance(&mut engine, 100);
+        assert_eq!(frame(&mut engine), Some(0));
+        assert_eq!(engine.load_animated_texture("broken", b"GIF89a"), INVALID_ATLAS);
+    }
+
+    /// KTX2 file of `vk_format` holding `levels` (largest first) for a `width` x `height` texture
+    fn ktx2_file(vk_format: u32, width: u32, height: u32, levels: &[&[u8]]) -> Vec<u8> {
+        let mut file = vec![0xab, b'K', b'T', b'X', b' ', b'2', b'0', 0xbb, b'\r', b'\n', 0x1a, b'\n'];
+        for field in [vk_format, 1, width, height, 0, 0, 1, levels.len() as u32, 0] {
+            file.extend(field.to_le_bytes());
+        }
+        file.resize(80, 0);
+        let mut offset = 80 + levels.len() * 24;
+        for level in levels {
+            for field in [offset, level.len(), level.len()] {
+                file.extend((field as u64).to_le_bytes());
+            }
+            offset += level.len();
+        }
+        levels.iter().for_each(|level| file.extend(*level));
+        file
+    }
+
+    #[test]
+    fn ktx2_textures_load_uncompressed_or_fall_back_when_the_gpu_lacks_the_format() {
+        let mut engine = headless(400, 300);
+        assert_eq!(engine.compressed_texture_formats(), 0);
+        let rgba = ktx2_file(43, 2, 1, &[&[255, 0, 0, 255, 0, 0, 255, 255], &[128, 0, 128, 255]]);
+        let parsed = ktx2::parse(&rgba).unwrap();
+        assert_eq!((parsed.format, parsed.size, parsed.level_count()), (ktx2::Format::Rgba8, [2, 1], 2));
+        engine.load_texture_ktx2("tiles", &rgba, None).unwrap();
+        let tiles = crate::ffi::strings::intern("tiles");
+        assert_eq!(engine.assets.get(tiles).unwrap().size, Vec2::new(2.0, 1.0));
+
+        // ETC2 needs a GPU that samples it: without one only the fallback loads
+        let etc2 = ktx2_file(147, 4, 4, &[&[0; 8]]);
+        assert_eq!(ktx2::parse(&etc2).unwrap().format.capability(), ktx2::FORMAT_ETC2);
+        assert!(engine.load_texture_ktx2("hero", &etc2, None).is_err());
+        let image = image::RgbaImage::from_pixel(3, 5, image::Rgba([255, 255, 255, 255]));
+        let mut png = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+        engine.set_texture_mipmaps(true);
+        engine.load_texture_ktx2("hero", &etc2, Some(&png)).unwrap();
+        let hero = engine.assets.get(crate::ffi::strings::intern("hero")).unwrap();
+        assert_eq!(hero.size, Vec2::new(3.0, 5.0));
+        let options = engine.egui_ctx.tex_manager().read().meta(hero.id).map(|meta| meta.options);
+        assert_eq!(options.and_then(|o| o.mipmap_mode), Some(egui::TextureFilter::Linear));
+
+        // Short level data and supercompressed files are rejected
+        assert!(ktx2::parse(&ktx2_file(151, 4, 4, &[&[0; 8]])).is_err());
+        let mut zstd = etc2.clone();
+        zstd[44] = 2;
+        assert!(ktx2::parse(&zstd).is_err());
+    }
+
+    #[test]
+    fn tilemaps_draw_visible_layers_once_their_tileset_loads_and_collide() {
+        let mut engine = headless(400, 300);
+        let map = engine.load_tilemap(TILED_JSON.as_bytes(), Pos2::ZERO, 2.0);
+        assert_ne!(map, INVALID_TILEMAP);
+        assert_eq!(engine.tilemaps.get(map).map(Tilemap::tile_count), Some(4));
+        // The collision layer is hidden by its group but still solid: (96, 32)..(128, 64) in world points
+        assert!(engine.is_tile_solid(Pos2::new(100.0, 40.0)));
+        assert!(!engine.is_tile_solid(Pos2::new(10.0, 10.0)));
+
+        // Nothing is drawn until the host loads the "cave" texture
+        assert!(engine.display_list().tiles.is_empty());
+        let image = image::RgbaImage::from_pixel(64, 32, image::Rgba([255, 255, 255, 255]));
+        let mut png = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+        engine.load_texture_from_bytes("cave", &png).unwrap();
+        let list = engine.display_list();
+        assert_eq!(list.tiles.len(), 1);
+        let ground = &list.tiles[0];
+        assert_eq!((ground.map, ground.layer), (map, 0));
+        assert_eq!(ground.bounds, Rect::from_min_max(Pos2::ZERO, Pos2::new(64.0, 64.0)));
+        // The flipped third tile (gid 3 at cell 0,1) samples its source right to left
+        let flipped = &ground.mesh.vertices[8..12];
+        assert_eq!(flipped[0].pos, Pos2::new(0.0, 32.0));
+        assert_eq!(flipped.iter().map(|v| v.uv).collect::<Vec<_>>(), [
+            Pos2::new(0.75, 0.0),
+            Pos2::new(0.5, 0.0),
+            Pos2::new(0.5, 0.5),
+            Pos2::new(0.75, 0.5)
+        ]);
+        assert!(list.to_json().contains(r#""kind":"tiles""#));
+
+        assert!(engine.set_tilemap_layer_visible(map, "collision", true));
+        assert_eq!(engine.display_list().tiles.len(), 2);
+        assert!(engine.set_tilemap_layer_visible(map, "ground", false));
+        assert!(!engine.set_tilemap_layer_visible(map, "missing", false));
+        assert_eq!(engine.display_list().tiles.iter().map(|t| t.layer).collect::<Vec<_>>(), [1]);
+
+        // Moving right, the player (200 wide at x 200) stops against the solid cell once the map sits at x 240
+        assert!(engine.remove_tilemap(map));
+        assert!(!engine.remove_tilemap(map));
+        assert!(engine.display_list().tiles.is_empty());
+        assert_ne!(engine.load_tilemap(TILED_JSON.as_bytes(), Pos2::new(240.0, 0.0), 2.0), INVALID_TILEMAP);
+        engine.set_direction(Direction::Right);
+        for _ in 0..10 {
+            advance(&mut engine, 50);
+        }
+        assert_eq!(engine.player_position().x, 236.0);
+    }
+
+    #[test]
+    fn tilemaps_load_from_tmx_and_ldtk_and_reject_what_they_cant_draw() {
+        let mut engine = headless(400, 300);
+        let tmx = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- exported from Tiled -->
+            <map version="1.10" orientation="orthogonal" width="4" height="2" tilewidth="16" tileheight="16" infinite="0">
+             <tileset firstgid="1" name="cave" tilewidth="16" tileheight="16" tilecount="8" columns="4">
+              <image source="tiles/cave.png" width="64" height="32"/>
+             </tileset>
+             <layer id="1" name="ground" width="4" height="2">
+              <data encoding="csv">1,2,0,0,
+            2147483651,0,0,0</data>
+             </layer>
+             <layer id="2" name="walls" width="4" height="2" visible="0">
+              <properties><property name="collision" type="bool" value="true"/></properties>
+              <data encoding="base64">AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAUAAAA=</data>
+             </layer>
+            </map>"#;
+        let map = engine.load_tilemap(tmx.as_bytes(), Pos2::ZERO, 2.0);
+        assert_eq!(engine.tilemaps.get(map).map(Tilemap::tile_count), Some(4));
+        assert!(engine.is_tile_solid(Pos2::new(100.0, 40.0)));
+        assert!(engine.remove_tilemap(map));
+
+        // Two levels side by side; the IntGrid layer collides and its auto tiles draw X-flipped
+        let ldtk = r#"{
+            "defs": {"tilesets": [{"uid": 7, "relPath": "tiles/cave.png", "pxWid": 64, "pxHei": 32}]},
+            "levels": [
+                {"identifier": "A", "worldX": 0, "worldY": 0, "layerInstances": []},
+                {"identifier": "B", "worldX": 256, "worldY": 0, "layerInstances": [
+                    {"__identifier": "Spawns", "__type": "Entities", "__gridSize": 16},
+                    {"__identifier": "Walls", "__type": "IntGrid", "__gridSize": 16, "__cWid": 2, "__cHei": 1,
+                        "__tilesetDefUid": 7, "intGridCsv": [0, 1],
+                        "autoLayerTiles": [{"px": [16, 0], "src": [16, 0], "f": 1}]}
+                ]}
+            ]
+        }"#;
+        let map = engine.load_tilemap(ldtk.as_bytes(), Pos2::ZERO, 1.0);
+        let loaded = engine.tilemaps.get(map).unwrap();
+        assert_eq!(loaded.tile_count(), 1);
+        assert_eq!(loaded.layers.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), ["Walls"]);
+        assert!(engine.is_tile_solid(Pos2::new(280.0, 8.0)));
+        assert!(!engine.is_tile_solid(Pos2::new(264.0, 8.0)));
+        assert!(!engine.is_tile_solid(Pos2::new(24.0, 8.0)));
+
+        let external = TILED_JSON.replace(r#""image": "tiles/cave.png""#, r#""source": "cave.tsx""#);
+        let infinite = TILED_JSON.replace(r#""orientation": "orthogonal""#, r#""infinite": true"#);
+        let short = TILED_JSON.replace("0, 0, 0, 5]", "0, 5]");
+        let unknown = TILED_JSON.replace("[1, 2,", "[99, 2,");
+        for bad in [&external, &infinite, &short, &unknown, "<map", "{", "\u{0}\u{ff}"] {
+            assert_eq!(engine.load_tilemap(bad.as_bytes(), Pos2::ZERO, 1.0), INVALID_TILEMAP, "{}", bad);
+        }
+        assert_eq!(engine.load_tilemap(TILED_JSON.as_bytes(), Pos2::ZERO, 0.0), INVALID_TILEMAP);
+    }
+
+    #[test]
+    fn challenge_code_round_trips() {
+        let mut engine = headless(800, 600);
+        assert_eq!(engine.challenge_code(), None);
+        engine.start_daily(20261016);
+        let code = engine.challenge_code().expect("level is active");
+
+        let mut other = headless(800, 600);
+        assert!(other.import_challenge_code(&code));
+        assert_eq!(other.challenge_code(), Some(code));
+        assert!(!other.import_challenge_code("not a code!"));
+    }
+
+    #[test]
+    fn sprites_spawn_and_remove() {
+        let mut engine = headless(800, 600);
+        let id = engine.spawn_sprite(Pos2::new(10.0, 10.0), Vec2::splat(20.0), Color32::RED);
+        assert_eq!(engine.sprite_count(), 1);
+        assert!(engine.set_sprite_position(id, Pos2::new(50.0, 50.0)));
+        assert!(engine.remove_sprite(id));
+        assert!(!engine.remove_sprite(id));
+        assert!(!engine.set_sprite_velocity(id, Vec2::X));
+        assert_eq!(engine.sprite_count(), 0);
+    }
+
+    #[test]
+    fn destroying_a_node_removes_its_children() {
+        let mut engine = headless(800, 600);
+        let parent = engine.create_node(Vec2::ZERO, Color32::WHITE);
+        let child = engine.create_node(Vec2::splat(10.0), Color32::RED);
+        assert!(engine.attach_node(child, Some(parent)));
+
+        assert!(engine.destroy_node(parent));
+        assert!(!engine.set_node_z(child, 1));
+        assert!(!engine.destroy_node(engine.player_node()));
+    }
+
+    #[test]
+    fn key_and_text_input_queue_for_egui() {
+        let mut engine = headless(800, 600);
+        assert!(!engine.key_event(0xFFFF, KeyAction::Down, KeyModifiers::empty()));
+        assert!(engine.key_event(0x0007_0004, KeyAction::Down, KeyModifiers::SHIFT));
+        engine.text_input("A\n");
+
+        let mut input = egui::RawInput::default();
+        engine.keyboard.drain_into(&mut input);
+        assert!(input.modifiers.shift);
+        assert!(matches!(input.events[0], egui::Event::Key { key: egui::Key::A, pressed: true, .. }));
+        assert_eq!(input.events[1], egui::Event::Text("A".to_string()));
+        assert_eq!(input.events.len(), 2);
+    }
+
+    #[test]
+    fn state_diff_sends_only_changes() {
+        let mut engine = headless(800, 600);
+        let full = engine.poll_state_diff();
+        assert_eq!(full[0], crate::state_diff::STATE_DIFF_VERSION);
+        assert!(engine.poll_state_diff().is_empty());
+
+        let id = engine.spawn_sprite(Pos2::new(1.0, 2.0), Vec2::splat(4.0), Color32::RED);
+        let diff = engine.poll_state_diff();
+        assert_eq!(diff[1], crate::state_diff::STATE_SPRITE);
+        assert_eq!(diff[2..10], id.to_le_bytes());
+        assert_eq!(diff.len(), 2 + 8 + 4 * 4 + 4);
+
+        engine.remove_sprite(id);
+        assert_eq!(engine.poll_state_diff()[1], crate::state_diff::STATE_SPRITE_REMOVED);
+
+        engine.reset_state_diff();
+        assert_eq!(engine.poll_state_diff(), full);
+    }
+
+    #[test]
+    fn despawn_group_removes_tagged_entities() {
+        let mut engine = headless(800, 600);
+        let enemy = engine.tag_mask("enemy").unwrap();
+        let pickup = engine.tag_mask("pickup").unwrap();
+        assert_eq!(engine.tag_mask("enemy"), Some(enemy));
+        assert_ne!(enemy, pickup);
+
+        let a = engine.spawn_sprite(Pos2::ZERO, Vec2::splat(4.0), Color32::RED);
+        let b = engine.spawn_sprite(Pos2::ZERO, Vec2::splat(4.0), Color32::RED);
+        let node = engine.create_node(Vec2::splat(4.0), Color32::RED);
+        assert!(engine.set_sprite_tags(a, enemy));
+        assert!(engine.set_sprite_tags(b, pickup));
+        assert!(engine.set_node_tags(node, enemy | pickup));
+        assert!(engine.set_node_tags(engine.player_node(), enemy));
+
+        assert_eq!(engine.count_tagged(enemy), 3);
+        assert_eq!(engine.count_tagged(enemy | pickup), 1);
+        assert_eq!(engine.despawn_group(enemy), 2);
+        assert_eq!(engine.sprite_count(), 1);
+        assert_eq!(engine.count_tagged(enemy), 1);
+        assert_eq!(engine.count_tagged(0), 0);
+    }
+
+    #[test]
+    fn gamepad_stick_moves_player_outside_dead_zone() {
+        let mut engine = headless(1000, 1000);
+        engine.set_tick_rate(10);
+
+        // Inside the default dead zone: no movement
+        assert!(engine.gamepad_event(0, Control::LeftStickX, 0.1));
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position(), Pos2::new(500.0, 500.0));
+
+        // Full tilt moves at full speed
+        assert!(engine.gamepad_event(0, Control::LeftStickX, 1.0));
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position(), Pos2::new(530.0, 500.0));
+
+        // D-pad wins over the stick; disconnecting stops it
+        assert!(engine.gamepad_event(0, Control::DpadUp, 1.0));
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position(), Pos2::new(530.0, 470.0));
+        engine.gamepad_disconnected(0);
+        advance(&mut engine, 100);
+        assert_eq!(engine.player_position(), Pos2::new(530.0, 470.0));
+
+        assert!(!engine.gamepad_event(9, Control::South, 1.0));
+    }
+
+    #[test]
+    fn tilt_follows_accelerometer_and_gyro() {
+        let mut engine = headless(1000, 1000);
+        assert_eq!(engine.tilt(), Vec2::ZERO);
+
+        // Right edge lowered 90 degrees: gravity reads along -x, the ball rolls right
+        for i in 0..100 {
+            engine.sensor_event(SensorType::Accelerometer, [-9.81, 0.0, 0.0], i);
+        }
+        assert!((engine.tilt() - Vec2::X).length() < 0.01);
+
+        // Rotating back a quarter turn around y brings it flat again
+        let quarter = std::f32::consts::FRAC_PI_2;
+        engine.sensor_event(SensorType::Gyroscope, [0.0, 0.0, 0.0], 0);
+        for i in 1..=100 {
+            engine.sensor_event(SensorType::Gyroscope, [0.0, quarter, 0.0], i * 10_000_000);
+        }
+        assert!(engine.tilt().length() < 0.05, "{:?}", engine.tilt());
+    }
+
+    #[test]
+    fn event_subscribers_get_their_own_copy() {
+        let mut engine = headless(250, 250);
+        engine.set_mode(GameMode::Auto);
+        let subscription = engine.subscribe_events();
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+        let polled = std::iter::from_fn(|| engine.poll_event()).count();
+        assert!(polled > 0);
+        assert_eq!(std::iter::from_fn(|| engine.poll_subscribed_event(subscription)).count(), polled);
+        assert_eq!(engine.event_stats().outbound.delivered, polled as u64);
+
+        engine.unsubscribe_events(subscription);
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+        assert!(engine.poll_subscribed_event(subscription).is_none());
+        assert!(engine.poll_event().is_some());
+    }
+
+    #[test]
+    fn event_stats_track_both_paths() {
+        let mut engine = headless(250, 250);
+        engine.set_mode(GameMode::Auto);
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+        let published = engine.event_stats().outbound.published;
+        assert!(published > 0);
+        while engine.poll_event().is_some() {}
+        assert_eq!(engine.event_stats().outbound.delivered, published);
+
+        engine.touch(10.0, 10.0, TouchAction::Down);
+        engine.touch(20.0, 10.0, TouchAction::Move);
+        engine.touch(30.0, 10.0, TouchAction::Move);
+        engine.touch(40.0, 10.0, TouchAction::Move);
+        engine.render();
+        let input = engine.event_stats().input;
+        assert_eq!((input.published, input.delivered, input.coalesced), (4, 2, 2));
+    }
+
+    #[test]
+    fn bounces_and_requests_emit_haptics() {
+        let mut engine = headless(250, 250);
+        engine.set_mode(GameMode::Auto);
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+        let haptic_count = |engine: &mut GameEngine| {
+            std::iter::from_fn(|| engine.poll_event())
+                .filter(|event| matches!(event, GameEvent::Haptic { .. }))
+                .count()
+        };
+        assert!(haptic_count(&mut engine) > 0);
+
+        assert!(engine.request_haptic(HapticPattern::Success));
+        assert!(engine.request_haptic(HapticPattern::Success));
+        engine.set_mode(GameMode::Manual);
+        advance(&mut engine, 100);
+        assert_eq!(haptic_count(&mut engine), 1);
+
+        assert!(engine.set_haptics_enabled(false));
+        assert!(!engine.request_haptic(HapticPattern::Success));
+        engine.set_mode(GameMode::Auto);
+        for _ in 0..10 {
+            advance(&mut engine, 100);
+        }
+        assert_eq!(haptic_count(&mut engine), 0);
+    }
+
+    #[test]
+    fn quirk_database_overrides_builtin_rules() {
+        let mali = DriverInfo {
+            vendor: "ARM".to_string(),
+            renderer: "Mali-T880".to_string(),
+            version: "OpenGL ES 3.2 v1.r22p0".to_string(),
+        };
+        assert_eq!(quirks::detect(&mali), Quirks::SKIP_GL_FINISH);
+
+        assert!(quirks::load_rules(r#"{"rules": [{"renderer": "mali-t8", "set": ["force_gles2"]}]}"#).is_ok());
+        assert_eq!(quirks::detect(&mali), Quirks::SKIP_GL_FINISH | Quirks::FORCE_GLES2);
+        assert!(quirks::load_rules(r#"{"rules": [{"version": "r22", "clear": ["skip_gl_finish"]}]}"#).is_ok());
+        assert_eq!(quirks::detect(&mali), Quirks::empty());
+        assert!(quirks::load_rules(r#"{"rules": [{"set": ["no_such_quirk"]}]}"#).is_err());
+        assert_eq!(quirks::detect(&mali), Quirks::empty());
+
+        let mut engine = headless(800, 600);
+        assert!(!engine.set_quirks(Quirks::SKIP_GL_FINISH));
+        assert_eq!(engine.quirks(), Quirks::empty());
+    }
+
+    /// 16-bit mono PCM WAV file
+    fn wav(rate: u32, samples: &[i16]) -> Vec<u8> {
+        let data_len = samples.len() as u32 * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        for field in [1u16, 1] {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        bytes.extend_from_slice(&rate.to_le_bytes());
+        bytes.extend_from_slice(&(rate * 2).to_le_bytes());
+        for field in [2u16, 16] {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for sample in samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn sounds_mix_with_channel_volumes() {
+        let mut audio = Audio::new(true);
+        assert!(audio.load(b"not a wav").is_err());
+        let click = audio.load(&wav(48_000, &[16384; 4])).unwrap();
+        let hum = audio.load(&wav(24_000, &[8192; 4])).unwrap();
+
+        assert!(audio.play(click, 1.0));
+        assert!(audio.play_music(hum));
+        audio.set_volume(Channel::Music, 0.5);
+        let out = audio.render(6, 2);
+        // Click lasts 4 frames; music at half rate repeats each sample and loops
+        assert!((out[0] - 0.625).abs() < 1e-3 && (out[1] - 0.625).abs() < 1e-3);
+        assert!((out[10] - 0.125).abs() < 1e-3);
+
+        audio.stop_music();
+        assert!(audio.set_bounce_sound(click));
+        audio.bounce(300.0);
+        assert!((audio.render(1, 1)[0] - 0.25).abs() < 1e-3);
+        assert!(audio.unload(click));
+        assert!(!audio.play(click, 1.0));
+
+        if !cfg!(feature = "audio") {
+            let mut engine = headless(800, 600);
+            assert!(engine.load_sound(&wav(48_000, &[0; 4])).is_err());
+        }
+    }
+
+    #[test]
+    fn palette_constrains_bounce_tints() {
+        let mut engine = headless(250, 250);
+        engine.player_tint = Color32::from_rgb(1, 2, 3);
+        engine.set_palette(Palette::Deuteranopia);
+        assert_eq!(engine.player_tint, Palette::Deuteranopia.constrain(Color32::RED));
+
+        engine.set_mode(GameMode::Auto);
+        let mut last = engine.player_tint;
+        for _ in 0..20 {
+            advance(&mut engine, 100);
+            if engine.player_tint != last {
+                assert_eq!(Palette::Deuteranopia.constrain(engine.player_tint), engine.player_tint);
+                last = engine.player_tint;
+            }
+        }
+        assert!(engine.score > 0);
+
+        // Seeded runs draw the same random values whatever the palette
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        Palette::Standard.bounce_color(&mut a, Color32::WHITE);
+        Palette::Tritanopia.bounce_color(&mut b, Color32::WHITE);
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn saved_state_restores_into_a_new_engine() {
+        // Variable timestep, so no sub-tick remainder is lost across the save
+        let mut engine = headless(800, 600);
+        engine.set_tick_rate(0);
+        engine.start_daily(20261016);
+        engine.set_mode(GameMode::Auto);
+        advance(&mut engine, 500);
+        let enemy = engine.tag_mask("enemy").unwrap();
+        let removed = engine.spawn_sprite(Pos2::new(1.0, 2.0), Vec2::splat(4.0), Color32::RED);
+        let sprite = engine.spawn_sprite(Pos2::new(5.0, 6.0), Vec2::splat(8.0), Color32::BLUE);
+        engine.remove_sprite(removed);
+        assert!(engine.set_sprite_tags(sprite, enemy));
+        let saved = engine.save_state();
+
+        // Everything but the run flags and elapsed time round-trips byte for byte
+        let same = |a: &[u8], b: &[u8]| a[..42] == b[..42] && a[58..] == b[58..];
+        let mut restored = headless(800, 600);
+        restored.set_tick_rate(0);
+        restored.restore_state(&saved).unwrap();
+        assert!(same(&restored.save_state(), &saved));
+        assert_eq!(restored.player_position(), engine.player_position());
+        assert_eq!(restored.challenge_code(), engine.challenge_code());
+        assert_eq!(restored.sprites.get(sprite).map(|s| s.position), Some(Pos2::new(5.0, 6.0)));
+        assert_eq!(restored.tag_mask("enemy"), Some(enemy));
+        assert_eq!(restored.count_tagged(enemy), 1);
+        assert_ne!(restored.run_summary().flags & crate::policy::RUN_FLAG_RESTORED, 0);
+
+        // Both continue identically
+        advance(&mut engine, 500);
+        advance(&mut restored, 500);
+        assert_eq!(restored.player_position(), engine.player_position());
+        assert_eq!(restored.player_tint, engine.player_tint);
+
+        let before = restored.save_state();
+        assert!(restored.restore_state(&saved[..saved.len() - 1]).is_err());
+        assert!(restored.restore_state(b"GSAV\x63").is_err());
+        assert!(same(&restored.save_state(), &before));
+    }
+
+    #[test]
+    fn player_state_setters_need_dev_mode_signed_for_this_instance() {
+        use crate::policy::{RUN_FLAG_DEV_MODE, RUN_FLAG_STATE_MUTATED, RUN_FLAG_TAMPER_ATTEMPT};
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let sign = |engine: &GameEngine| {
+            let hex = engine.dev_challenge();
+            let challenge: Vec<u8> =
+                (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect();
+            let message = [&b"game_engine dev mode v1\0"[..], &challenge].concat();
+            key.sign(&message).to_bytes().iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        };
+        let release = |engine: &mut GameEngine| engine.policy = Policy::new(false, Some(key.verifying_key()));
+        let turn = Transform { rotation: 1.0, ..Default::default() };
+        let tween_turn = |engine: &mut GameEngine, node| {
+            engine.animate(tween::Target::NodeTransform(node), tween::Value::Transform(turn), 1.0, Easing::Linear)
+        };
+
+        let mut engine = headless(400, 300);
+        release(&mut engine);
+        let player = engine.player_node();
+        let extra = engine.add_player(Pos2::new(50.0, 50.0), Color32::RED);
+        let node = engine.create_node(Vec2::splat(10.0), Color32::BLUE);
+        let sprite = engine.spawn_sprite(Pos2::ZERO, Vec2::splat(4.0), Color32::BLUE);
+
+        // Player nodes are rejected and flagged; host-owned nodes and sprites aren't
+        assert!(!engine.debug_set_player_position(Pos2::new(1.0, 1.0)));
+        assert!(!engine.set_node_transform(player, turn));
+        assert!(!engine.set_node_transform(extra, turn));
+        assert_eq!(tween_turn(&mut engine, extra), INVALID_TWEEN);
+        assert!(engine.set_node_transform(node, turn));
+        assert!(engine.set_sprite_position(sprite, Pos2::new(3.0, 4.0)));
+        let summary = engine.run_summary();
+        assert_eq!(summary.flags, RUN_FLAG_TAMPER_ATTEMPT);
+        assert_eq!(summary.rejected_mutations, 4);
+
+        // A signature for another instance's challenge, or garbage, doesn't unlock this one
+        let mut other = headless(400, 300);
+        release(&mut other);
+        assert_ne!(other.dev_challenge(), engine.dev_challenge());
+        assert!(!engine.enable_dev_mode(&sign(&other)));
+        assert!(!engine.enable_dev_mode("not hex"));
+        assert_eq!(engine.run_summary().rejected_mutations, 6);
+
+        assert!(engine.enable_dev_mode(&sign(&engine)));
+        assert!(engine.set_node_transform(player, turn));
+        assert_ne!(tween_turn(&mut engine, extra), INVALID_TWEEN);
+        assert!(engine.debug_set_player_position(Pos2::new(1.0, 1.0)));
+        let flags = engine.run_summary().flags;
+        assert_eq!(flags, RUN_FLAG_TAMPER_ATTEMPT | RUN_FLAG_DEV_MODE | RUN_FLAG_STATE_MUTATED);
+    }
+
+    #[test]
+    fn prefabs_spawn_by_name_and_saves_keep_only_what_instances_changed() {
+        let mut engine = headless(400, 300);
+        assert!(engine.load_prefabs("[]").is_err());
+        assert!(engine.load_prefabs(r##"{"coin": {"size": [8, 8]}, "bad": {"color": "#ff0000"}}"##).is_err());
+        assert!(engine.load_prefabs(r#"{"coin": {"size": [8, 8], "color": "red"}}"#).is_err());
+        assert_eq!(engine.spawn_prefab("coin", Pos2::ZERO), INVALID_SPRITE);
+
+        let prefabs = r##"{
+            "spike": {"size": [32, 32], "color": "#ff4040", "velocity": [10, 0], "tags": ["hazard"],
+                      "collider": {"shape": "circle", "size": [24, 24], "offset": [0, 4]}},
+            "coin": {"size": [8, 8], "color": "#ffd70080"}
+        }"##;
+        assert_eq!(engine.load_prefabs(prefabs), Ok(2));
+        let spike = engine.spawn_prefab("spike", Pos2::new(50.0, 50.0));
+        let coin = engine.spawn_prefab("coin", Pos2::new(80.0, 50.0));
+        let hazard = engine.tag_mask("hazard").unwrap();
+        let sprite = engine.sprites.get(spike).unwrap();
+        assert_eq!((sprite.size, sprite.velocity, sprite.tags), (Vec2::splat(32.0), Vec2::new(10.0, 0.0), hazard));
+        assert_eq!(sprite.color, Color32::from_rgb(0xff, 0x40, 0x40));
+        assert_eq!(engine.collider_count(), 1);
+        engine.sprites.get_mut(coin).unwrap().color = Color32::GREEN;
+
+        // Instances save a prefab index and their changes, smaller than plain sprites even with the names
+        let saved = engine.save_state();
+        let mut plain = headless(400, 300);
+        plain.spawn_sprite(Pos2::new(50.0, 50.0), Vec2::splat(32.0), Color32::RED);
+        plain.spawn_sprite(Pos2::new(80.0, 50.0), Vec2::splat(8.0), Color32::GREEN);
+        plain.tag_mask("hazard");
+        assert!(saved.len() < plain.save_state().len());
+
+        // Restoring needs the prefabs, and takes their current definitions
+        let mut restored = headless(400, 300);
+        assert!(restored.restore_state(&saved).is_err());
+        assert_eq!(restored.sprites.count(), 0);
+        restored.load_prefabs(&prefabs.replace("#ff4040", "#000000")).unwrap();
+        restored.restore_state(&saved).unwrap();
+        assert_eq!(restored.sprites.get(spike).map(|s| s.color), Some(Color32::BLACK));
+        assert_eq!(restored.sprites.get(coin).map(|s| s.color), Some(Color32::GREEN));
+        assert_eq!(restored.count_tagged(hazard), 1);
+        assert_eq!(restored.collider_count(), 1);
+        restored.restore_state(&saved).unwrap();
+        assert_eq!(restored.collider_count(), 1);
+    }
+
+    #[test]
+    fn sprites_walk_paths_around_solid_tiles() {
+        use crate::events::EVENT_PATH_FINISHED;
+
+        let mut engine = headless(400, 300);
+        // A wall from (150, 0) to (250, 200), leaving a gap along the bottom
+        let wall = r#"{
+            "orientation": "orthogonal", "tilewidth": 100, "tileheight": 100, "width": 1, "height": 2,
+            "tilesets": [{"firstgid": 1, "image": "wall.png", "imagewidth": 100, "imageheight": 100,
+                "tilewidth": 100, "tileheight": 100, "columns": 1, "tilecount": 1}],
+            "layers": [{"type": "tilelayer", "name": "collision", "width": 1, "height": 2, "data": [1, 1]}]
+        }"#;
+        assert_ne!(engine.load_tilemap(wall.as_bytes(), Pos2::new(150.0, 0.0), 1.0), INVALID_TILEMAP);
+        let sprite = engine.spawn_sprite(Pos2::new(50.0, 100.0), Vec2::splat(10.0), Color32::RED);
+        let paths = |engine: &mut GameEngine| {
+            std::iter::from_fn(|| engine.poll_event())
+                .map(|event| event.to_record())
+                .filter(|r| r.kind == EVENT_PATH_FINISHED)
+                .map(|r| (r.id, r.x, r.y, r.value))
+                .collect::<Vec<_>>()
+        };
+        assert!(!engine.move_entity_to(sprite, Pos2::new(200.0, 100.0)));
+        assert!(!engine.move_entity_to(sprite, Pos2::new(500.0, 100.0)));
+        assert!(!engine.move_entity_to(INVALID_SPRITE, Pos2::new(350.0, 100.0)));
+
+        assert!(engine.move_entity_to(sprite, Pos2::new(350.0, 100.0)));
+        let blocked = Rect::from_min_max(Pos2::new(150.0, 0.0), Pos2::new(250.0, 200.0));
+        let mut lowest: f32 = 0.0;
+        let mut finished = Vec::new();
+        for _ in 0..300 {
+            advance(&mut engine, 16);
+            let rect = Rect::from_center_size(engine.sprites.get(sprite).unwrap().position, Vec2::splat(10.0));
+            assert!(!rect.intersect(blocked).is_positive(), "{:?} walked into the wall", rect);
+            lowest = lowest.max(rect.max.y);
+            finished.extend(paths(&mut engine));
+            if !finished.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(finished, [(sprite, 350.0, 100.0, 1.0)]);
+        assert!(lowest > 200.0);
+        assert_eq!(engine.sprites.get(sprite).map(|s| s.velocity), Some(Vec2::ZERO));
+
+        // Host moves interrupt a path
+        assert!(engine.move_entity_to(sprite, Pos2::new(50.0, 100.0)));
+        advance(&mut engine, 100);
+        assert!(engine.set_sprite_velocity(sprite, Vec2::ZERO));
+        let interrupted = paths(&mut engine);
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].3, 0.0);
+    }
+
+    #[test]
+    fn tilemap_objects_spawn_the_prefabs_they_name() {
+        let mut engine = headless(400, 300);
+        engine.load_prefabs(r#"{"coin": {"size": [8, 8]}}"#).unwrap();
+        let objects = r#""objects": [
+            {"id": 1, "name": "", "type": "coin", "x": 16, "y": 0, "width": 8, "height": 8},
+            {"id": 2, "name": "coin", "gid": 1, "x": 32, "y": 16, "width": 16, "height": 16},
+            {"id": 3, "name": "door", "x": 0, "y": 0, "width": 16, "height": 16}
+        ]"#;
+        let map = engine.load_tilemap(TILED_JSON.replace(r#""objects": []"#, objects).as_bytes(), Pos2::new(100.0, 0.0), 2.0);
+        assert_ne!(map, INVALID_TILEMAP);
+        let mut positions = engine.sprites.iter().map(|s| s.position).collect::<Vec<_>>();
+        positions.sort_by(|a, b| a.x.total_cmp(&b.x));
+        // Rectangles hang from their top left, tile objects from their bottom left
+        assert_eq!(positions, [Pos2::new(140.0, 8.0), Pos2::new(180.0, 16.0)]);
+        assert!(engine.sprites.iter().all(|s| s.prefab.as_deref() == Some("coin")));
+
+        // TMX object groups pass their offset on; LDtk entities are placed by their pivot
+        let tmx = r#"<map orientation="orthogonal" width="1" height="1" tilewidth="16" tileheight="16">
+            <objectgroup name="spawns" offsetx="4"><object id="1" class="coin" x="0" y="0" width="8" height="8"/></objectgroup>
+        </map>"#;
+        let ldtk = r#"{"levels": [{"identifier": "B", "worldX": 256, "worldY": 0, "layerInstances": [
+            {"__identifier": "Spawns", "__type": "Entities", "__gridSize": 16, "entityInstances": [
+                {"__identifier": "coin", "px": [8, 16], "__pivot": [0.5, 1], "width": 8, "height": 8}
+            ]}
+        ]}]}"#;
+        let mut engine = headless(400, 300);
+        engine.load_prefabs(r#"{"coin": {"size": [8, 8]}}"#).unwrap();
+        assert_ne!(engine.load_tilemap(tmx.as_bytes(), Pos2::ZERO, 1.0), INVALID_TILEMAP);
+        assert_ne!(engine.load_tilemap(ldtk.as_bytes(), Pos2::ZERO, 1.0), INVALID_TILEMAP);
+        let mut positions = engine.sprites.iter().map(|s| s.position).collect::<Vec<_>>();
+        positions.sort_by(|a, b| a.x.total_cmp(&b.x));
+        assert_eq!(positions, [Pos2::new(8.0, 4.0), Pos2::new(264.0, 12.0)]);
+    }
+
+    #[test]
+    fn announcements_are_rate_limited_and_localized() {
+        let mut engine = headless(250, 250);
+        engine.set_mode(GameMode::Auto);
+        advance(&mut engine, 2000);
+        assert_eq!(engine.poll_announcement(), None);
+
+        engine.set_announcements_enabled(true);
+        assert!(engine.set_announcement_text("bounce", "Rebond : {surface}"));
+        assert!(!engine.set_announcement_text("nope", "x"));
+        let mut announcements = Vec::new();
+        for _ in 0..40 {
+            advance(&mut engine, 100);
+            announcements.extend(std::iter::from_fn(|| engine.poll_announcement()));
+        }
+        // 4 s of bouncing: at most one announcement per 1.5 s
+        assert!(!announcements.is_empty() && announcements.len() <= 3);
+        assert!(announcements.iter().all(|a| a.starts_with("Rebond : ") || a.starts_with("Score ")));
+    }
+
+    #[test]
+    fn recorded_input_replays_identically() {
+        let mut engine = headless(800, 600);
+        assert!(engine.start_recording());
+        engine.start_daily(20261016);
+        engine.set_direction(Direction::Right);
+        for _ in 0..10 {
+            advance(&mut engine, 16);
+        }
+        engine.set_direction(Direction::None);
+        let player = engine.player_position();
+        engine.touch(player.x, player.y, TouchAction::Down);
+        engine.touch(player.x - 50.0, player.y + 30.0, TouchAction::Move);
+        engine.touch(player.x - 50.0, player.y + 30.0, TouchAction::Up);
+        engine.set_mode(GameMode::Auto);
+        for _ in 0..60 {
+            advance(&mut engine, 16);
+        }
+        let recording = engine.stop_recording().unwrap();
+        assert!(engine.stop_recording().is_none());
+
+        let mut replayed = headless(800, 600);
+        replayed.start_replay(&recording).unwrap();
+        assert!(replayed.is_replaying());
+        // Host input is ignored while replaying
+        replayed.set_mode(GameMode::Manual);
+        replayed.finish_replay();
+        assert!(!replayed.is_replaying());
+        assert_eq!(replayed.player_position(), engine.player_position());
+        assert_eq!(replayed.score, engine.score);
+        assert_eq!(replayed.player_tint, engine.player_tint);
+        assert!(std::iter::from_fn(|| replayed.poll_event()).any(|e| matches!(e, GameEvent::ReplayFinished)));
+
+        assert!(replayed.start_replay(&recording[..recording.len() - 1]).is_err());
+        replayed.set_tick_rate(0);
+        assert!(!replayed.start_recording());
+    }
+
+    #[test]
+    fn assist_mode_pulls_toward_taps_and_slows_auto() {
+        let mut engine = headless(800, 600);
+        engine.set_assist_mode(true);
+        engine.touch(600.0, 300.0, TouchAction::Down);
+        engine.touch(600.0, 300.0, TouchAction::Up);
+        advance(&mut engine, 17);
+        let first = engine.player_position().x;
+        assert!(first > 400.0 && first < 500.0);
+        for _ in 0..120 {
+            advance(&mut engine, 16);
+        }
+        assert!((engine.player_position().x - 600.0).abs() < 1.0);
+        assert!(engine.assist_target.is_none());
+
+        // Auto mode covers half the distance per tick
+        let mut normal = headless(800, 600);
+        let mut assisted = headless(800, 600);
+        assisted.set_assist_mode(true);
+        for engine in [&mut normal, &mut assisted] {
+            engine.set_mode(GameMode::Auto);
+            advance(engine, 17);
+        }
+        let moved = |engine: &GameEngine| engine.player_position().x - 400.0;
+        assert!(moved(&normal) > 0.0);
+        assert!((moved(&assisted) - moved(&normal) * ASSIST_AUTO_SPEED).abs() < 1e-3);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn offscreen_context_renders_golden_pixels() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                // No usable libEGL on this machine
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([0.0, 0.0, 1.0, 1.0]);
+        finish_startup(&mut engine);
+        // Marked frame renders normally (no RenderDoc in tests)
+        assert_eq!(engine.trigger_gpu_capture(), Some(false));
+        engine.render();
+        let capture = engine.capture_region(Rect::from_min_size(Pos2::ZERO, Vec2::new(400.0, 300.0)), 1.0).unwrap();
+        assert_eq!((capture.width, capture.height), (400, 300));
+        // Corner is background; the player covers the center
+        assert_eq!(&capture.pixels[..4], &[0, 0, 255, 255]);
+        let center = (150 * 400 + 200) * 4;
+        assert_ne!(&capture.pixels[center..center + 4], &[0, 0, 255, 255]);
+        let first_frames = std::iter::from_fn(|| engine.poll_event());
+        assert_eq!(first_frames.filter(|e| matches!(e, GameEvent::FirstMeaningfulFrame)).count(), 1);
+
+        // The HUD pass paints on top (QR quiet zone is white), its corners masked round
+        assert!(engine.show_qr(b"hud", 280.0, qr::EcLevel::Low, 4));
+        engine.render();
+        let capture = engine.capture_region(Rect::from_min_size(Pos2::ZERO, Vec2::new(400.0, 300.0)), 1.0).unwrap();
+        let pixel = |x: usize, y: usize| &capture.pixels[(y * 400 + x) * 4..][..4];
+        assert_eq!(pixel(100, 22), &[255, 255, 255, 255]);
+        assert_eq!(pixel(70, 20), &[0, 0, 255, 255]);
+        assert_eq!(pixel(80, 30), &[255, 255, 255, 255]);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn pictures_rasterize_once_until_invalidated() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([0.0, 0.0, 1.0, 1.0]);
+        finish_startup(&mut engine);
+        let rasterized = |engine: &GameEngine| engine.renderer.as_ref().unwrap().pictures.rasterized;
+        let red_at = |engine: &mut GameEngine, x: f32, y: f32| {
+            let capture = engine.capture_region(Rect::from_min_size(Pos2::new(x, y), Vec2::splat(1.0)), 1.0).unwrap();
+            capture.pixels == [255, 0, 0, 255]
+        };
+
+        engine.submit_draw_list(&picture_list(7, 0xff0000ff)).unwrap();
+        engine.render();
+        assert_eq!(rasterized(&engine), 1);
+        for _ in 0..3 {
+            engine.render();
+        }
+        assert_eq!(rasterized(&engine), 1);
+        let json = engine.display_list().to_json();
+        assert!(json.contains(r#""cached":true"#));
+        // The cached texture covers just the picture's bounds
+        assert!(red_at(&mut engine, 50.0, 30.0));
+        assert!(!red_at(&mut engine, 5.0, 30.0));
+        // and keeps its colors (no sRGB decode on the way back out)
+        engine.submit_draw_list(&picture_list(8, 0x808080ff)).unwrap();
+        engine.render();
+        let capture = engine.capture_region(Rect::from_min_size(Pos2::new(50.0, 30.0), Vec2::splat(1.0)), 1.0).unwrap();
+        assert_eq!(capture.pixels, [128, 128, 128, 255]);
+        engine.submit_draw_list(&picture_list(7, 0xff0000ff)).unwrap();
+        engine.render();
+        assert_eq!(rasterized(&engine), 3);
+
+        // Same content resubmitted stays cached; invalidating redraws it
+        engine.submit_draw_list(&picture_list(7, 0xff0000ff)).unwrap();
+        engine.render();
+        assert_eq!(rasterized(&engine), 3);
+        assert!(engine.invalidate_picture(7));
+        engine.render();
+        assert_eq!(rasterized(&engine), 4);
+
+        // A list without the picture frees it
+        engine.submit_draw_list(&[crate::draw_list::DRAW_LIST_VERSION]).unwrap();
+        assert!(!engine.invalidate_picture(7));
+
+        // Partly off screen the texture keeps its scale: picture over x -90..10, red up to x 5
+        let mut list = picture_list(9, 0xff0000ff);
+        list[6..10].copy_from_slice(&(-90.0f32).to_le_bytes());
+        list[23..27].copy_from_slice(&(-90.0f32).to_le_bytes());
+        list[31..35].copy_from_slice(&95.0f32.to_le_bytes());
+        engine.submit_draw_list(&list).unwrap();
+        engine.render();
+        assert!(red_at(&mut engine, 2.0, 30.0));
+        assert!(!red_at(&mut engine, 7.0, 30.0));
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn custom_shaders_draw_sprites_with_host_uniforms() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([0.0, 0.0, 1.0, 1.0]);
+        finish_startup(&mut engine);
+        let pixel = |engine: &mut GameEngine, x: f32, y: f32| {
+            engine.capture_region(Rect::from_min_size(Pos2::new(x, y), Vec2::splat(1.0)), 1.0).unwrap().pixels
+        };
+
+        assert!(engine.register_shader("bad", None, "void main() { FRAG_COLOR = oops; }\n").is_err());
+        // Left half the tint, right half the sprite's own color
+        let fragment = "uniform vec4 u_tint;\nuniform vec4 u_color;\nVARYING vec2 v_uv;\n\
+            void main() { FRAG_COLOR = v_uv.x < 0.5 ? u_tint : u_color; }\n";
+        engine.register_shader("split", None, fragment).unwrap();
+        let sprite = engine.spawn_sprite(Pos2::new(60.0, 60.0), Vec2::splat(40.0), Color32::RED);
+        assert!(engine.set_sprite_material(sprite, Some("split")));
+        engine.set_sprite_uniform(sprite, "u_tint", &[0.0, 1.0, 0.0, 1.0]).unwrap();
+        assert_eq!(pixel(&mut engine, 50.0, 60.0), [0, 255, 0, 255]);
+        assert_eq!(pixel(&mut engine, 70.0, 60.0), [255, 0, 0, 255]);
+        // Square corners: the shader covers the whole quad, unsquashed where it's off the capture
+        assert_eq!(pixel(&mut engine, 40.0, 40.0), [0, 255, 0, 255]);
+
+        // Unchanged materials keep their callbacks, so paced frames can still be skipped
+        assert!(engine.render_at(1_000_000_000));
+        assert!(!engine.render_at(1_016_666_667));
+
+        // Re-registering swaps the program under the sprite
+        engine.register_shader("split", None, "void main() { FRAG_COLOR = vec4(1.0); }\n").unwrap();
+        assert_eq!(pixel(&mut engine, 70.0, 60.0), [255, 255, 255, 255]);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn sprites_and_image_nodes_draw_the_same_in_batches() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([0.0, 0.0, 1.0, 1.0]);
+        finish_startup(&mut engine);
+        let sheet = image::RgbaImage::from_fn(8, 4, |x, _| image::Rgba(if x < 4 { [255, 0, 0, 255] } else { [0, 255, 0, 255] }));
+        let mut png = Vec::new();
+        sheet.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+        let json = r#"{"frames":{"red":{"frame":{"x":0,"y":0,"w":4,"h":4}},"green":{"frame":{"x":4,"y":0,"w":4,"h":4}}}}"#;
+        let atlas = engine.load_atlas(json, &png);
+        assert_ne!(atlas, INVALID_ATLAS);
+
+        let colors = [Color32::RED, Color32::GREEN, Color32::WHITE];
+        let sprites: Vec<SpriteId> = (0..crate::sprite_batch::MIN_BATCH + 2)
+            .map(|i| engine.spawn_sprite(Pos2::new(20.0 + 36.0 * i as f32, 40.0), Vec2::splat(24.0), colors[i % 3]))
+            .collect();
+        for i in 0..crate::sprite_batch::MIN_BATCH {
+            let node = engine.create_node(Vec2::splat(24.0), Color32::WHITE);
+            let translation = Vec2::new(20.0 + 36.0 * i as f32, 120.0);
+            assert!(engine.set_node_transform(node, Transform { translation, ..Default::default() }));
+            let frame = if i % 2 == 0 { "red" } else { "green" };
+            assert!(engine.set_node_animation(node, atlas, Some(frame), 0.0, LoopMode::Loop));
+        }
+        // Uploads the sheet (run_scene below drops texture deltas)
+        assert!(engine.render());
+        let callbacks = |engine: &GameEngine| {
+            let shapes = engine.run_scene(egui::RawInput::default()).shapes;
+            shapes.iter().filter(|s| matches!(s.shape, egui::Shape::Callback(_))).count()
+        };
+        let screen = |engine: &mut GameEngine| engine.capture_region(Rect::from_min_size(Pos2::ZERO, Vec2::new(400.0, 300.0)), 1.0).unwrap();
+        let pixel = |capture: &capture::Capture, x: usize, y: usize| capture.pixels[(y * 400 + x) * 4..][..4].to_vec();
+
+        // One callback for the sprites and one for the atlas page, where egui would take a mesh each
+        assert_eq!(callbacks(&engine), 2);
+        let batched = screen(&mut engine);
+        engine.set_sprite_batching(false);
+        assert_eq!(callbacks(&engine), 0);
+        let unbatched = screen(&mut engine);
+        for i in 0..crate::sprite_batch::MIN_BATCH {
+            let x = 20 + 36 * i;
+            assert_eq!(pixel(&batched, x, 40), colors[i % 3].to_array(), "sprite {}", i);
+            let texel = if i % 2 == 0 { [255, 0, 0, 255] } else { [0, 255, 0, 255] };
+            assert_eq!(pixel(&batched, x, 120), texel, "node {}", i);
+            // Rounded corners leave the background showing in both
+            assert_eq!(pixel(&batched, x - 12, 28), [0, 0, 255, 255]);
+            assert_eq!(pixel(&batched, x - 12, 28), pixel(&unbatched, x - 12, 28));
+        }
+        // Only antialiased corner pixels may cover a little differently
+        let worst = batched.pixels.iter().zip(&unbatched.pixels).map(|(a, b)| a.abs_diff(*b)).max();
+        assert!(worst.is_some_and(|worst| worst <= 48), "{:?}", worst);
+
+        // Too few quads for a batch go through egui
+        engine.set_sprite_batching(true);
+        for &sprite in &sprites[..3] {
+            assert!(engine.remove_sprite(sprite));
+        }
+        assert_eq!(callbacks(&engine), 1);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn shaped_text_is_drawn_from_the_glyph_atlas() {
+        use crate::draw_list::*;
+
+        let Ok(font) = std::fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf") else {
+            eprintln!("skipping: DejaVu Sans not installed");
+            return;
+        };
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([0.0, 0.0, 1.0, 1.0]);
+        finish_startup(&mut engine);
+        engine.register_font(&font).unwrap();
+        // Full blocks, red: solid from above the baseline to below it
+        let text = "\u{2588}\u{2588}";
+        let mut bytes = vec![DRAW_LIST_VERSION, DRAW_TEXT];
+        [100.0f32, 100.0, 40.0].iter().for_each(|v| bytes.extend_from_slice(&v.to_le_bytes()));
+        bytes.extend_from_slice(&0xff0000ffu32.to_le_bytes());
+        bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(text.as_bytes());
+        engine.submit_draw_list(&bytes).unwrap();
+
+        let pixel = |engine: &mut GameEngine, x: f32, y: f32| {
+            engine.capture_region(Rect::from_min_size(Pos2::new(x, y), Vec2::splat(1.0)), 1.0).unwrap().pixels
+        };
+        assert_eq!(pixel(&mut engine, 110.0, 130.0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&mut engine, 140.0, 130.0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&mut engine, 110.0, 90.0), [0, 0, 255, 255]);
+        assert_eq!(pixel(&mut engine, 200.0, 130.0), [0, 0, 255, 255]);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn post_fx_grades_vignettes_and_blooms_presented_frames() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([128.0 / 255.0, 128.0 / 255.0, 128.0 / 255.0, 1.0]);
+        finish_startup(&mut engine);
+        // Surface pixel at (x, y) from the top left, as presented
+        let presented = |engine: &mut GameEngine, x: i32, y: i32| {
+            engine.render();
+            let mut pixel = [0u8; 4];
+            let gl = &engine.renderer.as_ref().unwrap().gl;
+            unsafe {
+                gl.read_pixels(x, 299 - y, 1, 1, glow::RGBA, glow::UNSIGNED_BYTE, glow::PixelPackData::Slice(&mut pixel));
+            }
+            pixel
+        };
+        assert_eq!(presented(&mut engine, 100, 150), [128, 128, 128, 255]);
+
+        // Darkens the corners, leaves the middle
+        let vignette = PostFxSettings { vignette_strength: 1.0, vignette_radius: 0.9, ..Default::default() };
+        assert!(engine.set_post_fx(vignette));
+        assert_eq!(presented(&mut engine, 100, 150), [128, 128, 128, 255]);
+        assert_eq!(presented(&mut engine, 0, 0), [0, 0, 0, 255]);
+        // Captures are unprocessed
+        let capture = engine.capture_region(Rect::from_min_size(Pos2::ZERO, Vec2::splat(1.0)), 1.0).unwrap();
+        assert_eq!(capture.pixels, [128, 128, 128, 255]);
+
+        // An inverting LUT
+        assert!(engine.set_color_lut(Some(&lut_png(8, |c| c.map(|v| 255 - v)))));
+        assert!(engine.set_post_fx(PostFxSettings { lut_intensity: 1.0, ..Default::default() }));
+        let graded = presented(&mut engine, 100, 150);
+        assert!(graded[..3].iter().all(|&c| c.abs_diff(127) <= 1), "{:?}", graded);
+
+        // Everything over the threshold glows
+        assert!(engine.set_post_fx(PostFxSettings { bloom_intensity: 1.0, bloom_spread: 1.0, ..Default::default() }));
+        let bloomed = presented(&mut engine, 100, 150);
+        assert!(bloomed[0] > 200, "{:?}", bloomed);
+
+        // Off again: straight to the surface
+        assert!(engine.set_post_fx(PostFxSettings::default()));
+        assert_eq!(presented(&mut engine, 100, 150), [128, 128, 128, 255]);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn unvalidated_frames_are_fenced_and_checked_for_errors_now_and_then() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let gl = renderer.gl.clone();
+        let mut sync = GpuSync::new(&gl);
+        assert!(sync.validation, "on in debug builds");
+        unsafe {
+            gl.enable(0xffff);
+            assert_eq!(sync.end_frame(&gl, true).error, glow::INVALID_ENUM);
+            sync.validation = false;
+            gl.enable(0xffff);
+            let errors: Vec<u32> = (0..60).map(|_| sync.end_frame(&gl, true).error).collect();
+            assert_eq!(errors.iter().filter(|&&error| error == glow::INVALID_ENUM).count(), 1);
+            assert_eq!(errors.last(), Some(&glow::NO_ERROR), "caught on the 60th frame, not the last");
+            sync.release(&gl);
+        }
+
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        finish_startup(&mut engine);
+        assert!(engine.set_gpu_validation(false));
+        for _ in 0..3 {
+            assert!(engine.render());
+        }
+        assert!(engine.set_gpu_validation(true));
+        assert!(engine.render());
+        assert!(!headless(400, 300).set_gpu_validation(true));
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn frame_stats_carry_gpu_time_per_pass_from_timer_queries() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        if !renderer.gpu_timer.supported {
+            eprintln!("skipping: no timer queries");
+            return;
+        }
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        finish_startup(&mut engine);
+        // Results are read a few frames late; finishing each frame makes sure they're in by then
+        assert!(engine.set_gpu_validation(true));
+        for _ in 0..8 {
+            assert!(engine.render());
+        }
+        let passes = engine.frame_stats().gpu_passes;
+        assert!(passes.clear_ms > 0.0 && passes.scene_ms > 0.0, "{:?}", passes);
+        assert!(passes.ui_ms >= 0.0 && passes.scene_ms < 1000.0, "{:?}", passes);
+
+        // Without a GL context there's nothing to time
+        let mut headless = headless(400, 300);
+        headless.render();
+        assert_eq!(headless.frame_stats().gpu_passes, stats::GpuPassStats::default());
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn trimmed_memory_is_rebuilt_as_the_next_frame_draws() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([128.0 / 255.0, 128.0 / 255.0, 128.0 / 255.0, 1.0]);
+        finish_startup(&mut engine);
+        let rasterized = |engine: &GameEngine| engine.renderer.as_ref().unwrap().pictures.rasterized;
+        let pixel = |engine: &mut GameEngine, x: f32, y: f32| {
+            engine.render();
+            let capture = engine.capture_region(Rect::from_min_size(Pos2::new(x, y), Vec2::splat(1.0)), 1.0).unwrap();
+            capture.pixels
+        };
+        let presented = |engine: &mut GameEngine| {
+            engine.render();
+            let mut pixel = [0u8; 4];
+            let gl = &engine.renderer.as_ref().unwrap().gl;
+            unsafe {
+                gl.read_pixels(300, 150, 1, 1, glow::RGBA, glow::UNSIGNED_BYTE, glow::PixelPackData::Slice(&mut pixel));
+            }
+            pixel
+        };
+        engine.submit_draw_list(&picture_list(7, 0xff0000ff)).unwrap();
+        assert!(engine.set_color_lut(Some(&lut_png(8, |c| c.map(|v| 255 - v)))));
+        assert!(engine.set_post_fx(PostFxSettings { lut_intensity: 1.0, ..Default::default() }));
+        assert_eq!(pixel(&mut engine, 50.0, 30.0), [255, 0, 0, 255]);
+        let graded = presented(&mut engine);
+        assert_eq!(rasterized(&engine), 1);
+
+        // A memory warning keeps what's on screen
+        engine.trim_memory(TrimLevel::Moderate);
+        assert_eq!(engine.vector_cache.len(), 0);
+        assert_eq!(pixel(&mut engine, 50.0, 30.0), [255, 0, 0, 255]);
+        assert_eq!(rasterized(&engine), 1);
+
+        // Backgrounding drops the picture, render targets and shaders; the next frame looks the same
+        engine.trim_memory(TrimLevel::Background);
+        assert_eq!(presented(&mut engine), graded);
+        assert_eq!(rasterized(&engine), 2);
+        assert_eq!(pixel(&mut engine, 50.0, 30.0), [255, 0, 0, 255]);
+        assert_eq!(TrimLevel::from_raw(2), None);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn video_capture_delivers_every_frame_in_order() {
+        unsafe extern "C" fn on_frame(user_data: *mut c_void, pixels: *const u8, width: u32, height: u32, ts: u64) {
+            let frames = &mut *(user_data as *mut Vec<(u32, u32, u64, [u8; 4])>);
+            let first = std::slice::from_raw_parts(pixels, 4).try_into().unwrap();
+            frames.push((width, height, ts, first));
+        }
+
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([0.0, 1.0, 0.0, 1.0]);
+        let mut frames: Vec<(u32, u32, u64, [u8; 4])> = Vec::new();
+        assert!(engine.start_video_capture(on_frame, &mut frames as *mut _ as *mut c_void, 2));
+        for _ in 0..5 {
+            engine.render();
+        }
+        engine.stop_video_capture();
+        // Not recording any more
+        engine.render();
+
+        assert_eq!(frames.len(), 5);
+        assert!(frames.windows(2).all(|pair| pair[0].2 <= pair[1].2));
+        for &(width, height, _, first) in &frames {
+            assert_eq!((width, height), (400, 300));
+            // Top-left corner is background
+            assert_eq!(first, [0, 255, 0, 255]);
+        }
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn render_hooks_draw_between_passes_and_leave_gl_state_alone() {
+        struct Host {
+            gl: Arc<glow::Context>,
+            calls: Vec<(u32, u32, u32)>,
+        }
+        // Records its stage; after the HUD, paints the bottom-left 10x10 pixels blue through a 1x1 viewport
+        unsafe extern "C" fn hook(user_data: *mut c_void, stage: u32, width: u32, height: u32) {
+            let host = &mut *(user_data as *mut Host);
+            host.calls.push((stage, width, height));
+            if stage == render_hook::RENDER_STAGE_AFTER_HUD {
+                let gl = &host.gl;
+                gl.viewport(0, 0, 1, 1);
+                gl.enable(glow::SCISSOR_TEST);
+                gl.scissor(0, 0, 10, 10);
+                gl.clear_color(0.0, 0.0, 1.0, 1.0);
+                gl.clear(glow::COLOR_BUFFER_BIT);
+                gl.use_program(None);
+                gl.bind_texture(glow::TEXTURE_2D, None);
+            }
+        }
+
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        engine.set_clear_color([0.0, 1.0, 0.0, 1.0]);
+        finish_startup(&mut engine);
+        let mut host = Host { gl: engine.renderer.as_ref().unwrap().gl.clone(), calls: Vec::new() };
+        let user_data = &mut host as *mut Host as *mut c_void;
+        assert!(!engine.set_custom_render_hook(3, Some(hook), user_data));
+        for stage in [
+            render_hook::RENDER_STAGE_AFTER_HUD,
+            render_hook::RENDER_STAGE_BEFORE_WORLD,
+            render_hook::RENDER_STAGE_AFTER_WORLD,
+        ] {
+            assert!(engine.set_custom_render_hook(stage, Some(hook), user_data));
+        }
+        // Nothing moves, but the host's drawing might
+        engine.set_render_on_demand(true);
+        assert!(engine.needs_redraw());
+
+        for _ in 0..2 {
+            assert!(engine.render());
+        }
+        assert_eq!(host.calls, [(0, 400, 300), (1, 400, 300), (2, 400, 300)].repeat(2));
+        let gl = &engine.renderer.as_ref().unwrap().gl;
+        let pixel = |x: i32, y: i32| {
+            let mut pixel = [0u8; 4];
+            unsafe { gl.read_pixels(x, y, 1, 1, glow::RGBA, glow::UNSIGNED_BYTE, glow::PixelPackData::Slice(&mut pixel)) };
+            pixel
+        };
+        assert_eq!(pixel(5, 5), [0, 0, 255, 255]);
+        assert_eq!(pixel(15, 5), [0, 255, 0, 255]);
+        let mut viewport = [0; 4];
+        unsafe { gl.get_parameter_i32_slice(glow::VIEWPORT, &mut viewport) };
+        assert_eq!(viewport, [0, 0, 400, 300]);
+
+        for stage in 0..3 {
+            assert!(engine.set_custom_render_hook(stage, None, std::ptr::null_mut()));
+        }
+        engine.render();
+        assert_eq!(host.calls.len(), 6);
+        assert!(!engine.needs_redraw());
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn warmup_front_loads_startup_work() {
+        assert!(!headless(400, 300).warmup());
+
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        assert!(engine.warmup());
+        let timings = engine.init_timings();
+        assert!(timings.renderer_ms > 0.0 && timings.warmup_ms > 0.0);
+        assert_eq!(timings.first_frame_ms, 0.0);
+        // Startup textures were waited for, so the first frame already shows the scene
+        assert!(!engine.display_list().splash);
+        assert!(engine.assets.get(*assets::PLAYER_TEXTURE_ID).is_some());
+
+        engine.render();
+        assert!(engine.init_timings().first_frame_ms >= timings.warmup_ms);
+        assert!(std::iter::from_fn(|| engine.poll_event()).any(|e| matches!(e, GameEvent::FirstMeaningfulFrame)));
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn spikes_are_rate_limited_and_marked_in_the_timeline() {
+        use crate::json::{self, Value};
+
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        // Every frame is a spike; only the first is logged within the interval
+        engine.set_spike_threshold(Duration::from_nanos(1), Duration::from_secs(60));
+        for _ in 0..3 {
+            engine.render();
+        }
+        assert_eq!(engine.spikes.suppressed, 2);
+        engine.set_spike_threshold(Duration::ZERO, Duration::ZERO);
+        engine.render();
+
+        let timeline = json::parse(&engine.export_timeline()).unwrap();
+        let events = timeline.get("traceEvents").and_then(Value::as_array).unwrap();
+        let named = |name: &str| events.iter().filter(|e| e.get("name").and_then(Value::as_str) == Some(name)).count();
+        assert_eq!(named("frame"), 4);
+        assert_eq!(named("spike"), 3);
+        assert_eq!(named("tessellate"), 4);
+        assert_eq!(named("gpu_wait"), 4);
+        let first = events.iter().find(|e| e.get("name").and_then(Value::as_str) == Some("frame")).unwrap();
+        assert_eq!(first.get("args").and_then(|a| a.get("interval_ms")), Some(&Value::Null));
+        assert!(first.get("args").and_then(|a| a.get("draw_calls")).and_then(Value::as_f64).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn poisoned_engine_reports_the_crash_and_refuses_calls() {
+        use crate::json::{self, Value};
+
+        let handle = Instance::into_handle(headless(400, 300));
+        assert_eq!(crate::game_get_status(handle), crate::GAME_STATUS_OK);
+        assert_eq!(crate::game_get_status(std::ptr::null_mut()), crate::GAME_STATUS_INVALID);
+        let engine = unsafe { Instance::engine(handle) };
+        while engine.poll_event().is_some() {}
+        engine.poison(Crash { message: "boom at src/engine.rs:1".to_string(), backtrace: "frames".to_string() });
+        engine.poison(Crash { message: "second".to_string(), backtrace: String::new() });
+
+        assert_eq!(crate::game_get_status(handle), crate::GAME_STATUS_POISONED);
+        crate::game_set_direction(handle, Direction::Right as i32);
+        assert_eq!(crate::game_get_player_x(handle), 0.0);
+        assert_eq!(engine.refused_calls, 2);
+
+        let mut record = crate::events::GameEventRecord::default();
+        assert!(crate::game_poll_event(handle, &mut record));
+        assert_eq!(record.kind, crate::events::EVENT_ENGINE_CRASHED);
+        assert!(!crate::game_poll_event(handle, &mut record));
+
+        let buffer = crate::game_last_crash(handle);
+        let crash = json::parse(&String::from_utf8_lossy(unsafe { std::slice::from_raw_parts(buffer.data, buffer.len) })).unwrap();
+        crate::game_free_buffer(buffer);
+        assert_eq!(crash.get("message").and_then(Value::as_str), Some("boom at src/engine.rs:1"));
+        assert_eq!(crash.get("backtrace").and_then(Value::as_str), Some("frames"));
+        crate::game_destroy(handle);
+    }
+
+    #[test]
+    fn ffi_calls_and_frame_phases_are_traced() {
+        let handle = Instance::into_handle(headless(400, 300));
+        let spans = crate::trace::record(|| {
+            crate::game_update(handle);
+            crate::game_render(handle);
+        });
+        assert_eq!(spans, ["game_update", "update", "game_render", "scene", "tessellate"]);
+        assert_eq!(crate::trace::record(|| crate::game_destroy(handle)), ["game_destroy"]);
+        assert_eq!(crate::trace::function_name("game_engine::game_init::{{closure}}::f"), "game_init");
+    }
+
+    #[test]
+    fn host_draw_list_is_drawn_in_its_layers() {
+        use crate::draw_list::*;
+        use crate::json::{self, numbers, Value};
+
+        let f32s = |out: &mut Vec<u8>, values: &[f32]| values.iter().for_each(|v| out.extend_from_slice(&v.to_le_bytes()));
+        let mut bytes = vec![DRAW_LIST_VERSION, DRAW_LAYER, DRAW_LAYER_BELOW, DRAW_RECT];
+        f32s(&mut bytes, &[10.0, 20.0, 30.0, 40.0]);
+        bytes.extend_from_slice(&0xff0000ffu32.to_le_bytes());
+        f32s(&mut bytes, &[0.0]);
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&[DRAW_LAYER, DRAW_LAYER_ABOVE, DRAW_TRANSFORM]);
+        f32s(&mut bytes, &[1.0, 0.0, 0.0, 1.0, 100.0, 0.0]);
+        bytes.extend_from_slice(&[DRAW_PATH, 0]);
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        f32s(&mut bytes, &[0.0, 0.0, 10.0, 10.0]);
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        f32s(&mut bytes, &[2.0]);
+        bytes.extend_from_slice(&0x00ff00ffu32.to_le_bytes());
+        bytes.push(DRAW_TEXT);
+        f32s(&mut bytes, &[5.0, 5.0, 12.0]);
+        bytes.extend_from_slice(&0xffffffffu32.to_le_bytes());
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(b"hi");
+
+        let mut engine = headless(400, 300);
+        engine.submit_draw_list(&bytes).unwrap();
+        // Truncated or unknown data is rejected and the previous list stays
+        assert!(engine.submit_draw_list(&bytes[..bytes.len() - 1]).is_err());
+        assert!(engine.submit_draw_list(&[DRAW_LIST_VERSION, 99]).is_err());
+        engine.render();
+
+        let list = json::parse(&engine.display_list().to_json()).unwrap();
+        let layers = list.get("layers").and_then(Value::as_array).unwrap();
+        let items = |i: usize| layers[i].get("items").and_then(Value::as_array).unwrap();
+        let below = items(0);
+        assert_eq!(below.len(), 1);
+        assert_eq!(below[0].get("transform"), Some(&numbers(&[1.0, 0.0, 0.0, 1.0, 25.0, 40.0])));
+        assert_eq!(below[0].get("size"), Some(&numbers(&[30.0, 40.0])));
+        assert_eq!(below[0].get("color").and_then(Value::as_str), Some("#ff0000ff"));
+        let above = items(6);
+        assert_eq!(above.len(), 2);
+        assert_eq!(above[0].get("points"), Some(&numbers(&[100.0, 0.0, 110.0, 10.0])));
+        assert_eq!(above[0].get("stroke").and_then(|s| s.get("width")).and_then(Value::as_f64), Some(2.0));
+        assert_eq!(above[1].get("pos"), Some(&numbers(&[105.0, 5.0])));
+        assert_eq!(above[1].get("text").and_then(Value::as_str), Some("hi"));
+
+        engine.submit_draw_list(&[DRAW_LIST_VERSION]).unwrap();
+        assert!(engine.display_list().host.iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn calls_from_other_threads_run_on_the_owner_thread() {
+        let handle = Instance::into_handle(headless(400, 300));
+        let address = handle as usize;
+        let caller = thread::spawn(move || {
+            let handle = address as crate::GameHandle;
+            let sprite = crate::game_spawn_sprite(handle, 10.0, 10.0, 4.0, 4.0, 0xffffffff);
+            (sprite, crate::game_get_sprite_count(handle))
+        });
+        // The owner picks queued calls up at its next engine call
+        while !caller.is_finished() {
+            crate::game_update(handle);
+            thread::sleep(Duration::from_millis(1));
+        }
+        let (sprite, count) = caller.join().unwrap();
+        assert_ne!(sprite, crate::sprites::INVALID_SPRITE);
+        assert_eq!(count, 1);
+
+        // With the owner idle the call times out, returns its default, never runs and says why
+        let idle = thread::spawn(move || {
+            let sprite = crate::game_spawn_sprite(address as crate::GameHandle, 0.0, 0.0, 1.0, 1.0, 0);
+            (sprite, crate::game_last_call_error())
+        });
+        assert_eq!(idle.join().unwrap(), (crate::sprites::INVALID_SPRITE, CallError::TimedOut as i32));
+        assert_eq!(crate::game_get_sprite_count(handle), 1);
+
+        // Calls returning nothing don't wait: queued while the owner is idle, run at its next call
+        let setter = thread::spawn(move || {
+            crate::game_set_clear_color(address as crate::GameHandle, 1.0, 0.0, 0.0, 1.0);
+            crate::game_last_call_error()
+        });
+        assert_eq!(setter.join().unwrap(), CallError::None as i32);
+        assert_eq!(unsafe { Instance::engine(handle) }.background.clear_color, [1.0, 0.0, 0.0, 1.0]);
+        crate::game_destroy(handle);
+    }
+
+    #[test]
+    fn vector_paths_follow_fill_rules_and_dash_patterns() {
+        use crate::vector::{self, FillRule, StrokeStyle, VectorPath};
+
+        let area = |mesh: &egui::epaint::Mesh| -> f32 {
+            mesh.indices.chunks(3).map(|t| {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[t[i] as usize].pos);
+                ((b - a).x * (c - a).y - (b - a).y * (c - a).x).abs() / 2.0
+            }).sum()
+        };
+        // A square inside another, both wound the same way
+        let squares = || {
+            let mut path = VectorPath::default();
+            for (min, max) in [(0.0, 100.0), (25.0, 75.0)] {
+                path.move_to(Pos2::new(min, min));
+                path.line_to(Pos2::new(max, min));
+                path.line_to(Pos2::new(max, max));
+                path.line_to(Pos2::new(min, max));
+                path.close();
+            }
+            path.build()
+        };
+        let non_zero = vector::fill(&squares(), FillRule::NonZero, Color32::RED).unwrap();
+        let even_odd = vector::fill(&squares(), FillRule::EvenOdd, Color32::RED).unwrap();
+        assert!((area(&non_zero) - 10000.0).abs() < 1.0);
+        assert!((area(&even_odd) - 7500.0).abs() < 1.0);
+
+        // 100 pt line, 10 on / 10 off: five dashes of 10 x 2
+        let mut line = VectorPath::default();
+        line.move_to(Pos2::ZERO);
+        line.quad_to(Pos2::new(50.0, 0.0), Pos2::new(100.0, 0.0));
+        let mut style = StrokeStyle::solid(2.0, Color32::WHITE);
+        assert!((area(&vector::stroke(&line.build(), &style).unwrap()) - 200.0).abs() < 1.0);
+        let mut line = VectorPath::default();
+        line.move_to(Pos2::ZERO);
+        line.line_to(Pos2::new(100.0, 0.0));
+        style.dashes = vec![10.0, 10.0];
+        style.dash_offset = 5.0;
+        let dashed = vector::stroke(&line.build(), &style).unwrap();
+        assert!((area(&dashed) - 100.0).abs() < 1.0);
+        assert_eq!(vector::bounds(&dashed).map(|b| b.min.x), Some(0.0));
+
+        // The same through a draw list: a filled, unstroked triangle moved by the transform
+        use crate::draw_list::*;
+        let mut bytes = vec![DRAW_LIST_VERSION, DRAW_TRANSFORM];
+        [1.0f32, 0.0, 0.0, 1.0, 10.0, 0.0].iter().for_each(|v| bytes.extend_from_slice(&v.to_le_bytes()));
+        bytes.push(DRAW_VECTOR);
+        bytes.extend_from_slice(&0xff0000ffu32.to_le_bytes());
+        bytes.push(DRAW_FILL_EVEN_ODD);
+        bytes.extend_from_slice(&0f32.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&[DRAW_CAP_ROUND, DRAW_JOIN_ROUND, 0]);
+        bytes.extend_from_slice(&0f32.to_le_bytes());
+        bytes.extend_from_slice(&4u32.to_le_bytes());
+        for (verb, x, y) in [(DRAW_VERB_MOVE, 0.0f32, 0.0f32), (DRAW_VERB_LINE, 20.0, 0.0), (DRAW_VERB_LINE, 0.0, 20.0)] {
+            bytes.push(verb);
+            bytes.extend_from_slice(&x.to_le_bytes());
+            bytes.extend_from_slice(&y.to_le_bytes());
+        }
+        bytes.push(DRAW_VERB_CLOSE);
+        let mut cache = VectorCache::default();
+        let list = HostDrawList::decode(&bytes, &mut cache, &mut |_, _, _, _| None).unwrap();
+        let DrawCommand::Vector { parts } = &list.layers[DRAW_LAYER_ABOVE as usize][0] else {
+            panic!("expected a vector command");
+        };
+        let mesh = &parts[0].mesh;
+        assert!((area(mesh) - 200.0).abs() < 0.1);
+        assert_eq!(vector::bounds(mesh), Some(Rect::from_min_max(Pos2::new(10.0, 0.0), Pos2::new(30.0, 20.0))));
+        assert!(HostDrawList::decode(&bytes[..bytes.len() - 1], &mut cache, &mut |_, _, _, _| None).is_err());
+    }
+
+    #[test]
+    fn vector_paints_color_and_texture_fills_and_meshes_are_cached() {
+        use crate::draw_list::*;
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+
+        let f32s = |out: &mut Vec<u8>, values: &[f32]| values.iter().for_each(|v| out.extend_from_slice(&v.to_le_bytes()));
+        // A 100 x 10 filled rect
+        let rect = |out: &mut Vec<u8>| {
+            out.push(DRAW_VECTOR);
+            out.extend_from_slice(&0xffffffffu32.to_le_bytes());
+            out.push(DRAW_FILL_NON_ZERO);
+            f32s(out, &[0.0]);
+            out.extend_from_slice(&0u32.to_le_bytes());
+            out.extend_from_slice(&[DRAW_CAP_BUTT, DRAW_JOIN_MITER, 0]);
+            f32s(out, &[0.0]);
+            out.extend_from_slice(&5u32.to_le_bytes());
+            for (verb, x, y) in [(DRAW_VERB_MOVE, 0.0, 0.0), (DRAW_VERB_LINE, 100.0, 0.0), (DRAW_VERB_LINE, 100.0, 10.0), (DRAW_VERB_LINE, 0.0, 10.0)] {
+                out.push(verb);
+                f32s(out, &[x, y]);
+            }
+            out.push(DRAW_VERB_CLOSE);
+        };
+
+        // Red to blue along x, drawn at twice the size: the gradient scales with the path
+        let mut bytes = vec![DRAW_LIST_VERSION, DRAW_TRANSFORM];
+        f32s(&mut bytes, &[2.0, 0.0, 0.0, 2.0, 0.0, 0.0]);
+        bytes.extend_from_slice(&[DRAW_PAINT, DRAW_PAINT_FILL, DRAW_PAINT_LINEAR]);
+        f32s(&mut bytes, &[0.0, 0.0, 100.0, 0.0]);
+        bytes.push(2);
+        f32s(&mut bytes, &[0.0]);
+        bytes.extend_from_slice(&0xff0000ffu32.to_le_bytes());
+        f32s(&mut bytes, &[1.0]);
+        bytes.extend_from_slice(&0x0000ffffu32.to_le_bytes());
+        rect(&mut bytes);
+        // Then the player texture spread over the rect, and a texture that isn't loaded
+        for name in [assets::PLAYER_TEXTURE, "missing"] {
+            bytes.extend_from_slice(&[DRAW_PAINT, DRAW_PAINT_FILL, DRAW_PAINT_TEXTURE]);
+            bytes.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(name.as_bytes());
+            f32s(&mut bytes, &[0.0, 0.0, 100.0, 10.0]);
+            bytes.extend_from_slice(&0xffffffffu32.to_le_bytes());
+            rect(&mut bytes);
+        }
+        engine.submit_draw_list(&bytes).unwrap();
+        assert_eq!((engine.vector_cache.hits, engine.vector_cache.misses), (0, 3));
+
+        let host = engine.display_list().host;
+        let above = &host[DRAW_LAYER_ABOVE as usize];
+        assert_eq!(above.len(), 3);
+        let mesh = |i: usize| match &above[i] {
+            DrawCommand::Vector { parts } => parts.first().map(|p| p.mesh.clone()),
+            _ => panic!("expected a vector command"),
+        };
+        let gradient = mesh(0).unwrap();
+        assert!(gradient.indices.len() / 3 > 100, "gradient fills are subdivided");
+        let color_at = |x: f32| gradient.vertices.iter().find(|v| v.pos == Pos2::new(x, 0.0)).map(|v| v.color);
+        assert_eq!(color_at(0.0), Some(Color32::RED));
+        assert_eq!(color_at(200.0), Some(Color32::BLUE));
+        assert_eq!(color_at(100.0), Some(Color32::RED.lerp_to_gamma(Color32::BLUE, 0.5)));
+
+        let textured = mesh(1).unwrap();
+        assert_eq!(textured.texture_id, engine.assets.get(*assets::PLAYER_TEXTURE_ID).unwrap().id);
+        let corner = textured.vertices.iter().find(|v| v.pos == Pos2::new(200.0, 20.0)).unwrap();
+        assert_eq!(corner.uv, Pos2::new(1.0, 1.0));
+        assert_eq!(mesh(2), None);
+
+        // Resubmitting reuses every mesh; bad stops are rejected
+        engine.submit_draw_list(&bytes).unwrap();
+        assert_eq!((engine.vector_cache.hits, engine.vector_cache.misses), (3, 3));
+        let mut descending = vec![DRAW_LIST_VERSION, DRAW_PAINT, DRAW_PAINT_STROKE, DRAW_PAINT_RADIAL];
+        f32s(&mut descending, &[0.0, 0.0, 10.0]);
+        descending.push(2);
+        for offset in [1.0, 0.0] {
+            f32s(&mut descending, &[offset]);
+            descending.extend_from_slice(&0xffffffffu32.to_le_bytes());
+        }
+        assert!(engine.submit_draw_list(&descending).is_err());
+    }
+
+    #[test]
+    fn paced_renders_hold_the_cap_and_skip_unchanged_frames() {
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+        assert!(!engine.set_target_fps(45));
+        assert!(engine.set_target_fps(30));
+
+        // 60 Hz vsyncs: every other one is due at 30 fps, and a repeated timestamp never renders twice
+        let vsync = |n: u64| 1_000_000_000 + n * 16_666_667;
+        assert!(engine.render_at(vsync(0)));
+        assert!(!engine.render_at(vsync(0)));
+        assert!(!engine.render_at(vsync(1)));
+        // Due, but nothing moved since the last frame
+        assert!(!engine.render_at(vsync(2)));
+        engine.set_clear_color([0.2, 0.4, 0.6, 1.0]);
+        assert!(!engine.render_at(vsync(3)));
+        assert!(engine.render_at(vsync(4)));
+
+        let stats = engine.pacing_stats();
+        assert_eq!(stats.target_fps, 30);
+        assert!((stats.refresh_hz - 60.0).abs() < 0.1, "{}", stats.refresh_hz);
+        assert_eq!((stats.rendered, stats.skipped_paced, stats.skipped_unchanged), (2, 3, 1));
+
+        // A frame drawn outside the pacer, or a new surface, means the next due vsync draws again
+        engine.render();
+        assert!(engine.render_at(vsync(6)));
+        engine.resize(400, 300);
+        assert!(engine.render_at(vsync(8)));
+        assert!(!engine.render_at(vsync(10)));
+    }
+
+    #[test]
+    fn the_frame_loop_renders_display_link_frames_while_running() {
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+        let handle = Instance::into_handle(engine);
+        let vsync = |n: u64| 1_000_000_000 + n * 16_666_667;
+        assert!(!crate::game_loop_frame(handle, vsync(0)));
+        assert!(!crate::game_stop_loop(handle));
+
+        assert!(crate::game_start_loop(handle));
+        assert!(!crate::game_start_loop(handle));
+        assert!(crate::game_loop_frame(handle, vsync(0)));
+        // Paced like game_render_with_timestamp: unchanged frames aren't drawn again
+        assert!(!crate::game_loop_frame(handle, vsync(1)));
+        crate::game_set_clear_color(handle, 0.2, 0.4, 0.6, 1.0);
+        assert!(crate::game_loop_frame(handle, vsync(2)));
+        let engine = unsafe { Instance::engine(handle) };
+        assert_eq!(engine.pacing_stats().rendered, 2);
+
+        assert!(crate::game_stop_loop(handle));
+        assert!(!crate::game_loop_frame(handle, vsync(3)));
+        assert!(crate::game_start_loop(handle));
+        crate::game_destroy(handle);
+        assert!(!crate::frame_loop::is_running(handle as usize));
+    }
+
+    #[test]
+    fn unchanged_frames_reuse_the_last_tessellation() {
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+        let counts = |engine: &GameEngine| (engine.tessellation.hits, engine.tessellation.misses);
+        engine.spawn_sprite(Pos2::new(50.0, 50.0), Vec2::splat(10.0), Color32::RED);
+        assert!(engine.render());
+        let (hits, misses) = counts(&engine);
+        assert!(engine.render());
+        assert!(engine.render());
+        assert_eq!(counts(&engine), (hits + 2, misses));
+
+        // Anything that changes the display list, or a redraw request, tessellates again
+        engine.set_direction(Direction::Right);
+        advance(&mut engine, 50);
+        assert!(engine.render());
+        assert_eq!(counts(&engine), (hits + 2, misses + 1));
+        // Stopping leaves the player where the last frame drew it
+        engine.set_direction(Direction::None);
+        assert!(engine.render());
+        assert_eq!(counts(&engine), (hits + 3, misses + 1));
+        assert!(engine.show_qr(b"hud", 100.0, qr::EcLevel::Medium, 4));
+        assert!(engine.render());
+        assert!(engine.render());
+        assert_eq!(counts(&engine), (hits + 4, misses + 2));
+        // So does a new scale factor
+        engine.set_scale_factor(2.0);
+        assert!(engine.render());
+        assert_eq!(counts(&engine).1, misses + 3);
+    }
+
+    #[test]
+    fn thermal_states_scale_down_frames_effects_and_particles() {
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+        let burst = EmitterConfig {
+            burst: 100,
+            lifetime_min: 5.0,
+            lifetime_max: 5.0,
+            size_start: 4.0,
+            size_end: 4.0,
+            color_start: 0xffffffff,
+            color_end: 0xffffffff,
+            ..Default::default()
+        };
+        let particles_thrown = |engine: &mut GameEngine| {
+            let before = engine.particle_count();
+            engine.spawn_emitter(Pos2::new(200.0, 150.0), burst);
+            engine.particle_count() - before
+        };
+        assert!(engine.set_post_fx(PostFxSettings { vignette_strength: 0.5, vignette_radius: 0.6, ..Default::default() }));
+        assert!(engine.post_fx.enabled());
+        assert_eq!(particles_thrown(&mut engine), 100);
+
+        // Warm: fewer particles, 60 fps at most
+        engine.set_thermal_state(ThermalState::Fair);
+        assert_eq!(particles_thrown(&mut engine), 75);
+        assert_eq!(engine.pacing_stats().target_fps, 60);
+        assert!(engine.set_target_fps(30));
+        assert_eq!(engine.pacing_stats().target_fps, 30);
+        assert!(engine.set_target_fps(0));
+
+        // Hot: 30 fps and no post-processing, though the settings are kept
+        engine.set_thermal_state(ThermalState::Critical);
+        assert_eq!(particles_thrown(&mut engine), 25);
+        assert_eq!(engine.pacing_stats().target_fps, 30);
+        assert!(!engine.post_fx.enabled());
+        assert_eq!(engine.post_fx().vignette_strength, 0.5);
+
+        // Cooled down with battery saver on: like fair
+        engine.set_thermal_state(ThermalState::Nominal);
+        engine.set_low_power_mode(true);
+        assert_eq!(engine.power_degradation(), Degradation { fps_limit: 60, post_fx: true, particles: 0.75 });
+        assert!(engine.post_fx.enabled());
+
+        // The host takes over
+        engine.set_thermal_state(ThermalState::Serious);
+        engine.set_power_scaling(false);
+        assert_eq!(engine.power_degradation(), Degradation::NONE);
+        assert_eq!(engine.pacing_stats().target_fps, 0);
+        assert_eq!(particles_thrown(&mut engine), 100);
+        assert_eq!(ThermalState::from_raw(4), None);
+    }
+
+    #[test]
+    fn on_demand_rendering_draws_only_when_something_changed() {
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+        engine.set_render_on_demand(true);
+        assert!(!engine.needs_redraw());
+        assert!(!engine.render());
+
+        // Commands mark the frame dirty until it is drawn
+        engine.set_clear_color([0.1, 0.2, 0.3, 1.0]);
+        assert!(engine.needs_redraw());
+        assert!(engine.render());
+        assert!(!engine.render());
+        assert!(engine.show_qr(b"redraw", 100.0, qr::EcLevel::Medium, 4));
+        assert!(engine.render());
+        assert!(!engine.needs_redraw());
+
+        // A held direction keeps frames coming until the player stops
+        engine.set_direction(Direction::Right);
+        assert!(engine.needs_redraw());
+        advance(&mut engine, 16);
+        assert!(engine.render());
+        engine.set_direction(Direction::None);
+        advance(&mut engine, 16);
+        assert!(engine.render());
+        assert!(!engine.needs_redraw());
+        assert!(!engine.render());
+
+        // So does a moving sprite
+        let sprite = engine.spawn_sprite(Pos2::new(50.0, 50.0), Vec2::splat(10.0), Color32::RED);
+        assert!(engine.render());
+        assert!(engine.set_sprite_velocity(sprite, Vec2::new(10.0, 0.0)));
+        assert!(engine.needs_redraw());
+
+        // Off, every render draws
+        engine.set_render_on_demand(false);
+        assert!(engine.set_sprite_velocity(sprite, Vec2::ZERO));
+        assert!(engine.render());
+        assert!(engine.render());
+    }
+
+    #[test]
+    fn clips_nest_per_layer_and_masks_wrap_content_in_stencil_callbacks() {
+        use crate::clip::{self, ClipShape};
+        use crate::draw_list::*;
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+
+        let f32s = |out: &mut Vec<u8>, values: &[f32]| values.iter().for_each(|v| out.extend_from_slice(&v.to_le_bytes()));
+        // Scissor to a rect, then mask to a rounded rect inside it, around one red rect
+        let mut bytes = vec![DRAW_LIST_VERSION, DRAW_CLIP, DRAW_CLIP_RECT];
+        f32s(&mut bytes, &[10.0, 10.0, 100.0, 50.0]);
+        bytes.extend_from_slice(&[DRAW_CLIP, DRAW_CLIP_ROUNDED_RECT]);
+        f32s(&mut bytes, &[50.0, 20.0, 100.0, 100.0, 8.0]);
+        bytes.push(DRAW_RECT);
+        f32s(&mut bytes, &[0.0, 0.0, 400.0, 300.0]);
+        bytes.extend_from_slice(&0xff0000ffu32.to_le_bytes());
+        f32s(&mut bytes, &[0.0]);
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&[DRAW_CLIP_POP, DRAW_CLIP_POP]);
+        engine.submit_draw_list(&bytes).unwrap();
+
+        let host = engine.display_list().host;
+        let above = &host[DRAW_LAYER_ABOVE as usize];
+        assert_eq!(above.len(), 5);
+        assert_eq!(above[0], DrawCommand::Clip(ClipShape::Rect(Rect::from_min_size(Pos2::new(10.0, 10.0), Vec2::new(100.0, 50.0)))));
+        assert!(matches!(&above[1], DrawCommand::Clip(ClipShape::Mask(mask))
+            if mask.bounds == Rect::from_min_size(Pos2::new(50.0, 20.0), Vec2::splat(100.0))));
+        let json = engine.display_list().to_json();
+        assert!(json.contains(r#""kind":"clip","shape":"mask""#) && json.contains(r#""kind":"pop_clip""#));
+
+        // The rect is scissored to both clips, between the mask's push and pop callbacks
+        let shapes = engine.run_scene(egui::RawInput::default()).shapes;
+        let is_callback = |s: &egui::epaint::ClippedShape| matches!(s.shape, egui::Shape::Callback(_));
+        let callbacks: Vec<usize> = (0..shapes.len()).filter(|&i| is_callback(&shapes[i])).collect();
+        assert_eq!(callbacks.len(), clip::MASK_SHAPES);
+        let content = &shapes[callbacks[0] + 1];
+        assert_eq!(callbacks[1], callbacks[0] + 2);
+        assert_eq!(content.clip_rect, Rect::from_min_max(Pos2::new(50.0, 20.0), Pos2::new(110.0, 60.0)));
+        // Callbacks are reused, so an unchanged frame compares equal (and paced renders can skip it)
+        assert_eq!(engine.run_scene(egui::RawInput::default()).shapes, shapes);
+
+        // Unclosed clips end with their layer; pops need a clip in the same layer
+        let mut unbalanced = vec![DRAW_LIST_VERSION, DRAW_CLIP, DRAW_CLIP_RECT];
+        f32s(&mut unbalanced, &[0.0, 0.0, 10.0, 10.0]);
+        assert!(engine.submit_draw_list(&unbalanced).is_ok());
+        unbalanced.extend_from_slice(&[DRAW_LAYER, DRAW_LAYER_BELOW, DRAW_CLIP_POP]);
+        assert!(engine.submit_draw_list(&unbalanced).is_err());
+        let mut deep = vec![DRAW_LIST_VERSION];
+        for _ in 0..=clip::MAX_CLIP_DEPTH {
+            deep.extend_from_slice(&[DRAW_CLIP, DRAW_CLIP_RECT]);
+            f32s(&mut deep, &[0.0, 0.0, 10.0, 10.0]);
+        }
+        assert!(engine.submit_draw_list(&deep).is_err());
+
+        // The HUD rounds the QR panel's corners the same way
+        engine.submit_draw_list(&[DRAW_LIST_VERSION]).unwrap();
+        assert!(engine.show_qr(b"clip", 200.0, crate::qr::EcLevel::Medium, 4));
+        let shapes = engine.run_scene(egui::RawInput::default()).shapes;
+        let hud = &shapes[shapes.len() - engine.hud.shape_count()..];
+        assert_eq!(hud.len(), 1 + clip::MASK_SHAPES);
+        assert!(is_callback(&hud[0]) && !is_callback(&hud[1]) && is_callback(&hud[2]));
+        assert_eq!(engine.run_scene(egui::RawInput::default()).shapes, shapes);
+    }
+
+    /// Draw list with picture `id` over (10, 10, 100, 50) holding one rect of `color`
+    fn picture_list(id: u32, color: u32) -> Vec<u8> {
+        use crate::draw_list::*;
+        let f32s = |out: &mut Vec<u8>, values: &[f32]| values.iter().for_each(|v| out.extend_from_slice(&v.to_le_bytes()));
+        let mut bytes = vec![DRAW_LIST_VERSION, DRAW_PICTURE];
+        bytes.extend_from_slice(&id.to_le_bytes());
+        f32s(&mut bytes, &[10.0, 10.0, 100.0, 50.0]);
+        bytes.push(DRAW_RECT);
+        f32s(&mut bytes, &[0.0, 0.0, 400.0, 300.0]);
+        bytes.extend_from_slice(&color.to_le_bytes());
+        f32s(&mut bytes, &[0.0]);
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.push(DRAW_PICTURE_END);
+        bytes
+    }
+
+    #[test]
+    fn pictures_group_commands_keyed_by_content() {
+        use crate::draw_list::*;
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+
+        engine.submit_draw_list(&picture_list(7, 0xff0000ff)).unwrap();
+        let key = |engine: &GameEngine| match &engine.display_list().host[DRAW_LAYER_ABOVE as usize][..] {
+            [DrawCommand::Picture { id: 7, key, bounds, commands, cached: None }] => {
+                assert_eq!(*bounds, Rect::from_min_size(Pos2::new(10.0, 10.0), Vec2::new(100.0, 50.0)));
+                assert_eq!(commands.len(), 1);
+                *key
+            }
+            other => panic!("expected one uncached picture, got {:?}", other),
+        };
+        let red = key(&engine);
+        engine.submit_draw_list(&picture_list(7, 0xff0000ff)).unwrap();
+        assert_eq!(key(&engine), red);
+        engine.submit_draw_list(&picture_list(7, 0x00ff00ff)).unwrap();
+        assert_ne!(key(&engine), red);
+        assert!(engine.display_list().to_json().contains(r#""kind":"picture","id":7"#));
+
+        // Without a renderer the content is drawn directly, clipped to the picture
+        let shapes = engine.run_scene(egui::RawInput::default()).shapes;
+        assert!(!shapes.iter().any(|s| matches!(s.shape, egui::Shape::Callback(_))));
+        let bounds = Rect::from_min_size(Pos2::new(10.0, 10.0), Vec2::new(100.0, 50.0));
+        assert!(shapes.iter().any(|s| s.clip_rect == bounds));
+        assert!(!engine.invalidate_picture(7));
+
+        // Pictures don't nest, change layer, end early or swallow outside clips
+        let mut list = picture_list(1, 0);
+        list.pop();
+        for tail in [&[DRAW_PICTURE][..], &[DRAW_LAYER, DRAW_LAYER_BELOW], &[DRAW_CLIP_POP], &[]] {
+            let mut bytes = list.clone();
+            bytes.extend_from_slice(tail);
+            assert!(engine.submit_draw_list(&bytes).is_err(), "accepted {:?}", tail);
+        }
+        assert!(engine.submit_draw_list(&[DRAW_LIST_VERSION, DRAW_PICTURE_END]).is_err());
+    }
+
+    #[test]
+    fn sprite_materials_carry_uniforms_into_the_display_list() {
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+        let sprite = engine.spawn_sprite(Pos2::new(100.0, 100.0), Vec2::splat(40.0), Color32::RED);
+        assert!(!engine.set_sprite_material(sprite, Some("glow")));
+        // Without a renderer sources are kept uncompiled
+        engine.register_shader("glow", None, "uniform vec4 u_tint;\nvoid main() { FRAG_COLOR = u_tint; }\n").unwrap();
+        assert!(engine.register_shader("", None, "").is_err());
+        assert!(engine.set_sprite_material(sprite, Some("glow")));
+        assert!(!engine.set_sprite_material(crate::sprites::INVALID_SPRITE, Some("glow")));
+
+        engine.set_sprite_uniform(sprite, "u_tint", &[0.0, 1.0, 0.0, 1.0]).unwrap();
+        engine.set_sprite_uniform(sprite, "u_amount", &[0.5]).unwrap();
+        assert!(engine.set_sprite_uniform(sprite, "u_color", &[1.0]).is_err());
+        assert!(engine.set_sprite_uniform(sprite, "u_tint", &[]).is_err());
+        assert!(engine.set_sprite_uniform(sprite, "u_tint", &[f32::NAN]).is_err());
+        let json = engine.display_list().to_json();
+        assert!(json.contains(r#""material":{"shader":"glow","uniforms":{"u_amount":[0.5],"u_tint":[0,1,0,1]}}"#), "{}", json);
+
+        // Lists compare by material content
+        let list = engine.display_list();
+        assert!(engine.display_list() == list);
+        engine.set_sprite_uniform(sprite, "u_amount", &[0.75]).unwrap();
+        assert!(engine.display_list() != list);
+        assert!(engine.set_sprite_material(sprite, None));
+        assert!(!engine.display_list().to_json().contains("material"));
+        assert!(engine.set_sprite_uniform(sprite, "u_amount", &[1.0]).is_err());
+    }
+
+    /// Color LUT PNG of `size` tiles mapping each grid color through `f`
+    fn lut_png(size: u32, f: impl Fn([u8; 3]) -> [u8; 3]) -> Vec<u8> {
+        let step = |i: u32| (i * 255 / (size - 1)) as u8;
+        let image = image::RgbaImage::from_fn(size * size, size, |x, y| {
+            let [r, g, b] = f([step(x % size), step(y), step(x / size)]);
+            image::Rgba([r, g, b, 255])
+        });
+        let mut png = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+        png
+    }
+
+    #[test]
+    fn post_fx_settings_are_validated_and_luts_need_a_tile_strip() {
+        let mut engine = headless(400, 300);
+        assert_eq!(engine.post_fx(), PostFxSettings::default());
+        let vignette = PostFxSettings { vignette_strength: 0.5, vignette_radius: 0.6, ..Default::default() };
+        assert!(engine.set_post_fx(vignette));
+        for bad in [
+            PostFxSettings { bloom_threshold: 1.5, ..vignette },
+            PostFxSettings { bloom_intensity: f32::NAN, ..vignette },
+            PostFxSettings { bloom_spread: f32::INFINITY, ..vignette },
+            PostFxSettings { lut_intensity: -0.1, ..vignette },
+        ] {
+            assert!(!engine.set_post_fx(bad));
+        }
+        assert_eq!(engine.post_fx(), vignette);
+
+        assert!(engine.set_color_lut(Some(&lut_png(4, |c| c))));
+        assert!(engine.set_color_lut(None));
+        assert!(!engine.set_color_lut(Some(b"not a png")));
+        let mut square = Vec::new();
+        image::RgbaImage::new(4, 4).write_to(&mut std::io::Cursor::new(&mut square), image::ImageFormat::Png).unwrap();
+        assert!(!engine.set_color_lut(Some(&square)));
+        // Grading without a LUT is a no-op rather than an error
+        assert!(engine.set_post_fx(PostFxSettings { lut_intensity: 1.0, ..Default::default() }));
+        assert!(!engine.post_fx.enabled());
+    }
+
+    #[test]
+    fn draw_list_text_is_shaped_with_bidi_and_font_fallback() {
+        use crate::draw_list::*;
+
+        let fonts = "/usr/share/fonts/truetype/dejavu";
+        let read = |name: &str| std::fs::read(format!("{}/{}.ttf", fonts, name));
+        let (Ok(sans), Ok(mono)) = (read("DejaVuSans"), read("DejaVuSansMono")) else {
+            eprintln!("skipping: DejaVu fonts not installed");
+            return;
+        };
+        // Atlas position of each glyph drawn for `text`, left to right
+        let shaped = |engine: &mut GameEngine, text: &str| -> Option<Vec<Pos2>> {
+            let mut bytes = vec![DRAW_LIST_VERSION, DRAW_TEXT];
+            [10.0f32, 10.0, 20.0].iter().for_each(|v| bytes.extend_from_slice(&v.to_le_bytes()));
+            bytes.extend_from_slice(&0xffffffffu32.to_le_bytes());
+            bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(text.as_bytes());
+            engine.submit_draw_list(&bytes).unwrap();
+            let DrawCommand::Text { glyphs, .. } = &engine.host_draws.layers[DRAW_LAYER_ABOVE as usize][0] else {
+                panic!("expected a text command");
+            };
+            glyphs.as_ref().map(|meshes| meshes.iter().flat_map(|mesh| mesh.vertices.chunks(4)).map(|quad| quad[0].uv).collect())
+        };
+
+        let mut engine = headless(400, 300);
+        assert_eq!(shaped(&mut engine, "office").is_some(), cfg!(feature = "debug-overlay"));
+        assert!(engine.register_font(b"not a font").is_err());
+
+        // Mono has no ligatures and no Hebrew (one .notdef box until a fallback has it)
+        engine.register_font(&mono).unwrap();
+        assert_eq!(shaped(&mut engine, "office").unwrap().len(), 6);
+        let missing = shaped(&mut engine, "ש").unwrap();
+        engine.register_font(&sans).unwrap();
+        let shin = shaped(&mut engine, "ש").unwrap();
+        assert_eq!(shin.len(), 1);
+        assert_ne!(shin, missing);
+        // Lam alef is one glyph (Arabic shaping), not two
+        assert_eq!(shaped(&mut engine, "لا").unwrap().len(), 1);
+
+        // Hebrew runs right to left after the Latin ("שלום" shows as "םולש"), space drawing nothing
+        let line = shaped(&mut engine, "ab שלום").unwrap();
+        let letters: Vec<Pos2> = ["a", "b", "ם", "ו", "ל", "ש"].iter().map(|c| shaped(&mut engine, c).unwrap()[0]).collect();
+        assert_eq!(line, letters);
+
+        // A right-to-left app (forced paragraph direction) puts the Latin run on the right instead
+        engine.set_text_direction(TextDirection::RightToLeft);
+        assert!(engine.text_is_stale());
+        let line = shaped(&mut engine, "ab שלום").unwrap();
+        assert_eq!(line, [&letters[2..], &letters[..2]].concat());
+        engine.set_text_direction(TextDirection::Auto);
+        assert_eq!(shaped(&mut engine, "ab שלום").unwrap(), letters);
+
+        // Sans first: "ffi" is a ligature
+        let mut engine = headless(400, 300);
+        engine.register_font(&sans).unwrap();
+        assert_eq!(shaped(&mut engine, "office").unwrap().len(), 4);
+        let list = engine.display_list().to_json();
+        assert!(list.contains("\"glyphs\":4"), "{}", list);
+    }
+
+    #[test]
+    fn glyph_atlas_grows_evicts_cold_pages_and_rerasterizes_after_trims() {
+        use crate::draw_list::*;
+        use crate::text::{MAX_PAGES, PAGE_SIZE};
+
+        let Ok(font) = std::fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf") else {
+            eprintln!("skipping: DejaVu Sans not installed");
+            return;
+        };
+        let letters: String = ('A'..='Z').chain('a'..='z').collect();
+        let submit = |engine: &mut GameEngine, size: f32| {
+            let mut bytes = vec![DRAW_LIST_VERSION, DRAW_TEXT];
+            [0.0f32, 0.0, size].iter().for_each(|v| bytes.extend_from_slice(&v.to_le_bytes()));
+            bytes.extend_from_slice(&0xffffffffu32.to_le_bytes());
+            bytes.extend_from_slice(&(letters.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(letters.as_bytes());
+            engine.submit_draw_list(&bytes).unwrap();
+        };
+        // Glyphs drawn by the list, and the atlas pages they come from
+        let drawn = |engine: &GameEngine| {
+            let DrawCommand::Text { glyphs: Some(meshes), .. } = &engine.host_draws.layers[DRAW_LAYER_ABOVE as usize][0] else {
+                panic!("expected shaped text");
+            };
+            (meshes.iter().map(|mesh| mesh.vertices.len() / 4).sum::<usize>(), meshes.len())
+        };
+
+        let mut engine = headless(400, 300);
+        engine.register_font(&font).unwrap();
+        submit(&mut engine, 150.0);
+        let (glyphs, pages) = drawn(&engine);
+        assert_eq!(glyphs, letters.len());
+        assert!(pages > 1, "large text should span pages");
+        assert_eq!(engine.glyph_atlas_stats().pages, pages as u32);
+
+        // Every size is new glyphs: the atlas grows to its limit, then reuses the coldest pages
+        for size in [100.0, 110.0, 120.0, 130.0, 140.0, 160.0] {
+            submit(&mut engine, size);
+            assert_eq!(drawn(&engine).0, letters.len());
+        }
+        let stats = engine.glyph_atlas_stats();
+        assert_eq!(stats.pages, MAX_PAGES as u32);
+        assert!(stats.evicted > 0);
+        assert!(stats.occupancy > 0.0 && stats.occupancy <= 1.0);
+        assert_eq!(stats.texture_bytes, (MAX_PAGES * PAGE_SIZE * PAGE_SIZE * 4) as u64);
+
+        // Trimming keeps what's on screen, and the list stays valid
+        let visible = drawn(&engine).1;
+        assert_eq!(engine.trim_glyph_atlas(true), MAX_PAGES - visible);
+        assert_eq!(engine.glyph_atlas_stats().pages, visible as u32);
+        assert_eq!(engine.trim_glyph_atlas(true), 0);
+
+        // Freeing everything re-rasterizes the list's glyphs when it's next drawn
+        let rasterized = engine.glyph_atlas_stats().rasterized;
+        assert_eq!(engine.trim_glyph_atlas(false), visible);
+        assert_eq!(engine.glyph_atlas_stats().pages, 0);
+        assert!(engine.needs_redraw());
+        engine.render();
+        let stats = engine.glyph_atlas_stats();
+        // Packed afresh rather than scattered over reused pages
+        assert!(stats.pages > 0 && stats.pages <= visible as u32);
+        assert_eq!(stats.rasterized, rasterized + letters.len() as u64);
+        assert_eq!(drawn(&engine).0, letters.len());
+    }
+
+    #[test]
+    fn text_nodes_align_on_their_origin_and_relayout_when_changed() {
+        let Ok(font) = std::fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf") else {
+            eprintln!("skipping: DejaVu Sans not installed");
+            return;
+        };
+        let mut engine = headless(400, 300);
+        engine.register_font(&font).unwrap();
+        assert!(engine.create_text_node("0", 0.0, Color32::WHITE, Align2::CENTER_CENTER).is_err());
+        let score = engine.create_text_node("0", 32.0, Color32::WHITE, Align2::RIGHT_TOP).unwrap();
+        let at = Transform { translation: Vec2::new(380.0, 20.0), ..Default::default() };
+        assert!(engine.set_node_transform(score, at));
+        let title = engine.create_text_node("Game\nOver", 24.0, Color32::RED, Align2::CENTER_CENTER).unwrap();
+        assert!(engine.set_node_transform(title, Transform { translation: Vec2::new(200.0, 150.0), ..Default::default() }));
+        assert!(engine.needs_redraw());
+        engine.render();
+
+        // World bounds of a text node's glyphs, and how many it draws
+        let drawn = |engine: &GameEngine, id: NodeId| {
+            let list = engine.display_list();
+            let node = list.nodes.iter().find(|node| node.node == id).unwrap();
+            let NodeVisual::Text(Label { glyphs: Some(glyphs), .. }) = &node.visual else {
+                panic!("expected shaped text");
+            };
+            let bounds = glyphs.iter().map(|mesh| mesh.calc_bounds()).fold(Rect::NOTHING, |a, b| a.union(b));
+            (node.world.bounds(bounds), glyphs.iter().map(|mesh| mesh.vertices.len() / 4).sum::<usize>())
+        };
+        let (bounds, glyphs) = drawn(&engine, score);
+        assert_eq!(glyphs, 1);
+        assert!(bounds.right() <= 380.0 && bounds.right() > 370.0, "{:?}", bounds);
+        assert!(bounds.top() >= 20.0);
+        let (bounds, glyphs) = drawn(&engine, title);
+        assert_eq!(glyphs, 8);
+        assert!((bounds.center().x - 200.0).abs() < 2.0 && (bounds.center().y - 150.0).abs() < 8.0, "{:?}", bounds);
+
+        // Growing the score keeps it right-aligned; setting the same text again lays nothing out
+        assert!(engine.set_node_text(score, "1200"));
+        assert!(engine.text_is_stale());
+        engine.render();
+        let (bounds, glyphs) = drawn(&engine, score);
+        assert_eq!(glyphs, 4);
+        assert!(bounds.right() <= 380.0 && bounds.left() < 340.0, "{:?}", bounds);
+        assert!(engine.set_node_text(score, "1200"));
+        assert!(!engine.text_is_stale());
+        assert!(!engine.set_node_text(engine.player_node(), "player"));
+
+        let list = engine.display_list().to_json();
+        assert!(list.contains("\"kind\":\"text\""), "{}", list);
+        assert!(list.contains("\"text\":\"1200\""), "{}", list);
+        assert!(list.contains("\"align\":[1,0]"), "{}", list);
+    }
+
+    #[test]
+    fn safe_area_insets_keep_the_hud_and_player_clear_of_notches() {
+        let mut engine = headless(800, 600);
+        assert!(engine.set_scale_factor(2.0));
+        assert!(!engine.set_safe_area_insets(Margin { top: -1.0, ..Margin::ZERO }));
+        assert!(!engine.set_safe_area_insets(Margin { left: f32::NAN, ..Margin::ZERO }));
+        // A 20 point notch at the top and a 10 point home indicator at the bottom
+        assert!(engine.set_safe_area_insets(Margin { top: 40.0, bottom: 20.0, left: 0.0, right: 0.0 }));
+        assert_eq!(engine.player_position(), Pos2::new(200.0, 150.0));
+        let list = engine.display_list();
+        assert_eq!(list.safe_area, Rect::from_min_max(Pos2::new(0.0, 20.0), Pos2::new(400.0, 290.0)));
+
+        // Widgets are anchored to the safe area's edges, and the QR code centered in it
+        let score = engine.create_ui_label("0", 20.0, Color32::WHITE, Align2::RIGHT_TOP, Vec2::splat(10.0)).unwrap();
+        let bar = engine
+            .create_ui_progress_bar(Color32::GREEN, Align2::CENTER_BOTTOM, Vec2::ZERO, Vec2::new(100.0, 8.0))
+            .unwrap();
+        assert!(engine.show_qr(b"notch", 100.0, qr::EcLevel::Low, 4));
+        let list = engine.display_list();
+        let rect = |id| list.ui.iter().find(|w| w.widget == id).unwrap().rect;
+        assert_eq!((rect(score).right(), rect(score).top()), (390.0, 30.0));
+        assert_eq!(rect(bar), Rect::from_min_size(Pos2::new(150.0, 282.0), Vec2::new(100.0, 8.0)));
+        assert!((list.qr.unwrap().center().y - 155.0).abs() <= 1.0, "{:?}", list.qr);
+
+        // The player stops short of the notch
+        let player = engine.player_node();
+        assert!(engine.set_player_direction(player, Direction::Up));
+        advance(&mut engine, 1000);
+        assert_eq!(engine.player_position().y, 20.0 + engine.player_size() / 2.0);
+
+        // and is pulled out from under insets that grow over it
+        assert!(engine.set_safe_area_insets(Margin { left: 300.0, ..Margin::ZERO }));
+        assert_eq!(engine.player_position().x, 150.0 + engine.player_size() / 2.0);
+    }
+
+    #[test]
+    fn rotations_remap_positions_to_the_new_world_size() {
+        use crate::orientation::{Orientation, RotationPolicy};
+
+        let mut engine = headless(400, 800);
+        engine.set_orientation(Orientation::Portrait);
+        let sprite = engine.spawn_sprite(Pos2::new(100.0, 200.0), Vec2::splat(10.0), Color32::RED);
+        let sprite_at = |engine: &GameEngine| engine.sprites.get(sprite).unwrap().position;
+        assert_eq!(engine.player_position(), Pos2::new(200.0, 400.0));
+
+        // Same place relative to the world's edges once the rotated surface arrives
+        engine.set_orientation(Orientation::Landscape);
+        assert_eq!(engine.player_position(), Pos2::new(200.0, 400.0));
+        engine.resize(800, 400);
+        assert_eq!(engine.player_position(), Pos2::new(400.0, 200.0));
+        assert_eq!(sprite_at(&engine), Pos2::new(200.0, 100.0));
+
+        // Other resizes (split screen, keyboards) leave positions alone
+        engine.resize(800, 300);
+        assert_eq!(engine.player_position(), Pos2::new(400.0, 200.0));
+        assert_eq!(sprite_at(&engine), Pos2::new(200.0, 100.0));
+
+        // Clamping only pulls the player back inside
+        engine.resize(800, 400);
+        engine.set_rotation_policy(RotationPolicy::Clamp);
+        engine.set_orientation(Orientation::Portrait);
+        engine.resize(400, 800);
+        assert_eq!(engine.player_position(), Pos2::new(400.0 - engine.player_size() / 2.0, 200.0));
+        assert_eq!(sprite_at(&engine), Pos2::new(200.0, 100.0));
+    }
+
+    #[test]
+    fn ui_widgets_anchor_to_screen_edges_and_buttons_take_their_taps() {
+        use crate::events::EVENT_BUTTON_PRESSED;
+        use crate::ui::WidgetKind;
+
+        let mut engine = headless(400, 300);
+        assert!(engine.create_ui_progress_bar(Color32::GREEN, Align2::LEFT_BOTTOM, Vec2::splat(20.0), Vec2::ZERO).is_err());
+        let score = engine.create_ui_label("0", 20.0, Color32::WHITE, Align2::RIGHT_TOP, Vec2::splat(10.0)).unwrap();
+        let health = engine
+            .create_ui_progress_bar(Color32::GREEN, Align2::LEFT_BOTTOM, Vec2::splat(20.0), Vec2::new(100.0, 8.0))
+            .unwrap();
+        // Over the player, which starts in the middle of the screen
+        let pause = engine
+            .create_ui_button("Pause", 16.0, Color32::WHITE, Color32::BLUE, Align2::CENTER_CENTER, Vec2::ZERO, Vec2::new(100.0, 40.0))
+            .unwrap();
+        assert!(engine.set_ui_progress(health, 1.5));
+        assert!(!engine.set_ui_progress(score, 0.5));
+        assert!(!engine.set_ui_text(health, "full"));
+
+        let rect = |engine: &GameEngine, id: WidgetId| engine.display_list().ui.iter().find(|w| w.widget == id).map(|w| w.rect);
+        let label = rect(&engine, score).unwrap();
+        assert_eq!((label.right(), label.top()), (390.0, 10.0));
+        assert_eq!(rect(&engine, health), Some(Rect::from_min_size(Pos2::new(20.0, 272.0), Vec2::new(100.0, 8.0))));
+        assert_eq!(rect(&engine, pause), Some(Rect::from_center_size(Pos2::new(200.0, 150.0), Vec2::new(100.0, 40.0))));
+
+        let presses = |engine: &mut GameEngine| {
+            std::iter::from_fn(|| engine.poll_event())
+                .map(|event| event.to_record())
+                .filter(|r| r.kind == EVENT_BUTTON_PRESSED)
+                .map(|r| r.id)
+                .collect::<Vec<_>>()
+        };
+        // A tap on the button is the UI's: the player underneath isn't dragged
+        engine.touch(200.0, 150.0, TouchAction::Down);
+        let held = engine.display_list().ui.into_iter().find(|w| w.widget == pause).unwrap();
+        assert!(matches!(held.kind, WidgetKind::Button { fill, .. } if fill != Color32::BLUE));
+        engine.touch(220.0, 150.0, TouchAction::Move);
+        engine.touch(220.0, 150.0, TouchAction::Up);
+        assert_eq!(presses(&mut engine), [pause]);
+        assert_eq!(engine.player_position(), Pos2::new(200.0, 150.0));
+
+        // Sliding off before lifting cancels the press
+        engine.touch(200.0, 150.0, TouchAction::Down);
+        engine.touch(200.0, 250.0, TouchAction::Move);
+        engine.touch(200.0, 250.0, TouchAction::Up);
+        assert!(presses(&mut engine).is_empty());
+
+        // Hidden buttons let touches through to the game
+        assert!(engine.set_ui_visible(pause, false));
+        assert_eq!(rect(&engine, pause), None);
+        engine.touch(200.0, 150.0, TouchAction::Down);
+        engine.touch(250.0, 150.0, TouchAction::Move);
+        engine.touch(250.0, 150.0, TouchAction::Up);
+        assert!(presses(&mut engine).is_empty());
+        assert_eq!(engine.player_position(), Pos2::new(250.0, 150.0));
+
+        assert!(engine.set_ui_text(score, "1200"));
+        let list = engine.display_list().to_json();
+        assert!(list.contains("\"kind\":\"label\""), "{}", list);
+        assert!(list.contains("\"text\":\"1200\""), "{}", list);
+        assert!(list.contains("\"kind\":\"progress\""), "{}", list);
+        assert!(engine.remove_ui_widget(pause));
+        assert!(!engine.remove_ui_widget(pause));
+        assert!(!engine.set_ui_visible(pause, true));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn thread_hints_reach_task_threads() {
+        use std::sync::mpsc;
+
+        // Nice value of the calling thread (field 19 of its stat line)
+        fn nice() -> i32 {
+            let stat = std::fs::read_to_string("/proc/thread-self/stat").unwrap();
+            stat[stat.rfind(')').unwrap() + 2..].split(' ').nth(16).unwrap().parse().unwrap()
+        }
+
+        let mut engine = headless(100, 100);
+        let (go_tx, go_rx) = mpsc::channel::<()>();
+        let (nice_tx, nice_rx) = mpsc::channel();
+        engine
+            .tasks
+            .spawn("nice-probe", move |_| {
+                go_rx.recv().unwrap();
+                nice_tx.send(nice()).unwrap();
+            })
+            .unwrap();
+
+        // Lowest priority is always allowed, so this works without privileges
+        assert!(engine.set_thread_hints(ThreadHints { affinity_mask: 0, priority: 19 }));
+        go_tx.send(()).unwrap();
+        assert_eq!(nice_rx.recv_timeout(Duration::from_secs(5)).unwrap(), 19);
+    }
+
+    /// Render until the built-in textures are uploaded and the splash is gone
+    fn finish_startup(engine: &mut GameEngine) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while engine.splash.is_showing() && Instant::now() < deadline {
+            engine.render();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert!(!engine.splash.is_showing(), "startup textures never loaded");
+    }
+
+    #[test]
+    fn splash_covers_startup_until_player_texture_loads() {
+        let mut engine = headless(400, 300);
+        assert!(engine.display_list().splash);
+        finish_startup(&mut engine);
+        assert!(!engine.display_list().splash);
+        assert!(engine.assets.get(*assets::PLAYER_TEXTURE_ID).is_some());
+        // The engine's own load doesn't surface as a host TextureReady
+        assert!(!std::iter::from_fn(|| engine.poll_event()).any(|e| matches!(e, GameEvent::TextureReady { .. })));
+    }
+
+    #[test]
+    fn display_list_describes_the_frame() {
+        use crate::json::{self, Value};
+
+        let mut engine = headless(400, 300);
+        engine.set_background_gradient(Some((Color32::RED, Color32::BLUE)));
+        let sprite = engine.spawn_sprite(Pos2::new(50.0, 60.0), Vec2::new(10.0, 20.0), Color32::GREEN);
+        let node = engine.create_node(Vec2::new(30.0, 30.0), Color32::WHITE);
+        let transform = Transform { translation: Vec2::new(100.0, 0.0), ..Default::default() };
+        assert!(engine.set_node_transform(node, transform));
+        assert!(engine.set_node_z(node, 5));
+
+        let list = json::parse(&engine.display_list().to_json()).unwrap();
+        let numbers = |values: &[f64]| Value::Array(values.iter().map(|&v| Value::Number(v)).collect());
+        assert_eq!(list.get("screen"), Some(&numbers(&[400.0, 300.0])));
+        let gradient = list.get("background").and_then(|b| b.get("gradient")).unwrap();
+        assert_eq!(gradient.get("top").and_then(Value::as_str), Some("#ff0000ff"));
+
+        let layers = list.get("layers").and_then(Value::as_array).unwrap();
+        let names: Vec<_> = layers.iter().filter_map(|l| l.get("name")?.as_str()).collect();
+        assert_eq!(names, ["host_below", "tiles", "obstacles", "sprites", "scene", "particles", "host_above", "hud"]);
+        let items = |i: usize| layers[i].get("items").and_then(Value::as_array).unwrap();
+
+        let sprites = items(3);
+        assert_eq!(sprites.len(), 1);
+        assert_eq!(sprites[0].get("id").and_then(Value::as_f64), Some(sprite as f64));
+        assert_eq!(sprites[0].get("rect"), Some(&numbers(&[45.0, 50.0, 10.0, 20.0])));
+
+        // Player (z 0) first, then the node above it
+        let scene = items(4);
+        assert_eq!(scene.len(), 2);
+        assert_eq!(scene[0].get("node").and_then(Value::as_f64), Some(engine.player_node() as f64));
+        assert_eq!(scene[1].get("node").and_then(Value::as_f64), Some(node as f64));
+        assert_eq!(scene[1].get("z").and_then(Value::as_f64), Some(5.0));
+        assert_eq!(scene[1].get("kind").and_then(Value::as_str), Some("rect"));
+        assert_eq!(scene[1].get("transform"), Some(&numbers(&[1.0, 0.0, 0.0, 1.0, 100.0, 0.0])));
+        assert_eq!(scene[1].get("color").and_then(Value::as_str), Some("#ffffffff"));
+        assert!(items(5).is_empty());
+        assert!(items(7).is_empty());
+
+        assert!(engine.show_qr(b"hud", 100.0, qr::EcLevel::Low, 4));
+        let list = json::parse(&engine.display_list().to_json()).unwrap();
+        let hud = &list.get("layers").and_then(Value::as_array).unwrap()[7];
+        assert_eq!(hud.get("items").and_then(Value::as_array).map(|items| items.len()), Some(1));
+    }
+
+    #[test]
+    fn snapshots_hand_over_the_newest_and_interpolate_between_steps() {
+        let (mut publisher, mut latest) = crate::simulation::triple_buffer();
+        assert_eq!(latest.take(), None);
+        publisher.publish(1);
+        publisher.publish(2);
+        assert_eq!(latest.take(), Some(2));
+        assert_eq!(latest.take(), None);
+        publisher.publish(3);
+        assert_eq!(latest.take(), Some(3));
+
+        let mut engine = headless(400, 300);
+        let sprite = engine.spawn_sprite(Pos2::new(100.0, 100.0), Vec2::splat(20.0), Color32::RED);
+        let before = engine.display_list_at(1.0);
+        engine.set_sprite_position(sprite, Pos2::new(200.0, 100.0));
+        engine.set_camera(Pos2::new(250.0, 150.0), 2.0, 0.0);
+        let after = engine.display_list_at(1.0);
+
+        let halfway = before.interpolate(&after, 0.5);
+        assert_eq!(halfway.sprites[0].1.center(), Pos2::new(150.0, 100.0));
+        assert_eq!(halfway.camera, before.camera.lerp(&after.camera, 0.5));
+        assert!(halfway.nodes == after.nodes, "the player didn't move");
+        assert!(before.interpolate(&after, 1.0) == after);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    fn simulation_thread_steps_the_engine_while_frames_draw_its_snapshots() {
+        let renderer = match Renderer::headless(400, 300) {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        let mut engine = GameEngine::new(Some(renderer), 400, 300);
+        finish_startup(&mut engine);
+        engine.set_mode(GameMode::Auto);
+        advance(&mut engine, 20);
+        let handle = Instance::into_handle(engine);
+        // Lowest priority is always allowed, so this works without privileges
+        assert!(crate::game_set_thread_hints(handle, 0, 19));
+
+        assert!(crate::game_set_simulation_thread(handle, true));
+        assert!(crate::game_set_simulation_thread(handle, true), "already running");
+        // Calls from this thread now wait for the simulation thread's next step
+        let start = crate::game_get_player_x(handle);
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut drawn = false;
+        while !drawn && Instant::now() < deadline {
+            drawn = crate::game_render(handle);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(drawn, "no snapshot arrived");
+        std::thread::sleep(Duration::from_millis(50));
+        assert_ne!(crate::game_get_player_x(handle), start, "the player didn't move without game_update");
+        assert_eq!(crate::game_get_status(handle), crate::GAME_STATUS_OK);
+
+        // It's one of the instance's task threads, so the thread hints reach it
+        #[cfg(target_os = "linux")]
+        {
+            let nice = std::fs::read_dir("/proc/self/task").unwrap().find_map(|task| {
+                let dir = task.ok()?.path();
+                if std::fs::read_to_string(dir.join("comm")).ok()?.trim() != "game-simulation" {
+                    return None;
+                }
+                let stat = std::fs::read_to_string(dir.join("stat")).ok()?;
+                stat[stat.rfind(')')? + 2..].split(' ').nth(16)?.parse::<i32>().ok()
+            });
+            assert_eq!(nice, Some(19));
+        }
+
+        assert!(!crate::game_set_simulation_thread(handle, false));
+        let engine = unsafe { Instance::engine(handle) };
+        assert!(engine.renderer.is_some());
+        assert!(engine.render());
+        crate::game_destroy(handle);
+    }
+
+    /// Counts the calling thread's heap allocations, for the hot path tests
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            std::alloc::System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            std::alloc::System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            std::alloc::System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// Heap allocations `f` made on this thread
+    fn allocations(f: impl FnOnce()) -> u64 {
+        let before = ALLOCATIONS.with(|count| count.get());
+        f();
+        ALLOCATIONS.with(|count| count.get()) - before
+    }
+
+    #[test]
+    fn steady_state_frames_do_not_allocate() {
+        let mut engine = headless(400, 300);
+        finish_startup(&mut engine);
+        // Obstacles from a level and a tilemap wall for the player to bounce off
+        engine.start_level(7);
+        let wall = r#"{
+            "orientation": "orthogonal", "tilewidth": 20, "tileheight": 100, "width": 1, "height": 1,
+            "tilesets": [{"firstgid": 1, "image": "wall.png", "imagewidth": 20, "imageheight": 100,
+                "tilewidth": 20, "tileheight": 100, "columns": 1, "tilecount": 1}],
+            "layers": [{"type": "tilelayer", "name": "collision", "width": 1, "height": 1, "data": [1]}]
+        }"#;
+        assert_ne!(engine.load_tilemap(wall.as_bytes(), Pos2::new(300.0, 100.0), 1.0), INVALID_TILEMAP);
+        engine.set_mode(GameMode::Auto);
+        let sprites: Vec<_> = (0..20)
+            .map(|i| {
+                let sprite = engine.spawn_sprite(Pos2::new(20.0 * i as f32, 100.0), Vec2::splat(16.0), Color32::RED);
+                engine.add_collider(collision::Owner::Sprite(sprite), collision::Shape::Box(Vec2::splat(16.0)), Vec2::ZERO);
+                sprite
+            })
+            .collect();
+        // Neighbours pass through each other and back, so the scene stays within the same cells
+        let mut frames = 0;
+        let mut vsync_ns = 0;
+        // Allocations while updating and rendering, and whether the player bounced
+        let mut frame = |engine: &mut GameEngine, paced: bool| {
+            if frames % 120 == 0 {
+                let speed = if frames % 240 == 0 { 30.0 } else { -30.0 };
+                for (i, &sprite) in sprites.iter().enumerate() {
+                    engine.set_sprite_velocity(sprite, Vec2::new(if i % 2 == 0 { speed } else { -speed }, 0.0));
+                }
+            }
+            frames += 1;
+            vsync_ns += 16_666_667;
+            let mut bounced = false;
+            let update = allocations(|| {
+                advance(engine, 16);
+                let list = engine.display_list();
+                engine.recycle_display_list(list);
+                while let Some(event) = engine.poll_event() {
+                    bounced |= matches!(event, GameEvent::Bounce { .. });
+                }
+            });
+            let render = allocations(|| {
+                if paced {
+                    assert!(engine.render_at(vsync_ns));
+                } else {
+                    engine.render();
+                }
+            });
+            (update, render, bounced)
+        };
+        // Warm up until every pool and queue has grown to what the scene needs
+        for _ in 0..300 {
+            frame(&mut engine, true);
+        }
+
+        let (mut bounces, mut paced) = (0, 0);
+        let mut bounced_before = false;
+        for _ in 0..120 {
+            let (update, render, bounced) = frame(&mut engine, true);
+            // rapier allocates for a new contact, and for the step after it, whose body resolve_obstacles moved
+            if !(cfg!(feature = "physics") && (bounced || bounced_before)) {
+                assert_eq!(update, 0, "allocations in a steady-state update");
+            }
+            bounces += bounced as u32;
+            bounced_before = bounced;
+            paced += render;
+        }
+        assert!(bounces > 0, "the player never reached an obstacle or wall");
+
+        // egui allocates in every pass and tessellation, but pacing adds nothing to it
+        let unpaced: u64 = (0..120).map(|_| frame(&mut engine, false).1).sum();
+        assert_eq!(paced, unpaced, "allocations in 120 paced frames and 120 unpaced ones");
+    }
+
+    #[test]
+    fn headless_render_and_capture_are_safe() {
+        let mut engine = headless(800, 600);
+        assert_eq!(engine.trigger_gpu_capture(), None);
+        engine.render();
+        assert!(engine.capture_region(Rect::from_min_size(Pos2::ZERO, Vec2::splat(10.0)), 1.0).is_err());
+    }
+}
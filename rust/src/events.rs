@@ -0,0 +1,273 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::haptics::HapticPattern;
+use crate::scoreboard::Stat;
+use crate::stats::EventPathStats;
+
+/// Maximum queued events; oldest events are dropped when the host stops polling
+const MAX_QUEUED_EVENTS: usize = 256;
+
+/// Surface the player bounced off
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u32)]
+pub enum Surface {
+    Left = 0,
+    Right = 1,
+    Top = 2,
+    Bottom = 3,
+    Obstacle = 4,
+}
+
+/// Events sent from the engine to the host
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    /// Player bounced off a wall or obstacle at (x, y) with the given impact speed
+    Bounce { surface: Surface, x: f32, y: f32, speed: f32 },
+    /// A node's Once animation reached its last frame
+    AnimationFinished { node: u64 },
+    /// An async texture load finished and the texture is usable
+    TextureReady { load: u64, width: f32, height: f32 },
+    /// An async texture load failed (unreadable file or undecodable image)
+    TextureFailed { load: u64 },
+    /// Game code asked for haptic feedback (bounces, pickups, game_request_haptic)
+    Haptic { pattern: HapticPattern },
+    /// A replay reached the end of its recording; host input is accepted again
+    ReplayFinished,
+    /// The first frame showing the scene (not the startup splash) was rendered
+    FirstMeaningfulFrame,
+    /// A call panicked; the instance now refuses calls other than polling and game_last_crash
+    EngineCrashed { message: String, backtrace: String },
+    /// A tween ended: reached its end value, or was cancelled, replaced or lost its entity
+    TweenFinished { tween: u64, completed: bool },
+    /// Colliders `a` and `b` started overlapping around (x, y), `depth` deep
+    CollisionEnter { a: u64, b: u64, x: f32, y: f32, depth: f32 },
+    /// Colliders `a` and `b` stopped overlapping, or one was removed
+    CollisionExit { a: u64, b: u64 },
+    /// A sprite stopped following its path at (x, y): at the end, or because it was given another
+    /// path, moved by the host or removed
+    PathFinished { sprite: u64, x: f32, y: f32, arrived: bool },
+    /// A UI button (game_ui_create_button) was tapped: the touch went down and came up on it
+    ButtonPressed { widget: u64 },
+    /// The game went from state `from` to state `to` (see state_machine module)
+    StateChanged { from: u32, to: u32 },
+    /// A play statistic changed since the last report (sent each stats interval, once one is set)
+    StatUpdated { stat: Stat, value: f32 },
+}
+
+/// Event kind constants for GameEventRecord::kind
+pub const EVENT_BOUNCE: u32 = 1;
+pub const EVENT_ANIMATION_FINISHED: u32 = 2;
+pub const EVENT_TEXTURE_READY: u32 = 3;
+pub const EVENT_TEXTURE_FAILED: u32 = 4;
+pub const EVENT_HAPTIC: u32 = 5;
+pub const EVENT_REPLAY_FINISHED: u32 = 6;
+pub const EVENT_FIRST_MEANINGFUL_FRAME: u32 = 7;
+pub const EVENT_ENGINE_CRASHED: u32 = 8;
+pub const EVENT_TWEEN_FINISHED: u32 = 9;
+pub const EVENT_COLLISION_ENTER: u32 = 10;
+pub const EVENT_COLLISION_EXIT: u32 = 11;
+pub const EVENT_PATH_FINISHED: u32 = 12;
+pub const EVENT_BUTTON_PRESSED: u32 = 13;
+pub const EVENT_STATE_CHANGED: u32 = 14;
+pub const EVENT_STAT_UPDATED: u32 = 15;
+
+/// Flat C representation of an event
+/// Field meaning depends on kind:
+/// - EVENT_BOUNCE: id = Surface, (x, y) = contact point, value = impact speed
+/// - EVENT_ANIMATION_FINISHED: id = node
+/// - EVENT_TEXTURE_READY: id = load id, (x, y) = texture size
+/// - EVENT_TEXTURE_FAILED: id = load id
+/// - EVENT_HAPTIC: id = HapticPattern
+/// - EVENT_REPLAY_FINISHED: no fields
+/// - EVENT_FIRST_MEANINGFUL_FRAME: no fields
+/// - EVENT_ENGINE_CRASHED: no fields (message and backtrace via game_last_crash)
+/// - EVENT_TWEEN_FINISHED: id = tween, value = 1 if it reached its end value, 0 if interrupted
+/// - EVENT_COLLISION_ENTER: id and other = the colliders (lower slot first), (x, y) = middle of the
+///   overlap, value = its depth
+/// - EVENT_COLLISION_EXIT: id and other = the colliders
+/// - EVENT_PATH_FINISHED: id = sprite, (x, y) = where it stopped, value = 1 if it reached the end, 0 if
+///   interrupted
+/// - EVENT_BUTTON_PRESSED: id = widget
+/// - EVENT_STATE_CHANGED: id = state entered, other = state left
+/// - EVENT_STAT_UPDATED: id = Stat, value = its new value
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GameEventRecord {
+    pub kind: u32,
+    pub id: u64,
+    pub x: f32,
+    pub y: f32,
+    pub value: f32,
+    /// Second entity of two-entity events
+    pub other: u64,
+}
+
+impl GameEvent {
+    pub fn to_record(&self) -> GameEventRecord {
+        match *self {
+            GameEvent::Bounce { surface, x, y, speed } => GameEventRecord {
+                kind: EVENT_BOUNCE,
+                id: surface as u64,
+                x,
+                y,
+                value: speed,
+                other: 0,
+            },
+            GameEvent::AnimationFinished { node } => GameEventRecord {
+                kind: EVENT_ANIMATION_FINISHED,
+                id: node,
+                ..Default::default()
+            },
+            GameEvent::TextureReady { load, width, height } => GameEventRecord {
+                kind: EVENT_TEXTURE_READY,
+                id: load,
+                x: width,
+                y: height,
+                ..Default::default()
+            },
+            GameEvent::TextureFailed { load } => GameEventRecord {
+                kind: EVENT_TEXTURE_FAILED,
+                id: load,
+                ..Default::default()
+            },
+            GameEvent::Haptic { pattern } => GameEventRecord {
+                kind: EVENT_HAPTIC,
+                id: pattern as u64,
+                ..Default::default()
+            },
+            GameEvent::ReplayFinished => GameEventRecord {
+                kind: EVENT_REPLAY_FINISHED,
+                ..Default::default()
+            },
+            GameEvent::FirstMeaningfulFrame => GameEventRecord {
+                kind: EVENT_FIRST_MEANINGFUL_FRAME,
+                ..Default::default()
+            },
+            GameEvent::EngineCrashed { .. } => GameEventRecord {
+                kind: EVENT_ENGINE_CRASHED,
+                ..Default::default()
+            },
+            GameEvent::TweenFinished { tween, completed } => GameEventRecord {
+                kind: EVENT_TWEEN_FINISHED,
+                id: tween,
+                value: if completed { 1.0 } else { 0.0 },
+                ..Default::default()
+            },
+            GameEvent::CollisionEnter { a, b, x, y, depth } => GameEventRecord {
+                kind: EVENT_COLLISION_ENTER,
+                id: a,
+                x,
+                y,
+                value: depth,
+                other: b,
+            },
+            GameEvent::CollisionExit { a, b } => GameEventRecord {
+                kind: EVENT_COLLISION_EXIT,
+                id: a,
+                other: b,
+                ..Default::default()
+            },
+            GameEvent::PathFinished { sprite, x, y, arrived } => GameEventRecord {
+                kind: EVENT_PATH_FINISHED,
+                id: sprite,
+                x,
+                y,
+                value: if arrived { 1.0 } else { 0.0 },
+                other: 0,
+            },
+            GameEvent::ButtonPressed { widget } => GameEventRecord {
+                kind: EVENT_BUTTON_PRESSED,
+                id: widget,
+                ..Default::default()
+            },
+            GameEvent::StateChanged { from, to } => GameEventRecord {
+                kind: EVENT_STATE_CHANGED,
+                id: to as u64,
+                other: from as u64,
+                ..Default::default()
+            },
+            GameEvent::StatUpdated { stat, value } => GameEventRecord {
+                kind: EVENT_STAT_UPDATED,
+                id: stat as u64,
+                value,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// A reader given its own copy of every event (EventQueue::subscribe)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SubscriptionId(u32);
+
+/// Outbound event queue polled by the host, plus one per subscriber
+#[derive(Default)]
+pub struct EventQueue {
+    /// Events with the time they were pushed
+    queue: VecDeque<(GameEvent, Instant)>,
+    stats: EventPathStats,
+    /// Events not yet taken by each subscriber; the oldest are dropped past MAX_QUEUED_EVENTS as well
+    subscribers: Vec<(SubscriptionId, VecDeque<GameEvent>)>,
+    next_subscriber: u32,
+}
+
+impl EventQueue {
+    pub fn push(&mut self, event: GameEvent) {
+        for (_, queue) in &mut self.subscribers {
+            if queue.len() >= MAX_QUEUED_EVENTS {
+                queue.pop_front();
+            }
+            queue.push_back(event.clone());
+        }
+        self.stats.published += 1;
+        if self.queue.len() >= MAX_QUEUED_EVENTS {
+            self.queue.pop_front();
+            self.stats.dropped += 1;
+            if self.stats.dropped.is_power_of_two() {
+                log::warn!("Event queue full, {} events dropped so far", self.stats.dropped);
+            }
+        }
+        self.queue.push_back((event, Instant::now()));
+    }
+
+    pub fn pop(&mut self) -> Option<GameEvent> {
+        let (event, pushed) = self.queue.pop_front()?;
+        self.stats.record_delivery(1, pushed, Instant::now());
+        Some(event)
+    }
+
+    /// Copy events from now on into a queue of their own, which pop doesn't touch
+    pub fn subscribe(&mut self) -> SubscriptionId {
+        self.next_subscriber += 1;
+        let id = SubscriptionId(self.next_subscriber);
+        self.subscribers.push((id, VecDeque::new()));
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) {
+        self.subscribers.retain(|(subscriber, _)| *subscriber != id);
+    }
+
+    /// The oldest event `id` hasn't taken yet
+    pub fn pop_subscribed(&mut self, id: SubscriptionId) -> Option<GameEvent> {
+        self.subscribers.iter_mut().find(|(subscriber, _)| *subscriber == id)?.1.pop_front()
+    }
+
+    /// Events waiting for the host
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Give back the space of a backlog the host has since drained (game_trim_memory)
+    pub fn shrink_to_fit(&mut self) {
+        self.queue.shrink_to_fit();
+        for (_, queue) in &mut self.subscribers {
+            queue.shrink_to_fit();
+        }
+    }
+
+    pub fn stats(&self) -> EventPathStats {
+        self.stats
+    }
+}
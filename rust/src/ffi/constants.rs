@@ -0,0 +1,608 @@
+/// Event kinds (GameEventRecord::kind)
+/// id = surface (0=left, 1=right, 2=top, 3=bottom, 4=obstacle), (x, y) = contact point, value = impact speed
+pub const GAME_EVENT_BOUNCE: u32 = 1;
+/// id = node whose once animation ended
+pub const GAME_EVENT_ANIMATION_FINISHED: u32 = 2;
+/// id = load id, (x, y) = texture size
+pub const GAME_EVENT_TEXTURE_READY: u32 = 3;
+/// id = load id
+pub const GAME_EVENT_TEXTURE_FAILED: u32 = 4;
+/// id = GAME_HAPTIC_* pattern
+pub const GAME_EVENT_HAPTIC: u32 = 5;
+/// No fields; host input is accepted again
+pub const GAME_EVENT_REPLAY_FINISHED: u32 = 6;
+/// No fields; the startup splash is gone and the scene was rendered
+pub const GAME_EVENT_FIRST_MEANINGFUL_FRAME: u32 = 7;
+/// No fields; a call panicked and the instance is poisoned (game_last_crash)
+pub const GAME_EVENT_ENGINE_CRASHED: u32 = 8;
+/// id = tween, value = 1 if it reached its end, 0 if cancelled, replaced or its entity was removed
+pub const GAME_EVENT_TWEEN_FINISHED: u32 = 9;
+/// id and other = colliders that started overlapping (lower slot first), (x, y) = middle of the overlap,
+/// value = its depth
+pub const GAME_EVENT_COLLISION_ENTER: u32 = 10;
+/// id and other = colliders that stopped overlapping or were removed
+pub const GAME_EVENT_COLLISION_EXIT: u32 = 11;
+/// id = sprite, (x, y) = where it stopped, value = 1 if it reached the end of its path, 0 if given
+/// another, moved by the host or removed
+pub const GAME_EVENT_PATH_FINISHED: u32 = 12;
+/// id = UI button tapped (the touch went down and came up on it)
+pub const GAME_EVENT_BUTTON_PRESSED: u32 = 13;
+/// id = game state entered, other = state left
+pub const GAME_EVENT_STATE_CHANGED: u32 = 14;
+/// id = GAME_STAT_*, value = its new value
+pub const GAME_EVENT_STAT_UPDATED: u32 = 15;
+
+/// Share card templates: 1080x1080
+pub const GAME_SHARE_TEMPLATE_SQUARE: u32 = 0;
+/// 1080x1920
+pub const GAME_SHARE_TEMPLATE_STORY: u32 = 1;
+
+/// Run summary flags (RunSummary::flags)
+pub const GAME_RUN_FLAG_DEV_MODE: u32 = 1 << 0;
+pub const GAME_RUN_FLAG_TAMPER_ATTEMPT: u32 = 1 << 1;
+pub const GAME_RUN_FLAG_TUNED: u32 = 1 << 2;
+pub const GAME_RUN_FLAG_STATE_MUTATED: u32 = 1 << 3;
+/// Resumed with game_restore_state
+pub const GAME_RUN_FLAG_RESTORED: u32 = 1 << 4;
+
+/// Optional subsystems for game_negotiate
+pub const GAME_FEATURE_PHYSICS: u64 = 1 << 0;
+pub const GAME_FEATURE_ASYNC_ASSETS: u64 = 1 << 1;
+pub const GAME_FEATURE_AUDIO: u64 = 1 << 2;
+pub const GAME_FEATURE_NETWORK: u64 = 1 << 3;
+pub const GAME_FEATURE_DEBUG_SERVER: u64 = 1 << 4;
+pub const GAME_FEATURE_HAPTICS: u64 = 1 << 5;
+
+/// Driver workarounds (game_get_quirks bits): no glFinish per frame; gpu_ms reads ~0
+pub const GAME_QUIRK_SKIP_GL_FINISH: u32 = 1 << 0;
+/// GLSL ES 1.00 shaders; read at game_init only
+pub const GAME_QUIRK_FORCE_GLES2: u32 = 1 << 1;
+
+/// Reasons a call from off the render thread is dropped (game_last_call_error)
+pub const GAME_CALL_OK: i32 = 0;
+pub const GAME_CALL_ERR_INBOX_FULL: i32 = 1;
+pub const GAME_CALL_ERR_TIMED_OUT: i32 = 2;
+
+/// Reasons a string argument is rejected (game_last_string_error)
+pub const GAME_STRING_OK: i32 = 0;
+pub const GAME_STRING_ERR_NULL: i32 = 1;
+pub const GAME_STRING_ERR_INVALID_UTF8: i32 = 2;
+#[cfg_attr(not(any(target_os = "android", target_os = "ios")), allow(dead_code))]
+pub const GAME_STRING_ERR_INTERIOR_NUL: i32 = 3;
+
+/// Log levels for game_set_log_callback
+pub const GAME_LOG_OFF: u32 = 0;
+pub const GAME_LOG_ERROR: u32 = 1;
+pub const GAME_LOG_WARN: u32 = 2;
+pub const GAME_LOG_INFO: u32 = 3;
+pub const GAME_LOG_DEBUG: u32 = 4;
+pub const GAME_LOG_TRACE: u32 = 5;
+
+/// Device orientation
+pub const GAME_ORIENTATION_PORTRAIT: u32 = 0;
+pub const GAME_ORIENTATION_LANDSCAPE: u32 = 1;
+
+/// What a rotation does to entity positions: same place relative to the world's edges (default)
+pub const GAME_ROTATION_REMAP_PROPORTIONAL: u32 = 0;
+/// Same coordinates; the player is kept inside
+pub const GAME_ROTATION_REMAP_CLAMP: u32 = 1;
+
+/// Screen coordinate spaces for game_screen_to_world_in and game_world_to_screen: points from the
+/// surface's top left (logical pixels over the view)
+pub const GAME_SCREEN_SPACE_POINTS: u32 = 0;
+/// Physical pixels from the surface's top left, like touches
+pub const GAME_SCREEN_SPACE_PIXELS: u32 = 1;
+/// Points from the safe area's top left (widgets inside the insets)
+pub const GAME_SCREEN_SPACE_SAFE_AREA: u32 = 2;
+
+/// Thermal states, as ProcessInfo.ThermalState
+pub const GAME_THERMAL_NOMINAL: u32 = 0;
+/// Fewer particles, paced frames capped at 60
+pub const GAME_THERMAL_FAIR: u32 = 1;
+/// Half the particles, 30 fps, no post-processing
+pub const GAME_THERMAL_SERIOUS: u32 = 2;
+/// A quarter of the particles, 30 fps, no post-processing
+pub const GAME_THERMAL_CRITICAL: u32 = 3;
+
+/// Render hook stages: after the clear, under obstacles, sprites and the scene
+pub const GAME_RENDER_STAGE_BEFORE_WORLD: u32 = 0;
+/// Over the scene and particles, under the HUD
+pub const GAME_RENDER_STAGE_AFTER_WORLD: u32 = 1;
+/// Over the HUD, before post effects and the debug overlay
+pub const GAME_RENDER_STAGE_AFTER_HUD: u32 = 2;
+
+/// State diff encoding (game_poll_state_diff): version byte, then tagged little-endian records
+pub const GAME_STATE_DIFF_VERSION: u8 = 1;
+/// float, points
+pub const GAME_STATE_PLAYER_X: u8 = 1;
+/// float, points
+pub const GAME_STATE_PLAYER_Y: u8 = 2;
+/// uint32_t, 0xRRGGBBAA
+pub const GAME_STATE_PLAYER_TINT: u8 = 3;
+/// uint8_t, 0=manual, 1=auto
+pub const GAME_STATE_MODE: u8 = 4;
+/// uint8_t, 0 or 1
+pub const GAME_STATE_DRAGGING: u8 = 5;
+/// uint32_t
+pub const GAME_STATE_SCORE: u8 = 6;
+/// uint32_t, 0 = free play
+pub const GAME_STATE_LEVEL_SEED: u8 = 7;
+/// uint64_t id, float x, y, w, h, uint32_t rgba
+pub const GAME_STATE_SPRITE: u8 = 16;
+/// uint64_t id
+pub const GAME_STATE_SPRITE_REMOVED: u8 = 17;
+
+/// Game states; games start in GAME_STATE_PLAYING. Menu, paused and game over hold the world still
+pub const GAME_STATE_MENU: u32 = 0;
+pub const GAME_STATE_PLAYING: u32 = 1;
+pub const GAME_STATE_PAUSED: u32 = 2;
+pub const GAME_STATE_GAME_OVER: u32 = 3;
+pub const GAME_INVALID_STATE: u32 = 0xFFFF_FFFF;
+
+/// Gameplay tunables for game_set_param / game_get_param (default, accepted range)
+/// Manual mode player speed, points/s (300, >= 0)
+pub const GAME_PARAM_SPEED: u32 = 0;
+/// Auto mode starting velocity outside levels, points/s (250)
+pub const GAME_PARAM_AUTO_VELOCITY_X: u32 = 1;
+/// (200)
+pub const GAME_PARAM_AUTO_VELOCITY_Y: u32 = 2;
+/// Thrown player slowdown per second (4, >= 0; 0 = no throwing)
+pub const GAME_PARAM_FRICTION: u32 = 3;
+/// Speed kept bouncing off walls in Auto mode (1, 0..1)
+pub const GAME_PARAM_RESTITUTION: u32 = 4;
+/// Side of the player's box, points (200, >= 1)
+pub const GAME_PARAM_PLAYER_SIZE: u32 = 5;
+
+/// Scoreboard stats for game_get_stat and GAME_EVENT_STAT_UPDATED
+/// Bounces this run (reset when a level starts)
+pub const GAME_STAT_SCORE: u32 = 0;
+/// Bounces since the engine started or game_reset_stats
+pub const GAME_STAT_BOUNCES: u32 = 1;
+/// Points the player traveled, dragged and thrown included
+pub const GAME_STAT_DISTANCE: u32 = 2;
+/// Seconds of game time in Auto mode
+pub const GAME_STAT_AUTO_TIME: u32 = 3;
+/// Seconds of game time in Manual mode
+pub const GAME_STAT_MANUAL_TIME: u32 = 4;
+/// Drags released fast enough to throw the player
+pub const GAME_STAT_THROWS: u32 = 5;
+
+/// Easing curves for game_animate_*
+pub const GAME_EASING_LINEAR: u32 = 0;
+pub const GAME_EASING_EASE_IN: u32 = 1;
+pub const GAME_EASING_EASE_OUT: u32 = 2;
+pub const GAME_EASING_EASE_IN_OUT: u32 = 3;
+/// Overshoots and springs back
+pub const GAME_EASING_ELASTIC: u32 = 4;
+/// Bounces to rest on the end value
+pub const GAME_EASING_BOUNCE: u32 = 5;
+
+/// Collider shapes: width x height, axis-aligned
+pub const GAME_COLLIDER_BOX: u32 = 0;
+/// Width across; height is ignored
+pub const GAME_COLLIDER_CIRCLE: u32 = 1;
+
+/// What a collider is attached to
+pub const GAME_COLLIDER_OWNER_SPRITE: u32 = 0;
+pub const GAME_COLLIDER_OWNER_NODE: u32 = 1;
+
+/// Text alignment: one horizontal value | one vertical value
+pub const GAME_TEXT_ALIGN_LEFT: u32 = 0;
+pub const GAME_TEXT_ALIGN_CENTER: u32 = 1;
+pub const GAME_TEXT_ALIGN_RIGHT: u32 = 2;
+pub const GAME_TEXT_ALIGN_TOP: u32 = 0;
+pub const GAME_TEXT_ALIGN_MIDDLE: u32 = 4;
+pub const GAME_TEXT_ALIGN_BOTTOM: u32 = 8;
+
+/// Host draw list format (game_submit_draw_list): version byte, then commands, each a tag byte and
+/// little-endian fields. Coordinates are points, colors 0xRRGGBBAA (uint32), strings a uint32 byte
+/// length then UTF-8
+pub const GAME_DRAW_LIST_VERSION: u8 = 1;
+/// x, y, w, h f32, fill u32, stroke width f32, stroke u32
+pub const GAME_DRAW_RECT: u8 = 1;
+/// Texture name, x, y, w, h f32, u0, v0, u1, v1 f32, tint u32
+pub const GAME_DRAW_IMAGE: u8 = 2;
+/// Closed u8, count u32, count x (x, y f32), fill u32, stroke width f32, stroke u32
+pub const GAME_DRAW_PATH: u8 = 3;
+/// x, y f32 (top left), size f32, color u32, text
+pub const GAME_DRAW_TEXT: u8 = 4;
+/// a, b, c, d, tx, ty f32 for the following commands (text: position only)
+pub const GAME_DRAW_TRANSFORM: u8 = 5;
+/// u8 layer (GAME_DRAW_LAYER_*) for the following commands
+pub const GAME_DRAW_LAYER: u8 = 6;
+/// Vector path, filled then stroked: fill u32, fill rule u8, stroke width f32, stroke u32, cap u8, join u8,
+/// dash count u8 (<= 16), dash/gap lengths f32, dash offset f32, verb count u32, then per verb a
+/// GAME_DRAW_VERB_* byte and its points as x, y f32 pairs (move 1, line 1, quad 2, cubic 3, close 0)
+pub const GAME_DRAW_VECTOR: u8 = 7;
+pub const GAME_DRAW_FILL_NON_ZERO: u8 = 0;
+pub const GAME_DRAW_FILL_EVEN_ODD: u8 = 1;
+pub const GAME_DRAW_CAP_BUTT: u8 = 0;
+pub const GAME_DRAW_CAP_ROUND: u8 = 1;
+pub const GAME_DRAW_CAP_SQUARE: u8 = 2;
+pub const GAME_DRAW_JOIN_MITER: u8 = 0;
+pub const GAME_DRAW_JOIN_ROUND: u8 = 1;
+pub const GAME_DRAW_JOIN_BEVEL: u8 = 2;
+pub const GAME_DRAW_VERB_MOVE: u8 = 0;
+pub const GAME_DRAW_VERB_LINE: u8 = 1;
+pub const GAME_DRAW_VERB_QUAD: u8 = 2;
+pub const GAME_DRAW_VERB_CUBIC: u8 = 3;
+pub const GAME_DRAW_VERB_CLOSE: u8 = 4;
+/// Paint replacing the fill or stroke color of the following vector commands: target u8, kind u8, then
+/// solid: nothing (back to the command's color); linear: x0, y0, x1, y1 f32, stops; radial: cx, cy, radius f32,
+/// stops; texture: texture name, x, y, w, h f32 (where the whole image lands), tint u32. Stops are a u8 count
+/// (1-16) then offset f32 (ascending) and color u32 each. Coordinates are the path's, before the transform
+/// Tessellated vector commands are cached, so resubmitting unchanged shapes is cheap
+pub const GAME_DRAW_PAINT: u8 = 8;
+pub const GAME_DRAW_PAINT_FILL: u8 = 0;
+pub const GAME_DRAW_PAINT_STROKE: u8 = 1;
+pub const GAME_DRAW_PAINT_SOLID: u8 = 0;
+pub const GAME_DRAW_PAINT_LINEAR: u8 = 1;
+pub const GAME_DRAW_PAINT_RADIAL: u8 = 2;
+pub const GAME_DRAW_PAINT_TEXTURE: u8 = 3;
+/// Clip the following commands of the same layer until GAME_DRAW_CLIP_POP: kind u8, then rect: x, y, w, h f32;
+/// rounded rect: x, y, w, h, radius f32; path: fill rule u8, verb count u32 and verbs as in GAME_DRAW_VECTOR.
+/// Clips nest up to 16 deep per layer and follow the transform; shapes other than rects need a stencil buffer
+pub const GAME_DRAW_CLIP: u8 = 9;
+pub const GAME_DRAW_CLIP_POP: u8 = 10;
+pub const GAME_DRAW_CLIP_RECT: u8 = 0;
+pub const GAME_DRAW_CLIP_ROUNDED_RECT: u8 = 1;
+pub const GAME_DRAW_CLIP_PATH: u8 = 2;
+/// Picture: id u32, x, y, w, h f32 (moved by the transform), then commands up to GAME_DRAW_PICTURE_END.
+/// They're rasterized once into a texture over those bounds and it's drawn instead until their bytes change or
+/// game_invalidate_picture(id). No nesting or layer changes inside; clips opened inside must be popped inside
+pub const GAME_DRAW_PICTURE: u8 = 11;
+pub const GAME_DRAW_PICTURE_END: u8 = 12;
+/// Beneath the level obstacles
+pub const GAME_DRAW_LAYER_BELOW: u8 = 0;
+/// Above the scene nodes, beneath the HUD (default)
+pub const GAME_DRAW_LAYER_ABOVE: u8 = 1;
+
+/// Paragraph direction for text: each line's first strong character
+pub const GAME_TEXT_DIRECTION_AUTO: u32 = 0;
+pub const GAME_TEXT_DIRECTION_LTR: u32 = 1;
+pub const GAME_TEXT_DIRECTION_RTL: u32 = 2;
+
+/// How much game_trim_memory frees: memory warning while on screen, keep what the frame draws
+pub const GAME_TRIM_MEMORY_MODERATE: u32 = 0;
+/// Entering the background: drop everything that can be rebuilt
+pub const GAME_TRIM_MEMORY_BACKGROUND: u32 = 1;
+
+/// Compressed texture formats (game_get_compressed_texture_formats bits)
+pub const GAME_TEXTURE_FORMAT_ETC2: u32 = 1 << 0;
+pub const GAME_TEXTURE_FORMAT_ASTC: u32 = 1 << 1;
+
+/// Animation loop modes
+pub const GAME_LOOP_MODE_LOOP: i32 = 0;
+pub const GAME_LOOP_MODE_ONCE: i32 = 1;
+pub const GAME_LOOP_MODE_PING_PONG: i32 = 2;
+
+/// QR error-correction levels (~7%, 15%, 25%, 30% recoverable)
+pub const GAME_QR_EC_LOW: i32 = 0;
+pub const GAME_QR_EC_MEDIUM: i32 = 1;
+pub const GAME_QR_EC_QUARTILE: i32 = 2;
+pub const GAME_QR_EC_HIGH: i32 = 3;
+/// Recommended quiet zone for game_show_qr, in modules (the engine takes any)
+#[allow(dead_code)]
+pub const GAME_QR_DEFAULT_QUIET_ZONE: u32 = 4;
+
+/// Which touch gestures the engine claims, from the next touch down on: every gesture (the default)
+pub const GAME_TOUCH_ROUTING_ALL: u32 = 0;
+/// Gestures going down on a UI button, the player or an entity with a collider
+pub const GAME_TOUCH_ROUTING_ENTITIES: u32 = 1;
+
+/// Key actions and modifier bits for game_key_event
+pub const GAME_KEY_DOWN: i32 = 0;
+pub const GAME_KEY_UP: i32 = 1;
+pub const GAME_KEY_REPEAT: i32 = 2;
+pub const GAME_KEY_MOD_SHIFT: u32 = 1 << 0;
+pub const GAME_KEY_MOD_CTRL: u32 = 1 << 1;
+pub const GAME_KEY_MOD_ALT: u32 = 1 << 2;
+/// Command on Apple keyboards
+pub const GAME_KEY_MOD_META: u32 = 1 << 3;
+
+/// Gamepad controls for game_gamepad_event
+pub const GAME_PAD_DPAD_UP: i32 = 0;
+pub const GAME_PAD_DPAD_DOWN: i32 = 1;
+pub const GAME_PAD_DPAD_LEFT: i32 = 2;
+pub const GAME_PAD_DPAD_RIGHT: i32 = 3;
+/// A / Cross
+pub const GAME_PAD_SOUTH: i32 = 4;
+pub const GAME_PAD_EAST: i32 = 5;
+pub const GAME_PAD_WEST: i32 = 6;
+pub const GAME_PAD_NORTH: i32 = 7;
+pub const GAME_PAD_START: i32 = 8;
+pub const GAME_PAD_SELECT: i32 = 9;
+pub const GAME_PAD_LEFT_SHOULDER: i32 = 10;
+pub const GAME_PAD_RIGHT_SHOULDER: i32 = 11;
+/// -1..1
+pub const GAME_PAD_LEFT_STICK_X: i32 = 32;
+/// -1..1, +y down (negate GCController yAxis)
+pub const GAME_PAD_LEFT_STICK_Y: i32 = 33;
+pub const GAME_PAD_RIGHT_STICK_X: i32 = 34;
+pub const GAME_PAD_RIGHT_STICK_Y: i32 = 35;
+/// 0..1
+pub const GAME_PAD_LEFT_TRIGGER: i32 = 36;
+pub const GAME_PAD_RIGHT_TRIGGER: i32 = 37;
+
+/// Motion sensors for game_sensor_event
+/// Axes follow Android's device frame (x right, y up, z out of the screen; flat face-up reads +z)
+/// CoreMotion reports acceleration in g with the opposite sign: pass -9.81 * userAcceleration+gravity
+/// m/s^2
+pub const GAME_SENSOR_ACCELEROMETER: i32 = 0;
+/// rad/s
+pub const GAME_SENSOR_GYROSCOPE: i32 = 1;
+
+/// Haptic patterns for GAME_EVENT_HAPTIC and game_request_haptic: UIImpactFeedbackGenerator styles
+pub const GAME_HAPTIC_LIGHT: u32 = 0;
+pub const GAME_HAPTIC_MEDIUM: u32 = 1;
+pub const GAME_HAPTIC_HEAVY: u32 = 2;
+/// UINotificationFeedbackGenerator types
+pub const GAME_HAPTIC_SUCCESS: u32 = 3;
+pub const GAME_HAPTIC_WARNING: u32 = 4;
+pub const GAME_HAPTIC_ERROR: u32 = 5;
+/// UISelectionFeedbackGenerator
+pub const GAME_HAPTIC_SELECTION: u32 = 6;
+
+/// No sound (needs GAME_FEATURE_AUDIO, built with the `audio` cargo feature)
+pub const GAME_INVALID_SOUND: u64 = 0;
+
+/// Volume channels for game_set_volume
+pub const GAME_VOLUME_MASTER: u32 = 0;
+pub const GAME_VOLUME_EFFECTS: u32 = 1;
+pub const GAME_VOLUME_MUSIC: u32 = 2;
+
+/// Color palettes for game_set_palette (bounce tints, drag highlight, status text)
+pub const GAME_PALETTE_STANDARD: u32 = 0;
+pub const GAME_PALETTE_DEUTERANOPIA: u32 = 1;
+pub const GAME_PALETTE_PROTANOPIA: u32 = 2;
+pub const GAME_PALETTE_TRITANOPIA: u32 = 3;
+
+/// Instance status (game_get_status)
+pub const GAME_STATUS_OK: i32 = 0;
+/// A call panicked; other calls return defaults until game_destroy
+pub const GAME_STATUS_POISONED: i32 = 1;
+/// Null handle
+pub const GAME_STATUS_INVALID: i32 = 2;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::affinity::CallError;
+    use crate::audio::Channel;
+    use crate::capabilities::Features;
+    use crate::ffi::strings::StringError;
+    use crate::input::gamepad::Control;
+    use crate::input::keyboard::{KeyAction, KeyModifiers};
+    use crate::input::routing::TouchRouting;
+    use crate::input::sensors::SensorType;
+    use crate::params::Param;
+    use crate::quirks::Quirks;
+    use crate::scoreboard::Stat;
+    use crate::tween::Easing;
+    use crate::{atlas, collision, draw_list, engine, events, haptics, ktx2, orientation, palette, policy, power, qr};
+    use crate::{render_hook, share, state_diff, state_machine, text};
+
+    #[test]
+    fn constants_match_the_engine() {
+        let events = [
+            (GAME_EVENT_BOUNCE, events::EVENT_BOUNCE),
+            (GAME_EVENT_ANIMATION_FINISHED, events::EVENT_ANIMATION_FINISHED),
+            (GAME_EVENT_TEXTURE_READY, events::EVENT_TEXTURE_READY),
+            (GAME_EVENT_TEXTURE_FAILED, events::EVENT_TEXTURE_FAILED),
+            (GAME_EVENT_HAPTIC, events::EVENT_HAPTIC),
+            (GAME_EVENT_REPLAY_FINISHED, events::EVENT_REPLAY_FINISHED),
+            (GAME_EVENT_FIRST_MEANINGFUL_FRAME, events::EVENT_FIRST_MEANINGFUL_FRAME),
+            (GAME_EVENT_ENGINE_CRASHED, events::EVENT_ENGINE_CRASHED),
+            (GAME_EVENT_TWEEN_FINISHED, events::EVENT_TWEEN_FINISHED),
+            (GAME_EVENT_COLLISION_ENTER, events::EVENT_COLLISION_ENTER),
+            (GAME_EVENT_COLLISION_EXIT, events::EVENT_COLLISION_EXIT),
+            (GAME_EVENT_PATH_FINISHED, events::EVENT_PATH_FINISHED),
+            (GAME_EVENT_BUTTON_PRESSED, events::EVENT_BUTTON_PRESSED),
+            (GAME_EVENT_STATE_CHANGED, events::EVENT_STATE_CHANGED),
+            (GAME_EVENT_STAT_UPDATED, events::EVENT_STAT_UPDATED),
+        ];
+        let u32s = [
+            (GAME_SHARE_TEMPLATE_SQUARE, share::SHARE_TEMPLATE_SQUARE),
+            (GAME_SHARE_TEMPLATE_STORY, share::SHARE_TEMPLATE_STORY),
+            (GAME_RUN_FLAG_DEV_MODE, policy::RUN_FLAG_DEV_MODE),
+            (GAME_RUN_FLAG_TAMPER_ATTEMPT, policy::RUN_FLAG_TAMPER_ATTEMPT),
+            (GAME_RUN_FLAG_TUNED, policy::RUN_FLAG_TUNED),
+            (GAME_RUN_FLAG_STATE_MUTATED, policy::RUN_FLAG_STATE_MUTATED),
+            (GAME_RUN_FLAG_RESTORED, policy::RUN_FLAG_RESTORED),
+            (GAME_QUIRK_SKIP_GL_FINISH, Quirks::SKIP_GL_FINISH.bits()),
+            (GAME_QUIRK_FORCE_GLES2, Quirks::FORCE_GLES2.bits()),
+            (GAME_ORIENTATION_PORTRAIT, orientation::ORIENTATION_PORTRAIT),
+            (GAME_ORIENTATION_LANDSCAPE, orientation::ORIENTATION_LANDSCAPE),
+            (GAME_ROTATION_REMAP_PROPORTIONAL, orientation::ROTATION_REMAP_PROPORTIONAL),
+            (GAME_ROTATION_REMAP_CLAMP, orientation::ROTATION_REMAP_CLAMP),
+            (GAME_SCREEN_SPACE_POINTS, engine::SCREEN_SPACE_POINTS),
+            (GAME_SCREEN_SPACE_PIXELS, engine::SCREEN_SPACE_PIXELS),
+            (GAME_SCREEN_SPACE_SAFE_AREA, engine::SCREEN_SPACE_SAFE_AREA),
+            (GAME_THERMAL_NOMINAL, power::THERMAL_NOMINAL),
+            (GAME_THERMAL_FAIR, power::THERMAL_FAIR),
+            (GAME_THERMAL_SERIOUS, power::THERMAL_SERIOUS),
+            (GAME_THERMAL_CRITICAL, power::THERMAL_CRITICAL),
+            (GAME_RENDER_STAGE_BEFORE_WORLD, render_hook::RENDER_STAGE_BEFORE_WORLD),
+            (GAME_RENDER_STAGE_AFTER_WORLD, render_hook::RENDER_STAGE_AFTER_WORLD),
+            (GAME_RENDER_STAGE_AFTER_HUD, render_hook::RENDER_STAGE_AFTER_HUD),
+            (GAME_STATE_MENU, state_machine::STATE_MENU),
+            (GAME_STATE_PLAYING, state_machine::STATE_PLAYING),
+            (GAME_STATE_PAUSED, state_machine::STATE_PAUSED),
+            (GAME_STATE_GAME_OVER, state_machine::STATE_GAME_OVER),
+            (GAME_INVALID_STATE, state_machine::INVALID_STATE),
+            (GAME_PARAM_SPEED, Param::Speed as u32),
+            (GAME_PARAM_AUTO_VELOCITY_X, Param::AutoVelocityX as u32),
+            (GAME_PARAM_AUTO_VELOCITY_Y, Param::AutoVelocityY as u32),
+            (GAME_PARAM_FRICTION, Param::Friction as u32),
+            (GAME_PARAM_RESTITUTION, Param::Restitution as u32),
+            (GAME_PARAM_PLAYER_SIZE, Param::PlayerSize as u32),
+            (GAME_STAT_SCORE, Stat::Score as u32),
+            (GAME_STAT_BOUNCES, Stat::Bounces as u32),
+            (GAME_STAT_DISTANCE, Stat::Distance as u32),
+            (GAME_STAT_AUTO_TIME, Stat::AutoTime as u32),
+            (GAME_STAT_MANUAL_TIME, Stat::ManualTime as u32),
+            (GAME_STAT_THROWS, Stat::Throws as u32),
+            (GAME_EASING_LINEAR, Easing::Linear as u32),
+            (GAME_EASING_EASE_IN, Easing::EaseIn as u32),
+            (GAME_EASING_EASE_OUT, Easing::EaseOut as u32),
+            (GAME_EASING_EASE_IN_OUT, Easing::EaseInOut as u32),
+            (GAME_EASING_ELASTIC, Easing::Elastic as u32),
+            (GAME_EASING_BOUNCE, Easing::Bounce as u32),
+            (GAME_COLLIDER_BOX, collision::SHAPE_BOX),
+            (GAME_COLLIDER_CIRCLE, collision::SHAPE_CIRCLE),
+            (GAME_COLLIDER_OWNER_SPRITE, collision::OWNER_SPRITE),
+            (GAME_COLLIDER_OWNER_NODE, collision::OWNER_NODE),
+            (GAME_TEXT_ALIGN_LEFT, text::TEXT_ALIGN_LEFT),
+            (GAME_TEXT_ALIGN_CENTER, text::TEXT_ALIGN_CENTER),
+            (GAME_TEXT_ALIGN_RIGHT, text::TEXT_ALIGN_RIGHT),
+            (GAME_TEXT_ALIGN_TOP, text::TEXT_ALIGN_TOP),
+            (GAME_TEXT_ALIGN_MIDDLE, text::TEXT_ALIGN_MIDDLE),
+            (GAME_TEXT_ALIGN_BOTTOM, text::TEXT_ALIGN_BOTTOM),
+            (GAME_TEXT_DIRECTION_AUTO, text::TEXT_DIRECTION_AUTO),
+            (GAME_TEXT_DIRECTION_LTR, text::TEXT_DIRECTION_LTR),
+            (GAME_TEXT_DIRECTION_RTL, text::TEXT_DIRECTION_RTL),
+            (GAME_TRIM_MEMORY_MODERATE, engine::TRIM_MEMORY_MODERATE),
+            (GAME_TRIM_MEMORY_BACKGROUND, engine::TRIM_MEMORY_BACKGROUND),
+            (GAME_TEXTURE_FORMAT_ETC2, ktx2::FORMAT_ETC2),
+            (GAME_TEXTURE_FORMAT_ASTC, ktx2::FORMAT_ASTC),
+            (GAME_TOUCH_ROUTING_ALL, TouchRouting::All as u32),
+            (GAME_TOUCH_ROUTING_ENTITIES, TouchRouting::Entities as u32),
+            (GAME_KEY_MOD_SHIFT, KeyModifiers::SHIFT.bits()),
+            (GAME_KEY_MOD_CTRL, KeyModifiers::CTRL.bits()),
+            (GAME_KEY_MOD_ALT, KeyModifiers::ALT.bits()),
+            (GAME_KEY_MOD_META, KeyModifiers::META.bits()),
+            (GAME_HAPTIC_LIGHT, haptics::HapticPattern::Light as u32),
+            (GAME_HAPTIC_MEDIUM, haptics::HapticPattern::Medium as u32),
+            (GAME_HAPTIC_HEAVY, haptics::HapticPattern::Heavy as u32),
+            (GAME_HAPTIC_SUCCESS, haptics::HapticPattern::Success as u32),
+            (GAME_HAPTIC_WARNING, haptics::HapticPattern::Warning as u32),
+            (GAME_HAPTIC_ERROR, haptics::HapticPattern::Error as u32),
+            (GAME_HAPTIC_SELECTION, haptics::HapticPattern::Selection as u32),
+            (GAME_VOLUME_MASTER, Channel::Master as u32),
+            (GAME_VOLUME_EFFECTS, Channel::Effects as u32),
+            (GAME_VOLUME_MUSIC, Channel::Music as u32),
+            (GAME_PALETTE_STANDARD, palette::Palette::Standard as u32),
+            (GAME_PALETTE_DEUTERANOPIA, palette::Palette::Deuteranopia as u32),
+            (GAME_PALETTE_PROTANOPIA, palette::Palette::Protanopia as u32),
+            (GAME_PALETTE_TRITANOPIA, palette::Palette::Tritanopia as u32),
+        ];
+        let u8s = [
+            (GAME_STATE_DIFF_VERSION, state_diff::STATE_DIFF_VERSION),
+            (GAME_STATE_PLAYER_X, state_diff::STATE_PLAYER_X),
+            (GAME_STATE_PLAYER_Y, state_diff::STATE_PLAYER_Y),
+            (GAME_STATE_PLAYER_TINT, state_diff::STATE_PLAYER_TINT),
+            (GAME_STATE_MODE, state_diff::STATE_MODE),
+            (GAME_STATE_DRAGGING, state_diff::STATE_DRAGGING),
+            (GAME_STATE_SCORE, state_diff::STATE_SCORE),
+            (GAME_STATE_LEVEL_SEED, state_diff::STATE_LEVEL_SEED),
+            (GAME_STATE_SPRITE, state_diff::STATE_SPRITE),
+            (GAME_STATE_SPRITE_REMOVED, state_diff::STATE_SPRITE_REMOVED),
+            (GAME_DRAW_LIST_VERSION, draw_list::DRAW_LIST_VERSION),
+            (GAME_DRAW_RECT, draw_list::DRAW_RECT),
+            (GAME_DRAW_IMAGE, draw_list::DRAW_IMAGE),
+            (GAME_DRAW_PATH, draw_list::DRAW_PATH),
+            (GAME_DRAW_TEXT, draw_list::DRAW_TEXT),
+            (GAME_DRAW_TRANSFORM, draw_list::DRAW_TRANSFORM),
+            (GAME_DRAW_LAYER, draw_list::DRAW_LAYER),
+            (GAME_DRAW_VECTOR, draw_list::DRAW_VECTOR),
+            (GAME_DRAW_FILL_NON_ZERO, draw_list::DRAW_FILL_NON_ZERO),
+            (GAME_DRAW_FILL_EVEN_ODD, draw_list::DRAW_FILL_EVEN_ODD),
+            (GAME_DRAW_CAP_BUTT, draw_list::DRAW_CAP_BUTT),
+            (GAME_DRAW_CAP_ROUND, draw_list::DRAW_CAP_ROUND),
+            (GAME_DRAW_CAP_SQUARE, draw_list::DRAW_CAP_SQUARE),
+            (GAME_DRAW_JOIN_MITER, draw_list::DRAW_JOIN_MITER),
+            (GAME_DRAW_JOIN_ROUND, draw_list::DRAW_JOIN_ROUND),
+            (GAME_DRAW_JOIN_BEVEL, draw_list::DRAW_JOIN_BEVEL),
+            (GAME_DRAW_VERB_MOVE, draw_list::DRAW_VERB_MOVE),
+            (GAME_DRAW_VERB_LINE, draw_list::DRAW_VERB_LINE),
+            (GAME_DRAW_VERB_QUAD, draw_list::DRAW_VERB_QUAD),
+            (GAME_DRAW_VERB_CUBIC, draw_list::DRAW_VERB_CUBIC),
+            (GAME_DRAW_VERB_CLOSE, draw_list::DRAW_VERB_CLOSE),
+            (GAME_DRAW_PAINT, draw_list::DRAW_PAINT),
+            (GAME_DRAW_PAINT_FILL, draw_list::DRAW_PAINT_FILL),
+            (GAME_DRAW_PAINT_STROKE, draw_list::DRAW_PAINT_STROKE),
+            (GAME_DRAW_PAINT_SOLID, draw_list::DRAW_PAINT_SOLID),
+            (GAME_DRAW_PAINT_LINEAR, draw_list::DRAW_PAINT_LINEAR),
+            (GAME_DRAW_PAINT_RADIAL, draw_list::DRAW_PAINT_RADIAL),
+            (GAME_DRAW_PAINT_TEXTURE, draw_list::DRAW_PAINT_TEXTURE),
+            (GAME_DRAW_CLIP, draw_list::DRAW_CLIP),
+            (GAME_DRAW_CLIP_POP, draw_list::DRAW_CLIP_POP),
+            (GAME_DRAW_CLIP_RECT, draw_list::DRAW_CLIP_RECT),
+            (GAME_DRAW_CLIP_ROUNDED_RECT, draw_list::DRAW_CLIP_ROUNDED_RECT),
+            (GAME_DRAW_CLIP_PATH, draw_list::DRAW_CLIP_PATH),
+            (GAME_DRAW_PICTURE, draw_list::DRAW_PICTURE),
+            (GAME_DRAW_PICTURE_END, draw_list::DRAW_PICTURE_END),
+            (GAME_DRAW_LAYER_BELOW, draw_list::DRAW_LAYER_BELOW),
+            (GAME_DRAW_LAYER_ABOVE, draw_list::DRAW_LAYER_ABOVE),
+        ];
+        let i32s = [
+            (GAME_CALL_OK, CallError::None as i32),
+            (GAME_CALL_ERR_INBOX_FULL, CallError::InboxFull as i32),
+            (GAME_CALL_ERR_TIMED_OUT, CallError::TimedOut as i32),
+            (GAME_STRING_OK, StringError::None as i32),
+            (GAME_STRING_ERR_NULL, StringError::Null as i32),
+            (GAME_STRING_ERR_INVALID_UTF8, StringError::InvalidUtf8 as i32),
+            #[cfg(any(target_os = "android", target_os = "ios"))]
+            (GAME_STRING_ERR_INTERIOR_NUL, StringError::InteriorNul as i32),
+            (GAME_LOOP_MODE_LOOP, atlas::LoopMode::Loop as i32),
+            (GAME_LOOP_MODE_ONCE, atlas::LoopMode::Once as i32),
+            (GAME_LOOP_MODE_PING_PONG, atlas::LoopMode::PingPong as i32),
+            (GAME_QR_EC_LOW, qr::EcLevel::Low as i32),
+            (GAME_QR_EC_MEDIUM, qr::EcLevel::Medium as i32),
+            (GAME_QR_EC_QUARTILE, qr::EcLevel::Quartile as i32),
+            (GAME_QR_EC_HIGH, qr::EcLevel::High as i32),
+            (GAME_KEY_DOWN, KeyAction::Down as i32),
+            (GAME_KEY_UP, KeyAction::Up as i32),
+            (GAME_KEY_REPEAT, KeyAction::Repeat as i32),
+            (GAME_PAD_DPAD_UP, Control::DpadUp as i32),
+            (GAME_PAD_DPAD_DOWN, Control::DpadDown as i32),
+            (GAME_PAD_DPAD_LEFT, Control::DpadLeft as i32),
+            (GAME_PAD_DPAD_RIGHT, Control::DpadRight as i32),
+            (GAME_PAD_SOUTH, Control::South as i32),
+            (GAME_PAD_EAST, Control::East as i32),
+            (GAME_PAD_WEST, Control::West as i32),
+            (GAME_PAD_NORTH, Control::North as i32),
+            (GAME_PAD_START, Control::Start as i32),
+            (GAME_PAD_SELECT, Control::Select as i32),
+            (GAME_PAD_LEFT_SHOULDER, Control::LeftShoulder as i32),
+            (GAME_PAD_RIGHT_SHOULDER, Control::RightShoulder as i32),
+            (GAME_PAD_LEFT_STICK_X, Control::LeftStickX as i32),
+            (GAME_PAD_LEFT_STICK_Y, Control::LeftStickY as i32),
+            (GAME_PAD_RIGHT_STICK_X, Control::RightStickX as i32),
+            (GAME_PAD_RIGHT_STICK_Y, Control::RightStickY as i32),
+            (GAME_PAD_LEFT_TRIGGER, Control::LeftTrigger as i32),
+            (GAME_PAD_RIGHT_TRIGGER, Control::RightTrigger as i32),
+            (GAME_SENSOR_ACCELEROMETER, SensorType::Accelerometer as i32),
+            (GAME_SENSOR_GYROSCOPE, SensorType::Gyroscope as i32),
+            (GAME_STATUS_OK, crate::GAME_STATUS_OK),
+            (GAME_STATUS_POISONED, crate::GAME_STATUS_POISONED),
+            (GAME_STATUS_INVALID, crate::GAME_STATUS_INVALID),
+        ];
+        for (c, engine) in events.into_iter().chain(u32s) {
+            assert_eq!(c, engine);
+        }
+        for (c, engine) in u8s {
+            assert_eq!(c, engine);
+        }
+        for (c, engine) in i32s {
+            assert_eq!(c, engine);
+        }
+
+        let features = [
+            (GAME_FEATURE_PHYSICS, Features::PHYSICS),
+            (GAME_FEATURE_ASYNC_ASSETS, Features::ASYNC_ASSETS),
+            (GAME_FEATURE_AUDIO, Features::AUDIO),
+            (GAME_FEATURE_NETWORK, Features::NETWORK),
+            (GAME_FEATURE_DEBUG_SERVER, Features::DEBUG_SERVER),
+            (GAME_FEATURE_HAPTICS, Features::HAPTICS),
+        ];
+        for (c, feature) in features {
+            assert_eq!(c, feature.bits());
+        }
+        assert_eq!(GAME_INVALID_SOUND, crate::audio::INVALID_SOUND);
+        let levels = [GAME_LOG_OFF, GAME_LOG_ERROR, GAME_LOG_WARN, GAME_LOG_INFO, GAME_LOG_DEBUG, GAME_LOG_TRACE];
+        for (level, filter) in levels.into_iter().zip(log::LevelFilter::iter()) {
+            assert_eq!(crate::logging::level_filter(level), filter);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// C API constants, written into include/game_api.h by build.rs; only tests compile them, against the
+// engine's own values
+#[cfg(test)]
+mod constants;
+pub mod strings;
@@ -0,0 +1,102 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr};
+#[cfg(any(target_os = "android", target_os = "ios"))]
+use std::ffi::CString;
+use std::sync::{Mutex, OnceLock};
+
+/// Why a string crossing the FFI boundary was rejected
+/// Values are part of the C API (game_last_string_error); never renumber
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum StringError {
+    None = 0,
+    /// Null pointer where a string was required
+    Null = 1,
+    /// Bytes are not valid UTF-8
+    InvalidUtf8 = 2,
+    /// Interior NUL byte, which C would silently truncate at (only strings handed to the platform
+    /// on Android and iOS are checked)
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    InteriorNul = 3,
+}
+
+impl std::fmt::Display for StringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            StringError::None => "no error",
+            StringError::Null => "null string",
+            StringError::InvalidUtf8 => "string is not valid UTF-8",
+            #[cfg(any(target_os = "android", target_os = "ios"))]
+            StringError::InteriorNul => "string contains a NUL byte",
+        })
+    }
+}
+
+thread_local! {
+    /// Outcome of the calling thread's last string conversion (like errno)
+    static LAST_ERROR: Cell<StringError> = const { Cell::new(StringError::None) };
+}
+
+fn record<T>(result: Result<T, StringError>) -> Result<T, StringError> {
+    LAST_ERROR.with(|last| last.set(result.as_ref().err().copied().unwrap_or(StringError::None)));
+    result
+}
+
+/// Error from the calling thread's last string conversion (StringError::None if it succeeded)
+pub fn last_error() -> StringError {
+    LAST_ERROR.with(Cell::get)
+}
+
+/// Borrow a NUL-terminated UTF-8 string from the host
+/// Invalid UTF-8 is rejected rather than replaced, so names round-trip exactly
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL-terminated string that outlives the borrow
+pub unsafe fn read<'a>(ptr: *const c_char) -> Result<&'a str, StringError> {
+    if ptr.is_null() {
+        return record(Err(StringError::Null));
+    }
+    record(CStr::from_ptr(ptr).to_str().map_err(|_| StringError::InvalidUtf8))
+}
+
+/// Copy a string for a C call, rejecting interior NULs instead of panicking
+#[cfg(any(target_os = "android", target_os = "ios"))]
+pub fn to_cstring(s: impl Into<Vec<u8>>) -> Result<CString, StringError> {
+    record(CString::new(s).map_err(|_| StringError::InteriorNul))
+}
+
+/// Cheap, copyable stand-in for a frequently used name
+/// Ids are process-wide and stable until exit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NameId(u32);
+
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<Box<str>, NameId>,
+    /// Names by id - 1
+    names: Vec<Box<str>>,
+}
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
+
+/// Id for `name`; equal names always map to the same id
+pub fn intern(name: &str) -> NameId {
+    let mut interner = interner().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(&id) = interner.ids.get(name) {
+        return id;
+    }
+    let id = NameId(interner.ids.len() as u32 + 1);
+    interner.ids.insert(name.into(), id);
+    interner.names.push(name.into());
+    id
+}
+
+/// The name `id` was interned from
+pub fn name(id: NameId) -> String {
+    let interner = interner().lock().unwrap_or_else(|e| e.into_inner());
+    interner.names.get(id.0 as usize - 1).map_or_else(String::new, |name| name.to_string())
+}
@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+/// Handles (GameHandle addresses) whose loop is running
+/// A frame callback checks this before touching its handle, so one still queued when the loop
+/// stops or the engine is destroyed does nothing
+static RUNNING: LazyLock<Mutex<HashSet<usize>>> = LazyLock::new(Mutex::default);
+
+fn running() -> MutexGuard<'static, HashSet<usize>> {
+    RUNNING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Draws the loop's frame for the vsync at the given time; true if a frame was drawn
+pub type FrameFn = fn(handle: usize, vsync_ns: u64) -> bool;
+
+/// Start `handle`'s loop on the calling thread; false if it was already running
+/// On Android the thread's Choreographer drives `frame` and the frame is presented to the current EGL
+/// surface. Elsewhere the host's display link calls game_loop_frame, which runs `frame` while the
+/// loop is running
+pub fn start(handle: usize, frame: FrameFn) -> Result<bool, String> {
+    if !running().insert(handle) {
+        return Ok(false);
+    }
+    if let Err(e) = sys::schedule(handle, frame) {
+        running().remove(&handle);
+        return Err(e);
+    }
+    Ok(true)
+}
+
+/// Stop `handle`'s loop (a frame already queued is dropped); false if it wasn't running
+/// Also called by game_destroy
+pub fn stop(handle: usize) -> bool {
+    running().remove(&handle)
+}
+
+pub fn is_running(handle: usize) -> bool {
+    running().contains(&handle)
+}
+
+#[cfg(target_os = "android")]
+mod sys {
+    use std::collections::HashSet;
+    use std::ffi::{c_char, c_long, c_void};
+    use std::sync::{LazyLock, Mutex, OnceLock};
+
+    use super::FrameFn;
+
+    type Callback64 = unsafe extern "C" fn(frame_time_nanos: i64, data: *mut c_void);
+    type Callback = unsafe extern "C" fn(frame_time_nanos: c_long, data: *mut c_void);
+    type GetInstance = unsafe extern "C" fn() -> *mut c_void;
+    type PostFrameCallback64 = unsafe extern "C" fn(*mut c_void, Callback64, *mut c_void);
+    type PostFrameCallback = unsafe extern "C" fn(*mut c_void, Callback, *mut c_void);
+
+    /// libandroid's Choreographer entry points, looked up at runtime so the library still loads on
+    /// API levels without them
+    struct Choreographer {
+        get_instance: GetInstance,
+        /// API 29; the frame time is 64-bit on every ABI
+        post_frame_callback64: Option<PostFrameCallback64>,
+        /// API 24; the frame time is a `long`, which wraps on 32-bit ABIs
+        post_frame_callback: Option<PostFrameCallback>,
+    }
+
+    #[link(name = "dl")]
+    extern "C" {
+        fn dlopen(filename: *const c_char, flags: i32) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+
+    #[link(name = "EGL")]
+    extern "C" {
+        fn eglGetCurrentDisplay() -> *mut c_void;
+        fn eglGetCurrentSurface(readdraw: i32) -> *mut c_void;
+        fn eglSwapBuffers(display: *mut c_void, surface: *mut c_void) -> u32;
+    }
+    const EGL_DRAW: i32 = 0x3059;
+    const RTLD_NOW: i32 = 2;
+
+    fn choreographer() -> Option<&'static Choreographer> {
+        static CHOREOGRAPHER: OnceLock<Option<Choreographer>> = OnceLock::new();
+        CHOREOGRAPHER
+            .get_or_init(|| unsafe {
+                let library = dlopen(c"libandroid.so".as_ptr(), RTLD_NOW);
+                if library.is_null() {
+                    return None;
+                }
+                let get_instance = dlsym(library, c"AChoreographer_getInstance".as_ptr());
+                if get_instance.is_null() {
+                    return None;
+                }
+                let post64 = dlsym(library, c"AChoreographer_postFrameCallback64".as_ptr());
+                let post = dlsym(library, c"AChoreographer_postFrameCallback".as_ptr());
+                Some(Choreographer {
+                    get_instance: std::mem::transmute::<*mut c_void, GetInstance>(get_instance),
+                    post_frame_callback64: (!post64.is_null())
+                        .then(|| std::mem::transmute::<*mut c_void, PostFrameCallback64>(post64)),
+                    post_frame_callback: (!post.is_null()).then(|| std::mem::transmute::<*mut c_void, PostFrameCallback>(post)),
+                })
+            })
+            .as_ref()
+    }
+
+    /// The frame function, set once by the first start (every loop runs the same one)
+    static FRAME: OnceLock<FrameFn> = OnceLock::new();
+
+    /// Handles with a callback queued, so a loop restarted before its last callback ran doesn't get two
+    static POSTED: LazyLock<Mutex<HashSet<usize>>> = LazyLock::new(Mutex::default);
+
+    fn posted(handle: usize, queued: bool) -> bool {
+        let mut posted = POSTED.lock().unwrap_or_else(|e| e.into_inner());
+        if queued {
+            posted.insert(handle)
+        } else {
+            posted.remove(&handle)
+        }
+    }
+
+    pub fn schedule(handle: usize, frame: FrameFn) -> Result<(), String> {
+        FRAME.get_or_init(|| frame);
+        if !posted(handle, true) {
+            // The queued callback carries on the restarted loop
+            return Ok(());
+        }
+        post(handle).inspect_err(|_| {
+            posted(handle, false);
+        })
+    }
+
+    /// Queue `handle`'s next frame; the caller has marked it posted
+    fn post(handle: usize) -> Result<(), String> {
+        let choreographer = choreographer().ok_or("AChoreographer unavailable (needs API 24)")?;
+        unsafe {
+            // Null without a Looper on this thread (e.g. GLSurfaceView's GL thread)
+            let instance = (choreographer.get_instance)();
+            if instance.is_null() {
+                return Err("No Looper on this thread for AChoreographer".into());
+            }
+            let data = handle as *mut c_void;
+            match (choreographer.post_frame_callback64, choreographer.post_frame_callback) {
+                (Some(post64), _) => post64(instance, on_frame64, data),
+                (None, Some(post)) => post(instance, on_frame, data),
+                (None, None) => return Err("AChoreographer_postFrameCallback unavailable".into()),
+            }
+        }
+        Ok(())
+    }
+
+    unsafe extern "C" fn on_frame64(frame_time_nanos: i64, data: *mut c_void) {
+        frame(data as usize, frame_time_nanos as u64);
+    }
+
+    unsafe extern "C" fn on_frame(frame_time_nanos: c_long, data: *mut c_void) {
+        frame(data as usize, frame_time_nanos as u64);
+    }
+
+    fn frame(handle: usize, vsync_ns: u64) {
+        if !super::is_running(handle) {
+            posted(handle, false);
+            return;
+        }
+        if FRAME.get().is_some_and(|frame| frame(handle, vsync_ns)) {
+            unsafe { eglSwapBuffers(eglGetCurrentDisplay(), eglGetCurrentSurface(EGL_DRAW)) };
+        }
+        // Post before the next vsync whether or not this one drew, unless the frame stopped the loop
+        if super::is_running(handle) {
+            if let Err(e) = post(handle) {
+                log::error!("Frame loop stopped: {}", e);
+                super::stop(handle);
+                posted(handle, false);
+            }
+        } else {
+            posted(handle, false);
+        }
+    }
+}
+
+/// The host's display link (CADisplayLink on iOS) calls game_loop_frame
+#[cfg(not(target_os = "android"))]
+mod sys {
+    use super::FrameFn;
+
+    pub fn schedule(_handle: usize, _frame: FrameFn) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use glow::HasContext;
+
+/// Open a KHR_debug group named `label` (no-op without KHR_debug / GL ES 3.2)
+/// Labels start with "game_engine/" so GPU profiles of the host app attribute the work to this crate
+pub fn push_group(gl: &glow::Context, label: &str) {
+    if gl.supports_debug() {
+        unsafe { gl.push_debug_group(glow::DEBUG_SOURCE_APPLICATION, 0, label) };
+    }
+}
+
+/// Close the group opened by the matching push_group
+pub fn pop_group(gl: &glow::Context) {
+    if gl.supports_debug() {
+        unsafe { gl.pop_debug_group() };
+    }
+}
+
+/// Run `f` inside a debug group
+pub fn group<R>(gl: &glow::Context, label: &str, f: impl FnOnce() -> R) -> R {
+    push_group(gl, label);
+    let result = f();
+    pop_group(gl);
+    result
+}
@@ -0,0 +1,104 @@
+use std::ffi::c_void;
+
+use crate::gl_debug;
+
+/// Debug group wrapping a frame picked for capture; search for it in RenderDoc or AGI
+pub const CAPTURE_GROUP_LABEL: &str = "game_engine/game_trigger_gpu_capture";
+
+/// RenderDoc in-app API function table (RENDERDOC_API_1_1_2), up to EndFrameCapture
+#[repr(C)]
+struct RenderDocApi {
+    // GetAPIVersion .. SetActiveWindow
+    _unused: [*const c_void; 19],
+    start_frame_capture: unsafe extern "C" fn(device: *mut c_void, window: *mut c_void),
+    _is_frame_capturing: unsafe extern "C" fn() -> u32,
+    end_frame_capture: unsafe extern "C" fn(device: *mut c_void, window: *mut c_void) -> u32,
+}
+
+/// RenderDoc's API if its library was injected into this process (never loads it ourselves)
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn find_renderdoc() -> Option<&'static RenderDocApi> {
+    use std::ffi::c_char;
+
+    #[link(name = "dl")]
+    extern "C" {
+        fn dlopen(filename: *const c_char, flags: i32) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+    const RTLD_NOW: i32 = 2;
+    const RTLD_NOLOAD: i32 = 4;
+    /// eRENDERDOC_API_Version_1_1_2
+    const API_VERSION: i32 = 10102;
+    type GetApi = unsafe extern "C" fn(version: i32, out: *mut *mut c_void) -> i32;
+
+    let libraries = [c"librenderdoc.so", c"libVkLayer_GLES_RenderDoc.so"];
+    let handle = libraries
+        .iter()
+        .map(|name| unsafe { dlopen(name.as_ptr(), RTLD_NOW | RTLD_NOLOAD) })
+        .find(|handle| !handle.is_null())?;
+    unsafe {
+        let get_api = dlsym(handle, c"RENDERDOC_GetAPI".as_ptr());
+        if get_api.is_null() {
+            return None;
+        }
+        let get_api: GetApi = std::mem::transmute(get_api);
+        let mut api = std::ptr::null_mut();
+        if get_api(API_VERSION, &mut api) != 1 || api.is_null() {
+            return None;
+        }
+        Some(&*(api as *const RenderDocApi))
+    }
+}
+
+/// RenderDoc doesn't inject into iOS or desktop hosts other than Linux
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn find_renderdoc() -> Option<&'static RenderDocApi> {
+    None
+}
+
+/// Marks (and with RenderDoc attached, captures) the next rendered frame
+#[derive(Default)]
+pub struct GpuCapture {
+    requested: bool,
+    /// Frame currently inside the debug group / RenderDoc capture
+    active: bool,
+    /// Looked up on the first request
+    renderdoc: Option<Option<&'static RenderDocApi>>,
+}
+
+impl GpuCapture {
+    /// Pick the next frame; returns true if RenderDoc will capture it
+    /// Without RenderDoc the frame is still wrapped in a KHR_debug group for AGI captures
+    pub fn request(&mut self) -> bool {
+        self.requested = true;
+        self.renderdoc.get_or_insert_with(find_renderdoc).is_some()
+    }
+
+    /// Start of a frame: open the debug group and RenderDoc capture if one was requested
+    pub fn begin_frame(&mut self, gl: &glow::Context) {
+        if !std::mem::take(&mut self.requested) {
+            return;
+        }
+        self.active = true;
+        if let Some(Some(api)) = self.renderdoc {
+            // Null device and window: capture whatever context is current
+            unsafe { (api.start_frame_capture)(std::ptr::null_mut(), std::ptr::null_mut()) };
+        }
+        gl_debug::push_group(gl, CAPTURE_GROUP_LABEL);
+    }
+
+    /// End of a frame (after glFinish): close what begin_frame opened
+    pub fn end_frame(&mut self, gl: &glow::Context) {
+        if !std::mem::take(&mut self.active) {
+            return;
+        }
+        gl_debug::pop_group(gl);
+        if let Some(Some(api)) = self.renderdoc {
+            if unsafe { (api.end_frame_capture)(std::ptr::null_mut(), std::ptr::null_mut()) } == 0 {
+                log::warn!("RenderDoc frame capture failed");
+            } else {
+                log::info!("RenderDoc captured the frame");
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use glow::HasContext;
+
+/// Frames between glGetError checks with validation off; the query is a round trip to the driver
+/// that stalls the pipeline on some GPUs, so it isn't made every frame
+const ERROR_CHECK_INTERVAL: u64 = 60;
+
+/// Longest the render thread blocks on the previous frame's fence
+const FENCE_TIMEOUT_NS: i32 = 50_000_000;
+
+/// How a frame's end is synchronized with the GPU
+/// With validation (debug builds, or game_set_gpu_validation) every frame ends in glFinish and
+/// glGetError, so GPU time is exact and errors are pinned to their frame. Without it the frame is
+/// fenced instead: the next frame waits only for the one before it, keeping the CPU at most a frame
+/// ahead without draining the pipeline, and errors are checked every ERROR_CHECK_INTERVAL frames
+pub struct GpuSync {
+    pub validation: bool,
+    /// Fences need GLES 3 / GL 3.2; without them frames aren't waited on at all
+    fences: bool,
+    /// Fence after the last frame's commands
+    pending: Option<glow::Fence>,
+    frames: u64,
+}
+
+/// What ending a frame found
+pub struct FrameEnd {
+    /// Time spent waiting on the GPU (for the last frame, or this one under validation)
+    pub gpu_wait: Duration,
+    /// GL error, or NO_ERROR (also on frames whose errors weren't checked)
+    pub error: u32,
+}
+
+impl GpuSync {
+    pub fn new(gl: &glow::Context) -> GpuSync {
+        GpuSync { validation: cfg!(debug_assertions), fences: gl.version().major >= 3, pending: None, frames: 0 }
+    }
+
+    /// Call after the frame's commands; `finish` is false for drivers where glFinish stalls (quirks)
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn end_frame(&mut self, gl: &glow::Context, finish: bool) -> FrameEnd {
+        let start = Instant::now();
+        self.frames += 1;
+        if self.validation {
+            self.release(gl);
+            if finish {
+                gl.finish();
+            }
+            return FrameEnd { gpu_wait: start.elapsed(), error: gl.get_error() };
+        }
+
+        if let Some(fence) = self.pending.take() {
+            let status = gl.client_wait_sync(fence, glow::SYNC_FLUSH_COMMANDS_BIT, FENCE_TIMEOUT_NS);
+            gl.delete_sync(fence);
+            if status == glow::WAIT_FAILED {
+                log::warn!("Waiting on the frame fence failed; frames won't be fenced");
+                self.fences = false;
+            }
+        }
+        let gpu_wait = start.elapsed();
+        if self.fences {
+            self.pending = gl.fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0).ok();
+        }
+        let error = if self.frames.is_multiple_of(ERROR_CHECK_INTERVAL) { gl.get_error() } else { glow::NO_ERROR };
+        FrameEnd { gpu_wait, error }
+    }
+
+    /// Delete the pending fence (before the context goes away, or when validation turns on)
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn release(&mut self, gl: &glow::Context) {
+        if let Some(fence) = self.pending.take() {
+            gl.delete_sync(fence);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use glow::HasContext;
+
+use crate::stats::GpuPassStats;
+
+/// Frames whose queries may still be in flight; results are read this many frames late, and a frame
+/// whose results aren't in by then is dropped rather than waited on
+const FRAMES_IN_FLIGHT: usize = 4;
+
+/// GL_GPU_DISJOINT_EXT: set when something (a power state change, another context) made the timer's
+/// results meaningless since it was last read
+const GPU_DISJOINT: u32 = 0x8FBB;
+
+/// What a timed stretch of GL commands is counted as
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pass {
+    Clear,
+    /// The world, host draw lists and everything else beneath the HUD
+    Scene,
+    /// egui's HUD and the debug overlay
+    Ui,
+}
+
+/// GPU time per pass from timer queries (GL_TIME_ELAPSED), read back a few frames later so the
+/// CPU never waits on them. Without timer query support every call is a no-op
+pub struct GpuTimer {
+    /// GL 3.3 or ARB_timer_query; GLES 3 with EXT_disjoint_timer_query
+    pub supported: bool,
+    /// Only GLES reports disjoint periods
+    embedded: bool,
+    /// Queries each recent frame issued, by pass; `current` is the frame being drawn
+    frames: [Vec<(Pass, glow::Query)>; FRAMES_IN_FLIGHT],
+    current: usize,
+    /// Queries whose results were read, for reuse
+    spare: Vec<glow::Query>,
+}
+
+impl GpuTimer {
+    pub fn new(gl: &glow::Context) -> GpuTimer {
+        let version = gl.version();
+        let extension = |name: &str| gl.supported_extensions().iter().any(|e| e.ends_with(name));
+        // glow loads the core query entry points, which GLES 2 lacks even with the extension
+        let supported = if version.is_embedded {
+            version.major >= 3 && extension("EXT_disjoint_timer_query")
+        } else {
+            version.major > 3 || (version.major == 3 && version.minor >= 3) || extension("ARB_timer_query")
+        };
+        GpuTimer { supported, embedded: version.is_embedded, frames: Default::default(), current: 0, spare: Vec::new() }
+    }
+
+    /// Start timing a frame; returns the pass times of the frame FRAMES_IN_FLIGHT frames back,
+    /// if its results are in
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn begin_frame(&mut self, gl: &glow::Context) -> Option<GpuPassStats> {
+        if !self.supported {
+            return None;
+        }
+        self.current = (self.current + 1) % FRAMES_IN_FLIGHT;
+        let queries = std::mem::take(&mut self.frames[self.current]);
+        let disjoint = self.embedded && gl.get_parameter_i32(GPU_DISJOINT) != 0;
+        let available = !queries.is_empty()
+            && queries.iter().all(|&(_, query)| gl.get_query_parameter_u32(query, glow::QUERY_RESULT_AVAILABLE) != 0);
+        let valid = available && !disjoint;
+        let mut stats = GpuPassStats::default();
+        for (pass, query) in queries {
+            if valid {
+                // Nanoseconds; 32 bits hold over 4 s, far beyond any frame
+                let ms = gl.get_query_parameter_u32(query, glow::QUERY_RESULT) as f32 / 1_000_000.0;
+                match pass {
+                    Pass::Clear => stats.clear_ms += ms,
+                    Pass::Scene => stats.scene_ms += ms,
+                    Pass::Ui => stats.ui_ms += ms,
+                }
+            }
+            self.spare.push(query);
+        }
+        valid.then_some(stats)
+    }
+
+    /// Run `f`, timing the GL commands it issues as `pass` (timed stretches can't nest)
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn time<R>(&mut self, gl: &glow::Context, pass: Pass, f: impl FnOnce() -> R) -> R {
+        let query = if self.supported { self.spare.pop().map(Ok).unwrap_or_else(|| gl.create_query()).ok() } else { None };
+        let Some(query) = query else {
+            return f();
+        };
+        gl.begin_query(glow::TIME_ELAPSED, query);
+        let result = f();
+        gl.end_query(glow::TIME_ELAPSED);
+        self.frames[self.current].push((pass, query));
+        result
+    }
+
+    /// Delete every query (before the context goes away)
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn release(&mut self, gl: &glow::Context) {
+        for frame in &mut self.frames {
+            for (_, query) in frame.drain(..) {
+                gl.delete_query(query);
+            }
+        }
+        for query in self.spare.drain(..) {
+            gl.delete_query(query);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::events::{EventQueue, GameEvent};
+
+/// Impact speeds (points/s) at which a bounce escalates to Medium and Heavy
+const MEDIUM_IMPACT_SPEED: f32 = 250.0;
+const HEAVY_IMPACT_SPEED: f32 = 600.0;
+
+/// Haptic patterns the host maps to platform feedback
+/// Values are part of the C API; never renumber
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum HapticPattern {
+    /// Light tap (UIImpactFeedbackGenerator .light, HapticFeedbackConstants.CLOCK_TICK)
+    Light = 0,
+    Medium = 1,
+    Heavy = 2,
+    /// Notification patterns (UINotificationFeedbackGenerator)
+    Success = 3,
+    Warning = 4,
+    Error = 5,
+    /// Selection change (UISelectionFeedbackGenerator)
+    Selection = 6,
+}
+
+impl HapticPattern {
+    pub fn from_raw(value: u32) -> Option<HapticPattern> {
+        match value {
+            0 => Some(HapticPattern::Light),
+            1 => Some(HapticPattern::Medium),
+            2 => Some(HapticPattern::Heavy),
+            3 => Some(HapticPattern::Success),
+            4 => Some(HapticPattern::Warning),
+            5 => Some(HapticPattern::Error),
+            6 => Some(HapticPattern::Selection),
+            _ => None,
+        }
+    }
+
+    /// Impact strength for a collision at `speed`
+    pub fn for_impact(speed: f32) -> HapticPattern {
+        if speed >= HEAVY_IMPACT_SPEED {
+            HapticPattern::Heavy
+        } else if speed >= MEDIUM_IMPACT_SPEED {
+            HapticPattern::Medium
+        } else {
+            HapticPattern::Light
+        }
+    }
+}
+
+/// Haptic requests queued by game code during a tick, sent as Haptic events at its end
+pub struct Haptics {
+    enabled: bool,
+    pending: Vec<HapticPattern>,
+    /// Strongest impact this tick; several bounces in one tick (a corner) buzz once
+    impact: Option<HapticPattern>,
+}
+
+impl Haptics {
+    pub fn new(enabled: bool) -> Self {
+        Haptics { enabled, pending: Vec::new(), impact: None }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pending.clear();
+            self.impact = None;
+        }
+    }
+
+    /// Queue a pattern; repeats within a tick are merged
+    /// Returns false while haptics are disabled
+    pub fn request(&mut self, pattern: HapticPattern) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if !self.pending.contains(&pattern) {
+            self.pending.push(pattern);
+        }
+        true
+    }
+
+    /// Queue an impact scaled by collision speed
+    pub fn impact(&mut self, speed: f32) {
+        if self.enabled {
+            let pattern = HapticPattern::for_impact(speed);
+            self.impact = Some(self.impact.map_or(pattern, |current| current.max(pattern)));
+        }
+    }
+
+    /// Move queued requests onto the outbound event channel
+    pub fn flush(&mut self, events: &mut EventQueue) {
+        if let Some(pattern) = self.impact.take() {
+            events.push(GameEvent::Haptic { pattern });
+        }
+        for pattern in self.pending.drain(..) {
+            events.push(GameEvent::Haptic { pattern });
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use khronos_egl as egl;
+
+/// EGL_PLATFORM_SURFACELESS_MESA (EGL_MESA_platform_surfaceless)
+const PLATFORM_SURFACELESS_MESA: egl::Enum = 0x31DD;
+
+/// RGBA8 GL ES 3 config for the off-screen surface
+const CONFIG_ATTRIBS: &[egl::Int] = &[
+    egl::SURFACE_TYPE,
+    egl::PBUFFER_BIT,
+    egl::RENDERABLE_TYPE,
+    egl::OPENGL_ES3_BIT,
+    egl::RED_SIZE,
+    8,
+    egl::GREEN_SIZE,
+    8,
+    egl::BLUE_SIZE,
+    8,
+    egl::ALPHA_SIZE,
+    8,
+    // Clip masks
+    egl::STENCIL_SIZE,
+    8,
+    egl::NONE,
+];
+
+/// Off-screen GL ES 3 context on a pbuffer surface, for rendering without a window (CI, golden images)
+/// libEGL is loaded at runtime; Mesa's surfaceless platform is preferred so no display server is needed
+/// The context is current on the creating thread, which must make every later GL call
+pub struct HeadlessContext {
+    egl: egl::DynamicInstance<egl::EGL1_4>,
+    display: egl::Display,
+    surface: egl::Surface,
+    context: egl::Context,
+}
+
+impl HeadlessContext {
+    /// Create a `width` x `height` pbuffer context, make it current and load GL through it
+    pub fn create(width: u32, height: u32) -> Result<(HeadlessContext, glow::Context), String> {
+        let egl = unsafe { egl::DynamicInstance::<egl::EGL1_4>::load_required() }
+            .map_err(|e| format!("Failed to load libEGL: {}", e))?;
+        let display = open_display(&egl)?;
+
+        let created = (|| {
+            egl.bind_api(egl::OPENGL_ES_API).map_err(|e| format!("eglBindAPI: {}", e))?;
+            let config = egl
+                .choose_first_config(display, CONFIG_ATTRIBS)
+                .map_err(|e| format!("eglChooseConfig: {}", e))?
+                .ok_or("No RGBA8 GL ES 3 pbuffer config")?;
+            // A zero-sized pbuffer is invalid; the engine resizes its viewport later anyway
+            let size = [egl::WIDTH, width.max(1) as egl::Int, egl::HEIGHT, height.max(1) as egl::Int, egl::NONE];
+            let surface = egl
+                .create_pbuffer_surface(display, config, &size)
+                .map_err(|e| format!("eglCreatePbufferSurface: {}", e))?;
+            let context = egl
+                .create_context(display, config, None, &[egl::CONTEXT_CLIENT_VERSION, 3, egl::NONE])
+                .map_err(|e| format!("eglCreateContext: {}", e));
+            let context = match context {
+                Ok(context) => context,
+                Err(e) => {
+                    let _ = egl.destroy_surface(display, surface);
+                    return Err(e);
+                }
+            };
+            Ok((surface, context))
+        })();
+        let (surface, context) = match created {
+            Ok(created) => created,
+            Err(e) => {
+                let _ = egl.terminate(display);
+                return Err(e);
+            }
+        };
+
+        let headless = HeadlessContext { egl, display, surface, context };
+        headless
+            .egl
+            .make_current(display, Some(surface), Some(surface), Some(context))
+            .map_err(|e| format!("eglMakeCurrent: {}", e))?;
+        let gl = unsafe {
+            glow::Context::from_loader_function(|name| {
+                headless.egl.get_proc_address(name).map_or(std::ptr::null(), |f| f as *const _)
+            })
+        };
+        Ok((headless, gl))
+    }
+}
+
+/// Initialized surfaceless display if the driver has one, otherwise the default display
+fn open_display(egl: &egl::DynamicInstance<egl::EGL1_4>) -> Result<egl::Display, String> {
+    if let Some(egl) = egl.upcast::<egl::EGL1_5>() {
+        let surfaceless =
+            unsafe { egl.get_platform_display(PLATFORM_SURFACELESS_MESA, egl::DEFAULT_DISPLAY, &[egl::ATTRIB_NONE]) };
+        if let Ok(display) = surfaceless {
+            if egl.initialize(display).is_ok() {
+                return Ok(display);
+            }
+        }
+    }
+    let display = unsafe { egl.get_display(egl::DEFAULT_DISPLAY) }.ok_or("No EGL display")?;
+    egl.initialize(display).map_err(|e| format!("eglInitialize: {}", e))?;
+    Ok(display)
+}
+
+impl Drop for HeadlessContext {
+    fn drop(&mut self) {
+        let _ = self.egl.make_current(self.display, None, None, None);
+        let _ = self.egl.destroy_context(self.display, self.context);
+        let _ = self.egl.destroy_surface(self.display, self.surface);
+        let _ = self.egl.terminate(self.display);
+    }
+}
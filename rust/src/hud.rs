@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+
+use egui::epaint::Mesh;
+use egui::{Color32, Rect, Shape, Vec2};
+
+use crate::clip::{self, ClipShape, ClipStack};
+use crate::qr::{EcLevel, QrCode};
+use crate::scene::Affine;
+
+/// QR code centered on screen
+#[derive(Clone)]
+pub struct QrOverlay {
+    code: QrCode,
+    /// Requested edge length in pixels, quiet zone included
+    size: f32,
+    quiet_zone: u32,
+    /// Rounded corner mask for the last screen drawn on, kept so frames compare equal
+    clip: RefCell<Option<(Rect, ClipShape)>>,
+}
+
+impl QrOverlay {
+    /// Bounds (quiet zone included) and module edge length
+    fn layout(&self, screen: Rect) -> (Rect, f32) {
+        let modules = self.code.size() as u32 + 2 * self.quiet_zone;
+        let module = (self.size / modules as f32).floor().max(1.0);
+        let edge = module * modules as f32;
+        let min = (screen.center() - Vec2::splat(edge / 2.0)).round();
+        (Rect::from_min_size(min, Vec2::splat(edge)), module)
+    }
+
+    /// Light background plus dark modules as one mesh
+    /// Modules snap to whole pixels so scanners see crisp edges
+    fn shape(&self, screen: Rect) -> Shape {
+        let (bounds, module) = self.layout(screen);
+        let min = bounds.min;
+
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(bounds, Color32::WHITE);
+        let origin = min + Vec2::splat(module * self.quiet_zone as f32);
+        for y in 0..self.code.size() {
+            for x in 0..self.code.size() {
+                if self.code.is_dark(x, y) {
+                    let pos = origin + Vec2::new(x as f32, y as f32) * module;
+                    mesh.add_colored_rect(Rect::from_min_size(pos, Vec2::splat(module)), Color32::BLACK);
+                }
+            }
+        }
+        Shape::mesh(mesh)
+    }
+
+    /// Mask rounding the panel's corners within the quiet zone (so no module is cut); None without one
+    fn clip(&self, screen: Rect) -> Option<ClipShape> {
+        let radius = self.layout(screen).1 * self.quiet_zone as f32 / 2.0;
+        if radius <= 0.0 {
+            return None;
+        }
+        let mut clip = self.clip.borrow_mut();
+        if let Some((cached_screen, shape)) = clip.as_ref() {
+            if *cached_screen == screen {
+                return Some(shape.clone());
+            }
+        }
+        match ClipShape::rounded_rect(&Affine::IDENTITY, self.layout(screen).0, radius) {
+            Ok(shape) => {
+                *clip = Some((screen, shape.clone()));
+                Some(shape)
+            }
+            Err(e) => {
+                log::warn!("QR panel clip failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Screen-space overlay drawn above the scene
+#[derive(Clone, Default)]
+pub struct Hud {
+    qr: Option<QrOverlay>,
+}
+
+impl Hud {
+    /// Show `data` as a QR code; false if it doesn't fit any QR version at that level
+    pub fn show_qr(&mut self, data: &[u8], size: f32, ec_level: EcLevel, quiet_zone: u32) -> bool {
+        match QrCode::encode(data, ec_level) {
+            Some(code) => {
+                self.qr = Some(QrOverlay { code, size, quiet_zone, clip: RefCell::new(None) });
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn hide_qr(&mut self) {
+        self.qr = None;
+    }
+
+    /// Shapes added by draw
+    pub fn shape_count(&self) -> usize {
+        match &self.qr {
+            Some(qr) if qr.quiet_zone > 0 => 1 + clip::MASK_SHAPES,
+            Some(_) => 1,
+            None => 0,
+        }
+    }
+
+    /// Where the QR code is drawn, if shown
+    pub fn qr_bounds(&self, screen: Rect) -> Option<Rect> {
+        self.qr.as_ref().map(|qr| qr.layout(screen).0)
+    }
+
+    pub fn draw(&self, painter: &egui::Painter, screen: Rect) {
+        if let Some(qr) = &self.qr {
+            let mut clips = ClipStack::new(painter);
+            if let Some(clip) = qr.clip(screen) {
+                clips.push(&clip);
+            }
+            clips.painter().add(qr.shape(screen));
+            clips.finish();
+        }
+    }
+}
+
@@ -0,0 +1,180 @@
+use egui::Vec2;
+
+/// Controllers tracked at once (pad ids 0..MAX_PADS)
+pub const MAX_PADS: usize = 4;
+
+/// Default radial dead zone for analog sticks (fraction of full deflection)
+pub const DEFAULT_DEAD_ZONE: f32 = 0.2;
+
+/// Buttons and axes reported by game_gamepad_event
+/// Values are part of the C API; never renumber
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum Control {
+    DpadUp = 0,
+    DpadDown = 1,
+    DpadLeft = 2,
+    DpadRight = 3,
+    /// A on Xbox layouts, Cross on PlayStation
+    South = 4,
+    East = 5,
+    West = 6,
+    North = 7,
+    Start = 8,
+    Select = 9,
+    LeftShoulder = 10,
+    RightShoulder = 11,
+    /// Axes: sticks -1..1 with +y pointing down the screen, triggers 0..1
+    LeftStickX = 32,
+    LeftStickY = 33,
+    RightStickX = 34,
+    RightStickY = 35,
+    LeftTrigger = 36,
+    RightTrigger = 37,
+}
+
+impl Control {
+    pub fn from_raw(value: i32) -> Option<Control> {
+        use Control::*;
+        Some(match value {
+            0 => DpadUp,
+            1 => DpadDown,
+            2 => DpadLeft,
+            3 => DpadRight,
+            4 => South,
+            5 => East,
+            6 => West,
+            7 => North,
+            8 => Start,
+            9 => Select,
+            10 => LeftShoulder,
+            11 => RightShoulder,
+            32 => LeftStickX,
+            33 => LeftStickY,
+            34 => RightStickX,
+            35 => RightStickY,
+            36 => LeftTrigger,
+            37 => RightTrigger,
+            _ => return None,
+        })
+    }
+
+    /// Index into PadState::axes, or None for buttons
+    fn axis(self) -> Option<usize> {
+        let raw = self as i32;
+        (raw >= Control::LeftStickX as i32).then(|| (raw - Control::LeftStickX as i32) as usize)
+    }
+}
+
+/// Latest reported state of one controller
+#[derive(Clone, Copy, Debug, Default)]
+struct PadState {
+    /// Bit per button Control value
+    buttons: u32,
+    axes: [f32; 6],
+}
+
+impl PadState {
+    fn pressed(&self, control: Control) -> bool {
+        self.buttons & (1 << control as i32) != 0
+    }
+
+    fn dpad(&self) -> Vec2 {
+        let axis = |neg, pos| (self.pressed(pos) as i32 - self.pressed(neg) as i32) as f32;
+        let dpad = Vec2::new(axis(Control::DpadLeft, Control::DpadRight), axis(Control::DpadUp, Control::DpadDown));
+        dpad.normalized()
+    }
+}
+
+/// Connected controllers, updated by host events and polled once per tick
+pub struct Gamepads {
+    pads: [PadState; MAX_PADS],
+    dead_zone: f32,
+    /// Bit per pad steering an extra player instead of the primary one (see players module)
+    bound: u32,
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self {
+            pads: [PadState::default(); MAX_PADS],
+            dead_zone: DEFAULT_DEAD_ZONE,
+            bound: 0,
+        }
+    }
+}
+
+impl Gamepads {
+    /// Set the stick dead zone (clamped to 0.0..=0.95)
+    pub fn set_dead_zone(&mut self, dead_zone: f32) {
+        self.dead_zone = dead_zone.clamp(0.0, 0.95);
+    }
+
+    /// Record a button (value > 0.5 = pressed) or axis value; false for unknown pads
+    pub fn event(&mut self, pad: u32, control: Control, value: f32) -> bool {
+        let Some(state) = self.pads.get_mut(pad as usize) else {
+            return false;
+        };
+        let value = if value.is_finite() { value } else { 0.0 };
+        match control.axis() {
+            Some(axis) => state.axes[axis] = value.clamp(-1.0, 1.0),
+            None if value > 0.5 => state.buttons |= 1 << control as i32,
+            None => state.buttons &= !(1 << control as i32),
+        }
+        true
+    }
+
+    /// Forget a controller's state so a held stick doesn't keep steering
+    pub fn disconnect(&mut self, pad: u32) {
+        if let Some(state) = self.pads.get_mut(pad as usize) {
+            *state = PadState::default();
+        }
+    }
+
+    /// Hand a pad to an extra player (or back to the primary one); false for unknown pads
+    pub fn set_bound(&mut self, pad: u32, bound: bool) -> bool {
+        if pad as usize >= MAX_PADS {
+            return false;
+        }
+        if bound {
+            self.bound |= 1 << pad;
+        } else {
+            self.bound &= !(1 << pad);
+        }
+        true
+    }
+
+    /// Movement requested by the first controller in use that isn't bound to an extra player
+    /// (length 0..=1, +y down)
+    pub fn movement(&self) -> Vec2 {
+        (0..MAX_PADS as u32)
+            .filter(|pad| self.bound & (1 << pad) == 0)
+            .map(|pad| self.pad_movement(pad))
+            .find(|&v| v != Vec2::ZERO)
+            .unwrap_or(Vec2::ZERO)
+    }
+
+    /// Movement requested by one controller; the d-pad wins over the left stick, and stick input
+    /// inside the dead zone is ignored
+    pub fn pad_movement(&self, pad: u32) -> Vec2 {
+        let Some(pad) = self.pads.get(pad as usize) else {
+            return Vec2::ZERO;
+        };
+        let dpad = pad.dpad();
+        if dpad != Vec2::ZERO {
+            return dpad;
+        }
+        let stick = Vec2::new(pad.axes[0], pad.axes[1]);
+        radial_dead_zone(stick, self.dead_zone)
+    }
+}
+
+/// Zero inside the dead zone, rescaled so output ramps from 0 at its edge to 1 at full tilt
+fn radial_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - dead_zone) / (1.0 - dead_zone)).min(1.0);
+    stick / length * scaled
+}
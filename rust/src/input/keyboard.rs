@@ -0,0 +1,144 @@
+use egui::Key;
+
+/// Events held between frames; older ones are dropped if the host stops rendering
+const MAX_PENDING_EVENTS: usize = 256;
+
+bitflags::bitflags! {
+    /// Modifier keys held during a key event
+    /// Bit values are part of the C API; never renumber
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct KeyModifiers: u32 {
+        const SHIFT = 1 << 0;
+        const CTRL = 1 << 1;
+        const ALT = 1 << 2;
+        /// Command on Apple keyboards, Windows/Super elsewhere
+        const META = 1 << 3;
+    }
+}
+
+impl KeyModifiers {
+    fn to_egui(self) -> egui::Modifiers {
+        let meta = self.contains(KeyModifiers::META);
+        egui::Modifiers {
+            alt: self.contains(KeyModifiers::ALT),
+            ctrl: self.contains(KeyModifiers::CTRL),
+            shift: self.contains(KeyModifiers::SHIFT),
+            mac_cmd: meta,
+            command: meta || self.contains(KeyModifiers::CTRL),
+        }
+    }
+}
+
+/// Key action enum
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum KeyAction {
+    Down = 0,
+    Up = 1,
+    Repeat = 2,
+}
+
+impl From<i32> for KeyAction {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => KeyAction::Up,
+            2 => KeyAction::Repeat,
+            _ => KeyAction::Down,
+        }
+    }
+}
+
+/// egui key for a USB HID keyboard usage (Flutter's PhysicalKeyboardKey.usbHidUsage)
+/// The 0x0007 usage page may be included or omitted
+pub fn key_from_hid(usage: u32) -> Option<Key> {
+    let usage = usage & 0xFFFF;
+    let key = match usage {
+        0x04..=0x1D => return Key::from_name(&char::from(b'A' + (usage - 0x04) as u8).to_string()),
+        0x1E..=0x26 => return Key::from_name(&char::from(b'1' + (usage - 0x1E) as u8).to_string()),
+        0x27 => Key::Num0,
+        0x28 | 0x58 => Key::Enter,
+        0x29 => Key::Escape,
+        0x2A => Key::Backspace,
+        0x2B => Key::Tab,
+        0x2C => Key::Space,
+        0x2D | 0x56 => Key::Minus,
+        0x2E => Key::Equals,
+        0x2F => Key::OpenBracket,
+        0x30 => Key::CloseBracket,
+        0x31 => Key::Backslash,
+        0x33 => Key::Semicolon,
+        0x34 => Key::Quote,
+        0x35 => Key::Backtick,
+        0x36 => Key::Comma,
+        0x37 => Key::Period,
+        0x38 => Key::Slash,
+        0x3A..=0x45 => return Key::from_name(&format!("F{}", usage - 0x3A + 1)),
+        0x49 => Key::Insert,
+        0x4A => Key::Home,
+        0x4B => Key::PageUp,
+        0x4C => Key::Delete,
+        0x4D => Key::End,
+        0x4E => Key::PageDown,
+        0x4F => Key::ArrowRight,
+        0x50 => Key::ArrowLeft,
+        0x51 => Key::ArrowDown,
+        0x52 => Key::ArrowUp,
+        0x57 => Key::Plus,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Keyboard and text input waiting for the next rendered egui frame
+#[derive(Default)]
+pub struct KeyboardInput {
+    events: Vec<egui::Event>,
+    modifiers: egui::Modifiers,
+    /// Events discarded because the host stopped rendering
+    dropped: u64,
+}
+
+impl KeyboardInput {
+    /// Queue a key press or release; returns false for keys egui doesn't know
+    pub fn key(&mut self, usage: u32, action: KeyAction, modifiers: KeyModifiers) -> bool {
+        self.modifiers = modifiers.to_egui();
+        let Some(key) = key_from_hid(usage) else {
+            return false;
+        };
+        self.push(egui::Event::Key {
+            key,
+            physical_key: Some(key),
+            pressed: action != KeyAction::Up,
+            repeat: action == KeyAction::Repeat,
+            modifiers: self.modifiers,
+        });
+        true
+    }
+
+    /// Queue committed text (typed characters, IME or soft keyboard output)
+    pub fn text(&mut self, text: &str) {
+        // Control characters arrive as key events instead
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        if !text.is_empty() {
+            self.push(egui::Event::Text(text));
+        }
+    }
+
+    fn push(&mut self, event: egui::Event) {
+        if self.events.len() == MAX_PENDING_EVENTS {
+            self.events.remove(0);
+            self.dropped += 1;
+        }
+        self.events.push(event);
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Move queued events and the current modifiers into a frame's input
+    pub fn drain_into(&mut self, input: &mut egui::RawInput) {
+        input.modifiers = self.modifiers;
+        input.events.append(&mut self.events);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod gamepad;
+pub mod keyboard;
+pub mod routing;
+pub mod sensors;
@@ -0,0 +1,60 @@
+use crate::engine::TouchAction;
+
+/// Which touches the engine claims (game_set_touch_routing); the host hands the rest to its own
+/// gesture handling, such as a scrollable the game view sits in
+/// Values are part of the C API; never renumber
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u32)]
+pub enum TouchRouting {
+    /// Every touch is the game's (the default)
+    All = 0,
+    /// Only gestures that go down on a UI button, the player or an entity with a collider
+    Entities = 1,
+}
+
+impl TouchRouting {
+    pub fn from_raw(value: u32) -> Option<TouchRouting> {
+        match value {
+            0 => Some(TouchRouting::All),
+            1 => Some(TouchRouting::Entities),
+            _ => None,
+        }
+    }
+}
+
+/// Decides per gesture whether the engine claims it: on its down touch, and the moves and lift
+/// that follow go the same way
+pub struct TouchRouter {
+    policy: TouchRouting,
+    /// Whether the current (or last) gesture is the engine's
+    claimed: bool,
+}
+
+impl Default for TouchRouter {
+    fn default() -> Self {
+        TouchRouter { policy: TouchRouting::All, claimed: true }
+    }
+}
+
+impl TouchRouter {
+    pub fn policy(&self) -> TouchRouting {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: TouchRouting) {
+        self.policy = policy;
+    }
+
+    /// Route a touch; a down starting on something the game owns (`on_entity`) is claimed under
+    /// either policy. Returns whether the engine claims the touch's gesture
+    pub fn route(&mut self, action: TouchAction, on_entity: bool) -> bool {
+        if action == TouchAction::Down {
+            self.claimed = on_entity || self.policy == TouchRouting::All;
+        }
+        self.claimed
+    }
+
+    pub fn claimed(&self) -> bool {
+        self.claimed
+    }
+}
@@ -0,0 +1,93 @@
+/// Standard gravity in m/s^2
+const GRAVITY: f32 = 9.81;
+
+/// Weight of each accelerometer sample in the gravity estimate
+/// Low, so the gyroscope carries quick rotations and the accelerometer corrects drift
+const ACCEL_WEIGHT: f32 = 0.1;
+
+/// Longest gap integrated between gyroscope samples (s)
+const MAX_GYRO_STEP: f32 = 0.1;
+
+/// Sensor kinds accepted by game_sensor_event
+/// Values are part of the C API; never renumber
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum SensorType {
+    /// m/s^2 in Android device axes (x right, y up, z out of the screen; flat face-up reads +z)
+    Accelerometer = 0,
+    /// rad/s around the same axes
+    Gyroscope = 1,
+}
+
+impl SensorType {
+    pub fn from_raw(value: i32) -> Option<SensorType> {
+        match value {
+            0 => Some(SensorType::Accelerometer),
+            1 => Some(SensorType::Gyroscope),
+            _ => None,
+        }
+    }
+}
+
+type Vec3 = [f32; 3];
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// Fuses accelerometer and gyroscope samples into a gravity direction (complementary filter)
+pub struct Sensors {
+    /// Estimated accelerometer reading of gravity alone, device axes
+    gravity: Vec3,
+    /// Timestamp (ns) of the last gyroscope sample
+    last_gyro: Option<u64>,
+}
+
+impl Default for Sensors {
+    fn default() -> Self {
+        Self {
+            // Flat, face-up: no tilt until the host reports otherwise
+            gravity: [0.0, 0.0, GRAVITY],
+            last_gyro: None,
+        }
+    }
+}
+
+impl Sensors {
+    /// Feed one sample; `timestamp` in nanoseconds on any monotonic clock
+    pub fn event(&mut self, sensor: SensorType, sample: Vec3, timestamp: u64) {
+        if sample.iter().any(|v| !v.is_finite()) {
+            return;
+        }
+        match sensor {
+            SensorType::Accelerometer => {
+                for (g, a) in self.gravity.iter_mut().zip(sample) {
+                    *g += (a - *g) * ACCEL_WEIGHT;
+                }
+            }
+            SensorType::Gyroscope => {
+                if let Some(last) = self.last_gyro {
+                    let dt = (timestamp.saturating_sub(last) as f32 * 1e-9).min(MAX_GYRO_STEP);
+                    // A world-fixed vector seen from a rotating device turns the other way: dg/dt = -w x g
+                    let turn = cross(sample, self.gravity);
+                    for (g, t) in self.gravity.iter_mut().zip(turn) {
+                        *g -= t * dt;
+                    }
+                }
+                self.last_gyro = Some(timestamp);
+            }
+        }
+    }
+
+    /// Screen-space tilt: the direction a ball would roll (x right, y down), length 0..=1
+    /// Zero when the device lies flat, length 1 when the screen is vertical
+    pub fn tilt(&self) -> egui::Vec2 {
+        let [x, y, _] = self.gravity;
+        let tilt = egui::Vec2::new(-x, y) / GRAVITY;
+        if tilt.length() > 1.0 {
+            tilt.normalized()
+        } else {
+            tilt
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use crate::Direction;
+
+/// Default buffer window
+pub const DEFAULT_BUFFER_MS: u32 = 80;
+
+/// Grab attempt that missed the player, retried on the next ticks
+#[derive(Clone, Copy, Debug)]
+pub struct PendingGrab {
+    pub x: f32,
+    pub y: f32,
+    at: Instant,
+}
+
+/// Holds inputs that arrived between ticks so they are not lost
+/// - A direction tap released before the next tick still moves the player for one tick
+/// - A grab that just missed the player is retried while the finger stays down
+pub struct InputBuffer {
+    window: Duration,
+    pending_direction: Option<(Direction, Instant)>,
+    pending_grab: Option<PendingGrab>,
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_millis(DEFAULT_BUFFER_MS as u64),
+            pending_direction: None,
+            pending_grab: None,
+        }
+    }
+}
+
+impl InputBuffer {
+    /// Set the buffer window (0 disables buffering)
+    pub fn set_window_ms(&mut self, ms: u32) {
+        self.window = Duration::from_millis(ms as u64);
+        if ms == 0 {
+            self.pending_direction = None;
+            self.pending_grab = None;
+        }
+    }
+
+    /// A buffered tap or grab is waiting for the next tick
+    pub fn has_pending(&self) -> bool {
+        self.pending_direction.is_some() || self.pending_grab.is_some()
+    }
+
+    /// Drop buffered input (it belongs to a session that was replaced)
+    pub fn clear(&mut self) {
+        self.pending_direction = None;
+        self.pending_grab = None;
+    }
+
+    fn is_fresh(&self, at: Instant, now: Instant) -> bool {
+        now.duration_since(at) <= self.window
+    }
+
+    /// Record a direction press
+    pub fn push_direction(&mut self, direction: Direction) {
+        if direction != Direction::None && !self.window.is_zero() {
+            self.pending_direction = Some((direction, Instant::now()));
+        }
+    }
+
+    /// Direction to apply this tick
+    /// The held direction wins; otherwise a fresh buffered tap is used once
+    pub fn take_direction(&mut self, held: Direction, now: Instant) -> Direction {
+        let pending = self.pending_direction.take();
+        if held != Direction::None {
+            return held;
+        }
+        match pending {
+            Some((direction, at)) if self.is_fresh(at, now) => direction,
+            _ => Direction::None,
+        }
+    }
+
+    /// Record a grab that missed the player
+    pub fn push_grab(&mut self, x: f32, y: f32) {
+        if !self.window.is_zero() {
+            self.pending_grab = Some(PendingGrab {
+                x,
+                y,
+                at: Instant::now(),
+            });
+        }
+    }
+
+    /// Follow the finger while a grab is pending (keeps the original timestamp)
+    pub fn move_grab(&mut self, x: f32, y: f32) {
+        if let Some(grab) = self.pending_grab.as_mut() {
+            grab.x = x;
+            grab.y = y;
+        }
+    }
+
+    /// Finger lifted: nothing left to retry
+    pub fn cancel_grab(&mut self) {
+        self.pending_grab = None;
+    }
+
+    /// Pending grab still inside the buffer window (expired grabs are dropped)
+    pub fn pending_grab(&mut self, now: Instant) -> Option<PendingGrab> {
+        match self.pending_grab {
+            Some(grab) if self.is_fresh(grab.at, now) => Some(grab),
+            Some(_) => {
+                self.pending_grab = None;
+                None
+            }
+            None => None,
+        }
+    }
+}
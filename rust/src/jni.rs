@@ -1,10 +1,85 @@
 #![allow(non_snake_case)]
 
-use jni::JNIEnv;
-use jni::objects::JClass;
-use jni::sys::{jlong, jint, jfloat};
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::{Arc, Mutex, OnceLock};
 
-use crate::{game_init, game_resize, game_update, game_render, game_set_direction, game_set_mode, game_touch, game_destroy, GameHandle};
+use jni::{JNIEnv, JavaVM};
+use jni::objects::{GlobalRef, JByteArray, JClass, JFloatArray, JObject, JString, JValue};
+use jni::sys::{jboolean, jbyteArray, jdoubleArray, jfloatArray, jlong, jlongArray, jint, jfloat, jstring};
+
+use crate::events::GameEventRecord;
+use crate::ffi::strings;
+use crate::lod::LodSettings;
+use crate::pacing::PacingStats;
+use crate::particles::EmitterConfig;
+use crate::policy::RunSummary;
+use crate::postfx::PostFxSettings;
+use crate::stats::{EventStats, FrameStats, InitTimings};
+use crate::text::GlyphAtlasStats;
+use crate::{game_capture_region, game_init, game_resize, game_update, game_render, game_set_direction, game_set_mode, game_touch, game_destroy, GameHandle};
+use crate::{game_get_node_position, game_set_player_direction, game_set_throw_friction, game_set_touch_routing, game_touch_claimed, game_touch_player};
+use crate::{game_add_state, game_allow_transition, game_get_state, game_get_state_name, game_set_state_hook, game_transition};
+use crate::{game_poll_event, game_start_daily, game_export_challenge_code, game_import_challenge_code};
+use crate::{game_spawn_sprite, game_set_sprite_position, game_set_sprite_velocity, game_remove_sprite, game_get_sprite_count};
+use crate::{game_load_prefabs, game_move_entity_to, game_spawn_prefab};
+use crate::{game_get_particle_count, game_move_emitter, game_set_bounce_particles, game_spawn_emitter, game_stop_emitter};
+use crate::{game_animate_color, game_animate_node, game_animate_position, game_animate_size, game_cancel_tween};
+use crate::{game_add_node_collider, game_add_sprite_collider, game_colliders_touching, game_hit_test, game_remove_collider};
+use crate::{game_get_param, game_set_input_buffer_ms, game_set_param, game_set_physics};
+use crate::{game_get_stat, game_reset_stats, game_set_stats_interval};
+use crate::{game_debug_set_player_position, game_enable_dev_mode, game_get_dev_challenge, game_get_run_summary};
+use crate::{game_get_event_stats, game_get_frame_stats, game_set_simulation_thread, game_set_tick_rate};
+use crate::{game_free_buffer, game_render_share_card};
+use crate::{game_load_atlas, game_set_node_animation};
+use crate::{game_load_tilemap, game_remove_tilemap, game_set_tilemap_layer_visible, game_tilemap_is_solid};
+use crate::{game_hide_qr, game_show_qr};
+use crate::{game_ui_create_button, game_ui_create_label, game_ui_create_progress_bar, game_ui_remove, game_ui_set_progress, game_ui_set_text, game_ui_set_visible};
+use crate::{game_load_texture_from_bytes, game_load_texture_from_path};
+use crate::{game_load_texture_async, game_load_texture_from_path_async};
+use crate::{game_add_player, game_bind_player_gamepad, game_bind_player_touch_region, game_remove_player, game_unbind_player};
+use crate::{game_set_player_color, game_set_player_texture};
+use crate::{game_create_node, game_create_text_node, game_set_node_text, game_get_player_node, game_set_node_transform, game_set_node_z, game_attach_node, game_detach_node, game_destroy_node};
+use crate::{game_negotiate, game_get_features};
+use crate::game_set_debug_overlay;
+use crate::{game_set_clear_color, game_set_background_gradient, game_clear_background_gradient};
+use crate::{game_last_call_error, game_last_string_error};
+use crate::{game_set_orientation, game_set_rotation_policy, game_set_safe_area_insets, game_set_scale_factor};
+use crate::{
+    game_reset_camera, game_screen_to_world, game_screen_to_world_in, game_set_camera, game_set_camera_follow, game_set_lod,
+    game_set_world_size, game_world_to_screen,
+};
+use crate::game_set_sprite_batching;
+use crate::{game_key_event, game_text_input};
+use crate::{game_poll_state_diff, game_reset_state_diff};
+use crate::{game_tag_mask, game_set_sprite_tags, game_set_node_tags, game_count_entities_with_tag, game_despawn_group};
+use crate::{game_gamepad_event, game_gamepad_disconnected, game_set_gamepad_dead_zone};
+use crate::{game_sensor_event, game_get_tilt, game_set_tilt_gravity};
+use crate::{game_request_haptic, game_set_haptics_enabled};
+use crate::{game_get_quirks, game_load_quirks, game_set_quirks};
+use crate::{game_load_sound, game_unload_sound, game_play_sound, game_play_music, game_stop_music, game_set_volume, game_set_bounce_sound};
+use crate::game_set_palette;
+use crate::{game_restore_state, game_save_state};
+use crate::{game_finish_replay, game_is_replaying, game_start_recording, game_start_replay, game_stop_recording, game_stop_replay};
+use crate::{game_poll_announcement, game_set_announcement_text, game_set_announcements_enabled};
+use crate::game_set_assist_mode;
+use crate::{game_set_gpu_validation, game_trigger_gpu_capture};
+use crate::game_dump_display_list;
+use crate::{game_set_custom_render_hook, game_start_video_capture, game_stop_video_capture};
+use crate::game_set_thread_hints;
+use crate::game_set_log_callback;
+use crate::{game_get_init_timings, game_warmup};
+use crate::{game_export_timeline, game_set_spike_threshold};
+use crate::{game_get_status, game_last_crash};
+use crate::game_submit_draw_list;
+use crate::{game_get_pacing_stats, game_render_with_timestamp, game_set_target_fps};
+use crate::{game_start_loop, game_stop_loop};
+use crate::{game_set_low_power_mode, game_set_power_scaling, game_set_thermal_state};
+use crate::{game_needs_redraw, game_set_render_on_demand};
+use crate::game_invalidate_picture;
+use crate::{game_set_color_lut, game_set_post_fx};
+use crate::{game_register_shader, game_set_sprite_material, game_set_sprite_uniform};
+use crate::{game_get_glyph_atlas_stats, game_register_font, game_set_text_direction, game_trim_glyph_atlas, game_trim_memory};
 
 #[no_mangle]
 pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameInit(
@@ -42,8 +117,8 @@ pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRender(
     _env: JNIEnv,
     _class: JClass,
     handle: jlong,
-) {
-    game_render(handle as GameHandle);
+) -> jboolean {
+    game_render(handle as GameHandle) as jboolean
 }
 
 #[no_mangle]
@@ -56,6 +131,17 @@ pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetDirection
     game_set_direction(handle as GameHandle, direction);
 }
 
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetPlayerDirection(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    player: jlong,
+    direction: jint,
+) -> jboolean {
+    game_set_player_direction(handle as GameHandle, player as u64, direction) as jboolean
+}
+
 #[no_mangle]
 pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetMode(
     _env: JNIEnv,
@@ -66,6 +152,129 @@ pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetMode(
     game_set_mode(handle as GameHandle, mode);
 }
 
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTransition(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    name: JString,
+) -> jboolean {
+    let Ok(Some(name)) = optional_cstring(&mut env, &name) else {
+        return 0;
+    };
+    game_transition(handle as GameHandle, name.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetState(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    game_get_state(handle as GameHandle) as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetStateName(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    state: jint,
+) -> jstring {
+    let mut buf = [0u8; 64];
+    let len = game_get_state_name(handle as GameHandle, state as u32, buf.as_mut_ptr() as *mut _, buf.len());
+    if len < 0 {
+        return std::ptr::null_mut();
+    }
+    let name = String::from_utf8_lossy(&buf[..len as usize]);
+    env.new_string(name)
+        .map(|s| s.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAddState(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    name: JString,
+    simulates: jboolean,
+) -> jint {
+    let Ok(Some(name)) = optional_cstring(&mut env, &name) else {
+        return crate::state_machine::INVALID_STATE as jint;
+    };
+    game_add_state(handle as GameHandle, name.as_ptr(), simulates != 0) as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAllowTransition(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    from: jint,
+    to: jint,
+) -> jboolean {
+    game_allow_transition(handle as GameHandle, from as u32, to as u32) as jboolean
+}
+
+/// `hook` is the address of a GameStateHook in another native library (handed to Kotlin by its own JNI), 0 to remove
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetStateHook(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    state: jint,
+    hook: jlong,
+    user_data: jlong,
+) -> jboolean {
+    let hook = (hook != 0).then(|| unsafe { std::mem::transmute::<usize, crate::state_machine::StateHook>(hook as usize) });
+    game_set_state_hook(handle as GameHandle, state as u32, hook, user_data as *mut c_void) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStartDaily(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    date_seed: jint,
+) {
+    game_start_daily(handle as GameHandle, date_seed as u32);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameExportChallengeCode(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let mut buf = [0u8; 16];
+    let len = game_export_challenge_code(handle as GameHandle, buf.as_mut_ptr() as *mut _, buf.len());
+    if len < 0 {
+        return std::ptr::null_mut();
+    }
+    let code = String::from_utf8_lossy(&buf[..len as usize]);
+    env.new_string(code)
+        .map(|s| s.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameImportChallengeCode(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    code: JString,
+) -> jboolean {
+    let code: String = match env.get_string(&code) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(code) = strings::to_cstring(code) else {
+        return 0;
+    };
+    game_import_challenge_code(handle as GameHandle, code.as_ptr()) as jboolean
+}
+
 #[no_mangle]
 pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTouch(
     _env: JNIEnv,
@@ -78,6 +287,48 @@ pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTouch(
     game_touch(handle as GameHandle, x, y, action);
 }
 
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTouchPlayer(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    player: jlong,
+    x: jfloat,
+    y: jfloat,
+    action: jint,
+) -> jboolean {
+    game_touch_player(handle as GameHandle, player as u64, x, y, action) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetTouchRouting(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    policy: jint,
+) -> jboolean {
+    game_set_touch_routing(handle as GameHandle, policy as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTouchClaimed(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    game_touch_claimed(handle as GameHandle) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetThrowFriction(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    friction: jfloat,
+) -> jboolean {
+    game_set_throw_friction(handle as GameHandle, friction) as jboolean
+}
+
 #[no_mangle]
 pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameDestroy(
     _env: JNIEnv,
@@ -85,4 +336,2522 @@ pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameDestroy(
     handle: jlong,
 ) {
     game_destroy(handle as GameHandle);
+    // The engine is gone, so nothing calls a recording listener any more
+    frame_sinks().lock().unwrap().remove(&handle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSpawnSprite(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+    w: jfloat,
+    h: jfloat,
+    rgba: jint,
+) -> jlong {
+    game_spawn_sprite(handle as GameHandle, x, y, w, h, rgba as u32) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadPrefabs(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    json: JString,
+) -> jboolean {
+    let json: String = match env.get_string(&json) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(json) = strings::to_cstring(json) else {
+        return 0;
+    };
+    game_load_prefabs(handle as GameHandle, json.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSpawnPrefab(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    name: JString,
+    x: jfloat,
+    y: jfloat,
+) -> jlong {
+    let name: String = match env.get_string(&name) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(name) = strings::to_cstring(name) else {
+        return 0;
+    };
+    game_spawn_prefab(handle as GameHandle, name.as_ptr(), x, y) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSpritePosition(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    x: jfloat,
+    y: jfloat,
+) -> jboolean {
+    game_set_sprite_position(handle as GameHandle, id as u64, x, y) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSpriteVelocity(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    vx: jfloat,
+    vy: jfloat,
+) -> jboolean {
+    game_set_sprite_velocity(handle as GameHandle, id as u64, vx, vy) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameMoveEntityTo(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    entity: jlong,
+    x: jfloat,
+    y: jfloat,
+) -> jboolean {
+    game_move_entity_to(handle as GameHandle, entity as u64, x, y) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRemoveSprite(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    game_remove_sprite(handle as GameHandle, id as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetSpriteCount(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    game_get_sprite_count(handle as GameHandle) as jint
+}
+
+/// Emitter settings from gameSpawnEmitter's params array (see GameNative.kt for the order)
+fn emitter_config(env: &JNIEnv, params: &JFloatArray, color_start: jint, color_end: jint) -> Option<EmitterConfig> {
+    let mut p = [0.0f32; 14];
+    if env.get_array_length(params).ok()? != p.len() as i32 {
+        return None;
+    }
+    env.get_float_array_region(params, 0, &mut p).ok()?;
+    Some(EmitterConfig {
+        burst: p[0] as u32,
+        rate: p[1],
+        duration: p[2],
+        lifetime_min: p[3],
+        lifetime_max: p[4],
+        angle: p[5],
+        spread: p[6],
+        speed_min: p[7],
+        speed_max: p[8],
+        speed_end: p[9],
+        gravity_x: p[10],
+        gravity_y: p[11],
+        size_start: p[12],
+        size_end: p[13],
+        color_start: color_start as u32,
+        color_end: color_end as u32,
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSpawnEmitter(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+    params: JFloatArray,
+    color_start: jint,
+    color_end: jint,
+) -> jlong {
+    let Some(config) = emitter_config(&env, &params, color_start, color_end) else {
+        return 0;
+    };
+    game_spawn_emitter(handle as GameHandle, x, y, &config) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameMoveEmitter(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    x: jfloat,
+    y: jfloat,
+) -> jboolean {
+    game_move_emitter(handle as GameHandle, id as u64, x, y) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStopEmitter(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    game_stop_emitter(handle as GameHandle, id as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetBounceParticles(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    params: JFloatArray,
+    color_start: jint,
+    color_end: jint,
+) -> jboolean {
+    if params.is_null() {
+        return game_set_bounce_particles(handle as GameHandle, std::ptr::null()) as jboolean;
+    }
+    let Some(config) = emitter_config(&env, &params, color_start, color_end) else {
+        return 0;
+    };
+    game_set_bounce_particles(handle as GameHandle, &config) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetParticleCount(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    game_get_particle_count(handle as GameHandle) as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAnimatePosition(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sprite: jlong,
+    x: jfloat,
+    y: jfloat,
+    duration_ms: jint,
+    easing: jint,
+) -> jlong {
+    game_animate_position(handle as GameHandle, sprite as u64, x, y, duration_ms.max(0) as u32, easing as u32) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAnimateSize(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sprite: jlong,
+    w: jfloat,
+    h: jfloat,
+    duration_ms: jint,
+    easing: jint,
+) -> jlong {
+    game_animate_size(handle as GameHandle, sprite as u64, w, h, duration_ms.max(0) as u32, easing as u32) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAnimateColor(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sprite: jlong,
+    rgba: jint,
+    duration_ms: jint,
+    easing: jint,
+) -> jlong {
+    game_animate_color(handle as GameHandle, sprite as u64, rgba as u32, duration_ms.max(0) as u32, easing as u32) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAnimateNode(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    node: jlong,
+    x: jfloat,
+    y: jfloat,
+    rotation: jfloat,
+    scale_x: jfloat,
+    scale_y: jfloat,
+    duration_ms: jint,
+    easing: jint,
+) -> jlong {
+    let duration_ms = duration_ms.max(0) as u32;
+    game_animate_node(handle as GameHandle, node as u64, x, y, rotation, scale_x, scale_y, duration_ms, easing as u32) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameCancelTween(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    tween: jlong,
+) -> jboolean {
+    game_cancel_tween(handle as GameHandle, tween as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAddSpriteCollider(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sprite: jlong,
+    shape: jint,
+    width: jfloat,
+    height: jfloat,
+    offset_x: jfloat,
+    offset_y: jfloat,
+) -> jlong {
+    game_add_sprite_collider(handle as GameHandle, sprite as u64, shape as u32, width, height, offset_x, offset_y) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAddNodeCollider(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    node: jlong,
+    shape: jint,
+    width: jfloat,
+    height: jfloat,
+    offset_x: jfloat,
+    offset_y: jfloat,
+) -> jlong {
+    game_add_node_collider(handle as GameHandle, node as u64, shape as u32, width, height, offset_x, offset_y) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRemoveCollider(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    collider: jlong,
+) -> jboolean {
+    game_remove_collider(handle as GameHandle, collider as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameCollidersTouching(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    a: jlong,
+    b: jlong,
+) -> jboolean {
+    game_colliders_touching(handle as GameHandle, a as u64, b as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameHitTest(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+) -> jlongArray {
+    let mut kind = 0;
+    let id = game_hit_test(handle as GameHandle, x, y, &mut kind);
+    if id == 0 {
+        return std::ptr::null_mut();
+    }
+    let Ok(array) = env.new_long_array(2) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_long_array_region(&array, 0, &[kind as i64, id as i64]).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetInputBufferMs(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    ms: jint,
+) {
+    game_set_input_buffer_ms(handle as GameHandle, ms.max(0) as u32);
+}
+
+/// Returns [kind, id, x, y, value, other], or null when no event is queued
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gamePollEvent(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jdoubleArray {
+    let mut record = GameEventRecord::default();
+    if !game_poll_event(handle as GameHandle, &mut record) {
+        return std::ptr::null_mut();
+    }
+    let values = [
+        record.kind as f64,
+        record.id as f64,
+        record.x as f64,
+        record.y as f64,
+        record.value as f64,
+        record.other as f64,
+    ];
+    let Ok(array) = env.new_double_array(values.len() as i32) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_double_array_region(&array, 0, &values).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetPhysics(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    gravity_x: jfloat,
+    gravity_y: jfloat,
+    restitution: jfloat,
+    damping: jfloat,
+) {
+    game_set_physics(handle as GameHandle, gravity_x, gravity_y, restitution, damping);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetParam(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    key: jint,
+    value: jfloat,
+) -> jboolean {
+    game_set_param(handle as GameHandle, key as u32, value) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetParam(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    key: jint,
+) -> jfloat {
+    game_get_param(handle as GameHandle, key as u32)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetStat(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    key: jint,
+) -> jfloat {
+    game_get_stat(handle as GameHandle, key as u32)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetStatsInterval(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    interval_ms: jint,
+) {
+    game_set_stats_interval(handle as GameHandle, interval_ms.max(0) as u32);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameResetStats(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_reset_stats(handle as GameHandle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameDebugSetPlayerPosition(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+) -> jboolean {
+    game_debug_set_player_position(handle as GameHandle, x, y) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetDevChallenge(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let challenge = game_get_dev_challenge(handle as GameHandle);
+    if challenge.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(challenge.data, challenge.len) };
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    game_free_buffer(challenge);
+    env.new_string(text)
+        .map(|s| s.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameEnableDevMode(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    token: JString,
+) -> jboolean {
+    let token: String = match env.get_string(&token) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(token) = strings::to_cstring(token) else {
+        return 0;
+    };
+    game_enable_dev_mode(handle as GameHandle, token.as_ptr()) as jboolean
+}
+
+/// Returns [seed, flags, rejectedMutations, elapsedMs], or null for an invalid handle
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetRunSummary(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jlongArray {
+    let mut summary = RunSummary::default();
+    if !game_get_run_summary(handle as GameHandle, &mut summary) {
+        return std::ptr::null_mut();
+    }
+    let values = [
+        summary.seed as i64,
+        summary.flags as i64,
+        summary.rejected_mutations as i64,
+        summary.elapsed_ms as i64,
+    ];
+    let Ok(array) = env.new_long_array(values.len() as i32) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_long_array_region(&array, 0, &values).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetTickRate(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    hz: jint,
+) {
+    game_set_tick_rate(handle as GameHandle, hz.max(0) as u32);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSimulationThread(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) -> jboolean {
+    game_set_simulation_thread(handle as GameHandle, enabled != 0) as jboolean
+}
+
+/// Returns RGBA8 pixels (top row first), or null on failure
+/// Size is (round(w * scale), round(h * scale))
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameCaptureRegion(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+    w: jfloat,
+    h: jfloat,
+    scale: jfloat,
+) -> jbyteArray {
+    let handle = handle as GameHandle;
+    let required = game_capture_region(handle, x, y, w, h, scale, std::ptr::null_mut(), 0);
+    if required <= 0 {
+        return std::ptr::null_mut();
+    }
+    let mut pixels = vec![0u8; required as usize];
+    if game_capture_region(handle, x, y, w, h, scale, pixels.as_mut_ptr(), pixels.len()) != required {
+        return std::ptr::null_mut();
+    }
+    env.byte_array_from_slice(&pixels)
+        .map(|a| a.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRenderShareCard(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    template_id: jint,
+) -> jbyteArray {
+    let card = game_render_share_card(handle as GameHandle, template_id as u32);
+    if card.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let png = unsafe { std::slice::from_raw_parts(card.data, card.len) };
+    let array = env.byte_array_from_slice(png)
+        .map(|a| a.into_raw())
+        .unwrap_or(std::ptr::null_mut());
+    game_free_buffer(card);
+    array
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameCreateNode(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    w: jfloat,
+    h: jfloat,
+    rgba: jint,
+) -> jlong {
+    game_create_node(handle as GameHandle, w, h, rgba as u32) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameCreateTextNode(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    text: JString,
+    size: jfloat,
+    rgba: jint,
+    align: jint,
+) -> jlong {
+    let text: String = match env.get_string(&text) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(text) = strings::to_cstring(text) else {
+        return 0;
+    };
+    game_create_text_node(handle as GameHandle, text.as_ptr(), size, rgba as u32, align as u32) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetNodeText(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    node: jlong,
+    text: JString,
+) -> jboolean {
+    let text: String = match env.get_string(&text) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(text) = strings::to_cstring(text) else {
+        return 0;
+    };
+    game_set_node_text(handle as GameHandle, node as u64, text.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetPlayerNode(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jlong {
+    game_get_player_node(handle as GameHandle) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAddPlayer(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+    rgba: jint,
+) -> jlong {
+    game_add_player(handle as GameHandle, x, y, rgba as u32) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRemovePlayer(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    player: jlong,
+) -> jboolean {
+    game_remove_player(handle as GameHandle, player as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameBindPlayerGamepad(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    player: jlong,
+    pad: jint,
+) -> jboolean {
+    game_bind_player_gamepad(handle as GameHandle, player as u64, pad as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameBindPlayerTouchRegion(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    player: jlong,
+    x: jfloat,
+    y: jfloat,
+    w: jfloat,
+    h: jfloat,
+) -> jboolean {
+    game_bind_player_touch_region(handle as GameHandle, player as u64, x, y, w, h) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUnbindPlayer(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    player: jlong,
+) -> jboolean {
+    game_unbind_player(handle as GameHandle, player as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetPlayerColor(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    player: jlong,
+    rgba: jint,
+) -> jboolean {
+    game_set_player_color(handle as GameHandle, player as u64, rgba as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetPlayerTexture(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    player: jlong,
+    name: JString,
+) -> jboolean {
+    let Ok(name) = optional_cstring(&mut env, &name) else {
+        return 0;
+    };
+    let name = name.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+    game_set_player_texture(handle as GameHandle, player as u64, name) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetNodePosition(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    node: jlong,
+) -> jfloatArray {
+    let (mut x, mut y) = (0.0, 0.0);
+    if !game_get_node_position(handle as GameHandle, node as u64, &mut x, &mut y) {
+        return std::ptr::null_mut();
+    }
+    let Ok(array) = env.new_float_array(2) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &[x, y]).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetNodeTransform(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    x: jfloat,
+    y: jfloat,
+    rotation: jfloat,
+    scale_x: jfloat,
+    scale_y: jfloat,
+) -> jboolean {
+    game_set_node_transform(handle as GameHandle, id as u64, x, y, rotation, scale_x, scale_y) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetNodeZ(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    z: jint,
+) -> jboolean {
+    game_set_node_z(handle as GameHandle, id as u64, z) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameAttachNode(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    child: jlong,
+    parent: jlong,
+) -> jboolean {
+    game_attach_node(handle as GameHandle, child as u64, parent as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameDetachNode(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    game_detach_node(handle as GameHandle, id as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameDestroyNode(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    game_destroy_node(handle as GameHandle, id as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadAtlas(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    json: JString,
+    png: JByteArray,
+) -> jlong {
+    let json: String = match env.get_string(&json) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(json) = strings::to_cstring(json) else {
+        return 0;
+    };
+    let Ok(png) = env.convert_byte_array(&png) else {
+        return 0;
+    };
+    game_load_atlas(handle as GameHandle, json.as_ptr(), png.as_ptr(), png.len()) as jlong
+}
+
+/// A null name clears the node's animation
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetNodeAnimation(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    node: jlong,
+    atlas: jlong,
+    name: JString,
+    fps: jfloat,
+    mode: jint,
+) -> jboolean {
+    let name = if name.is_null() {
+        None
+    } else {
+        let name: String = match env.get_string(&name) {
+            Ok(s) => s.into(),
+            Err(_) => return 0,
+        };
+        match strings::to_cstring(name) {
+            Ok(name) => Some(name),
+            Err(_) => return 0,
+        }
+    };
+    let name_ptr = name.as_ref().map_or(std::ptr::null(), |n| n.as_ptr());
+    game_set_node_animation(handle as GameHandle, node as u64, atlas as u64, name_ptr, fps, mode) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadTilemap(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+    x: jfloat,
+    y: jfloat,
+    scale: jfloat,
+) -> jlong {
+    let Ok(bytes) = env.convert_byte_array(&data) else {
+        return 0;
+    };
+    game_load_tilemap(handle as GameHandle, bytes.as_ptr(), bytes.len(), x, y, scale) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRemoveTilemap(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    map: jlong,
+) -> jboolean {
+    game_remove_tilemap(handle as GameHandle, map as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetTilemapLayerVisible(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    map: jlong,
+    name: JString,
+    visible: jboolean,
+) -> jboolean {
+    let name: String = match env.get_string(&name) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(name) = strings::to_cstring(name) else {
+        return 0;
+    };
+    game_set_tilemap_layer_visible(handle as GameHandle, map as u64, name.as_ptr(), visible != 0) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTilemapIsSolid(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+) -> jboolean {
+    game_tilemap_is_solid(handle as GameHandle, x, y) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameShowQr(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JString,
+    size: jfloat,
+    ec_level: jint,
+    quiet_zone: jint,
+) -> jboolean {
+    let data: String = match env.get_string(&data) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(data) = strings::to_cstring(data) else {
+        return 0;
+    };
+    game_show_qr(handle as GameHandle, data.as_ptr(), size, ec_level, quiet_zone.max(0) as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameHideQr(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_hide_qr(handle as GameHandle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUiCreateLabel(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    text: JString,
+    size: jfloat,
+    rgba: jint,
+    anchor: jint,
+    x: jfloat,
+    y: jfloat,
+) -> jlong {
+    let text: String = match env.get_string(&text) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(text) = strings::to_cstring(text) else {
+        return 0;
+    };
+    game_ui_create_label(handle as GameHandle, text.as_ptr(), size, rgba as u32, anchor as u32, x, y) as jlong
+}
+
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUiCreateButton(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    text: JString,
+    size: jfloat,
+    text_rgba: jint,
+    fill_rgba: jint,
+    anchor: jint,
+    x: jfloat,
+    y: jfloat,
+    width: jfloat,
+    height: jfloat,
+) -> jlong {
+    let text: String = match env.get_string(&text) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(text) = strings::to_cstring(text) else {
+        return 0;
+    };
+    game_ui_create_button(
+        handle as GameHandle,
+        text.as_ptr(),
+        size,
+        text_rgba as u32,
+        fill_rgba as u32,
+        anchor as u32,
+        x,
+        y,
+        width,
+        height,
+    ) as jlong
+}
+
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUiCreateProgressBar(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    rgba: jint,
+    anchor: jint,
+    x: jfloat,
+    y: jfloat,
+    width: jfloat,
+    height: jfloat,
+) -> jlong {
+    game_ui_create_progress_bar(handle as GameHandle, rgba as u32, anchor as u32, x, y, width, height) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUiSetText(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    widget: jlong,
+    text: JString,
+) -> jboolean {
+    let text: String = match env.get_string(&text) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(text) = strings::to_cstring(text) else {
+        return 0;
+    };
+    game_ui_set_text(handle as GameHandle, widget as u64, text.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUiSetProgress(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    widget: jlong,
+    value: jfloat,
+) -> jboolean {
+    game_ui_set_progress(handle as GameHandle, widget as u64, value) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUiSetVisible(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    widget: jlong,
+    visible: jboolean,
+) -> jboolean {
+    game_ui_set_visible(handle as GameHandle, widget as u64, visible != 0) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUiRemove(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    widget: jlong,
+) -> jboolean {
+    game_ui_remove(handle as GameHandle, widget as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadTextureFromBytes(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+    name: JString,
+) -> jboolean {
+    let Ok(bytes) = env.convert_byte_array(&data) else {
+        return 0;
+    };
+    let name: String = match env.get_string(&name) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(name) = strings::to_cstring(name) else {
+        return 0;
+    };
+    game_load_texture_from_bytes(handle as GameHandle, bytes.as_ptr(), bytes.len(), name.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadTextureFromPath(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    path: JString,
+) -> jboolean {
+    let path: String = match env.get_string(&path) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(path) = strings::to_cstring(path) else {
+        return 0;
+    };
+    game_load_texture_from_path(handle as GameHandle, path.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadTextureAsync(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+    name: JString,
+) -> jlong {
+    let Ok(bytes) = env.convert_byte_array(&data) else {
+        return 0;
+    };
+    let name: String = match env.get_string(&name) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(name) = strings::to_cstring(name) else {
+        return 0;
+    };
+    game_load_texture_async(handle as GameHandle, bytes.as_ptr(), bytes.len(), name.as_ptr()) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadTextureFromPathAsync(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    path: JString,
+) -> jlong {
+    let path: String = match env.get_string(&path) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(path) = strings::to_cstring(path) else {
+        return 0;
+    };
+    game_load_texture_from_path_async(handle as GameHandle, path.as_ptr()) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameNegotiate(
+    _env: JNIEnv,
+    _class: JClass,
+    requested_features: jlong,
+) -> jlong {
+    game_negotiate(requested_features as u64) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetFeatures(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jlong {
+    game_get_features(handle as GameHandle) as jlong
+}
+
+/// Returns [fps, cpuMs, gpuMs, frameMs, frameMsP95, frameCount, culledSprites, culledNodes, culledParticles],
+/// or null for an invalid handle
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetFrameStats(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jfloatArray {
+    let mut stats = FrameStats::default();
+    if !game_get_frame_stats(handle as GameHandle, &mut stats) {
+        return std::ptr::null_mut();
+    }
+    let values = [
+        stats.fps,
+        stats.cpu_ms,
+        stats.gpu_ms,
+        stats.frame_ms,
+        stats.frame_ms_p95,
+        stats.frame_count as f32,
+        stats.culled.sprites as f32,
+        stats.culled.nodes as f32,
+        stats.culled.particles as f32,
+        stats.gpu_passes.clear_ms,
+        stats.gpu_passes.scene_ms,
+        stats.gpu_passes.ui_ms,
+    ];
+    let Ok(array) = env.new_float_array(values.len() as i32) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &values).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetEventStats(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jdoubleArray {
+    let mut stats = EventStats::default();
+    if !game_get_event_stats(handle as GameHandle, &mut stats) {
+        return std::ptr::null_mut();
+    }
+    let mut values = Vec::with_capacity(10);
+    for path in [stats.outbound, stats.input] {
+        values.extend([
+            path.published as f64,
+            path.delivered as f64,
+            path.dropped as f64,
+            path.coalesced as f64,
+            path.max_latency_ms as f64,
+        ]);
+    }
+    let Ok(array) = env.new_double_array(values.len() as i32) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_double_array_region(&array, 0, &values).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetDebugOverlay(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) -> jboolean {
+    game_set_debug_overlay(handle as GameHandle, enabled != 0) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetClearColor(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    r: jfloat,
+    g: jfloat,
+    b: jfloat,
+    a: jfloat,
+) {
+    game_set_clear_color(handle as GameHandle, r, g, b, a);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetBackgroundGradient(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    top_rgba: jint,
+    bottom_rgba: jint,
+) {
+    game_set_background_gradient(handle as GameHandle, top_rgba as u32, bottom_rgba as u32);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameClearBackgroundGradient(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_clear_background_gradient(handle as GameHandle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLastCallError(
+    _env: JNIEnv,
+    _class: JClass,
+) -> jint {
+    game_last_call_error()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLastStringError(
+    _env: JNIEnv,
+    _class: JClass,
+) -> jint {
+    game_last_string_error()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetScaleFactor(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    scale: jfloat,
+) {
+    game_set_scale_factor(handle as GameHandle, scale);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSafeAreaInsets(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    top: jfloat,
+    bottom: jfloat,
+    left: jfloat,
+    right: jfloat,
+) -> jboolean {
+    game_set_safe_area_insets(handle as GameHandle, top, bottom, left, right) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetOrientation(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    orientation: jint,
+) -> jboolean {
+    game_set_orientation(handle as GameHandle, orientation as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetRotationPolicy(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    policy: jint,
+) -> jboolean {
+    game_set_rotation_policy(handle as GameHandle, policy as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetCamera(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+    zoom: jfloat,
+    rotation: jfloat,
+) -> jboolean {
+    game_set_camera(handle as GameHandle, x, y, zoom, rotation) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetCameraFollow(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    rate: jfloat,
+) -> jboolean {
+    game_set_camera_follow(handle as GameHandle, rate) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameResetCamera(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_reset_camera(handle as GameHandle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetWorldSize(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    width: jfloat,
+    height: jfloat,
+) -> jboolean {
+    game_set_world_size(handle as GameHandle, width, height) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameScreenToWorld(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+) -> jfloatArray {
+    let (mut world_x, mut world_y) = (0.0, 0.0);
+    if !game_screen_to_world(handle as GameHandle, x, y, &mut world_x, &mut world_y) {
+        return std::ptr::null_mut();
+    }
+    let Ok(array) = env.new_float_array(2) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &[world_x, world_y]).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameScreenToWorldIn(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+    space: jint,
+) -> jfloatArray {
+    let (mut world_x, mut world_y) = (0.0, 0.0);
+    if !game_screen_to_world_in(handle as GameHandle, x, y, space as u32, &mut world_x, &mut world_y) {
+        return std::ptr::null_mut();
+    }
+    let Ok(array) = env.new_float_array(2) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &[world_x, world_y]).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameWorldToScreen(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    x: jfloat,
+    y: jfloat,
+    space: jint,
+) -> jfloatArray {
+    let (mut screen_x, mut screen_y) = (0.0, 0.0);
+    if !game_world_to_screen(handle as GameHandle, x, y, space as u32, &mut screen_x, &mut screen_y) {
+        return std::ptr::null_mut();
+    }
+    let Ok(array) = env.new_float_array(2) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &[screen_x, screen_y]).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetLod(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    detail_min_px: jfloat,
+    simplify_below_zoom: jfloat,
+) -> jboolean {
+    let settings = LodSettings { detail_min_px, simplify_below_zoom };
+    game_set_lod(handle as GameHandle, &settings) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSpriteBatching(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) {
+    game_set_sprite_batching(handle as GameHandle, enabled != 0)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameKeyEvent(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    keycode: jint,
+    action: jint,
+    modifiers: jint,
+) -> jboolean {
+    game_key_event(handle as GameHandle, keycode as u32, action, modifiers as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTextInput(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    text: JString,
+) -> jboolean {
+    let text: String = match env.get_string(&text) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(text) = strings::to_cstring(text) else {
+        return 0;
+    };
+    game_text_input(handle as GameHandle, text.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameResetStateDiff(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_reset_state_diff(handle as GameHandle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gamePollStateDiff(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jbyteArray {
+    let diff = game_poll_state_diff(handle as GameHandle);
+    if diff.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(diff.data, diff.len) };
+    let array = env.byte_array_from_slice(bytes)
+        .map(|a| a.into_raw())
+        .unwrap_or(std::ptr::null_mut());
+    game_free_buffer(diff);
+    array
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSpriteTags(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    tags: jlong,
+) -> jboolean {
+    game_set_sprite_tags(handle as GameHandle, id as u64, tags as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetNodeTags(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    tags: jlong,
+) -> jboolean {
+    game_set_node_tags(handle as GameHandle, id as u64, tags as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameCountEntitiesWithTag(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    tags: jlong,
+) -> jint {
+    game_count_entities_with_tag(handle as GameHandle, tags as u64) as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameDespawnGroup(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    tags: jlong,
+) -> jint {
+    game_despawn_group(handle as GameHandle, tags as u64) as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTagMask(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    name: JString,
+) -> jlong {
+    let name: String = match env.get_string(&name) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(name) = strings::to_cstring(name) else {
+        return 0;
+    };
+    game_tag_mask(handle as GameHandle, name.as_ptr()) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGamepadEvent(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    pad_id: jint,
+    control: jint,
+    value: jfloat,
+) -> jboolean {
+    game_gamepad_event(handle as GameHandle, pad_id as u32, control, value) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGamepadDisconnected(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    pad_id: jint,
+) {
+    game_gamepad_disconnected(handle as GameHandle, pad_id as u32);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetGamepadDeadZone(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    dead_zone: jfloat,
+) {
+    game_set_gamepad_dead_zone(handle as GameHandle, dead_zone);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSensorEvent(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sensor_type: jint,
+    x: jfloat,
+    y: jfloat,
+    z: jfloat,
+    timestamp: jlong,
+) -> jboolean {
+    game_sensor_event(handle as GameHandle, sensor_type, x, y, z, timestamp as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetTiltGravity(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    strength: jfloat,
+) {
+    game_set_tilt_gravity(handle as GameHandle, strength);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetTilt(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jfloatArray {
+    let (mut x, mut y) = (0.0, 0.0);
+    if !game_get_tilt(handle as GameHandle, &mut x, &mut y) {
+        return std::ptr::null_mut();
+    }
+    let Ok(array) = env.new_float_array(2) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &[x, y]).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRequestHaptic(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    pattern: jint,
+) -> jboolean {
+    game_request_haptic(handle as GameHandle, pattern as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetHapticsEnabled(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) -> jboolean {
+    game_set_haptics_enabled(handle as GameHandle, enabled != 0) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetQuirks(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    game_get_quirks(handle as GameHandle) as jint
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetQuirks(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    quirks: jint,
+) -> jboolean {
+    game_set_quirks(handle as GameHandle, quirks as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadQuirks(
+    mut env: JNIEnv,
+    _class: JClass,
+    json: JString,
+) -> jboolean {
+    let json: String = match env.get_string(&json) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
+    };
+    let Ok(json) = strings::to_cstring(json) else {
+        return 0;
+    };
+    game_load_quirks(json.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameUnloadSound(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    game_unload_sound(handle as GameHandle, id as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gamePlaySound(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    volume: jfloat,
+) -> jboolean {
+    game_play_sound(handle as GameHandle, id as u64, volume) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gamePlayMusic(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    game_play_music(handle as GameHandle, id as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStopMusic(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_stop_music(handle as GameHandle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetVolume(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    channel: jint,
+    volume: jfloat,
+) -> jboolean {
+    game_set_volume(handle as GameHandle, channel as u32, volume) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetBounceSound(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+) -> jboolean {
+    game_set_bounce_sound(handle as GameHandle, id as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLoadSound(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+) -> jlong {
+    let Ok(bytes) = env.convert_byte_array(&data) else {
+        return 0;
+    };
+    game_load_sound(handle as GameHandle, bytes.as_ptr(), bytes.len()) as jlong
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetPalette(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    palette: jint,
+) -> jboolean {
+    game_set_palette(handle as GameHandle, palette as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSaveState(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jbyteArray {
+    let saved = game_save_state(handle as GameHandle);
+    if saved.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(saved.data, saved.len) };
+    let array = env.byte_array_from_slice(bytes)
+        .map(|a| a.into_raw())
+        .unwrap_or(std::ptr::null_mut());
+    game_free_buffer(saved);
+    array
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRestoreState(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+) -> jboolean {
+    let Ok(bytes) = env.convert_byte_array(&data) else {
+        return 0;
+    };
+    game_restore_state(handle as GameHandle, bytes.as_ptr(), bytes.len()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetAnnouncementsEnabled(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) {
+    game_set_announcements_enabled(handle as GameHandle, enabled != 0);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetAnnouncementText(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    key: JString,
+    text: JString,
+) -> jboolean {
+    let (Ok(key), Ok(text)) = (env.get_string(&key), env.get_string(&text)) else {
+        return 0;
+    };
+    let (key, text): (String, String) = (key.into(), text.into());
+    let (Ok(key), Ok(text)) = (strings::to_cstring(key), strings::to_cstring(text)) else {
+        return 0;
+    };
+    game_set_announcement_text(handle as GameHandle, key.as_ptr(), text.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gamePollAnnouncement(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let announcement = game_poll_announcement(handle as GameHandle);
+    if announcement.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(announcement.data, announcement.len) };
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    game_free_buffer(announcement);
+    env.new_string(text)
+        .map(|s| s.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStartRecording(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    game_start_recording(handle as GameHandle) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStopRecording(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jbyteArray {
+    let recording = game_stop_recording(handle as GameHandle);
+    if recording.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(recording.data, recording.len) };
+    let array = env.byte_array_from_slice(bytes)
+        .map(|a| a.into_raw())
+        .unwrap_or(std::ptr::null_mut());
+    game_free_buffer(recording);
+    array
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStartReplay(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+) -> jboolean {
+    let Ok(bytes) = env.convert_byte_array(&data) else {
+        return 0;
+    };
+    game_start_replay(handle as GameHandle, bytes.as_ptr(), bytes.len()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameFinishReplay(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_finish_replay(handle as GameHandle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStopReplay(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_stop_replay(handle as GameHandle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameIsReplaying(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    game_is_replaying(handle as GameHandle) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetAssistMode(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) {
+    game_set_assist_mode(handle as GameHandle, enabled != 0);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTriggerGpuCapture(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    game_trigger_gpu_capture(handle as GameHandle) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetGpuValidation(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) -> jboolean {
+    game_set_gpu_validation(handle as GameHandle, enabled != 0) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameDumpDisplayList(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let list = game_dump_display_list(handle as GameHandle);
+    if list.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(list.data, list.len) };
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    game_free_buffer(list);
+    env.new_string(text)
+        .map(|s| s.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// GameNative.FrameListener receiving one engine's recorded frames
+struct FrameSink {
+    vm: JavaVM,
+    listener: GlobalRef,
+}
+
+/// Sinks by engine handle; boxed so the user_data pointer stays put while the map changes
+fn frame_sinks() -> &'static Mutex<HashMap<jlong, Box<FrameSink>>> {
+    static SINKS: OnceLock<Mutex<HashMap<jlong, Box<FrameSink>>>> = OnceLock::new();
+    SINKS.get_or_init(Default::default)
+}
+
+/// Wraps the frame in a direct ByteBuffer (no copy) for listener.onFrame
+unsafe extern "C" fn deliver_frame(user_data: *mut c_void, pixels: *const u8, width: u32, height: u32, timestamp_ns: u64) {
+    let sink = &*(user_data as *const FrameSink);
+    // Frames arrive on the GL thread, which Android has already attached
+    let Ok(mut env) = sink.vm.attach_current_thread_permanently() else {
+        return;
+    };
+    let len = width as usize * height as usize * 4;
+    let Ok(buffer) = env.new_direct_byte_buffer(pixels as *mut u8, len) else {
+        return;
+    };
+    let args = [
+        JValue::Object(&buffer),
+        JValue::Int(width as i32),
+        JValue::Int(height as i32),
+        JValue::Long(timestamp_ns as i64),
+    ];
+    if env.call_method(&sink.listener, "onFrame", "(Ljava/nio/ByteBuffer;IIJ)V", &args).is_err() {
+        // Don't leave a pending exception on the render thread
+        let _ = env.exception_clear();
+        log::warn!("FrameListener.onFrame failed");
+    }
+    let _ = env.delete_local_ref(buffer);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStartVideoCapture(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    listener: JObject,
+    ring_size: jint,
+) -> jboolean {
+    let (Ok(vm), Ok(listener)) = (env.get_java_vm(), env.new_global_ref(&listener)) else {
+        return 0;
+    };
+    let mut sinks = frame_sinks().lock().unwrap();
+    let sink = Box::new(FrameSink { vm, listener });
+    let user_data = &*sink as *const FrameSink as *mut c_void;
+    // Stops (and flushes to) any previous listener before it is replaced below
+    let started = game_start_video_capture(handle as GameHandle, Some(deliver_frame), user_data, ring_size.max(0) as u32);
+    if started {
+        sinks.insert(handle, sink);
+    } else {
+        sinks.remove(&handle);
+    }
+    started as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStopVideoCapture(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    game_stop_video_capture(handle as GameHandle);
+    frame_sinks().lock().unwrap().remove(&handle);
+}
+
+/// `hook` is the address of a GameRenderHook in another native library (handed to Kotlin by its own JNI), 0 to remove
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetCustomRenderHook(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    hook: jlong,
+    user_data: jlong,
+    stage: jint,
+) -> jboolean {
+    let hook = (hook != 0).then(|| unsafe { std::mem::transmute::<usize, crate::render_hook::RenderHook>(hook as usize) });
+    game_set_custom_render_hook(handle as GameHandle, hook, user_data as *mut c_void, stage as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetThreadHints(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    affinity_mask: jlong,
+    priority: jint,
+) -> jboolean {
+    game_set_thread_hints(handle as GameHandle, affinity_mask as u64, priority) as jboolean
+}
+
+/// GameNative.LogListener receiving forwarded engine logs
+struct LogSink {
+    vm: JavaVM,
+    listener: GlobalRef,
+}
+
+/// Shared rather than passed as user_data, so replacing the listener can't free one still in use
+fn log_sink() -> &'static Mutex<Option<Arc<LogSink>>> {
+    static SINK: OnceLock<Mutex<Option<Arc<LogSink>>>> = OnceLock::new();
+    SINK.get_or_init(Default::default)
+}
+
+unsafe extern "C" fn deliver_log(_user_data: *mut c_void, level: i32, target: *const c_char, message: *const c_char) {
+    let Some(sink) = log_sink().lock().unwrap().clone() else {
+        return;
+    };
+    // Logs come from any engine thread; task threads are attached until they exit
+    let Ok(mut env) = sink.vm.attach_current_thread_permanently() else {
+        return;
+    };
+    let target = CStr::from_ptr(target).to_string_lossy();
+    let message = CStr::from_ptr(message).to_string_lossy();
+    let (Ok(target), Ok(message)) = (env.new_string(target), env.new_string(message)) else {
+        let _ = env.exception_clear();
+        return;
+    };
+    let args = [JValue::Int(level), JValue::Object(&target), JValue::Object(&message)];
+    if env.call_method(&sink.listener, "onLog", "(ILjava/lang/String;Ljava/lang/String;)V", &args).is_err() {
+        // Not logged: it would come straight back here
+        let _ = env.exception_clear();
+    }
+    let _ = env.delete_local_ref(target);
+    let _ = env.delete_local_ref(message);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetLogCallback(
+    env: JNIEnv,
+    _class: JClass,
+    listener: JObject,
+    max_level: jint,
+) {
+    if listener.is_null() || max_level <= 0 {
+        game_set_log_callback(None, std::ptr::null_mut(), 0);
+        *log_sink().lock().unwrap() = None;
+        return;
+    }
+    let (Ok(vm), Ok(listener)) = (env.get_java_vm(), env.new_global_ref(&listener)) else {
+        return;
+    };
+    *log_sink().lock().unwrap() = Some(Arc::new(LogSink { vm, listener }));
+    game_set_log_callback(Some(deliver_log), std::ptr::null_mut(), max_level as u32);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameWarmup(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    game_warmup(handle as GameHandle) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetInitTimings(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jfloatArray {
+    let mut timings = InitTimings::default();
+    if !game_get_init_timings(handle as GameHandle, &mut timings) {
+        return std::ptr::null_mut();
+    }
+    let values = [timings.renderer_ms, timings.engine_ms, timings.warmup_ms, timings.first_frame_ms];
+    let Ok(array) = env.new_float_array(values.len() as i32) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_float_array_region(&array, 0, &values).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSpikeThreshold(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    threshold_ms: jfloat,
+    log_interval_ms: jint,
+) {
+    game_set_spike_threshold(handle as GameHandle, threshold_ms, log_interval_ms.max(0) as u32);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameExportTimeline(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let timeline = game_export_timeline(handle as GameHandle);
+    if timeline.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(timeline.data, timeline.len) };
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    game_free_buffer(timeline);
+    env.new_string(text)
+        .map(|s| s.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetStatus(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    game_get_status(handle as GameHandle)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameLastCrash(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let crash = game_last_crash(handle as GameHandle);
+    if crash.data.is_null() {
+        return std::ptr::null_mut();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(crash.data, crash.len) };
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    game_free_buffer(crash);
+    env.new_string(text)
+        .map(|s| s.into_raw())
+        .unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSubmitDrawList(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+) -> jboolean {
+    let Ok(bytes) = env.convert_byte_array(&data) else {
+        return 0;
+    };
+    game_submit_draw_list(handle as GameHandle, bytes.as_ptr(), bytes.len()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRenderWithTimestamp(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    vsync_ns: jlong,
+) -> jboolean {
+    game_render_with_timestamp(handle as GameHandle, vsync_ns as u64) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetTargetFps(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    fps: jint,
+) -> jboolean {
+    game_set_target_fps(handle as GameHandle, fps as u32) as jboolean
+}
+
+/// Call on a HandlerThread that owns the EGL context and surface; frames then run and are swapped
+/// from its Choreographer with no per-frame JNI calls
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStartLoop(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    game_start_loop(handle as GameHandle) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameStopLoop(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    game_stop_loop(handle as GameHandle) as jboolean
+}
+
+/// Returns [targetFps, refreshHz, rendered, skippedPaced, skippedUnchanged], or null for an invalid handle
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetPacingStats(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jdoubleArray {
+    let mut stats = PacingStats::default();
+    if !game_get_pacing_stats(handle as GameHandle, &mut stats) {
+        return std::ptr::null_mut();
+    }
+    let values = [
+        stats.target_fps as f64,
+        stats.refresh_hz as f64,
+        stats.rendered as f64,
+        stats.skipped_paced as f64,
+        stats.skipped_unchanged as f64,
+    ];
+    let Ok(array) = env.new_double_array(values.len() as i32) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_double_array_region(&array, 0, &values).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
+/// Called with PowerManager's thermal status mapped to THERMAL_* (NONE and LIGHT nominal, MODERATE
+/// fair, SEVERE serious, CRITICAL and up critical)
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetThermalState(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    state: jint,
+) -> jboolean {
+    game_set_thermal_state(handle as GameHandle, state as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetLowPowerMode(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) {
+    game_set_low_power_mode(handle as GameHandle, enabled != 0)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetPowerScaling(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) {
+    game_set_power_scaling(handle as GameHandle, enabled != 0)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetRenderOnDemand(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    enabled: jboolean,
+) {
+    game_set_render_on_demand(handle as GameHandle, enabled != 0);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameNeedsRedraw(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    game_needs_redraw(handle as GameHandle) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameInvalidatePicture(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jint,
+) -> jboolean {
+    game_invalidate_picture(handle as GameHandle, id as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetPostFx(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    bloom_intensity: jfloat,
+    bloom_threshold: jfloat,
+    bloom_spread: jfloat,
+    vignette_strength: jfloat,
+    vignette_radius: jfloat,
+    lut_intensity: jfloat,
+) -> jboolean {
+    let settings =
+        PostFxSettings { bloom_intensity, bloom_threshold, bloom_spread, vignette_strength, vignette_radius, lut_intensity };
+    game_set_post_fx(handle as GameHandle, &settings) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetColorLut(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    png: JByteArray,
+) -> jboolean {
+    if png.is_null() {
+        return game_set_color_lut(handle as GameHandle, std::ptr::null(), 0) as jboolean;
+    }
+    let Ok(bytes) = env.convert_byte_array(&png) else {
+        return 0;
+    };
+    game_set_color_lut(handle as GameHandle, bytes.as_ptr(), bytes.len()) as jboolean
+}
+
+/// Copy a nullable Java string for a C call; Err if it can't be read or has a NUL
+fn optional_cstring(env: &mut JNIEnv, s: &JString) -> Result<Option<CString>, ()> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    let s: String = env.get_string(s).map_err(|_| ())?.into();
+    strings::to_cstring(s).map(Some).map_err(|_| ())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRegisterShader(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    vert_src: JString,
+    frag_src: JString,
+    name: JString,
+) -> jboolean {
+    let (Ok(vertex), Ok(Some(fragment)), Ok(Some(name))) = (
+        optional_cstring(&mut env, &vert_src),
+        optional_cstring(&mut env, &frag_src),
+        optional_cstring(&mut env, &name),
+    ) else {
+        return 0;
+    };
+    let vertex = vertex.as_ref().map_or(std::ptr::null(), |v| v.as_ptr());
+    game_register_shader(handle as GameHandle, vertex, fragment.as_ptr(), name.as_ptr()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSpriteMaterial(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    shader: JString,
+) -> jboolean {
+    let Ok(shader) = optional_cstring(&mut env, &shader) else {
+        return 0;
+    };
+    let shader = shader.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+    game_set_sprite_material(handle as GameHandle, id as u64, shader) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetSpriteUniform(
+    mut env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    id: jlong,
+    name: JString,
+    values: JFloatArray,
+) -> jboolean {
+    let Ok(Some(name)) = optional_cstring(&mut env, &name) else {
+        return 0;
+    };
+    let Ok(len) = env.get_array_length(&values) else {
+        return 0;
+    };
+    let mut buffer = vec![0.0f32; len as usize];
+    if env.get_float_array_region(&values, 0, &mut buffer).is_err() {
+        return 0;
+    }
+    game_set_sprite_uniform(handle as GameHandle, id as u64, name.as_ptr(), buffer.as_ptr(), len as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameRegisterFont(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    data: JByteArray,
+) -> jboolean {
+    let Ok(bytes) = env.convert_byte_array(&data) else {
+        return 0;
+    };
+    game_register_font(handle as GameHandle, bytes.as_ptr(), bytes.len()) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameSetTextDirection(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    direction: jint,
+) -> jboolean {
+    game_set_text_direction(handle as GameHandle, direction as u32) as jboolean
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTrimGlyphAtlas(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    keep_visible: jboolean,
+) -> jint {
+    game_trim_glyph_atlas(handle as GameHandle, keep_visible != 0) as jint
+}
+
+/// Called from onTrimMemory: RUNNING_* levels map to TRIM_MEMORY_MODERATE, UI_HIDDEN and up to
+/// TRIM_MEMORY_BACKGROUND
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameTrimMemory(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    level: jint,
+) -> jboolean {
+    game_trim_memory(handle as GameHandle, level as u32) as jboolean
+}
+
+/// Returns [pages, maxPages, glyphs, occupancy, textureBytes, rasterized, evicted], or null for an invalid handle
+#[no_mangle]
+pub extern "system" fn Java_com_example_flutter_1con_GameNative_gameGetGlyphAtlasStats(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jdoubleArray {
+    let mut stats = GlyphAtlasStats::default();
+    if !game_get_glyph_atlas_stats(handle as GameHandle, &mut stats) {
+        return std::ptr::null_mut();
+    }
+    let values = [
+        stats.pages as f64,
+        stats.max_pages as f64,
+        stats.glyphs as f64,
+        stats.occupancy as f64,
+        stats.texture_bytes as f64,
+        stats.rasterized as f64,
+        stats.evicted as f64,
+    ];
+    let Ok(array) = env.new_double_array(values.len() as i32) else {
+        return std::ptr::null_mut();
+    };
+    if env.set_double_array_region(&array, 0, &values).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
 }
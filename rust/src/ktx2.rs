@@ -0,0 +1,203 @@
+use glow::HasContext;
+
+/// File identifier every KTX2 file starts with
+const IDENTIFIER: [u8; 12] = [0xab, b'K', b'T', b'X', b' ', b'2', b'0', 0xbb, b'\r', b'\n', 0x1a, b'\n'];
+
+/// Header plus the fixed-size index before the level index
+const HEADER_BYTES: usize = 80;
+
+/// Compressed formats the GPU can sample, for game_get_compressed_texture_formats
+/// Bit values are part of the C API; never renumber
+pub const FORMAT_ETC2: u32 = 1 << 0;
+pub const FORMAT_ASTC: u32 = 1 << 1;
+
+/// Texel layouts this loader uploads (Vulkan formats of the same name)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Rgba8,
+    Etc2Rgb,
+    /// One bit of alpha
+    Etc2Rgba1,
+    Etc2Rgba,
+    /// Block width and height in texels
+    Astc(u8, u8),
+}
+
+/// ASTC block sizes in Vulkan (and GL) format order
+const ASTC_BLOCKS: [(u8, u8); 14] =
+    [(4, 4), (5, 4), (5, 5), (6, 5), (6, 6), (8, 5), (8, 6), (8, 8), (10, 5), (10, 6), (10, 8), (10, 10), (12, 10), (12, 12)];
+
+impl Format {
+    /// Format from a KTX2 vkFormat; UNORM and SRGB variants both map to the same layout
+    fn from_vk(vk_format: u32) -> Option<Format> {
+        match vk_format {
+            37 | 43 => Some(Format::Rgba8),
+            147 | 148 => Some(Format::Etc2Rgb),
+            149 | 150 => Some(Format::Etc2Rgba1),
+            151 | 152 => Some(Format::Etc2Rgba),
+            157..=184 => {
+                let (w, h) = ASTC_BLOCKS[(vk_format - 157) as usize / 2];
+                Some(Format::Astc(w, h))
+            }
+            _ => None,
+        }
+    }
+
+    /// FORMAT_* bit the GPU needs for this format; 0 for uncompressed
+    pub fn capability(self) -> u32 {
+        match self {
+            Format::Rgba8 => 0,
+            Format::Etc2Rgb | Format::Etc2Rgba1 | Format::Etc2Rgba => FORMAT_ETC2,
+            Format::Astc(..) => FORMAT_ASTC,
+        }
+    }
+
+    /// Texels per block side and bytes per block
+    fn block(self) -> (usize, usize, usize) {
+        match self {
+            Format::Rgba8 => (1, 1, 4),
+            Format::Etc2Rgb | Format::Etc2Rgba1 => (4, 4, 8),
+            Format::Etc2Rgba => (4, 4, 16),
+            Format::Astc(w, h) => (w as usize, h as usize, 16),
+        }
+    }
+
+    /// sRGB GL internal format: color data is sampled as sRGB, the way egui's own textures are
+    fn gl_format(self) -> u32 {
+        match self {
+            Format::Rgba8 => glow::SRGB8_ALPHA8,
+            Format::Etc2Rgb => glow::COMPRESSED_SRGB8_ETC2,
+            Format::Etc2Rgba1 => glow::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            Format::Etc2Rgba => glow::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+            Format::Astc(w, h) => {
+                let index = ASTC_BLOCKS.iter().position(|&block| block == (w, h)).unwrap_or(0);
+                glow::COMPRESSED_SRGB8_ALPHA8_ASTC_4x4_KHR + index as u32
+            }
+        }
+    }
+
+    /// Bytes of one level of `width` x `height` texels
+    fn level_bytes(self, width: usize, height: usize) -> usize {
+        let (block_w, block_h, bytes) = self.block();
+        width.div_ceil(block_w) * height.div_ceil(block_h) * bytes
+    }
+}
+
+/// A 2D KTX2 texture: its format, base size and mip levels, largest first
+pub struct Ktx2<'a> {
+    pub format: Format,
+    pub size: [usize; 2],
+    levels: Vec<&'a [u8]>,
+}
+
+/// Parse a KTX2 file without copying its texel data
+/// Only plain 2D textures are supported: no arrays, cube maps, 3D textures or supercompression
+/// (Basis Universal or zstd), which would need transcoding
+pub fn parse(bytes: &[u8]) -> Result<Ktx2<'_>, String> {
+    if !bytes.starts_with(&IDENTIFIER) || bytes.len() < HEADER_BYTES {
+        return Err("Not a KTX2 file".to_string());
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+    let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap_or_default());
+
+    let vk_format = u32_at(12);
+    let format = Format::from_vk(vk_format).ok_or_else(|| format!("Unsupported KTX2 format {}", vk_format))?;
+    let (width, height, depth) = (u32_at(20) as usize, u32_at(24) as usize, u32_at(28));
+    let (layers, faces, level_count, supercompression) = (u32_at(32), u32_at(36), u32_at(40), u32_at(44));
+    if width == 0 || height == 0 || depth > 1 || layers > 1 || faces != 1 {
+        return Err("Only 2D KTX2 textures are supported".to_string());
+    }
+    if supercompression != 0 {
+        return Err(format!("KTX2 supercompression scheme {} isn't supported", supercompression));
+    }
+
+    // Level 0 means "generate mipmaps", which compressed formats can't do
+    let level_count = level_count.max(1) as usize;
+    let index_end = HEADER_BYTES + level_count * 24;
+    if bytes.len() < index_end {
+        return Err("KTX2 level index is truncated".to_string());
+    }
+    let mut levels = Vec::with_capacity(level_count);
+    for level in 0..level_count {
+        let entry = HEADER_BYTES + level * 24;
+        let (offset, length) = (u64_at(entry) as usize, u64_at(entry + 8) as usize);
+        let (w, h) = ((width >> level).max(1), (height >> level).max(1));
+        let expected = format.level_bytes(w, h);
+        let data = offset.checked_add(length).and_then(|end| bytes.get(offset..end)).ok_or("KTX2 level data is truncated")?;
+        if length < expected {
+            return Err(format!("KTX2 level {} has {} bytes, expected {}", level, length, expected));
+        }
+        levels.push(&data[..expected]);
+    }
+    Ok(Ktx2 { format, size: [width, height], levels })
+}
+
+impl Ktx2<'_> {
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Base level as an egui image (uncompressed textures only), for loading without GL
+    pub fn to_image(&self) -> Option<egui::ColorImage> {
+        (self.format == Format::Rgba8).then(|| egui::ColorImage::from_rgba_unmultiplied(self.size, self.levels[0]))
+    }
+
+    /// Create a GL texture holding every level, sampled trilinearly when there are mipmaps
+    /// The caller checks the GPU supports the format (supported_formats)
+    ///
+    /// # Safety
+    /// The GL context must be current on this thread
+    pub unsafe fn upload(&self, gl: &glow::Context) -> Result<glow::Texture, String> {
+        let texture = gl.create_texture()?;
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        let internal = self.format.gl_format();
+        for (level, data) in self.levels.iter().enumerate() {
+            let (w, h) = ((self.size[0] >> level).max(1) as i32, (self.size[1] >> level).max(1) as i32);
+            if self.format == Format::Rgba8 {
+                gl.tex_image_2d(
+                    glow::TEXTURE_2D,
+                    level as i32,
+                    internal as i32,
+                    w,
+                    h,
+                    0,
+                    glow::RGBA,
+                    glow::UNSIGNED_BYTE,
+                    Some(data),
+                );
+            } else {
+                gl.compressed_tex_image_2d(glow::TEXTURE_2D, level as i32, internal as i32, w, h, 0, data.len() as i32, data);
+            }
+        }
+        let min_filter = if self.levels.len() > 1 { glow::LINEAR_MIPMAP_LINEAR } else { glow::LINEAR };
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, min_filter as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, self.levels.len() as i32 - 1);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+
+        let error = gl.get_error();
+        if error != glow::NO_ERROR {
+            gl.delete_texture(texture);
+            return Err(format!("GL error 0x{:x} uploading {:?} texture", error, self.format));
+        }
+        Ok(texture)
+    }
+}
+
+/// FORMAT_* bits for the compressed formats the current context can sample
+/// ETC2 is core in OpenGL ES 3.0; ASTC needs the LDR extension
+pub fn supported_formats(gl: &glow::Context) -> u32 {
+    let extensions = gl.supported_extensions();
+    let version = gl.version();
+    let mut formats = 0;
+    if (version.is_embedded && version.major >= 3) || extensions.contains("GL_ARB_ES3_compatibility") {
+        formats |= FORMAT_ETC2;
+    }
+    if extensions.contains("GL_KHR_texture_compression_astc_ldr") {
+        formats |= FORMAT_ASTC;
+    }
+    formats
+}
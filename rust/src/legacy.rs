@@ -0,0 +1,47 @@
+/// Entry points from before scene nodes, when the game had exactly one player. They still work,
+/// acting on the primary player (the node game_get_player_node returns), but new hosts should use
+/// the node-addressed calls that replace them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LegacyCall {
+    SetDirection,
+    Touch,
+    GetPlayerX,
+    GetPlayerY,
+}
+
+impl LegacyCall {
+    fn name(self) -> &'static str {
+        match self {
+            LegacyCall::SetDirection => "game_set_direction",
+            LegacyCall::Touch => "game_touch",
+            LegacyCall::GetPlayerX => "game_get_player_x",
+            LegacyCall::GetPlayerY => "game_get_player_y",
+        }
+    }
+
+    fn replacement(self) -> &'static str {
+        match self {
+            LegacyCall::SetDirection => "game_set_player_direction",
+            LegacyCall::Touch => "game_touch_player",
+            LegacyCall::GetPlayerX | LegacyCall::GetPlayerY => "game_get_node_position",
+        }
+    }
+}
+
+/// Legacy calls already warned about; each is logged once per engine, since input calls come
+/// every frame
+#[derive(Default)]
+pub struct Deprecations {
+    warned: u32,
+}
+
+impl Deprecations {
+    /// Log that `call` is deprecated if it hasn't been yet
+    pub fn warn(&mut self, call: LegacyCall) {
+        let bit = 1 << call as u32;
+        if self.warned & bit == 0 {
+            self.warned |= bit;
+            log::warn!("{} is deprecated; use {} with game_get_player_node", call.name(), call.replacement());
+        }
+    }
+}
@@ -1,31 +1,180 @@
+// The C API hands the engine raw pointers (the GameHandle, strings, out-parameters) through safe
+// `extern "C"` functions, as the host calls them the same way either way. Its contract, stated in
+// game_api.h, is that pointers are null or valid for the call; every export null-checks before
+// dereferencing, so clippy's per-function unsafe requirement is waived for the whole FFI surface
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
 // JNI module only for Android
 #[cfg(target_os = "android")]
 mod jni;
 
+mod a11y;
+mod affinity;
+mod animated;
+mod arena;
+mod assets;
+mod atlas;
+mod audio;
+mod background;
+// Typed Dart API generated by flutter_rust_bridge (see flutter_rust_bridge.yaml)
+#[cfg(feature = "frb")]
+pub mod bridge;
+mod buffer;
+mod camera;
+mod capabilities;
+mod capture;
+mod challenge;
+mod clip;
+mod collision;
+mod crash;
+mod debug_overlay;
+mod display_list;
+mod draw_list;
+mod engine;
+mod events;
+mod ffi;
+mod frame_loop;
+// Written by flutter_rust_bridge_codegen from the bridge module
+#[cfg(feature = "frb")]
+mod frb_generated;
+mod gl_debug;
+mod gpu_capture;
+mod gpu_sync;
+mod gpu_timer;
+mod haptics;
+#[cfg(feature = "headless")]
+mod headless;
+mod hud;
+mod input;
+mod input_buffer;
+mod json;
+mod ktx2;
+mod legacy;
+mod lod;
+mod logging;
+mod material;
+mod nav;
+mod orientation;
+#[cfg(feature = "physics")]
+mod physics;
+mod pacing;
+mod palette;
+mod params;
+mod particles;
+mod picture;
+mod players;
+mod policy;
+mod pool;
+mod postfx;
+mod power;
+mod prefab;
+mod procgen;
+mod qr;
+mod quirks;
+mod render_hook;
+mod replay;
+mod render_target;
+mod ring_queue;
+mod rng;
+mod save;
+mod scene;
+mod scoreboard;
+mod shader;
+mod share;
+mod simulation;
+mod spatial;
+mod spikes;
+mod splash;
+mod sprite_batch;
+mod sprites;
+mod state_diff;
+mod state_machine;
+mod stats;
+mod svg;
+mod tags;
+mod tasks;
+mod tessellation;
+mod text;
+mod thread_hints;
+mod throw;
+mod tilemap;
+mod trace;
+mod tween;
+mod ui;
+mod vector;
+mod video_capture;
+mod xml;
+
+use std::ffi::{c_char, c_void};
 use std::panic;
+use std::path::Path;
 use std::sync::Arc;
-
-use egui::{Color32, Pos2, Rect, Rounding, Stroke, Vec2};
-use glow::HasContext;
+use std::time::{Duration, Instant};
+
+use egui::{Pos2, Rect, Vec2};
+
+use atlas::{AtlasId, LoopMode, INVALID_ATLAS};
+use audio::{Channel, SoundId, INVALID_SOUND};
+use buffer::GameBuffer;
+use collision::{ColliderId, INVALID_COLLIDER};
+use events::GameEventRecord;
+use ffi::strings;
+use haptics::HapticPattern;
+use input::gamepad::Control;
+use input::keyboard::{KeyAction, KeyModifiers};
+use input::routing::TouchRouting;
+use input::sensors::SensorType;
+use legacy::LegacyCall;
+use lod::LodSettings;
+use logging::LogCallback;
+use orientation::{Orientation, RotationPolicy};
+use pacing::PacingStats;
+use players::Controller;
+use palette::Palette;
+use params::Param;
+use policy::RunSummary;
+use particles::{EmitterConfig, EmitterId, INVALID_EMITTER};
+use postfx::PostFxSettings;
+use power::ThermalState;
+use quirks::Quirks;
+use render_hook::RenderHook;
+use scene::{NodeId, Transform, INVALID_NODE};
+use scoreboard::Stat;
+use sprites::{SpriteId, INVALID_SPRITE};
+use state_machine::{StateHook, StateId, INVALID_STATE};
+use stats::{EventStats, FrameStats, InitTimings};
+use text::GlyphAtlasStats;
+use thread_hints::ThreadHints;
+use tilemap::{TilemapId, INVALID_TILEMAP};
+use tween::{Easing, TweenId, INVALID_TWEEN};
+use ui::{WidgetId, INVALID_WIDGET};
+use video_capture::FrameCallback;
+
+pub use affinity::Instance;
+pub use engine::{Direction, GameEngine, GameMode, Renderer, ScreenSpace, TouchAction, TrimLevel};
+
+/// Trace span for the FFI function the macro is expanded in, entered until dropped
+macro_rules! ffi_span {
+    () => {{
+        fn f() {}
+        tracing::info_span!("ffi", function = trace::function_name(std::any::type_name_of_val(&f))).entered()
+    }};
+}
 
 /// Wrap FFI calls with panic catching to prevent crashes across FFI boundary
+/// The panic's message, location and backtrace are logged (see crash module)
 macro_rules! catch_panic {
-    ($default:expr, $body:expr) => {
+    ($default:expr, $body:expr) => {{
+        let _span = ffi_span!();
         match panic::catch_unwind(panic::AssertUnwindSafe(|| $body)) {
             Ok(result) => result,
             Err(e) => {
-                let msg = if let Some(s) = e.downcast_ref::<&str>() {
-                    s.to_string()
-                } else if let Some(s) = e.downcast_ref::<String>() {
-                    s.clone()
-                } else {
-                    "Unknown panic".to_string()
-                };
-                log::error!("Panic caught in FFI: {}", msg);
+                let crash = crash::take(&*e);
+                log::error!("Panic caught in FFI: {}\n{}", crash.message, crash.backtrace);
                 $default
             }
         }
-    };
+    }};
 }
 
 // Platform-specific GL loader
@@ -38,116 +187,198 @@ extern "C" {
 // iOS uses EAGL - GL functions are resolved at link time
 // No runtime loader needed
 
-/// Direction enum for player movement
-#[derive(Default, Clone, Copy, Debug, PartialEq)]
-#[repr(i32)]
-pub enum Direction {
-    #[default]
-    None = 0,
-    Up = 1,
-    Down = 2,
-    Left = 3,
-    Right = 4,
-}
-
-/// Game mode enum
-#[derive(Default, Clone, Copy, Debug, PartialEq)]
-#[repr(i32)]
-pub enum GameMode {
-    #[default]
-    Manual = 0,
-    Auto = 1,
-}
-
-impl From<i32> for Direction {
-    fn from(value: i32) -> Self {
-        match value {
-            1 => Direction::Up,
-            2 => Direction::Down,
-            3 => Direction::Left,
-            4 => Direction::Right,
-            _ => Direction::None,
-        }
+/// glow context for the GL context current on this thread (set up by the host view)
+#[cfg(target_os = "android")]
+fn platform_gl() -> Option<glow::Context> {
+    Some(unsafe {
+        glow::Context::from_loader_function(|s| {
+            let c_str = match strings::to_cstring(s) {
+                Ok(c) => c,
+                Err(_) => return std::ptr::null(),
+            };
+            eglGetProcAddress(c_str.as_ptr() as *const i8)
+        })
+    })
+}
+
+#[cfg(target_os = "ios")]
+fn platform_gl() -> Option<glow::Context> {
+    extern "C" {
+        fn dlsym(handle: *mut std::ffi::c_void, symbol: *const i8) -> *mut std::ffi::c_void;
     }
+    const RTLD_DEFAULT: *mut std::ffi::c_void = -2isize as *mut std::ffi::c_void;
+
+    Some(unsafe {
+        glow::Context::from_loader_function(|s| {
+            let c_str = match strings::to_cstring(s) {
+                Ok(c) => c,
+                Err(_) => return std::ptr::null_mut(),
+            };
+            dlsym(RTLD_DEFAULT, c_str.as_ptr())
+        })
+    })
 }
 
-/// Touch action enum
-#[derive(Clone, Copy, Debug)]
-#[repr(i32)]
-pub enum TouchAction {
-    Down = 0,
-    Up = 1,
-    Move = 2,
+/// Desktop hosts have no view-provided context; they render through game_init_headless
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn platform_gl() -> Option<glow::Context> {
+    None
 }
 
-impl From<i32> for TouchAction {
-    fn from(value: i32) -> Self {
-        match value {
-            0 => TouchAction::Down,
-            1 => TouchAction::Up,
-            2 => TouchAction::Move,
-            _ => TouchAction::Down,
+/// Opaque handle for FFI
+pub type GameHandle = *mut Instance;
+
+/// Run `$body` with the engine behind `$handle`, returning `$default` for a null or poisoned handle
+/// A panic in `$body` poisons the engine: it reports EVENT_ENGINE_CRASHED and refuses later calls
+/// The `poisoned` form runs even then, for calls the host needs to learn about the crash
+/// Calls from a thread other than the one that created the engine are run on that thread (see affinity)
+/// The `queued` form, for calls returning nothing, doesn't wait for that; `$body` may only capture
+/// values that can be sent to the render thread
+macro_rules! with_engine {
+    (queued, $handle:expr, |$engine:ident| $body:expr) => {{
+        let handle: GameHandle = $handle;
+        let _span = ffi_span!();
+        match unsafe { handle.as_ref() } {
+            None => {}
+            Some(instance) if instance.is_owner_thread() => {
+                with_engine!(@call unsafe { Instance::engine(handle) }, (), false, |$engine| $body)
+            }
+            Some(instance) => {
+                instance.post(move |engine| with_engine!(@call engine, (), false, |$engine| $body));
+            }
         }
-    }
+    }};
+    ($handle:expr, $default:expr, |$engine:ident| $body:expr) => {
+        with_engine!(@run $handle, $default, false, |$engine| $body)
+    };
+    (poisoned, $handle:expr, $default:expr, |$engine:ident| $body:expr) => {
+        with_engine!(@run $handle, $default, true, |$engine| $body)
+    };
+    (@run $handle:expr, $default:expr, $allow_poisoned:expr, |$engine:ident| $body:expr) => {{
+        let handle: GameHandle = $handle;
+        let _span = ffi_span!();
+        match unsafe { handle.as_ref() } {
+            None => $default,
+            Some(instance) if instance.is_owner_thread() => {
+                with_engine!(@call unsafe { Instance::engine(handle) }, $default, $allow_poisoned, |$engine| $body)
+            }
+            Some(instance) => instance
+                .call_from_other_thread(|engine| with_engine!(@call engine, $default, $allow_poisoned, |$engine| $body))
+                .unwrap_or($default),
+        }
+    }};
+    (@call $instance_engine:expr, $default:expr, $allow_poisoned:expr, |$engine:ident| $body:expr) => {{
+        let engine: &mut GameEngine = $instance_engine;
+        if engine.is_poisoned() && !$allow_poisoned {
+            engine.refuse_call();
+            $default
+        } else {
+            match panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                let $engine = &mut *engine;
+                $body
+            })) {
+                Ok(result) => result,
+                Err(e) => {
+                    let crash = crash::take(&*e);
+                    log::error!("Panic caught in FFI: {}\n{}", crash.message, crash.backtrace);
+                    engine.poison(crash);
+                    $default
+                }
+            }
+        }
+    }};
 }
 
-/// Game state held across FFI boundary
-pub struct GameState {
-    gl: Arc<glow::Context>,
-    width: u32,
-    height: u32,
-
-    // egui
-    egui_ctx: egui::Context,
-    egui_painter: egui_glow::Painter,
-
-    // Player state
-    player_x: f32,
-    player_y: f32,
-    player_size: f32,
-    current_direction: Direction,
-
-    // Touch state
-    is_player_touched: bool,
-    drag_offset_x: f32,
-    drag_offset_y: f32,
-
-    // Game mode
-    game_mode: GameMode,
-    velocity_x: f32,
-    velocity_y: f32,
+/// with_engine! for the Dart bridge: None for a null or poisoned handle, or a call that was dropped
+#[cfg(feature = "frb")]
+fn call_engine<T>(handle: usize, f: impl FnOnce(&mut GameEngine) -> T) -> Option<T> {
+    with_engine!(handle as GameHandle, None, |engine| Some(f(engine)))
+}
 
-    // Player texture (keep TextureHandle alive to prevent texture from being freed)
-    player_texture: Option<egui::TextureHandle>,
-    player_texture_size: (f32, f32), // (width, height) of the original image
+/// Declare which optional subsystems (GAME_FEATURE_* bits) the host intends to use
+/// Returns the subset this build and device actually provide; only those are initialized
+/// Applies to instances created by later game_init calls; without it everything available is granted
+#[no_mangle]
+pub extern "C" fn game_negotiate(requested_features: u64) -> u64 {
+    catch_panic!(0, {
+        let granted = capabilities::negotiate(requested_features);
+        log::info!("Negotiated features: requested={:#x} granted={:?}", requested_features, granted);
+        granted.bits()
+    })
+}
 
-    // Player tint color (changes on bounce)
-    player_tint: Color32,
+/// Load a driver quirk database (JSON, see quirks::load_rules) replacing any loaded before
+/// Rules are matched against the GL vendor/renderer/version by later game_init calls
+/// Returns false if the JSON is invalid; the previous database stays in effect
+#[no_mangle]
+pub extern "C" fn game_load_quirks(json: *const c_char) -> bool {
+    catch_panic!(false, {
+        let json = match unsafe { strings::read(json) } {
+            Ok(json) => json,
+            Err(e) => {
+                log::warn!("game_load_quirks: {}", e);
+                return false;
+            }
+        };
+        match quirks::load_rules(json) {
+            Ok(count) => {
+                log::info!("Loaded {} quirk rules", count);
+                true
+            }
+            Err(e) => {
+                log::warn!("game_load_quirks: {}", e);
+                false
+            }
+        }
+    })
+}
 
-    // Time tracking
-    last_frame_time: std::time::Instant,
+/// Driver workarounds in effect for this instance (GAME_QUIRK_* bits)
+#[no_mangle]
+pub extern "C" fn game_get_quirks(handle: GameHandle) -> u32 {
+    with_engine!(handle, 0, |engine| engine.quirks().bits())
 }
 
-/// Opaque handle for FFI
-pub type GameHandle = *mut GameState;
+/// Replace this instance's workarounds (GAME_QUIRK_* bits; unknown bits are ignored)
+/// Returns false for a headless instance
+#[no_mangle]
+pub extern "C" fn game_set_quirks(handle: GameHandle, quirks: u32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_quirks(Quirks::from_bits_truncate(quirks)))
+}
 
-/// Generate a random bright color based on current time
-fn random_color() -> Color32 {
-    let time = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
+/// Features granted to this instance (GAME_FEATURE_* bits)
+#[no_mangle]
+pub extern "C" fn game_get_features(handle: GameHandle) -> u64 {
+    with_engine!(handle, 0, |engine| engine.features().bits())
+}
 
-    let r = ((time >> 0) & 0xFF) as u8;
-    let g = ((time >> 8) & 0xFF) as u8;
-    let b = ((time >> 16) & 0xFF) as u8;
+/// Why the calling thread's last call from off the render thread was dropped (0 = it wasn't)
+/// 1 = the render thread's inbox was full, 2 = it didn't run a call returning a value within 250 ms
+/// Calls returning nothing are only queued for the render thread, so they can only fail with 1
+#[no_mangle]
+pub extern "C" fn game_last_call_error() -> i32 {
+    catch_panic!(0, { affinity::last_error() as i32 })
+}
 
-    // Ensure colors are bright (minimum 128)
-    Color32::from_rgb(128 + (r / 2), 128 + (g / 2), 128 + (b / 2))
+/// Why the calling thread's last string argument was rejected (0 = it was accepted)
+/// 1 = null, 2 = invalid UTF-8, 3 = interior NUL (StringError)
+#[no_mangle]
+pub extern "C" fn game_last_string_error() -> i32 {
+    catch_panic!(0, { strings::last_error() as i32 })
 }
 
-/// Embed player image at compile time
-const PLAYER_IMAGE_BYTES: &[u8] = include_bytes!("../assets/player.png");
+/// Forward engine log records (level, target, message) to `callback` as well as logcat / os_log
+/// `max_level` is GAME_LOG_ERROR (1) .. GAME_LOG_TRACE (5); a null callback or 0 stops forwarding
+/// Process-wide, for every instance; may be called before game_init
+/// `user_data` must stay valid until the callback is replaced, and a replaced callback may still be
+/// running on another thread when this returns
+#[no_mangle]
+pub extern "C" fn game_set_log_callback(callback: Option<LogCallback>, user_data: *mut c_void, max_level: u32) {
+    catch_panic!((), {
+        let max_level = logging::level_filter(max_level);
+        logging::set_callback(callback.filter(|_| max_level != log::LevelFilter::Off), user_data, max_level);
+    })
+}
 
 /// Initialize the game engine
 /// Called from GLSurfaceView.onSurfaceCreated() on Android
@@ -156,20 +387,9 @@ const PLAYER_IMAGE_BYTES: &[u8] = include_bytes!("../assets/player.png");
 #[no_mangle]
 pub extern "C" fn game_init(width: u32, height: u32) -> GameHandle {
     catch_panic!(std::ptr::null_mut(), {
-        // Initialize platform-specific logging (only once)
-        #[cfg(target_os = "android")]
-        android_logger::init_once(
-            android_logger::Config::default()
-                .with_max_level(log::LevelFilter::Info)
-                .with_tag("RustGame"),
-        );
-
-        #[cfg(target_os = "ios")]
-        {
-            let _ = oslog::OsLogger::new("com.example.flutter_con")
-                .level_filter(log::LevelFilter::Info)
-                .init();
-        }
+        logging::init();
+        trace::install();
+        crash::install_hook();
 
         log::info!("game_init: {}x{}", width, height);
 
@@ -178,103 +398,53 @@ pub extern "C" fn game_init(width: u32, height: u32) -> GameHandle {
             log::warn!("game_init called with zero dimensions, will resize later");
         }
 
-        // Create glow context - platform specific GL loader
-        #[cfg(target_os = "android")]
-        let gl = unsafe {
-            glow::Context::from_loader_function(|s| {
-                let c_str = match std::ffi::CString::new(s) {
-                    Ok(c) => c,
-                    Err(_) => return std::ptr::null(),
-                };
-                eglGetProcAddress(c_str.as_ptr() as *const i8)
-            })
-        };
-
-        #[cfg(target_os = "ios")]
-        let gl = unsafe {
-            extern "C" {
-                fn dlsym(handle: *mut std::ffi::c_void, symbol: *const i8) -> *mut std::ffi::c_void;
-            }
-            const RTLD_DEFAULT: *mut std::ffi::c_void = -2isize as *mut std::ffi::c_void;
-
-            glow::Context::from_loader_function(|s| {
-                let c_str = match std::ffi::CString::new(s) {
-                    Ok(c) => c,
-                    Err(_) => return std::ptr::null_mut(),
-                };
-                dlsym(RTLD_DEFAULT, c_str.as_ptr())
-            })
+        let Some(gl) = platform_gl() else {
+            log::error!("game_init: no platform GL loader on this target (use game_init_headless)");
+            return std::ptr::null_mut();
         };
 
-        let gl = Arc::new(gl);
-
-        // Set initial viewport
-        unsafe {
-            gl.viewport(0, 0, width as i32, height as i32);
-        }
-
-        // Create egui context
-        let egui_ctx = egui::Context::default();
-
-        // Create egui_glow painter for OpenGL ES
-        let egui_painter = match egui_glow::Painter::new(gl.clone(), "", None, false) {
-            Ok(painter) => painter,
+        let renderer = match Renderer::new(Arc::new(gl)) {
+            Ok(renderer) => renderer,
             Err(e) => {
                 log::error!("Failed to create egui painter: {}", e);
                 return std::ptr::null_mut();
             }
         };
 
-        let player_size = 200.0;
-
-        // Load player texture from embedded PNG
-        let (player_texture, player_texture_size) = match image::load_from_memory(PLAYER_IMAGE_BYTES) {
-            Ok(img) => {
-                let rgba = img.to_rgba8();
-                let img_width = rgba.width() as f32;
-                let img_height = rgba.height() as f32;
-                let size = [rgba.width() as usize, rgba.height() as usize];
-                let pixels = rgba.into_raw();
-
-                let color_image = egui::ColorImage::from_rgba_unmultiplied(size, &pixels);
-                let texture = egui_ctx.load_texture(
-                    "player",
-                    color_image,
-                    egui::TextureOptions::LINEAR,
-                );
-                log::info!("Player texture loaded: {}x{}", img_width, img_height);
-                (Some(texture), (img_width, img_height))
-            }
-            Err(e) => {
-                log::error!("Failed to load player image: {}", e);
-                (None, (player_size, player_size)) // Default to square
-            }
-        };
-
-        let state = Box::new(GameState {
-            gl,
-            width,
-            height,
-            egui_ctx,
-            egui_painter,
-            player_x: width as f32 / 2.0,
-            player_y: height as f32 / 2.0,
-            player_size,
-            current_direction: Direction::None,
-            is_player_touched: false,
-            drag_offset_x: 0.0,
-            drag_offset_y: 0.0,
-            game_mode: GameMode::Manual,
-            velocity_x: 0.0,
-            velocity_y: 0.0,
-            player_texture,
-            player_texture_size,
-            player_tint: Color32::WHITE,
-            last_frame_time: std::time::Instant::now(),
-        });
+        let engine = GameEngine::new(Some(renderer), width, height);
 
         log::info!("Game initialized successfully");
-        Box::into_raw(state)
+        Instance::into_handle(engine)
+    })
+}
+
+/// Initialize an engine rendering into its own off-screen `width` x `height` surface
+/// For desktop CI and golden-image tests: render with game_render, read back with game_capture_region
+/// The GL context stays current on the calling thread; make every later call from it
+/// Needs the `headless` cargo feature and a libEGL at runtime (Mesa's surfaceless platform needs no display)
+/// Returns null on failure
+#[no_mangle]
+pub extern "C" fn game_init_headless(width: u32, height: u32) -> GameHandle {
+    catch_panic!(std::ptr::null_mut(), {
+        #[cfg(feature = "headless")]
+        {
+            logging::init();
+            trace::install();
+            crash::install_hook();
+            log::info!("game_init_headless: {}x{}", width, height);
+            match Renderer::headless(width, height) {
+                Ok(renderer) => Instance::into_handle(GameEngine::new(Some(renderer), width, height)),
+                Err(e) => {
+                    log::error!("game_init_headless failed: {}", e);
+                    std::ptr::null_mut()
+                }
+            }
+        }
+        #[cfg(not(feature = "headless"))]
+        {
+            log::warn!("game_init_headless ({}x{}): built without the headless feature", width, height);
+            std::ptr::null_mut()
+        }
     })
 }
 
@@ -282,310 +452,2553 @@ pub extern "C" fn game_init(width: u32, height: u32) -> GameHandle {
 /// Called from GLSurfaceView.onSurfaceChanged()
 #[no_mangle]
 pub extern "C" fn game_resize(handle: GameHandle, width: u32, height: u32) {
-    catch_panic!((), {
-        if handle.is_null() {
-            return;
+    with_engine!(queued, handle, |engine| {
+        engine.resize(width, height);
+        log::info!("game_resize: {}x{}", width, height);
+    })
+}
+
+/// Set physical pixels per logical point (devicePixelRatio / UIScreen.scale)
+/// Game coordinates, sizes and capture regions are in points; touches stay in physical pixels
+/// The player keeps its place on screen; ignored unless finite and positive
+#[no_mangle]
+pub extern "C" fn game_set_scale_factor(handle: GameHandle, scale: f32) {
+    with_engine!(queued, handle, |engine| {
+        if engine.set_scale_factor(scale) {
+            log::info!("game_set_scale_factor: {}", scale);
+        } else {
+            log::warn!("game_set_scale_factor: invalid scale {}", scale);
         }
-        let state = unsafe { &mut *handle };
+    })
+}
+
+/// Point the camera at world point (x, y) (points) with `zoom` (1 = 1:1, 2 = twice as big) turned
+/// `rotation` radians clockwise. Obstacles, sprites, scene nodes and particles are drawn through it and
+/// touches are mapped back into the world; the background, host draw lists and HUD stay put
+/// Returns false (camera unchanged) for non-finite values or a zoom outside 0.05..=20
+#[no_mangle]
+pub extern "C" fn game_set_camera(handle: GameHandle, x: f32, y: f32, zoom: f32, rotation: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_camera(Pos2::new(x, y), zoom, rotation))
+}
+
+/// Smoothly follow the player: the camera center closes `rate` of the distance per second
+/// (exponentially; around 5 feels natural). 0 stops following; false for negative or non-finite rates
+#[no_mangle]
+pub extern "C" fn game_set_camera_follow(handle: GameHandle, rate: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_camera_follow(rate))
+}
 
-        // Center player on first resize (when dimensions were 0)
-        if state.width == 0 || state.height == 0 {
-            state.player_x = width as f32 / 2.0;
-            state.player_y = height as f32 / 2.0;
+/// Back to the default view (world origin at the screen's top left, 1:1) and stop following
+#[no_mangle]
+pub extern "C" fn game_reset_camera(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.reset_camera())
+}
+
+/// Physical pixels covered by notches, home indicators and system bars at each screen edge (e.g. the
+/// window's safe-area or system-bar insets). UI widgets and the QR code are laid out clear of them, and
+/// the player is kept out from under them. Returns false (insets unchanged) for negative or NaN values
+#[no_mangle]
+pub extern "C" fn game_set_safe_area_insets(handle: GameHandle, top: f32, bottom: f32, left: f32, right: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_safe_area_insets(egui::Margin { left, right, top, bottom }))
+}
+
+/// Report the device orientation (GAME_ORIENTATION_*), before the resize a rotation brings
+/// When that resize arrives the player, sprites and their paths are moved per the rotation policy
+/// (game_set_rotation_policy) rather than only clamped into the new bounds. False for unknown values
+#[no_mangle]
+pub extern "C" fn game_set_orientation(handle: GameHandle, orientation: u32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(orientation) = Orientation::from_raw(orientation) else {
+            log::warn!("game_set_orientation: unknown orientation {}", orientation);
+            return false;
+        };
+        engine.set_orientation(orientation);
+        true
+    })
+}
+
+/// How rotations move entities (GAME_ROTATION_REMAP_*): proportionally to the world's new size
+/// (the default), or not at all beyond keeping the player inside. False for unknown values
+#[no_mangle]
+pub extern "C" fn game_set_rotation_policy(handle: GameHandle, policy: u32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(policy) = RotationPolicy::from_raw(policy) else {
+            log::warn!("game_set_rotation_policy: unknown policy {}", policy);
+            return false;
+        };
+        engine.set_rotation_policy(policy);
+        true
+    })
+}
+
+/// Make the world `width` x `height` points instead of the screen's size: the player bounces off its
+/// edges and levels are laid out over it. 0 x 0 goes back to the screen. False for other sizes <= 0
+#[no_mangle]
+pub extern "C" fn game_set_world_size(handle: GameHandle, width: f32, height: f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let size = (width != 0.0 || height != 0.0).then_some(Vec2::new(width, height));
+        engine.set_world_size(size)
+    })
+}
+
+/// Write the world point under screen point (x, y) (points, not pixels) as the last frame showed it
+/// Returns false for a null handle or output pointer
+#[no_mangle]
+pub extern "C" fn game_screen_to_world(handle: GameHandle, x: f32, y: f32, out_x: *mut f32, out_y: *mut f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out_x.is_null() || out_y.is_null() {
+            return false;
+        }
+        let world = engine.screen_to_world(Pos2::new(x, y));
+        unsafe {
+            *out_x = world.x;
+            *out_y = world.y;
         }
+        true
+    })
+}
 
-        state.width = width;
-        state.height = height;
+/// Write the world point under point (x, y) of screen space `space` (GAME_SCREEN_SPACE_*) as the
+/// last frame showed it. Returns false for a null handle or output pointer, or an unknown space
+#[no_mangle]
+pub extern "C" fn game_screen_to_world_in(handle: GameHandle, x: f32, y: f32, space: u32, out_x: *mut f32, out_y: *mut f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(space) = ScreenSpace::from_raw(space) else {
+            log::warn!("game_screen_to_world_in: unknown space {}", space);
+            return false;
+        };
+        if out_x.is_null() || out_y.is_null() {
+            return false;
+        }
+        let world = engine.screen_space_to_world(Pos2::new(x, y), space);
+        unsafe {
+            *out_x = world.x;
+            *out_y = world.y;
+        }
+        true
+    })
+}
 
+/// Write where world point (x, y) showed in the last frame, in screen space `space`
+/// (GAME_SCREEN_SPACE_*), through the camera and the scale factor: for placing host widgets such as
+/// tooltips over entities. Returns false for a null handle or output pointer, or an unknown space
+#[no_mangle]
+pub extern "C" fn game_world_to_screen(handle: GameHandle, x: f32, y: f32, space: u32, out_x: *mut f32, out_y: *mut f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(space) = ScreenSpace::from_raw(space) else {
+            log::warn!("game_world_to_screen: unknown space {}", space);
+            return false;
+        };
+        if out_x.is_null() || out_y.is_null() {
+            return false;
+        }
+        let screen = engine.world_to_screen(Pos2::new(x, y), space);
         unsafe {
-            state.gl.viewport(0, 0, width as i32, height as i32);
+            *out_x = screen.x;
+            *out_y = screen.y;
         }
+        true
+    })
+}
 
-        log::info!("game_resize: {}x{}", width, height);
+/// Drop fine detail from entities drawn small and from wide views to keep dense scenes smooth (see
+/// LodSettings); NULL turns it off. Returns false (settings unchanged) if a value is negative or not finite
+#[no_mangle]
+pub extern "C" fn game_set_lod(handle: GameHandle, settings: *const LodSettings) -> bool {
+    with_engine!(handle, false, |engine| {
+        let settings = if settings.is_null() { LodSettings::default() } else { unsafe { *settings } };
+        engine.set_lod(settings)
+    })
+}
+
+/// Current level-of-detail rules; false for a null handle or output pointer
+#[no_mangle]
+pub extern "C" fn game_get_lod(handle: GameHandle, out: *mut LodSettings) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out.is_null() {
+            return false;
+        }
+        unsafe { *out = engine.lod() };
+        true
     })
 }
 
+/// Draw runs of sprites and image nodes sharing a texture as one instanced draw each (on by default),
+/// or each as its own egui mesh, to rule the batcher out when something draws wrong
+#[no_mangle]
+pub extern "C" fn game_set_sprite_batching(handle: GameHandle, enabled: bool) {
+    with_engine!(queued, handle, |engine| engine.set_sprite_batching(enabled))
+}
+
 /// Update game state
 /// Called each frame before render
+/// Runs zero or more fixed-rate ticks (see game_set_tick_rate)
 /// Optimized: minimal allocations, no logging in hot path
 #[no_mangle]
 pub extern "C" fn game_update(handle: GameHandle) {
-    catch_panic!((), {
-        if handle.is_null() {
-            return;
-        }
-        let state = unsafe { &mut *handle };
-
-        // Calculate delta time with frame cap to prevent huge jumps
-        let now = std::time::Instant::now();
-        let delta = now.duration_since(state.last_frame_time).as_secs_f32();
-        state.last_frame_time = now;
+    // The simulation thread steps the engine itself
+    if simulation::is_running(handle as usize) {
+        return;
+    }
+    with_engine!(queued, handle, |engine| engine.update(Instant::now()))
+}
 
-        // Cap delta time to prevent physics explosions after pause
-        let delta = delta.min(0.1); // Max 100ms per frame
+/// Render the game using egui
+/// Called from GLSurfaceView.onDrawFrame()
+/// Returns true if a frame was drawn; false for a zero-sized surface, or when rendering on demand
+/// and nothing changed (the framebuffer is untouched then and need not be presented)
+#[no_mangle]
+pub extern "C" fn game_render(handle: GameHandle) -> bool {
+    // Frames come from the simulation thread's snapshots while it runs
+    if simulation::is_running(handle as usize) {
+        return catch_panic!(false, simulation::draw(handle as usize).unwrap_or(false));
+    }
+    // Runs poisoned so the Dart event stream still receives EVENT_ENGINE_CRASHED
+    with_engine!(poisoned, handle, false, |engine| {
+        let drawn = if engine.is_poisoned() {
+            engine.refuse_call();
+            false
+        } else {
+            engine.render()
+        };
+        #[cfg(feature = "frb")]
+        bridge::service(handle as usize, engine);
+        drawn
+    })
+}
 
-        let half = state.player_size / 2.0;
+/// Render on demand: game_render and game_render_with_timestamp only draw when game_needs_redraw
+/// is true, so a static scene (Manual mode, no touch) costs no GPU work. Off by default
+#[no_mangle]
+pub extern "C" fn game_set_render_on_demand(handle: GameHandle, enabled: bool) {
+    with_engine!(queued, handle, |engine| engine.set_render_on_demand(enabled))
+}
 
-        match state.game_mode {
-            GameMode::Manual => {
-                // Move player based on direction
-                let speed = 300.0 * delta;
-                match state.current_direction {
-                    Direction::Up => state.player_y -= speed,
-                    Direction::Down => state.player_y += speed,
-                    Direction::Left => state.player_x -= speed,
-                    Direction::Right => state.player_x += speed,
-                    Direction::None => {}
-                }
+/// Whether the next frame would differ from the last one drawn: the simulation or an animation is
+/// running, or a command changed the scene. Hosts rendering on demand request frames while this is
+/// true (check after game_update and after sending input or commands) and stop when it turns false
+#[no_mangle]
+pub extern "C" fn game_needs_redraw(handle: GameHandle) -> bool {
+    with_engine!(handle, false, |engine| engine.needs_redraw())
+}
 
-                // Clamp to bounds
-                state.player_x = state.player_x.clamp(half, state.width as f32 - half);
-                state.player_y = state.player_y.clamp(half, state.height as f32 - half);
-            }
-            GameMode::Auto => {
-                // Velocity-based movement
-                state.player_x += state.velocity_x * delta;
-                state.player_y += state.velocity_y * delta;
+/// Render for the vsync at `vsync_ns` (Choreographer frameTimeNanos, CADisplayLink timestamp in ns)
+/// Holds the game_set_target_fps cap and skips frames identical to the one on screen, to save power
+/// Returns true if a frame was drawn; on false the framebuffer is untouched and must not be presented
+#[no_mangle]
+pub extern "C" fn game_render_with_timestamp(handle: GameHandle, vsync_ns: u64) -> bool {
+    // Frames come from the simulation thread's snapshots while it runs
+    if simulation::is_running(handle as usize) {
+        return catch_panic!(false, simulation::draw(handle as usize).unwrap_or(false));
+    }
+    with_engine!(poisoned, handle, false, |engine| {
+        let drawn = if engine.is_poisoned() {
+            engine.refuse_call();
+            false
+        } else {
+            engine.render_at(vsync_ns)
+        };
+        #[cfg(feature = "frb")]
+        bridge::service(handle as usize, engine);
+        drawn
+    })
+}
 
-                // Bounce off walls and change color on each bounce
-                if state.player_x <= half || state.player_x >= state.width as f32 - half {
-                    state.velocity_x = -state.velocity_x;
-                    state.player_x = state.player_x.clamp(half, state.width as f32 - half);
-                    state.player_tint = random_color();
-                }
-                if state.player_y <= half || state.player_y >= state.height as f32 - half {
-                    state.velocity_y = -state.velocity_y;
-                    state.player_y = state.player_y.clamp(half, state.height as f32 - half);
-                    state.player_tint = random_color();
-                }
+/// Drive update and render from the display's vsync instead of per-frame host calls
+/// Call on the render thread, with the GL context current. On Android it needs a Looper on that thread
+/// (a HandlerThread owning the EGL context, not GLSurfaceView's GL thread): AChoreographer calls back
+/// each vsync and the engine presents to the current EGL surface itself. On iOS, register a CADisplayLink
+/// that calls game_loop_frame. Don't call game_update or game_render while the loop runs
+/// Returns false if it was already running or no Choreographer is available
+#[no_mangle]
+pub extern "C" fn game_start_loop(handle: GameHandle) -> bool {
+    catch_panic!(false, {
+        let Some(instance) = (unsafe { handle.as_ref() }) else {
+            return false;
+        };
+        if !instance.is_owner_thread() {
+            log::warn!("game_start_loop: must be called on the render thread");
+            return false;
+        }
+        match frame_loop::start(handle as usize, loop_frame) {
+            Ok(started) => started,
+            Err(e) => {
+                log::warn!("game_start_loop: {}", e);
+                false
             }
         }
     })
 }
 
-/// Render the game using egui
-/// Called from GLSurfaceView.onDrawFrame()
-/// Optimized: pre-computed colors, minimal allocations
+/// Stop the loop game_start_loop started; false if it wasn't running
 #[no_mangle]
-pub extern "C" fn game_render(handle: GameHandle) {
-    catch_panic!((), {
-        if handle.is_null() {
-            return;
-        }
-        let state = unsafe { &mut *handle };
+pub extern "C" fn game_stop_loop(handle: GameHandle) -> bool {
+    catch_panic!(false, frame_loop::stop(handle as usize))
+}
 
-        // Skip render if dimensions are zero
-        if state.width == 0 || state.height == 0 {
-            return;
-        }
+/// A loop frame for the display link vsync at `vsync_ns` (CADisplayLink targetTimestamp in ns): update,
+/// then render per game_render_with_timestamp. Returns true if a frame was drawn and should be presented;
+/// false when the loop isn't running
+#[no_mangle]
+pub extern "C" fn game_loop_frame(handle: GameHandle, vsync_ns: u64) -> bool {
+    frame_loop::is_running(handle as usize) && loop_frame(handle as usize, vsync_ns)
+}
 
-        // Clear background
-        unsafe {
-            state.gl.clear_color(0.1, 0.1, 0.15, 1.0);
-            state.gl.clear(glow::COLOR_BUFFER_BIT);
-        }
-
-        let screen_rect = Rect::from_min_size(
-            Pos2::ZERO,
-            Vec2::new(state.width as f32, state.height as f32),
-        );
-
-        // Pre-compute values outside closure to reduce allocations
-        let player_x = state.player_x;
-        let player_y = state.player_y;
-        let player_size = state.player_size;
-        let is_touched = state.is_player_touched;
-        let player_texture_id = state.player_texture.as_ref().map(|t| t.id());
-        let player_texture_size = state.player_texture_size;
-        let player_tint = state.player_tint;
-
-        // Run egui frame
-        let raw_input = egui::RawInput {
-            screen_rect: Some(screen_rect),
-            ..Default::default()
-        };
-
-        let full_output = state.egui_ctx.run(raw_input, |ctx| {
-            let painter = ctx.layer_painter(egui::LayerId::background());
-
-            let center = Pos2::new(player_x, player_y);
-
-            // Calculate render size maintaining aspect ratio
-            // Scale so the larger dimension fits within player_size
-            let (tex_w, tex_h) = player_texture_size;
-            let aspect = tex_w / tex_h;
-            let (render_w, render_h) = if aspect >= 1.0 {
-                // Wider than tall: width = player_size, height = player_size / aspect
-                (player_size, player_size / aspect)
-            } else {
-                // Taller than wide: height = player_size, width = player_size * aspect
-                (player_size * aspect, player_size)
-            };
-            let rect = Rect::from_center_size(center, Vec2::new(render_w, render_h));
-
-            // Draw player image or fallback to box
-            if let Some(tex_id) = player_texture_id {
-                // Apply tint: orange when dragging, otherwise player_tint (changes on bounce)
-                let tint = if is_touched {
-                    Color32::from_rgb(255, 150, 50) // Orange when dragging
-                } else {
-                    player_tint // Current color (changes on bounce)
-                };
-
-                painter.image(
-                    tex_id,
-                    rect,
-                    Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)), // UV coords
-                    tint,
-                );
-            } else {
-                // Fallback: draw colored box if texture failed to load
-                let fill_color = if is_touched {
-                    Color32::from_rgb(255, 150, 50)
-                } else {
-                    player_tint
-                };
-
-                painter.rect(
-                    rect,
-                    Rounding::same(8.0),
-                    fill_color,
-                    Stroke::new(2.0, Color32::WHITE),
-                );
-            }
-        });
+/// What the host would otherwise call each frame
+fn loop_frame(handle: usize, vsync_ns: u64) -> bool {
+    let handle = handle as GameHandle;
+    game_update(handle);
+    game_render_with_timestamp(handle, vsync_ns)
+}
 
-        // Tessellate and paint
-        let clipped_primitives = state.egui_ctx.tessellate(full_output.shapes, 1.0);
+/// Cap game_render_with_timestamp to `fps` frames per second: 30, 60 or 120 (0, the default,
+/// renders on every vsync). Frames land on the vsync grid, e.g. every other vsync for 30 on 60 Hz
+/// Returns false (and keeps the current cap) for other values
+#[no_mangle]
+pub extern "C" fn game_set_target_fps(handle: GameHandle, fps: u32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_target_fps(fps))
+}
 
-        state.egui_painter.paint_and_update_textures(
-            [state.width, state.height],
-            1.0,
-            &clipped_primitives,
-            &full_output.textures_delta,
-        );
+/// Frame pacing counters: cap, estimated refresh rate, frames drawn and vsyncs skipped
+/// Returns false for a null handle or output pointer
+#[no_mangle]
+pub extern "C" fn game_get_pacing_stats(handle: GameHandle, out: *mut PacingStats) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out.is_null() {
+            return false;
+        }
+        unsafe { *out = engine.pacing_stats() };
+        true
     })
 }
 
-/// Handle direction input from Flutter
-/// No logging in hot path for performance
+/// Report the device's thermal state (GAME_THERMAL_*: ProcessInfo.thermalState, or Android's
+/// THERMAL_STATUS_* mapped onto it). While power scaling is on, hotter states emit fewer particles,
+/// then also cap paced frames and skip post-processing. False for unknown states
 #[no_mangle]
-pub extern "C" fn game_set_direction(handle: GameHandle, direction: i32) {
-    catch_panic!((), {
-        if handle.is_null() {
-            return;
-        }
-        let state = unsafe { &mut *handle };
-        state.current_direction = Direction::from(direction);
+pub extern "C" fn game_set_thermal_state(handle: GameHandle, state: u32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(state) = ThermalState::from_raw(state) else {
+            log::warn!("game_set_thermal_state: unknown state {}", state);
+            return false;
+        };
+        engine.set_thermal_state(state);
+        true
     })
 }
 
-/// Set game mode (Manual=0, Auto=1)
+/// Report battery saver (Android) or Low Power Mode (iOS); it's treated like a fair thermal state
 #[no_mangle]
-pub extern "C" fn game_set_mode(handle: GameHandle, mode: i32) {
-    catch_panic!((), {
-        if handle.is_null() {
-            return;
-        }
-        let state = unsafe { &mut *handle };
+pub extern "C" fn game_set_low_power_mode(handle: GameHandle, enabled: bool) {
+    with_engine!(queued, handle, |engine| engine.set_low_power_mode(enabled))
+}
 
-        let new_mode = match mode {
-            1 => GameMode::Auto,
-            _ => GameMode::Manual,
-        };
+/// Turn off the engine's own scaling for heat and battery (on by default), e.g. when the host
+/// lowers its frame rate and effects itself; the reported states are kept for turning it back on
+#[no_mangle]
+pub extern "C" fn game_set_power_scaling(handle: GameHandle, enabled: bool) {
+    with_engine!(queued, handle, |engine| engine.set_power_scaling(enabled))
+}
+
+/// Do the first frame's one-time GPU work up front: upload the font atlas and player texture,
+/// compile egui's shaders and prime the tessellator, so the first game_render doesn't hitch
+/// Call on the render thread right after game_init; blocks briefly for the startup textures to decode
+/// Returns false for a headless instance or null handle
+#[no_mangle]
+pub extern "C" fn game_warmup(handle: GameHandle) -> bool {
+    with_engine!(handle, false, |engine| engine.warmup())
+}
 
-        // Initialize velocity when switching to auto mode
-        if new_mode == GameMode::Auto && state.game_mode != GameMode::Auto {
-            state.velocity_x = 250.0;
-            state.velocity_y = 200.0;
+/// Show or hide the on-screen debug overlay (FPS, entity count, draw calls, touches, last GL error)
+/// Returns false if this build has no overlay (enable the `debug-overlay` cargo feature)
+#[no_mangle]
+pub extern "C" fn game_set_debug_overlay(handle: GameHandle, enabled: bool) -> bool {
+    with_engine!(handle, false, |engine| {
+        let applied = engine.set_debug_overlay(enabled);
+        if !applied {
+            log::warn!("game_set_debug_overlay: built without the debug-overlay feature");
         }
+        applied
+    })
+}
 
-        state.game_mode = new_mode;
-        log::info!("Game mode set to {:?}", new_mode);
+/// Post-process presented frames with bloom, a vignette and color grading; NULL turns it all off
+/// Captures and share cards stay unprocessed. Returns false (settings unchanged) if a value is out of range
+#[no_mangle]
+pub extern "C" fn game_set_post_fx(handle: GameHandle, settings: *const PostFxSettings) -> bool {
+    with_engine!(handle, false, |engine| {
+        let settings = if settings.is_null() { PostFxSettings::default() } else { unsafe { *settings } };
+        engine.set_post_fx(settings)
     })
 }
 
-/// Handle touch events
-/// Optimized: no logging in hot path, minimal branching
+/// Current post-processing settings; false for a null handle or output pointer
 #[no_mangle]
-pub extern "C" fn game_touch(handle: GameHandle, x: f32, y: f32, action: i32) {
-    catch_panic!((), {
-        if handle.is_null() {
-            return;
+pub extern "C" fn game_get_post_fx(handle: GameHandle, out: *mut PostFxSettings) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out.is_null() {
+            return false;
         }
-        let state = unsafe { &mut *handle };
-        let touch_action = TouchAction::from(action);
-
-        // Check if touch is within player box
-        let half = state.player_size / 2.0;
-        let is_on_player = x >= state.player_x - half
-            && x <= state.player_x + half
-            && y >= state.player_y - half
-            && y <= state.player_y + half;
-
-        match touch_action {
-            TouchAction::Down => {
-                if is_on_player {
-                    state.is_player_touched = true;
-                    state.drag_offset_x = state.player_x - x;
-                    state.drag_offset_y = state.player_y - y;
-                }
-            }
-            TouchAction::Up => {
-                state.is_player_touched = false;
-            }
-            TouchAction::Move => {
-                if state.is_player_touched {
-                    state.player_x = x + state.drag_offset_x;
-                    state.player_y = y + state.drag_offset_y;
+        unsafe { *out = engine.post_fx() };
+        true
+    })
+}
 
-                    // Clamp to screen bounds
-                    state.player_x = state.player_x.clamp(half, state.width as f32 - half);
-                    state.player_y = state.player_y.clamp(half, state.height as f32 - half);
-                }
-            }
+/// Color grading table for game_set_post_fx's lut_intensity: a PNG of N tiles of N x N pixels
+/// side by side (red across each tile, green down, blue from tile to tile), N from 2 to 64
+/// NULL removes it. Returns false (LUT unchanged) if the PNG can't be decoded or has another shape
+#[no_mangle]
+pub extern "C" fn game_set_color_lut(handle: GameHandle, png: *const u8, len: usize) -> bool {
+    with_engine!(handle, false, |engine| {
+        let png = (!png.is_null()).then(|| unsafe { std::slice::from_raw_parts(png, len) });
+        engine.set_color_lut(png)
+    })
+}
+
+/// Wrap the next rendered frame in a KHR_debug group ("game_engine/game_trigger_gpu_capture")
+/// so it can be found in an Android GPU Inspector or RenderDoc capture; under RenderDoc the
+/// frame is also captured programmatically. AGI captures must still be started from AGI
+/// Returns true if RenderDoc will capture the frame, false otherwise (or for a headless instance)
+#[no_mangle]
+pub extern "C" fn game_trigger_gpu_capture(handle: GameHandle) -> bool {
+    with_engine!(handle, false, |engine| match engine.trigger_gpu_capture() {
+        Some(captured) => {
+            log::info!("GPU capture marker set for the next frame (RenderDoc: {})", captured);
+            captured
+        }
+        None => {
+            log::warn!("game_trigger_gpu_capture: no renderer");
+            false
         }
     })
 }
 
-/// Get player X position (for debugging/verification)
+/// End each frame with glFinish and glGetError (exact GPU times in frame stats, errors logged on the
+/// frame that made them), or with a fence the next frame waits on and an error check every 60 frames,
+/// which doesn't drain the GPU pipeline. On by default in debug builds, off in release
+/// Returns false for a headless instance or null handle
 #[no_mangle]
-pub extern "C" fn game_get_player_x(handle: GameHandle) -> f32 {
-    catch_panic!(0.0, {
-        if handle.is_null() {
-            return 0.0;
+pub extern "C" fn game_set_gpu_validation(handle: GameHandle, enabled: bool) -> bool {
+    with_engine!(handle, false, |engine| engine.set_gpu_validation(enabled))
+}
+
+/// Record gameplay: every rendered frame (without the debug overlay) goes to `callback` as RGBA8,
+/// top row first, a few frames after it was drawn (read back through a ring of `ring_size` GPU buffers;
+/// 0 picks the default). Called on the render thread inside game_render; copy the pixels before returning
+/// `user_data` must stay valid until game_stop_video_capture or game_destroy
+/// Replaces a running recording; false without a renderer or on GL older than ES 3
+#[no_mangle]
+pub extern "C" fn game_start_video_capture(
+    handle: GameHandle,
+    callback: Option<FrameCallback>,
+    user_data: *mut c_void,
+    ring_size: u32,
+) -> bool {
+    let Some(callback) = callback else {
+        log::warn!("game_start_video_capture: null callback");
+        return false;
+    };
+    with_engine!(handle, false, |engine| engine.start_video_capture(callback, user_data, ring_size as usize))
+}
+
+/// Stop recording; frames still in flight are delivered before this returns
+/// Call on the render thread
+#[no_mangle]
+pub extern "C" fn game_stop_video_capture(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.stop_video_capture())
+}
+
+/// Let the host draw into presented frames with its own GL calls (another native renderer sharing
+/// the context): `hook` is called on the render thread at `stage`, one of RENDER_STAGE_BEFORE_WORLD (after the
+/// clear), RENDER_STAGE_AFTER_WORLD (before the HUD) or RENDER_STAGE_AFTER_HUD (before post effects), with
+/// the engine's target bound. Framebuffer, viewport, scissor, blend, depth/cull/stencil tests, color mask,
+/// program, texture and buffer bindings are restored after it returns. NULL removes the stage's hook
+/// `user_data` must stay valid until the hook is replaced or game_destroy; false for an unknown stage
+#[no_mangle]
+pub extern "C" fn game_set_custom_render_hook(
+    handle: GameHandle,
+    hook: Option<RenderHook>,
+    user_data: *mut c_void,
+    stage: u32,
+) -> bool {
+    with_engine!(handle, false, |engine| engine.set_custom_render_hook(stage, hook, user_data))
+}
+
+/// Scheduling hints for the engine's internal threads (background tasks and any engine-driven loop),
+/// e.g. to keep them on the big cores: `affinity_mask` bit n allows CPU n (0 = any), `priority` is a
+/// nice value from -20 (most urgent) to 19 as in Process.setThreadPriority (0 = unchanged)
+/// iOS has no affinity; there the priority picks a QoS class for threads started afterwards
+/// Threads the host owns (the GL thread calling game_render) are left alone
+/// Returns false if a running thread couldn't take the hints
+#[no_mangle]
+pub extern "C" fn game_set_thread_hints(handle: GameHandle, affinity_mask: u64, priority: i32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_thread_hints(ThreadHints { affinity_mask, priority }))
+}
+
+/// Frame timing over the last ~120 frames (FPS, CPU/GPU time, p95 frame interval, GPU time per pass)
+/// Returns false for a null handle or output pointer
+#[no_mangle]
+pub extern "C" fn game_get_frame_stats(handle: GameHandle, out: *mut FrameStats) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out.is_null() {
+            return false;
         }
-        let state = unsafe { &*handle };
-        state.player_x
+        unsafe { *out = engine.frame_stats() };
+        true
     })
 }
 
-/// Get player Y position (for debugging/verification)
+/// Startup cost breakdown: renderer and engine creation, game_warmup, time to the first scene frame
+/// Returns false for a null handle or output pointer
 #[no_mangle]
-pub extern "C" fn game_get_player_y(handle: GameHandle) -> f32 {
-    catch_panic!(0.0, {
-        if handle.is_null() {
-            return 0.0;
+pub extern "C" fn game_get_init_timings(handle: GameHandle, out: *mut InitTimings) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out.is_null() {
+            return false;
         }
-        let state = unsafe { &*handle };
-        state.player_y
+        unsafe { *out = engine.init_timings() };
+        true
     })
 }
 
-/// Clean up resources
-/// Safe to call multiple times (idempotent)
+/// Counters for both event paths: outbound events (game_poll_event) and host input
+/// Counts published, delivered, dropped and coalesced events plus the worst delivery latency
+/// Returns false for a null handle or output pointer
 #[no_mangle]
-pub extern "C" fn game_destroy(handle: GameHandle) {
-    catch_panic!((), {
-        if handle.is_null() {
-            return;
+pub extern "C" fn game_get_event_stats(handle: GameHandle, out: *mut EventStats) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out.is_null() {
+            return false;
         }
-        let mut state = unsafe { Box::from_raw(handle) };
+        unsafe { *out = engine.event_stats() };
+        true
+    })
+}
 
-        // egui_painter cleanup
-        state.egui_painter.destroy();
+/// Capture a screen rect (x, y, w, h in points) scaled by `scale` as RGBA8 pixels, top row first
+/// The scene is re-rendered into a temporary framebuffer, so the crop stays sharp at any scale
+/// Output size is (round(w * scale), round(h * scale)); pass out = null to query the size
+/// Returns the number of bytes required, or -1 on failure; nothing is written if capacity is too small
+/// Must be called on the GL thread
+#[no_mangle]
+pub extern "C" fn game_capture_region(
+    handle: GameHandle,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    scale: f32,
+    out: *mut u8,
+    capacity: usize,
+) -> i64 {
+    with_engine!(handle, -1, |engine| {
+        let region = capture::region(x, y, w, h);
+        let Some((width, height)) = capture::output_size(region, scale) else {
+            log::warn!("game_capture_region: invalid size {}x{} at scale {}", w, h, scale);
+            return -1;
+        };
+        let required = width as usize * height as usize * 4;
+        if out.is_null() || capacity < required {
+            return required as i64;
+        }
+
+        match engine.capture_region(region, scale) {
+            Ok(capture) => {
+                debug_assert_eq!((capture.width, capture.height), (width, height));
+                unsafe { std::ptr::copy_nonoverlapping(capture.pixels.as_ptr(), out, required) };
+                required as i64
+            }
+            Err(e) => {
+                log::error!("game_capture_region failed: {}", e);
+                -1
+            }
+        }
+    })
+}
+
+/// Install host allocation functions for buffers returned by the engine (e.g. share cards)
+/// Must be called before the first buffer is returned; returns false otherwise or if either is null
+/// Without an allocator, buffers come from the engine's allocator and must go back via game_free_buffer
+#[no_mangle]
+pub extern "C" fn game_set_allocator(alloc: Option<buffer::AllocFn>, free: Option<buffer::FreeFn>) -> bool {
+    catch_panic!(false, {
+        let (Some(alloc), Some(free)) = (alloc, free) else {
+            return false;
+        };
+        let installed = buffer::set_allocator(alloc, free);
+        if !installed {
+            log::warn!("game_set_allocator: allocator already set or buffers already returned");
+        }
+        installed
+    })
+}
+
+/// Release a buffer returned by the engine (null buffers are ignored)
+/// Calls the host free function if one was installed
+#[no_mangle]
+pub extern "C" fn game_free_buffer(buffer: GameBuffer) {
+    catch_panic!((), {
+        unsafe { buffer::free_buffer(buffer) };
+    })
+}
+
+/// Compose a share card for `template_id` (SHARE_TEMPLATE_*) as PNG bytes
+/// The card shows the screen around the player, the run's score and the logo
+/// Returns an empty buffer on failure; release the result with game_free_buffer
+/// Must be called on the GL thread
+#[no_mangle]
+pub extern "C" fn game_render_share_card(handle: GameHandle, template_id: u32) -> GameBuffer {
+    with_engine!(handle, GameBuffer::empty(), |engine| {
+        match engine.render_share_card(template_id) {
+            Ok(png) => buffer::into_buffer(png),
+            Err(e) => {
+                log::error!("game_render_share_card failed: {}", e);
+                GameBuffer::empty()
+            }
+        }
+    })
+}
+
+/// Deprecated: game_set_player_direction with game_get_player_node
+/// Steers the primary player; warns once through the log
+#[no_mangle]
+pub extern "C" fn game_set_direction(handle: GameHandle, direction: i32) {
+    with_engine!(queued, handle, |engine| {
+        let player = engine.legacy_player(LegacyCall::SetDirection);
+        engine.set_player_direction(player, Direction::from(direction));
+    })
+}
+
+/// Handle direction input from Flutter for `player` (game_get_player_node) in Manual mode
+/// Returns false if the node isn't a player
+/// No logging in hot path for performance
+#[no_mangle]
+pub extern "C" fn game_set_player_direction(handle: GameHandle, player: NodeId, direction: i32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_player_direction(player, Direction::from(direction)))
+}
+
+/// Set game mode (Manual=0, Auto=1)
+#[no_mangle]
+pub extern "C" fn game_set_mode(handle: GameHandle, mode: i32) {
+    with_engine!(queued, handle, |engine| engine.set_mode(GameMode::from(mode)))
+}
+
+/// Go to game state `name`: "menu", "playing" (the state games start in), "paused", "game_over" or
+/// one added with game_add_state. Menu, paused and game over hold the world still, leaving touches to
+/// UI buttons. Hooks of the state left and entered run, then GAME_EVENT_STATE_CHANGED is reported
+/// False for unknown states and moves the current state doesn't allow
+#[no_mangle]
+pub extern "C" fn game_transition(handle: GameHandle, name: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| match unsafe { strings::read(name) } {
+        Ok(name) => engine.transition(name),
+        Err(e) => {
+            log::warn!("game_transition: {}", e);
+            false
+        }
+    })
+}
+
+/// Current game state (GAME_STATE_* or a game_add_state id)
+#[no_mangle]
+pub extern "C" fn game_get_state(handle: GameHandle) -> StateId {
+    with_engine!(handle, INVALID_STATE, |engine| engine.state())
+}
+
+/// Write state `state`'s name (NUL-terminated) into `out`
+/// Returns the name's length, or -1 for unknown states or a buffer too small
+#[no_mangle]
+pub extern "C" fn game_get_state_name(handle: GameHandle, state: StateId, out: *mut c_char, capacity: usize) -> i32 {
+    with_engine!(handle, -1, |engine| {
+        let Some(name) = engine.state_name(state) else {
+            return -1;
+        };
+        if out.is_null() || name.len() + 1 > capacity {
+            return -1;
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(name.as_ptr(), out.cast(), name.len());
+            *out.add(name.len()) = 0;
+        }
+        name.len() as i32
+    })
+}
+
+/// Add game state `name`, the world moving on in it if `simulates`; no transition leads to or from it
+/// until game_allow_transition. Returns its id, or GAME_INVALID_STATE for an empty or taken name
+#[no_mangle]
+pub extern "C" fn game_add_state(handle: GameHandle, name: *const c_char, simulates: bool) -> StateId {
+    with_engine!(handle, INVALID_STATE, |engine| match unsafe { strings::read(name) } {
+        Ok(name) => engine.add_state(name, simulates),
+        Err(e) => {
+            log::warn!("game_add_state: {}", e);
+            INVALID_STATE
+        }
+    })
+}
+
+/// Let the game go from state `from` to state `to`; false for unknown states
+#[no_mangle]
+pub extern "C" fn game_allow_transition(handle: GameHandle, from: StateId, to: StateId) -> bool {
+    with_engine!(handle, false, |engine| engine.allow_transition(from, to))
+}
+
+/// Call `hook` on the render thread as `state` is left (`entering` false) and entered, before the
+/// change event; NULL removes it. `user_data` must stay valid until the hook is replaced or
+/// game_destroy; false for an unknown state
+#[no_mangle]
+pub extern "C" fn game_set_state_hook(handle: GameHandle, state: StateId, hook: Option<StateHook>, user_data: *mut c_void) -> bool {
+    with_engine!(handle, false, |engine| engine.set_state_hook(state, hook, user_data))
+}
+
+/// Set the color the frame is cleared to before drawing (components 0.0..=1.0)
+#[no_mangle]
+pub extern "C" fn game_set_clear_color(handle: GameHandle, r: f32, g: f32, b: f32, a: f32) {
+    with_engine!(queued, handle, |engine| engine.set_clear_color([r, g, b, a]))
+}
+
+/// Cover the clear color with a vertical gradient (packed 0xRRGGBBAA, top to bottom)
+#[no_mangle]
+pub extern "C" fn game_set_background_gradient(handle: GameHandle, top_rgba: u32, bottom_rgba: u32) {
+    with_engine!(queued, handle, |engine| {
+        let top = sprites::color_from_rgba(top_rgba);
+        let bottom = sprites::color_from_rgba(bottom_rgba);
+        engine.set_background_gradient(Some((top, bottom)));
+    })
+}
+
+/// Remove the background gradient, leaving the flat clear color
+#[no_mangle]
+pub extern "C" fn game_clear_background_gradient(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.set_background_gradient(None))
+}
+
+/// Set the simulation tick rate in Hz (0 = variable timestep)
+/// A fixed rate makes movement and physics identical across devices
+#[no_mangle]
+pub extern "C" fn game_set_tick_rate(handle: GameHandle, hz: u32) {
+    with_engine!(queued, handle, |engine| engine.set_tick_rate(hz))
+}
+
+/// Run game logic on its own thread, stepping at the tick rate (60 Hz at a variable timestep), so slow
+/// ticks and slow frames don't hold each other up. game_render then draws the simulation's snapshots,
+/// interpolated a step behind, game_update does nothing, and every other call runs on the simulation
+/// thread between steps. The engine's own GL work (custom shaders, cached pictures, post effects, render
+/// hooks, captures, the debug overlay) waits until it stops
+/// Call on the render thread; returns whether the simulation thread runs afterwards
+#[no_mangle]
+pub extern "C" fn game_set_simulation_thread(handle: GameHandle, enabled: bool) -> bool {
+    catch_panic!(false, {
+        let Some(instance) = (unsafe { handle.as_ref() }) else {
+            return false;
+        };
+        if !enabled {
+            if let Some(renderer) = simulation::stop(handle as usize) {
+                with_engine!(handle, (), |engine| engine.restore_renderer(renderer));
+            }
+            return false;
+        }
+        if simulation::is_running(handle as usize) {
+            return true;
+        }
+        if !instance.is_owner_thread() {
+            log::warn!("game_set_simulation_thread called off the render thread");
+            return false;
+        }
+        let taken = with_engine!(handle, None, |engine| {
+            engine.take_renderer().map(|renderer| (renderer, engine.egui_context(), engine.tick_rate()))
+        });
+        let Some((renderer, egui_ctx, rate)) = taken else {
+            return false;
+        };
+        let spawn = |task: simulation::Task| {
+            with_engine!(handle, Err(std::io::Error::other("the engine refused the call")), |engine| {
+                engine.spawn_task("simulation", task)
+            })
+        };
+        match simulation::start(handle as usize, rate, renderer, egui_ctx, step_simulation, spawn) {
+            Ok(()) => true,
+            Err(renderer) => {
+                with_engine!(handle, (), |engine| engine.restore_renderer(renderer));
+                false
+            }
+        }
+    })
+}
+
+fn step_simulation(handle: usize, now: Instant) -> Option<simulation::Snapshot> {
+    with_engine!(handle as GameHandle, None, |engine| {
+        let snapshot = engine.step_simulation(now);
+        // game_render only draws snapshots meanwhile, so the Dart streams are fed from here
+        #[cfg(feature = "frb")]
+        bridge::service(handle, engine);
+        Some(snapshot)
+    })
+}
+
+/// Pop the next outbound event into `out`
+/// Returns false when the queue is empty
+/// Still works on a poisoned instance, to deliver EVENT_ENGINE_CRASHED
+#[no_mangle]
+pub extern "C" fn game_poll_event(handle: GameHandle, out: *mut GameEventRecord) -> bool {
+    with_engine!(poisoned, handle, false, |engine| {
+        if out.is_null() {
+            return false;
+        }
+        match engine.poll_event() {
+            Some(event) => {
+                unsafe { *out = event.to_record() };
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Engine state changed since the last poll, for hosts mirroring it in Dart
+/// Encoding: version byte, then tagged little-endian records (GAME_STATE_* in game_api.h)
+/// Returns an empty buffer when nothing changed; release the result with game_free_buffer
+#[no_mangle]
+pub extern "C" fn game_poll_state_diff(handle: GameHandle) -> GameBuffer {
+    with_engine!(handle, GameBuffer::empty(), |engine| {
+        let diff = engine.poll_state_diff();
+        if diff.is_empty() {
+            return GameBuffer::empty();
+        }
+        buffer::into_buffer(diff)
+    })
+}
+
+/// Make the next game_poll_state_diff carry every field (e.g. after the host rebuilt its mirror)
+#[no_mangle]
+pub extern "C" fn game_reset_state_diff(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.reset_state_diff())
+}
+
+/// Serialize player, mode, level, score and sprites so the host can persist them
+/// across process death; release the result with game_free_buffer
+#[no_mangle]
+pub extern "C" fn game_save_state(handle: GameHandle) -> GameBuffer {
+    with_engine!(handle, GameBuffer::empty(), |engine| buffer::into_buffer(engine.save_state()))
+}
+
+/// Resume from game_save_state bytes; sprites keep their ids
+/// Returns false (leaving the game untouched) for corrupt or incompatible data
+/// Textures, atlases, sounds and scene nodes are not saved; reload them as at startup
+/// Load the prefabs the save's sprites were spawned from first; it fails without them
+#[no_mangle]
+pub extern "C" fn game_restore_state(handle: GameHandle, data: *const u8, len: usize) -> bool {
+    with_engine!(handle, false, |engine| {
+        if data.is_null() {
+            return false;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        match engine.restore_state(bytes) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("game_restore_state: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Start recording host input (direction, mode, touch, controller, sensor, level starts)
+/// with tick indices, from a snapshot of the current state
+/// Returns false in variable timestep mode (tick rate 0) or while a replay is playing
+#[no_mangle]
+pub extern "C" fn game_start_recording(handle: GameHandle) -> bool {
+    with_engine!(handle, false, |engine| engine.start_recording())
+}
+
+/// Finish the recording started by game_start_recording and return it for game_start_replay
+/// Empty when no recording was running; release with game_free_buffer
+#[no_mangle]
+pub extern "C" fn game_stop_recording(handle: GameHandle) -> GameBuffer {
+    with_engine!(handle, GameBuffer::empty(), |engine| {
+        engine.stop_recording().map_or(GameBuffer::empty(), buffer::into_buffer)
+    })
+}
+
+/// Restore a recording's starting state and replay its input at its fixed tick rate
+/// Host input is ignored until GAME_EVENT_REPLAY_FINISHED or game_stop_replay
+/// Returns false (leaving the game untouched) for corrupt or incompatible data
+#[no_mangle]
+pub extern "C" fn game_start_replay(handle: GameHandle, data: *const u8, len: usize) -> bool {
+    with_engine!(handle, false, |engine| {
+        if data.is_null() {
+            return false;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        match engine.start_replay(bytes) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("game_start_replay: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Run the rest of the replay immediately instead of in real time (regression tests)
+#[no_mangle]
+pub extern "C" fn game_finish_replay(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.finish_replay())
+}
+
+/// Abandon a replay; the game continues from where it stopped
+#[no_mangle]
+pub extern "C" fn game_stop_replay(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.stop_replay())
+}
+
+#[no_mangle]
+pub extern "C" fn game_is_replaying(handle: GameHandle) -> bool {
+    with_engine!(handle, false, |engine| engine.is_replaying())
+}
+
+/// Configure the Auto mode physics world
+/// gravity in pixels/s^2, restitution 0..1 (1 = perfectly elastic; sets GAME_PARAM_RESTITUTION), damping per second
+/// Requires the `physics` feature; ignored otherwise
+#[no_mangle]
+pub extern "C" fn game_set_physics(handle: GameHandle, gravity_x: f32, gravity_y: f32, restitution: f32, damping: f32) {
+    with_engine!(queued, handle, |engine| {
+        if !engine.set_physics(Vec2::new(gravity_x, gravity_y), restitution, damping) {
+            log::warn!("game_set_physics ignored: physics was not negotiated or is not in this build");
+        }
+    })
+}
+
+/// Tune gameplay without rebuilding: `key` is a GAME_PARAM_* (player speed, Auto mode starting
+/// velocity, throw friction, wall restitution, player size), from the next tick on
+/// False for unknown keys and values outside the key's range (game_get_param gives the current one)
+#[no_mangle]
+pub extern "C" fn game_set_param(handle: GameHandle, key: u32, value: f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(param) = Param::from_raw(key) else {
+            log::warn!("game_set_param: unknown key {}", key);
+            return false;
+        };
+        engine.set_param(param, value)
+    })
+}
+
+/// Current value of a GAME_PARAM_* (its default until set); NaN for unknown keys
+#[no_mangle]
+pub extern "C" fn game_get_param(handle: GameHandle, key: u32) -> f32 {
+    with_engine!(handle, f32::NAN, |engine| Param::from_raw(key).map_or(f32::NAN, |param| engine.param(param)))
+}
+
+/// Current value of a GAME_STAT_* for scoreboards (bounces, distance traveled, time per mode,
+/// throws); NaN for unknown keys. Changes also arrive as GAME_EVENT_STAT_UPDATED
+#[no_mangle]
+pub extern "C" fn game_get_stat(handle: GameHandle, key: u32) -> f32 {
+    with_engine!(handle, f32::NAN, |engine| Stat::from_raw(key).map_or(f32::NAN, |stat| engine.stat(stat)))
+}
+
+/// Report stats that changed as GAME_EVENT_STAT_UPDATED every `interval_ms` of game time
+/// (e.g. 1000 for a scoreboard); 0, the default, stops the reports
+#[no_mangle]
+pub extern "C" fn game_set_stats_interval(handle: GameHandle, interval_ms: u32) {
+    with_engine!(queued, handle, |engine| engine.set_stats_interval(interval_ms))
+}
+
+/// Zero the stats (a new session); the current run's score is kept
+#[no_mangle]
+pub extern "C" fn game_reset_stats(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.reset_stats())
+}
+
+/// Set the input buffer window in milliseconds (0 disables buffering)
+/// Direction taps and near-miss grabs within the window are applied on the next tick
+#[no_mangle]
+pub extern "C" fn game_set_input_buffer_ms(handle: GameHandle, ms: u32) {
+    with_engine!(queued, handle, |engine| engine.set_input_buffer_ms(ms))
+}
+
+/// Start the daily challenge for a date (e.g. 20261016 for yyyymmdd)
+/// Everyone passing the same date_seed gets the same level and bounce colors
+#[no_mangle]
+pub extern "C" fn game_start_daily(handle: GameHandle, date_seed: u32) {
+    with_engine!(queued, handle, |engine| engine.start_daily(date_seed))
+}
+
+/// Write the active level's challenge code (NUL-terminated) into `out`
+/// Returns the code length, or -1 if no level is active or the buffer is too small
+#[no_mangle]
+pub extern "C" fn game_export_challenge_code(handle: GameHandle, out: *mut c_char, capacity: usize) -> i32 {
+    with_engine!(handle, -1, |engine| {
+        if out.is_null() {
+            return -1;
+        }
+        let Some(code) = engine.challenge_code() else {
+            return -1;
+        };
+        if code.len() + 1 > capacity {
+            return -1;
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(code.as_ptr(), out.cast(), code.len());
+            *out.add(code.len()) = 0;
+        }
+        code.len() as i32
+    })
+}
+
+/// Start the level described by a challenge code shared by another player
+/// Returns false if the code is malformed
+#[no_mangle]
+pub extern "C" fn game_import_challenge_code(handle: GameHandle, code: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        match unsafe { strings::read(code) } {
+            Ok(code) => engine.import_challenge_code(code),
+            Err(e) => {
+                log::warn!("game_import_challenge_code: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Spawn a sprite centered at (x, y) with size (w, h) and color 0xRRGGBBAA
+/// Returns the sprite id, or 0 on failure
+#[no_mangle]
+pub extern "C" fn game_spawn_sprite(handle: GameHandle, x: f32, y: f32, w: f32, h: f32, rgba: u32) -> SpriteId {
+    with_engine!(handle, INVALID_SPRITE, |engine| {
+        engine.spawn_sprite(Pos2::new(x, y), Vec2::new(w, h), sprites::color_from_rgba(rgba))
+    })
+}
+
+/// Add sprite prefabs from a JSON object of name to definition, replacing ones of the same name:
+/// {"spike": {"size": [32, 32], "color": "#ff4040", "velocity": [0, 0], "tags": ["hazard"],
+///            "shader": "glow", "collider": {"shape": "circle", "size": [24, 24], "offset": [0, 4]}}}
+/// Only size is required. Returns false (loading none) if any definition is invalid
+#[no_mangle]
+pub extern "C" fn game_load_prefabs(handle: GameHandle, json: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        let result = unsafe { strings::read(json) }.map_err(|e| e.to_string()).and_then(|json| engine.load_prefabs(json));
+        match result {
+            Ok(_) => true,
+            Err(e) => {
+                log::warn!("game_load_prefabs: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Spawn prefab `name` centered at (x, y); saves store it by name and only what's changed since
+/// Returns the sprite id, or 0 if there's no such prefab
+#[no_mangle]
+pub extern "C" fn game_spawn_prefab(handle: GameHandle, name: *const c_char, x: f32, y: f32) -> SpriteId {
+    with_engine!(handle, INVALID_SPRITE, |engine| {
+        match unsafe { strings::read(name) } {
+            Ok(name) => engine.spawn_prefab(name, Pos2::new(x, y)),
+            Err(e) => {
+                log::warn!("game_spawn_prefab: {}", e);
+                INVALID_SPRITE
+            }
+        }
+    })
+}
+
+/// Move a sprite to (x, y)
+/// Returns false if the id is unknown or already removed
+#[no_mangle]
+pub extern "C" fn game_set_sprite_position(handle: GameHandle, id: SpriteId, x: f32, y: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_sprite_position(id, Pos2::new(x, y)))
+}
+
+/// Set a sprite's velocity in pixels per second (integrated in game_update)
+#[no_mangle]
+pub extern "C" fn game_set_sprite_velocity(handle: GameHandle, id: SpriteId, vx: f32, vy: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_sprite_velocity(id, Vec2::new(vx, vy)))
+}
+
+/// Send a sprite to (x, y) along a path around level obstacles and solid tiles, moving at a steady
+/// speed like the player in Auto mode (its current speed, or 200 points per second if it's still)
+/// EVENT_PATH_FINISHED reports when it arrives or is interrupted (another path, a position or velocity
+/// set by the host, removal). Returns false for unknown sprites or if there's no way there
+#[no_mangle]
+pub extern "C" fn game_move_entity_to(handle: GameHandle, entity: SpriteId, x: f32, y: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.move_entity_to(entity, Pos2::new(x, y)))
+}
+
+/// Remove a sprite
+/// Returns false if the id is unknown or already removed
+#[no_mangle]
+pub extern "C" fn game_remove_sprite(handle: GameHandle, id: SpriteId) -> bool {
+    with_engine!(handle, false, |engine| engine.remove_sprite(id))
+}
+
+/// Number of live sprites
+#[no_mangle]
+pub extern "C" fn game_get_sprite_count(handle: GameHandle) -> u32 {
+    with_engine!(handle, 0, |engine| engine.sprite_count() as u32)
+}
+
+/// Start a particle emitter at (x, y): its burst is thrown at once, then `rate` particles per second
+/// for `duration` seconds (0 = until game_stop_emitter). Particles are drawn above the scene
+/// Returns the emitter id, or 0 if `config` is NULL or invalid or there are too many emitters
+#[no_mangle]
+pub extern "C" fn game_spawn_emitter(handle: GameHandle, x: f32, y: f32, config: *const EmitterConfig) -> EmitterId {
+    with_engine!(handle, INVALID_EMITTER, |engine| {
+        if config.is_null() {
+            return INVALID_EMITTER;
+        }
+        engine.spawn_emitter(Pos2::new(x, y), unsafe { *config })
+    })
+}
+
+/// Move a continuous emitter to (x, y), e.g. to follow something
+/// Returns false if the id is unknown or the emitter has finished
+#[no_mangle]
+pub extern "C" fn game_move_emitter(handle: GameHandle, id: EmitterId, x: f32, y: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.move_emitter(id, Pos2::new(x, y)))
+}
+
+/// Stop an emitter; particles it already threw live out their lifetime
+/// Returns false if the id is unknown or the emitter has finished
+#[no_mangle]
+pub extern "C" fn game_stop_emitter(handle: GameHandle, id: EmitterId) -> bool {
+    with_engine!(handle, false, |engine| engine.stop_emitter(id))
+}
+
+/// Spray `config`'s burst each time the player bounces in Auto mode, its angle relative to the surface
+/// normal (0 throws straight away from the wall); NULL turns it off. Returns false if `config` is invalid
+#[no_mangle]
+pub extern "C" fn game_set_bounce_particles(handle: GameHandle, config: *const EmitterConfig) -> bool {
+    with_engine!(handle, false, |engine| {
+        let config = (!config.is_null()).then(|| unsafe { *config });
+        engine.set_bounce_particles(config)
+    })
+}
+
+/// Number of live particles
+#[no_mangle]
+pub extern "C" fn game_get_particle_count(handle: GameHandle) -> u32 {
+    with_engine!(handle, 0, |engine| engine.particle_count() as u32)
+}
+
+/// Ease a sprite from where it is to (x, y) over `duration_ms`, with `easing` GAME_EASING_*
+/// Returns the tween id, reported by EVENT_TWEEN_FINISHED when it ends; 0 for unknown sprites or easings
+/// A running tween on the same property ends early (reported as interrupted)
+#[no_mangle]
+pub extern "C" fn game_animate_position(
+    handle: GameHandle,
+    sprite: SpriteId,
+    x: f32,
+    y: f32,
+    duration_ms: u32,
+    easing: u32,
+) -> TweenId {
+    let to = tween::Value::Vec2(Vec2::new(x, y));
+    animate(handle, tween::Target::SpritePosition(sprite), to, duration_ms, easing)
+}
+
+/// Ease a sprite's size to w x h (see game_animate_position)
+#[no_mangle]
+pub extern "C" fn game_animate_size(
+    handle: GameHandle,
+    sprite: SpriteId,
+    w: f32,
+    h: f32,
+    duration_ms: u32,
+    easing: u32,
+) -> TweenId {
+    let to = tween::Value::Vec2(Vec2::new(w, h));
+    animate(handle, tween::Target::SpriteSize(sprite), to, duration_ms, easing)
+}
+
+/// Ease a sprite's color to `rgba` (0xRRGGBBAA; see game_animate_position)
+#[no_mangle]
+pub extern "C" fn game_animate_color(handle: GameHandle, sprite: SpriteId, rgba: u32, duration_ms: u32, easing: u32) -> TweenId {
+    let to = tween::Value::Color(sprites::color_from_rgba(rgba));
+    animate(handle, tween::Target::SpriteColor(sprite), to, duration_ms, easing)
+}
+
+/// Ease a scene node's local transform to the one given (as in game_set_node_transform; see
+/// game_animate_position). The player node's translation stays driven by the game
+/// Player nodes are rejected unless dev mode is enabled
+#[no_mangle]
+pub extern "C" fn game_animate_node(
+    handle: GameHandle,
+    node: NodeId,
+    x: f32,
+    y: f32,
+    rotation: f32,
+    scale_x: f32,
+    scale_y: f32,
+    duration_ms: u32,
+    easing: u32,
+) -> TweenId {
+    let to = tween::Value::Transform(Transform {
+        translation: Vec2::new(x, y),
+        rotation,
+        scale: Vec2::new(scale_x, scale_y),
+    });
+    animate(handle, tween::Target::NodeTransform(node), to, duration_ms, easing)
+}
+
+fn animate(handle: GameHandle, target: tween::Target, to: tween::Value, duration_ms: u32, easing: u32) -> TweenId {
+    with_engine!(handle, INVALID_TWEEN, |engine| match Easing::from_raw(easing) {
+        Some(easing) => engine.animate(target, to, duration_ms as f32 / 1000.0, easing),
+        None => {
+            log::warn!("Unknown easing {}", easing);
+            INVALID_TWEEN
+        }
+    })
+}
+
+/// Stop a tween where it is; EVENT_TWEEN_FINISHED still follows (as interrupted)
+/// Returns false if it already ended
+#[no_mangle]
+pub extern "C" fn game_cancel_tween(handle: GameHandle, tween: TweenId) -> bool {
+    with_engine!(handle, false, |engine| engine.cancel_tween(tween))
+}
+
+/// Attach a collider to a sprite, centered on it plus (offset_x, offset_y)
+/// shape GAME_COLLIDER_BOX is `width` x `height`; GAME_COLLIDER_CIRCLE is `width` across
+/// Overlaps with other colliders are reported as EVENT_COLLISION_ENTER / EVENT_COLLISION_EXIT
+/// Returns the collider id, or 0 for unknown sprites or shapes, or bad sizes; removed with the sprite
+#[no_mangle]
+pub extern "C" fn game_add_sprite_collider(
+    handle: GameHandle,
+    sprite: SpriteId,
+    shape: u32,
+    width: f32,
+    height: f32,
+    offset_x: f32,
+    offset_y: f32,
+) -> ColliderId {
+    let offset = Vec2::new(offset_x, offset_y);
+    add_collider(handle, collision::Owner::Sprite(sprite), shape, width, height, offset)
+}
+
+/// Attach a collider to a scene node at its world origin plus (offset_x, offset_y); use
+/// game_get_player_node for the player (see game_add_sprite_collider)
+#[no_mangle]
+pub extern "C" fn game_add_node_collider(
+    handle: GameHandle,
+    node: NodeId,
+    shape: u32,
+    width: f32,
+    height: f32,
+    offset_x: f32,
+    offset_y: f32,
+) -> ColliderId {
+    let offset = Vec2::new(offset_x, offset_y);
+    add_collider(handle, collision::Owner::Node(node), shape, width, height, offset)
+}
+
+fn add_collider(handle: GameHandle, owner: collision::Owner, shape: u32, width: f32, height: f32, offset: Vec2) -> ColliderId {
+    with_engine!(handle, INVALID_COLLIDER, |engine| match collision::Shape::from_raw(shape, width, height) {
+        Some(shape) => engine.add_collider(owner, shape, offset),
+        None => {
+            log::warn!("Unknown collider shape {}", shape);
+            INVALID_COLLIDER
+        }
+    })
+}
+
+/// Remove a collider; EVENT_COLLISION_EXIT follows for each collider it was overlapping
+/// Returns false if it's unknown or its entity is gone
+#[no_mangle]
+pub extern "C" fn game_remove_collider(handle: GameHandle, collider: ColliderId) -> bool {
+    with_engine!(handle, false, |engine| engine.remove_collider(collider))
+}
+
+/// Whether two colliders overlapped at the end of the last tick
+#[no_mangle]
+pub extern "C" fn game_colliders_touching(handle: GameHandle, a: ColliderId, b: ColliderId) -> bool {
+    with_engine!(handle, false, |engine| engine.colliders_touching(a, b))
+}
+
+/// The topmost sprite or node whose collider covers screen point (x, y) (points, like
+/// game_screen_to_world), so host gesture handlers can tell touches meant for the game from the rest
+/// Writes its kind (GAME_COLLIDER_OWNER_*) to `out_kind` unless NULL
+/// Returns its id, or 0 if no collider is there
+#[no_mangle]
+pub extern "C" fn game_hit_test(handle: GameHandle, x: f32, y: f32, out_kind: *mut u32) -> u64 {
+    with_engine!(handle, 0, |engine| {
+        let Some((kind, id)) = engine.hit_test(Pos2::new(x, y)).map(collision::Owner::to_raw) else {
+            return 0;
+        };
+        if !out_kind.is_null() {
+            unsafe { *out_kind = kind };
+        }
+        id
+    })
+}
+
+/// Compile GLSL as shader `name` for game_set_sprite_material, replacing any shader of that name
+/// Sources skip the #version line and use ATTRIBUTE, VARYING, TEXTURE and FRAG_COLOR; the vertex
+/// shader gets `a_pos` (0 to 1 across the sprite), NULL `vert_src` passes it on as `v_uv`
+/// The fragment shader writes premultiplied color and may read u_color (the sprite's) and u_size (pixels)
+/// Returns false if compiling fails (the log has why) or a string is invalid
+#[no_mangle]
+pub extern "C" fn game_register_shader(
+    handle: GameHandle,
+    vert_src: *const c_char,
+    frag_src: *const c_char,
+    name: *const c_char,
+) -> bool {
+    with_engine!(handle, false, |engine| {
+        let vertex = if vert_src.is_null() { Ok(None) } else { unsafe { strings::read(vert_src) }.map(Some) };
+        let (vertex, fragment, name) = match (vertex, unsafe { strings::read(frag_src) }, unsafe { strings::read(name) }) {
+            (Ok(vertex), Ok(fragment), Ok(name)) => (vertex, fragment, name),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                log::warn!("game_register_shader: {}", e);
+                return false;
+            }
+        };
+        match engine.register_shader(name, vertex, fragment) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("game_register_shader '{}': {}", name, e);
+                false
+            }
+        }
+    })
+}
+
+/// Draw a sprite with a shader from game_register_shader (its uniforms start unset), or as a
+/// colored quad again with NULL; returns false if the sprite or shader is unknown
+#[no_mangle]
+pub extern "C" fn game_set_sprite_material(handle: GameHandle, id: SpriteId, shader: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        let shader = if shader.is_null() { None } else {
+            match unsafe { strings::read(shader) } {
+                Ok(shader) => Some(shader),
+                Err(e) => {
+                    log::warn!("game_set_sprite_material: {}", e);
+                    return false;
+                }
+            }
+        };
+        engine.set_sprite_material(id, shader)
+    })
+}
+
+/// Set uniform `name` (float, vec2, vec3 or vec4 for 1-4 `count` values) of a sprite's material
+/// Returns false if the sprite has no material, the name is u_color or u_size, or the values are invalid
+#[no_mangle]
+pub extern "C" fn game_set_sprite_uniform(
+    handle: GameHandle,
+    id: SpriteId,
+    name: *const c_char,
+    values: *const f32,
+    count: u32,
+) -> bool {
+    with_engine!(handle, false, |engine| {
+        let name = match unsafe { strings::read(name) } {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("game_set_sprite_uniform: {}", e);
+                return false;
+            }
+        };
+        if values.is_null() {
+            return false;
+        }
+        let values = unsafe { std::slice::from_raw_parts(values, count as usize) };
+        match engine.set_sprite_uniform(id, name, values) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("game_set_sprite_uniform: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Bit mask for a tag name ("enemy", "pickup", "ui"), registering the name on first use
+/// Combine masks with | to require several tags; returns 0 on failure or once 64 tags exist
+#[no_mangle]
+pub extern "C" fn game_tag_mask(handle: GameHandle, name: *const c_char) -> u64 {
+    with_engine!(handle, 0, |engine| {
+        let name = match unsafe { strings::read(name) } {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("game_tag_mask: {}", e);
+                return 0;
+            }
+        };
+        engine.tag_mask(name).unwrap_or_else(|| {
+            log::warn!("game_tag_mask: no tag bits left for '{}'", name);
+            0
+        })
+    })
+}
+
+/// Replace a sprite's tags (mask from game_tag_mask; 0 clears them)
+#[no_mangle]
+pub extern "C" fn game_set_sprite_tags(handle: GameHandle, id: SpriteId, tags: u64) -> bool {
+    with_engine!(handle, false, |engine| engine.set_sprite_tags(id, tags))
+}
+
+/// Replace a scene node's tags (mask from game_tag_mask; 0 clears them)
+#[no_mangle]
+pub extern "C" fn game_set_node_tags(handle: GameHandle, id: NodeId, tags: u64) -> bool {
+    with_engine!(handle, false, |engine| engine.set_node_tags(id, tags))
+}
+
+/// Number of sprites and nodes carrying every tag in `tags`
+#[no_mangle]
+pub extern "C" fn game_count_entities_with_tag(handle: GameHandle, tags: u64) -> u32 {
+    with_engine!(handle, 0, |engine| engine.count_tagged(tags) as u32)
+}
+
+/// Remove every sprite and node carrying every tag in `tags` (nodes take their children along)
+/// The player node is never removed; returns the number of tagged entities removed
+#[no_mangle]
+pub extern "C" fn game_despawn_group(handle: GameHandle, tags: u64) -> u32 {
+    with_engine!(handle, 0, |engine| engine.despawn_group(tags) as u32)
+}
+
+/// Challenge a dev-mode token must sign, as hex text (release with game_free_buffer)
+/// Fresh for every engine instance, so a token only unlocks the instance it was signed for
+#[no_mangle]
+pub extern "C" fn game_get_dev_challenge(handle: GameHandle) -> GameBuffer {
+    with_engine!(handle, GameBuffer::empty(), |engine| buffer::into_buffer(engine.dev_challenge().into_bytes()))
+}
+
+/// Enable dev mode with `token`: the hex ed25519 signature of "game_engine dev mode v1\0" followed by
+/// the raw game_get_dev_challenge bytes, made with the key whose public half is GAME_DEV_PUBLIC_KEY
+/// Debug builds start in dev mode; returns whether dev mode is now enabled
+#[no_mangle]
+pub extern "C" fn game_enable_dev_mode(handle: GameHandle, token: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        match unsafe { strings::read(token) } {
+            Ok(token) => engine.enable_dev_mode(token),
+            Err(e) => {
+                log::warn!("game_enable_dev_mode: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Debug: teleport the player (rejected unless dev mode is enabled)
+#[no_mangle]
+pub extern "C" fn game_debug_set_player_position(handle: GameHandle, x: f32, y: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.debug_set_player_position(Pos2::new(x, y)))
+}
+
+/// Write the current run summary (seed, RUN_FLAG_* bits) into `out`
+#[no_mangle]
+pub extern "C" fn game_get_run_summary(handle: GameHandle, out: *mut RunSummary) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out.is_null() {
+            return false;
+        }
+        unsafe { *out = engine.run_summary() };
+        true
+    })
+}
+
+/// Create a scene node at the root (w, h = 0 creates an empty group)
+/// Rect nodes draw a solid 0xRRGGBBAA quad centered on the node origin
+/// Returns the node id, or 0 on failure
+#[no_mangle]
+pub extern "C" fn game_create_node(handle: GameHandle, w: f32, h: f32, rgba: u32) -> NodeId {
+    with_engine!(handle, INVALID_NODE, |engine| {
+        engine.create_node(Vec2::new(w, h), sprites::color_from_rgba(rgba))
+    })
+}
+
+/// Create a scene node at the root drawing `text` (UTF-8, lines split at '\n') `size` points per em in
+/// 0xRRGGBBAA, shaped with the registered fonts (game_register_font). `align` (GAME_TEXT_ALIGN_* bits)
+/// picks the corner or edge of the text's block placed on the node origin; it moves, turns and scales
+/// with the node. Returns the node id, or 0 for a bad size, alignment or string
+#[no_mangle]
+pub extern "C" fn game_create_text_node(handle: GameHandle, text: *const c_char, size: f32, rgba: u32, align: u32) -> NodeId {
+    with_engine!(handle, INVALID_NODE, |engine| {
+        let Some(align) = text::align_from_raw(align) else {
+            log::warn!("game_create_text_node: unknown alignment {:#x}", align);
+            return INVALID_NODE;
+        };
+        let result = unsafe { strings::read(text) }
+            .map_err(|e| e.to_string())
+            .and_then(|text| engine.create_text_node(text, size, sprites::color_from_rgba(rgba), align));
+        match result {
+            Ok(node) => node,
+            Err(e) => {
+                log::warn!("game_create_text_node: {}", e);
+                INVALID_NODE
+            }
+        }
+    })
+}
+
+/// Replace a text node's text (UTF-8), e.g. to update a score; it's laid out again only when it changes
+/// Returns false if the node doesn't draw text or the string is invalid
+#[no_mangle]
+pub extern "C" fn game_set_node_text(handle: GameHandle, node: NodeId, text: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        match unsafe { strings::read(text) } {
+            Ok(text) => engine.set_node_text(node, text),
+            Err(e) => {
+                log::warn!("game_set_node_text: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Id of the player's scene node (attach children to it to build composite players)
+#[no_mangle]
+pub extern "C" fn game_get_player_node(handle: GameHandle) -> NodeId {
+    with_engine!(handle, INVALID_NODE, |engine| engine.player_node())
+}
+
+/// Add a player for local multiplayer at world point (x, y), drawn as the player image tinted `rgba`
+/// (0xRRGGBBAA). It moves in Manual mode like the primary player, steered by game_set_player_direction
+/// and game_touch_player until bound to a controller (game_bind_player_*)
+/// Returns its node id, or 0 once there are 7 besides the primary player
+#[no_mangle]
+pub extern "C" fn game_add_player(handle: GameHandle, x: f32, y: f32, rgba: u32) -> NodeId {
+    with_engine!(handle, INVALID_NODE, |engine| engine.add_player(Pos2::new(x, y), sprites::color_from_rgba(rgba)))
+}
+
+/// Remove a player added with game_add_player; false for other nodes
+#[no_mangle]
+pub extern "C" fn game_remove_player(handle: GameHandle, player: NodeId) -> bool {
+    with_engine!(handle, false, |engine| engine.remove_player(player))
+}
+
+/// Steer an added player with controller `pad` (see game_gamepad_event), which stops steering the
+/// primary player. False for other nodes, unknown pads and pads bound to another player
+#[no_mangle]
+pub extern "C" fn game_bind_player_gamepad(handle: GameHandle, player: NodeId, pad: u32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_player_controller(player, Controller::Gamepad(pad)))
+}
+
+/// Steer an added player with touches in a screen region (points from the surface's top left): it
+/// heads for the finger while one is down there, so each half of a tablet can steer its own player
+/// False for other nodes or a region that isn't a positive finite size
+#[no_mangle]
+pub extern "C" fn game_bind_player_touch_region(handle: GameHandle, player: NodeId, x: f32, y: f32, w: f32, h: f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let region = Rect::from_min_size(Pos2::new(x, y), Vec2::new(w, h));
+        if !(region.is_finite() && region.is_positive()) {
+            return false;
+        }
+        engine.set_player_controller(player, Controller::TouchRegion(region))
+    })
+}
+
+/// Leave an added player to game_set_player_direction and game_touch_player only, releasing its
+/// gamepad or touch region; false for other nodes
+#[no_mangle]
+pub extern "C" fn game_unbind_player(handle: GameHandle, player: NodeId) -> bool {
+    with_engine!(handle, false, |engine| engine.set_player_controller(player, Controller::Channel))
+}
+
+/// Tint a player 0xRRGGBBAA: an added one, or the primary one until its next bounce
+/// False for nodes that aren't players
+#[no_mangle]
+pub extern "C" fn game_set_player_color(handle: GameHandle, player: NodeId, rgba: u32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_player_color(player, sprites::color_from_rgba(rgba)))
+}
+
+/// Draw an added player with texture `name` (game_load_texture_*; drawn once loaded), or the built-in
+/// player image for null. False for other nodes or a bad string
+#[no_mangle]
+pub extern "C" fn game_set_player_texture(handle: GameHandle, player: NodeId, name: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        let name = if name.is_null() { None } else {
+            match unsafe { strings::read(name) } {
+                Ok(name) => Some(name),
+                Err(e) => {
+                    log::warn!("game_set_player_texture: {}", e);
+                    return false;
+                }
+            }
+        };
+        engine.set_player_texture(player, name)
+    })
+}
+
+/// Write a node's world position (the player node's is the player's center) to out_x/out_y
+/// Returns false for unknown nodes or null pointers
+#[no_mangle]
+pub extern "C" fn game_get_node_position(handle: GameHandle, node: NodeId, out_x: *mut f32, out_y: *mut f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out_x.is_null() || out_y.is_null() {
+            return false;
+        }
+        let Some(position) = engine.node_position(node) else {
+            return false;
+        };
+        unsafe {
+            *out_x = position.x;
+            *out_y = position.y;
+        }
+        true
+    })
+}
+
+/// Set a node's local transform (translation in pixels, rotation in radians, scale)
+/// The player node's translation is driven by the game and ignored here
+/// Player nodes are rejected unless dev mode is enabled
+#[no_mangle]
+pub extern "C" fn game_set_node_transform(
+    handle: GameHandle,
+    id: NodeId,
+    x: f32,
+    y: f32,
+    rotation: f32,
+    scale_x: f32,
+    scale_y: f32,
+) -> bool {
+    with_engine!(handle, false, |engine| {
+        let transform = Transform {
+            translation: Vec2::new(x, y),
+            rotation,
+            scale: Vec2::new(scale_x, scale_y),
+        };
+        engine.set_node_transform(id, transform)
+    })
+}
+
+/// Set a node's z-order (higher draws on top; ties keep tree order)
+#[no_mangle]
+pub extern "C" fn game_set_node_z(handle: GameHandle, id: NodeId, z: i32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_node_z(id, z))
+}
+
+/// Attach a node under a parent (its transform becomes relative to the parent)
+/// Returns false for unknown ids, cycles, or attaching the player under another node
+#[no_mangle]
+pub extern "C" fn game_attach_node(handle: GameHandle, child: NodeId, parent: NodeId) -> bool {
+    with_engine!(handle, false, |engine| {
+        if parent == INVALID_NODE {
+            return false;
+        }
+        engine.attach_node(child, Some(parent))
+    })
+}
+
+/// Detach a node from its parent, making it a root
+#[no_mangle]
+pub extern "C" fn game_detach_node(handle: GameHandle, id: NodeId) -> bool {
+    with_engine!(handle, false, |engine| engine.attach_node(id, None))
+}
+
+/// Destroy a node and its descendants (the player node cannot be destroyed)
+#[no_mangle]
+pub extern "C" fn game_destroy_node(handle: GameHandle, id: NodeId) -> bool {
+    with_engine!(handle, false, |engine| engine.destroy_node(id))
+}
+
+/// Replace the host draw list: rects, images, paths, vector art and text drawn every frame beneath the obstacles
+/// or above the scene (GAME_DRAW_* commands in game_api.h, see draw_list module)
+/// Submit a new list whenever the content changes; a list of just the version byte clears it
+/// Returns false (keeping the previous list) for malformed data
+#[no_mangle]
+pub extern "C" fn game_submit_draw_list(handle: GameHandle, data: *const u8, len: usize) -> bool {
+    with_engine!(handle, false, |engine| {
+        if data.is_null() {
+            return false;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        match engine.submit_draw_list(bytes) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("game_submit_draw_list: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Add a TrueType/OpenType font (copied) to the fallback chain for draw-list text and text nodes: the first
+/// registered is primary, later ones supply characters it lacks. Text is shaped (ligatures, Arabic joining,
+/// bidi) only once a font is registered, in lists submitted afterwards and in every text node. Returns false if the font can't be parsed
+#[no_mangle]
+pub extern "C" fn game_register_font(handle: GameHandle, data: *const u8, len: usize) -> bool {
+    with_engine!(handle, false, |engine| {
+        if data.is_null() {
+            return false;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        match engine.register_font(bytes) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("game_register_font: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Paragraph direction for draw-list text and text nodes (GAME_TEXT_DIRECTION_*): AUTO takes each line's
+/// from its first strong character; LTR and RTL force it, to match the host's Directionality so mixed-script
+/// text reads the way Flutter shows it. Returns false for unknown directions
+#[no_mangle]
+pub extern "C" fn game_set_text_direction(handle: GameHandle, direction: u32) -> bool {
+    with_engine!(handle, false, |engine| {
+        match text::TextDirection::from_raw(direction) {
+            Some(direction) => {
+                engine.set_text_direction(direction);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Free glyph atlas pages (draw-list text) the current list's text doesn't use, or every page if `keep_visible`
+/// is false, e.g. on a low-memory warning or before the GL context is torn down in the background. Text is
+/// rasterized again as it's next drawn. Returns how many pages were freed (0 for a null handle)
+#[no_mangle]
+pub extern "C" fn game_trim_glyph_atlas(handle: GameHandle, keep_visible: bool) -> u32 {
+    with_engine!(handle, 0, |engine| engine.trim_glyph_atlas(keep_visible) as u32)
+}
+
+/// Free memory the engine can rebuild (GAME_TRIM_MEMORY_*), from Android's onTrimMemory or an iOS
+/// memory warning: glyph pages, tessellated paths and, when backgrounded, cached pictures,
+/// post-processing targets and shaders. Call on the render thread; everything is made again lazily
+/// as the next frame needs it. False for unknown levels
+#[no_mangle]
+pub extern "C" fn game_trim_memory(handle: GameHandle, level: u32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(level) = TrimLevel::from_raw(level) else {
+            log::warn!("game_trim_memory: unknown level {}", level);
+            return false;
+        };
+        engine.trim_memory(level);
+        true
+    })
+}
+
+/// Glyph atlas occupancy: pages, cached glyphs, fraction filled, texture memory, rasterizations and evictions
+/// Returns false for a null handle or output pointer
+#[no_mangle]
+pub extern "C" fn game_get_glyph_atlas_stats(handle: GameHandle, out: *mut GlyphAtlasStats) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out.is_null() {
+            return false;
+        }
+        unsafe { *out = engine.glyph_atlas_stats() };
+        true
+    })
+}
+
+/// Drop the cached raster of draw-list picture `id` (GAME_DRAW_PICTURE), so it's redrawn from its commands
+/// on the next frame; needed when something the commands don't show changes, like a reloaded texture
+/// Returns false if the picture wasn't cached
+#[no_mangle]
+pub extern "C" fn game_invalidate_picture(handle: GameHandle, id: u32) -> bool {
+    with_engine!(handle, false, |engine| engine.invalidate_picture(id))
+}
+
+/// Load a PNG from memory into the asset cache under `name`, replacing any texture of that name
+/// SVG is rasterized at the scale factor (game_set_scale_factor) and redrawn when it changes
+/// Loading "player" reskins the player; "logo" is used on share cards
+#[no_mangle]
+pub extern "C" fn game_load_texture_from_bytes(
+    handle: GameHandle,
+    data: *const u8,
+    len: usize,
+    name: *const c_char,
+) -> bool {
+    with_engine!(handle, false, |engine| {
+        if data.is_null() {
+            return false;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        let name = match unsafe { strings::read(name) } {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("game_load_texture_from_bytes: {}", e);
+                return false;
+            }
+        };
+
+        match engine.load_texture_from_bytes(name, bytes) {
+            Ok(()) => true,
+            Err(e) => {
+                log::error!("Failed to load texture '{}': {}", name, e);
+                false
+            }
+        }
+    })
+}
+
+/// Load a PNG or SVG file into the asset cache, named after the file stem ("dl/player.png" -> "player")
+#[no_mangle]
+pub extern "C" fn game_load_texture_from_path(handle: GameHandle, path: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        let path = match unsafe { strings::read(path) } {
+            Ok(path) => path,
+            Err(e) => {
+                log::warn!("game_load_texture_from_path: {}", e);
+                return false;
+            }
+        };
+
+        match engine.load_texture_from_path(Path::new(path)) {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to load texture: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Decode a PNG or SVG on the background thread and add it to the asset cache as `name`
+/// The bytes are copied, so the caller may free them immediately
+/// Completion is reported by EVENT_TEXTURE_READY / EVENT_TEXTURE_FAILED with the returned load id
+/// Returns the load id, or 0 on failure
+#[no_mangle]
+pub extern "C" fn game_load_texture_async(handle: GameHandle, data: *const u8, len: usize, name: *const c_char) -> u64 {
+    with_engine!(handle, 0, |engine| {
+        if data.is_null() {
+            return 0;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) }.to_vec();
+        let name = match unsafe { strings::read(name) } {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("game_load_texture_async: {}", e);
+                return 0;
+            }
+        };
+
+        engine.load_texture_async(name, bytes).unwrap_or_else(|e| {
+            log::error!("game_load_texture_async: {}", e);
+            0
+        })
+    })
+}
+
+/// Read and decode a PNG or SVG file on the background thread (named after the file stem)
+/// Completion is reported like game_load_texture_async; returns the load id, or 0 on failure
+#[no_mangle]
+pub extern "C" fn game_load_texture_from_path_async(handle: GameHandle, path: *const c_char) -> u64 {
+    with_engine!(handle, 0, |engine| {
+        let path = match unsafe { strings::read(path) } {
+            Ok(path) => path,
+            Err(e) => {
+                log::warn!("game_load_texture_from_path_async: {}", e);
+                return 0;
+            }
+        };
+
+        engine.load_texture_from_path_async(Path::new(path)).unwrap_or_else(|e| {
+            log::error!("game_load_texture_from_path_async: {}", e);
+            0
+        })
+    })
+}
+
+/// Load a KTX2 texture as `name`, uploading ETC2 or ASTC blocks (and mip levels) as they are
+/// If the GPU can't sample its format (game_get_compressed_texture_formats), the PNG or SVG
+/// `fallback` bytes are loaded instead; fallback may be NULL for uncompressed KTX2
+#[no_mangle]
+pub extern "C" fn game_load_texture_ktx2(
+    handle: GameHandle,
+    data: *const u8,
+    len: usize,
+    fallback: *const u8,
+    fallback_len: usize,
+    name: *const c_char,
+) -> bool {
+    with_engine!(handle, false, |engine| {
+        if data.is_null() {
+            return false;
+        }
+        let name = match unsafe { strings::read(name) } {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("game_load_texture_ktx2: {}", e);
+                return false;
+            }
+        };
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        let fallback = (!fallback.is_null()).then(|| unsafe { std::slice::from_raw_parts(fallback, fallback_len) });
+        match engine.load_texture_ktx2(name, bytes, fallback) {
+            Ok(()) => true,
+            Err(e) => {
+                log::error!("Failed to load texture '{}': {}", name, e);
+                false
+            }
+        }
+    })
+}
+
+/// Compressed texture formats the GPU can sample: GAME_TEXTURE_FORMAT_* bits (0 before the
+/// renderer exists)
+#[no_mangle]
+pub extern "C" fn game_get_compressed_texture_formats(handle: GameHandle) -> u32 {
+    with_engine!(handle, 0, |engine| engine.compressed_texture_formats())
+}
+
+/// Generate mipmaps for PNG and SVG textures loaded from now on (off by default), so textures
+/// drawn well below their size don't shimmer; costs a third more texture memory
+#[no_mangle]
+pub extern "C" fn game_set_texture_mipmaps(handle: GameHandle, enabled: bool) {
+    with_engine!(queued, handle, |engine| engine.set_texture_mipmaps(enabled))
+}
+
+/// Load a sprite sheet from atlas JSON (TexturePacker hash/array or Aseprite) and PNG bytes
+/// Numbered frames ("run_01.png", ...) form animations by name stem; Aseprite frameTags win
+/// Returns the atlas id, or 0 on failure
+#[no_mangle]
+pub extern "C" fn game_load_atlas(handle: GameHandle, json: *const c_char, png: *const u8, png_len: usize) -> AtlasId {
+    with_engine!(handle, INVALID_ATLAS, |engine| {
+        if png.is_null() {
+            return INVALID_ATLAS;
+        }
+        let json = match unsafe { strings::read(json) } {
+            Ok(json) => json,
+            Err(e) => {
+                log::warn!("game_load_atlas: {}", e);
+                return INVALID_ATLAS;
+            }
+        };
+        let png = unsafe { std::slice::from_raw_parts(png, png_len) };
+        engine.load_atlas(json, png)
+    })
+}
+
+/// Load an animated GIF, WebP or APNG as an atlas with one animation called `name`, played with
+/// game_set_node_animation (fps 0 keeps each frame's own delay). Returns the atlas id, or
+/// INVALID_ATLAS if it can't be decoded or doesn't fit a sprite sheet
+#[no_mangle]
+pub extern "C" fn game_load_animated_texture(handle: GameHandle, data: *const u8, len: usize, name: *const c_char) -> AtlasId {
+    with_engine!(handle, INVALID_ATLAS, |engine| {
+        if data.is_null() {
+            return INVALID_ATLAS;
+        }
+        let name = match unsafe { strings::read(name) } {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("game_load_animated_texture: {}", e);
+                return INVALID_ATLAS;
+            }
+        };
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        engine.load_animated_texture(name, bytes)
+    })
+}
+
+/// Play an atlas animation (or a single named frame) on a node, including the player node
+/// fps > 0 overrides per-frame durations; mode: 0=loop, 1=once, 2=ping-pong
+/// Once animations report EVENT_ANIMATION_FINISHED; name = null clears the animation
+#[no_mangle]
+pub extern "C" fn game_set_node_animation(
+    handle: GameHandle,
+    node: NodeId,
+    atlas: AtlasId,
+    name: *const c_char,
+    fps: f32,
+    mode: i32,
+) -> bool {
+    with_engine!(handle, false, |engine| {
+        let name = if name.is_null() {
+            None
+        } else {
+            match unsafe { strings::read(name) } {
+                Ok(name) => Some(name),
+                Err(e) => {
+                    log::warn!("game_set_node_animation: {}", e);
+                    return false;
+                }
+            }
+        };
+        engine.set_node_animation(node, atlas, name, fps, LoopMode::from(mode))
+    })
+}
+
+/// Load a Tiled (TMX or JSON) or LDtk map, its top left at world point (x, y), one map pixel = scale
+/// points. Tilesets are drawn from textures named after their image's file stem; layers with a
+/// "collision" property or name, and LDtk IntGrid layers, block the player
+/// Returns the tilemap id, or INVALID_TILEMAP if the map can't be read
+#[no_mangle]
+pub extern "C" fn game_load_tilemap(handle: GameHandle, data: *const u8, len: usize, x: f32, y: f32, scale: f32) -> TilemapId {
+    with_engine!(handle, INVALID_TILEMAP, |engine| {
+        if data.is_null() {
+            return INVALID_TILEMAP;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        engine.load_tilemap(bytes, Pos2::new(x, y), scale)
+    })
+}
+
+/// Remove a tilemap; returns false for unknown ids
+#[no_mangle]
+pub extern "C" fn game_remove_tilemap(handle: GameHandle, map: TilemapId) -> bool {
+    with_engine!(handle, false, |engine| engine.remove_tilemap(map))
+}
+
+/// Show or hide a tilemap's layers named `name`; hidden collision layers still collide
+/// Returns false for unknown maps or layer names
+#[no_mangle]
+pub extern "C" fn game_set_tilemap_layer_visible(handle: GameHandle, map: TilemapId, name: *const c_char, visible: bool) -> bool {
+    with_engine!(handle, false, |engine| {
+        match unsafe { strings::read(name) } {
+            Ok(name) => engine.set_tilemap_layer_visible(map, name, visible),
+            Err(e) => {
+                log::warn!("game_set_tilemap_layer_visible: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Whether any tilemap has a solid collision cell at world point (x, y)
+#[no_mangle]
+pub extern "C" fn game_tilemap_is_solid(handle: GameHandle, x: f32, y: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.is_tile_solid(Pos2::new(x, y)))
+}
+
+/// Show a QR code (e.g. a challenge code) centered above the scene
+/// size = edge length in pixels including the quiet zone; ec_level: 0=L, 1=M, 2=Q, 3=H
+/// quiet_zone = light border in modules (4 recommended)
+/// Returns false if the data doesn't fit a QR code at that level
+#[no_mangle]
+pub extern "C" fn game_show_qr(
+    handle: GameHandle,
+    data: *const c_char,
+    size: f32,
+    ec_level: i32,
+    quiet_zone: u32,
+) -> bool {
+    with_engine!(handle, false, |engine| {
+        if data.is_null() {
+            return false;
+        }
+        let data = match unsafe { strings::read(data) } {
+            Ok(data) => data.as_bytes(),
+            Err(e) => {
+                log::warn!("game_show_qr: {}", e);
+                return false;
+            }
+        };
+        let shown = engine.show_qr(data, size, qr::EcLevel::from(ec_level), quiet_zone);
+        if !shown {
+            log::warn!("game_show_qr: {} bytes don't fit a QR code", data.len());
+        }
+        shown
+    })
+}
+
+/// Hide the QR code shown by game_show_qr
+#[no_mangle]
+pub extern "C" fn game_hide_qr(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.hide_qr())
+}
+
+/// Add a UI label drawn above the game: `text` (UTF-8, lines split at '\n') `size` points per em in
+/// 0xRRGGBBAA. `anchor` (GAME_TEXT_ALIGN_* bits) picks the screen corner, edge or middle it's placed at
+/// and the same point of the text's block; (x, y) moves it that many points in from those edges
+/// (right and down from a middle). Returns the widget id, or 0 for a bad size, anchor or string
+#[no_mangle]
+pub extern "C" fn game_ui_create_label(
+    handle: GameHandle,
+    text: *const c_char,
+    size: f32,
+    rgba: u32,
+    anchor: u32,
+    x: f32,
+    y: f32,
+) -> WidgetId {
+    with_engine!(handle, INVALID_WIDGET, |engine| {
+        let Some(anchor) = text::align_from_raw(anchor) else {
+            log::warn!("game_ui_create_label: unknown anchor {:#x}", anchor);
+            return INVALID_WIDGET;
+        };
+        let result = unsafe { strings::read(text) }
+            .map_err(|e| e.to_string())
+            .and_then(|text| engine.create_ui_label(text, size, sprites::color_from_rgba(rgba), anchor, Vec2::new(x, y)));
+        match result {
+            Ok(widget) => widget,
+            Err(e) => {
+                log::warn!("game_ui_create_label: {}", e);
+                INVALID_WIDGET
+            }
+        }
+    })
+}
+
+/// Add a UI button of width x height points (0 fits the text) filled with `fill_rgba`, its `text`
+/// drawn `size` points per em in `text_rgba`; anchored like game_ui_create_label
+/// Taps arrive as GAME_EVENT_BUTTON_PRESSED with the widget id; touches that go down on a button
+/// don't reach the game. Returns the widget id, or 0 for a bad size, anchor or string
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub extern "C" fn game_ui_create_button(
+    handle: GameHandle,
+    text: *const c_char,
+    size: f32,
+    text_rgba: u32,
+    fill_rgba: u32,
+    anchor: u32,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+) -> WidgetId {
+    with_engine!(handle, INVALID_WIDGET, |engine| {
+        let Some(anchor) = text::align_from_raw(anchor) else {
+            log::warn!("game_ui_create_button: unknown anchor {:#x}", anchor);
+            return INVALID_WIDGET;
+        };
+        let result = unsafe { strings::read(text) }.map_err(|e| e.to_string()).and_then(|text| {
+            engine.create_ui_button(
+                text,
+                size,
+                sprites::color_from_rgba(text_rgba),
+                sprites::color_from_rgba(fill_rgba),
+                anchor,
+                Vec2::new(x, y),
+                Vec2::new(width, height),
+            )
+        });
+        match result {
+            Ok(widget) => widget,
+            Err(e) => {
+                log::warn!("game_ui_create_button: {}", e);
+                INVALID_WIDGET
+            }
+        }
+    })
+}
+
+/// Add an empty UI progress bar of width x height points in 0xRRGGBBAA (its track is a faded copy);
+/// anchored like game_ui_create_label. Returns the widget id, or 0 for a bad size or anchor
+#[no_mangle]
+pub extern "C" fn game_ui_create_progress_bar(
+    handle: GameHandle,
+    rgba: u32,
+    anchor: u32,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+) -> WidgetId {
+    with_engine!(handle, INVALID_WIDGET, |engine| {
+        let Some(anchor) = text::align_from_raw(anchor) else {
+            log::warn!("game_ui_create_progress_bar: unknown anchor {:#x}", anchor);
+            return INVALID_WIDGET;
+        };
+        let color = sprites::color_from_rgba(rgba);
+        match engine.create_ui_progress_bar(color, anchor, Vec2::new(x, y), Vec2::new(width, height)) {
+            Ok(widget) => widget,
+            Err(e) => {
+                log::warn!("game_ui_create_progress_bar: {}", e);
+                INVALID_WIDGET
+            }
+        }
+    })
+}
+
+/// Replace a UI label's or button's text (UTF-8); false for other widgets or an invalid string
+#[no_mangle]
+pub extern "C" fn game_ui_set_text(handle: GameHandle, widget: WidgetId, text: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        match unsafe { strings::read(text) } {
+            Ok(text) => engine.set_ui_text(widget, text),
+            Err(e) => {
+                log::warn!("game_ui_set_text: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Fill a UI progress bar `value` of the way (clamped to 0..1); false for other widgets
+#[no_mangle]
+pub extern "C" fn game_ui_set_progress(handle: GameHandle, widget: WidgetId, value: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_ui_progress(widget, value))
+}
+
+/// Show or hide a UI widget; hidden buttons take no touches. False if the id is unknown
+#[no_mangle]
+pub extern "C" fn game_ui_set_visible(handle: GameHandle, widget: WidgetId, visible: bool) -> bool {
+    with_engine!(handle, false, |engine| engine.set_ui_visible(widget, visible))
+}
+
+/// Remove a UI widget; false if the id is unknown
+#[no_mangle]
+pub extern "C" fn game_ui_remove(handle: GameHandle, widget: WidgetId) -> bool {
+    with_engine!(handle, false, |engine| engine.remove_ui_widget(widget))
+}
+
+/// Deprecated: game_touch_player with game_get_player_node
+/// Touches for the primary player; warns once through the log
+#[no_mangle]
+pub extern "C" fn game_touch(handle: GameHandle, x: f32, y: f32, action: i32) {
+    with_engine!(queued, handle, |engine| {
+        let player = engine.legacy_player(LegacyCall::Touch);
+        engine.touch_player(player, x, y, TouchAction::from(action));
+    })
+}
+
+/// Handle touch events (x, y in physical pixels); touching `player` (game_get_player_node) grabs it,
+/// and an added player (game_add_player) heads for the finger. Returns false if the node isn't a player
+/// Optimized: no logging in hot path, minimal branching
+#[no_mangle]
+pub extern "C" fn game_touch_player(handle: GameHandle, player: NodeId, x: f32, y: f32, action: i32) -> bool {
+    with_engine!(handle, false, |engine| engine.touch_player(player, x, y, TouchAction::from(action)))
+}
+
+/// Which touch gestures the engine claims (GAME_TOUCH_ROUTING_*), from the next touch down on: all
+/// of them (the default), or only those going down on a UI button, the player or an entity with a
+/// collider. Gestures it doesn't claim are ignored, for the host to give to its own handlers (e.g. a
+/// scrollable around the game view). False for unknown policies
+#[no_mangle]
+pub extern "C" fn game_set_touch_routing(handle: GameHandle, policy: u32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(policy) = TouchRouting::from_raw(policy) else {
+            log::warn!("game_set_touch_routing: unknown policy {}", policy);
+            return false;
+        };
+        engine.set_touch_routing(policy);
+        true
+    })
+}
+
+/// Whether the engine claimed the gesture the last game_touch_player belongs to; call after each
+/// touch down to decide whether the host's gesture recognizers should yield
+#[no_mangle]
+pub extern "C" fn game_touch_claimed(handle: GameHandle) -> bool {
+    with_engine!(handle, false, |engine| engine.touch_claimed())
+}
+
+/// Letting go of the player mid-drag throws it at the finger's speed; `friction` is how quickly it
+/// slows down (per second, exponentially: 4, the default, leaves 2% of the speed after a second)
+/// 0 drops released players where they are. Returns false for negative or non-finite values
+#[no_mangle]
+pub extern "C" fn game_set_throw_friction(handle: GameHandle, friction: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.set_throw_friction(friction))
+}
+
+/// Handle a key event from a hardware keyboard or forwarded by Flutter
+/// keycode = USB HID usage (PhysicalKeyboardKey.usbHidUsage); action: 0=down, 1=up, 2=repeat
+/// modifiers = GAME_KEY_MOD_* bits; returns false if the key is not recognized
+#[no_mangle]
+pub extern "C" fn game_key_event(handle: GameHandle, keycode: u32, action: i32, modifiers: u32) -> bool {
+    with_engine!(handle, false, |engine| {
+        engine.key_event(keycode, KeyAction::from(action), KeyModifiers::from_bits_truncate(modifiers))
+    })
+}
+
+/// Handle committed text input (UTF-8), e.g. from the soft keyboard or an IME
+/// Delivered to egui text fields on the next frame
+#[no_mangle]
+pub extern "C" fn game_text_input(handle: GameHandle, utf8: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        match unsafe { strings::read(utf8) } {
+            Ok(text) => {
+                engine.text_input(text);
+                true
+            }
+            Err(e) => {
+                log::warn!("game_text_input: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Handle a game controller button or axis (pad_id 0..3)
+/// control = GAME_PAD_* value; buttons: value > 0.5 = pressed; sticks -1..1 (+y down); triggers 0..1
+/// The left stick and d-pad move the player in Manual mode; returns false for unknown pads or controls
+#[no_mangle]
+pub extern "C" fn game_gamepad_event(handle: GameHandle, pad_id: u32, control: i32, value: f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(control) = Control::from_raw(control) else {
+            return false;
+        };
+        engine.gamepad_event(pad_id, control, value)
+    })
+}
+
+/// Forget a disconnected controller so a held stick stops steering
+#[no_mangle]
+pub extern "C" fn game_gamepad_disconnected(handle: GameHandle, pad_id: u32) {
+    with_engine!(queued, handle, |engine| engine.gamepad_disconnected(pad_id))
+}
+
+/// Set the analog stick dead zone (fraction of full deflection, default 0.2)
+#[no_mangle]
+pub extern "C" fn game_set_gamepad_dead_zone(handle: GameHandle, dead_zone: f32) {
+    with_engine!(queued, handle, |engine| engine.set_gamepad_dead_zone(dead_zone))
+}
+
+/// Forward a motion sensor sample (sensor_type: 0=accelerometer m/s^2, 1=gyroscope rad/s)
+/// Axes follow Android's device frame (x right, y up, z out of the screen; flat face-up reads +z)
+/// timestamp in nanoseconds (SensorEvent.timestamp); returns false for unknown sensor types
+#[no_mangle]
+pub extern "C" fn game_sensor_event(handle: GameHandle, sensor_type: i32, x: f32, y: f32, z: f32, timestamp: u64) -> bool {
+    with_engine!(handle, false, |engine| {
+        let Some(sensor) = SensorType::from_raw(sensor_type) else {
+            return false;
+        };
+        engine.sensor_event(sensor, [x, y, z], timestamp);
+        true
+    })
+}
+
+/// Write the fused device tilt (direction a ball would roll, x right, y down, length 0..1)
+/// Returns false for a null handle or output pointer
+#[no_mangle]
+pub extern "C" fn game_get_tilt(handle: GameHandle, out_x: *mut f32, out_y: *mut f32) -> bool {
+    with_engine!(handle, false, |engine| {
+        if out_x.is_null() || out_y.is_null() {
+            return false;
+        }
+        let tilt = engine.tilt();
+        unsafe {
+            *out_x = tilt.x;
+            *out_y = tilt.y;
+        }
+        true
+    })
+}
+
+/// Let device tilt accelerate the player in Auto mode (px/s^2 at full tilt, 0 = off)
+#[no_mangle]
+pub extern "C" fn game_set_tilt_gravity(handle: GameHandle, strength: f32) {
+    with_engine!(queued, handle, |engine| engine.set_tilt_gravity(strength))
+}
+
+/// Queue haptic feedback; the host receives it as an EVENT_HAPTIC event after the next tick
+/// Returns false for unknown patterns or while haptics are disabled or not negotiated
+#[no_mangle]
+pub extern "C" fn game_request_haptic(handle: GameHandle, pattern: u32) -> bool {
+    with_engine!(handle, false, |engine| match HapticPattern::from_raw(pattern) {
+        Some(pattern) => engine.request_haptic(pattern),
+        None => false,
+    })
+}
+
+/// Turn haptic events on or off (e.g. a user setting)
+/// Returns false if HAPTICS was not negotiated
+#[no_mangle]
+pub extern "C" fn game_set_haptics_enabled(handle: GameHandle, enabled: bool) -> bool {
+    with_engine!(handle, false, |engine| engine.set_haptics_enabled(enabled))
+}
+
+/// Decode a WAV file (PCM 8/16-bit or float, mono or stereo) for playback
+/// Returns the sound id, or INVALID_SOUND if the data is invalid or AUDIO was not negotiated
+#[no_mangle]
+pub extern "C" fn game_load_sound(handle: GameHandle, data: *const u8, len: usize) -> SoundId {
+    with_engine!(handle, INVALID_SOUND, |engine| {
+        if data.is_null() {
+            return INVALID_SOUND;
+        }
+        let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+        match engine.load_sound(bytes) {
+            Ok(id) => id,
+            Err(e) => {
+                log::error!("Failed to load sound: {}", e);
+                INVALID_SOUND
+            }
+        }
+    })
+}
+
+/// Free a loaded sound; returns false for unknown ids
+#[no_mangle]
+pub extern "C" fn game_unload_sound(handle: GameHandle, id: SoundId) -> bool {
+    with_engine!(handle, false, |engine| engine.unload_sound(id))
+}
+
+/// Play a sound effect once at `volume` (0..1); returns false for unknown ids
+#[no_mangle]
+pub extern "C" fn game_play_sound(handle: GameHandle, id: SoundId, volume: f32) -> bool {
+    with_engine!(handle, false, |engine| engine.play_sound(id, volume))
+}
+
+/// Loop a sound as background music, replacing the current track
+#[no_mangle]
+pub extern "C" fn game_play_music(handle: GameHandle, id: SoundId) -> bool {
+    with_engine!(handle, false, |engine| engine.play_music(id))
+}
+
+#[no_mangle]
+pub extern "C" fn game_stop_music(handle: GameHandle) {
+    with_engine!(queued, handle, |engine| engine.stop_music())
+}
+
+/// Set a volume (0..1) for GAME_VOLUME_MASTER, _EFFECTS or _MUSIC
+/// Returns false for unknown channels
+#[no_mangle]
+pub extern "C" fn game_set_volume(handle: GameHandle, channel: u32, volume: f32) -> bool {
+    with_engine!(handle, false, |engine| match Channel::from_raw(channel) {
+        Some(channel) => {
+            engine.set_volume(channel, volume);
+            true
+        }
+        None => false,
+    })
+}
+
+/// Sound played on every bounce, louder for harder impacts (INVALID_SOUND = none)
+#[no_mangle]
+pub extern "C" fn game_set_bounce_sound(handle: GameHandle, id: SoundId) -> bool {
+    with_engine!(handle, false, |engine| engine.set_bounce_sound(id))
+}
+
+/// Pick the colors used for bounce tints, the drag highlight and status text
+/// GAME_PALETTE_STANDARD, _DEUTERANOPIA, _PROTANOPIA or _TRITANOPIA; false for unknown values
+#[no_mangle]
+pub extern "C" fn game_set_palette(handle: GameHandle, palette: u32) -> bool {
+    with_engine!(handle, false, |engine| match Palette::from_raw(palette) {
+        Some(palette) => {
+            engine.set_palette(palette);
+            true
+        }
+        None => false,
+    })
+}
+
+/// One-handed assist mode (a user setting, off by default): in Manual mode a tap anywhere
+/// smoothly pulls the player toward it, and Auto mode runs at half speed
+#[no_mangle]
+pub extern "C" fn game_set_assist_mode(handle: GameHandle, enabled: bool) {
+    with_engine!(queued, handle, |engine| engine.set_assist_mode(enabled))
+}
+
+/// Turn screen-reader announcements on or off (off by default)
+#[no_mangle]
+pub extern "C" fn game_set_announcements_enabled(handle: GameHandle, enabled: bool) {
+    with_engine!(queued, handle, |engine| engine.set_announcements_enabled(enabled))
+}
+
+/// Localize an announcement: key is one of "bounce", "surface.left", "surface.right",
+/// "surface.top", "surface.bottom", "surface.obstacle" or "score"
+/// "{surface}" and "{score}" in the text are filled in; returns false for unknown keys
+#[no_mangle]
+pub extern "C" fn game_set_announcement_text(handle: GameHandle, key: *const c_char, text: *const c_char) -> bool {
+    with_engine!(handle, false, |engine| {
+        let (key, text) = match unsafe { (strings::read(key), strings::read(text)) } {
+            (Ok(key), Ok(text)) => (key, text),
+            (Err(e), _) | (_, Err(e)) => {
+                log::warn!("game_set_announcement_text: {}", e);
+                return false;
+            }
+        };
+        engine.set_announcement_text(key, text)
+    })
+}
+
+/// Next screen-reader announcement as UTF-8 text (no NUL), for a live region
+/// Returns an empty buffer when none is pending; release with game_free_buffer
+#[no_mangle]
+pub extern "C" fn game_poll_announcement(handle: GameHandle) -> GameBuffer {
+    with_engine!(handle, GameBuffer::empty(), |engine| match engine.poll_announcement() {
+        Some(text) => buffer::into_buffer(text.into_bytes()),
+        None => GameBuffer::empty(),
+    })
+}
+
+/// What the current frame draws, as UTF-8 JSON (no NUL), before tessellation
+/// Layers in draw order with rects, node transforms, textures and tints; for golden-list tests and debugging
+/// Release with game_free_buffer
+#[no_mangle]
+pub extern "C" fn game_dump_display_list(handle: GameHandle) -> GameBuffer {
+    with_engine!(handle, GameBuffer::empty(), |engine| buffer::into_buffer(
+        engine.display_list().to_json().into_bytes()
+    ))
+}
+
+/// Log frames slower than `threshold_ms` (frame interval or time in game_render) with their phase
+/// timings and engine context, at most once per `log_interval_ms`; 0 disables detection
+/// Defaults: 50 ms, one entry per 5 s. Spikes are marked in game_export_timeline either way
+#[no_mangle]
+pub extern "C" fn game_set_spike_threshold(handle: GameHandle, threshold_ms: f32, log_interval_ms: u32) {
+    with_engine!(queued, handle, |engine| {
+        let threshold = Duration::try_from_secs_f32(threshold_ms / 1000.0).unwrap_or_default();
+        engine.set_spike_threshold(threshold, Duration::from_millis(log_interval_ms as u64))
+    })
+}
+
+/// The last ~300 rendered frames as Chrome trace event JSON (open in Perfetto / chrome://tracing):
+/// per-frame phase slices with queue depths and draw counts, plus a "spike" marker on slow frames
+/// Release with game_free_buffer
+#[no_mangle]
+pub extern "C" fn game_export_timeline(handle: GameHandle) -> GameBuffer {
+    with_engine!(handle, GameBuffer::empty(), |engine| buffer::into_buffer(engine.export_timeline().into_bytes()))
+}
+
+/// The instance is usable
+pub const GAME_STATUS_OK: i32 = 0;
+/// A call panicked; every call but game_poll_event, game_last_crash, game_get_status and game_destroy
+/// returns its default value. Destroy the instance and create a new one
+pub const GAME_STATUS_POISONED: i32 = 1;
+/// Null handle
+pub const GAME_STATUS_INVALID: i32 = 2;
+
+/// GAME_STATUS_*: whether calls on `handle` will do anything
+#[no_mangle]
+pub extern "C" fn game_get_status(handle: GameHandle) -> i32 {
+    with_engine!(poisoned, handle, GAME_STATUS_INVALID, |engine| if engine.is_poisoned() {
+        GAME_STATUS_POISONED
+    } else {
+        GAME_STATUS_OK
+    })
+}
+
+/// The panic that poisoned this instance as JSON: `{"message":..,"backtrace":..}`
+/// The message includes the source location; empty when the instance isn't poisoned
+/// Release with game_free_buffer
+#[no_mangle]
+pub extern "C" fn game_last_crash(handle: GameHandle) -> GameBuffer {
+    with_engine!(poisoned, handle, GameBuffer::empty(), |engine| match engine.last_crash() {
+        Some(crash) => buffer::into_buffer(crash.to_json().into_bytes()),
+        None => GameBuffer::empty(),
+    })
+}
+
+/// Deprecated: game_get_node_position with game_get_player_node
+/// Primary player X position; warns once through the log
+#[no_mangle]
+pub extern "C" fn game_get_player_x(handle: GameHandle) -> f32 {
+    with_engine!(handle, 0.0, |engine| {
+        let player = engine.legacy_player(LegacyCall::GetPlayerX);
+        engine.node_position(player).map_or(0.0, |position| position.x)
+    })
+}
+
+/// Deprecated: game_get_node_position with game_get_player_node
+/// Primary player Y position; warns once through the log
+#[no_mangle]
+pub extern "C" fn game_get_player_y(handle: GameHandle) -> f32 {
+    with_engine!(handle, 0.0, |engine| {
+        let player = engine.legacy_player(LegacyCall::GetPlayerY);
+        engine.node_position(player).map_or(0.0, |position| position.y)
+    })
+}
+
+/// Clean up resources
+/// Safe to call with a null handle; must be called on the thread that created the engine
+#[no_mangle]
+pub extern "C" fn game_destroy(handle: GameHandle) {
+    catch_panic!((), {
+        let Some(instance) = (unsafe { handle.as_ref() }) else {
+            return;
+        };
+        // Take the engine back from its simulation thread, if it has one
+        if let Some(renderer) = simulation::stop(handle as usize) {
+            with_engine!(handle, (), |engine| engine.restore_renderer(renderer));
+        }
+        if !instance.is_owner_thread() {
+            // GL objects can only be deleted with the context current; leaking beats crashing the driver
+            log::error!("game_destroy called off the render thread; the instance is leaked");
+            return;
+        }
+        #[cfg(feature = "frb")]
+        bridge::forget(handle as usize);
+        frame_loop::stop(handle as usize);
+        // Dropping the engine joins background tasks and destroys the painter
+        unsafe { Instance::destroy(handle) };
 
         log::info!("game_destroy: cleaned up");
-        // state is dropped here, freeing all resources
     })
 }
@@ -0,0 +1,34 @@
+/// Level-of-detail rules for dense scenes, evaluated for each entity every frame from how large it's
+/// drawn. All zero is off: everything is drawn in full detail
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LodSettings {
+    /// Entities drawn smaller than this many screen pixels (their larger side) lose outlines and custom
+    /// shaders, and particles this small aren't drawn
+    pub detail_min_px: f32,
+    /// Below this camera zoom every sprite and obstacle is a plain square-cornered rect without custom
+    /// shaders
+    pub simplify_below_zoom: f32,
+}
+
+impl LodSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.detail_min_px >= 0.0 && self.detail_min_px.is_finite()) {
+            return Err(format!("detail_min_px must be 0 or more, got {}", self.detail_min_px));
+        }
+        if !(self.simplify_below_zoom >= 0.0 && self.simplify_below_zoom.is_finite()) {
+            return Err(format!("simplify_below_zoom must be 0 or more, got {}", self.simplify_below_zoom));
+        }
+        Ok(())
+    }
+
+    /// Whether an entity drawn `pixels` across keeps its fine detail
+    pub fn detailed(&self, pixels: f32) -> bool {
+        pixels >= self.detail_min_px
+    }
+
+    /// Whether the view is zoomed out far enough to draw sprites and obstacles plainly
+    pub fn simplified(&self, zoom: f32) -> bool {
+        zoom < self.simplify_below_zoom
+    }
+}
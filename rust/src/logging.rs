@@ -0,0 +1,185 @@
+use std::cell::Cell;
+use std::ffi::{c_char, c_void, CString};
+use std::sync::{Once, RwLock};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Receives forwarded log records: `level` is GAME_LOG_* (1 = error .. 5 = trace), `target` the Rust
+/// module path; both strings are NUL-terminated UTF-8 and only valid during the call
+/// Called on whichever thread logged (render thread, task threads, audio), possibly concurrently
+pub type LogCallback =
+    unsafe extern "C" fn(user_data: *mut c_void, level: i32, target: *const c_char, message: *const c_char);
+
+/// Most verbose level sent to logcat / os_log
+#[cfg(any(target_os = "android", target_os = "ios"))]
+const PLATFORM_LEVEL: LevelFilter = LevelFilter::Info;
+/// Desktop builds have no platform log; records only go to the callback
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+const PLATFORM_LEVEL: LevelFilter = LevelFilter::Off;
+
+#[derive(Clone, Copy)]
+struct Forward {
+    callback: LogCallback,
+    /// Host pointer, passed back untouched
+    user_data: usize,
+    max_level: LevelFilter,
+}
+
+static FORWARD: RwLock<Option<Forward>> = RwLock::new(None);
+
+thread_local! {
+    /// Set while this thread is inside the callback, so logging from the host's handler
+    /// (or engine calls it makes) doesn't recurse
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Process-wide logger: the platform log plus the host callback
+struct EngineLogger {
+    platform: Option<Box<dyn Log>>,
+}
+
+impl Log for EngineLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= PLATFORM_LEVEL || metadata.level() <= forward_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if let Some(platform) = &self.platform {
+            if platform.enabled(record.metadata()) {
+                platform.log(record);
+            }
+        }
+        forward(record);
+    }
+
+    fn flush(&self) {
+        if let Some(platform) = &self.platform {
+            platform.flush();
+        }
+    }
+}
+
+#[cfg(target_os = "android")]
+fn platform_logger() -> Option<Box<dyn Log>> {
+    let config = android_logger::Config::default().with_max_level(PLATFORM_LEVEL).with_tag("RustGame");
+    Some(Box::new(android_logger::AndroidLogger::new(config)))
+}
+
+#[cfg(target_os = "ios")]
+fn platform_logger() -> Option<Box<dyn Log>> {
+    Some(Box::new(oslog::OsLogger::new("com.example.flutter_con").level_filter(PLATFORM_LEVEL)))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn platform_logger() -> Option<Box<dyn Log>> {
+    None
+}
+
+/// Install the engine's logger (once per process); later calls do nothing
+/// Leaves a logger the host process installed first in place, without forwarding
+pub fn init() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        if log::set_boxed_logger(Box::new(EngineLogger { platform: platform_logger() })).is_ok() {
+            update_max_level();
+        }
+    });
+}
+
+/// Forward records at `max_level` or more severe to `callback` (None stops forwarding)
+/// `user_data` must stay valid until the callback is replaced or cleared
+pub fn set_callback(callback: Option<LogCallback>, user_data: *mut c_void, max_level: LevelFilter) {
+    init();
+    let forward = callback.map(|callback| Forward { callback, user_data: user_data as usize, max_level });
+    *FORWARD.write().unwrap_or_else(|e| e.into_inner()) = forward;
+    update_max_level();
+}
+
+/// GAME_LOG_* value to a filter: 0 = off, 1 = error .. 5 = trace (larger values clamp to trace)
+pub fn level_filter(level: u32) -> LevelFilter {
+    match level {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+fn forward_level() -> LevelFilter {
+    FORWARD.read().unwrap_or_else(|e| e.into_inner()).as_ref().map_or(LevelFilter::Off, |f| f.max_level)
+}
+
+/// log's global filter skips formatting records nobody wants
+fn update_max_level() {
+    log::set_max_level(PLATFORM_LEVEL.max(forward_level()));
+}
+
+fn forward(record: &Record) {
+    if FORWARDING.with(Cell::get) {
+        return;
+    }
+    // Copied out so the callback may replace itself without deadlocking
+    let forward = *FORWARD.read().unwrap_or_else(|e| e.into_inner());
+    let Some(forward) = forward.filter(|f| record.level() <= f.max_level) else {
+        return;
+    };
+    let target = to_c_string(record.target().to_string());
+    let message = to_c_string(record.args().to_string());
+    FORWARDING.with(|f| f.set(true));
+    unsafe {
+        (forward.callback)(
+            forward.user_data as *mut c_void,
+            record.level() as i32,
+            target.as_ptr(),
+            message.as_ptr(),
+        );
+    }
+    FORWARDING.with(|f| f.set(false));
+}
+
+/// Interior NULs would cut the message short on the host; show them as U+FFFD
+fn to_c_string(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|e| {
+        let s = String::from_utf8_lossy(&e.into_vec()).replace('\0', "\u{FFFD}");
+        CString::new(s).unwrap_or_default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    static RECEIVED: Mutex<Vec<(i32, String, String)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn collect(user_data: *mut c_void, level: i32, target: *const c_char, message: *const c_char) {
+        assert_eq!(user_data as usize, 0x1234);
+        let target = std::ffi::CStr::from_ptr(target).to_string_lossy().into_owned();
+        let message = std::ffi::CStr::from_ptr(message).to_string_lossy().into_owned();
+        // Logging from inside the callback is dropped, not forwarded back here
+        log::error!("from the callback");
+        RECEIVED.lock().unwrap().push((level, target, message));
+    }
+
+    fn received(message: &str) -> Vec<(i32, String, String)> {
+        RECEIVED.lock().unwrap().iter().filter(|r| r.2.contains(message)).cloned().collect()
+    }
+
+    #[test]
+    fn records_are_forwarded_to_the_callback() {
+        set_callback(Some(collect), 0x1234 as *mut c_void, level_filter(3));
+        log::info!("forwarded {}", 1);
+        log::debug!("too verbose");
+        log::warn!("nul\0inside");
+        set_callback(None, std::ptr::null_mut(), LevelFilter::Off);
+        log::error!("after clearing");
+
+        assert_eq!(received("forwarded 1"), vec![(3, "game_engine::logging::tests".to_string(), "forwarded 1".to_string())]);
+        assert_eq!(received("nul\u{FFFD}inside").len(), 1);
+        assert!(received("too verbose").is_empty());
+        assert!(received("from the callback").is_empty());
+        assert!(received("after clearing").is_empty());
+    }
+}
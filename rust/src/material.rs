@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use egui::{Color32, PaintCallback, Painter, Rect};
+use glow::HasContext;
+
+use crate::ffi::strings::{self, NameId};
+use crate::shader::{self, Quad};
+
+/// Uniforms the engine sets on every material draw (hosts can't override them)
+/// u_color: sprite color, premultiplied; u_size: sprite size in pixels
+pub const BUILTIN_UNIFORMS: [&str; 2] = ["u_color", "u_size"];
+
+/// Most uniforms one material can hold
+pub const MAX_UNIFORMS: usize = 16;
+
+/// Host GLSL registered with game_register_shader
+/// Sources use the engine's dialect (see shader::compile): the vertex shader gets `a_pos`, 0 to 1
+/// across the sprite, and the fragment shader writes premultiplied FRAG_COLOR
+pub struct CustomShader {
+    /// None without a renderer (sprites using it keep their built-in look)
+    program: Option<glow::Program>,
+}
+
+/// Shaders by name; re-registering a name replaces its program for every sprite using it
+#[derive(Default)]
+pub struct ShaderRegistry {
+    shaders: HashMap<NameId, Arc<CustomShader>>,
+}
+
+impl ShaderRegistry {
+    /// Compile `fragment` (and `vertex`, or the default quad shader) as `name` on `gl`
+    /// Without `gl` the sources are only kept; on a compile error the old shader stays
+    pub fn register(&mut self, gl: Option<&glow::Context>, name: &str, vertex: Option<&str>, fragment: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("Shader name is empty".to_string());
+        }
+        let program = match gl {
+            Some(gl) => Some(unsafe { shader::compile(gl, vertex.unwrap_or(shader::QUAD_VERTEX), fragment)? }),
+            None => None,
+        };
+        let shader = Arc::new(CustomShader { program });
+        if let Some(old) = self.shaders.insert(strings::intern(name), shader) {
+            // Only the display list being built references programs, so the old one can go now
+            if let (Some(gl), Some(program)) = (gl, old.program) {
+                unsafe { gl.delete_program(program) };
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<CustomShader>> {
+        self.shaders.get(&strings::intern(name))
+    }
+
+    /// How `material` draws a sprite of `color` this frame
+    pub fn resolve(&self, material: &Arc<Material>, color: Color32) -> MaterialDraw {
+        let callback = self.callback(material, color);
+        MaterialDraw { material: material.clone(), callback }
+    }
+
+    /// None if the material's shader isn't registered or compiled
+    fn callback(&self, material: &Material, color: Color32) -> Option<Arc<egui_glow::CallbackFn>> {
+        let shader = self.get(&material.shader)?;
+        let program = shader.program?;
+        let mut drawn = material.drawn.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((s, c, callback)) = drawn.as_ref() {
+            if Arc::ptr_eq(s, shader) && *c == color {
+                return Some(callback.clone());
+            }
+        }
+        let uniforms = material.uniforms.clone();
+        let rgba = color.to_array().map(|c| c as f32 / 255.0);
+        let callback = egui_glow::CallbackFn::new(move |info, painter| unsafe {
+            let gl = painter.gl();
+            let location = |name: &str| gl.get_uniform_location(program, name);
+            let [width, height] = shader::callback_viewport(gl, &info);
+            gl.use_program(Some(program));
+            gl.uniform_4_f32(location("u_color").as_ref(), rgba[0], rgba[1], rgba[2], rgba[3]);
+            gl.uniform_2_f32(location("u_size").as_ref(), width as f32, height as f32);
+            for (name, values) in &uniforms {
+                let location = location(name);
+                match values[..] {
+                    [x] => gl.uniform_1_f32(location.as_ref(), x),
+                    [x, y] => gl.uniform_2_f32(location.as_ref(), x, y),
+                    [x, y, z] => gl.uniform_3_f32(location.as_ref(), x, y, z),
+                    [x, y, z, w] => gl.uniform_4_f32(location.as_ref(), x, y, z, w),
+                    _ => {}
+                }
+            }
+            shader::with(gl, |gl| Quad::new(gl), |quad| quad.draw(gl));
+        });
+        let callback = Arc::new(callback);
+        *drawn = Some((shader.clone(), color, callback.clone()));
+        Some(callback)
+    }
+
+    /// Delete every program (call before the context goes away)
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn destroy(&mut self, gl: &glow::Context) {
+        for (_, shader) in self.shaders.drain() {
+            if let Some(program) = shader.program {
+                gl.delete_program(program);
+            }
+        }
+    }
+}
+
+/// A sprite's shader and the uniform values the host gave it
+pub struct Material {
+    pub shader: String,
+    /// Sorted by name, 1-4 components each
+    pub uniforms: Vec<(String, Vec<f32>)>,
+    /// Callback last made for it, with the shader and color it was made for; reused while
+    /// neither changes, since egui compares callbacks by pointer (see pacing module)
+    drawn: Mutex<Option<Drawn>>,
+}
+
+/// Shader, sprite color, and the callback drawing the material with them
+type Drawn = (Arc<CustomShader>, Color32, Arc<egui_glow::CallbackFn>);
+
+impl Material {
+    pub fn new(shader: &str) -> Material {
+        Material { shader: shader.to_string(), uniforms: Vec::new(), drawn: Mutex::default() }
+    }
+
+    /// Set uniform `name` to `values` (float to vec4)
+    pub fn set_uniform(&mut self, name: &str, values: &[f32]) -> Result<(), String> {
+        if name.is_empty() || BUILTIN_UNIFORMS.contains(&name) {
+            return Err(format!("Uniform name {:?} is reserved or empty", name));
+        }
+        if !(1..=4).contains(&values.len()) || values.iter().any(|v| !v.is_finite()) {
+            return Err(format!("Uniform {} needs 1-4 finite values", name));
+        }
+        match self.uniforms.binary_search_by(|(n, _)| n.as_str().cmp(name)) {
+            Ok(i) => self.uniforms[i].1 = values.to_vec(),
+            Err(_) if self.uniforms.len() == MAX_UNIFORMS => return Err(format!("More than {} uniforms", MAX_UNIFORMS)),
+            Err(i) => self.uniforms.insert(i, (name.to_string(), values.to_vec())),
+        }
+        *self.drawn.get_mut().unwrap_or_else(|e| e.into_inner()) = None;
+        Ok(())
+    }
+}
+
+impl Clone for Material {
+    fn clone(&self) -> Material {
+        Material { shader: self.shader.clone(), uniforms: self.uniforms.clone(), drawn: Mutex::default() }
+    }
+}
+
+impl std::fmt::Debug for Material {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Material").field("shader", &self.shader).field("uniforms", &self.uniforms).finish()
+    }
+}
+
+impl PartialEq for Material {
+    fn eq(&self, other: &Self) -> bool {
+        self.shader == other.shader && self.uniforms == other.uniforms
+    }
+}
+
+/// A material resolved for this frame; equal while its shader, uniforms and color are
+#[derive(Clone)]
+pub struct MaterialDraw {
+    pub material: Arc<Material>,
+    /// None if the shader can't draw (not registered, or no renderer)
+    callback: Option<Arc<egui_glow::CallbackFn>>,
+}
+
+impl MaterialDraw {
+    /// Draw over `rect` (the shader's quad fills it); false if the caller should draw the sprite itself
+    pub fn paint(&self, painter: &Painter, rect: Rect) -> bool {
+        let Some(callback) = &self.callback else {
+            return false;
+        };
+        painter.add(PaintCallback { rect, callback: callback.clone() });
+        true
+    }
+}
+
+impl PartialEq for MaterialDraw {
+    fn eq(&self, other: &Self) -> bool {
+        let same_callback = match (&self.callback, &other.callback) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        self.material == other.material && same_callback
+    }
+}
@@ -0,0 +1,254 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+use egui::{Pos2, Rect, Vec2};
+
+/// Most cells in a grid; bigger worlds get coarser cells
+pub const MAX_CELLS: usize = 65_536;
+
+/// Edges touching a blocked cell don't count as crossing it
+const EPSILON: f32 = 1e-3;
+
+/// Walkable squares over a rectangle of the world, for routing around obstacles
+/// Cells are blocked by whatever overlaps them; callers grow obstacles by the mover's half size
+/// so a path through open cells keeps it clear of them
+pub struct NavGrid {
+    origin: Pos2,
+    cell: f32,
+    width: usize,
+    height: usize,
+    blocked: Vec<bool>,
+}
+
+/// Open cell waiting in the A* frontier, cheapest estimate first
+struct Open {
+    estimate: f32,
+    cell: usize,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap pops the largest; ties go to the lower cell so searches are deterministic
+        other.estimate.total_cmp(&self.estimate).then_with(|| other.cell.cmp(&self.cell))
+    }
+}
+
+impl NavGrid {
+    /// Open grid of `cell`-sized squares covering `bounds`, grown so there are at most MAX_CELLS
+    pub fn new(bounds: Rect, cell: f32) -> NavGrid {
+        let area = bounds.width().max(0.0) * bounds.height().max(0.0);
+        let mut cell = cell.max((area / MAX_CELLS as f32).sqrt()).max(1.0);
+        let cells = |cell: f32| (((bounds.width() / cell).ceil() as usize).max(1), ((bounds.height() / cell).ceil() as usize).max(1));
+        // Rounding up can add a row and a column
+        while cells(cell).0 * cells(cell).1 > MAX_CELLS {
+            cell *= 1.01;
+        }
+        let (width, height) = cells(cell);
+        NavGrid { origin: bounds.min, cell, width, height, blocked: vec![false; width * height] }
+    }
+
+    /// Block every cell `rect` overlaps (touching an edge doesn't count)
+    pub fn block(&mut self, rect: Rect) {
+        let min = (rect.min - self.origin) / self.cell;
+        let max = (rect.max - self.origin) / self.cell;
+        let x0 = (min.x + EPSILON).floor().max(0.0) as usize;
+        let y0 = (min.y + EPSILON).floor().max(0.0) as usize;
+        let x1 = ((max.x - EPSILON).ceil().max(0.0) as usize).min(self.width);
+        let y1 = ((max.y - EPSILON).ceil().max(0.0) as usize).min(self.height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.blocked[y * self.width + x] = true;
+            }
+        }
+    }
+
+    fn cell_at(&self, point: Pos2) -> Option<usize> {
+        let local = (point - self.origin) / self.cell;
+        let (x, y) = (local.x.floor(), local.y.floor());
+        if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+
+    fn center(&self, cell: usize) -> Pos2 {
+        let (x, y) = ((cell % self.width) as f32, (cell / self.width) as f32);
+        self.origin + Vec2::new(x + 0.5, y + 0.5) * self.cell
+    }
+
+    fn cell_rect(&self, x: usize, y: usize) -> Rect {
+        Rect::from_min_size(self.origin + Vec2::new(x as f32, y as f32) * self.cell, Vec2::splat(self.cell))
+    }
+
+    /// Waypoints from `from` to `to` (ending exactly at `to`), or None if `to` is blocked, off the
+    /// grid or unreachable. A* over the eight neighbours (no cutting blocked corners), then smoothed
+    /// by skipping waypoints the path can go straight past
+    /// `from` may be in a blocked cell, so movers that start against an obstacle can leave it
+    pub fn find_path(&self, from: Pos2, to: Pos2) -> Option<Vec<Pos2>> {
+        let start = self.cell_at(from)?;
+        let goal = self.cell_at(to)?;
+        if self.blocked[goal] {
+            return None;
+        }
+        let route = self.search(start, goal)?;
+
+        let mut points = Vec::with_capacity(route.len() + 1);
+        points.push(from);
+        points.extend(route[1..route.len().saturating_sub(1)].iter().map(|&cell| self.center(cell)));
+        points.push(to);
+
+        // Go straight to the furthest waypoint in sight of each one reached
+        let mut path = Vec::new();
+        let mut at = 0;
+        while at < points.len() - 1 {
+            let next = (at + 2..points.len()).rev().find(|&j| self.is_clear(points[at], points[j])).unwrap_or(at + 1);
+            path.push(points[next]);
+            at = next;
+        }
+        Some(path)
+    }
+
+    /// Cells from `start` to `goal` inclusive
+    fn search(&self, start: usize, goal: usize) -> Option<Vec<usize>> {
+        let goal_center = self.center(goal);
+        let heuristic = |cell: usize| {
+            // Octile distance in cells
+            let d = (self.center(cell) - goal_center).abs() / self.cell;
+            d.x.max(d.y) + (std::f32::consts::SQRT_2 - 1.0) * d.x.min(d.y)
+        };
+        let mut cost = vec![f32::INFINITY; self.blocked.len()];
+        let mut came_from = vec![usize::MAX; self.blocked.len()];
+        let mut open = BinaryHeap::new();
+        cost[start] = 0.0;
+        open.push(Open { estimate: heuristic(start), cell: start });
+
+        while let Some(Open { estimate, cell }) = open.pop() {
+            if cell == goal {
+                let mut route = vec![goal];
+                let mut current = goal;
+                while current != start {
+                    current = came_from[current];
+                    route.push(current);
+                }
+                route.reverse();
+                return Some(route);
+            }
+            // Stale entry for a cell since reached more cheaply
+            if estimate > cost[cell] + heuristic(cell) + EPSILON {
+                continue;
+            }
+            let (x, y) = ((cell % self.width) as isize, (cell / self.width) as isize);
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)] {
+                let Some(next) = self.open_cell(x + dx, y + dy) else {
+                    continue;
+                };
+                let diagonal = dx != 0 && dy != 0;
+                if diagonal && (self.open_cell(x + dx, y).is_none() || self.open_cell(x, y + dy).is_none()) {
+                    continue;
+                }
+                let step = if diagonal { std::f32::consts::SQRT_2 } else { 1.0 };
+                let reached = cost[cell] + step;
+                if reached < cost[next] {
+                    cost[next] = reached;
+                    came_from[next] = cell;
+                    open.push(Open { estimate: reached + heuristic(next), cell: next });
+                }
+            }
+        }
+        None
+    }
+
+    fn open_cell(&self, x: isize, y: isize) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        let cell = y as usize * self.width + x as usize;
+        (!self.blocked[cell]).then_some(cell)
+    }
+
+    /// Whether the segment from `a` to `b` stays on the grid and out of blocked cells
+    fn is_clear(&self, a: Pos2, b: Pos2) -> bool {
+        let (Some(_), Some(_)) = (self.cell_at(a), self.cell_at(b)) else {
+            return false;
+        };
+        let min = ((a.min(b) - self.origin) / self.cell).floor();
+        let max = ((a.max(b) - self.origin) / self.cell).floor();
+        for y in (min.y.max(0.0) as usize)..=(max.y as usize).min(self.height - 1) {
+            for x in (min.x.max(0.0) as usize)..=(max.x as usize).min(self.width - 1) {
+                if self.blocked[y * self.width + x] && crosses(a, b, self.cell_rect(x, y).shrink(EPSILON)) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Whether the segment from `a` to `b` passes through `rect` (Liang-Barsky clipping)
+fn crosses(a: Pos2, b: Pos2, rect: Rect) -> bool {
+    let delta = b - a;
+    let (mut enter, mut exit) = (0.0f32, 1.0f32);
+    for (d, low, high) in [(delta.x, rect.min.x - a.x, rect.max.x - a.x), (delta.y, rect.min.y - a.y, rect.max.y - a.y)] {
+        if d == 0.0 {
+            if low > 0.0 || high < 0.0 {
+                return false;
+            }
+            continue;
+        }
+        let (t0, t1) = if d > 0.0 { (low / d, high / d) } else { (high / d, low / d) };
+        enter = enter.max(t0);
+        exit = exit.min(t1);
+        if enter > exit {
+            return false;
+        }
+    }
+    true
+}
+
+/// Waypoints a sprite is following at a steady speed, as Auto mode moves the player
+pub struct Route {
+    waypoints: VecDeque<Pos2>,
+    speed: f32,
+}
+
+impl Route {
+    pub fn new(waypoints: Vec<Pos2>, speed: f32) -> Route {
+        Route { waypoints: waypoints.into(), speed }
+    }
+
+    /// Move every waypoint left through `f` (the world was resized)
+    pub fn map_points(&mut self, f: impl Fn(Pos2) -> Pos2) {
+        for point in &mut self.waypoints {
+            *point = f(*point);
+        }
+    }
+
+    /// Velocity taking a mover at `position` on along the route for the next `delta` seconds,
+    /// landing exactly on waypoints rather than overshooting; None once it's at the end
+    pub fn steer(&mut self, position: Pos2, delta: f32) -> Option<Vec2> {
+        while self.waypoints.front().is_some_and(|&point| point.distance(position) <= EPSILON) {
+            self.waypoints.pop_front();
+        }
+        let to_next = *self.waypoints.front()? - position;
+        let distance = to_next.length();
+        if distance <= self.speed * delta {
+            Some(to_next / delta)
+        } else {
+            Some(to_next / distance * self.speed)
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use egui::{Pos2, Vec2};
+
+/// Device orientation across FFI (game_set_orientation); never renumber
+pub const ORIENTATION_PORTRAIT: u32 = 0;
+pub const ORIENTATION_LANDSCAPE: u32 = 1;
+
+/// What happens to entity positions when a rotation resizes the world (game_set_rotation_policy)
+/// Never renumber
+pub const ROTATION_REMAP_PROPORTIONAL: u32 = 0;
+pub const ROTATION_REMAP_CLAMP: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+impl Orientation {
+    pub fn from_raw(raw: u32) -> Option<Orientation> {
+        match raw {
+            ORIENTATION_PORTRAIT => Some(Orientation::Portrait),
+            ORIENTATION_LANDSCAPE => Some(Orientation::Landscape),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RotationPolicy {
+    /// Entities keep their place relative to the world's edges (a third of the way across stays a
+    /// third of the way across), as level obstacles do
+    #[default]
+    Proportional,
+    /// Entities keep their coordinates; the player is pulled back inside if it ends up outside
+    Clamp,
+}
+
+impl RotationPolicy {
+    pub fn from_raw(raw: u32) -> Option<RotationPolicy> {
+        match raw {
+            ROTATION_REMAP_PROPORTIONAL => Some(RotationPolicy::Proportional),
+            ROTATION_REMAP_CLAMP => Some(RotationPolicy::Clamp),
+            _ => None,
+        }
+    }
+}
+
+/// Maps world points from the world size before a rotation to the size after it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Remap {
+    scale: Vec2,
+}
+
+impl Remap {
+    pub fn apply(&self, point: Pos2) -> Pos2 {
+        Pos2::new(point.x * self.scale.x, point.y * self.scale.y)
+    }
+}
+
+/// The host's orientation, and a change waiting for the resize that completes it
+/// Hosts report the new orientation before the surface is resized (onConfigurationChanged before
+/// onSurfaceChanged, viewWillTransition before layout), so the world size is noted then and the
+/// remap happens on the next resize
+#[derive(Default)]
+pub struct OrientationTracker {
+    orientation: Option<Orientation>,
+    pub policy: RotationPolicy,
+    /// World size when the orientation last changed, until the next resize
+    pending: Option<Vec2>,
+}
+
+impl OrientationTracker {
+    /// Note the host's orientation while the world is `world` points; false if it didn't change
+    /// The first orientation reported only sets the starting one
+    pub fn set(&mut self, orientation: Orientation, world: Vec2) -> bool {
+        let previous = self.orientation.replace(orientation);
+        if previous.is_none_or(|previous| previous == orientation) {
+            return false;
+        }
+        // Turning back before the resize arrived keeps the size it started from
+        self.pending.get_or_insert(world);
+        true
+    }
+
+    /// The remap for a resize to a `world` points world, if it completes an orientation change
+    /// None for other resizes, under the Clamp policy, or if the world's size didn't change (a
+    /// fixed world set with game_set_world_size)
+    pub fn finish(&mut self, world: Vec2) -> Option<Remap> {
+        let from = self.pending.take()?;
+        if self.policy != RotationPolicy::Proportional || from == world || from.min_elem() <= 0.0 {
+            return None;
+        }
+        Some(Remap { scale: world / from })
+    }
+}
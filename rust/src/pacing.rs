@@ -0,0 +1,153 @@
+use egui::epaint::ClippedShape;
+use egui::Shape;
+
+/// Frame rate caps accepted by game_set_target_fps (0 renders on every vsync)
+pub const TARGET_FPS: [u32; 4] = [0, 30, 60, 120];
+
+/// Vsync intervals longer than this are treated as pauses, not refresh periods
+const MAX_PERIOD_NS: u64 = 100_000_000;
+
+/// Refresh period assumed until two consecutive vsyncs have been seen (60 Hz)
+const DEFAULT_PERIOD_NS: u64 = 16_666_667;
+
+/// Pacing counters for game_get_pacing_stats
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PacingStats {
+    /// Frame rate cap, including any power scaling limit (0 = every vsync)
+    pub target_fps: u32,
+    /// Display refresh rate estimated from vsync timestamps (0 until known)
+    pub refresh_hz: f32,
+    /// Frames drawn by game_render_with_timestamp
+    pub rendered: u64,
+    /// Vsyncs skipped to hold the cap, or because they were already rendered
+    pub skipped_paced: u64,
+    /// Vsyncs skipped because the frame would have looked the same as the one on screen
+    pub skipped_unchanged: u64,
+}
+
+/// What made a frame look the way it did; equal keys draw identical pixels
+pub struct FrameKey<'a> {
+    pub size: [u32; 2],
+    pub clear_color: [f32; 4],
+    pub shapes: &'a [ClippedShape],
+}
+
+/// Decides which vsyncs get a frame when the host passes presentation timestamps
+/// (game_render_with_timestamp): holds a frame rate cap on the display's vsync grid, drops repeat
+/// renders for a vsync that already has a frame, and remembers the last frame to skip unchanged ones
+#[derive(Default)]
+pub struct FramePacer {
+    target_fps: u32,
+    /// Cap the engine imposes on top of the host's (power scaling); 0 for none
+    fps_limit: u32,
+    last_vsync: Option<u64>,
+    last_rendered: Option<u64>,
+    /// Smallest recent vsync interval, in nanoseconds
+    period_ns: Option<u64>,
+    /// Size and clear color of the last frame drawn, None if it must be drawn again
+    last_frame: Option<([u32; 2], [f32; 4])>,
+    /// Its shapes, copied into the same vector each frame so pacing doesn't allocate one
+    last_shapes: Vec<ClippedShape>,
+    stats: PacingStats,
+}
+
+impl FramePacer {
+    /// Cap the frame rate; false (and unchanged) unless `fps` is one of TARGET_FPS
+    pub fn set_target_fps(&mut self, fps: u32) -> bool {
+        if !TARGET_FPS.contains(&fps) {
+            return false;
+        }
+        self.target_fps = fps;
+        true
+    }
+
+    /// Hold paced frames to at most `fps` whatever the host asks for (0 lifts the limit)
+    pub fn set_fps_limit(&mut self, fps: u32) {
+        self.fps_limit = fps;
+    }
+
+    /// The cap frames are held to: the lower of the host's and the limit
+    fn effective_fps(&self) -> u32 {
+        match (self.target_fps, self.fps_limit) {
+            (fps, 0) | (0, fps) => fps,
+            (target, limit) => target.min(limit),
+        }
+    }
+
+    /// Whether the vsync at `vsync_ns` should get a frame
+    pub fn begin(&mut self, vsync_ns: u64) -> bool {
+        if let Some(delta) = self.last_vsync.and_then(|last| vsync_ns.checked_sub(last)) {
+            if delta > 0 && delta < MAX_PERIOD_NS {
+                // Hosts skip vsyncs when busy; the shortest interval seen lately is the refresh period.
+                // Decay slowly so a refresh rate drop (120 -> 60 Hz) is picked up after a few frames
+                let period = self.period_ns.map_or(delta, |p| if delta < p { delta } else { p + (delta - p) / 8 });
+                self.period_ns = Some(period);
+            }
+        }
+        self.last_vsync = Some(vsync_ns);
+
+        let Some(last) = self.last_rendered else {
+            return true;
+        };
+        let fps = self.effective_fps();
+        let due = vsync_ns > last && (fps == 0 || {
+            // Render on the first vsync within half a refresh of the cap's interval,
+            // so 30 fps on a 60 Hz display is every other vsync rather than drifting
+            let interval = 1_000_000_000 / fps as u64;
+            let slack = self.period_ns.unwrap_or(DEFAULT_PERIOD_NS) / 2;
+            vsync_ns - last + slack >= interval
+        });
+        if !due {
+            self.stats.skipped_paced += 1;
+        }
+        due
+    }
+
+    /// Whether `frame` differs from the last frame drawn; stores it if so
+    /// The vsync at `vsync_ns` counts as rendered either way, since the screen is up to date
+    pub fn changed(&mut self, vsync_ns: u64, frame: FrameKey) -> bool {
+        self.last_rendered = Some(vsync_ns);
+        if self.last_frame == Some((frame.size, frame.clear_color)) && self.last_shapes == frame.shapes {
+            self.stats.skipped_unchanged += 1;
+            return false;
+        }
+        self.last_frame = Some((frame.size, frame.clear_color));
+        copy_shapes(&mut self.last_shapes, frame.shapes);
+        self.stats.rendered += 1;
+        true
+    }
+
+    /// Forget the last frame so the next one is drawn (something outside the frame key changed,
+    /// e.g. a frame drawn through game_render or the surface was recreated)
+    pub fn invalidate(&mut self) {
+        self.last_frame = None;
+    }
+
+    pub fn stats(&self) -> PacingStats {
+        PacingStats {
+            target_fps: self.effective_fps(),
+            refresh_hz: self.period_ns.map_or(0.0, |p| 1e9 / p as f32),
+            ..self.stats
+        }
+    }
+}
+
+/// Make `dst` a copy of `src`, reusing its vector and the vertex and index buffers of meshes already
+/// in the same place (sprites are meshes, so a moving scene copies without allocating)
+fn copy_shapes(dst: &mut Vec<ClippedShape>, src: &[ClippedShape]) {
+    dst.truncate(src.len());
+    for (dst, src) in dst.iter_mut().zip(src) {
+        dst.clip_rect = src.clip_rect;
+        match (&mut dst.shape, &src.shape) {
+            (Shape::Mesh(dst), Shape::Mesh(src)) => {
+                dst.indices.clone_from(&src.indices);
+                dst.vertices.clone_from(&src.vertices);
+                dst.texture_id = src.texture_id;
+            }
+            (dst, src) => *dst = src.clone(),
+        }
+    }
+    let copied = dst.len();
+    dst.extend_from_slice(&src[copied..]);
+}
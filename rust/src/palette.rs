@@ -0,0 +1,90 @@
+use egui::Color32;
+
+use crate::rng::Rng;
+
+/// Color sets for bounce tints and HUD highlights
+/// Values are part of the C API; never renumber
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(u32)]
+pub enum Palette {
+    /// Any bright color
+    #[default]
+    Standard = 0,
+    /// Safe for red-green deficiency (green-weak)
+    Deuteranopia = 1,
+    /// Safe for red-green deficiency (red-weak; avoids dark reds)
+    Protanopia = 2,
+    /// Safe for blue-yellow deficiency
+    Tritanopia = 3,
+}
+
+// Okabe-Ito colors, distinguishable under the common color vision deficiencies
+const ORANGE: Color32 = Color32::from_rgb(230, 159, 0);
+const SKY_BLUE: Color32 = Color32::from_rgb(86, 180, 233);
+const BLUISH_GREEN: Color32 = Color32::from_rgb(0, 158, 115);
+const YELLOW: Color32 = Color32::from_rgb(240, 228, 66);
+const BLUE: Color32 = Color32::from_rgb(0, 114, 178);
+const VERMILLION: Color32 = Color32::from_rgb(213, 94, 0);
+const REDDISH_PURPLE: Color32 = Color32::from_rgb(204, 121, 167);
+
+const DEUTERANOPIA_TINTS: &[Color32] = &[SKY_BLUE, YELLOW, BLUE, REDDISH_PURPLE, Color32::WHITE];
+const PROTANOPIA_TINTS: &[Color32] = &[SKY_BLUE, YELLOW, BLUE, Color32::WHITE];
+const TRITANOPIA_TINTS: &[Color32] = &[VERMILLION, BLUISH_GREEN, REDDISH_PURPLE, Color32::WHITE];
+
+impl Palette {
+    pub fn from_raw(value: u32) -> Option<Palette> {
+        match value {
+            0 => Some(Palette::Standard),
+            1 => Some(Palette::Deuteranopia),
+            2 => Some(Palette::Protanopia),
+            3 => Some(Palette::Tritanopia),
+            _ => None,
+        }
+    }
+
+    /// Next player tint after a bounce, never the current one for fixed palettes
+    /// Draws one value from `rng` whatever the palette, so seeded runs stay in step
+    pub fn bounce_color(self, rng: &mut Rng, current: Color32) -> Color32 {
+        let Some(tints) = self.tints() else {
+            return rng.bright_color();
+        };
+        let others: Vec<Color32> = tints.iter().copied().filter(|&c| c != current).collect();
+        others[(rng.next_u64() % others.len() as u64) as usize]
+    }
+
+    /// `tint` if this palette allows it, otherwise the palette's first tint
+    pub fn constrain(self, tint: Color32) -> Color32 {
+        match self.tints() {
+            Some(tints) if !tints.contains(&tint) => tints[0],
+            _ => tint,
+        }
+    }
+
+    /// Fixed tint set, or None for any bright color
+    fn tints(self) -> Option<&'static [Color32]> {
+        match self {
+            Palette::Standard => None,
+            Palette::Deuteranopia => Some(DEUTERANOPIA_TINTS),
+            Palette::Protanopia => Some(PROTANOPIA_TINTS),
+            Palette::Tritanopia => Some(TRITANOPIA_TINTS),
+        }
+    }
+
+    /// Player tint while dragged; kept out of the bounce tints
+    pub fn highlight(self) -> Color32 {
+        match self {
+            Palette::Standard => Color32::from_rgb(255, 150, 50),
+            Palette::Deuteranopia | Palette::Protanopia => ORANGE,
+            Palette::Tritanopia => Color32::from_rgb(255, 140, 160),
+        }
+    }
+
+    /// (normal, alert) text colors for status readouts
+    pub fn status_colors(self) -> (Color32, Color32) {
+        match self {
+            Palette::Standard => (Color32::from_rgb(120, 255, 120), Color32::from_rgb(255, 90, 90)),
+            Palette::Deuteranopia | Palette::Protanopia => (SKY_BLUE, ORANGE),
+            Palette::Tritanopia => (Color32::from_gray(230), VERMILLION),
+        }
+    }
+}
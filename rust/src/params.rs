@@ -0,0 +1,85 @@
+use std::ops::RangeInclusive;
+
+/// Gameplay tunables hosts set at runtime (game_set_param) instead of recompiling
+/// Values are part of the C API; never renumber
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u32)]
+pub enum Param {
+    /// Manual mode player speed (points/s)
+    Speed = 0,
+    /// Auto mode starting velocity (points/s) outside levels, which bring their own
+    AutoVelocityX = 1,
+    AutoVelocityY = 2,
+    /// How quickly a thrown player slows down (per second, exponentially); 0 turns throwing off
+    Friction = 3,
+    /// Speed kept by the player bouncing off a wall in Auto mode: 1 is perfectly elastic
+    /// The physics world bounces with it too (game_set_physics sets it)
+    Restitution = 4,
+    /// Side of the player's box (points)
+    PlayerSize = 5,
+}
+
+const COUNT: usize = 6;
+
+/// Default and accepted values of a parameter
+pub struct ParamSpec {
+    pub default: f32,
+    pub range: RangeInclusive<f32>,
+}
+
+impl Param {
+    pub fn from_raw(value: u32) -> Option<Param> {
+        match value {
+            0 => Some(Param::Speed),
+            1 => Some(Param::AutoVelocityX),
+            2 => Some(Param::AutoVelocityY),
+            3 => Some(Param::Friction),
+            4 => Some(Param::Restitution),
+            5 => Some(Param::PlayerSize),
+            _ => None,
+        }
+    }
+
+    pub fn spec(self) -> ParamSpec {
+        let (default, range) = match self {
+            Param::Speed => (300.0, 0.0..=f32::MAX),
+            Param::AutoVelocityX => (250.0, f32::MIN..=f32::MAX),
+            Param::AutoVelocityY => (200.0, f32::MIN..=f32::MAX),
+            // The speed left after a second is e^-4 (about 2%) of the release speed
+            Param::Friction => (4.0, 0.0..=f32::MAX),
+            Param::Restitution => (1.0, 0.0..=1.0),
+            Param::PlayerSize => (200.0, 1.0..=f32::MAX),
+        };
+        ParamSpec { default, range }
+    }
+}
+
+/// Current value of every parameter, starting at the defaults
+pub struct Params {
+    values: [f32; COUNT],
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        let mut values = [0.0; COUNT];
+        for (raw, value) in values.iter_mut().enumerate() {
+            *value = Param::from_raw(raw as u32).map_or(0.0, |p| p.spec().default);
+        }
+        Params { values }
+    }
+}
+
+impl Params {
+    pub fn get(&self, param: Param) -> f32 {
+        self.values[param as usize]
+    }
+
+    /// False (unchanged) for values outside the parameter's range, including NaN
+    pub fn set(&mut self, param: Param, value: f32) -> bool {
+        if !param.spec().range.contains(&value) {
+            return false;
+        }
+        self.values[param as usize] = value;
+        true
+    }
+}
@@ -0,0 +1,322 @@
+use std::f32::consts::TAU;
+
+use egui::epaint::Mesh;
+use egui::{Color32, Pos2, Rect, Vec2};
+
+use crate::arena::{Arena, Id, INVALID_ID};
+use crate::rng::Rng;
+use crate::sprites;
+
+/// Emitter id handed across FFI
+pub type EmitterId = Id;
+
+/// Invalid emitter id returned on failure
+pub const INVALID_EMITTER: EmitterId = INVALID_ID;
+
+/// Most live particles; emitters skip new ones while the pool is full
+pub const MAX_PARTICLES: usize = 4096;
+
+/// Most live emitters
+pub const MAX_EMITTERS: usize = 256;
+
+/// How an emitter throws particles and how they change over their lifetime
+/// Angles are radians with y down (0 = right, π/2 = down)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EmitterConfig {
+    /// Particles emitted at once when the emitter starts
+    pub burst: u32,
+    /// Particles per second emitted after that (0 for a burst only)
+    pub rate: f32,
+    /// Seconds a continuous emitter runs (0 = until stopped)
+    pub duration: f32,
+    /// Seconds each particle lives, picked between the two
+    pub lifetime_min: f32,
+    pub lifetime_max: f32,
+    /// Direction particles are thrown and the cone around it (TAU = all around)
+    pub angle: f32,
+    pub spread: f32,
+    /// Speed at birth in points per second, picked between the two
+    pub speed_min: f32,
+    pub speed_max: f32,
+    /// Speed at death as a fraction of the birth speed (1 = constant, 0 = comes to rest)
+    pub speed_end: f32,
+    /// Acceleration in points per second squared (e.g. gravity)
+    pub gravity_x: f32,
+    pub gravity_y: f32,
+    /// Edge of the particle's square in points, at birth and at death
+    pub size_start: f32,
+    pub size_end: f32,
+    /// 0xRRGGBBAA at birth and at death
+    pub color_start: u32,
+    pub color_end: u32,
+}
+
+impl EmitterConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let values = [
+            self.rate,
+            self.duration,
+            self.lifetime_min,
+            self.lifetime_max,
+            self.angle,
+            self.spread,
+            self.speed_min,
+            self.speed_max,
+            self.speed_end,
+            self.gravity_x,
+            self.gravity_y,
+            self.size_start,
+            self.size_end,
+        ];
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err("Emitter values must be finite".into());
+        }
+        if self.burst == 0 && self.rate <= 0.0 {
+            return Err("Emitter needs a burst or a rate".into());
+        }
+        if self.burst as usize > MAX_PARTICLES {
+            return Err(format!("Burst of {} is over the {} particle limit", self.burst, MAX_PARTICLES));
+        }
+        if !(self.lifetime_min > 0.0 && self.lifetime_max >= self.lifetime_min) {
+            return Err(format!("Invalid lifetime {}-{}", self.lifetime_min, self.lifetime_max));
+        }
+        if self.speed_max < self.speed_min {
+            return Err(format!("Invalid speed {}-{}", self.speed_min, self.speed_max));
+        }
+        let negative = [self.rate, self.duration, self.spread, self.speed_min, self.speed_end, self.size_start, self.size_end];
+        if negative.iter().any(|v| *v < 0.0) {
+            return Err("Rate, duration, spread, speeds and sizes can't be negative".into());
+        }
+        Ok(())
+    }
+}
+
+struct Emitter {
+    config: EmitterConfig,
+    position: Pos2,
+    /// Seconds since it started
+    age: f32,
+    /// Particles owed by the rate but not emitted yet (fraction of one)
+    owed: f32,
+    stopped: bool,
+}
+
+/// What a particle looks like over its life, from its emitter
+#[derive(Clone, Copy)]
+struct Style {
+    speed_end: f32,
+    gravity: Vec2,
+    size: [f32; 2],
+    color: [Color32; 2],
+}
+
+struct Particle {
+    position: Pos2,
+    /// Position at the previous tick (for render interpolation)
+    prev_position: Pos2,
+    /// Thrown velocity at birth, scaled by the speed curve as it ages
+    velocity: Vec2,
+    /// Velocity gained from gravity so far
+    fall: Vec2,
+    age: f32,
+    lifetime: f32,
+    style: Style,
+}
+
+/// Emitters and the particles they threw, simulated on the game tick and drawn as one mesh
+/// Purely cosmetic: particles have their own random source (replays and daily challenges stay
+/// deterministic) and aren't saved
+pub struct ParticleSystem {
+    emitters: Arena<Emitter>,
+    particles: Vec<Particle>,
+    rng: Rng,
+    /// Burst thrown away from the surface on player bounces (game_set_bounce_particles)
+    bounce: Option<EmitterConfig>,
+    /// Particles not emitted because the pool was full
+    pub dropped: u64,
+    /// Fraction of each burst and of the pool used (power scaling)
+    budget: f32,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        ParticleSystem {
+            emitters: Arena::default(),
+            particles: Vec::new(),
+            rng: Rng::from_time(),
+            bounce: None,
+            dropped: 0,
+            budget: 1.0,
+        }
+    }
+}
+
+impl ParticleSystem {
+    /// Start an emitter at `position`; its burst is thrown right away
+    /// Burst-only emitters are finished once spawned, so their id is only good for the return check
+    pub fn spawn(&mut self, position: Pos2, config: EmitterConfig) -> Result<EmitterId, String> {
+        config.validate()?;
+        if self.emitters.count() == MAX_EMITTERS {
+            return Err(format!("At most {} emitters", MAX_EMITTERS));
+        }
+        self.emit(&config, position, 0.0, self.scaled(config.burst));
+        let id = self.emitters.insert(Emitter { config, position, age: 0.0, owed: 0.0, stopped: config.rate == 0.0 });
+        Ok(id)
+    }
+
+    /// Move where a continuous emitter throws from; false if it's finished or unknown
+    pub fn move_emitter(&mut self, id: EmitterId, position: Pos2) -> bool {
+        self.emitters.get_mut(id).map(|e| e.position = position).is_some()
+    }
+
+    /// Stop emitting; particles already thrown live out their lifetime
+    pub fn stop(&mut self, id: EmitterId) -> bool {
+        self.emitters.remove(id).is_some()
+    }
+
+    /// Spray on player bounces, or None for no bounce particles
+    pub fn set_bounce(&mut self, config: Option<EmitterConfig>) -> Result<(), String> {
+        if let Some(config) = &config {
+            config.validate()?;
+        }
+        self.bounce = config;
+        Ok(())
+    }
+
+    /// Throw the bounce burst from `point`, its angle turned to face `toward` (the player's center)
+    pub fn bounce(&mut self, point: Pos2, toward: Pos2) {
+        let Some(config) = self.bounce else {
+            return;
+        };
+        let normal = toward - point;
+        let turn = if normal.length_sq() > 0.0 { normal.angle() } else { 0.0 };
+        self.emit(&config, point, turn, self.scaled(config.burst));
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Throw `budget` (0 to 1) of the particles emitters ask for from now on, in a pool that much
+    /// smaller; live particles are left alone
+    pub fn set_budget(&mut self, budget: f32) {
+        self.budget = budget.clamp(0.0, 1.0);
+    }
+
+    /// Give back pool space a past burst grew to (game_trim_memory)
+    pub fn shrink_to_fit(&mut self) {
+        self.particles.shrink_to_fit();
+    }
+
+    /// Anything still moving or about to be emitted
+    pub fn is_active(&self) -> bool {
+        !self.particles.is_empty() || self.emitters.count() > 0
+    }
+
+    pub fn store_previous_positions(&mut self) {
+        for particle in &mut self.particles {
+            particle.prev_position = particle.position;
+        }
+    }
+
+    /// Age and move particles by `delta` seconds, drop the dead ones and let emitters emit
+    pub fn update(&mut self, delta: f32) {
+        self.particles.retain_mut(|p| {
+            p.age += delta;
+            if p.age >= p.lifetime {
+                return false;
+            }
+            let t = p.age / p.lifetime;
+            p.fall += p.style.gravity * delta;
+            p.position += (p.velocity * lerp(1.0, p.style.speed_end, t) + p.fall) * delta;
+            true
+        });
+
+        let mut due = Vec::new();
+        let mut finished = Vec::new();
+        for (id, emitter) in self.emitters.entries_mut() {
+            if emitter.stopped {
+                finished.push(id);
+                continue;
+            }
+            // The last partial step of a timed emitter only emits for the time left
+            let duration = emitter.config.duration;
+            let active = if duration > 0.0 { delta.min(duration - emitter.age) } else { delta };
+            emitter.age += delta;
+            emitter.owed += emitter.config.rate * self.budget * active.max(0.0);
+            let count = emitter.owed.floor();
+            emitter.owed -= count;
+            due.push((emitter.config, emitter.position, count as usize));
+            if emitter.config.duration > 0.0 && emitter.age >= emitter.config.duration {
+                emitter.stopped = true;
+            }
+        }
+        for id in finished {
+            self.emitters.remove(id);
+        }
+        for (config, position, count) in due {
+            self.emit(&config, position, 0.0, count);
+        }
+    }
+
+    /// A burst of `count` cut to the budget, keeping at least one particle
+    fn scaled(&self, count: u32) -> usize {
+        if count == 0 {
+            return 0;
+        }
+        ((count as f32 * self.budget).round() as usize).max(1)
+    }
+
+    /// Throw `count` particles from `position`, with `turn` radians added to the config's angle
+    fn emit(&mut self, config: &EmitterConfig, position: Pos2, turn: f32, count: usize) {
+        let room = ((MAX_PARTICLES as f32 * self.budget) as usize).saturating_sub(self.particles.len());
+        self.dropped += count.saturating_sub(room) as u64;
+        let style = Style {
+            speed_end: config.speed_end,
+            gravity: Vec2::new(config.gravity_x, config.gravity_y),
+            size: [config.size_start, config.size_end],
+            color: [config.color_start, config.color_end].map(sprites::color_from_rgba),
+        };
+        for _ in 0..count.min(room) {
+            let spread = config.spread.min(TAU);
+            let angle = config.angle + turn + self.rng.range(-spread / 2.0, spread / 2.0);
+            let speed = self.rng.range(config.speed_min, config.speed_max);
+            self.particles.push(Particle {
+                position,
+                prev_position: position,
+                velocity: Vec2::angled(angle) * speed,
+                fall: Vec2::ZERO,
+                age: 0.0,
+                lifetime: self.rng.range(config.lifetime_min, config.lifetime_max),
+                style,
+            });
+        }
+    }
+
+    /// Every particle touching `view` (world points) and at least `min_size` across as a colored square
+    /// appended to `mesh` (a single draw call), positions interpolated `alpha` of the way from the previous
+    /// tick. Returns how many were left out
+    pub fn mesh(&self, alpha: f32, view: Rect, min_size: f32, mesh: &mut Mesh) -> u32 {
+        mesh.reserve_triangles(self.particles.len() * 2);
+        mesh.reserve_vertices(self.particles.len() * 4);
+        let mut culled = 0;
+        for p in &self.particles {
+            let t = p.age / p.lifetime;
+            let size = lerp(p.style.size[0], p.style.size[1], t);
+            let center = p.prev_position.lerp(p.position, alpha);
+            let rect = Rect::from_center_size(center, Vec2::splat(size));
+            if size < min_size || !view.intersects(rect) {
+                culled += 1;
+                continue;
+            }
+            let [start, end] = p.style.color;
+            mesh.add_colored_rect(rect, start.lerp_to_gamma(end, t));
+        }
+        culled
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
@@ -0,0 +1,283 @@
+use std::sync::Mutex;
+
+use egui::{Pos2, Rect, Vec2};
+use rapier2d::na::Unit;
+use rapier2d::prelude::{
+    ActiveEvents, BroadPhaseBvh, CCDSolver, CoefficientCombineRule, ColliderBuilder, ColliderHandle, ColliderSet,
+    CollisionEvent, ContactPair, EventHandler, ImpulseJointSet, IntegrationParameters, IslandManager,
+    MultibodyJointSet, NarrowPhase, PhysicsPipeline, Real, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
+    SharedShape, Vector,
+};
+
+use crate::events::Surface;
+
+/// Surfaces of the walls, in the order of PhysicsWorld::walls
+const WALLS: [Surface; 4] = [Surface::Left, Surface::Right, Surface::Top, Surface::Bottom];
+
+/// Dynamic axis-aligned rigid body
+#[derive(Clone, Copy, Debug)]
+pub struct RigidBody {
+    pub position: Pos2,
+    pub velocity: Vec2,
+    pub half_size: Vec2,
+}
+
+/// Contact reported by a step (one per collision)
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub surface: Surface,
+    pub point: Pos2,
+    /// Speed along the contact normal before the bounce
+    pub speed: f32,
+}
+
+/// Rigid-body world on rapier2d: the player is a dynamic box with gravity, damping and restitution,
+/// colliding with static walls (the screen bounds) and level obstacles
+/// The engine owns the player's state; each step loads it into the world and reads it back
+pub struct PhysicsWorld {
+    pub gravity: Vec2,
+    pub restitution: f32,
+    pub linear_damping: f32,
+    pipeline: PhysicsPipeline,
+    parameters: IntegrationParameters,
+    islands: IslandManager,
+    broad_phase: BroadPhaseBvh,
+    narrow_phase: NarrowPhase,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    impulse_joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    ccd: CCDSolver,
+    body: RigidBodyHandle,
+    player: ColliderHandle,
+    half_size: Vec2,
+    /// Half-spaces beyond each edge of the bounds: no speed tunnels through them, and their bounding boxes
+    /// always overlap the player's, so its contact pairs with them (and their buffers) outlive each bounce
+    walls: [ColliderHandle; 4],
+    bounds: Rect,
+    /// Obstacle colliders and the rects they were built for, reused by later steps in order
+    obstacles: Vec<(ColliderHandle, Rect)>,
+    pushes: Pushes,
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let body = bodies.insert(RigidBodyBuilder::dynamic().lock_rotations().can_sleep(false));
+        // Unit mass keeps the solver's impulses exact, so a bounce keeps exactly `restitution` of the speed
+        let player = colliders.insert_with_parent(
+            ColliderBuilder::cuboid(0.5, 0.5)
+                .mass(1.0)
+                .friction(0.0)
+                .friction_combine_rule(CoefficientCombineRule::Min)
+                .restitution_combine_rule(CoefficientCombineRule::Max)
+                .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
+                .contact_force_event_threshold(0.0),
+            body,
+            &mut bodies,
+        );
+        let bounds = Rect::from_min_size(Pos2::ZERO, Vec2::splat(1.0));
+        let walls = WALLS.map(|surface| {
+            let (normal, edge) = wall(surface, bounds);
+            colliders.insert(static_collider(SharedShape::halfspace(normal)).translation(to_vector(edge.to_vec2())))
+        });
+
+        Self {
+            gravity: Vec2::ZERO,
+            restitution: 1.0,
+            linear_damping: 0.0,
+            pipeline: PhysicsPipeline::new(),
+            parameters: IntegrationParameters {
+                // World units are points
+                length_unit: 100.0,
+                // Contacts only once the player overlaps a surface: a speculative contact lets it close
+                // the gap within the bouncing step, keeping less than `restitution` of its speed
+                normalized_prediction_distance: 0.0,
+                ..IntegrationParameters::default()
+            },
+            islands: IslandManager::new(),
+            broad_phase: BroadPhaseBvh::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies,
+            colliders,
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd: CCDSolver::new(),
+            body,
+            player,
+            half_size: Vec2::splat(0.5),
+            walls,
+            bounds,
+            obstacles: Vec::new(),
+            pushes: Pushes { player, pushes: Mutex::new(Vec::new()) },
+        }
+    }
+}
+
+impl PhysicsWorld {
+    /// Advance one body by dt against static colliders, appending contacts
+    pub fn step(
+        &mut self,
+        body: &mut RigidBody,
+        bounds: Rect,
+        obstacles: &[Rect],
+        dt: f32,
+        contacts: &mut Vec<Contact>,
+    ) {
+        self.sync_colliders(body.half_size, bounds, obstacles);
+
+        // Writing through the sets queues a change for the next step (an allocation), so the world is
+        // only told what differs from where its last step left things
+        let position = to_vector(body.position.to_vec2());
+        let velocity = to_vector(body.velocity);
+        // Continuous collision only when the player could pass its own half size in a step; rapier's
+        // CCD pass rebuilds a query tree every step it runs
+        let ccd = body.velocity.length() * dt > body.half_size.min_elem();
+        let current = &self.bodies[self.body];
+        if *current.translation() != position
+            || *current.linvel() != velocity
+            || current.linear_damping() != self.linear_damping
+            || current.is_ccd_enabled() != ccd
+        {
+            let rigid_body = &mut self.bodies[self.body];
+            rigid_body.set_translation(position, true);
+            rigid_body.set_linvel(velocity, true);
+            rigid_body.set_linear_damping(self.linear_damping);
+            rigid_body.enable_ccd(ccd);
+        }
+        if self.colliders[self.player].restitution() != self.restitution {
+            self.colliders[self.player].set_restitution(self.restitution);
+        }
+        self.parameters.dt = dt;
+
+        self.pushes.pushes.lock().expect("pushes lock").clear();
+        self.pipeline.step(
+            &to_vector(self.gravity),
+            &self.parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd,
+            &(),
+            &self.pushes,
+        );
+
+        let rigid_body = &self.bodies[self.body];
+        let velocity = body.velocity;
+        body.position = Pos2::new(rigid_body.translation().x, rigid_body.translation().y);
+        body.velocity = Vec2::new(rigid_body.linvel().x, rigid_body.linvel().y);
+
+        // Contacts the player was moving into at the start of the step, not ones it rests or slides on
+        for push in self.pushes.pushes.lock().expect("pushes lock").iter() {
+            let speed = velocity.dot(push.normal);
+            if speed > 0.0 {
+                let wall = self.walls.iter().position(|&wall| wall == push.collider);
+                let surface = wall.map_or(Surface::Obstacle, |i| WALLS[i]);
+                contacts.push(Contact { surface, point: push.point, speed });
+            }
+        }
+    }
+
+    /// Fit the player, walls and obstacle colliders to this step's shapes, rebuilding only what changed
+    fn sync_colliders(&mut self, half_size: Vec2, bounds: Rect, obstacles: &[Rect]) {
+        if half_size != self.half_size {
+            self.half_size = half_size;
+            self.colliders[self.player].set_shape(SharedShape::cuboid(half_size.x, half_size.y));
+            // A new shape resets the mass properties
+            self.colliders[self.player].set_mass(1.0);
+        }
+
+        if bounds != self.bounds {
+            self.bounds = bounds;
+            for (&handle, surface) in self.walls.iter().zip(WALLS) {
+                self.colliders[handle].set_translation(to_vector(wall(surface, bounds).1.to_vec2()));
+            }
+        }
+
+        for (i, &rect) in obstacles.iter().enumerate() {
+            let shape = || SharedShape::cuboid(rect.width() / 2.0, rect.height() / 2.0);
+            match self.obstacles.get_mut(i) {
+                Some((handle, built)) if *built != rect => {
+                    let collider = &mut self.colliders[*handle];
+                    if built.size() != rect.size() {
+                        collider.set_shape(shape());
+                    }
+                    collider.set_translation(to_vector(rect.center().to_vec2()));
+                    *built = rect;
+                }
+                Some(_) => {}
+                None => {
+                    let collider = static_collider(shape()).translation(to_vector(rect.center().to_vec2()));
+                    self.obstacles.push((self.colliders.insert(collider), rect));
+                }
+            }
+        }
+        for (handle, _) in self.obstacles.drain(obstacles.len().min(self.obstacles.len())..) {
+            self.colliders.remove(handle, &mut self.islands, &mut self.bodies, false);
+        }
+    }
+}
+
+/// Outward normal and a point on the edge of the wall behind `surface`
+fn wall(surface: Surface, bounds: Rect) -> (Unit<Vector<Real>>, Pos2) {
+    match surface {
+        Surface::Left => (Vector::x_axis(), bounds.left_center()),
+        Surface::Right => (-Vector::x_axis(), bounds.right_center()),
+        Surface::Top => (Vector::y_axis(), bounds.center_top()),
+        _ => (-Vector::y_axis(), bounds.center_bottom()),
+    }
+}
+
+fn to_vector(v: Vec2) -> Vector<Real> {
+    Vector::new(v.x, v.y)
+}
+
+fn static_collider(shape: SharedShape) -> ColliderBuilder {
+    ColliderBuilder::new(shape).friction(0.0).restitution(0.0)
+}
+
+/// Where the solver pushed the player off a collider during a step
+struct Push {
+    collider: ColliderHandle,
+    point: Pos2,
+    /// Contact normal, pointing from the player into the collider
+    normal: Vec2,
+}
+
+/// Collects the contacts rapier applied an impulse through, as contact force events
+struct Pushes {
+    player: ColliderHandle,
+    pushes: Mutex<Vec<Push>>,
+}
+
+impl EventHandler for Pushes {
+    fn handle_collision_event(&self, _: &RigidBodySet, _: &ColliderSet, _: CollisionEvent, _: Option<&ContactPair>) {}
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: Real,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        pair: &ContactPair,
+        _total_force_magnitude: Real,
+    ) {
+        let player_first = pair.collider1 == self.player;
+        let pushed = pair.manifolds.iter().filter(|m| m.points.iter().any(|p| p.data.impulse > 0.0));
+        for manifold in pushed.filter(|m| !m.data.solver_contacts.is_empty()) {
+            let contacts = &manifold.data.solver_contacts;
+            let sum = contacts.iter().fold(Vec2::ZERO, |sum, c| sum + Vec2::new(c.point.x, c.point.y));
+            let normal = Vec2::new(manifold.data.normal.x, manifold.data.normal.y);
+            let (collider, normal) = if player_first { (pair.collider2, normal) } else { (pair.collider1, -normal) };
+            self.pushes.lock().expect("pushes lock").push(Push {
+                collider,
+                point: (sum / contacts.len() as f32).to_pos2(),
+                normal,
+            });
+        }
+    }
+}
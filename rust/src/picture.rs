@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use egui::epaint::ClippedShape;
+use egui::{PaintCallback, Painter, Rect, Shape, TextureId};
+use glow::HasContext;
+
+use crate::render_target::RenderTarget;
+use crate::shader::{self, Quad, Resource};
+
+/// Largest picture edge in pixels; bigger pictures are drawn directly every frame
+pub const MAX_PICTURE_SIZE: u32 = 4096;
+
+/// Brackets the shapes of a picture that isn't cached yet, so the renderer can find and rasterize them
+/// Only ever inside a callback shape; composite (or flatten) removes them before egui_glow sees them
+pub struct PictureMarker {
+    pub id: u32,
+    /// Hash of the picture's content; a different key means different pixels
+    pub key: u64,
+    /// Screen points covered (the picture is clipped to them)
+    pub bounds: Rect,
+    begin: bool,
+}
+
+/// Open a picture on `painter`: shapes up to `end` are its content, painted clipped to `bounds`
+pub fn begin(painter: &Painter, id: u32, key: u64, bounds: Rect) {
+    marker(painter, PictureMarker { id, key, bounds, begin: true });
+}
+
+pub fn end(painter: &Painter) {
+    marker(painter, PictureMarker { id: 0, key: 0, bounds: Rect::NOTHING, begin: false });
+}
+
+fn marker(painter: &Painter, marker: PictureMarker) {
+    painter.add(Shape::Callback(PaintCallback { rect: marker.bounds, callback: Arc::new(marker) }));
+}
+
+fn as_marker(shape: &ClippedShape) -> Option<&PictureMarker> {
+    match &shape.shape {
+        Shape::Callback(callback) => callback.callback.downcast_ref::<PictureMarker>(),
+        _ => None,
+    }
+}
+
+/// Replace each marked picture in `shapes` by the texture `rasterize` makes of its content, drawn
+/// under the clip in effect where the picture began; if it returns None, the content stays in place
+pub fn composite(
+    shapes: Vec<ClippedShape>,
+    mut rasterize: impl FnMut(&PictureMarker, &[ClippedShape]) -> Option<CachedPicture>,
+) -> Vec<ClippedShape> {
+    let mut out = Vec::with_capacity(shapes.len());
+    let mut open: Option<(ClippedShape, Vec<ClippedShape>)> = None;
+    for shape in shapes {
+        match (as_marker(&shape).map(|m| m.begin), &mut open) {
+            (Some(true), _) => open = Some((shape, Vec::new())),
+            (Some(false), Some(_)) => {
+                let (begin, content) = open.take().unwrap();
+                let marker = as_marker(&begin).unwrap();
+                match rasterize(marker, &content) {
+                    Some(cached) => out.push(ClippedShape { clip_rect: begin.clip_rect, shape: cached.shape(marker.bounds) }),
+                    None => out.extend(content.into_iter().map(|mut shape| {
+                        shape.clip_rect = shape.clip_rect.intersect(begin.clip_rect);
+                        shape
+                    })),
+                }
+            }
+            (Some(false), None) => {}
+            (None, Some((_, content))) => content.push(shape),
+            (None, None) => out.push(shape),
+        }
+    }
+    out
+}
+
+/// Draw every uncached picture's content directly (captures, or no renderer)
+pub fn flatten(shapes: Vec<ClippedShape>) -> Vec<ClippedShape> {
+    composite(shapes, |_, _| None)
+}
+
+/// A rasterized picture, drawn by a callback rather than an egui mesh: egui's shader decodes
+/// textures as sRGB on GLES 3, but pictures hold the framebuffer's gamma-space pixels as they are
+#[derive(Clone)]
+pub struct CachedPicture {
+    pub texture: TextureId,
+    /// One per raster, so frames drawing it compare equal (egui compares callbacks by pointer)
+    callback: Arc<egui_glow::CallbackFn>,
+}
+
+impl CachedPicture {
+    fn new(texture: TextureId) -> CachedPicture {
+        let callback = egui_glow::CallbackFn::new(move |info, painter| {
+            if let Some(texture) = painter.texture(texture) {
+                unsafe {
+                    shader::callback_viewport(painter.gl(), &info);
+                    shader::with(painter.gl(), |gl| BlitProgram::new(gl), |blit| blit.draw(painter.gl(), texture));
+                }
+            }
+        });
+        CachedPicture { texture, callback: Arc::new(callback) }
+    }
+
+    /// The picture covering `bounds`
+    pub fn shape(&self, bounds: Rect) -> Shape {
+        Shape::Callback(PaintCallback { rect: bounds, callback: self.callback.clone() })
+    }
+}
+
+impl PartialEq for CachedPicture {
+    fn eq(&self, other: &Self) -> bool {
+        self.texture == other.texture
+    }
+}
+
+impl std::fmt::Debug for CachedPicture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.texture.fmt(f)
+    }
+}
+
+/// Draws a premultiplied texture over the whole viewport (set to the callback's rect)
+struct BlitProgram {
+    program: glow::Program,
+    quad: Quad,
+}
+
+const BLIT_FRAGMENT: &str = "uniform sampler2D u_texture;\nVARYING vec2 v_uv;\n\
+    void main() { FRAG_COLOR = TEXTURE(u_texture, v_uv); }\n";
+
+impl BlitProgram {
+    unsafe fn new(gl: &glow::Context) -> Result<BlitProgram, String> {
+        let program = shader::compile(gl, shader::QUAD_VERTEX, BLIT_FRAGMENT)?;
+        Ok(BlitProgram { program, quad: Quad::new(gl)? })
+    }
+
+    /// Blends with egui's (premultiplied) blend state, which is still set inside callbacks
+    unsafe fn draw(&self, gl: &glow::Context, texture: glow::Texture) {
+        gl.use_program(Some(self.program));
+        gl.active_texture(glow::TEXTURE0);
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.uniform_1_i32(gl.get_uniform_location(self.program, "u_texture").as_ref(), 0);
+        self.quad.draw(gl);
+    }
+}
+
+impl Resource for BlitProgram {
+    unsafe fn delete(&self, gl: &glow::Context) {
+        gl.delete_program(self.program);
+        self.quad.delete(gl);
+    }
+}
+
+/// Pixel size of a picture covering `bounds`; None if it's empty or too big to cache
+pub fn pixel_size(bounds: Rect, pixels_per_point: f32) -> Option<[u32; 2]> {
+    let size = (bounds.size() * pixels_per_point).round();
+    if !(size.x >= 1.0 && size.y >= 1.0) || size.x > MAX_PICTURE_SIZE as f32 || size.y > MAX_PICTURE_SIZE as f32 {
+        return None;
+    }
+    Some([size.x as u32, size.y as u32])
+}
+
+struct Cached {
+    key: u64,
+    size: [u32; 2],
+    /// Its texture is registered with the egui painter, which owns it
+    picture: CachedPicture,
+}
+
+/// Rasterized draw-list pictures by host id, kept until the host invalidates them, their content or
+/// size changes, or a submitted list stops using them (like Flutter's raster cache)
+#[derive(Default)]
+pub struct PictureCache {
+    entries: HashMap<u32, Cached>,
+    /// Pictures rendered into textures so far
+    pub rasterized: u64,
+}
+
+impl PictureCache {
+    /// Picture `id` if it was rasterized from `key` at `size` pixels
+    pub fn get(&self, id: u32, key: u64, size: [u32; 2]) -> Option<CachedPicture> {
+        self.entries.get(&id).filter(|c| c.key == key && c.size == size).map(|c| c.picture.clone())
+    }
+
+    /// Render `shapes` (the content of `marker`) into a texture and cache it, replacing what `id` had
+    /// The frame's textures must already be uploaded
+    pub fn rasterize(
+        &mut self,
+        gl: &glow::Context,
+        painter: &mut egui_glow::Painter,
+        ctx: &egui::Context,
+        marker: &PictureMarker,
+        shapes: &[ClippedShape],
+        pixels_per_point: f32,
+    ) -> Result<CachedPicture, String> {
+        let size = pixel_size(marker.bounds, pixels_per_point).ok_or("Picture too small or too big to cache")?;
+        let offset = -marker.bounds.min.to_vec2();
+        let shapes = shapes
+            .iter()
+            .cloned()
+            .map(|mut clipped| {
+                clipped.clip_rect = clipped.clip_rect.translate(offset);
+                clipped.shape.translate(offset);
+                clipped
+            })
+            .collect();
+        let primitives = ctx.tessellate(shapes, pixels_per_point);
+        let texture = unsafe {
+            let target = RenderTarget::new(gl, size[0], size[1])?;
+            target.draw(gl, [0.0; 4], || painter.paint_primitives(size, pixels_per_point, &primitives));
+            target.into_texture(gl)
+        };
+        let picture = CachedPicture::new(painter.register_native_texture(texture));
+        if let Some(old) = self.entries.insert(marker.id, Cached { key: marker.key, size, picture: picture.clone() }) {
+            painter.free_texture(old.picture.texture);
+        }
+        self.rasterized += 1;
+        Ok(picture)
+    }
+
+    /// Drop picture `id`; false if it wasn't cached
+    pub fn invalidate(&mut self, painter: &mut egui_glow::Painter, id: u32) -> bool {
+        self.entries.remove(&id).map(|c| painter.free_texture(c.picture.texture)).is_some()
+    }
+
+    /// Drop every picture; they're rasterized again when next drawn. Returns how many were dropped
+    pub fn clear(&mut self, painter: &mut egui_glow::Painter) -> usize {
+        let dropped = self.entries.len();
+        for (_, cached) in std::mem::take(&mut self.entries) {
+            painter.free_texture(cached.picture.texture);
+        }
+        dropped
+    }
+
+    /// Drop the pictures whose id fails `keep`
+    pub fn retain(&mut self, painter: &mut egui_glow::Painter, keep: impl Fn(u32) -> bool) {
+        self.entries.retain(|&id, cached| {
+            if !keep(id) {
+                painter.free_texture(cached.picture.texture);
+            }
+            keep(id)
+        });
+    }
+}
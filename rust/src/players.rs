@@ -0,0 +1,162 @@
+use egui::{Color32, Pos2, Rect, Vec2};
+
+use crate::engine::{Direction, TouchAction};
+use crate::ffi::strings::NameId;
+use crate::input::gamepad::Gamepads;
+use crate::scene::NodeId;
+
+/// Players a game can have besides the primary one (local multiplayer on one screen)
+pub const MAX_EXTRA_PLAYERS: usize = 7;
+
+/// What steers an extra player besides game_set_player_direction, which always does
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Controller {
+    /// Direction calls only
+    Channel,
+    /// A controller's d-pad or left stick; the primary player no longer follows it
+    Gamepad(u32),
+    /// Touches in this screen region (points): the player heads for the finger while it's down
+    /// Regions tell fingers apart, so each player of a split screen follows its own
+    TouchRegion(Rect),
+}
+
+pub struct Player {
+    pub node: NodeId,
+    pub position: Pos2,
+    pub prev_position: Pos2,
+    pub controller: Controller,
+    pub direction: Direction,
+    /// World point a finger in the player's touch region is holding
+    pub touch_target: Option<Pos2>,
+    pub tint: Color32,
+    /// Loaded texture drawn instead of the built-in player image
+    pub texture: Option<NameId>,
+}
+
+impl Player {
+    pub fn is_moving(&self) -> bool {
+        self.direction != Direction::None || self.touch_target.is_some_and(|target| target != self.position)
+    }
+}
+
+/// Players added with game_add_player, moved in Manual mode alongside the primary player
+#[derive(Default)]
+pub struct Players {
+    players: Vec<Player>,
+}
+
+impl Players {
+    /// Add a player drawn by `node`; false when there are MAX_EXTRA_PLAYERS already
+    pub fn add(&mut self, node: NodeId, position: Pos2, tint: Color32) -> bool {
+        if self.players.len() >= MAX_EXTRA_PLAYERS {
+            return false;
+        }
+        self.players.push(Player {
+            node,
+            position,
+            prev_position: position,
+            controller: Controller::Channel,
+            direction: Direction::None,
+            touch_target: None,
+            tint,
+            texture: None,
+        });
+        true
+    }
+
+    /// Keep only the players `keep` says to (those whose node still exists)
+    pub fn retain(&mut self, keep: impl FnMut(&Player) -> bool) {
+        self.players.retain(keep);
+    }
+
+    pub fn get(&self, node: NodeId) -> Option<&Player> {
+        self.players.iter().find(|p| p.node == node)
+    }
+
+    pub fn get_mut(&mut self, node: NodeId) -> Option<&mut Player> {
+        self.players.iter_mut().find(|p| p.node == node)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Player> {
+        self.players.iter()
+    }
+
+    /// Position of each player node where the last tick left it, the primary one at `primary`
+    /// (see Scene::collect)
+    pub fn positions(&self, primary: (NodeId, Pos2)) -> impl Fn(NodeId) -> Option<Pos2> + '_ {
+        move |node| if node == primary.0 { Some(primary.1) } else { self.get(node).map(|p| p.position) }
+    }
+
+    /// Like positions, `alpha` of the way from the previous tick to the last one
+    pub fn interpolated(&self, primary: (NodeId, Pos2), alpha: f32) -> impl Fn(NodeId) -> Option<Pos2> + '_ {
+        move |node| {
+            if node == primary.0 {
+                return Some(primary.1);
+            }
+            // Players at rest are drawn exactly where they are (see GameEngine::display_list_at)
+            let p = self.get(node)?;
+            Some(if p.prev_position == p.position { p.position } else { p.prev_position.lerp(p.position, alpha) })
+        }
+    }
+
+    pub fn is_moving(&self, gamepads: &Gamepads) -> bool {
+        self.players.iter().any(|p| {
+            p.is_moving() || matches!(p.controller, Controller::Gamepad(pad) if gamepads.pad_movement(pad) != Vec2::ZERO)
+        })
+    }
+
+    /// The player whose touch region holds screen point `screen`, if any: it takes the touch,
+    /// heading for world point `world` until the finger lifts
+    pub fn touch(&mut self, screen: Pos2, world: Pos2, action: TouchAction) -> bool {
+        let Some(player) = self
+            .players
+            .iter_mut()
+            .find(|p| matches!(p.controller, Controller::TouchRegion(region) if region.contains(screen)))
+        else {
+            return false;
+        };
+        player.touch_target = (action != TouchAction::Up).then_some(world);
+        true
+    }
+
+    pub fn store_previous_positions(&mut self) {
+        for player in &mut self.players {
+            player.prev_position = player.position;
+        }
+    }
+
+    /// Move each player `speed` points/s for `delta` seconds the way its controls say, keeping
+    /// its center within `min`..`max`
+    pub fn step(&mut self, delta: f32, speed: f32, gamepads: &Gamepads, min: Pos2, max: Pos2) {
+        let distance = speed * delta;
+        for player in &mut self.players {
+            let pad = match player.controller {
+                Controller::Gamepad(pad) => gamepads.pad_movement(pad),
+                _ => Vec2::ZERO,
+            };
+            // A finger past the edge holds the player at the edge
+            player.touch_target = player.touch_target.map(|target| target.clamp(min, max));
+            let step = if pad != Vec2::ZERO {
+                pad * distance
+            } else if let Some(target) = player.touch_target {
+                let to_target = target - player.position;
+                to_target * (distance / to_target.length()).min(1.0)
+            } else {
+                direction_vector(player.direction) * distance
+            };
+            if step.is_finite() {
+                player.position = (player.position + step).clamp(min, max);
+            }
+        }
+    }
+}
+
+fn direction_vector(direction: Direction) -> Vec2 {
+    match direction {
+        Direction::Up => -Vec2::Y,
+        Direction::Down => Vec2::Y,
+        Direction::Left => -Vec2::X,
+        Direction::Right => Vec2::X,
+        Direction::None => Vec2::ZERO,
+    }
+}
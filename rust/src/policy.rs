@@ -0,0 +1,203 @@
+use std::time::{Duration, Instant};
+
+use ed25519_dalek::{Signature, VerifyingKey};
+
+use crate::rng::Rng;
+use crate::save::SavedRun;
+
+/// Run used dev mode (never leaderboard-eligible)
+pub const RUN_FLAG_DEV_MODE: u32 = 1 << 0;
+/// A direct state mutation was attempted while dev mode was off
+pub const RUN_FLAG_TAMPER_ATTEMPT: u32 = 1 << 1;
+/// Gameplay tuning (physics etc.) changed during the run
+pub const RUN_FLAG_TUNED: u32 = 1 << 2;
+/// A direct state mutation was applied (dev mode)
+pub const RUN_FLAG_STATE_MUTATED: u32 = 1 << 3;
+/// Run was resumed from host-supplied saved state (game_restore_state)
+pub const RUN_FLAG_RESTORED: u32 = 1 << 4;
+
+/// Ed25519 public key (64 hex digits) dev-mode tokens must be signed with, baked in at build time
+/// Only the public half ships in the binary; builds without it can never enable dev mode
+const DEV_PUBLIC_KEY: Option<&str> = option_env!("GAME_DEV_PUBLIC_KEY");
+
+/// Domain separation for the signed message, so a dev token can't be any other signature from the same key
+const DEV_MODE_CONTEXT: &[u8] = b"game_engine dev mode v1\0";
+
+/// Kinds of host calls the policy cares about
+#[derive(Clone, Copy, Debug)]
+pub enum Mutation {
+    /// Directly overwrites player state (debug setters, transforms and tweens on player nodes)
+    PlayerState,
+    /// Changes gameplay tuning mid-run
+    Tuning,
+}
+
+/// Summary of the current run, exported for leaderboard validation
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunSummary {
+    /// Level seed (0 when no level is active)
+    pub seed: u32,
+    /// RUN_FLAG_* bits
+    pub flags: u32,
+    /// Number of rejected mutation attempts
+    pub rejected_mutations: u32,
+    /// Milliseconds since the run started
+    pub elapsed_ms: u64,
+}
+
+/// Guards direct state mutation and records anything that makes a run untrustworthy
+/// Debug builds start in dev mode; release builds need a token signed for this instance's challenge
+pub struct Policy {
+    dev_mode: bool,
+    dev_key: Option<VerifyingKey>,
+    /// Random bytes a dev token must sign, so a captured token doesn't unlock other instances
+    challenge: [u8; 16],
+    flags: u32,
+    rejected: u32,
+    run_started: Instant,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self::new(cfg!(debug_assertions), build_dev_key())
+    }
+}
+
+/// Parse the build-time GAME_DEV_PUBLIC_KEY
+fn build_dev_key() -> Option<VerifyingKey> {
+    let hex = DEV_PUBLIC_KEY.filter(|k| !k.is_empty())?;
+    let key = decode_hex::<32>(hex).and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok());
+    if key.is_none() {
+        log::warn!("Ignoring GAME_DEV_PUBLIC_KEY: not a valid ed25519 public key");
+    }
+    key
+}
+
+fn new_challenge() -> [u8; 16] {
+    let mut challenge = [0u8; 16];
+    if let Err(e) = getrandom::fill(&mut challenge) {
+        log::warn!("No OS randomness for the dev challenge ({}); falling back to the clock", e);
+        let mut rng = Rng::from_time();
+        for chunk in challenge.chunks_mut(8) {
+            chunk.copy_from_slice(&rng.next_u64().to_le_bytes());
+        }
+    }
+    challenge
+}
+
+fn decode_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    let hex = hex.trim().as_bytes();
+    if hex.len() != N * 2 {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (byte, pair) in bytes.iter_mut().zip(hex.chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+impl Policy {
+    pub fn new(dev_mode: bool, dev_key: Option<VerifyingKey>) -> Self {
+        Self {
+            dev_mode,
+            dev_key,
+            challenge: new_challenge(),
+            flags: if dev_mode { RUN_FLAG_DEV_MODE } else { 0 },
+            rejected: 0,
+            run_started: Instant::now(),
+        }
+    }
+
+    /// Challenge a dev token must sign, as lowercase hex
+    pub fn dev_challenge(&self) -> String {
+        self.challenge.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Bytes a dev token signs: the context string followed by the challenge
+    fn dev_message(&self) -> Vec<u8> {
+        [DEV_MODE_CONTEXT, &self.challenge[..]].concat()
+    }
+
+    /// Enable dev mode if the token (128 hex digits) is an ed25519 signature of this instance's
+    /// challenge by the build-time GAME_DEV_PUBLIC_KEY
+    pub fn enable_dev_mode(&mut self, token: &str) -> bool {
+        let Some(key) = self.dev_key else {
+            log::warn!("Dev mode unavailable: no GAME_DEV_PUBLIC_KEY in this build");
+            return self.dev_mode;
+        };
+
+        let verified = decode_hex::<64>(token)
+            .map(|bytes| Signature::from_bytes(&bytes))
+            .is_some_and(|signature| key.verify_strict(&self.dev_message(), &signature).is_ok());
+        if verified {
+            self.dev_mode = true;
+            self.flags |= RUN_FLAG_DEV_MODE;
+            log::info!("Dev mode enabled");
+            true
+        } else {
+            self.flags |= RUN_FLAG_TAMPER_ATTEMPT;
+            self.rejected += 1;
+            log::warn!("Dev mode rejected: invalid signature");
+            false
+        }
+    }
+
+    /// Check whether a mutation is allowed, recording it in the run flags
+    pub fn allow(&mut self, mutation: Mutation) -> bool {
+        match mutation {
+            Mutation::PlayerState => {
+                if self.dev_mode {
+                    self.flags |= RUN_FLAG_STATE_MUTATED;
+                    true
+                } else {
+                    self.flags |= RUN_FLAG_TAMPER_ATTEMPT;
+                    self.rejected += 1;
+                    log::warn!("Rejected {:?} mutation: dev mode is off", mutation);
+                    false
+                }
+            }
+            Mutation::Tuning => {
+                self.flags |= RUN_FLAG_TUNED;
+                true
+            }
+        }
+    }
+
+    /// Start a fresh run (new level); dev mode carries over
+    pub fn start_run(&mut self) {
+        self.flags = if self.dev_mode { RUN_FLAG_DEV_MODE } else { 0 };
+        self.rejected = 0;
+        self.run_started = Instant::now();
+    }
+
+    /// Run bookkeeping for a save
+    pub fn saved_run(&self) -> SavedRun {
+        SavedRun {
+            flags: self.flags,
+            rejected: self.rejected,
+            elapsed_ms: self.run_started.elapsed().as_millis() as u64,
+        }
+    }
+
+    /// Continue a saved run; it stays marked as restored so validators can tell
+    pub fn resume_run(&mut self, run: SavedRun) {
+        self.flags = run.flags | RUN_FLAG_RESTORED;
+        if self.dev_mode {
+            self.flags |= RUN_FLAG_DEV_MODE;
+        }
+        self.rejected = run.rejected;
+        let now = Instant::now();
+        self.run_started = now.checked_sub(Duration::from_millis(run.elapsed_ms)).unwrap_or(now);
+    }
+
+    pub fn summary(&self, seed: u32) -> RunSummary {
+        RunSummary {
+            seed,
+            flags: self.flags,
+            rejected_mutations: self.rejected,
+            elapsed_ms: self.run_started.elapsed().as_millis() as u64,
+        }
+    }
+}
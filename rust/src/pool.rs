@@ -0,0 +1,86 @@
+use egui::epaint::Mesh;
+use egui::{Color32, Rect};
+
+use crate::collision::Contact;
+use crate::display_list::{DisplayList, NodeDraw};
+use crate::material::MaterialDraw;
+use crate::scene::{Affine, DrawItem, NodeId};
+use crate::sprites::SpriteId;
+use crate::tilemap::TileDraw;
+use crate::tween;
+
+/// `vec` emptied and retyped for reuse, keeping its allocation: collecting a vector's own iterator
+/// into one of a same-sized element type is done in place
+pub fn recycle<T, U>(mut vec: Vec<T>) -> Vec<U> {
+    vec.clear();
+    vec.into_iter().map(|_| unreachable!()).collect()
+}
+
+/// Vectors a tick fills with what it reports; empty between ticks, keeping their capacity
+#[derive(Default)]
+pub struct TickScratch {
+    pub contacts: Vec<Contact>,
+    /// Level and tile obstacles near the player (resolve_obstacles, and the Auto mode physics step)
+    pub obstacles: Vec<Rect>,
+    #[cfg(feature = "physics")]
+    pub physics_contacts: Vec<crate::physics::Contact>,
+    pub ended_tweens: Vec<tween::Finished>,
+    pub finished_animations: Vec<NodeId>,
+}
+
+/// Buffers display lists are built in, kept between frames so a steady scene doesn't allocate
+#[derive(Default)]
+pub struct FrameScratch {
+    /// Vectors of display lists already drawn (GameEngine::recycle_display_list)
+    lists: Vec<ListBuffers>,
+    pub visible: Vec<SpriteId>,
+    pub stack: Vec<(NodeId, Affine)>,
+    items: Vec<DrawItem<'static>>,
+}
+
+/// Lists kept for reuse; a frame needs one, plus one held as the last drawn
+const MAX_SPARE_LISTS: usize = 2;
+
+impl FrameScratch {
+    /// Empty vectors for the next display list
+    pub fn list_buffers(&mut self) -> ListBuffers {
+        self.lists.pop().unwrap_or_default()
+    }
+
+    pub fn recycle_list(&mut self, list: DisplayList) {
+        if self.lists.len() < MAX_SPARE_LISTS {
+            self.lists.push(ListBuffers::from(list));
+        }
+    }
+
+    /// Empty buffer for a scene traversal borrowing the scene for `'a`
+    pub fn items<'a>(&mut self) -> Vec<DrawItem<'a>> {
+        recycle(std::mem::take(&mut self.items))
+    }
+
+    pub fn recycle_items(&mut self, items: Vec<DrawItem<'_>>) {
+        self.items = recycle(items);
+    }
+}
+
+/// The vectors of a display list, emptied for the next one to be built in
+#[derive(Default)]
+pub struct ListBuffers {
+    pub tiles: Vec<TileDraw>,
+    pub obstacles: Vec<(Rect, Color32)>,
+    pub sprites: Vec<(SpriteId, Rect, Color32, Option<MaterialDraw>)>,
+    pub nodes: Vec<NodeDraw>,
+    pub particles: Mesh,
+}
+
+impl From<DisplayList> for ListBuffers {
+    fn from(list: DisplayList) -> ListBuffers {
+        let DisplayList { mut tiles, mut obstacles, mut sprites, mut nodes, mut particles, .. } = list;
+        tiles.clear();
+        obstacles.clear();
+        sprites.clear();
+        nodes.clear();
+        particles.clear();
+        ListBuffers { tiles, obstacles, sprites, nodes, particles }
+    }
+}
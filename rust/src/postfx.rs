@@ -0,0 +1,353 @@
+use std::sync::Arc;
+
+use glow::HasContext;
+
+use crate::render_target::RenderTarget;
+use crate::shader::{self, Quad, Resource};
+
+/// Largest color LUT edge (its strip is size² pixels wide)
+pub const MAX_LUT_SIZE: u32 = 64;
+
+/// Effects applied to the scene and HUD before presenting (game_set_post_fx)
+/// All zero is off: frames are painted straight to the surface with no extra passes
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PostFxSettings {
+    /// How much of the blurred highlights is added back (0 = no bloom)
+    pub bloom_intensity: f32,
+    /// Brightness (brightest channel, 0-1) above which pixels glow
+    pub bloom_threshold: f32,
+    /// Blur width; 1 spreads highlights about 8 pixels
+    pub bloom_spread: f32,
+    /// Darkening at the corners (0 = no vignette, 1 = black)
+    pub vignette_strength: f32,
+    /// Where darkening starts, from the center (0) to the corners (1)
+    pub vignette_radius: f32,
+    /// Blend toward the color LUT's colors (0 = off; needs game_set_color_lut)
+    pub lut_intensity: f32,
+}
+
+impl PostFxSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let unit = |name: &str, value: f32| {
+            if (0.0..=1.0).contains(&value) { Ok(()) } else { Err(format!("{} must be within 0-1, got {}", name, value)) }
+        };
+        let positive = |name: &str, value: f32| {
+            if value >= 0.0 && value.is_finite() { Ok(()) } else { Err(format!("{} must be 0 or more, got {}", name, value)) }
+        };
+        positive("bloom_intensity", self.bloom_intensity)?;
+        unit("bloom_threshold", self.bloom_threshold)?;
+        positive("bloom_spread", self.bloom_spread)?;
+        unit("vignette_strength", self.vignette_strength)?;
+        unit("vignette_radius", self.vignette_radius)?;
+        unit("lut_intensity", self.lut_intensity)
+    }
+
+    fn bloom(&self) -> bool {
+        self.bloom_intensity > 0.0
+    }
+}
+
+/// Color grading table: `size` tiles of `size` x `size` pixels side by side, red increasing across
+/// each tile, green down it and blue from tile to tile (the usual unwrapped 3D LUT PNG)
+pub struct ColorLut {
+    size: u32,
+    pixels: Vec<u8>,
+}
+
+impl ColorLut {
+    /// Decode a PNG in that layout
+    pub fn decode(bytes: &[u8]) -> Result<ColorLut, String> {
+        let image = image::load_from_memory(bytes).map_err(|e| e.to_string())?.to_rgba8();
+        let (width, size) = image.dimensions();
+        if !(2..=MAX_LUT_SIZE).contains(&size) || width != size * size {
+            return Err(format!("Color LUT must be N² x N pixels with N in 2-{}, got {}x{}", MAX_LUT_SIZE, width, size));
+        }
+        Ok(ColorLut { size, pixels: image.into_raw() })
+    }
+}
+
+/// Offscreen frame plus the passes that post-process it onto the surface
+/// Captures (game_capture_region, share cards) and pictures render without it
+#[derive(Default)]
+pub struct PostFx {
+    pub settings: PostFxSettings,
+    /// Skipped to save power (the settings are kept for when it resumes)
+    pub suspended: bool,
+    /// Set by the host; uploaded on the next frame
+    lut: Option<Arc<ColorLut>>,
+    /// GL objects, made on first use and remade when the surface size or LUT changes
+    gpu: Option<Gpu>,
+    /// Surface framebuffer while the frame is redirected
+    surface: Option<Option<glow::Framebuffer>>,
+}
+
+struct Gpu {
+    size: [u32; 2],
+    scene: RenderTarget,
+    /// Half resolution, ping-ponged by the blur passes
+    bloom: [RenderTarget; 2],
+    /// Texture and edge size of the uploaded LUT, and the table it came from
+    lut: Option<(glow::Texture, u32, Arc<ColorLut>)>,
+}
+
+impl PostFx {
+    /// Whether any effect is on and not suspended; frames only go through the offscreen target then
+    pub fn enabled(&self) -> bool {
+        let s = &self.settings;
+        !self.suspended && (s.bloom() || s.vignette_strength > 0.0 || (s.lut_intensity > 0.0 && self.lut.is_some()))
+    }
+
+    pub fn set_lut(&mut self, lut: Option<ColorLut>) {
+        self.lut = lut.map(Arc::new);
+    }
+
+    /// Redirect painting into the offscreen target, cleared to `clear_color`
+    /// False (and painting stays on the surface) if effects are off or the target can't be made
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn begin(&mut self, gl: &glow::Context, size: [u32; 2], clear_color: [f32; 4]) -> bool {
+        if !self.enabled() {
+            return false;
+        }
+        if self.gpu.as_ref().is_some_and(|gpu| gpu.size != size) {
+            self.destroy(gl);
+        }
+        if self.gpu.is_none() {
+            match Gpu::new(gl, size) {
+                Ok(gpu) => self.gpu = Some(gpu),
+                Err(e) => {
+                    log::warn!("Post-processing off: {}", e);
+                    self.settings = PostFxSettings::default();
+                    return false;
+                }
+            }
+        }
+        let gpu = self.gpu.as_mut().unwrap();
+        if gpu.lut.as_ref().map(|(_, _, lut)| Arc::as_ptr(lut)) != self.lut.as_ref().map(Arc::as_ptr) {
+            gpu.upload_lut(gl, self.lut.clone());
+        }
+        self.surface = Some(gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING));
+        gpu.scene.bind(gl);
+        egui_glow::painter::clear(gl, size, clear_color);
+        gl.clear(glow::STENCIL_BUFFER_BIT);
+        true
+    }
+
+    /// Run the effects over what was painted since `begin`, onto the surface
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn finish(&mut self, gl: &Arc<glow::Context>) {
+        let (Some(surface), Some(gpu)) = (self.surface.take(), self.gpu.as_ref()) else {
+            return;
+        };
+        gl.disable(glow::BLEND);
+        gl.disable(glow::SCISSOR_TEST);
+        gl.disable(glow::STENCIL_TEST);
+        gl.color_mask(true, true, true, true);
+        let settings = self.settings;
+        let drawn = shader::with(gl, |gl| Programs::new(gl), |programs| {
+            if settings.bloom() {
+                programs.bloom(gl, gpu, &settings);
+            }
+            gl.bind_framebuffer(glow::FRAMEBUFFER, surface);
+            gl.viewport(0, 0, gpu.size[0] as i32, gpu.size[1] as i32);
+            programs.composite(gl, gpu, &settings);
+        });
+        if !drawn {
+            // Nothing reached the surface; paint directly from the next frame on
+            self.settings = PostFxSettings::default();
+        }
+        gl.bind_framebuffer(glow::FRAMEBUFFER, surface);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        gl.use_program(None);
+    }
+
+    /// Free the GL objects (they're made again when needed)
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn destroy(&mut self, gl: &glow::Context) {
+        if let Some(gpu) = self.gpu.take() {
+            gpu.destroy(gl);
+        }
+    }
+}
+
+impl Gpu {
+    unsafe fn new(gl: &glow::Context, size: [u32; 2]) -> Result<Gpu, String> {
+        let half = size.map(|s| s.div_ceil(2));
+        let scene = RenderTarget::new(gl, size[0], size[1])?;
+        let first = RenderTarget::new(gl, half[0], half[1]);
+        let second = RenderTarget::new(gl, half[0], half[1]);
+        match (first, second) {
+            (Ok(first), Ok(second)) => Ok(Gpu { size, scene, bloom: [first, second], lut: None }),
+            (first, second) => {
+                scene.destroy(gl);
+                let mut error = String::new();
+                for target in [first, second] {
+                    match target {
+                        Ok(target) => target.destroy(gl),
+                        Err(e) => error = e,
+                    }
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Replace the LUT texture; RGBA8 rather than an egui texture, which GLES 3 would sample as sRGB
+    unsafe fn upload_lut(&mut self, gl: &glow::Context, lut: Option<Arc<ColorLut>>) {
+        if let Some((texture, _, _)) = self.lut.take() {
+            gl.delete_texture(texture);
+        }
+        let Some(lut) = lut else {
+            return;
+        };
+        let texture = match gl.create_texture() {
+            Ok(texture) => texture,
+            Err(e) => {
+                log::warn!("Color LUT texture: {}", e);
+                return;
+            }
+        };
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            (lut.size * lut.size) as i32,
+            lut.size as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(&lut.pixels),
+        );
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+        self.lut = Some((texture, lut.size, lut));
+    }
+
+    unsafe fn destroy(self, gl: &glow::Context) {
+        self.scene.destroy(gl);
+        for target in self.bloom {
+            target.destroy(gl);
+        }
+        if let Some((texture, _, _)) = self.lut {
+            gl.delete_texture(texture);
+        }
+    }
+}
+
+/// Keeps the pixels brighter than the threshold (scaled by how far over they are), at half size
+const BRIGHT_FRAGMENT: &str = "uniform sampler2D u_source;\nuniform float u_threshold;\nVARYING vec2 v_uv;\n\
+    void main() {\n  vec3 c = TEXTURE(u_source, v_uv).rgb;\n  float b = max(c.r, max(c.g, c.b));\n  \
+    FRAG_COLOR = vec4(c * (max(b - u_threshold, 0.0) / max(b, 0.0001)), 1.0);\n}\n";
+
+/// 9-tap Gaussian along `u_step`, in 5 samples using linear filtering between texels
+const BLUR_FRAGMENT: &str = "uniform sampler2D u_source;\nuniform vec2 u_step;\nVARYING vec2 v_uv;\n\
+    void main() {\n  vec3 c = TEXTURE(u_source, v_uv).rgb * 0.2270270;\n  \
+    c += (TEXTURE(u_source, v_uv + u_step * 1.3846154).rgb + TEXTURE(u_source, v_uv - u_step * 1.3846154).rgb) * 0.3162162;\n  \
+    c += (TEXTURE(u_source, v_uv + u_step * 3.2307692).rgb + TEXTURE(u_source, v_uv - u_step * 3.2307692).rgb) * 0.0702703;\n  \
+    FRAG_COLOR = vec4(c, 1.0);\n}\n";
+
+/// Scene plus bloom, graded through the LUT (blue interpolated between tiles), then vignetted
+const COMPOSITE_FRAGMENT: &str = "uniform sampler2D u_scene;\nuniform sampler2D u_bloom;\nuniform sampler2D u_lut;\n\
+    uniform float u_bloom_intensity;\nuniform float u_lut_intensity;\nuniform float u_lut_size;\n\
+    uniform vec2 u_vignette;\nVARYING vec2 v_uv;\n\
+    vec3 grade(vec3 c) {\n  float n = u_lut_size;\n  float blue = c.b * (n - 1.0);\n  float tile = floor(blue);\n  \
+    vec2 uv = vec2((c.r * (n - 1.0) + 0.5) / (n * n), (c.g * (n - 1.0) + 0.5) / n);\n  \
+    vec3 low = TEXTURE(u_lut, uv + vec2(tile / n, 0.0)).rgb;\n  \
+    vec3 high = TEXTURE(u_lut, uv + vec2(min(tile + 1.0, n - 1.0) / n, 0.0)).rgb;\n  \
+    return mix(low, high, blue - tile);\n}\n\
+    void main() {\n  vec3 c = TEXTURE(u_scene, v_uv).rgb + TEXTURE(u_bloom, v_uv).rgb * u_bloom_intensity;\n  \
+    c = clamp(c, 0.0, 1.0);\n  if (u_lut_intensity > 0.0) {\n    c = mix(c, grade(c), u_lut_intensity);\n  }\n  \
+    float d = length(v_uv - 0.5) * 1.4142136;\n  \
+    c *= 1.0 - u_vignette.x * smoothstep(min(u_vignette.y, 0.999), 1.0, d);\n  \
+    FRAG_COLOR = vec4(c, 1.0);\n}\n";
+
+/// Post-processing shaders, one set per GL context
+struct Programs {
+    bright: glow::Program,
+    blur: glow::Program,
+    composite: glow::Program,
+    quad: Quad,
+}
+
+impl Programs {
+    unsafe fn new(gl: &glow::Context) -> Result<Programs, String> {
+        let mut programs = Vec::new();
+        for fragment in [BRIGHT_FRAGMENT, BLUR_FRAGMENT, COMPOSITE_FRAGMENT] {
+            match shader::compile(gl, shader::QUAD_VERTEX, fragment) {
+                Ok(program) => programs.push(program),
+                Err(e) => {
+                    for program in programs {
+                        gl.delete_program(program);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        let [bright, blur, composite] = programs.try_into().unwrap();
+        Ok(Programs { bright, blur, composite, quad: Quad::new(gl)? })
+    }
+
+    /// Bright pass into bloom[0], then blur it across (into bloom[1]) and back down
+    unsafe fn bloom(&self, gl: &glow::Context, gpu: &Gpu, settings: &PostFxSettings) {
+        let [first, second] = &gpu.bloom;
+        let texel = [1.0 / first.width as f32, 1.0 / first.height as f32].map(|t| t * settings.bloom_spread);
+
+        first.bind(gl);
+        gl.use_program(Some(self.bright));
+        self.sampler(gl, self.bright, "u_source", 0, gpu.scene.texture());
+        gl.uniform_1_f32(gl.get_uniform_location(self.bright, "u_threshold").as_ref(), settings.bloom_threshold);
+        self.quad.draw(gl);
+
+        gl.use_program(Some(self.blur));
+        let u_step = gl.get_uniform_location(self.blur, "u_step");
+        for (source, target, step) in [(first, second, [texel[0], 0.0]), (second, first, [0.0, texel[1]])] {
+            target.bind(gl);
+            self.sampler(gl, self.blur, "u_source", 0, source.texture());
+            gl.uniform_2_f32(u_step.as_ref(), step[0], step[1]);
+            self.quad.draw(gl);
+        }
+    }
+
+    /// Into the bound framebuffer; unused samplers get the scene texture so none is left unbound
+    unsafe fn composite(&self, gl: &glow::Context, gpu: &Gpu, settings: &PostFxSettings) {
+        let program = self.composite;
+        let scene = gpu.scene.texture();
+        let bloom = if settings.bloom() { gpu.bloom[0].texture() } else { scene };
+        let (lut, lut_size) = gpu.lut.as_ref().map_or((scene, 0), |(texture, size, _)| (*texture, *size));
+        let lut_intensity = if lut_size > 0 { settings.lut_intensity } else { 0.0 };
+        let uniform = |name: &str| gl.get_uniform_location(program, name);
+
+        gl.use_program(Some(program));
+        self.sampler(gl, program, "u_scene", 0, scene);
+        self.sampler(gl, program, "u_bloom", 1, bloom);
+        self.sampler(gl, program, "u_lut", 2, lut);
+        gl.uniform_1_f32(uniform("u_bloom_intensity").as_ref(), settings.bloom_intensity);
+        gl.uniform_1_f32(uniform("u_lut_intensity").as_ref(), lut_intensity);
+        gl.uniform_1_f32(uniform("u_lut_size").as_ref(), lut_size as f32);
+        gl.uniform_2_f32(uniform("u_vignette").as_ref(), settings.vignette_strength, settings.vignette_radius);
+        self.quad.draw(gl);
+        gl.active_texture(glow::TEXTURE0);
+    }
+
+    unsafe fn sampler(&self, gl: &glow::Context, program: glow::Program, name: &str, unit: u32, texture: glow::Texture) {
+        gl.active_texture(glow::TEXTURE0 + unit);
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.uniform_1_i32(gl.get_uniform_location(program, name).as_ref(), unit as i32);
+    }
+}
+
+impl Resource for Programs {
+    unsafe fn delete(&self, gl: &glow::Context) {
+        for program in [self.bright, self.blur, self.composite] {
+            gl.delete_program(program);
+        }
+        self.quad.delete(gl);
+    }
+}
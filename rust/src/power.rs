@@ -0,0 +1,77 @@
+/// Thermal states across FFI (game_set_thermal_state), as iOS's ProcessInfo.ThermalState
+/// Android hosts map PowerManager's THERMAL_STATUS_* onto these; never renumber
+pub const THERMAL_NOMINAL: u32 = 0;
+pub const THERMAL_FAIR: u32 = 1;
+pub const THERMAL_SERIOUS: u32 = 2;
+pub const THERMAL_CRITICAL: u32 = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThermalState {
+    #[default]
+    Nominal,
+    Fair,
+    Serious,
+    Critical,
+}
+
+impl ThermalState {
+    pub fn from_raw(raw: u32) -> Option<ThermalState> {
+        match raw {
+            THERMAL_NOMINAL => Some(ThermalState::Nominal),
+            THERMAL_FAIR => Some(ThermalState::Fair),
+            THERMAL_SERIOUS => Some(ThermalState::Serious),
+            THERMAL_CRITICAL => Some(ThermalState::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// What the engine gives up to draw less power
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Degradation {
+    /// Cap on paced frames (game_render_with_timestamp) on top of the host's own; 0 for none
+    pub fps_limit: u32,
+    /// Whether post-processing runs
+    pub post_fx: bool,
+    /// Fraction of the usual particles emitted
+    pub particles: f32,
+}
+
+impl Degradation {
+    pub const NONE: Degradation = Degradation { fps_limit: 0, post_fx: true, particles: 1.0 };
+
+    /// The policy for `state`: shed particles first, then frames and post-processing
+    fn for_state(state: ThermalState) -> Degradation {
+        match state {
+            ThermalState::Nominal => Degradation::NONE,
+            ThermalState::Fair => Degradation { fps_limit: 60, particles: 0.75, ..Degradation::NONE },
+            ThermalState::Serious => Degradation { fps_limit: 30, post_fx: false, particles: 0.5 },
+            ThermalState::Critical => Degradation { fps_limit: 30, post_fx: false, particles: 0.25 },
+        }
+    }
+}
+
+/// Thermal and battery conditions the host reports, and whether the engine scales itself down for them
+pub struct PowerState {
+    pub thermal: ThermalState,
+    /// Battery saver (Android) or Low Power Mode (iOS); degrades like a fair thermal state
+    pub low_power: bool,
+    /// Off, the host manages frame rate and effects itself (game_set_power_scaling)
+    pub scaling: bool,
+}
+
+impl Default for PowerState {
+    fn default() -> Self {
+        PowerState { thermal: ThermalState::Nominal, low_power: false, scaling: true }
+    }
+}
+
+impl PowerState {
+    pub fn degradation(&self) -> Degradation {
+        if !self.scaling {
+            return Degradation::NONE;
+        }
+        let state = if self.low_power { self.thermal.max(ThermalState::Fair) } else { self.thermal };
+        Degradation::for_state(state)
+    }
+}
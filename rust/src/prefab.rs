@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use egui::{Color32, Vec2};
+
+use crate::collision::Shape;
+use crate::json::{self, Value};
+use crate::sprites;
+
+/// Most prefab definitions, so a runaway data file can't grow the table without bound
+pub const MAX_PREFABS: usize = 4096;
+
+/// Template sprite spawned by name (game_spawn_prefab, or objects in a tilemap)
+/// Saves refer to it by name and store only where an instance differs, so changing a definition
+/// changes the instances in existing saves too
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prefab {
+    pub size: Vec2,
+    pub color: Color32,
+    pub velocity: Vec2,
+    /// Tag names, registered when an instance spawns
+    pub tags: Vec<String>,
+    /// Registered shader drawing instances, if it exists when they spawn
+    pub shader: Option<String>,
+    /// Collider shape and offset attached to each instance
+    pub collider: Option<(Shape, Vec2)>,
+}
+
+/// Prefab definitions by name
+#[derive(Default)]
+pub struct Prefabs {
+    prefabs: HashMap<Arc<str>, Prefab>,
+}
+
+impl Prefabs {
+    /// Add the prefabs in a JSON object of name to definition, replacing ones of the same name
+    /// Nothing is added if any definition is invalid; returns how many were loaded
+    ///
+    /// ```json
+    /// {"spike": {"size": [32, 32], "color": "#ff4040", "velocity": [0, 0], "tags": ["hazard"],
+    ///            "shader": "glow", "collider": {"shape": "circle", "size": [24, 24], "offset": [0, 4]}}}
+    /// ```
+    /// Only size is required; unknown keys are ignored so newer data loads in older engines
+    pub fn load(&mut self, text: &str) -> Result<usize, String> {
+        let Value::Object(members) = json::parse(text)? else {
+            return Err("Prefabs must be a JSON object of name to definition".to_string());
+        };
+        let mut parsed = Vec::with_capacity(members.len());
+        for (name, value) in &members {
+            if name.is_empty() {
+                return Err("Prefab names can't be empty".to_string());
+            }
+            parsed.push((name.as_str(), parse(value).map_err(|e| format!("Prefab '{}': {}", name, e))?));
+        }
+        let added = parsed.iter().filter(|(name, _)| !self.prefabs.contains_key(*name)).count();
+        if self.prefabs.len() + added > MAX_PREFABS {
+            return Err(format!("At most {} prefabs", MAX_PREFABS));
+        }
+        for (name, prefab) in parsed {
+            self.prefabs.insert(name.into(), prefab);
+        }
+        Ok(members.len())
+    }
+
+    /// The prefab and its shared name
+    pub fn get(&self, name: &str) -> Option<(&Arc<str>, &Prefab)> {
+        self.prefabs.get_key_value(name)
+    }
+
+    pub fn count(&self) -> usize {
+        self.prefabs.len()
+    }
+}
+
+fn parse(value: &Value) -> Result<Prefab, String> {
+    let vec2 = |key: &str, value: &Value| -> Result<Option<Vec2>, String> {
+        let Some(field) = value.get(key) else {
+            return Ok(None);
+        };
+        match field.as_array() {
+            Some([x, y]) => match (x.as_f32(), y.as_f32()) {
+                (Some(x), Some(y)) if x.is_finite() && y.is_finite() => Ok(Some(Vec2::new(x, y))),
+                _ => Err(format!("'{}' must be two numbers", key)),
+            },
+            _ => Err(format!("'{}' must be two numbers", key)),
+        }
+    };
+
+    let size = vec2("size", value)?.ok_or("'size' is required")?;
+    if size.x <= 0.0 || size.y <= 0.0 {
+        return Err(format!("size {:?} must be positive", size));
+    }
+    let color = match value.get("color") {
+        Some(color) => color.as_str().and_then(parse_color).ok_or("'color' must be \"#rrggbb\" or \"#rrggbbaa\"")?,
+        None => Color32::WHITE,
+    };
+    let tags = match value.get("tags") {
+        Some(tags) => tags
+            .as_array()
+            .and_then(|tags| tags.iter().map(|tag| tag.as_str().map(str::to_string)).collect::<Option<Vec<_>>>())
+            .ok_or("'tags' must be an array of names")?,
+        None => Vec::new(),
+    };
+    let shader = match value.get("shader") {
+        Some(shader) => Some(shader.as_str().ok_or("'shader' must be a name")?.to_string()),
+        None => None,
+    };
+    let collider = match value.get("collider") {
+        Some(collider) => {
+            let shape_size = vec2("size", collider)?.unwrap_or(size);
+            let shape = match collider.get("shape").and_then(Value::as_str) {
+                Some("box") | None => Shape::Box(shape_size),
+                Some("circle") => Shape::Circle(shape_size.x / 2.0),
+                Some(other) => return Err(format!("unknown collider shape '{}'", other)),
+            };
+            shape.validate()?;
+            Some((shape, vec2("offset", collider)?.unwrap_or(Vec2::ZERO)))
+        }
+        None => None,
+    };
+    Ok(Prefab { size, color, velocity: vec2("velocity", value)?.unwrap_or(Vec2::ZERO), tags, shader, collider })
+}
+
+/// "#rrggbb" or "#rrggbbaa"
+fn parse_color(text: &str) -> Option<Color32> {
+    let hex = text.strip_prefix('#').filter(|hex| hex.bytes().all(|c| c.is_ascii_hexdigit()))?;
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        6 => Some(sprites::color_from_rgba(value << 8 | 0xff)),
+        8 => Some(sprites::color_from_rgba(value)),
+        _ => None,
+    }
+}
@@ -0,0 +1,89 @@
+use egui::{Color32, Pos2, Rect, Vec2};
+
+use crate::rng::Rng;
+
+/// Number of obstacles placed in a generated level
+const OBSTACLE_COUNT: usize = 5;
+
+/// Static obstacle in normalized coordinates (0..1 of screen size)
+#[derive(Clone, Debug)]
+pub struct Obstacle {
+    pub center: Pos2,
+    pub size: Vec2,
+    pub color: Color32,
+}
+
+impl Obstacle {
+    /// Obstacle rect in screen pixels
+    pub fn screen_rect(&self, width: f32, height: f32) -> Rect {
+        Rect::from_center_size(
+            Pos2::new(self.center.x * width, self.center.y * height),
+            Vec2::new(self.size.x * width, self.size.y * height),
+        )
+    }
+}
+
+/// Procedurally generated level layout
+/// Positions are normalized so the same seed gives the same layout at any resolution
+#[derive(Clone, Debug)]
+pub struct Level {
+    pub seed: u32,
+    pub player_start: Pos2,
+    pub auto_velocity: Vec2,
+    pub obstacles: Vec<Obstacle>,
+}
+
+/// Generate a level from a seed
+/// Deterministic: the same seed always produces the same level
+pub fn generate(seed: u32) -> Level {
+    let mut rng = Rng::new(seed as u64);
+
+    let player_start = Pos2::new(0.5, 0.5);
+
+    // Auto velocity: fixed speed, seeded direction (avoid near-axis angles)
+    let speed = rng.range(250.0, 350.0);
+    let angle = rng.range(0.2, std::f32::consts::FRAC_PI_2 - 0.2)
+        + (rng.next_u64() % 4) as f32 * std::f32::consts::FRAC_PI_2;
+    let auto_velocity = Vec2::new(angle.cos() * speed, angle.sin() * speed);
+
+    // Keep obstacles away from the player spawn area
+    let spawn_zone = Rect::from_center_size(player_start, Vec2::splat(0.35));
+
+    let mut obstacles = Vec::with_capacity(OBSTACLE_COUNT);
+    let mut attempts = 0;
+    while obstacles.len() < OBSTACLE_COUNT && attempts < 100 {
+        attempts += 1;
+
+        let size = Vec2::new(rng.range(0.08, 0.2), rng.range(0.04, 0.1));
+        let center = Pos2::new(
+            rng.range(size.x / 2.0, 1.0 - size.x / 2.0),
+            rng.range(size.y / 2.0, 1.0 - size.y / 2.0),
+        );
+        let rect = Rect::from_center_size(center, size);
+
+        if rect.intersects(spawn_zone) {
+            continue;
+        }
+        if obstacles
+            .iter()
+            .any(|o: &Obstacle| Rect::from_center_size(o.center, o.size).expand(0.02).intersects(rect))
+        {
+            continue;
+        }
+
+        // Darker than the player tints so obstacles read as background
+        let color = rng.bright_color();
+        obstacles.push(Obstacle {
+            center,
+            size,
+            color: Color32::from_rgb(color.r() / 2, color.g() / 2, color.b() / 2),
+        });
+    }
+
+    Level {
+        seed,
+        player_start,
+        auto_velocity,
+        obstacles,
+    }
+}
@@ -0,0 +1,468 @@
+/// Error-correction level (fraction of codewords recoverable: ~7%, 15%, 25%, 30%)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+pub enum EcLevel {
+    Low = 0,
+    Medium = 1,
+    Quartile = 2,
+    High = 3,
+}
+
+impl From<i32> for EcLevel {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => EcLevel::Low,
+            2 => EcLevel::Quartile,
+            3 => EcLevel::High,
+            _ => EcLevel::Medium,
+        }
+    }
+}
+
+impl EcLevel {
+    /// Two-bit value stored in the format information
+    fn format_bits(self) -> u32 {
+        match self {
+            EcLevel::Low => 1,
+            EcLevel::Medium => 0,
+            EcLevel::Quartile => 3,
+            EcLevel::High => 2,
+        }
+    }
+}
+
+const MAX_VERSION: usize = 40;
+
+/// Error-correction codewords per block, indexed by [EcLevel][version]
+const ECC_CODEWORDS_PER_BLOCK: [[u8; MAX_VERSION + 1]; 4] = [
+    [0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28],
+    [0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+];
+
+/// Error-correction blocks, indexed by [EcLevel][version]
+const NUM_ERROR_CORRECTION_BLOCKS: [[u8; MAX_VERSION + 1]; 4] = [
+    [0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25],
+    [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49],
+    [0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68],
+    [0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81],
+];
+
+/// QR Code symbol (byte mode), modules stored row-major, true = dark
+#[derive(Clone)]
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode bytes with the smallest version that fits; None if the data is too long
+    pub fn encode(data: &[u8], ec_level: EcLevel) -> Option<QrCode> {
+        let ecl = ec_level as usize;
+        let version = (1..=MAX_VERSION).find(|&v| {
+            let count_bits = if v <= 9 { 8 } else { 16 };
+            data.len() < (1 << count_bits) && 4 + count_bits + data.len() * 8 <= num_data_codewords(v, ecl) * 8
+        })?;
+
+        // Mode indicator (byte), character count, payload
+        let mut bits = BitBuffer::default();
+        bits.append(0b0100, 4);
+        bits.append(data.len() as u32, if version <= 9 { 8 } else { 16 });
+        for &byte in data {
+            bits.append(byte as u32, 8);
+        }
+
+        // Terminator, byte alignment, then alternating pad bytes
+        let capacity = num_data_codewords(version, ecl) * 8;
+        bits.append(0, (capacity - bits.len).min(4));
+        bits.append(0, (8 - bits.len % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.len >= capacity {
+                break;
+            }
+            bits.append(pad, 8);
+        }
+
+        let codewords = add_ecc_and_interleave(&bits.bytes, version, ecl);
+        let mut builder = Builder::new(version, ec_level);
+        builder.draw_function_patterns();
+        builder.draw_codewords(&codewords);
+
+        // Pick the mask with the lowest penalty
+        let mut best = (0, i32::MAX);
+        for mask in 0..8 {
+            builder.apply_mask(mask);
+            builder.draw_format_bits(mask);
+            let penalty = builder.penalty();
+            if penalty < best.1 {
+                best = (mask, penalty);
+            }
+            builder.apply_mask(mask); // XOR again to undo
+        }
+        builder.apply_mask(best.0);
+        builder.draw_format_bits(best.0);
+
+        Some(QrCode {
+            size: builder.size,
+            modules: builder.modules,
+        })
+    }
+
+    /// Modules per side (21 for version 1, +4 per version)
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+}
+
+#[derive(Default)]
+struct BitBuffer {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitBuffer {
+    fn append(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if (value >> i) & 1 != 0 {
+                let last = self.bytes.len() - 1;
+                self.bytes[last] |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+/// Modules available for data and ECC after function patterns
+fn num_raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let num_align = version / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn num_data_codewords(version: usize, ecl: usize) -> usize {
+    num_raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[ecl][version] as usize * NUM_ERROR_CORRECTION_BLOCKS[ecl][version] as usize
+}
+
+/// Split data into blocks, append Reed-Solomon ECC to each, and interleave
+fn add_ecc_and_interleave(data: &[u8], version: usize, ecl: usize) -> Vec<u8> {
+    let num_blocks = NUM_ERROR_CORRECTION_BLOCKS[ecl][version] as usize;
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[ecl][version] as usize;
+    let raw_codewords = num_raw_data_modules(version) / 8;
+    let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+    let short_block_len = raw_codewords / num_blocks;
+
+    let divisor = reed_solomon_divisor(ecc_len);
+    let mut blocks = Vec::with_capacity(num_blocks);
+    let mut k = 0;
+    for i in 0..num_blocks {
+        let data_len = short_block_len - ecc_len + usize::from(i >= num_short_blocks);
+        let mut block = data[k..k + data_len].to_vec();
+        k += data_len;
+        let ecc = reed_solomon_remainder(&block, &divisor);
+        if i < num_short_blocks {
+            block.push(0); // Placeholder so all blocks line up; skipped when interleaving
+        }
+        block.extend(ecc);
+        blocks.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..blocks[0].len() {
+        for (j, block) in blocks.iter().enumerate() {
+            if i != short_block_len - ecc_len || j >= num_short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+/// Multiply in GF(2^8) with the QR polynomial 0x11D
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (r, &coef) in result.iter_mut().zip(divisor) {
+            *r ^= gf_multiply(coef, factor);
+        }
+    }
+    result
+}
+
+struct Builder {
+    version: usize,
+    ec_level: EcLevel,
+    size: usize,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl Builder {
+    fn new(version: usize, ec_level: EcLevel) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            version,
+            ec_level,
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        }
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.is_function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        // Timing patterns
+        for i in 0..self.size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        // Finder patterns with separators
+        let far = self.size as i32 - 4;
+        for (cx, cy) in [(3, 3), (far, 3), (3, far)] {
+            for dy in -4..=4 {
+                for dx in -4..=4 {
+                    let (x, y) = (cx + dx, cy + dy);
+                    if (0..self.size as i32).contains(&x) && (0..self.size as i32).contains(&y) {
+                        let dist = dx.abs().max(dy.abs());
+                        self.set_function(x as usize, y as usize, dist != 2 && dist != 4);
+                    }
+                }
+            }
+        }
+
+        // Alignment patterns, skipping the three finder corners
+        let positions = self.alignment_positions();
+        let n = positions.len();
+        for i in 0..n {
+            for j in 0..n {
+                if [(0, 0), (0, n - 1), (n - 1, 0)].contains(&(i, j)) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let x = (positions[i] as i32 + dx) as usize;
+                        let y = (positions[j] as i32 + dy) as usize;
+                        self.set_function(x, y, dx.abs().max(dy.abs()) != 1);
+                    }
+                }
+            }
+        }
+
+        // Reserve format areas (overwritten once the mask is chosen) and draw version info
+        self.draw_format_bits(0);
+        self.draw_version();
+    }
+
+    fn alignment_positions(&self) -> Vec<usize> {
+        if self.version == 1 {
+            return Vec::new();
+        }
+        let num_align = self.version / 7 + 2;
+        let step = if self.version == 32 {
+            26
+        } else {
+            (self.version * 4 + num_align * 2 + 1) / (num_align * 2 - 2) * 2
+        };
+        let mut result = vec![6];
+        let mut pos = self.size - 7;
+        for _ in 0..num_align - 1 {
+            result.insert(1, pos);
+            pos -= step;
+        }
+        result
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = self.ec_level.format_bits() << 3 | mask;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = (data << 10 | rem) ^ 0x5412;
+        let bit = |i: u32| (bits >> i) & 1 != 0;
+
+        // Copy around the top-left finder
+        for i in 0..6 {
+            self.set_function(8, i as usize, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i as usize, 8, bit(i));
+        }
+
+        // Copy split between the other two finders
+        let size = self.size;
+        for i in 0..8 {
+            self.set_function(size - 1 - i as usize, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i as usize, bit(i));
+        }
+        self.set_function(8, size - 8, true); // Always-dark module
+    }
+
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let mut rem = self.version as u32;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = (self.version as u32) << 12 | rem;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Place codewords in the zigzag column-pair order, skipping function modules
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let total_bits = data.len() * 8;
+        let mut i = 0;
+        let mut right = self.size as i32 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5; // Skip the vertical timing column
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..self.size {
+                for j in 0..2 {
+                    let x = (right - j) as usize;
+                    let y = if upward { self.size - 1 - vert } else { vert };
+                    let index = y * self.size + x;
+                    if !self.is_function[index] && i < total_bits {
+                        self.modules[index] = (data[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if invert && !self.is_function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    /// Standard mask penalty: long runs, 2x2 blocks, finder-like patterns, dark balance
+    fn penalty(&self) -> i32 {
+        let size = self.size;
+        let at = |x: usize, y: usize| self.modules[y * size + x];
+        let mut result = 0;
+
+        for transpose in [false, true] {
+            let get = |line: usize, i: usize| if transpose { at(line, i) } else { at(i, line) };
+            for line in 0..size {
+                // Runs of five or more same-colored modules
+                let mut run = 1;
+                for i in 1..size {
+                    if get(line, i) == get(line, i - 1) {
+                        run += 1;
+                        if run == 5 {
+                            result += 3;
+                        } else if run > 5 {
+                            result += 1;
+                        }
+                    } else {
+                        run = 1;
+                    }
+                }
+
+                // 1:1:3:1:1 finder-like patterns with four light modules on either side
+                const PATTERN: [bool; 11] = [true, false, true, true, true, false, true, false, false, false, false];
+                for start in 0..size.saturating_sub(10) {
+                    let forward = (0..11).all(|k| get(line, start + k) == PATTERN[k]);
+                    let backward = (0..11).all(|k| get(line, start + k) == PATTERN[10 - k]);
+                    if forward || backward {
+                        result += 40;
+                    }
+                }
+            }
+        }
+
+        // 2x2 blocks of one color
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let c = at(x, y);
+                if c == at(x + 1, y) && c == at(x, y + 1) && c == at(x + 1, y + 1) {
+                    result += 3;
+                }
+            }
+        }
+
+        // Dark/light balance, 10 points per 5% away from 50%
+        let total = (size * size) as i32;
+        let dark = self.modules.iter().filter(|&&m| m).count() as i32;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        result + k * 10
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::Mutex;
+
+use crate::json::{self, Value};
+
+bitflags::bitflags! {
+    /// Driver workarounds, chosen from the GL vendor/renderer/version strings at init
+    /// Bit values are part of the C API; never renumber
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Quirks: u32 {
+        /// Don't call glFinish after a frame (stalls for several frames on some Mali drivers)
+        /// GPU time in frame stats reads ~0 while set
+        const SKIP_GL_FINISH = 1 << 0;
+        /// Build egui shaders as GLSL ES 1.00 even on an ES 3 context (broken ES 3 shaders on old Adreno)
+        /// Only read when the renderer is created
+        const FORCE_GLES2 = 1 << 1;
+    }
+}
+
+/// Driver strings reported by the GL context
+pub struct DriverInfo {
+    pub vendor: String,
+    pub renderer: String,
+    pub version: String,
+}
+
+/// Known drivers: (vendor, renderer, version) substrings and the quirks they need
+/// Empty strings match anything
+const BUILTIN_RULES: &[(&str, &str, &str, Quirks)] = &[
+    ("ARM", "Mali-T", "", Quirks::SKIP_GL_FINISH),
+    ("ARM", "Mali-G71", "", Quirks::SKIP_GL_FINISH),
+    ("Qualcomm", "Adreno (TM) 3", "", Quirks::FORCE_GLES2),
+];
+
+/// One entry of the runtime quirk database (game_load_quirks)
+struct Rule {
+    vendor: String,
+    renderer: String,
+    version: String,
+    set: Quirks,
+    clear: Quirks,
+}
+
+/// Rules loaded at runtime; applied after the built-ins so they can override them
+static LOADED_RULES: Mutex<Vec<Rule>> = Mutex::new(Vec::new());
+
+/// Case-insensitive substring match; an empty pattern matches anything
+fn matches(value: &str, pattern: &str) -> bool {
+    pattern.is_empty() || value.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+impl DriverInfo {
+    fn matches(&self, vendor: &str, renderer: &str, version: &str) -> bool {
+        matches(&self.vendor, vendor) && matches(&self.renderer, renderer) && matches(&self.version, version)
+    }
+}
+
+/// Quirks for a driver: built-in rules, then loaded rules in order
+pub fn detect(info: &DriverInfo) -> Quirks {
+    let mut quirks = Quirks::empty();
+    for &(vendor, renderer, version, set) in BUILTIN_RULES {
+        if info.matches(vendor, renderer, version) {
+            quirks |= set;
+        }
+    }
+    let loaded = LOADED_RULES.lock().unwrap_or_else(|e| e.into_inner());
+    for rule in loaded.iter() {
+        if info.matches(&rule.vendor, &rule.renderer, &rule.version) {
+            quirks = (quirks | rule.set) - rule.clear;
+        }
+    }
+    quirks
+}
+
+/// Replace the loaded rules with a JSON database:
+/// { "rules": [ { "vendor": "ARM", "renderer": "Mali-G52", "version": "r20",
+///                "set": ["skip_gl_finish"], "clear": ["force_gles2"] } ] }
+/// Match fields are optional substrings. Returns the number of rules
+pub fn load_rules(text: &str) -> Result<usize, String> {
+    let root = json::parse(text)?;
+    let entries = root
+        .get("rules")
+        .and_then(Value::as_array)
+        .ok_or("Quirk database has no rules array")?;
+
+    let mut rules = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        let pattern = |key: &str| match entry.get(key) {
+            None => Ok(String::new()),
+            Some(value) => value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| format!("Rule {}: '{}' must be a string", i, key)),
+        };
+        let flags = |key: &str| -> Result<Quirks, String> {
+            let Some(value) = entry.get(key) else {
+                return Ok(Quirks::empty());
+            };
+            let names = value.as_array().ok_or_else(|| format!("Rule {}: '{}' must be an array", i, key))?;
+            names.iter().try_fold(Quirks::empty(), |quirks, name| {
+                // Flag names in lower case ("skip_gl_finish")
+                let name = name.as_str().unwrap_or_default();
+                Quirks::from_name(&name.to_uppercase())
+                    .map(|quirk| quirks | quirk)
+                    .ok_or_else(|| format!("Rule {}: unknown quirk '{}'", i, name))
+            })
+        };
+        rules.push(Rule {
+            vendor: pattern("vendor")?,
+            renderer: pattern("renderer")?,
+            version: pattern("version")?,
+            set: flags("set")?,
+            clear: flags("clear")?,
+        });
+    }
+
+    let count = rules.len();
+    *LOADED_RULES.lock().unwrap_or_else(|e| e.into_inner()) = rules;
+    Ok(count)
+}
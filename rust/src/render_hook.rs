@@ -0,0 +1,158 @@
+use std::ffi::c_void;
+
+use glow::HasContext;
+
+use crate::gl_debug;
+
+/// Host GL drawing injected into a frame: called on the render thread with the engine's target bound
+/// (the surface, or the offscreen scene when post effects are on) and its viewport covering it
+/// `stage` is RENDER_STAGE_*, `width` / `height` the target's size in pixels
+pub type RenderHook = unsafe extern "C" fn(user_data: *mut c_void, stage: u32, width: u32, height: u32);
+
+/// After the background is cleared, before obstacles, sprites and the scene
+pub const RENDER_STAGE_BEFORE_WORLD: u32 = 0;
+/// After the scene and particles, before the HUD
+pub const RENDER_STAGE_AFTER_WORLD: u32 = 1;
+/// After the HUD, before post effects and the debug overlay
+pub const RENDER_STAGE_AFTER_HUD: u32 = 2;
+
+const STAGE_COUNT: usize = 3;
+
+const STAGE_LABELS: [&str; STAGE_COUNT] =
+    ["game_engine/hook/before_world", "game_engine/hook/after_world", "game_engine/hook/after_hud"];
+
+#[derive(Clone, Copy)]
+struct Hook {
+    function: RenderHook,
+    /// Host pointer, passed back untouched
+    user_data: *mut c_void,
+}
+
+/// One host hook per stage of presented frames (captures and share cards don't call them)
+#[derive(Default)]
+pub struct RenderHooks {
+    hooks: [Option<Hook>; STAGE_COUNT],
+}
+
+impl RenderHooks {
+    /// Install `function` at `stage`, replacing the one there; None removes it
+    /// False for an unknown stage
+    pub fn set(&mut self, stage: u32, function: Option<RenderHook>, user_data: *mut c_void) -> bool {
+        let Some(slot) = self.hooks.get_mut(stage as usize) else {
+            return false;
+        };
+        *slot = function.map(|function| Hook { function, user_data });
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.iter().all(Option::is_none)
+    }
+
+    /// Call the hook at `stage`, if any, with the GL state the engine relies on saved around it
+    /// # Safety
+    /// On the thread `gl` is current on; the hook's user_data must still be valid
+    pub unsafe fn run(&self, gl: &glow::Context, stage: u32, size: [u32; 2]) {
+        let Some(hook) = self.hooks[stage as usize] else {
+            return;
+        };
+        gl_debug::group(gl, STAGE_LABELS[stage as usize], || {
+            let state = GlState::save(gl);
+            (hook.function)(hook.user_data, stage, size[0], size[1]);
+            let error = gl.get_error();
+            if error != glow::NO_ERROR {
+                log::warn!("Render hook at stage {} left GL error {:#x}", stage, error);
+            }
+            state.restore(gl);
+        });
+    }
+}
+
+/// Pipeline state a host's GL calls are likely to change and the engine's passes (egui, post effects,
+/// video capture) expect to find as they left it
+struct GlState {
+    framebuffer: Option<glow::Framebuffer>,
+    viewport: [i32; 4],
+    scissor_box: [i32; 4],
+    /// SCISSOR_TEST, BLEND, DEPTH_TEST, CULL_FACE, STENCIL_TEST, DITHER
+    capabilities: [(u32, bool); 6],
+    /// Source and destination factors for RGB and alpha
+    blend_func: [u32; 4],
+    blend_equation: [u32; 2],
+    color_mask: [bool; 4],
+    program: Option<glow::Program>,
+    active_texture: u32,
+    /// Bound to TEXTURE0, where egui samples from
+    texture: Option<glow::Texture>,
+    array_buffer: Option<glow::Buffer>,
+    /// Only queried with VAOs (ES 3); ES 2 keeps the element buffer as global state
+    vertex_array: Option<Option<glow::VertexArray>>,
+    element_buffer: Option<glow::Buffer>,
+    unpack_alignment: i32,
+}
+
+impl GlState {
+    unsafe fn save(gl: &glow::Context) -> GlState {
+        let mut viewport = [0; 4];
+        gl.get_parameter_i32_slice(glow::VIEWPORT, &mut viewport);
+        let mut scissor_box = [0; 4];
+        gl.get_parameter_i32_slice(glow::SCISSOR_BOX, &mut scissor_box);
+        let capabilities = [glow::SCISSOR_TEST, glow::BLEND, glow::DEPTH_TEST, glow::CULL_FACE, glow::STENCIL_TEST, glow::DITHER]
+            .map(|capability| (capability, gl.is_enabled(capability)));
+        let blend_func = [glow::BLEND_SRC_RGB, glow::BLEND_DST_RGB, glow::BLEND_SRC_ALPHA, glow::BLEND_DST_ALPHA]
+            .map(|parameter| gl.get_parameter_i32(parameter) as u32);
+        let blend_equation =
+            [glow::BLEND_EQUATION_RGB, glow::BLEND_EQUATION_ALPHA].map(|parameter| gl.get_parameter_i32(parameter) as u32);
+        let active_texture = gl.get_parameter_i32(glow::ACTIVE_TEXTURE) as u32;
+        gl.active_texture(glow::TEXTURE0);
+        let texture = gl.get_parameter_texture(glow::TEXTURE_BINDING_2D);
+        gl.active_texture(active_texture);
+        GlState {
+            framebuffer: gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING),
+            viewport,
+            scissor_box,
+            capabilities,
+            blend_func,
+            blend_equation,
+            color_mask: gl.get_parameter_bool_array::<4>(glow::COLOR_WRITEMASK),
+            program: gl.get_parameter_program(glow::CURRENT_PROGRAM),
+            active_texture,
+            texture,
+            array_buffer: gl.get_parameter_buffer(glow::ARRAY_BUFFER_BINDING),
+            vertex_array: (gl.version().major >= 3).then(|| gl.get_parameter_vertex_array(glow::VERTEX_ARRAY_BINDING)),
+            element_buffer: gl.get_parameter_buffer(glow::ELEMENT_ARRAY_BUFFER_BINDING),
+            unpack_alignment: gl.get_parameter_i32(glow::UNPACK_ALIGNMENT),
+        }
+    }
+
+    unsafe fn restore(&self, gl: &glow::Context) {
+        gl.bind_framebuffer(glow::FRAMEBUFFER, self.framebuffer);
+        let [x, y, width, height] = self.viewport;
+        gl.viewport(x, y, width, height);
+        let [x, y, width, height] = self.scissor_box;
+        gl.scissor(x, y, width, height);
+        for (capability, enabled) in self.capabilities {
+            if enabled {
+                gl.enable(capability);
+            } else {
+                gl.disable(capability);
+            }
+        }
+        let [src_rgb, dst_rgb, src_alpha, dst_alpha] = self.blend_func;
+        gl.blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha);
+        gl.blend_equation_separate(self.blend_equation[0], self.blend_equation[1]);
+        let [r, g, b, a] = self.color_mask;
+        gl.color_mask(r, g, b, a);
+        gl.use_program(self.program);
+        gl.active_texture(glow::TEXTURE0);
+        gl.bind_texture(glow::TEXTURE_2D, self.texture);
+        gl.active_texture(self.active_texture);
+        if let Some(vertex_array) = self.vertex_array {
+            gl.bind_vertex_array(vertex_array);
+        }
+        // Element buffer binding belongs to the VAO restored above
+        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, self.element_buffer);
+        gl.bind_buffer(glow::ARRAY_BUFFER, self.array_buffer);
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, self.unpack_alignment);
+    }
+}
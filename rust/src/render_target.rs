@@ -0,0 +1,129 @@
+use glow::HasContext;
+
+/// Offscreen RGBA8 texture with a stencil buffer (for clip masks) that egui primitives can be painted into
+pub struct RenderTarget {
+    pub width: u32,
+    pub height: u32,
+    framebuffer: glow::Framebuffer,
+    texture: glow::Texture,
+    stencil: Option<glow::Renderbuffer>,
+}
+
+impl RenderTarget {
+    /// Allocate a `width` x `height` target; fails if the driver can't make it complete
+    /// # Safety
+    /// On the thread `gl` is current on; leaves the framebuffer binding unchanged
+    pub unsafe fn new(gl: &glow::Context, width: u32, height: u32) -> Result<RenderTarget, String> {
+        let prev_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        let texture = gl.create_texture()?;
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            width as i32,
+            height as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            None,
+        );
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+
+        let framebuffer = match gl.create_framebuffer() {
+            Ok(fb) => fb,
+            Err(e) => {
+                gl.delete_texture(texture);
+                return Err(e);
+            }
+        };
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(texture), 0);
+        let stencil = gl.create_renderbuffer().ok();
+        if let Some(stencil) = stencil {
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(stencil));
+            gl.renderbuffer_storage(glow::RENDERBUFFER, glow::STENCIL_INDEX8, width as i32, height as i32);
+            gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::STENCIL_ATTACHMENT, glow::RENDERBUFFER, Some(stencil));
+            gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+        }
+        let complete = gl.check_framebuffer_status(glow::FRAMEBUFFER) == glow::FRAMEBUFFER_COMPLETE;
+        gl.bind_framebuffer(glow::FRAMEBUFFER, prev_framebuffer);
+
+        let target = RenderTarget { width, height, framebuffer, texture, stencil };
+        if !complete {
+            target.destroy(gl);
+            return Err("Offscreen framebuffer incomplete".to_string());
+        }
+        Ok(target)
+    }
+
+    /// Run `f` with this target bound, cleared to `clear_color` and with the viewport covering it,
+    /// then restore the caller's framebuffer binding and viewport
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn draw<T>(&self, gl: &glow::Context, clear_color: [f32; 4], f: impl FnOnce() -> T) -> T {
+        let prev_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        let mut prev_viewport = [0i32; 4];
+        gl.get_parameter_i32_slice(glow::VIEWPORT, &mut prev_viewport);
+
+        self.bind(gl);
+        egui_glow::painter::clear(gl, [self.width, self.height], clear_color);
+        gl.clear(glow::STENCIL_BUFFER_BIT);
+        let result = f();
+
+        gl.bind_framebuffer(glow::FRAMEBUFFER, prev_framebuffer);
+        gl.viewport(prev_viewport[0], prev_viewport[1], prev_viewport[2], prev_viewport[3]);
+        result
+    }
+
+    /// Make this the framebuffer drawn to, with the viewport covering it
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn bind(&self, gl: &glow::Context) {
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+        gl.viewport(0, 0, self.width as i32, self.height as i32);
+    }
+
+    /// Its color texture (rows bottom first), for sampling while another target is bound
+    pub fn texture(&self) -> glow::Texture {
+        self.texture
+    }
+
+    /// RGBA8 pixels, bottom row first (as GL reads them); call from within draw
+    /// # Safety
+    /// On the thread `gl` is current on, with this target bound
+    pub unsafe fn read_pixels(&self, gl: &glow::Context) -> Vec<u8> {
+        let mut pixels = vec![0u8; (self.width * self.height * 4) as usize];
+        gl.read_pixels(
+            0,
+            0,
+            self.width as i32,
+            self.height as i32,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelPackData::Slice(&mut pixels),
+        );
+        pixels
+    }
+
+    /// Free the framebuffer and keep the texture (rows bottom first), e.g. to register with egui
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn into_texture(self, gl: &glow::Context) -> glow::Texture {
+        gl.delete_framebuffer(self.framebuffer);
+        if let Some(stencil) = self.stencil {
+            gl.delete_renderbuffer(stencil);
+        }
+        self.texture
+    }
+
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn destroy(self, gl: &glow::Context) {
+        let texture = self.into_texture(gl);
+        gl.delete_texture(texture);
+    }
+}
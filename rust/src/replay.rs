@@ -0,0 +1,220 @@
+use egui::Vec2;
+
+use crate::engine::{Direction, GameMode, TouchAction};
+use crate::input::gamepad::Control;
+use crate::input::sensors::SensorType;
+use crate::save::Reader;
+
+/// First bytes of every recording
+const MAGIC: &[u8; 4] = b"GREP";
+
+/// Format version after the magic; bump when the layout changes
+pub const REPLAY_VERSION: u8 = 1;
+
+/// Host input that changes the simulation, as recorded and replayed
+/// Touch coordinates are in points, so a replay doesn't depend on the recording device's scale
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameCommand {
+    SetDirection(Direction),
+    SetMode(GameMode),
+    Touch { x: f32, y: f32, action: TouchAction },
+    Gamepad { pad: u32, control: Control, value: f32 },
+    GamepadDisconnected { pad: u32 },
+    Sensor { sensor: SensorType, sample: [f32; 3], timestamp: u64 },
+    StartLevel { seed: u32 },
+    /// Release velocity (points/s) of a player let go mid-drag
+    Throw { vx: f32, vy: f32 },
+}
+
+impl GameCommand {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match *self {
+            GameCommand::SetDirection(direction) => out.extend_from_slice(&[0, direction as u8]),
+            GameCommand::SetMode(mode) => out.extend_from_slice(&[1, mode as u8]),
+            GameCommand::Touch { x, y, action } => {
+                out.extend_from_slice(&[2, action as u8]);
+                out.extend_from_slice(&x.to_le_bytes());
+                out.extend_from_slice(&y.to_le_bytes());
+            }
+            GameCommand::Gamepad { pad, control, value } => {
+                out.extend_from_slice(&[3, control as u8]);
+                out.extend_from_slice(&pad.to_le_bytes());
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            GameCommand::GamepadDisconnected { pad } => {
+                out.push(4);
+                out.extend_from_slice(&pad.to_le_bytes());
+            }
+            GameCommand::Sensor { sensor, sample, timestamp } => {
+                out.extend_from_slice(&[5, sensor as u8]);
+                for value in sample {
+                    out.extend_from_slice(&value.to_le_bytes());
+                }
+                out.extend_from_slice(&timestamp.to_le_bytes());
+            }
+            GameCommand::StartLevel { seed } => {
+                out.push(6);
+                out.extend_from_slice(&seed.to_le_bytes());
+            }
+            GameCommand::Throw { vx, vy } => {
+                out.push(7);
+                out.extend_from_slice(&vx.to_le_bytes());
+                out.extend_from_slice(&vy.to_le_bytes());
+            }
+        }
+    }
+
+    fn decode(reader: &mut Reader) -> Result<GameCommand, String> {
+        let kind = reader.u8()?;
+        Ok(match kind {
+            0 => GameCommand::SetDirection(Direction::from(reader.u8()? as i32)),
+            1 => GameCommand::SetMode(GameMode::from(reader.u8()? as i32)),
+            2 => {
+                let action = reader.u8()?;
+                if action > TouchAction::Move as u8 {
+                    return Err(format!("Unknown touch action {}", action));
+                }
+                let (x, y) = (reader.f32()?, reader.f32()?);
+                GameCommand::Touch { x, y, action: TouchAction::from(action as i32) }
+            }
+            3 => {
+                let raw = reader.u8()?;
+                let control = Control::from_raw(raw as i32).ok_or_else(|| format!("Unknown control {}", raw))?;
+                let (pad, value) = (reader.u32()?, reader.f32()?);
+                GameCommand::Gamepad { pad, control, value }
+            }
+            4 => GameCommand::GamepadDisconnected { pad: reader.u32()? },
+            5 => {
+                let raw = reader.u8()?;
+                let sensor = SensorType::from_raw(raw as i32).ok_or_else(|| format!("Unknown sensor {}", raw))?;
+                let sample = [reader.f32()?, reader.f32()?, reader.f32()?];
+                GameCommand::Sensor { sensor, sample, timestamp: reader.u64()? }
+            }
+            6 => GameCommand::StartLevel { seed: reader.u32()? },
+            7 => GameCommand::Throw { vx: reader.f32()?, vy: reader.f32()? },
+            _ => return Err(format!("Unknown command kind {}", kind)),
+        })
+    }
+}
+
+/// A recorded session: the state it started from and every command with its tick
+pub struct Recording {
+    /// Fixed tick rate during recording; replays run at the same rate
+    pub tick_rate: u32,
+    /// Screen size in points; walls and clamps depend on it
+    pub screen: Vec2,
+    /// save_state bytes taken when recording started
+    pub snapshot: Vec<u8>,
+    /// Ticks run while recording
+    pub ticks: u64,
+    /// (tick index since the start, command) in arrival order
+    /// A command with index n arrived before tick n ran
+    pub commands: Vec<(u64, GameCommand)>,
+}
+
+impl Recording {
+    /// Little-endian binary encoding, starting with MAGIC and REPLAY_VERSION
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + self.snapshot.len() + self.commands.len() * 24);
+        out.extend_from_slice(MAGIC);
+        out.push(REPLAY_VERSION);
+        out.extend_from_slice(&self.tick_rate.to_le_bytes());
+        out.extend_from_slice(&self.screen.x.to_le_bytes());
+        out.extend_from_slice(&self.screen.y.to_le_bytes());
+        out.extend_from_slice(&(self.snapshot.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.snapshot);
+        out.extend_from_slice(&self.ticks.to_le_bytes());
+        out.extend_from_slice(&(self.commands.len() as u32).to_le_bytes());
+        for (tick, command) in &self.commands {
+            out.extend_from_slice(&tick.to_le_bytes());
+            command.encode(&mut out);
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Recording, String> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(4)? != MAGIC {
+            return Err("Not a replay".to_string());
+        }
+        let version = reader.u8()?;
+        if version != REPLAY_VERSION {
+            return Err(format!("Unsupported replay version {}", version));
+        }
+
+        let tick_rate = reader.u32()?;
+        let screen = Vec2::new(reader.f32()?, reader.f32()?);
+        let snapshot_len = reader.u32()? as usize;
+        let snapshot = reader.take(snapshot_len)?.to_vec();
+        let ticks = reader.u64()?;
+
+        let count = reader.count()?;
+        let mut commands = Vec::with_capacity(count.min(1024) as usize);
+        let mut last_tick = 0;
+        for _ in 0..count {
+            let tick = reader.u64()?;
+            if tick < last_tick || tick > ticks {
+                return Err(format!("Command at tick {} is out of order", tick));
+            }
+            last_tick = tick;
+            commands.push((tick, GameCommand::decode(&mut reader)?));
+        }
+
+        if !reader.is_at_end() {
+            return Err("Trailing bytes after replay".to_string());
+        }
+        Ok(Recording { tick_rate, screen, snapshot, ticks, commands })
+    }
+}
+
+/// A recording in progress, counting ticks from where it started
+pub struct Recorder {
+    start_tick: u64,
+    recording: Recording,
+}
+
+impl Recorder {
+    pub fn new(start_tick: u64, tick_rate: u32, screen: Vec2, snapshot: Vec<u8>) -> Recorder {
+        let recording = Recording { tick_rate, screen, snapshot, ticks: 0, commands: Vec::new() };
+        Recorder { start_tick, recording }
+    }
+
+    /// Log a command that arrived before tick `tick` runs
+    pub fn push(&mut self, tick: u64, command: GameCommand) {
+        self.recording.commands.push((tick - self.start_tick, command));
+    }
+
+    /// Close the recording after tick `tick`
+    pub fn finish(mut self, tick: u64) -> Recording {
+        self.recording.ticks = tick - self.start_tick;
+        self.recording
+    }
+}
+
+/// A recording being played back
+pub struct Playback {
+    start_tick: u64,
+    recording: Recording,
+    next: usize,
+}
+
+impl Playback {
+    pub fn new(start_tick: u64, recording: Recording) -> Playback {
+        Playback { start_tick, recording, next: 0 }
+    }
+
+    /// Next command due before tick `tick` runs
+    pub fn due(&mut self, tick: u64) -> Option<GameCommand> {
+        let &(at, command) = self.recording.commands.get(self.next)?;
+        if at > tick - self.start_tick {
+            return None;
+        }
+        self.next += 1;
+        Some(command)
+    }
+
+    /// True once `tick` ticks past the start cover the recording
+    pub fn is_finished(&self, tick: u64) -> bool {
+        tick - self.start_tick >= self.recording.ticks
+    }
+}
@@ -0,0 +1,159 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// One entry; `sequence` says whose turn it is: its position when free for the producer claiming
+/// that position, position + 1 once written, position + capacity once read again
+struct Slot<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Bounded lock-free queue (Vyukov's): producers claim a position with one compare-exchange and
+/// never wait on each other or the consumer; a full queue hands the value back instead
+pub struct RingQueue<T> {
+    slots: Box<[Slot<T>]>,
+    /// capacity - 1 (capacity is a power of two)
+    mask: usize,
+    /// Next position to write
+    tail: AtomicUsize,
+    /// Next position to read
+    head: AtomicUsize,
+}
+
+// A value is written by the producer that claimed its slot and read by the consumer that claimed it
+unsafe impl<T: Send> Send for RingQueue<T> {}
+unsafe impl<T: Send> Sync for RingQueue<T> {}
+
+impl<T> RingQueue<T> {
+    /// Queue holding `capacity` values, rounded up to a power of two
+    pub fn new(capacity: usize) -> RingQueue<T> {
+        let capacity = capacity.max(2).next_power_of_two();
+        let slots = (0..capacity)
+            .map(|i| Slot { sequence: AtomicUsize::new(i), value: UnsafeCell::new(MaybeUninit::uninit()) })
+            .collect();
+        RingQueue { slots, mask: capacity - 1, tail: AtomicUsize::new(0), head: AtomicUsize::new(0) }
+    }
+
+    /// Append `value`, or hand it back if the queue is full
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let mut position = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[position & self.mask];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            match sequence.wrapping_sub(position) as isize {
+                0 => match self.tail.compare_exchange_weak(position, position.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.sequence.store(position.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => position = current,
+                },
+                // Not read since the last lap: full
+                lag if lag < 0 => return Err(value),
+                // Another producer took this position
+                _ => position = self.tail.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    /// The oldest value, if any
+    pub fn pop(&self) -> Option<T> {
+        let mut position = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[position & self.mask];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            match sequence.wrapping_sub(position.wrapping_add(1)) as isize {
+                0 => match self.head.compare_exchange_weak(position, position.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        let value = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.sequence.store(position.wrapping_add(self.mask + 1), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => position = current,
+                },
+                // Not written yet: empty
+                lag if lag < 0 => return None,
+                _ => position = self.head.load(Ordering::Relaxed),
+            }
+        }
+    }
+}
+
+impl<T> Drop for RingQueue<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn is_bounded_and_keeps_each_producers_order() {
+        let queue = RingQueue::new(3);
+        for i in 0..4 {
+            assert_eq!(queue.push(i), Ok(()));
+        }
+        // Rounded up to 4; a full queue hands the value back
+        assert_eq!(queue.push(4), Err(4));
+        assert_eq!(queue.pop(), Some(0));
+        assert_eq!(queue.push(4), Ok(()));
+        assert_eq!(std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+        let queue = Arc::new(RingQueue::new(64));
+        let producers: Vec<_> = (0..4u32)
+            .map(|producer| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    for i in 0..1000 {
+                        while queue.push((producer, i)).is_err() {
+                            thread::yield_now();
+                        }
+                    }
+                })
+            })
+            .collect();
+        let mut next = [0; 4];
+        while next.iter().any(|&n| n < 1000) {
+            if let Some((producer, i)) = queue.pop() {
+                assert_eq!(i, next[producer as usize]);
+                next[producer as usize] += 1;
+            }
+        }
+        producers.into_iter().for_each(|p| p.join().unwrap());
+        assert_eq!(queue.pop(), None);
+
+        // Values still queued are dropped with the queue
+        let value = Arc::new(());
+        let queue = RingQueue::new(2);
+        queue.push(value.clone()).unwrap();
+        drop(queue);
+        assert_eq!(Arc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn positions_wrap_past_usize_max() {
+        // A queue that has already carried usize::MAX - 1 values
+        let queue = RingQueue::new(4);
+        let start = usize::MAX - 1;
+        for i in 0..4 {
+            let position = start.wrapping_add(i);
+            queue.slots[position & queue.mask].sequence.store(position, Ordering::Relaxed);
+        }
+        queue.tail.store(start, Ordering::Relaxed);
+        queue.head.store(start, Ordering::Relaxed);
+
+        for lap in 0..3 {
+            for i in 0..4 {
+                assert_eq!(queue.push(lap * 4 + i), Ok(()));
+            }
+            assert!(queue.push(99).is_err());
+            assert_eq!(std::iter::from_fn(|| queue.pop()).collect::<Vec<_>>(), (lap * 4..lap * 4 + 4).collect::<Vec<_>>());
+        }
+    }
+}
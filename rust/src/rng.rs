@@ -0,0 +1,55 @@
+use egui::Color32;
+
+/// Deterministic pseudo-random number generator (SplitMix64)
+/// Same seed produces the same sequence on every device
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seed from the current time (non-deterministic play)
+    pub fn from_time() -> Self {
+        let time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        Self::new(time as u64)
+    }
+
+    /// Internal state; Rng::new(state) continues the same sequence
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform float in [min, max)
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Random bright color (each channel at least 128)
+    pub fn bright_color(&mut self) -> Color32 {
+        let bits = self.next_u64();
+        let r = (bits & 0xFF) as u8;
+        let g = ((bits >> 8) & 0xFF) as u8;
+        let b = ((bits >> 16) & 0xFF) as u8;
+        Color32::from_rgb(128 + (r / 2), 128 + (g / 2), 128 + (b / 2))
+    }
+}
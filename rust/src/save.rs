@@ -0,0 +1,269 @@
+use std::sync::Arc;
+
+use bincode::Options;
+use egui::{Color32, Pos2, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Id;
+use crate::sprites::{self, Sprite};
+use crate::tags::TagMask;
+
+/// First bytes of every save
+const MAGIC: &[u8; 4] = b"GSAV";
+
+/// Format version after the magic; bump when SaveFile changes
+/// Versions 1 and 2 (hand-encoded, before bincode) and later ones are rejected rather than guessed at
+pub const SAVE_VERSION: u8 = 3;
+
+/// Fields a prefab instance saves because they differ from its prefab; the rest come from the prefab
+/// when it's restored. Position and velocity are always saved
+pub const OVERRIDE_SIZE: u8 = 1;
+pub const OVERRIDE_COLOR: u8 = 2;
+pub const OVERRIDE_TAGS: u8 = 4;
+/// Every field: sprites that aren't prefab instances
+pub const OVERRIDE_ALL: u8 = OVERRIDE_SIZE | OVERRIDE_COLOR | OVERRIDE_TAGS;
+
+/// Most sprites or tag names a save may declare, so corrupt counts can't allocate wildly
+const MAX_ENTRIES: u32 = 1 << 20;
+
+/// Run bookkeeping carried across a save (see policy module)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedRun {
+    pub flags: u32,
+    pub rejected: u32,
+    pub elapsed_ms: u64,
+}
+
+/// A sprite with its id, so host references stay valid
+/// Prefab instances (`sprite.prefab` set) carry only the `overrides` fields; decoding leaves the
+/// others at zero for the engine to fill from the prefab
+#[derive(Clone, Debug)]
+pub struct SavedSprite {
+    pub id: Id,
+    pub sprite: Sprite,
+    pub overrides: u8,
+}
+
+/// Everything needed to resume a game after process death
+/// Textures, atlases, sounds and scene nodes are host resources and are rebuilt by the host
+#[derive(Clone, Debug)]
+pub struct SavedState {
+    pub mode: u8,
+    /// Player center in points
+    pub player: Pos2,
+    pub velocity: Vec2,
+    pub tint: Color32,
+    pub score: u32,
+    /// Active level seed, 0 for free play
+    pub level_seed: u32,
+    pub rng_state: u64,
+    pub run: SavedRun,
+    /// Registered tag names in bit order, so saved masks keep their meaning
+    pub tag_names: Vec<String>,
+    pub sprites: Vec<SavedSprite>,
+}
+
+/// What follows MAGIC and SAVE_VERSION, bincode-encoded
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    mode: u8,
+    player: [f32; 2],
+    velocity: [f32; 2],
+    /// 0xRRGGBBAA
+    tint: u32,
+    score: u32,
+    level_seed: u32,
+    rng_state: u64,
+    run: SavedRun,
+    tag_names: Vec<String>,
+    /// Prefab names once each, for SpriteRecord::prefab to refer to
+    prefabs: Vec<String>,
+    sprites: Vec<SpriteRecord>,
+}
+
+/// A saved sprite; fields a prefab instance takes from its prefab are left out
+#[derive(Serialize, Deserialize)]
+struct SpriteRecord {
+    id: Id,
+    /// Index into SaveFile::prefabs, for prefab instances
+    prefab: Option<u32>,
+    position: [f32; 2],
+    size: Option<[f32; 2]>,
+    velocity: [f32; 2],
+    /// 0xRRGGBBAA
+    color: Option<u32>,
+    tags: Option<TagMask>,
+}
+
+/// Fixed-size little-endian integers, so the header fields sit at fixed offsets
+fn options() -> impl Options {
+    bincode::DefaultOptions::new().with_fixint_encoding().reject_trailing_bytes()
+}
+
+impl SavedState {
+    /// MAGIC and SAVE_VERSION, then the state as a bincode SaveFile
+    pub fn encode(&self) -> Vec<u8> {
+        let mut prefabs: Vec<&Arc<str>> = Vec::new();
+        for name in self.sprites.iter().filter_map(|saved| saved.sprite.prefab.as_ref()) {
+            if !prefabs.contains(&name) {
+                prefabs.push(name);
+            }
+        }
+        let sprites = self
+            .sprites
+            .iter()
+            .map(|SavedSprite { id, sprite, overrides }| {
+                let prefab = sprite.prefab.as_ref().and_then(|name| prefabs.iter().position(|p| *p == name));
+                let overrides = if prefab.is_some() { *overrides } else { OVERRIDE_ALL };
+                let saved = |field: u8| overrides & field != 0;
+                SpriteRecord {
+                    id: *id,
+                    prefab: prefab.map(|index| index as u32),
+                    position: [sprite.position.x, sprite.position.y],
+                    size: saved(OVERRIDE_SIZE).then_some([sprite.size.x, sprite.size.y]),
+                    velocity: [sprite.velocity.x, sprite.velocity.y],
+                    color: saved(OVERRIDE_COLOR).then(|| rgba(sprite.color)),
+                    tags: saved(OVERRIDE_TAGS).then_some(sprite.tags),
+                }
+            })
+            .collect();
+        let file = SaveFile {
+            mode: self.mode,
+            player: [self.player.x, self.player.y],
+            velocity: [self.velocity.x, self.velocity.y],
+            tint: rgba(self.tint),
+            score: self.score,
+            level_seed: self.level_seed,
+            rng_state: self.rng_state,
+            run: self.run,
+            tag_names: self.tag_names.clone(),
+            prefabs: prefabs.iter().map(|name| name.to_string()).collect(),
+            sprites,
+        };
+
+        let mut out = Vec::with_capacity(64 + self.sprites.len() * 48);
+        out.extend_from_slice(MAGIC);
+        out.push(SAVE_VERSION);
+        options().serialize_into(&mut out, &file).expect("saves encode into memory");
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<SavedState, String> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(4)? != MAGIC {
+            return Err("Not a saved game".to_string());
+        }
+        let version = reader.u8()?;
+        if version != SAVE_VERSION {
+            return Err(format!("Unsupported save version {}", version));
+        }
+        let body = &bytes[reader.position()..];
+        // A length can't claim more bytes than the save has, so corrupt counts can't allocate wildly
+        let file: SaveFile =
+            options().with_limit(body.len() as u64).deserialize(body).map_err(|e| format!("Corrupt save: {}", e))?;
+
+        if file.tag_names.len() > TagMask::BITS as usize {
+            return Err(format!("Save declares {} tags", file.tag_names.len()));
+        }
+        let prefabs: Vec<Arc<str>> = file.prefabs.into_iter().map(Arc::from).collect();
+        let mut sprites = Vec::with_capacity(file.sprites.len());
+        for record in file.sprites {
+            let prefab = match record.prefab {
+                Some(index) => Some(prefabs.get(index as usize).ok_or("Sprite refers to an unknown prefab")?.clone()),
+                None => None,
+            };
+            // Sprites outside prefabs save every field
+            if prefab.is_none() && (record.size.is_none() || record.color.is_none() || record.tags.is_none()) {
+                return Err("Sprite is missing fields".to_string());
+            }
+            let field = |saved: bool, field: u8| if saved { field } else { 0 };
+            let overrides = field(record.size.is_some(), OVERRIDE_SIZE)
+                | field(record.color.is_some(), OVERRIDE_COLOR)
+                | field(record.tags.is_some(), OVERRIDE_TAGS);
+            let position = Pos2::from(record.position);
+            let sprite = Sprite {
+                position,
+                prev_position: position,
+                size: record.size.map_or(Vec2::ZERO, Vec2::from),
+                velocity: Vec2::from(record.velocity),
+                color: record.color.map_or(Color32::TRANSPARENT, sprites::color_from_rgba),
+                tags: record.tags.unwrap_or(0),
+                material: None,
+                prefab,
+            };
+            sprites.push(SavedSprite { id: record.id, sprite, overrides });
+        }
+
+        Ok(SavedState {
+            mode: file.mode,
+            player: Pos2::from(file.player),
+            velocity: Vec2::from(file.velocity),
+            tint: sprites::color_from_rgba(file.tint),
+            score: file.score,
+            level_seed: file.level_seed,
+            rng_state: file.rng_state,
+            run: file.run,
+            tag_names: file.tag_names,
+            sprites,
+        })
+    }
+}
+
+/// Packed 0xRRGGBBAA (inverse of sprites::color_from_rgba)
+fn rgba(color: Color32) -> u32 {
+    u32::from_be_bytes(color.to_srgba_unmultiplied())
+}
+
+/// Bounds-checked little-endian reader (also used for replays and host draw lists)
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, pos: 0 }
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    /// Bytes read so far
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len());
+        let end = end.ok_or("Data is truncated")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    pub fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// Element count, bounded by MAX_ENTRIES
+    pub fn count(&mut self) -> Result<u32, String> {
+        let count = self.u32()?;
+        if count > MAX_ENTRIES {
+            return Err(format!("Data declares {} entries", count));
+        }
+        Ok(count)
+    }
+}
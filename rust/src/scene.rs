@@ -0,0 +1,416 @@
+use egui::epaint::{Mesh, Vertex};
+use egui::{Color32, Pos2, Rect, Shape, Stroke, TextureId, Vec2};
+
+use crate::arena::{Arena, Id, INVALID_ID};
+use crate::atlas::AnimatedSprite;
+use crate::tags::{self, TagMask};
+use crate::text::Label;
+
+/// Scene node id handed across FFI
+pub type NodeId = Id;
+
+/// Invalid node id returned on failure
+pub const INVALID_NODE: NodeId = INVALID_ID;
+
+/// Local transform relative to the parent node
+#[derive(Clone, Copy, Debug)]
+pub struct Transform {
+    pub translation: Vec2,
+    /// Radians, clockwise in screen space
+    pub rotation: f32,
+    pub scale: Vec2,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: Vec2::ZERO,
+            rotation: 0.0,
+            scale: Vec2::splat(1.0),
+        }
+    }
+}
+
+/// 2D affine matrix: p' = [a c; b d] * p + t
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    t: Vec2,
+}
+
+impl Affine {
+    pub const IDENTITY: Affine = Affine {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        t: Vec2::ZERO,
+    };
+
+    pub fn from_transform(transform: &Transform) -> Self {
+        let (sin, cos) = transform.rotation.sin_cos();
+        Self {
+            a: cos * transform.scale.x,
+            b: sin * transform.scale.x,
+            c: -sin * transform.scale.y,
+            d: cos * transform.scale.y,
+            t: transform.translation,
+        }
+    }
+
+    /// self * child (apply child first, then self)
+    pub fn then(&self, child: &Affine) -> Affine {
+        Affine {
+            a: self.a * child.a + self.c * child.b,
+            b: self.b * child.a + self.d * child.b,
+            c: self.a * child.c + self.c * child.d,
+            d: self.b * child.c + self.d * child.d,
+            t: self.apply(child.t.to_pos2()).to_vec2(),
+        }
+    }
+
+    /// Column-major `[a, b, c, d, tx, ty]`
+    pub fn to_array(self) -> [f32; 6] {
+        [self.a, self.b, self.c, self.d, self.t.x, self.t.y]
+    }
+
+    /// Inverse of to_array
+    pub fn from_array([a, b, c, d, tx, ty]: [f32; 6]) -> Self {
+        Self { a, b, c, d, t: Vec2::new(tx, ty) }
+    }
+
+    /// Blend component-wise `t` of the way to `other`; fine between the small steps of consecutive ticks
+    pub fn lerp(&self, other: &Affine, t: f32) -> Affine {
+        let [a, b] = [self.to_array(), other.to_array()];
+        Affine::from_array(std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t))
+    }
+
+    /// The transform undoing this one (None if it collapses space to a line or point)
+    pub fn inverse(&self) -> Option<Affine> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < f32::EPSILON || !det.is_finite() {
+            return None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        let t = Vec2::new(-(a * self.t.x + c * self.t.y), -(b * self.t.x + d * self.t.y));
+        Some(Affine { a, b, c, d, t })
+    }
+
+    /// Area scale factor (determinant)
+    pub fn area_scale(&self) -> f32 {
+        (self.a * self.d - self.b * self.c).abs()
+    }
+
+    pub fn apply(&self, p: Pos2) -> Pos2 {
+        Pos2::new(
+            self.a * p.x + self.c * p.y + self.t.x,
+            self.b * p.x + self.d * p.y + self.t.y,
+        )
+    }
+
+    /// Scale along x and y if this only scales and translates (no rotation, skew or flip)
+    pub fn axis_scale(&self) -> Option<Vec2> {
+        (self.b == 0.0 && self.c == 0.0 && self.a > 0.0 && self.d > 0.0).then(|| Vec2::new(self.a, self.d))
+    }
+
+    /// Axis-aligned bounds of `rect` once transformed
+    pub fn bounds(&self, rect: Rect) -> Rect {
+        let corners = [rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom()];
+        Rect::from_points(&corners.map(|p| self.apply(p)))
+    }
+
+    /// Corners of a rect of `size` centered on the local origin (TL, TR, BR, BL)
+    pub fn quad(&self, size: Vec2) -> [Pos2; 4] {
+        let h = size / 2.0;
+        [
+            self.apply(Pos2::new(-h.x, -h.y)),
+            self.apply(Pos2::new(h.x, -h.y)),
+            self.apply(Pos2::new(h.x, h.y)),
+            self.apply(Pos2::new(-h.x, h.y)),
+        ]
+    }
+}
+
+/// What a node draws at its origin
+#[derive(Clone, Debug)]
+pub enum NodeContent {
+    /// Pure grouping node
+    Group,
+    /// Solid rect centered on the node origin
+    Rect { size: Vec2, color: Color32 },
+    /// The player; translation follows the game's player position
+    Player,
+    /// Text aligned on the node origin
+    Text(Box<Label>),
+}
+
+pub struct Node {
+    pub local: Transform,
+    pub z: i32,
+    pub content: NodeContent,
+    /// Flipbook drawn in place of the content's default visual
+    pub animation: Option<AnimatedSprite>,
+    /// Group membership (see tags module)
+    pub tags: TagMask,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+/// Drawable collected by a traversal
+pub struct DrawItem<'a> {
+    pub id: NodeId,
+    pub z: i32,
+    pub world: Affine,
+    pub content: &'a NodeContent,
+    pub animation: Option<&'a AnimatedSprite>,
+}
+
+/// Node hierarchy traversed by the draw path
+/// Children inherit their parent's transform; siblings draw in z order, then insertion order
+pub struct Scene {
+    nodes: Arena<Node>,
+    roots: Vec<NodeId>,
+    player: NodeId,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        let mut nodes = Arena::default();
+        let player = nodes.insert(Node {
+            local: Transform::default(),
+            z: 0,
+            content: NodeContent::Player,
+            animation: None,
+            tags: 0,
+            parent: None,
+            children: Vec::new(),
+        });
+        Self {
+            nodes,
+            roots: vec![player],
+            player,
+        }
+    }
+}
+
+impl Scene {
+    pub fn player(&self) -> NodeId {
+        self.player
+    }
+
+    /// Create a root node
+    pub fn create(&mut self, content: NodeContent) -> NodeId {
+        let id = self.nodes.insert(Node {
+            local: Transform::default(),
+            z: 0,
+            content,
+            animation: None,
+            tags: 0,
+            parent: None,
+            children: Vec::new(),
+        });
+        self.roots.push(id);
+        id
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.nodes.get_mut(id)
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.nodes.contains(id)
+    }
+
+    /// True if `node` is `ancestor` or one of its descendants
+    fn is_descendant(&self, node: NodeId, ancestor: NodeId) -> bool {
+        let mut current = Some(node);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.nodes.get(id).and_then(|n| n.parent);
+        }
+        false
+    }
+
+    fn unlink(&mut self, id: NodeId) {
+        let parent = self.nodes.get_mut(id).and_then(|n| n.parent.take());
+        match parent.and_then(|p| self.nodes.get_mut(p)) {
+            Some(parent) => parent.children.retain(|&c| c != id),
+            None => self.roots.retain(|&r| r != id),
+        }
+    }
+
+    /// Attach `child` under `parent` (None = make it a root)
+    /// Rejects unknown ids, cycles, and re-parenting the player
+    pub fn attach(&mut self, child: NodeId, parent: Option<NodeId>) -> bool {
+        if !self.nodes.contains(child) {
+            return false;
+        }
+        if let Some(parent) = parent {
+            if child == self.player || !self.nodes.contains(parent) || self.is_descendant(parent, child) {
+                return false;
+            }
+        }
+
+        self.unlink(child);
+        match parent {
+            Some(parent_id) => {
+                if let Some(node) = self.nodes.get_mut(child) {
+                    node.parent = Some(parent_id);
+                }
+                if let Some(parent) = self.nodes.get_mut(parent_id) {
+                    parent.children.push(child);
+                }
+            }
+            None => self.roots.push(child),
+        }
+        true
+    }
+
+    /// Destroy a node and all of its descendants (the player node cannot be destroyed)
+    pub fn destroy(&mut self, id: NodeId) -> bool {
+        if id == self.player || !self.nodes.contains(id) {
+            return false;
+        }
+        self.unlink(id);
+
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes.remove(id) {
+                stack.extend(node.children);
+            }
+        }
+        true
+    }
+
+    /// Collect drawables with world transforms, sorted by z (stable)
+    /// `players` gives the position of each player node (whose translation the game drives);
+    /// `stack` is scratch space for the traversal
+    pub fn collect<'a>(
+        &'a self,
+        players: impl Fn(NodeId) -> Option<Pos2>,
+        stack: &mut Vec<(NodeId, Affine)>,
+        out: &mut Vec<DrawItem<'a>>,
+    ) {
+        stack.clear();
+        stack.extend(self.roots.iter().rev().map(|&id| (id, Affine::IDENTITY)));
+
+        while let Some((id, parent_world)) = stack.pop() {
+            let Some(node) = self.nodes.get(id) else {
+                continue;
+            };
+
+            let mut local = node.local;
+            if let Some(position) = players(id) {
+                local.translation = position.to_vec2();
+            }
+            let world = parent_world.then(&Affine::from_transform(&local));
+
+            out.push(DrawItem {
+                id,
+                z: node.z,
+                world,
+                content: &node.content,
+                animation: node.animation.as_ref(),
+            });
+            stack.extend(node.children.iter().rev().map(|&child| (child, world)));
+        }
+
+        out.sort_by_key(|item| item.z);
+    }
+
+    /// World position of a node's origin, or None if it doesn't exist (`players` as for collect)
+    pub fn world_origin(&self, id: NodeId, players: impl Fn(NodeId) -> Option<Pos2>) -> Option<Pos2> {
+        let mut point = Pos2::ZERO;
+        let mut current = Some(id);
+        // Out through each transform from the node up to its root
+        while let Some(id) = current {
+            let node = self.nodes.get(id)?;
+            let mut local = node.local;
+            if let Some(position) = players(id) {
+                local.translation = position.to_vec2();
+            }
+            point = Affine::from_transform(&local).apply(point);
+            current = node.parent;
+        }
+        Some(point)
+    }
+
+    /// Advance node animations, collecting nodes whose Once animation finished
+    pub fn update_animations(&mut self, delta: f32, finished: &mut Vec<NodeId>) {
+        for (id, node) in self.nodes.entries_mut() {
+            if let Some(animation) = node.animation.as_mut() {
+                if animation.update(delta) {
+                    finished.push(id);
+                }
+            }
+        }
+    }
+
+    /// Whether any node's animation is still changing frames
+    pub fn is_animating(&self) -> bool {
+        self.nodes.iter().any(|n| n.animation.as_ref().is_some_and(|a| a.is_playing()))
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &Label> {
+        self.nodes.iter().filter_map(|n| match &n.content {
+            NodeContent::Text(label) => Some(&**label),
+            _ => None,
+        })
+    }
+
+    pub fn labels_mut(&mut self) -> impl Iterator<Item = &mut Label> {
+        self.nodes.iter_mut().filter_map(|n| match &mut n.content {
+            NodeContent::Text(label) => Some(&mut **label),
+            _ => None,
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.nodes.count()
+    }
+
+    /// Nodes carrying every bit of `mask`
+    pub fn count_tagged(&self, mask: TagMask) -> usize {
+        self.nodes.iter().filter(|n| tags::matches(n.tags, mask)).count()
+    }
+
+    /// Destroy every node carrying all bits of `mask` (with descendants; never the player)
+    /// Returns how many tagged nodes were removed (descendants without the tags aren't counted)
+    pub fn destroy_tagged(&mut self, mask: TagMask) -> usize {
+        let tagged: Vec<NodeId> = self
+            .nodes
+            .entries()
+            .filter(|&(id, n)| id != self.player && tags::matches(n.tags, mask))
+            .map(|(id, _)| id)
+            .collect();
+        // A tagged node may already be gone with a tagged ancestor; it still counts
+        for &id in &tagged {
+            self.destroy(id);
+        }
+        tagged.len()
+    }
+}
+
+/// Solid quad under a world transform
+pub fn rect_shape(world: &Affine, size: Vec2, fill: Color32, stroke: Stroke) -> Shape {
+    Shape::convex_polygon(world.quad(size).to_vec(), fill, stroke)
+}
+
+/// Textured quad under a world transform
+pub fn image_shape(world: &Affine, size: Vec2, texture: TextureId, uv: Rect, tint: Color32) -> Shape {
+    let corners = world.quad(size);
+    let uvs = [uv.left_top(), uv.right_top(), uv.right_bottom(), uv.left_bottom()];
+
+    let mut mesh = Mesh::with_texture(texture);
+    for (pos, uv) in corners.into_iter().zip(uvs) {
+        mesh.vertices.push(Vertex { pos, uv, color: tint });
+    }
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(0, 2, 3);
+    Shape::mesh(mesh)
+}
@@ -0,0 +1,124 @@
+use egui::Pos2;
+
+use crate::engine::GameMode;
+use crate::events::{EventQueue, GameEvent};
+
+/// What the engine counts for scoreboards (game_get_stat)
+/// Values are part of the C API; never renumber
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u32)]
+pub enum Stat {
+    /// Bounces in the current run (reset when a level starts; shown on share cards)
+    Score = 0,
+    /// Bounces since the engine started or stats were reset
+    Bounces = 1,
+    /// Points the player traveled, dragged and thrown included; teleports don't count
+    Distance = 2,
+    /// Seconds of game time in Auto mode
+    AutoTime = 3,
+    /// Seconds of game time in Manual mode
+    ManualTime = 4,
+    /// Drags released fast enough to throw the player
+    Throws = 5,
+}
+
+const COUNT: usize = 6;
+
+impl Stat {
+    pub fn from_raw(value: u32) -> Option<Stat> {
+        match value {
+            0 => Some(Stat::Score),
+            1 => Some(Stat::Bounces),
+            2 => Some(Stat::Distance),
+            3 => Some(Stat::AutoTime),
+            4 => Some(Stat::ManualTime),
+            5 => Some(Stat::Throws),
+            _ => None,
+        }
+    }
+}
+
+/// Play statistics, reported to the host as StatUpdated events every interval of game time once
+/// the host sets one
+pub struct Scoreboard {
+    values: [f32; COUNT],
+    /// Values as last reported, so only changed stats are sent
+    reported: [f32; COUNT],
+    /// Where the player was at the end of the last tick
+    last_position: Pos2,
+    /// Seconds between reports; 0 (the default) turns them off
+    interval: f32,
+    since_report: f32,
+}
+
+impl Scoreboard {
+    /// Start counting with the player at `position`
+    pub fn new(position: Pos2) -> Self {
+        Scoreboard {
+            values: [0.0; COUNT],
+            reported: [0.0; COUNT],
+            last_position: position,
+            interval: 0.0,
+            since_report: 0.0,
+        }
+    }
+
+    pub fn get(&self, stat: Stat) -> f32 {
+        self.values[stat as usize]
+    }
+
+    pub fn bounce(&mut self) {
+        self.values[Stat::Bounces as usize] += 1.0;
+    }
+
+    pub fn throw(&mut self) {
+        self.values[Stat::Throws as usize] += 1.0;
+    }
+
+    /// The player jumped to `position` (level start, restored state): travel counts from there
+    pub fn place(&mut self, position: Pos2) {
+        self.last_position = position;
+    }
+
+    /// Count a tick of `delta` seconds in `mode` that left the player at `position`, with
+    /// `score` bounces this run
+    pub fn tick(&mut self, delta: f32, mode: GameMode, position: Pos2, score: u32) {
+        let time = match mode {
+            GameMode::Auto => Stat::AutoTime,
+            GameMode::Manual => Stat::ManualTime,
+        };
+        self.values[time as usize] += delta;
+        self.values[Stat::Distance as usize] += (position - self.last_position).length();
+        self.last_position = position;
+        self.values[Stat::Score as usize] = score as f32;
+        self.since_report += delta;
+    }
+
+    /// Report every `seconds` of game time; 0 stops the reports
+    pub fn set_interval(&mut self, seconds: f32) {
+        self.interval = seconds.max(0.0);
+        self.since_report = 0.0;
+    }
+
+    /// Zero every stat but the score, which belongs to the run; the next report sends the change
+    pub fn reset(&mut self) {
+        let score = self.get(Stat::Score);
+        self.values = [0.0; COUNT];
+        self.values[Stat::Score as usize] = score;
+    }
+
+    /// Report the stats that changed since the last report once an interval has passed
+    pub fn flush(&mut self, events: &mut EventQueue) {
+        if self.interval <= 0.0 || self.since_report < self.interval {
+            return;
+        }
+        self.since_report = 0.0;
+        for (raw, (&value, reported)) in self.values.iter().zip(&mut self.reported).enumerate() {
+            if value != *reported {
+                *reported = value;
+                let stat = Stat::from_raw(raw as u32).expect("every index below COUNT is a stat");
+                events.push(GameEvent::StatUpdated { stat, value });
+            }
+        }
+    }
+}
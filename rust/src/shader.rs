@@ -0,0 +1,218 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use glow::HasContext;
+
+/// Vertex shader for passes covering the whole viewport with `Quad`; `v_uv` runs 0 to 1 across it,
+/// bottom row first like GL textures
+pub const QUAD_VERTEX: &str = "ATTRIBUTE vec2 a_pos;\nVARYING vec2 v_uv;\n\
+    void main() {\n  v_uv = a_pos;\n  gl_Position = vec4(a_pos * 2.0 - 1.0, 0.0, 1.0);\n}\n";
+
+/// Compile a program for the context's GLSL version. Sources use ATTRIBUTE and VARYING for the
+/// interface, TEXTURE to sample and write FRAG_COLOR; attribute 0 is `a_pos`
+/// # Safety
+/// On the thread `gl` is current on
+pub unsafe fn compile(gl: &glow::Context, vertex: &str, fragment: &str) -> Result<glow::Program, String> {
+    let version = egui_glow::ShaderVersion::get(gl);
+    let mut header = version.version_declaration().to_string();
+    if version.is_embedded() {
+        header += "#ifdef GL_FRAGMENT_PRECISION_HIGH\nprecision highp float;\n#else\nprecision mediump float;\n#endif\n";
+    }
+    let (vertex_interface, fragment_interface) = if version.is_new_shader_interface() {
+        (
+            "#define ATTRIBUTE in\n#define VARYING out\n",
+            "#define VARYING in\n#define TEXTURE texture\nout vec4 frag_color;\n#define FRAG_COLOR frag_color\n",
+        )
+    } else {
+        (
+            "#define ATTRIBUTE attribute\n#define VARYING varying\n",
+            "#define VARYING varying\n#define TEXTURE texture2D\n#define FRAG_COLOR gl_FragColor\n",
+        )
+    };
+
+    let program = gl.create_program()?;
+    let mut shaders = Vec::new();
+    for (kind, interface, body) in
+        [(glow::VERTEX_SHADER, vertex_interface, vertex), (glow::FRAGMENT_SHADER, fragment_interface, fragment)]
+    {
+        let shader = gl.create_shader(kind)?;
+        gl.shader_source(shader, &format!("{}{}{}", header, interface, body));
+        gl.compile_shader(shader);
+        if !gl.get_shader_compile_status(shader) {
+            let log = gl.get_shader_info_log(shader);
+            gl.delete_shader(shader);
+            for shader in shaders {
+                gl.delete_shader(shader);
+            }
+            gl.delete_program(program);
+            return Err(log);
+        }
+        gl.attach_shader(program, shader);
+        shaders.push(shader);
+    }
+    gl.bind_attrib_location(program, 0, "a_pos");
+    gl.link_program(program);
+    for shader in shaders {
+        gl.detach_shader(program, shader);
+        gl.delete_shader(shader);
+    }
+    if !gl.get_program_link_status(program) {
+        let log = gl.get_program_info_log(program);
+        gl.delete_program(program);
+        return Err(log);
+    }
+    Ok(program)
+}
+
+/// GL objects made for one context on first use (programs, buffers), deleted by `release`
+pub trait Resource: Any {
+    /// # Safety
+    /// On the thread `gl` is current on
+    unsafe fn delete(&self, gl: &glow::Context);
+}
+
+thread_local! {
+    /// GL contexts are current on one thread, so their resources live in that thread's list,
+    /// keyed by the context's address
+    static RESOURCES: RefCell<Vec<(usize, Rc<dyn Resource>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Run `f` with `gl`'s `T`, made by `create` on first use; false (and logged) if that fails
+/// # Safety
+/// On the thread `gl` is current on
+pub unsafe fn with<T: Resource>(
+    gl: &Arc<glow::Context>,
+    create: impl FnOnce(&glow::Context) -> Result<T, String>,
+    f: impl FnOnce(&T),
+) -> bool {
+    let context = Arc::as_ptr(gl) as usize;
+    let find = |resources: &Vec<(usize, Rc<dyn Resource>)>| {
+        resources.iter().find_map(|(c, r)| (*c == context).then(|| r.clone()).and_then(|r| (r as Rc<dyn Any>).downcast::<T>().ok()))
+    };
+    let resource = match RESOURCES.with(|r| find(&r.borrow())) {
+        Some(resource) => resource,
+        None => match create(gl) {
+            Ok(resource) => {
+                let resource = Rc::new(resource);
+                RESOURCES.with(|r| r.borrow_mut().push((context, resource.clone())));
+                resource
+            }
+            Err(e) => {
+                log::error!("Creating {} failed: {}", std::any::type_name::<T>(), e);
+                return false;
+            }
+        },
+    };
+    // Not borrowed while `f` runs, so it can use other resources
+    f(&resource);
+    true
+}
+
+/// Delete every resource of `gl`'s context (call before the context goes away)
+pub fn release(gl: &Arc<glow::Context>) {
+    let context = Arc::as_ptr(gl) as usize;
+    let released: Vec<_> = RESOURCES.with(|r| {
+        let mut resources = r.borrow_mut();
+        let (released, kept) = resources.drain(..).partition(|(c, _)| *c == context);
+        *resources = kept;
+        released
+    });
+    for (_, resource) in released {
+        unsafe { resource.delete(gl) };
+    }
+}
+
+/// Set the viewport to a callback's whole rect and return its pixel size
+/// egui_glow clamps it to the screen, which would squash quads that are partly off screen
+/// # Safety
+/// On the thread `gl` is current on
+pub unsafe fn callback_viewport(gl: &glow::Context, info: &egui::PaintCallbackInfo) -> [i32; 2] {
+    let [left, top, right, bottom] =
+        [info.viewport.min.x, info.viewport.min.y, info.viewport.max.x, info.viewport.max.y].map(|v| (v * info.pixels_per_point).round() as i32);
+    gl.viewport(left, info.screen_size_px[1] as i32 - bottom, right - left, bottom - top);
+    [right - left, bottom - top]
+}
+
+/// Unit square as a triangle strip in attribute 0, for full-viewport passes (see QUAD_VERTEX)
+pub struct Quad {
+    buffer: glow::Buffer,
+    /// None where egui_glow emulates vertex arrays (it rebinds its attributes before each mesh)
+    vertex_array: Option<glow::VertexArray>,
+}
+
+impl Quad {
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn new(gl: &glow::Context) -> Result<Quad, String> {
+        let buffer = gl.create_buffer()?;
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(buffer));
+        gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, bytes_of(&[0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0]), glow::STATIC_DRAW);
+        gl.bind_buffer(glow::ARRAY_BUFFER, None);
+        let vertex_array = if supports_vertex_arrays(gl) { Some(gl.create_vertex_array()?) } else { None };
+        Ok(Quad { buffer, vertex_array })
+    }
+
+    /// Draw it with the program in use
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn draw(&self, gl: &glow::Context) {
+        self.bind(gl, self.buffer);
+        gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+        self.unbind(gl);
+    }
+
+    /// Bind this quad's vertex array with `buffer` as attribute 0 (2 floats per vertex)
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn bind(&self, gl: &glow::Context, buffer: glow::Buffer) {
+        if let Some(vertex_array) = self.vertex_array {
+            gl.bind_vertex_array(Some(vertex_array));
+        }
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(buffer));
+        gl.vertex_attrib_pointer_f32(0, 2, glow::FLOAT, false, 0, 0);
+        gl.enable_vertex_attrib_array(0);
+    }
+
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn unbind(&self, gl: &glow::Context) {
+        gl.disable_vertex_attrib_array(0);
+        if self.vertex_array.is_some() {
+            gl.bind_vertex_array(None);
+        }
+    }
+
+    /// # Safety
+    /// On the thread `gl` is current on
+    pub unsafe fn delete(&self, gl: &glow::Context) {
+        gl.delete_buffer(self.buffer);
+        if let Some(vertex_array) = self.vertex_array {
+            gl.delete_vertex_array(vertex_array);
+        }
+    }
+}
+
+impl Resource for Quad {
+    unsafe fn delete(&self, gl: &glow::Context) {
+        Quad::delete(self, gl);
+    }
+}
+
+/// Same test egui_glow uses to pick real or emulated vertex array objects
+pub fn supports_vertex_arrays(gl: &glow::Context) -> bool {
+    let version = unsafe { gl.get_parameter_string(glow::VERSION) };
+    let extension = || gl.supported_extensions().iter().any(|e| e.ends_with("_vertex_array_object"));
+    if version.contains("OpenGL ES ") {
+        !version.contains("2.0") || extension()
+    } else {
+        !version.starts_with('2') || extension()
+    }
+}
+
+/// Bytes of plain numbers, for buffer uploads
+pub fn bytes_of<T: Copy>(values: &[T]) -> &[u8] {
+    // SAFETY: f32 and u32 have no padding or invalid bit patterns
+    unsafe { std::slice::from_raw_parts(values.as_ptr() as *const u8, std::mem::size_of_val(values)) }
+}
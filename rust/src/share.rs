@@ -0,0 +1,146 @@
+use egui::emath::TSTransform;
+use egui::epaint::ClippedShape;
+use egui::{Color32, Pos2, Rect, Rounding, Shape, TextureId, Vec2};
+
+use crate::background::vertical_gradient;
+
+/// 1080x1080 card for feeds
+pub const SHARE_TEMPLATE_SQUARE: u32 = 0;
+/// 1080x1920 card for stories
+pub const SHARE_TEMPLATE_STORY: u32 = 1;
+
+/// Card background behind everything else (also the capture clear color)
+pub const BACKGROUND: [f32; 4] = [0.06, 0.06, 0.1, 1.0];
+
+const GRADIENT_TOP: Color32 = Color32::from_rgb(40, 36, 90);
+const GRADIENT_BOTTOM: Color32 = Color32::from_rgb(15, 15, 26);
+const FRAME_COLOR: Color32 = Color32::from_rgb(240, 240, 255);
+const SCORE_COLOR: Color32 = Color32::from_rgb(255, 200, 60);
+
+/// Where each element of a template goes, in card pixels
+pub struct Layout {
+    pub size: Vec2,
+    /// Screenshot frame
+    pub shot: Rect,
+    /// Center of the score row
+    pub score_center: Pos2,
+    pub digit_height: f32,
+    /// Logo mark (image is fitted inside, aspect preserved)
+    pub logo: Rect,
+}
+
+impl Layout {
+    pub fn for_template(template_id: u32) -> Option<Layout> {
+        match template_id {
+            SHARE_TEMPLATE_SQUARE => Some(Layout {
+                size: Vec2::new(1080.0, 1080.0),
+                shot: Rect::from_min_size(Pos2::new(60.0, 60.0), Vec2::new(960.0, 720.0)),
+                score_center: Pos2::new(480.0, 930.0),
+                digit_height: 150.0,
+                logo: Rect::from_min_size(Pos2::new(860.0, 860.0), Vec2::splat(140.0)),
+            }),
+            SHARE_TEMPLATE_STORY => Some(Layout {
+                size: Vec2::new(1080.0, 1920.0),
+                shot: Rect::from_min_size(Pos2::new(90.0, 360.0), Vec2::new(900.0, 1200.0)),
+                score_center: Pos2::new(540.0, 200.0),
+                digit_height: 200.0,
+                logo: Rect::from_center_size(Pos2::new(540.0, 1730.0), Vec2::splat(180.0)),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn card_rect(&self) -> Rect {
+        Rect::from_min_size(Pos2::ZERO, self.size)
+    }
+}
+
+/// Largest screen rect with the frame's aspect ratio, centered on `focus` and kept on screen
+pub fn crop_around(focus: Pos2, screen: Vec2, frame: Vec2) -> Rect {
+    let scale = (screen.x / frame.x).min(screen.y / frame.y);
+    let size = frame * scale;
+    let min = (focus - size / 2.0).clamp(Pos2::ZERO, (screen - size).to_pos2());
+    Rect::from_min_size(min, size)
+}
+
+/// Compose a card from the scene's egui output
+/// The scene shapes (screen coordinates) are cropped to `crop` and scaled into the screenshot
+/// frame; background, score and logo are added around them
+pub fn compose(
+    layout: &Layout,
+    mut output: egui::FullOutput,
+    crop: Rect,
+    score: u32,
+    logo: Option<(TextureId, Vec2)>,
+) -> egui::FullOutput {
+    let card = layout.card_rect();
+    let mut shapes = Vec::with_capacity(output.shapes.len() + 32);
+    let mut push = |shape: Shape| shapes.push(ClippedShape { clip_rect: card, shape });
+
+    push(vertical_gradient(card, GRADIENT_TOP, GRADIENT_BOTTOM));
+    push(Shape::rect_filled(layout.shot.expand(12.0), Rounding::same(24.0), FRAME_COLOR));
+    push(Shape::rect_filled(layout.shot, Rounding::same(12.0), Color32::from_rgb(26, 26, 38)));
+
+    // Screenshot: map the crop onto the frame
+    let zoom = layout.shot.width() / crop.width();
+    let transform = TSTransform::new(layout.shot.min.to_vec2() - crop.min.to_vec2() * zoom, zoom);
+    let scene_shapes = std::mem::take(&mut output.shapes).into_iter().map(|mut clipped| {
+        clipped.shape.transform(transform);
+        clipped.clip_rect = (transform * clipped.clip_rect).intersect(layout.shot);
+        clipped
+    });
+    shapes.extend(scene_shapes);
+
+    let mut push = |shape: Shape| shapes.push(ClippedShape { clip_rect: card, shape });
+    for shape in seven_segment(score, layout.score_center, layout.digit_height, SCORE_COLOR) {
+        push(shape);
+    }
+    if let Some((texture, image_size)) = logo {
+        let scale = (layout.logo.width() / image_size.x).min(layout.logo.height() / image_size.y);
+        let rect = Rect::from_center_size(layout.logo.center(), image_size * scale);
+        push(Shape::image(texture, rect, Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)), Color32::WHITE));
+    }
+
+    output.shapes = shapes;
+    output
+}
+
+/// Segments lit per digit, bit 0..6 = a (top), b, c, d (bottom), e, f, g (middle)
+const SEGMENTS: [u8; 10] = [0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F];
+
+/// Draw a number as seven-segment digits centered on `center`
+/// The engine ships without fonts, so the score is built from rects
+pub fn seven_segment(value: u32, center: Pos2, height: f32, color: Color32) -> Vec<Shape> {
+    let digits: Vec<u8> = value.to_string().bytes().map(|b| b - b'0').collect();
+    let width = height * 0.55;
+    let thickness = height * 0.12;
+    let spacing = height * 0.2;
+    let total = digits.len() as f32 * width + (digits.len() as f32 - 1.0) * spacing;
+
+    let rounding = Rounding::same(thickness / 2.0);
+    let half = thickness / 2.0;
+    let mut shapes = Vec::new();
+    for (i, digit) in digits.iter().enumerate() {
+        let left = center.x - total / 2.0 + i as f32 * (width + spacing);
+        let (right, top, mid, bottom) = (left + width, center.y - height / 2.0, center.y, center.y + height / 2.0);
+
+        let horizontal = |y: f32| Rect::from_min_max(Pos2::new(left, y - half), Pos2::new(right, y + half));
+        let vertical = |x: f32, y0: f32, y1: f32| Rect::from_min_max(Pos2::new(x - half, y0), Pos2::new(x + half, y1));
+        let segments = [
+            horizontal(top + half),
+            vertical(right - half, top, mid),
+            vertical(right - half, mid, bottom),
+            horizontal(bottom - half),
+            vertical(left + half, mid, bottom),
+            vertical(left + half, top, mid),
+            horizontal(mid),
+        ];
+
+        for (bit, rect) in segments.into_iter().enumerate() {
+            if SEGMENTS[*digit as usize] & (1 << bit) != 0 {
+                shapes.push(Shape::rect_filled(rect, rounding, color));
+            }
+        }
+    }
+    shapes
+}
@@ -0,0 +1,217 @@
+use std::cell::{RefCell, UnsafeCell};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::affinity::Instance;
+use crate::display_list::DisplayList;
+use crate::engine::Renderer;
+use crate::hud::Hud;
+use crate::tasks::CancellationToken;
+
+/// Step rate while the engine runs at a variable timestep (tick rate 0)
+const VARIABLE_STEP_RATE: u32 = 60;
+
+/// The engine's state after a simulation step, as the render thread draws it
+pub struct Snapshot {
+    /// At the tick just run (not interpolated)
+    pub list: DisplayList,
+    pub hud: Hud,
+    /// Surface size in pixels and physical pixels per point
+    pub size: [u32; 2],
+    pub pixels_per_point: f32,
+    pub stepped_at: Instant,
+}
+
+/// Steps the engine behind the handle to `now` on the simulation thread; None if it refused (poisoned)
+pub type StepFn = fn(handle: usize, now: Instant) -> Option<Snapshot>;
+
+/// The simulation loop, for the instance's TaskGroup to run (see GameEngine::spawn_task)
+pub type Task = Box<dyn FnOnce(CancellationToken) + Send>;
+
+/// Bit of TripleBuffer::middle set while the middle slot holds a value the reader hasn't taken
+const FRESH: usize = 4;
+
+/// Three slots handing values from one writer to one reader without either waiting: the writer fills
+/// its back slot and swaps it for the middle one, the reader swaps its front slot for the middle one
+/// when it's fresh. Values the reader never got to are dropped by the writer
+struct TripleBuffer<T> {
+    slots: [UnsafeCell<Option<T>>; 3],
+    /// Index of the middle slot, plus FRESH
+    middle: AtomicUsize,
+}
+
+// Each slot is only touched by the side whose index it is; the middle one by neither
+unsafe impl<T: Send> Sync for TripleBuffer<T> {}
+
+/// Writing half of a triple buffer
+pub struct Publisher<T> {
+    buffer: Arc<TripleBuffer<T>>,
+    back: usize,
+}
+
+/// Reading half of a triple buffer
+pub struct Latest<T> {
+    buffer: Arc<TripleBuffer<T>>,
+    front: usize,
+}
+
+pub fn triple_buffer<T>() -> (Publisher<T>, Latest<T>) {
+    let buffer = Arc::new(TripleBuffer { slots: Default::default(), middle: AtomicUsize::new(1) });
+    (Publisher { buffer: buffer.clone(), back: 0 }, Latest { buffer, front: 2 })
+}
+
+impl<T> Publisher<T> {
+    /// Make `value` the newest, replacing one the reader hasn't taken
+    pub fn publish(&mut self, value: T) {
+        unsafe { *self.buffer.slots[self.back].get() = Some(value) };
+        self.back = self.buffer.middle.swap(self.back | FRESH, Ordering::AcqRel) & !FRESH;
+    }
+}
+
+impl<T> Latest<T> {
+    /// The newest value published since the last take, if any
+    pub fn take(&mut self) -> Option<T> {
+        if self.buffer.middle.load(Ordering::Relaxed) & FRESH == 0 {
+            return None;
+        }
+        self.front = self.buffer.middle.swap(self.front, Ordering::AcqRel) & !FRESH;
+        unsafe { (*self.buffer.slots[self.front].get()).take() }
+    }
+}
+
+/// The render thread's side of an instance whose engine runs on a simulation thread: the renderer,
+/// taken from the engine meanwhile, and the snapshots frames are drawn from
+struct Simulation {
+    stop: Arc<AtomicBool>,
+    /// Disconnects once the loop has returned and no longer touches the engine
+    finished: mpsc::Receiver<()>,
+    snapshots: Latest<Snapshot>,
+    /// The two newest snapshots; frames are drawn between them, a step behind the simulation
+    previous: Option<Snapshot>,
+    current: Option<Snapshot>,
+    renderer: Renderer,
+    egui_ctx: egui::Context,
+}
+
+thread_local! {
+    /// Simulations started on this thread, by handle; only their render thread draws or stops them
+    static SIMULATIONS: RefCell<HashMap<usize, Simulation>> = RefCell::new(HashMap::new());
+}
+
+/// Whether `handle`'s engine runs on a simulation thread started from the calling thread
+pub fn is_running(handle: usize) -> bool {
+    SIMULATIONS.with(|simulations| simulations.borrow().contains_key(&handle))
+}
+
+/// Move `handle`'s engine to a thread calling `step` `rate` times a second (VARIABLE_STEP_RATE for 0),
+/// and draw its snapshots with `renderer` on the calling thread, which must own the engine and hold no
+/// reference to it. `spawn` starts the thread in the instance's TaskGroup, which cancels it if the
+/// engine is dropped first. Gives the renderer back if the thread can't be started
+#[allow(clippy::result_large_err)]
+pub fn start(
+    handle: usize,
+    rate: u32,
+    renderer: Renderer,
+    egui_ctx: egui::Context,
+    step: StepFn,
+    spawn: impl FnOnce(Task) -> std::io::Result<()>,
+) -> Result<(), Renderer> {
+    let interval = Duration::from_secs_f64(1.0 / if rate == 0 { VARIABLE_STEP_RATE } else { rate } as f64);
+    let (mut publisher, snapshots) = triple_buffer();
+    let stop = Arc::new(AtomicBool::new(false));
+    let (go, wait_go) = mpsc::sync_channel(1);
+    let (claimed, wait_claimed) = mpsc::sync_channel(1);
+    let (finished, wait_finished) = mpsc::sync_channel::<()>(0);
+    let stopping = stop.clone();
+    let spawned = spawn(Box::new(move |token: CancellationToken| {
+        let _finished = finished;
+        // `spawn` may still borrow the engine; wait until the starting thread has let go of it
+        if wait_go.recv().is_err() {
+            return;
+        }
+        // The starting thread waits for this before going on, and doesn't touch the engine after
+        unsafe { (*(handle as *const Instance)).claim() };
+        let _ = claimed.send(());
+        let mut next = Instant::now();
+        while !stopping.load(Ordering::Acquire) && !token.is_cancelled() {
+            let now = Instant::now();
+            if let Some(snapshot) = step(handle, now) {
+                publisher.publish(snapshot);
+            }
+            // A step that overran starts the next one late rather than running a burst to catch up;
+            // the engine's accumulator runs the ticks that time needs
+            next = (next + interval).max(now);
+            thread::sleep(next.saturating_duration_since(Instant::now()));
+        }
+    }));
+    if let Err(e) = spawned {
+        log::error!("Couldn't start the simulation thread: {}", e);
+        return Err(renderer);
+    }
+    // Only fails if the thread died before claiming; the engine is still this thread's then
+    if go.send(()).is_err() || wait_claimed.recv().is_err() {
+        let _ = wait_finished.recv();
+        return Err(renderer);
+    }
+    log::info!("Simulation thread started at {:?} per step", interval);
+    let simulation = Simulation {
+        stop,
+        finished: wait_finished,
+        snapshots,
+        previous: None,
+        current: None,
+        renderer,
+        egui_ctx,
+    };
+    SIMULATIONS.with(|simulations| simulations.borrow_mut().insert(handle, simulation));
+    Ok(())
+}
+
+/// Stop `handle`'s simulation thread and take the engine back onto the calling thread
+/// Returns the renderer to put back into it; None if no simulation was started from this thread
+pub fn stop(handle: usize) -> Option<Renderer> {
+    let simulation = SIMULATIONS.with(|simulations| simulations.borrow_mut().remove(&handle))?;
+    simulation.stop.store(true, Ordering::Release);
+    // Never sent on: returns once the loop has, panicked or not (the TaskGroup logs panics)
+    let _ = simulation.finished.recv();
+    // The loop is over, and with it any reference to the engine
+    unsafe { (*(handle as *const Instance)).claim() };
+    log::info!("Simulation thread stopped");
+    Some(simulation.renderer)
+}
+
+/// Draw `handle`'s newest snapshots if its simulation was started from this thread
+/// Some(false) until the first snapshot arrives; None if no simulation runs here
+pub fn draw(handle: usize) -> Option<bool> {
+    SIMULATIONS.with(|simulations| {
+        let mut simulations = simulations.borrow_mut();
+        Some(simulations.get_mut(&handle)?.draw(Instant::now()))
+    })
+}
+
+impl Simulation {
+    fn draw(&mut self, now: Instant) -> bool {
+        if let Some(snapshot) = self.snapshots.take() {
+            self.previous = self.current.replace(snapshot);
+        }
+        let Some(current) = &self.current else {
+            return false;
+        };
+        // Interpolated as far past the previous snapshot as it's been since the current one was made,
+        // so motion stays smooth while the steps arrive at their own pace
+        let list = match &self.previous {
+            Some(previous) => {
+                let step = current.stepped_at.saturating_duration_since(previous.stepped_at).as_secs_f32();
+                let elapsed = now.saturating_duration_since(current.stepped_at).as_secs_f32();
+                let alpha = if step > 0.0 { (elapsed / step).min(1.0) } else { 1.0 };
+                previous.list.interpolate(&current.list, alpha)
+            }
+            None => current.list.clone(),
+        };
+        self.renderer.draw_snapshot(&self.egui_ctx, &list, &current.hud, current.size, current.pixels_per_point);
+        true
+    }
+}
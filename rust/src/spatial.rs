@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use egui::Rect;
+
+use crate::arena::Id;
+
+/// Cells an entry covers, as column and row ranges
+#[derive(Clone, PartialEq)]
+struct Cells {
+    columns: RangeInclusive<i32>,
+    rows: RangeInclusive<i32>,
+}
+
+/// Uniform grid over world points: finds the entries near a region without visiting every one
+/// Entries are re-bucketed only when their bounds cross into other cells
+pub struct SpatialHash {
+    /// Cell side in world points
+    cell: f32,
+    cells: HashMap<(i32, i32), Vec<Id>>,
+    entries: HashMap<Id, Cells>,
+    /// Buckets of cells emptied since, reused for newly occupied ones so entries moving around
+    /// don't allocate
+    spare: Vec<Vec<Id>>,
+}
+
+impl SpatialHash {
+    pub fn new(cell: f32) -> Self {
+        SpatialHash { cell, cells: HashMap::new(), entries: HashMap::new(), spare: Vec::new() }
+    }
+
+    fn cells(&self, rect: Rect) -> Cells {
+        let cell = |v: f32| (v / self.cell).floor().clamp(i32::MIN as f32, i32::MAX as f32) as i32;
+        Cells { columns: cell(rect.min.x)..=cell(rect.max.x), rows: cell(rect.min.y)..=cell(rect.max.y) }
+    }
+
+    /// Place `id` at `bounds`, moving it if it was already indexed
+    pub fn update(&mut self, id: Id, bounds: Rect) {
+        let cells = self.cells(bounds);
+        if self.entries.get(&id) == Some(&cells) {
+            return;
+        }
+        self.remove(id);
+        for column in cells.columns.clone() {
+            for row in cells.rows.clone() {
+                self.cells.entry((column, row)).or_insert_with(|| self.spare.pop().unwrap_or_default()).push(id);
+            }
+        }
+        self.entries.insert(id, cells);
+    }
+
+    pub fn remove(&mut self, id: Id) {
+        let Some(cells) = self.entries.remove(&id) else {
+            return;
+        };
+        for column in cells.columns {
+            for row in cells.rows.clone() {
+                if let Some(ids) = self.cells.get_mut(&(column, row)) {
+                    ids.retain(|&other| other != id);
+                    if ids.is_empty() {
+                        self.spare.extend(self.cells.remove(&(column, row)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drop entries `keep` rejects
+    pub fn retain(&mut self, mut keep: impl FnMut(Id) -> bool) {
+        let gone: Vec<Id> = self.entries.keys().copied().filter(|&id| !keep(id)).collect();
+        for id in gone {
+            self.remove(id);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// Give back the space of cells and entries since removed (game_trim_memory)
+    pub fn shrink_to_fit(&mut self) {
+        for ids in self.cells.values_mut() {
+            ids.shrink_to_fit();
+        }
+        self.cells.shrink_to_fit();
+        self.entries.shrink_to_fit();
+        self.spare = Vec::new();
+    }
+
+    /// Entries in cells `region` touches, each once and in arena slot order; callers check exact bounds
+    pub fn query(&self, region: Rect, out: &mut Vec<Id>) {
+        out.clear();
+        let Cells { columns, rows } = self.cells(region);
+        // A region wider than the index holds is cheaper to answer from the entries
+        let area = (*columns.end() as i64 - *columns.start() as i64 + 1) * (*rows.end() as i64 - *rows.start() as i64 + 1);
+        if area > self.cells.len() as i64 {
+            out.extend(self.entries.keys().copied());
+        } else {
+            for column in columns {
+                for row in rows.clone() {
+                    out.extend(self.cells.get(&(column, row)).into_iter().flatten());
+                }
+            }
+        }
+        // Low 32 bits of an id are its slot, which is draw order
+        out.sort_unstable_by_key(|&id| id as u32);
+        out.dedup();
+    }
+}
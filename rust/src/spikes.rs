@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::json::{number, object, string, Value};
+use crate::stats::to_ms;
+
+/// Frames kept for the exported timeline (~5 s at 60 fps)
+const TIMELINE_FRAMES: usize = 300;
+
+/// Phases timed per frame; later marks are dropped
+const MAX_SPANS: usize = 8;
+
+/// Default spike threshold: three missed vsyncs at 60 Hz
+pub const DEFAULT_THRESHOLD: Duration = Duration::from_millis(50);
+
+/// Default minimum time between spike log entries; spikes in between are counted and reported with the next
+pub const DEFAULT_LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Phases of one game_render, timed back to back from the frame start
+#[derive(Clone, Copy)]
+pub struct FrameProfile {
+    start: Instant,
+    last: Instant,
+    spans: [(&'static str, Duration); MAX_SPANS],
+    len: usize,
+}
+
+impl FrameProfile {
+    pub fn new(start: Instant) -> FrameProfile {
+        FrameProfile { start, last: start, spans: [("", Duration::ZERO); MAX_SPANS], len: 0 }
+    }
+
+    /// End the current phase (begun at the previous mark or the frame start) and name it
+    pub fn mark(&mut self, name: &'static str) {
+        let now = Instant::now();
+        if self.len < MAX_SPANS {
+            self.spans[self.len] = (name, now.saturating_duration_since(self.last));
+            self.len += 1;
+        }
+        self.last = now;
+    }
+
+    pub fn spans(&self) -> &[(&'static str, Duration)] {
+        &self.spans[..self.len]
+    }
+
+    /// Frame start to the last mark
+    pub fn duration(&self) -> Duration {
+        self.last.saturating_duration_since(self.start)
+    }
+}
+
+/// Engine load around a frame, logged with spikes to tell what the frame was busy with
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameContext {
+    /// Outbound events waiting for the host to poll
+    pub queued_events: usize,
+    /// Texture decodes in flight on the worker thread
+    pub pending_loads: usize,
+    /// Sprites plus scene nodes
+    pub entities: usize,
+    pub draw_calls: usize,
+    pub vertices: usize,
+    /// Textures created or updated this frame, and their pixel data
+    pub texture_uploads: usize,
+    pub texture_upload_bytes: usize,
+    /// Textures freed this frame
+    pub texture_frees: usize,
+}
+
+struct TimelineFrame {
+    profile: FrameProfile,
+    /// Since the previous frame's start (None for the first frame)
+    interval: Option<Duration>,
+    context: FrameContext,
+    spike: bool,
+}
+
+/// Flags frames slower than a threshold, logs them with their phase timings and context
+/// (rate-limited), and keeps a timeline of recent frames for game_export_timeline
+pub struct SpikeDetector {
+    /// Zero disables detection (the timeline is still kept)
+    threshold: Duration,
+    log_interval: Duration,
+    last_log: Option<Instant>,
+    /// Spikes not logged since the last entry
+    pub suppressed: u32,
+    last_start: Option<Instant>,
+    timeline: VecDeque<TimelineFrame>,
+    /// Timeline timestamps count from here
+    origin: Instant,
+}
+
+impl Default for SpikeDetector {
+    fn default() -> Self {
+        SpikeDetector {
+            threshold: DEFAULT_THRESHOLD,
+            log_interval: DEFAULT_LOG_INTERVAL,
+            last_log: None,
+            suppressed: 0,
+            last_start: None,
+            timeline: VecDeque::with_capacity(TIMELINE_FRAMES),
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl SpikeDetector {
+    /// `threshold` zero turns detection off
+    pub fn configure(&mut self, threshold: Duration, log_interval: Duration) {
+        self.threshold = threshold;
+        self.log_interval = log_interval;
+    }
+
+    /// Add a finished frame; true if it was a spike
+    /// A frame spikes when its own work or the gap since the previous frame start exceeds the threshold,
+    /// so hitches caused outside game_render (host thread stalls) are caught too
+    pub fn record(&mut self, profile: FrameProfile, context: FrameContext) -> bool {
+        let interval = self.last_start.replace(profile.start).map(|last| profile.start.saturating_duration_since(last));
+        let worst = interval.unwrap_or_default().max(profile.duration());
+        let spike = !self.threshold.is_zero() && worst > self.threshold;
+
+        if self.timeline.len() == TIMELINE_FRAMES {
+            self.timeline.pop_front();
+        }
+        self.timeline.push_back(TimelineFrame { profile, interval, context, spike });
+
+        if spike {
+            let now = Instant::now();
+            if self.last_log.is_none_or(|last| now.saturating_duration_since(last) >= self.log_interval) {
+                self.last_log = Some(now);
+                self.log_spike(&profile, interval, &context);
+                self.suppressed = 0;
+            } else {
+                self.suppressed += 1;
+            }
+        }
+        spike
+    }
+
+    fn log_spike(&self, profile: &FrameProfile, interval: Option<Duration>, context: &FrameContext) {
+        let spans: Vec<String> = profile.spans().iter().map(|(name, d)| format!("{}={:.1}", name, to_ms(*d))).collect();
+        log::warn!(
+            "Frame spike: {:.1} ms since the previous frame, {:.1} ms in game_render [{}]; \
+             events queued {}, loads pending {}, entities {}, draw calls {}, vertices {}, \
+             texture uploads {} ({} bytes), frees {}; {} more spikes since the last report",
+            interval.map_or(0.0, to_ms),
+            to_ms(profile.duration()),
+            spans.join(" "),
+            context.queued_events,
+            context.pending_loads,
+            context.entities,
+            context.draw_calls,
+            context.vertices,
+            context.texture_uploads,
+            context.texture_upload_bytes,
+            context.texture_frees,
+            self.suppressed,
+        );
+    }
+
+    /// Recent frames in Chrome trace event format (load in Perfetto or chrome://tracing):
+    /// a "frame" slice per game_render with its phases nested inside, and a "spike" instant on spikes
+    pub fn timeline_json(&self) -> String {
+        let micros = |at: Instant| at.saturating_duration_since(self.origin).as_secs_f64() * 1e6;
+        let slice = |name: &str, ts: f64, dur: Duration, args: Value| {
+            object([
+                ("name", string(name)),
+                ("ph", string("X")),
+                ("ts", Value::Number(ts)),
+                ("dur", Value::Number(dur.as_secs_f64() * 1e6)),
+                ("pid", Value::Number(1.0)),
+                ("tid", Value::Number(1.0)),
+                ("args", args),
+            ])
+        };
+
+        let mut events = Vec::new();
+        for frame in &self.timeline {
+            let start = micros(frame.profile.start);
+            let c = &frame.context;
+            let args = object([
+                ("interval_ms", frame.interval.map_or(Value::Null, |d| number(to_ms(d)))),
+                ("queued_events", Value::Number(c.queued_events as f64)),
+                ("pending_loads", Value::Number(c.pending_loads as f64)),
+                ("entities", Value::Number(c.entities as f64)),
+                ("draw_calls", Value::Number(c.draw_calls as f64)),
+                ("vertices", Value::Number(c.vertices as f64)),
+                ("texture_uploads", Value::Number(c.texture_uploads as f64)),
+                ("texture_upload_bytes", Value::Number(c.texture_upload_bytes as f64)),
+                ("texture_frees", Value::Number(c.texture_frees as f64)),
+            ]);
+            events.push(slice("frame", start, frame.profile.duration(), args));
+
+            let mut ts = start;
+            for &(name, duration) in frame.profile.spans() {
+                events.push(slice(name, ts, duration, object([])));
+                ts += duration.as_secs_f64() * 1e6;
+            }
+
+            if frame.spike {
+                events.push(object([
+                    ("name", string("spike")),
+                    ("ph", string("i")),
+                    ("s", string("g")),
+                    ("ts", Value::Number(start)),
+                    ("pid", Value::Number(1.0)),
+                    ("tid", Value::Number(1.0)),
+                ]));
+            }
+        }
+
+        object([("traceEvents", Value::Array(events)), ("displayTimeUnit", string("ms"))]).to_string()
+    }
+}
@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+
+use egui::{Color32, Painter, Pos2, Rect, Vec2};
+
+use crate::assets::{self, LoadId};
+
+/// Logo shown while startup assets load; tiny so it decodes synchronously without a visible delay
+const LOGO_BYTES: &[u8] = include_bytes!("../assets/logo.png");
+
+/// Logo edge in points
+const LOGO_SIZE: f32 = 72.0;
+
+/// Longest the splash stays up if a startup load never reports back
+const MAX_DURATION: Duration = Duration::from_secs(5);
+
+const SPINNER_DOTS: usize = 8;
+const SPINNER_RADIUS: f32 = 14.0;
+const SPINNER_DOT_RADIUS: f32 = 3.0;
+/// Seconds per revolution
+const SPINNER_PERIOD: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    /// Waiting for startup loads
+    Loading,
+    /// Loads done; the next frame shows the scene
+    Ready,
+    /// The first scene frame was presented
+    Done,
+}
+
+/// Placeholder frame (logo plus spinner) drawn while startup textures decode off-thread,
+/// so the first frames don't show the player's fallback rectangle
+pub struct Splash {
+    logo: Option<egui::TextureHandle>,
+    /// Startup loads still in flight
+    pending: Vec<LoadId>,
+    started: Instant,
+    state: State,
+}
+
+impl Splash {
+    pub fn new(ctx: &egui::Context, pending: Vec<LoadId>) -> Splash {
+        let logo = match assets::decode_image(LOGO_BYTES) {
+            Ok(image) => Some(ctx.load_texture("splash-logo", image, egui::TextureOptions::LINEAR)),
+            Err(e) => {
+                log::error!("Failed to load splash logo: {}", e);
+                None
+            }
+        };
+        let state = if pending.is_empty() { State::Ready } else { State::Loading };
+        Splash { logo, pending, started: Instant::now(), state }
+    }
+
+    /// A load finished (successfully or not); false if it wasn't a startup load
+    pub fn load_done(&mut self, load: LoadId) -> bool {
+        let Some(index) = self.pending.iter().position(|&id| id == load) else {
+            return false;
+        };
+        self.pending.swap_remove(index);
+        if self.pending.is_empty() && self.state == State::Loading {
+            self.state = State::Ready;
+        }
+        true
+    }
+
+    /// Start of a frame: give up on loads that are taking too long
+    pub fn update(&mut self, now: Instant) {
+        if self.state == State::Loading && now.saturating_duration_since(self.started) >= MAX_DURATION {
+            log::warn!("Startup assets still loading after {:?}, showing the scene", MAX_DURATION);
+            self.state = State::Ready;
+        }
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.state == State::Loading
+    }
+
+    /// After a frame was presented; true exactly once, for the first frame showing the scene
+    pub fn frame_presented(&mut self) -> bool {
+        if self.state != State::Ready {
+            return false;
+        }
+        self.state = State::Done;
+        self.logo = None;
+        true
+    }
+
+    /// Logo centered on screen with a spinner below it
+    pub fn draw(&self, painter: &Painter, screen: Rect, now: Instant) {
+        let center = screen.center();
+        if let Some(logo) = &self.logo {
+            let rect = Rect::from_center_size(center, Vec2::splat(LOGO_SIZE));
+            let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+            painter.image(logo.id(), rect, uv, Color32::WHITE);
+        }
+
+        // Dots fade behind the leading one as it goes round
+        let spinner_center = center + Vec2::new(0.0, LOGO_SIZE / 2.0 + SPINNER_RADIUS * 2.0);
+        let turns = now.saturating_duration_since(self.started).as_secs_f32() / SPINNER_PERIOD;
+        let lead = (turns.fract() * SPINNER_DOTS as f32) as usize;
+        for i in 0..SPINNER_DOTS {
+            let angle = i as f32 / SPINNER_DOTS as f32 * std::f32::consts::TAU;
+            let offset = Vec2::angled(angle - std::f32::consts::FRAC_PI_2) * SPINNER_RADIUS;
+            let age = (lead + SPINNER_DOTS - i) % SPINNER_DOTS;
+            let alpha = 1.0 - age as f32 / SPINNER_DOTS as f32;
+            painter.circle_filled(spinner_center + offset, SPINNER_DOT_RADIUS, Color32::WHITE.gamma_multiply(alpha));
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use egui::{Color32, PaintCallback, Painter, Pos2, Rect, TextureId, Vec2};
+use glow::HasContext;
+
+use crate::display_list::rounded_rect;
+use crate::scene::{self, Affine};
+use crate::shader::{self, bytes_of};
+
+/// Fewest quads drawn as a batch; shorter runs go through egui, since a paint callback resets
+/// egui's GL state and costs more than a few extra triangles
+pub const MIN_BATCH: usize = 8;
+
+const VERTEX: &str = "ATTRIBUTE vec2 a_pos;\nATTRIBUTE vec4 a_axes;\nATTRIBUTE vec2 a_origin;\n\
+    ATTRIBUTE vec2 a_size;\nATTRIBUTE vec4 a_uv;\nATTRIBUTE vec4 a_color;\n\
+    uniform vec2 u_extent;\nuniform float u_pixels_per_point;\n\
+    VARYING vec2 v_local;\nVARYING vec2 v_half;\nVARYING vec2 v_uv;\nVARYING vec4 v_color;\nVARYING float v_pixels;\n\
+    void main() {\n\
+      vec2 local = (a_pos - 0.5) * a_size;\n\
+      vec2 p = (a_origin + a_axes.xy * local.x + a_axes.zw * local.y) / u_extent * 2.0 - 1.0;\n\
+      gl_Position = vec4(p.x, -p.y, 0.0, 1.0);\n\
+      v_local = local;\n  v_half = a_size * 0.5;\n  v_uv = mix(a_uv.xy, a_uv.zw, a_pos);\n  v_color = a_color;\n\
+      v_pixels = u_pixels_per_point * sqrt(abs(a_axes.x * a_axes.w - a_axes.y * a_axes.z));\n\
+    }\n";
+
+/// Tints the texture in gamma space like egui does, so batched and unbatched quads match
+const FRAGMENT: &str = "uniform sampler2D u_sampler;\nuniform float u_textured;\nuniform float u_srgb;\n\
+    uniform float u_rounding;\n\
+    VARYING vec2 v_local;\nVARYING vec2 v_half;\nVARYING vec2 v_uv;\nVARYING vec4 v_color;\nVARYING float v_pixels;\n\
+    vec3 gamma_from_linear(vec3 rgb) {\n\
+      return mix(1.055 * pow(rgb, vec3(1.0 / 2.4)) - 0.055, rgb * 12.92, vec3(lessThan(rgb, vec3(0.0031308))));\n\
+    }\n\
+    void main() {\n\
+      vec4 color = v_color;\n\
+      if (u_textured > 0.5) {\n\
+        vec4 texel = TEXTURE(u_sampler, v_uv);\n\
+        if (u_srgb > 0.5) {\n      texel.rgb = gamma_from_linear(texel.rgb);\n    }\n\
+        color *= texel;\n\
+      }\n\
+      if (u_rounding > 0.0) {\n\
+        float r = min(u_rounding, min(v_half.x, v_half.y));\n\
+        vec2 q = abs(v_local) - v_half + r;\n\
+        float d = length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - r;\n\
+        color *= clamp(0.5 - d * v_pixels, 0.0, 1.0);\n\
+      }\n\
+      FRAG_COLOR = color;\n\
+    }\n";
+
+/// Per-quad attributes in Instance order: name, components, and whether they're normalized bytes
+const ATTRIBUTES: [(&str, i32, bool); 5] =
+    [("a_axes", 4, false), ("a_origin", 2, false), ("a_size", 2, false), ("a_uv", 4, false), ("a_color", 4, true)];
+
+/// One quad as the GPU reads it: a rect of `size` centered on the local origin, placed in
+/// screen points by `transform` (relative to the batch's screen rect)
+/// Not the Instance behind GameHandle, so kept out of the header:
+/// cbindgen:ignore
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Instance {
+    transform: [f32; 6],
+    size: [f32; 2],
+    uv: [f32; 4],
+    /// Premultiplied, normalized by the attribute pointer
+    color: [u8; 4],
+}
+
+/// With instancing, a_pos comes from the unit square; without, every corner carries its quad
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Vertex {
+    corner: [f32; 2],
+    instance: Instance,
+}
+
+/// Corners of two triangles covering a quad, for drawing without instancing
+const CORNERS: [[f32; 2]; 6] = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0]];
+
+/// A run of game-layer quads sharing a texture (or none) and corner rounding, drawn in one call
+/// instead of one egui mesh each. Quads are kept in draw order; a quad of another texture, or anything
+/// drawn between them, ends the run (`flush`)
+pub struct SpriteBatch {
+    /// Screen rect in points; quads are placed relative to it so the callback survives egui
+    /// translating shapes (captures crop by translating)
+    screen: Rect,
+    texture: Option<TextureId>,
+    /// Corner radius in local points (untextured quads only)
+    rounding: f32,
+    quads: Vec<(Affine, Vec2, Rect, Color32)>,
+}
+
+impl SpriteBatch {
+    pub fn new(screen: Rect) -> SpriteBatch {
+        SpriteBatch { screen, texture: None, rounding: 0.0, quads: Vec::new() }
+    }
+
+    /// Add a colored rect (rounded by `rounding` world points) drawn through `camera`
+    pub fn push_rect(&mut self, painter: &Painter, camera: &Affine, rect: Rect, rounding: f32, color: Color32) {
+        if self.texture.is_some() || self.rounding != rounding {
+            self.flush(painter);
+        }
+        self.rounding = rounding;
+        let center = rect.center();
+        let world = camera.then(&Affine::from_array([1.0, 0.0, 0.0, 1.0, center.x, center.y]));
+        self.quads.push((world, rect.size(), Rect::ZERO, color));
+    }
+
+    /// Add an image of `size` centered on `world`'s origin (in screen points)
+    pub fn push_image(&mut self, painter: &Painter, world: Affine, size: Vec2, texture: TextureId, uv: Rect, tint: Color32) {
+        if self.texture != Some(texture) {
+            self.flush(painter);
+        }
+        self.texture = Some(texture);
+        self.rounding = 0.0;
+        self.quads.push((world, size, uv, tint));
+    }
+
+    /// Paint the quads so far, as one callback if there are enough of them
+    pub fn flush(&mut self, painter: &Painter) {
+        if self.quads.len() < MIN_BATCH {
+            for (world, size, uv, color) in self.quads.drain(..) {
+                painter.add(match self.texture {
+                    Some(texture) => scene::image_shape(&world, size, texture, uv, color),
+                    None => rounded_rect(&world, Rect::from_center_size(Pos2::ZERO, size), self.rounding, color),
+                });
+            }
+            self.texture = None;
+            return;
+        }
+
+        let origin = self.screen.min.to_vec2();
+        let instances: Vec<Instance> = self
+            .quads
+            .drain(..)
+            .map(|(world, size, uv, color)| {
+                let mut transform = world.to_array();
+                transform[4] -= origin.x;
+                transform[5] -= origin.y;
+                Instance { transform, size: size.into(), uv: [uv.min.x, uv.min.y, uv.max.x, uv.max.y], color: color.to_array() }
+            })
+            .collect();
+        let (texture, rounding) = (self.texture.take(), self.rounding);
+        let callback = egui_glow::CallbackFn::new(move |info, painter| unsafe {
+            let gl = painter.gl();
+            let texture = match texture {
+                Some(id) => match painter.texture(id) {
+                    Some(texture) => Some(texture),
+                    // Not uploaded (yet); egui would draw nothing either
+                    None => return,
+                },
+                None => None,
+            };
+            let [width, height] = shader::callback_viewport(gl, &info);
+            let extent = [width as f32 / info.pixels_per_point, height as f32 / info.pixels_per_point];
+            shader::with(gl, |gl| BatchRenderer::new(gl), |renderer| {
+                renderer.draw(gl, &instances, texture, rounding, extent, info.pixels_per_point)
+            });
+        });
+        painter.add(PaintCallback { rect: self.screen, callback: Arc::new(callback) });
+    }
+}
+
+/// The batch program and its buffers, made once per context (see shader::with)
+struct BatchRenderer {
+    program: glow::Program,
+    /// Unit square as a triangle strip (attribute 0) when instancing
+    corners: glow::Buffer,
+    /// Instances, or expanded vertices without instancing; refilled every draw
+    quads: glow::Buffer,
+    /// None where egui_glow emulates vertex arrays
+    vertex_array: Option<glow::VertexArray>,
+    /// Attribute locations for ATTRIBUTES (None if the compiler dropped one)
+    attributes: [Option<u32>; 5],
+    /// GLES 3 / GL 3.3: glVertexAttribDivisor and glDrawArraysInstanced
+    instancing: bool,
+    /// egui_glow uploads sRGB textures where supported (same test), sampling them back as linear
+    srgb_textures: bool,
+}
+
+impl BatchRenderer {
+    unsafe fn new(gl: &glow::Context) -> Result<BatchRenderer, String> {
+        let program = shader::compile(gl, VERTEX, FRAGMENT)?;
+        let attributes = ATTRIBUTES.map(|(name, _, _)| gl.get_attrib_location(program, name));
+        let corners = gl.create_buffer()?;
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(corners));
+        gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, bytes_of(&[0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0]), glow::STATIC_DRAW);
+        gl.bind_buffer(glow::ARRAY_BUFFER, None);
+        let quads = gl.create_buffer()?;
+        let vertex_array = if shader::supports_vertex_arrays(gl) { Some(gl.create_vertex_array()?) } else { None };
+        let version = gl.version();
+        let instancing = version.major > 3 || (version.major == 3 && (version.is_embedded || version.minor >= 3));
+        let shader_version = egui_glow::ShaderVersion::get(gl);
+        let srgb_textures = shader_version == egui_glow::ShaderVersion::Es300
+            || gl.supported_extensions().iter().any(|extension| extension.contains("sRGB"));
+        log::debug!("Sprite batches: instancing {}, sRGB textures {}", instancing, srgb_textures);
+        Ok(BatchRenderer { program, corners, quads, vertex_array, attributes, instancing, srgb_textures })
+    }
+
+    unsafe fn draw(
+        &self,
+        gl: &glow::Context,
+        instances: &[Instance],
+        texture: Option<glow::Texture>,
+        rounding: f32,
+        extent: [f32; 2],
+        pixels_per_point: f32,
+    ) {
+        let location = |name: &str| gl.get_uniform_location(self.program, name);
+        gl.use_program(Some(self.program));
+        gl.uniform_2_f32(location("u_extent").as_ref(), extent[0], extent[1]);
+        gl.uniform_1_f32(location("u_pixels_per_point").as_ref(), pixels_per_point);
+        gl.uniform_1_f32(location("u_textured").as_ref(), if texture.is_some() { 1.0 } else { 0.0 });
+        gl.uniform_1_f32(location("u_srgb").as_ref(), if self.srgb_textures { 1.0 } else { 0.0 });
+        gl.uniform_1_f32(location("u_rounding").as_ref(), rounding);
+        if texture.is_some() {
+            gl.active_texture(glow::TEXTURE0);
+            gl.bind_texture(glow::TEXTURE_2D, texture);
+            gl.uniform_1_i32(location("u_sampler").as_ref(), 0);
+        }
+        if let Some(vertex_array) = self.vertex_array {
+            gl.bind_vertex_array(Some(vertex_array));
+        }
+
+        let instance_offset = if self.instancing {
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.corners));
+            gl.vertex_attrib_pointer_f32(0, 2, glow::FLOAT, false, 0, 0);
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.quads));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, bytes_of(instances), glow::STREAM_DRAW);
+            0
+        } else {
+            let vertices: Vec<Vertex> =
+                instances.iter().flat_map(|&instance| CORNERS.map(|corner| Vertex { corner, instance })).collect();
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.quads));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, bytes_of(&vertices), glow::STREAM_DRAW);
+            gl.vertex_attrib_pointer_f32(0, 2, glow::FLOAT, false, size_of::<Vertex>() as i32, 0);
+            std::mem::offset_of!(Vertex, instance) as i32
+        };
+        gl.enable_vertex_attrib_array(0);
+        let stride = if self.instancing { size_of::<Instance>() } else { size_of::<Vertex>() } as i32;
+        let mut offset = instance_offset;
+        for (location, (_, components, bytes)) in self.attributes.iter().zip(ATTRIBUTES) {
+            if let Some(location) = *location {
+                if bytes {
+                    gl.vertex_attrib_pointer_f32(location, components, glow::UNSIGNED_BYTE, true, stride, offset);
+                } else {
+                    gl.vertex_attrib_pointer_f32(location, components, glow::FLOAT, false, stride, offset);
+                }
+                gl.enable_vertex_attrib_array(location);
+                if self.instancing {
+                    gl.vertex_attrib_divisor(location, 1);
+                }
+            }
+            offset += if bytes { components } else { components * 4 };
+        }
+
+        if self.instancing {
+            gl.draw_arrays_instanced(glow::TRIANGLE_STRIP, 0, 4, instances.len() as i32);
+        } else {
+            gl.draw_arrays(glow::TRIANGLES, 0, (instances.len() * CORNERS.len()) as i32);
+        }
+
+        // Attribute state outlives the draw without vertex arrays; leave it as egui expects
+        for location in self.attributes.iter().flatten().copied().chain([0]) {
+            if self.instancing {
+                gl.vertex_attrib_divisor(location, 0);
+            }
+            gl.disable_vertex_attrib_array(location);
+        }
+        if self.vertex_array.is_some() {
+            gl.bind_vertex_array(None);
+        }
+        gl.bind_buffer(glow::ARRAY_BUFFER, None);
+    }
+}
+
+impl shader::Resource for BatchRenderer {
+    unsafe fn delete(&self, gl: &glow::Context) {
+        gl.delete_program(self.program);
+        gl.delete_buffer(self.corners);
+        gl.delete_buffer(self.quads);
+        if let Some(vertex_array) = self.vertex_array {
+            gl.delete_vertex_array(vertex_array);
+        }
+    }
+}
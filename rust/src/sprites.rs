@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use egui::{Color32, Pos2, Rect, Vec2};
+
+use crate::arena::{Arena, Id, INVALID_ID};
+use crate::material::Material;
+use crate::tags::{self, TagMask};
+
+/// Sprite id handed across FFI
+pub type SpriteId = Id;
+
+/// Invalid sprite id returned on failure
+pub const INVALID_SPRITE: SpriteId = INVALID_ID;
+
+/// A simple colored quad driven by the host
+#[derive(Clone, Debug)]
+pub struct Sprite {
+    pub position: Pos2,
+    /// Position at the previous tick (for render interpolation)
+    pub prev_position: Pos2,
+    pub size: Vec2,
+    pub velocity: Vec2,
+    pub color: Color32,
+    /// Group membership (see tags module)
+    pub tags: TagMask,
+    /// Custom shader drawing it instead of a colored quad (not saved: shaders are the host's)
+    pub material: Option<Arc<Material>>,
+    /// Prefab it was spawned from, which saves refer to instead of storing what it defines
+    pub prefab: Option<Arc<str>>,
+}
+
+impl Sprite {
+    /// World rect interpolated between the previous and current tick (exactly the current one at rest)
+    pub fn interpolated_rect(&self, alpha: f32) -> Rect {
+        if self.prev_position == self.position {
+            return Rect::from_center_size(self.position, self.size);
+        }
+        Rect::from_center_size(self.prev_position.lerp(self.position, alpha), self.size)
+    }
+
+    /// Bounds of every interpolated rect between the previous tick and this one
+    pub fn swept_rect(&self) -> Rect {
+        self.interpolated_rect(0.0).union(self.interpolated_rect(1.0))
+    }
+}
+
+/// Arena of host-driven sprites (slot order is draw order)
+pub type SpriteArena = Arena<Sprite>;
+
+impl SpriteArena {
+    /// Advance sprite positions by their velocities
+    pub fn update(&mut self, delta: f32) {
+        for sprite in self.iter_mut() {
+            sprite.position += sprite.velocity * delta;
+        }
+    }
+
+    /// Remove every sprite carrying all bits of `mask`; returns how many were removed
+    pub fn remove_tagged(&mut self, mask: TagMask) -> usize {
+        let tagged: Vec<SpriteId> = self.entries().filter(|(_, s)| tags::matches(s.tags, mask)).map(|(id, _)| id).collect();
+        for &id in &tagged {
+            self.remove(id);
+        }
+        tagged.len()
+    }
+}
+
+/// Convert packed 0xRRGGBBAA into a color
+pub fn color_from_rgba(rgba: u32) -> Color32 {
+    let [r, g, b, a] = rgba.to_be_bytes();
+    Color32::from_rgba_unmultiplied(r, g, b, a)
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use egui::{Color32, Pos2, Vec2};
+
+use crate::arena::Id;
+
+/// Format version, first byte of every non-empty diff
+pub const STATE_DIFF_VERSION: u8 = 1;
+
+/// Record tags in a diff; values are part of the C API, never renumber
+/// Scalars are followed by one little-endian value of the listed type
+pub const STATE_PLAYER_X: u8 = 1; // f32, points
+pub const STATE_PLAYER_Y: u8 = 2; // f32, points
+pub const STATE_PLAYER_TINT: u8 = 3; // u32, 0xRRGGBBAA
+pub const STATE_MODE: u8 = 4; // u8, 0 = manual, 1 = auto
+pub const STATE_DRAGGING: u8 = 5; // u8, 0 or 1
+pub const STATE_SCORE: u8 = 6; // u32
+pub const STATE_LEVEL_SEED: u8 = 7; // u32, 0 = free play
+/// Sprite added or changed: id u64, x f32, y f32, w f32, h f32, rgba u32
+pub const STATE_SPRITE: u8 = 16;
+/// Sprite removed: id u64
+pub const STATE_SPRITE_REMOVED: u8 = 17;
+
+/// Mirrored sprite fields
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpriteState {
+    pub position: Pos2,
+    pub size: Vec2,
+    pub color: Color32,
+}
+
+/// Engine state as the host mirrors it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub player: Pos2,
+    pub tint: Color32,
+    pub mode: u8,
+    pub dragging: bool,
+    pub score: u32,
+    pub level_seed: u32,
+    pub sprites: BTreeMap<Id, SpriteState>,
+}
+
+/// Encodes each snapshot as the changes since the previous one
+/// The first diff (and the first after reset) carries every field
+#[derive(Default)]
+pub struct StateDiffer {
+    last: Option<Snapshot>,
+}
+
+impl StateDiffer {
+    /// Send everything again on the next diff (e.g. the host rebuilt its mirror)
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+
+    /// Changes since the last call; empty when nothing changed
+    pub fn diff(&mut self, current: Snapshot) -> Vec<u8> {
+        let mut out = Encoder::default();
+        let last = self.last.as_ref();
+        let changed = |f: fn(&Snapshot) -> u32| last.is_none_or(|last| f(last) != f(&current));
+
+        if changed(|s| s.player.x.to_bits()) {
+            out.tag(STATE_PLAYER_X).f32(current.player.x);
+        }
+        if changed(|s| s.player.y.to_bits()) {
+            out.tag(STATE_PLAYER_Y).f32(current.player.y);
+        }
+        if changed(|s| rgba(s.tint)) {
+            out.tag(STATE_PLAYER_TINT).u32(rgba(current.tint));
+        }
+        if changed(|s| s.mode as u32) {
+            out.tag(STATE_MODE).u8(current.mode);
+        }
+        if changed(|s| s.dragging as u32) {
+            out.tag(STATE_DRAGGING).u8(current.dragging as u8);
+        }
+        if changed(|s| s.score) {
+            out.tag(STATE_SCORE).u32(current.score);
+        }
+        if changed(|s| s.level_seed) {
+            out.tag(STATE_LEVEL_SEED).u32(current.level_seed);
+        }
+
+        for (&id, sprite) in &current.sprites {
+            if last.and_then(|last| last.sprites.get(&id)) != Some(sprite) {
+                out.tag(STATE_SPRITE)
+                    .u64(id)
+                    .f32(sprite.position.x)
+                    .f32(sprite.position.y)
+                    .f32(sprite.size.x)
+                    .f32(sprite.size.y)
+                    .u32(rgba(sprite.color));
+            }
+        }
+        if let Some(last) = last {
+            for &id in last.sprites.keys().filter(|id| !current.sprites.contains_key(id)) {
+                out.tag(STATE_SPRITE_REMOVED).u64(id);
+            }
+        }
+
+        self.last = Some(current);
+        out.finish()
+    }
+}
+
+/// Packed 0xRRGGBBAA (inverse of sprites::color_from_rgba)
+fn rgba(color: Color32) -> u32 {
+    u32::from_be_bytes(color.to_srgba_unmultiplied())
+}
+
+#[derive(Default)]
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn tag(&mut self, tag: u8) -> &mut Self {
+        if self.bytes.is_empty() {
+            self.bytes.push(STATE_DIFF_VERSION);
+        }
+        self.u8(tag)
+    }
+
+    fn u8(&mut self, value: u8) -> &mut Self {
+        self.bytes.push(value);
+        self
+    }
+
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u64(&mut self, value: u64) -> &mut Self {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn f32(&mut self, value: f32) -> &mut Self {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
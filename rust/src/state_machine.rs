@@ -0,0 +1,151 @@
+use std::ffi::c_void;
+
+/// A game state: the built-in ones below, then game_add_state's in order
+pub type StateId = u32;
+
+pub const STATE_MENU: StateId = 0;
+/// The state a game starts in, so hosts that never transition run as before
+pub const STATE_PLAYING: StateId = 1;
+pub const STATE_PAUSED: StateId = 2;
+pub const STATE_GAME_OVER: StateId = 3;
+/// Returned for states that couldn't be added
+pub const INVALID_STATE: StateId = u32::MAX;
+
+/// States a game can have, built-in ones included
+const MAX_STATES: usize = 32;
+
+/// Host code run on the render thread as `state` is left (`entering` false) or entered, before the
+/// change is reported as GAME_EVENT_STATE_CHANGED
+pub type StateHook = unsafe extern "C" fn(user_data: *mut c_void, state: StateId, entering: bool);
+
+#[derive(Clone, Copy)]
+struct Hook {
+    function: StateHook,
+    /// Host pointer, passed back untouched
+    user_data: *mut c_void,
+}
+
+struct State {
+    name: Box<str>,
+    /// Whether the world moves on in this state; paused and menu states hold it still
+    simulates: bool,
+    /// States this one may go to
+    to: Vec<StateId>,
+    hook: Option<Hook>,
+}
+
+/// A change of state that happened
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transition {
+    pub from: StateId,
+    pub to: StateId,
+}
+
+/// Named game states (menu, playing, paused, game over, and any the host adds) and the transitions
+/// allowed between them
+pub struct StateMachine {
+    states: Vec<State>,
+    current: StateId,
+}
+
+impl Default for StateMachine {
+    fn default() -> Self {
+        let state = |name: &str, simulates, to: &[StateId]| State { name: name.into(), simulates, to: to.to_vec(), hook: None };
+        StateMachine {
+            states: vec![
+                state("menu", false, &[STATE_PLAYING]),
+                state("playing", true, &[STATE_PAUSED, STATE_GAME_OVER, STATE_MENU]),
+                state("paused", false, &[STATE_PLAYING, STATE_MENU]),
+                state("game_over", false, &[STATE_PLAYING, STATE_MENU]),
+            ],
+            current: STATE_PLAYING,
+        }
+    }
+}
+
+impl StateMachine {
+    /// Add a state no transition leads to or from yet (see allow)
+    pub fn add(&mut self, name: &str, simulates: bool) -> Result<StateId, String> {
+        if name.is_empty() {
+            return Err("state names can't be empty".to_string());
+        }
+        if self.id(name).is_some() {
+            return Err(format!("state \"{}\" already exists", name));
+        }
+        if self.states.len() >= MAX_STATES {
+            return Err(format!("a game has at most {} states", MAX_STATES));
+        }
+        self.states.push(State { name: name.into(), simulates, to: Vec::new(), hook: None });
+        Ok(self.states.len() as StateId - 1)
+    }
+
+    /// Let the game go from `from` to `to`; false for unknown states
+    pub fn allow(&mut self, from: StateId, to: StateId) -> bool {
+        if self.state(to).is_none() || from == to {
+            return false;
+        }
+        let Some(state) = self.states.get_mut(from as usize) else {
+            return false;
+        };
+        if !state.to.contains(&to) {
+            state.to.push(to);
+        }
+        true
+    }
+
+    /// Call `function` as `state` is entered and left; None removes its hook
+    /// False for an unknown state
+    pub fn set_hook(&mut self, state: StateId, function: Option<StateHook>, user_data: *mut c_void) -> bool {
+        let Some(state) = self.states.get_mut(state as usize) else {
+            return false;
+        };
+        state.hook = function.map(|function| Hook { function, user_data });
+        true
+    }
+
+    pub fn id(&self, name: &str) -> Option<StateId> {
+        self.states.iter().position(|s| &*s.name == name).map(|index| index as StateId)
+    }
+
+    pub fn name(&self, state: StateId) -> Option<&str> {
+        self.state(state).map(|s| &*s.name)
+    }
+
+    pub fn current(&self) -> StateId {
+        self.current
+    }
+
+    /// Whether the world moves on in the current state
+    pub fn simulates(&self) -> bool {
+        self.states[self.current as usize].simulates
+    }
+
+    /// Go to state `to` if the current one may
+    pub fn transition(&mut self, to: StateId) -> Result<Transition, String> {
+        let Some(target) = self.state(to) else {
+            return Err(format!("unknown state {}", to));
+        };
+        let from = &self.states[self.current as usize];
+        if !from.to.contains(&to) {
+            return Err(format!("no transition from \"{}\" to \"{}\"", from.name, target.name));
+        }
+        let transition = Transition { from: self.current, to };
+        self.current = to;
+        Ok(transition)
+    }
+
+    /// Call the exit hook of the state left, then the enter hook of the one entered
+    /// # Safety
+    /// On the render thread; the hooks' user_data must still be valid
+    pub unsafe fn run_hooks(&self, transition: Transition) {
+        for (state, entering) in [(transition.from, false), (transition.to, true)] {
+            if let Some(hook) = self.states[state as usize].hook {
+                (hook.function)(hook.user_data, state, entering);
+            }
+        }
+    }
+
+    fn state(&self, state: StateId) -> Option<&State> {
+        self.states.get(state as usize)
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Frames kept for averages and percentiles (~2 s at 60 fps)
+const WINDOW: usize = 120;
+
+/// Frame timing summary over the recent window, exported to the host
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameStats {
+    /// Frames per second from the average frame interval
+    pub fps: f32,
+    /// Average CPU time spent in game_render, in milliseconds
+    pub cpu_ms: f32,
+    /// Average time waiting for the GPU to finish the frame, in milliseconds
+    pub gpu_ms: f32,
+    /// Average interval between frames, in milliseconds
+    pub frame_ms: f32,
+    /// 95th percentile frame interval, in milliseconds
+    pub frame_ms_p95: f32,
+    /// Frames rendered since init
+    pub frame_count: u64,
+    /// Entities the last frame skipped for lying outside the camera's view
+    pub culled: CullStats,
+    /// Average GPU time per pass from timer queries; all zero where the GPU has none
+    pub gpu_passes: GpuPassStats,
+}
+
+/// GPU time of a frame's passes in milliseconds, measured with timer queries
+/// (EXT_disjoint_timer_query on GLES). With cpu_ms, shows whether frames are CPU or GPU bound
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GpuPassStats {
+    pub clear_ms: f32,
+    /// The world, host draw lists and everything else beneath the HUD
+    pub scene_ms: f32,
+    /// egui's HUD and the debug overlay
+    pub ui_ms: f32,
+}
+
+/// Entities left out of a frame because they were off screen (with a margin) or, for particles, too
+/// small to see (LodSettings::detail_min_px), by kind
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CullStats {
+    pub sprites: u32,
+    /// Scene nodes, including the player
+    pub nodes: u32,
+    pub particles: u32,
+}
+
+struct Sample {
+    interval_ms: f32,
+    cpu_ms: f32,
+    gpu_ms: f32,
+}
+
+/// Collects per-frame timings from game_render
+#[derive(Default)]
+pub struct FrameTimer {
+    samples: VecDeque<Sample>,
+    last_frame: Option<Instant>,
+    frame_count: u64,
+    culled: CullStats,
+    /// Pass times of recent frames, which arrive a few frames late and not for every frame
+    gpu_passes: VecDeque<GpuPassStats>,
+}
+
+pub fn to_ms(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
+
+impl FrameTimer {
+    /// Record a frame that started at `start`; the interval is measured between frame starts
+    pub fn record(&mut self, start: Instant, cpu: Duration, gpu: Duration, culled: CullStats) {
+        self.frame_count += 1;
+        self.culled = culled;
+        let Some(last) = self.last_frame.replace(start) else {
+            return;
+        };
+
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            interval_ms: to_ms(start - last),
+            cpu_ms: to_ms(cpu),
+            gpu_ms: to_ms(gpu),
+        });
+    }
+
+    /// Record a frame's GPU pass times once its timer queries have results
+    pub fn record_gpu_passes(&mut self, passes: GpuPassStats) {
+        if self.gpu_passes.len() == WINDOW {
+            self.gpu_passes.pop_front();
+        }
+        self.gpu_passes.push_back(passes);
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        let gpu_passes = self.gpu_pass_averages();
+        let n = self.samples.len();
+        if n == 0 {
+            return FrameStats {
+                frame_count: self.frame_count,
+                culled: self.culled,
+                gpu_passes,
+                ..Default::default()
+            };
+        }
+
+        let average = |f: fn(&Sample) -> f32| self.samples.iter().map(f).sum::<f32>() / n as f32;
+        let frame_ms = average(|s| s.interval_ms);
+
+        let mut intervals: Vec<f32> = self.samples.iter().map(|s| s.interval_ms).collect();
+        intervals.sort_by(f32::total_cmp);
+        let p95 = intervals[((n as f32 * 0.95).ceil() as usize).clamp(1, n) - 1];
+
+        FrameStats {
+            fps: if frame_ms > 0.0 { 1000.0 / frame_ms } else { 0.0 },
+            cpu_ms: average(|s| s.cpu_ms),
+            gpu_ms: average(|s| s.gpu_ms),
+            frame_ms,
+            frame_ms_p95: p95,
+            frame_count: self.frame_count,
+            culled: self.culled,
+            gpu_passes,
+        }
+    }
+
+    fn gpu_pass_averages(&self) -> GpuPassStats {
+        let n = self.gpu_passes.len().max(1) as f32;
+        let average = |f: fn(&GpuPassStats) -> f32| self.gpu_passes.iter().map(f).sum::<f32>() / n;
+        GpuPassStats { clear_ms: average(|p| p.clear_ms), scene_ms: average(|p| p.scene_ms), ui_ms: average(|p| p.ui_ms) }
+    }
+}
+
+/// Counters for one event path, exported to the host
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EventPathStats {
+    /// Events that entered the path
+    pub published: u64,
+    /// Events that reached their consumer (host poll or a rendered frame)
+    pub delivered: u64,
+    /// Events discarded because a queue was full
+    pub dropped: u64,
+    /// Events superseded by a newer one before delivery (e.g. touch moves within a frame)
+    pub coalesced: u64,
+    /// Longest time an event waited for delivery since init, in milliseconds
+    pub max_latency_ms: f32,
+}
+
+impl EventPathStats {
+    /// Count a delivery of events that have waited since `since`
+    pub fn record_delivery(&mut self, count: u64, since: Instant, now: Instant) {
+        self.delivered += count;
+        self.max_latency_ms = self.max_latency_ms.max(to_ms(now.saturating_duration_since(since)));
+    }
+}
+
+/// Where startup time went, in milliseconds, for game_get_init_timings
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InitTimings {
+    /// Renderer creation: driver queries, quirk detection, egui painter (0 without a renderer)
+    pub renderer_ms: f32,
+    /// Engine construction after the renderer
+    pub engine_ms: f32,
+    /// game_warmup (0 if it wasn't called)
+    pub warmup_ms: f32,
+    /// From engine construction to the first frame showing the scene (0 until then)
+    pub first_frame_ms: f32,
+}
+
+/// Both event paths, for game_get_event_stats and the debug overlay
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EventStats {
+    /// Engine to host (game_poll_event)
+    pub outbound: EventPathStats,
+    /// Host to engine (touch, direction, keys, text, gamepad, sensors)
+    pub input: EventPathStats,
+}
+
+/// Tracks host input until a rendered frame shows its effect
+#[derive(Default)]
+pub struct InputMetrics {
+    stats: EventPathStats,
+    pending: u64,
+    oldest_pending: Option<Instant>,
+    move_pending: bool,
+}
+
+impl InputMetrics {
+    pub fn received(&mut self, now: Instant) {
+        self.stats.published += 1;
+        self.pending += 1;
+        self.oldest_pending.get_or_insert(now);
+    }
+
+    /// A pointer move; earlier moves in the same frame count as coalesced
+    pub fn received_move(&mut self, now: Instant) {
+        if self.move_pending {
+            self.stats.coalesced += 1;
+            self.pending -= 1;
+        }
+        self.move_pending = true;
+        self.received(now);
+    }
+
+    /// A frame was rendered with all input received so far
+    pub fn frame_presented(&mut self, now: Instant) {
+        if let Some(oldest) = self.oldest_pending.take() {
+            self.stats.record_delivery(self.pending, oldest, now);
+        }
+        self.pending = 0;
+        self.move_pending = false;
+    }
+
+    pub fn stats(&self) -> EventPathStats {
+        self.stats
+    }
+}
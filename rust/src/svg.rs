@@ -0,0 +1,47 @@
+use egui::{ColorImage, Vec2};
+use resvg::{tiny_skia, usvg};
+
+/// Longest side of a rasterized image in pixels; bigger images are drawn at a lower scale
+pub const MAX_PIXELS: f32 = 4096.0;
+
+/// Whether `bytes` look like an SVG document rather than a bitmap
+pub fn is_svg(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(1024)];
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    text.starts_with('<') && text.contains("<svg")
+}
+
+/// Parsed SVG image (usvg's tree), ready to rasterize at any scale with resvg
+/// Text is skipped: this build has no fonts to lay it out with
+#[derive(Debug)]
+pub struct Document {
+    /// Intrinsic size in points (width and height, or the viewBox's)
+    pub size: Vec2,
+    tree: usvg::Tree,
+}
+
+/// Parse an SVG document
+pub fn parse(bytes: &[u8]) -> Result<Document, String> {
+    let tree = usvg::Tree::from_data(bytes, &usvg::Options::default()).map_err(|e| e.to_string())?;
+    let size = tree.size();
+    Ok(Document { size: Vec2::new(size.width(), size.height()), tree })
+}
+
+impl Document {
+    /// Pixel size of the image drawn at `scale` pixels per point, capped at MAX_PIXELS a side
+    pub fn pixel_size(&self, scale: f32) -> [usize; 2] {
+        let scale = scale.min(MAX_PIXELS / self.size.max_elem());
+        [self.size.x, self.size.y].map(|side| ((side * scale).round() as usize).max(1))
+    }
+
+    /// Draw the image at `scale` pixels per point
+    pub fn rasterize(&self, scale: f32) -> ColorImage {
+        let [width, height] = self.pixel_size(scale);
+        let mut pixmap = tiny_skia::Pixmap::new(width as u32, height as u32).expect("pixel size is at least 1x1");
+        let to_pixels = tiny_skia::Transform::from_scale(width as f32 / self.size.x, height as f32 / self.size.y);
+        resvg::render(&self.tree, to_pixels, &mut pixmap.as_mut());
+        // tiny-skia keeps premultiplied RGBA, as egui does
+        ColorImage::from_rgba_premultiplied([width, height], pixmap.data())
+    }
+}
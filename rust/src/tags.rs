@@ -0,0 +1,44 @@
+/// Set of tag bits carried by a sprite or node
+pub type TagMask = u64;
+
+/// Registered tag names, one bit each
+#[derive(Default)]
+pub struct TagRegistry {
+    names: Vec<String>,
+}
+
+impl TagRegistry {
+    /// Registry with `names` already assigned, in bit order (restoring saved state)
+    pub fn from_names(mut names: Vec<String>) -> TagRegistry {
+        names.truncate(TagMask::BITS as usize);
+        TagRegistry { names }
+    }
+
+    /// Registered names in bit order
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Bit for `name`, registering it on first use; None once all 64 bits are taken
+    pub fn mask(&mut self, name: &str) -> Option<TagMask> {
+        let index = match self.names.iter().position(|n| n == name) {
+            Some(index) => index,
+            None if self.names.len() < TagMask::BITS as usize => {
+                self.names.push(name.to_string());
+                self.names.len() - 1
+            }
+            None => return None,
+        };
+        Some(1 << index)
+    }
+
+    /// Bits of the registered names among `names`, without registering the others
+    pub fn find(&self, names: &[String]) -> TagMask {
+        names.iter().filter_map(|name| self.names.iter().position(|n| n == name)).fold(0, |mask, index| mask | 1 << index)
+    }
+}
+
+/// True if `tags` carries every bit in `mask` (an empty mask matches nothing)
+pub fn matches(tags: TagMask, mask: TagMask) -> bool {
+    mask != 0 && tags & mask == mask
+}
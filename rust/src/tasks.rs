@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::thread_hints::{self, NativeThread, ThreadHints};
+
+/// Shared cancellation flag checked by background tasks
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+struct Task {
+    name: &'static str,
+    handle: JoinHandle<()>,
+    /// Set by the thread once it starts
+    native: Arc<OnceLock<NativeThread>>,
+}
+
+/// Owns every background thread of a game instance
+/// Tasks share one cancellation token; shutdown cancels and joins them so no
+/// thread outlives the GameHandle
+#[derive(Default)]
+pub struct TaskGroup {
+    token: CancellationToken,
+    tasks: Vec<Task>,
+    /// Scheduling hints for every task thread; locked while a thread starts so none misses a change
+    hints: Arc<Mutex<ThreadHints>>,
+}
+
+impl TaskGroup {
+    /// Spawn a named background task that should return once the token is cancelled
+    pub fn spawn<F>(&mut self, name: &'static str, f: F) -> std::io::Result<()>
+    where
+        F: FnOnce(CancellationToken) + Send + 'static,
+    {
+        // Drop handles of tasks that already finished
+        self.tasks.retain(|t| !t.handle.is_finished());
+
+        let token = self.token.clone();
+        let hints = self.hints.clone();
+        let native = Arc::new(OnceLock::new());
+        let started = native.clone();
+        let handle = std::thread::Builder::new().name(format!("game-{}", name)).spawn(move || {
+            {
+                let hints = hints.lock().unwrap();
+                let _ = started.set(thread_hints::current_thread());
+                if !hints.is_default() {
+                    thread_hints::apply_current(&hints);
+                }
+            }
+            f(token)
+        })?;
+        self.tasks.push(Task { name, handle, native });
+        Ok(())
+    }
+
+    /// Scheduling hints for every task thread, running and future
+    /// Running threads only change where the OS allows it (Linux, Android); elsewhere the hints
+    /// reach tasks spawned later. Returns false if a running thread couldn't take them
+    pub fn set_hints(&mut self, hints: ThreadHints) -> bool {
+        let mut current = self.hints.lock().unwrap();
+        *current = hints;
+        let mut applied = true;
+        for task in self.tasks.iter().filter(|t| !t.handle.is_finished()) {
+            if let Some(&thread) = task.native.get() {
+                applied &= thread_hints::apply(thread, &hints);
+            }
+        }
+        applied
+    }
+
+    /// Cancel all tasks and join them, waiting at most `timeout` in total
+    /// Tasks still running after the timeout are logged and detached
+    pub fn shutdown(&mut self, timeout: Duration) {
+        self.token.cancel();
+        if self.tasks.is_empty() {
+            return;
+        }
+
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline && self.tasks.iter().any(|t| !t.handle.is_finished()) {
+            std::thread::sleep(Duration::from_millis(2));
+        }
+
+        for task in self.tasks.drain(..) {
+            if task.handle.is_finished() {
+                if task.handle.join().is_err() {
+                    log::error!("Task '{}' panicked", task.name);
+                }
+            } else {
+                log::error!("Task '{}' did not stop within {:?}, detaching", task.name, timeout);
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use egui::epaint::ClippedPrimitive;
+
+/// The scene and HUD passes as last tessellated, reused while the frame shows the same display list
+/// egui warns that comparing shapes costs half as much as tessellating them, so the shapes aren't
+/// compared: the caller vouches that nothing they're made from changed (the display list is the one
+/// last drawn, and no textures changed). The debug overlay changes every frame and isn't cached
+#[derive(Default)]
+pub struct TessellationCache {
+    /// Pixels per point the passes were tessellated at, and the scene and HUD primitives
+    passes: Option<(f32, [Vec<ClippedPrimitive>; 2])>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl TessellationCache {
+    /// The cached passes if `unchanged` and they were tessellated at `pixels_per_point`, otherwise
+    /// the ones `tessellate` makes, kept for next time
+    pub fn get_or_tessellate(
+        &mut self,
+        unchanged: bool,
+        pixels_per_point: f32,
+        tessellate: impl FnOnce() -> [Vec<ClippedPrimitive>; 2],
+    ) -> &[Vec<ClippedPrimitive>; 2] {
+        let hit = unchanged && self.passes.as_ref().is_some_and(|(ppp, _)| *ppp == pixels_per_point);
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+            self.passes = Some((pixels_per_point, tessellate()));
+        }
+        &self.passes.as_ref().unwrap().1
+    }
+
+    /// Forget the passes (their vertices can be large); the next frame tessellates again
+    pub fn clear(&mut self) {
+        self.passes = None;
+    }
+}
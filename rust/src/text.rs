@@ -0,0 +1,661 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ab_glyph::{Font as _, FontArc, GlyphId, GlyphImageFormat, PxScale};
+use egui::epaint::Mesh;
+use egui::{Align, Align2, Color32, ColorImage, Pos2, Rect, TextureHandle, TextureId, TextureOptions, Vec2};
+use unicode_bidi::{Level, ParagraphBidiInfo};
+
+/// Edge of a glyph atlas page in pixels
+pub const PAGE_SIZE: usize = 512;
+
+/// Most glyph atlas pages (1 MiB of texture each); past this, cold pages are evicted
+pub const MAX_PAGES: usize = 8;
+
+/// Most fonts in the fallback chain
+pub const MAX_FONTS: usize = 16;
+
+/// Empty pixels around each glyph so linear filtering doesn't pick up its neighbours
+const GLYPH_PADDING: usize = 1;
+
+/// Text alignment bits across FFI: one horizontal and one vertical value, or'd together
+pub const TEXT_ALIGN_LEFT: u32 = 0;
+pub const TEXT_ALIGN_CENTER: u32 = 1;
+pub const TEXT_ALIGN_RIGHT: u32 = 2;
+pub const TEXT_ALIGN_TOP: u32 = 0;
+pub const TEXT_ALIGN_MIDDLE: u32 = 4;
+pub const TEXT_ALIGN_BOTTOM: u32 = 8;
+
+/// Alignment from its FFI bits (TEXT_ALIGN_*); None for unknown values
+pub fn align_from_raw(bits: u32) -> Option<Align2> {
+    let x = match bits & 3 {
+        TEXT_ALIGN_LEFT => Align::Min,
+        TEXT_ALIGN_CENTER => Align::Center,
+        TEXT_ALIGN_RIGHT => Align::Max,
+        _ => return None,
+    };
+    let y = match bits & !3 {
+        TEXT_ALIGN_TOP => Align::Min,
+        TEXT_ALIGN_MIDDLE => Align::Center,
+        TEXT_ALIGN_BOTTOM => Align::Max,
+        _ => return None,
+    };
+    Some(Align2([x, y]))
+}
+
+/// Paragraph direction across FFI: from each line's first strong character, or forced to match the
+/// host's (Flutter's Directionality)
+pub const TEXT_DIRECTION_AUTO: u32 = 0;
+pub const TEXT_DIRECTION_LTR: u32 = 1;
+pub const TEXT_DIRECTION_RTL: u32 = 2;
+
+/// Base direction lines are reordered in (Unicode bidi paragraph level)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TextDirection {
+    /// From the line's first strong character, left to right if it has none
+    #[default]
+    Auto,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl TextDirection {
+    pub fn from_raw(raw: u32) -> Option<TextDirection> {
+        match raw {
+            TEXT_DIRECTION_AUTO => Some(TextDirection::Auto),
+            TEXT_DIRECTION_LTR => Some(TextDirection::LeftToRight),
+            TEXT_DIRECTION_RTL => Some(TextDirection::RightToLeft),
+            _ => None,
+        }
+    }
+
+    fn level(self) -> Option<Level> {
+        match self {
+            TextDirection::Auto => None,
+            TextDirection::LeftToRight => Some(Level::ltr()),
+            TextDirection::RightToLeft => Some(Level::rtl()),
+        }
+    }
+}
+
+/// Text a scene node draws (scores, labels), with the `align` corner or edge of its block on the
+/// node origin; it moves, turns and scales with the node
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub text: String,
+    /// Points per em
+    pub size: f32,
+    pub color: Color32,
+    pub align: Align2,
+    /// Glyph meshes in node-local points; None without fonts
+    pub glyphs: Option<Arc<[Mesh]>>,
+    /// Atlas generation the glyphs were laid out for; None until laid out, and again once the
+    /// text changes (the engine lays every text out in one pass, so they share atlas pages)
+    pub shaped_at: Option<u64>,
+}
+
+impl Label {
+    pub fn new(text: &str, size: f32, color: Color32, align: Align2) -> Label {
+        Label { text: text.to_string(), size, color, align, glyphs: None, shaped_at: None }
+    }
+
+    /// Lay the text out in the shaper's current pass
+    pub fn layout(&mut self, shaper: &mut TextShaper, ctx: &egui::Context, pixels_per_point: f32) {
+        let glyphs = shaper.layout(ctx, &self.text, Pos2::ZERO, self.align, self.size, self.color, pixels_per_point);
+        self.glyphs = glyphs.map(Into::into);
+    }
+
+    /// Local bounds: the glyphs drawn, or an estimate of egui's fallback layout without fonts
+    pub fn bounds(&self) -> Rect {
+        match &self.glyphs {
+            Some(glyphs) => glyphs.iter().map(Mesh::calc_bounds).fold(Rect::NOTHING, |a, b| a.union(b)),
+            None => {
+                let lines = self.text.split('\n');
+                let widest = lines.clone().map(|line| line.chars().count()).max().unwrap_or(0);
+                let size = Vec2::new(widest as f32 * 0.6, lines.count() as f32 * 1.2) * self.size;
+                self.align.anchor_size(Pos2::ZERO, size)
+            }
+        }
+    }
+}
+
+/// A font in the fallback chain
+struct Font {
+    /// Shaped straight from the file each time (parsing a face is cheap)
+    data: Arc<[u8]>,
+    raster: FontArc,
+    /// Has color bitmap glyphs (CBDT or sbix emoji fonts)
+    color: bool,
+}
+
+impl Font {
+    fn new(data: Vec<u8>) -> Result<Font, String> {
+        let data: Arc<[u8]> = data.into();
+        let face = rustybuzz::Face::from_slice(&data, 0).ok_or("Not a TrueType/OpenType font")?;
+        if face.units_per_em() == 0 {
+            return Err("Font has no units per em".to_string());
+        }
+        let color = face.tables().cbdt.is_some() || face.tables().sbix.is_some();
+        let raster = FontArc::try_from_vec(data.to_vec()).map_err(|e| e.to_string())?;
+        Ok(Font { data, raster, color })
+    }
+}
+
+/// Shapes text with a real shaping engine (rustybuzz: ligatures, Arabic joining, Indic reordering),
+/// reorders it per the Unicode bidi algorithm and falls back through the registered fonts for
+/// characters the first one lacks; glyphs are rasterized into a paged atlas shared by every text
+/// the engine draws
+#[derive(Default)]
+pub struct TextShaper {
+    /// Host fonts in registration order (the first is primary), then egui's bundled ones if built with them
+    fonts: Vec<Font>,
+    host_fonts: usize,
+    direction: TextDirection,
+    atlas: GlyphAtlas,
+}
+
+impl TextShaper {
+    /// A shaper falling back to egui's bundled fonts when built with them (debug-overlay feature)
+    pub fn new() -> TextShaper {
+        TextShaper { fonts: bundled_fonts(), ..TextShaper::default() }
+    }
+
+    /// Add a TrueType/OpenType font after the other host fonts in the fallback chain
+    pub fn add_font(&mut self, data: Vec<u8>) -> Result<(), String> {
+        if self.host_fonts == MAX_FONTS {
+            return Err(format!("At most {} fonts", MAX_FONTS));
+        }
+        self.fonts.insert(self.host_fonts, Font::new(data)?);
+        self.host_fonts += 1;
+        // Glyphs are cached by font index, which the bundled fonts' just moved
+        self.atlas.forget_fonts_from(self.host_fonts - 1);
+        Ok(())
+    }
+
+    pub fn font_count(&self) -> usize {
+        self.fonts.len()
+    }
+
+    /// Set the paragraph direction, making every layout stale if it changed
+    pub fn set_direction(&mut self, direction: TextDirection) {
+        if self.direction != direction {
+            self.direction = direction;
+            self.invalidate();
+        }
+    }
+
+    /// Start a layout pass: glyphs the pass draws keep their atlas pixels until the next pass, so
+    /// call it before laying out everything that will be drawn together
+    pub fn begin_pass(&mut self) {
+        self.atlas.pass += 1;
+    }
+
+    /// Changes when meshes laid out before may no longer match the atlas (glyphs were evicted,
+    /// or the scale changed), and need laying out again
+    pub fn generation(&self) -> u64 {
+        self.atlas.generation
+    }
+
+    /// Mark every layout stale, e.g. because it was rasterized for another scale
+    pub fn invalidate(&mut self) {
+        self.atlas.generation += 1;
+    }
+
+    /// Free the atlas pages the last pass didn't draw from, or every page unless `keep_current`
+    /// (their glyphs are rasterized again when next laid out); returns how many were freed
+    pub fn trim(&mut self, keep_current: bool) -> usize {
+        self.atlas.trim(keep_current)
+    }
+
+    pub fn atlas_stats(&self) -> GlyphAtlasStats {
+        self.atlas.stats()
+    }
+
+    /// Lay out `text` (lines split at '\n') with the `align` corner or edge of its block at `pos`, each
+    /// line aligned within the block the same way, `size` points per em, rasterized for `pixels_per_point`,
+    /// as one mesh per atlas page it draws from
+    /// None without fonts (callers fall back to egui's layout)
+    #[allow(clippy::too_many_arguments)]
+    pub fn layout(
+        &mut self,
+        ctx: &egui::Context,
+        text: &str,
+        pos: Pos2,
+        align: Align2,
+        size: f32,
+        color: Color32,
+        pixels_per_point: f32,
+    ) -> Option<Vec<Mesh>> {
+        let primary = rustybuzz::Face::from_slice(&self.fonts.first()?.data, 0)?;
+        let units = primary.units_per_em() as f32;
+        let ascent = primary.ascender() as f32 * size / units;
+        let line_height = (primary.ascender() - primary.descender() + primary.line_gap()) as f32 * size / units;
+        drop(primary);
+
+        // Shaped up front: aligning needs every line's width
+        let lines: Vec<_> = text.split('\n').map(|line| self.shape_line(line, size)).collect();
+        let widths: Vec<f32> = lines
+            .iter()
+            .map(|runs| runs.iter().flat_map(|(_, glyphs)| glyphs).map(|glyph| glyph.advance).sum())
+            .collect();
+        let block_width = widths.iter().copied().fold(0.0, f32::max);
+        let block = align.anchor_size(pos, Vec2::new(block_width, line_height * lines.len() as f32));
+
+        let mut meshes: Vec<Mesh> = Vec::new();
+        for (i, (runs, width)) in lines.into_iter().zip(widths).enumerate() {
+            let indent = (block_width - width) * align.x().to_factor();
+            let baseline = block.min + Vec2::new(indent, ascent + i as f32 * line_height);
+            let mut pen = 0.0;
+            for (font, glyphs) in runs {
+                for glyph in glyphs {
+                    let origin = baseline + Vec2::new(pen + glyph.offset.x, -glyph.offset.y);
+                    pen += glyph.advance;
+                    // Snapped to whole pixels so the atlas is sampled texel for texel
+                    let origin_px = (origin.to_vec2() * pixels_per_point).round();
+                    let key = GlyphKey { font, glyph: glyph.id, size_px: (size * pixels_per_point).to_bits() };
+                    let Some(slot) = self.atlas.get(ctx, key, &self.fonts[font].raster, size * pixels_per_point) else {
+                        continue;
+                    };
+                    let rect = Rect::from_min_size(
+                        ((origin_px + slot.offset) / pixels_per_point).to_pos2(),
+                        slot.size / pixels_per_point,
+                    );
+                    let page = meshes.iter().position(|mesh| mesh.texture_id == slot.texture).unwrap_or_else(|| {
+                        meshes.push(Mesh::with_texture(slot.texture));
+                        meshes.len() - 1
+                    });
+                    // Color glyphs keep their own colors, only taking the text's opacity
+                    let tint = if slot.color { Color32::from_white_alpha(color.a()) } else { color };
+                    meshes[page].add_rect_with_uv(rect, slot.uv, tint);
+                }
+            }
+        }
+        Some(meshes)
+    }
+
+    /// Glyphs of one line in visual order (left to right), in runs of one font each
+    fn shape_line(&self, line: &str, size: f32) -> Vec<(usize, Vec<ShapedGlyph>)> {
+        let bidi = ParagraphBidiInfo::new(line, self.direction.level());
+        let (levels, runs) = bidi.visual_runs(0..line.len());
+        let mut out = Vec::new();
+        for run in runs {
+            let rtl = levels[run.start].is_rtl();
+            let mut segments = self.font_segments(&line[run.clone()], run.start);
+            // Segments are in logical order; right-to-left runs place the last one leftmost
+            if rtl {
+                segments.reverse();
+            }
+            for (font, range) in segments {
+                let face = match rustybuzz::Face::from_slice(&self.fonts[font].data, 0) {
+                    Some(face) => face,
+                    None => continue,
+                };
+                let scale = size / face.units_per_em() as f32;
+                let mut buffer = rustybuzz::UnicodeBuffer::new();
+                buffer.push_str(&line[range]);
+                buffer.set_direction(if rtl { rustybuzz::Direction::RightToLeft } else { rustybuzz::Direction::LeftToRight });
+                let shaped = rustybuzz::shape(&face, &[], buffer);
+                // Right-to-left output is already in visual order
+                let glyphs = shaped
+                    .glyph_infos()
+                    .iter()
+                    .zip(shaped.glyph_positions())
+                    .map(|(info, position)| ShapedGlyph {
+                        id: info.glyph_id as u16,
+                        advance: position.x_advance as f32 * scale,
+                        offset: Vec2::new(position.x_offset as f32, position.y_offset as f32) * scale,
+                    })
+                    .collect();
+                out.push((font, glyphs));
+            }
+        }
+        out
+    }
+
+    /// Split `text` (starting at byte `start` of its line) into runs of the first font covering
+    /// each character; marks, spaces and characters joined on (ZWJ emoji sequences) stay with the
+    /// font before them when it has them, and characters asking for emoji presentation (followed by
+    /// U+FE0F) prefer a color font
+    fn font_segments(&self, text: &str, start: usize) -> Vec<(usize, std::ops::Range<usize>)> {
+        let mut segments: Vec<(usize, std::ops::Range<usize>)> = Vec::new();
+        let faces: Vec<_> = self.fonts.iter().map(|f| rustybuzz::Face::from_slice(&f.data, 0)).collect();
+        let has = |font: usize, c: char| faces[font].as_ref().is_some_and(|f| f.glyph_index(c).is_some());
+        let mut chars = text.char_indices().peekable();
+        let mut joined = false;
+        while let Some((i, c)) = chars.next() {
+            let range = start + i..start + i + c.len_utf8();
+            let current = segments.last().map(|(font, _)| *font);
+            let keep = current.filter(|&font| (joined || c.is_whitespace() || is_mark(c)) && has(font, c));
+            let emoji = chars.peek().is_some_and(|&(_, next)| next == '\u{FE0F}');
+            let font = keep
+                .or_else(|| (0..faces.len()).find(|&font| emoji && self.fonts[font].color && has(font, c)))
+                .or_else(|| (0..faces.len()).find(|&font| has(font, c)))
+                .unwrap_or(0);
+            joined = c == '\u{200D}';
+            match segments.last_mut() {
+                Some((last, r)) if *last == font => r.end = range.end,
+                _ => segments.push((font, range)),
+            }
+        }
+        segments
+    }
+}
+
+#[cfg(feature = "debug-overlay")]
+fn bundled_fonts() -> Vec<Font> {
+    let fonts = egui::FontDefinitions::default().font_data.into_values();
+    fonts
+        .filter_map(|data| Font::new(data.font.to_vec()).map_err(|e| log::warn!("Skipping bundled font: {}", e)).ok())
+        .collect()
+}
+
+#[cfg(not(feature = "debug-overlay"))]
+fn bundled_fonts() -> Vec<Font> {
+    Vec::new()
+}
+
+/// Combining marks (vowel signs, diacritics), joiners and emoji modifiers (variation selectors,
+/// skin tones, keycaps, flag tags) belong with the character before them
+fn is_mark(c: char) -> bool {
+    matches!(c as u32, 0x0300..=0x036F | 0x0591..=0x05C7 | 0x064B..=0x065F | 0x0670 | 0x0900..=0x0903 | 0x093A..=0x094F | 0x0951..=0x0957 | 0x0962..=0x0963 | 0x200C..=0x200D)
+        || matches!(c as u32, 0x20E3 | 0xFE00..=0xFE0F | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F)
+}
+
+
+struct ShapedGlyph {
+    id: u16,
+    /// Points
+    advance: f32,
+    /// Points, y up
+    offset: Vec2,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: usize,
+    glyph: u16,
+    /// f32 bits of the pixel size
+    size_px: u32,
+}
+
+/// Where a rasterized glyph sits in the atlas
+#[derive(Clone, Copy)]
+struct GlyphSlot {
+    page: usize,
+    texture: TextureId,
+    uv: Rect,
+    /// Pixels from the pen position to the bitmap's top left
+    offset: Vec2,
+    /// Bitmap size in pixels
+    size: Vec2,
+    /// Colored pixels (emoji), drawn untinted; others are white coverage tinted with the text color
+    color: bool,
+}
+
+/// Glyph atlas occupancy for game_get_glyph_atlas_stats
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GlyphAtlasStats {
+    /// Texture pages allocated (PAGE_SIZE square each)
+    pub pages: u32,
+    /// Most pages before cold ones are evicted
+    pub max_pages: u32,
+    /// Glyph bitmaps cached
+    pub glyphs: u32,
+    /// Fraction of the pages' pixels handed out to glyphs (0 to 1)
+    pub occupancy: f32,
+    /// Texture memory held by the pages
+    pub texture_bytes: u64,
+    /// Glyphs rasterized since init; evicted ones count again when they're drawn again
+    pub rasterized: u64,
+    /// Glyphs dropped to make room for others, or by game_trim_glyph_atlas
+    pub evicted: u64,
+}
+
+/// One texture of glyph bitmaps (white, coverage in alpha), packed in shelves
+struct Page {
+    texture: TextureHandle,
+    /// Top and height of each shelf, and how far along it is filled
+    shelves: Vec<(usize, usize, usize)>,
+    /// Pixels handed out
+    used: usize,
+    /// Layout pass that last drew from the page
+    last_used: u64,
+}
+
+impl Page {
+    fn new(ctx: &egui::Context, index: usize, pass: u64) -> Page {
+        let image = ColorImage::new([PAGE_SIZE; 2], Color32::TRANSPARENT);
+        let texture = ctx.load_texture(format!("glyph_atlas_{}", index), image, TextureOptions::LINEAR);
+        Page { texture, shelves: Vec::new(), used: 0, last_used: pass }
+    }
+
+    /// Top left of a free `width` x `height` area: the shortest shelf it fits on, or a new shelf
+    fn allocate(&mut self, width: usize, height: usize) -> Option<[usize; 2]> {
+        let best = self
+            .shelves
+            .iter_mut()
+            .filter(|(_, h, filled)| *h >= height && *filled + width <= PAGE_SIZE)
+            .min_by_key(|(_, h, _)| *h);
+        let position = match best {
+            Some((top, _, filled)) => {
+                let position = [*filled, *top];
+                *filled += width;
+                position
+            }
+            None => {
+                let top = self.shelves.last().map_or(0, |(top, h, _)| top + h);
+                if top + height > PAGE_SIZE {
+                    return None;
+                }
+                self.shelves.push((top, height, width));
+                [0, top]
+            }
+        };
+        self.used += width * height;
+        Some(position)
+    }
+}
+
+/// Glyph bitmaps for all the text the engine draws, in pages added as they fill up. Past MAX_PAGES
+/// the page drawn from longest ago is cleared for new glyphs; pages the current layout pass drew
+/// from are never evicted, so a pass's meshes stay valid until the generation moves
+#[derive(Default)]
+struct GlyphAtlas {
+    pages: Vec<Page>,
+    /// Bitmap per glyph; None for glyphs with no outline (spaces) or that didn't fit
+    glyphs: HashMap<GlyphKey, Option<GlyphSlot>>,
+    /// Current layout pass (TextShaper::begin_pass)
+    pass: u64,
+    /// Bumped when glyphs lose their pixels, so meshes laid out before may sample the wrong ones
+    generation: u64,
+    rasterized: u64,
+    evicted: u64,
+    warned_full: bool,
+}
+
+impl GlyphAtlas {
+    /// Drop the cached slots of fonts `font` and after (their pixels stay allocated)
+    fn forget_fonts_from(&mut self, font: usize) {
+        self.glyphs.retain(|key, _| key.font < font);
+    }
+
+    /// Slot of `key`, rasterizing it from `font` at `size_px` pixels per em on first use
+    fn get(&mut self, ctx: &egui::Context, key: GlyphKey, font: &FontArc, size_px: f32) -> Option<GlyphSlot> {
+        let slot = match self.glyphs.get(&key) {
+            Some(slot) => *slot,
+            None => {
+                let slot = self.rasterize(ctx, font, key.glyph, size_px);
+                self.glyphs.insert(key, slot);
+                slot
+            }
+        };
+        if let Some(slot) = slot {
+            self.pages[slot.page].last_used = self.pass;
+        }
+        slot
+    }
+
+    fn rasterize(&mut self, ctx: &egui::Context, font: &FontArc, glyph: u16, size_px: f32) -> Option<GlyphSlot> {
+        let (pixels, offset, color) = outline_pixels(font, glyph, size_px).or_else(|| color_pixels(font, glyph, size_px))?;
+        let [width, height] = pixels.size;
+        let Some((page, [x, y])) = self.allocate(ctx, width + GLYPH_PADDING * 2, height + GLYPH_PADDING * 2) else {
+            if !std::mem::replace(&mut self.warned_full, true) {
+                log::warn!("Glyph atlas full of glyphs in use; new glyphs are skipped");
+            }
+            return None;
+        };
+        // Padding included, so a reused page's old pixels don't bleed in
+        let mut image = ColorImage::new([width + GLYPH_PADDING * 2, height + GLYPH_PADDING * 2], Color32::TRANSPARENT);
+        let stride = image.size[0];
+        for (row, pixels) in pixels.pixels.chunks_exact(width).enumerate() {
+            let start = (row + GLYPH_PADDING) * stride + GLYPH_PADDING;
+            image.pixels[start..start + width].copy_from_slice(pixels);
+        }
+        let texture = &mut self.pages[page].texture;
+        texture.set_partial([x, y], image, TextureOptions::LINEAR);
+        self.rasterized += 1;
+
+        let min = [x + GLYPH_PADDING, y + GLYPH_PADDING].map(|v| v as f32 / PAGE_SIZE as f32);
+        let size = Vec2::new(width as f32, height as f32);
+        Some(GlyphSlot {
+            page,
+            texture: texture.id(),
+            uv: Rect::from_min_size(Pos2::new(min[0], min[1]), size / PAGE_SIZE as f32),
+            offset,
+            size,
+            color,
+        })
+    }
+
+    /// Page and top left of a free `width` x `height` area: on a page with room, a new page, or
+    /// the coldest page cleared; None if every page is full of glyphs the current pass uses
+    fn allocate(&mut self, ctx: &egui::Context, width: usize, height: usize) -> Option<(usize, [usize; 2])> {
+        if width > PAGE_SIZE || height > PAGE_SIZE {
+            return None;
+        }
+        let found = self.pages.iter_mut().enumerate().find_map(|(i, page)| Some((i, page.allocate(width, height)?)));
+        if found.is_some() {
+            return found;
+        }
+        let page = if self.pages.len() < MAX_PAGES {
+            self.pages.push(Page::new(ctx, self.pages.len(), self.pass));
+            self.pages.len() - 1
+        } else {
+            let pass = self.pass;
+            let (coldest, _) =
+                self.pages.iter().enumerate().filter(|(_, p)| p.last_used < pass).min_by_key(|(_, p)| p.last_used)?;
+            self.clear_page(coldest);
+            coldest
+        };
+        Some((page, self.pages[page].allocate(width, height)?))
+    }
+
+    /// Evict every glyph on `page`, leaving it empty (its stale pixels are overwritten as it refills)
+    fn clear_page(&mut self, page: usize) {
+        let before = self.glyphs.len();
+        self.glyphs.retain(|_, slot| slot.is_none_or(|slot| slot.page != page));
+        self.evicted += (before - self.glyphs.len()) as u64;
+        let page = &mut self.pages[page];
+        page.shelves.clear();
+        page.used = 0;
+        self.generation += 1;
+    }
+
+    /// Free the pages the current pass didn't draw from, or all of them unless `keep_current`;
+    /// returns how many were freed
+    fn trim(&mut self, keep_current: bool) -> usize {
+        let keep: Vec<bool> = self.pages.iter().map(|p| keep_current && p.last_used == self.pass).collect();
+        let freed = keep.iter().filter(|k| !**k).count();
+        if freed == 0 {
+            return 0;
+        }
+        // New index of each kept page
+        let mut next = 0;
+        let index: Vec<usize> = keep
+            .iter()
+            .map(|&k| {
+                let i = next;
+                next += k as usize;
+                i
+            })
+            .collect();
+        let evicted = &mut self.evicted;
+        self.glyphs.retain(|_, slot| match slot {
+            Some(slot) if keep[slot.page] => {
+                slot.page = index[slot.page];
+                true
+            }
+            Some(_) => {
+                *evicted += 1;
+                false
+            }
+            None => true,
+        });
+        let mut pages = keep.iter();
+        self.pages.retain(|_| *pages.next().unwrap_or(&false));
+        self.generation += 1;
+        freed
+    }
+
+    fn stats(&self) -> GlyphAtlasStats {
+        let area = self.pages.len() * PAGE_SIZE * PAGE_SIZE;
+        GlyphAtlasStats {
+            pages: self.pages.len() as u32,
+            max_pages: MAX_PAGES as u32,
+            glyphs: self.glyphs.values().filter(|slot| slot.is_some()).count() as u32,
+            occupancy: if area > 0 { self.pages.iter().map(|p| p.used).sum::<usize>() as f32 / area as f32 } else { 0.0 },
+            texture_bytes: (area * 4) as u64,
+            rasterized: self.rasterized,
+            evicted: self.evicted,
+        }
+    }
+}
+
+/// Coverage of an outline glyph (white, coverage in alpha) and its top left from the pen, in pixels;
+/// None for glyphs without an outline
+fn outline_pixels(font: &FontArc, glyph: u16, size_px: f32) -> Option<(ColorImage, Vec2, bool)> {
+    // ab_glyph scales by ascent - descent, not the em
+    let units_per_em = font.units_per_em()?;
+    let scale = PxScale::from(size_px * font.height_unscaled() / units_per_em);
+    let outline = font.outline_glyph(GlyphId(glyph).with_scale(scale))?;
+    let bounds = outline.px_bounds();
+    let [width, height] = [bounds.width() as usize, bounds.height() as usize];
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let mut image = ColorImage::new([width, height], Color32::TRANSPARENT);
+    outline.draw(|x, y, coverage| {
+        image.pixels[y as usize * width + x as usize] = Color32::from_white_alpha((coverage.clamp(0.0, 1.0) * 255.0).round() as u8);
+    });
+    Some((image, Vec2::new(bounds.min.x, bounds.min.y), false))
+}
+
+/// Color bitmap glyph (CBDT/sbix emoji) scaled from the nearest strike, and its top left from the pen,
+/// in pixels; None if the font has no PNG or BGRA bitmap for it
+fn color_pixels(font: &FontArc, glyph: u16, size_px: f32) -> Option<(ColorImage, Vec2, bool)> {
+    let strike = font.glyph_raster_image2(GlyphId(glyph), size_px.ceil() as u16)?;
+    let bitmap = match strike.format {
+        GlyphImageFormat::Png => image::load_from_memory_with_format(strike.data, image::ImageFormat::Png).ok()?.to_rgba8(),
+        GlyphImageFormat::BitmapPremulBgra32 => {
+            let (width, height) = (strike.width as u32, strike.height as u32);
+            let mut rgba = strike.data.get(..(width * height * 4) as usize)?.to_vec();
+            for pixel in rgba.chunks_exact_mut(4) {
+                let a = pixel[3];
+                let unmultiply = |c: u8| if a == 0 { 0 } else { (c as u32 * 255 / a as u32).min(255) as u8 };
+                let [b, g, r] = [pixel[0], pixel[1], pixel[2]].map(unmultiply);
+                pixel.copy_from_slice(&[r, g, b, a]);
+            }
+            image::RgbaImage::from_raw(width, height, rgba)?
+        }
+        _ => return None,
+    };
+    let scale = size_px / strike.pixels_per_em.max(1) as f32;
+    let width = ((bitmap.width() as f32 * scale).round() as u32).max(1);
+    let height = ((bitmap.height() as f32 * scale).round() as u32).max(1);
+    let scaled = image::imageops::resize(&bitmap, width, height, image::imageops::FilterType::Triangle);
+    let image = ColorImage::from_rgba_unmultiplied([width as usize, height as usize], scaled.as_raw());
+    // The strike's origin is its bottom left from the pen, y up
+    let offset = Vec2::new(strike.origin.x, -(strike.origin.y + bitmap.height() as f32)) * scale;
+    Some((image, offset, true))
+}
@@ -0,0 +1,125 @@
+/// Scheduling hints for the engine's own threads
+/// Lets integrators keep work on big cores of heterogeneous (big.LITTLE) CPUs
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ThreadHints {
+    /// Bit n allows CPU n; 0 leaves affinity alone
+    pub affinity_mask: u64,
+    /// Nice value, -20 (most urgent) to 19, as in Android's Process.setThreadPriority; 0 leaves it alone
+    pub priority: i32,
+}
+
+impl ThreadHints {
+    pub fn is_default(&self) -> bool {
+        *self == ThreadHints::default()
+    }
+}
+
+/// OS thread id that hints can be applied to from another thread
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub type NativeThread = i32;
+
+/// Other platforms only let a thread change its own scheduling
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub type NativeThread = ();
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod sys {
+    use super::{NativeThread, ThreadHints};
+
+    /// cpu_set_t is a 1024-bit mask
+    const CPU_SET_WORDS: usize = 16;
+    const PRIO_PROCESS: i32 = 0;
+
+    extern "C" {
+        fn gettid() -> i32;
+        fn sched_setaffinity(pid: i32, size: usize, mask: *const u64) -> i32;
+        fn setpriority(which: i32, who: u32, prio: i32) -> i32;
+    }
+
+    pub fn current() -> NativeThread {
+        unsafe { gettid() }
+    }
+
+    pub fn apply(thread: NativeThread, hints: &ThreadHints) -> bool {
+        let mut applied = true;
+        if hints.affinity_mask != 0 {
+            let mut mask = [0u64; CPU_SET_WORDS];
+            mask[0] = hints.affinity_mask;
+            if unsafe { sched_setaffinity(thread, std::mem::size_of_val(&mask), mask.as_ptr()) } != 0 {
+                log::warn!("sched_setaffinity({:#x}): {}", hints.affinity_mask, std::io::Error::last_os_error());
+                applied = false;
+            }
+        }
+        if hints.priority != 0 {
+            // On Linux the nice value is per thread when `who` is a thread id
+            if unsafe { setpriority(PRIO_PROCESS, thread as u32, hints.priority.clamp(-20, 19)) } != 0 {
+                log::warn!("setpriority({}): {}", hints.priority, std::io::Error::last_os_error());
+                applied = false;
+            }
+        }
+        applied
+    }
+}
+
+#[cfg(target_vendor = "apple")]
+mod sys {
+    use super::{NativeThread, ThreadHints};
+
+    const QOS_CLASS_USER_INTERACTIVE: u32 = 0x21;
+    const QOS_CLASS_USER_INITIATED: u32 = 0x19;
+    const QOS_CLASS_UTILITY: u32 = 0x11;
+
+    extern "C" {
+        fn pthread_set_qos_class_self_np(qos_class: u32, relative_priority: i32) -> i32;
+    }
+
+    pub fn current() -> NativeThread {}
+
+    /// No affinity API; the priority picks a QoS class, which also steers the thread between core types
+    pub fn apply_current(hints: &ThreadHints) -> bool {
+        let qos = match hints.priority {
+            0 => return hints.affinity_mask == 0,
+            i32::MIN..=-8 => QOS_CLASS_USER_INTERACTIVE,
+            -7..=-1 => QOS_CLASS_USER_INITIATED,
+            _ => QOS_CLASS_UTILITY,
+        };
+        let result = unsafe { pthread_set_qos_class_self_np(qos, 0) };
+        if result != 0 {
+            log::warn!("pthread_set_qos_class_self_np({:#x}) failed: {}", qos, result);
+        }
+        result == 0 && hints.affinity_mask == 0
+    }
+
+    pub fn apply(_thread: NativeThread, _hints: &ThreadHints) -> bool {
+        false
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+mod sys {
+    use super::{NativeThread, ThreadHints};
+
+    pub fn current() -> NativeThread {}
+
+    pub fn apply(_thread: NativeThread, hints: &ThreadHints) -> bool {
+        hints.is_default()
+    }
+}
+
+/// Id of the calling thread
+pub fn current_thread() -> NativeThread {
+    sys::current()
+}
+
+/// Apply hints to the calling thread; false if any part couldn't be applied
+pub fn apply_current(hints: &ThreadHints) -> bool {
+    #[cfg(target_vendor = "apple")]
+    return sys::apply_current(hints);
+    #[cfg(not(target_vendor = "apple"))]
+    sys::apply(sys::current(), hints)
+}
+
+/// Apply hints to another running thread; only supported on Linux and Android
+pub fn apply(thread: NativeThread, hints: &ThreadHints) -> bool {
+    sys::apply(thread, hints)
+}
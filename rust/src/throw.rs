@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use egui::{Pos2, Vec2};
+
+/// Only the drag's last stretch sets the release velocity
+const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+/// Drags whose last stretch is shorter than this are taps, never throws
+const MIN_SPAN: Duration = Duration::from_millis(8);
+
+/// Drag samples kept (moves arrive at up to a few hundred Hz)
+const MAX_SAMPLES: usize = 32;
+
+/// Releases slower than this (points/s) drop the player where it is
+const MIN_THROW_SPEED: f32 = 50.0;
+
+/// Fastest throw (points/s); quicker flicks are capped
+const MAX_THROW_SPEED: f32 = 4000.0;
+
+/// A thrown player comes to rest below this speed (points/s)
+const REST_SPEED: f32 = 5.0;
+
+/// Recent positions of a drag, to tell how fast the finger was moving when it lifted
+#[derive(Default)]
+pub struct DragTracker {
+    samples: VecDeque<(Instant, Pos2)>,
+}
+
+impl DragTracker {
+    pub fn start(&mut self, at: Instant, position: Pos2) {
+        self.samples.clear();
+        self.push(at, position);
+    }
+
+    pub fn push(&mut self, at: Instant, position: Pos2) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, position));
+    }
+
+    /// Velocity (points/s) over the window before `at` when the drag ended there; zero if the finger
+    /// rested before lifting or the drag was too short to tell. Forgets the drag
+    pub fn release(&mut self, at: Instant, position: Pos2) -> Vec2 {
+        let first = self.samples.iter().find(|(t, _)| at.saturating_duration_since(*t) <= VELOCITY_WINDOW).copied();
+        self.samples.clear();
+        let Some((first_at, first)) = first else {
+            return Vec2::ZERO;
+        };
+        let span = at.saturating_duration_since(first_at);
+        if span < MIN_SPAN {
+            return Vec2::ZERO;
+        }
+        let velocity = (position - first) / span.as_secs_f32();
+        velocity * (MAX_THROW_SPEED / velocity.length()).min(1.0)
+    }
+}
+
+/// The player's glide after being thrown, slowed by friction (Param::Friction, an exponential
+/// decay rate per second) until it rests
+#[derive(Default)]
+pub struct Throw {
+    velocity: Vec2,
+}
+
+impl Throw {
+    /// Glide at `velocity` (points/s) from now, unless throwing is off (no friction) or it's too slow
+    pub fn start(&mut self, velocity: Vec2, friction: f32) {
+        let thrown = friction > 0.0 && velocity.is_finite() && velocity.length() >= MIN_THROW_SPEED;
+        self.velocity = if thrown { velocity } else { Vec2::ZERO };
+    }
+
+    pub fn stop(&mut self) {
+        self.velocity = Vec2::ZERO;
+    }
+
+    /// Stop moving along an axis (the player hit a wall there)
+    pub fn stop_axis(&mut self, x: bool, y: bool) {
+        if x {
+            self.velocity.x = 0.0;
+        }
+        if y {
+            self.velocity.y = 0.0;
+        }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.velocity != Vec2::ZERO
+    }
+
+    /// Distance covered over the next `delta` seconds, slowing down meanwhile
+    pub fn step(&mut self, delta: f32, friction: f32) -> Vec2 {
+        if !self.is_moving() || friction <= 0.0 {
+            self.stop();
+            return Vec2::ZERO;
+        }
+        // Exact integral of v * e^(-friction * t) over the step
+        let decay = (-friction * delta).exp();
+        let distance = self.velocity * (1.0 - decay) / friction;
+        self.velocity *= decay;
+        if self.velocity.length() < REST_SPEED {
+            self.velocity = Vec2::ZERO;
+        }
+        distance
+    }
+}
@@ -0,0 +1,115 @@
+use egui::{Pos2, Vec2};
+
+use super::{checked_cells, GridDef, LayerDef, MapDef, SpawnDef, TileDef, TilesetDef, FLIP_X, FLIP_Y};
+use crate::json::Value;
+
+/// Map from an LDtk project: every level's layers, each level placed at its world position
+/// IntGrid layers are collision layers (any non-zero value is solid); entities spawn the prefab named
+/// by their identifier
+pub fn from_json(root: &Value) -> Result<MapDef, String> {
+    let number = |value: &Value, key: &str| value.get(key).and_then(Value::as_f32);
+
+    let mut uids = Vec::new();
+    let mut tilesets = Vec::new();
+    let defs = root.get("defs").and_then(|defs| defs.get("tilesets")).and_then(Value::as_array);
+    for tileset in defs.unwrap_or_default() {
+        // Embedded atlases (LDtk's own icons) have no image path
+        let Some(image) = tileset.get("relPath").and_then(Value::as_str) else {
+            continue;
+        };
+        let (Some(uid), Some(width), Some(height)) = (number(tileset, "uid"), number(tileset, "pxWid"), number(tileset, "pxHei")) else {
+            return Err(format!("LDtk tileset '{}' is missing its uid or size", image));
+        };
+        uids.push(uid as i64);
+        tilesets.push(TilesetDef { image: image.to_string(), image_size: Vec2::new(width, height) });
+    }
+
+    let mut layers = Vec::new();
+    let mut spawns = Vec::new();
+    let mut cell = None;
+    for level in root.get("levels").and_then(Value::as_array).unwrap_or_default() {
+        let identifier = level.get("identifier").and_then(Value::as_str).unwrap_or_default();
+        let Some(instances) = level.get("layerInstances").and_then(Value::as_array) else {
+            return Err(format!("Level '{}' is saved separately; turn off 'Save levels to separate files'", identifier));
+        };
+        let origin = Vec2::new(number(level, "worldX").unwrap_or(0.0), number(level, "worldY").unwrap_or(0.0));
+        // LDtk lists layers top first
+        for layer in instances.iter().rev() {
+            let name = layer.get("__identifier").and_then(Value::as_str).unwrap_or_default();
+            let kind = layer.get("__type").and_then(Value::as_str).unwrap_or_default();
+            let offset = origin
+                + Vec2::new(
+                    number(layer, "__pxTotalOffsetX").unwrap_or(0.0),
+                    number(layer, "__pxTotalOffsetY").unwrap_or(0.0),
+                );
+            if kind == "Entities" {
+                for entity in layer.get("entityInstances").and_then(Value::as_array).unwrap_or_default() {
+                    let pair = |key: &str| {
+                        let values = entity.get(key)?.as_array()?;
+                        Some(Vec2::new(values.first()?.as_f32()?, values.get(1)?.as_f32()?))
+                    };
+                    let (Some(prefab), Some(position)) = (entity.get("__identifier").and_then(Value::as_str), pair("px")) else {
+                        return Err(format!("Layer '{}' has an entity without a name or position", name));
+                    };
+                    // px is the pivot point; the spawn is the center
+                    let size = Vec2::new(number(entity, "width").unwrap_or(0.0), number(entity, "height").unwrap_or(0.0));
+                    let pivot = pair("__pivot").unwrap_or(Vec2::ZERO);
+                    let center = position - pivot * size + size / 2.0 + offset;
+                    spawns.push(SpawnDef { prefab: prefab.to_string(), position: center.to_pos2() });
+                }
+                continue;
+            }
+            let grid = number(layer, "__gridSize").filter(|&size| size > 0.0).ok_or_else(|| format!("Layer '{}' has no grid size", name))?;
+            cell.get_or_insert(Vec2::splat(grid));
+
+            let mut tiles = Vec::new();
+            if let Some(uid) = number(layer, "__tilesetDefUid") {
+                let tileset = uids.iter().position(|&u| u == uid as i64).ok_or_else(|| format!("Layer '{}' uses an unknown tileset", name))?;
+                let placed = ["gridTiles", "autoLayerTiles"].into_iter().filter_map(|key| layer.get(key)?.as_array());
+                for tile in placed.flatten() {
+                    let pair = |key: &str| {
+                        let values = tile.get(key)?.as_array()?;
+                        Some(Pos2::new(values.first()?.as_f32()?, values.get(1)?.as_f32()?))
+                    };
+                    let (Some(position), Some(source)) = (pair("px"), pair("src")) else {
+                        return Err(format!("Layer '{}' has a tile without a position", name));
+                    };
+                    let flip = number(tile, "f").unwrap_or(0.0) as u8;
+                    tiles.push(TileDef {
+                        tileset,
+                        position: position + offset,
+                        size: Vec2::splat(grid),
+                        source,
+                        flip: flip & (FLIP_X | FLIP_Y),
+                    });
+                }
+            }
+
+            let collision = match layer.get("intGridCsv").and_then(Value::as_array) {
+                Some(values) if kind == "IntGrid" => {
+                    let width = number(layer, "__cWid").unwrap_or(0.0) as usize;
+                    let height = number(layer, "__cHei").unwrap_or(0.0) as usize;
+                    if values.len() != checked_cells(width, height)? {
+                        return Err(format!("Layer '{}' has {} values for {}x{} cells", name, values.len(), width, height));
+                    }
+                    Some(GridDef {
+                        origin: offset.to_pos2(),
+                        cell: Vec2::splat(grid),
+                        width,
+                        height,
+                        solid: values.iter().map(|value| value.as_f64().unwrap_or(0.0) != 0.0).collect(),
+                    })
+                }
+                _ => None,
+            };
+            layers.push(LayerDef {
+                name: name.to_string(),
+                visible: layer.get("visible").and_then(Value::as_bool).unwrap_or(true),
+                opacity: number(layer, "__opacity").unwrap_or(1.0),
+                tiles,
+                collision,
+            });
+        }
+    }
+    Ok(MapDef { cell: cell.unwrap_or(Vec2::splat(16.0)), tilesets, layers, spawns })
+}
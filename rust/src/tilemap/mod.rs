@@ -0,0 +1,308 @@
+mod ldtk;
+mod tiled;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use egui::epaint::Vertex;
+use egui::{Color32, Mesh, Pos2, Rect, TextureId, Vec2};
+
+use crate::arena::{Id, INVALID_ID};
+use crate::assets;
+use crate::ffi::strings::{self, NameId};
+use crate::json;
+use crate::xml;
+
+/// Tilemap id handed across FFI
+pub type TilemapId = Id;
+
+/// Invalid tilemap id returned on failure
+pub const INVALID_TILEMAP: TilemapId = INVALID_ID;
+
+/// Chunks are this many map cells on a side; one mesh (draw call) per chunk and tileset
+const CHUNK_CELLS: f32 = 16.0;
+
+/// Most cells a map or layer may have, so a hostile file can't make the engine allocate gigabytes
+const MAX_CELLS: usize = 1 << 22;
+
+/// Tile drawn mirrored across its vertical axis
+const FLIP_X: u8 = 1;
+/// Mirrored across its horizontal axis
+const FLIP_Y: u8 = 2;
+/// Mirrored across its top-left to bottom-right diagonal (before the other flips)
+const FLIP_DIAGONAL: u8 = 4;
+
+/// Tile sheet image, by path as written in the map file
+struct TilesetDef {
+    image: String,
+    /// Pixels
+    image_size: Vec2,
+}
+
+/// One placed tile, in map pixels
+struct TileDef {
+    tileset: usize,
+    /// Top left of where it's drawn
+    position: Pos2,
+    size: Vec2,
+    /// Sub-image of the tileset, top left in image pixels
+    source: Pos2,
+    flip: u8,
+}
+
+/// Cells of a collision layer, row by row
+struct GridDef {
+    origin: Pos2,
+    cell: Vec2,
+    width: usize,
+    height: usize,
+    solid: Vec<bool>,
+}
+
+struct LayerDef {
+    name: String,
+    visible: bool,
+    opacity: f32,
+    tiles: Vec<TileDef>,
+    collision: Option<GridDef>,
+}
+
+/// Object placed in the map, naming the prefab to spawn there
+struct SpawnDef {
+    prefab: String,
+    /// Center of the object
+    position: Pos2,
+}
+
+/// A map file parsed into what the engine draws and collides with, layers bottom first (map pixels)
+struct MapDef {
+    /// Cell size of the map grid, which chunks are laid out on
+    cell: Vec2,
+    tilesets: Vec<TilesetDef>,
+    layers: Vec<LayerDef>,
+    spawns: Vec<SpawnDef>,
+}
+
+/// Tiles of one layer and tileset in a chunk, batched into one mesh in world points
+/// The mesh's texture id is filled in when drawn: the tileset's texture may load later
+pub struct Chunk {
+    pub tileset: usize,
+    pub bounds: Rect,
+    pub mesh: Arc<Mesh>,
+}
+
+/// Solid cells of a collision layer in world points
+struct Grid {
+    origin: Pos2,
+    cell: Vec2,
+    width: usize,
+    height: usize,
+    solid: Vec<bool>,
+}
+
+impl Grid {
+    /// Cell range covering `rect`, clamped to the grid; None if it misses the grid
+    fn cells(&self, rect: Rect) -> Option<([usize; 2], [usize; 2])> {
+        let min = ((rect.min - self.origin) / self.cell).floor();
+        // One past the cell holding the max corner
+        let max = ((rect.max - self.origin) / self.cell).floor() + Vec2::splat(1.0);
+        if max.x <= 0.0 || max.y <= 0.0 || min.x >= self.width as f32 || min.y >= self.height as f32 {
+            return None;
+        }
+        let x = [min.x.max(0.0) as usize, (max.x as usize).min(self.width)];
+        let y = [min.y.max(0.0) as usize, (max.y as usize).min(self.height)];
+        Some((x, y))
+    }
+
+    fn cell_rect(&self, x: usize, y: usize) -> Rect {
+        Rect::from_min_size(self.origin + Vec2::new(x as f32, y as f32) * self.cell, self.cell)
+    }
+}
+
+pub struct Layer {
+    pub name: String,
+    pub visible: bool,
+    pub chunks: Vec<Chunk>,
+    collision: Option<Grid>,
+}
+
+/// Tile layers loaded from a Tiled (TMX or JSON) or LDtk map, drawn beneath entities
+/// Tileset images are textures the host loads under the image's file stem ("tiles/cave.png" -> "cave")
+/// Objects (Tiled) and entities (LDtk) name prefabs to spawn: by class or type, else name, in Tiled
+pub struct Tilemap {
+    pub layers: Vec<Layer>,
+    /// Texture name of each tileset
+    textures: Vec<NameId>,
+    tile_count: usize,
+    /// Prefab names and world centers
+    spawns: Vec<(String, Pos2)>,
+}
+
+impl Tilemap {
+    /// Parse a map file, placing its top left at `origin` with one map pixel `scale` world points
+    /// The format is told from the content: XML is TMX, JSON with "levels" LDtk, other JSON Tiled's
+    pub fn parse(bytes: &[u8], origin: Pos2, scale: f32) -> Result<Tilemap, String> {
+        let text = std::str::from_utf8(bytes).map_err(|_| "Map file isn't UTF-8".to_string())?;
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        let map = if text.starts_with('<') {
+            tiled::from_tmx(&xml::parse(text)?)?
+        } else {
+            let root = json::parse(text)?;
+            if root.get("levels").is_some() {
+                ldtk::from_json(&root)?
+            } else {
+                tiled::from_json(&root)?
+            }
+        };
+        Ok(Tilemap::build(map, origin, scale))
+    }
+
+    fn build(map: MapDef, origin: Pos2, scale: f32) -> Tilemap {
+        let world = |p: Pos2| origin + p.to_vec2() * scale;
+        let chunk_size = map.cell * CHUNK_CELLS;
+        let mut tile_count = 0;
+        let layers = map
+            .layers
+            .into_iter()
+            .map(|layer| {
+                let tint = Color32::WHITE.gamma_multiply(layer.opacity.clamp(0.0, 1.0));
+                // Keyed by tileset then chunk row and column, so chunks come out in a stable order
+                let mut meshes: BTreeMap<(usize, i64, i64), Mesh> = BTreeMap::new();
+                for tile in &layer.tiles {
+                    let chunk = (tile.position.to_vec2() / chunk_size).floor();
+                    let mesh = meshes.entry((tile.tileset, chunk.y as i64, chunk.x as i64)).or_default();
+                    let rect = Rect::from_min_max(world(tile.position), world(tile.position + tile.size));
+                    add_tile(mesh, rect, uv_corners(tile, &map.tilesets[tile.tileset]), tint);
+                }
+                tile_count += layer.tiles.len();
+                let chunks = meshes
+                    .into_iter()
+                    .map(|((tileset, _, _), mesh)| Chunk { tileset, bounds: mesh.calc_bounds(), mesh: Arc::new(mesh) })
+                    .collect();
+                let collision = layer.collision.map(|grid| Grid {
+                    origin: world(grid.origin),
+                    cell: grid.cell * scale,
+                    width: grid.width,
+                    height: grid.height,
+                    solid: grid.solid,
+                });
+                Layer { name: layer.name, visible: layer.visible, chunks, collision }
+            })
+            .collect();
+        let textures = map
+            .tilesets
+            .iter()
+            .map(|tileset| strings::intern(&assets::name_from_path(Path::new(&tileset.image)).unwrap_or_default()))
+            .collect();
+        let spawns = map.spawns.into_iter().map(|spawn| (spawn.prefab, world(spawn.position))).collect();
+        Tilemap { layers, textures, tile_count, spawns }
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.tile_count
+    }
+
+    /// Prefabs the map places, with their world centers
+    pub fn spawns(&self) -> &[(String, Pos2)] {
+        &self.spawns
+    }
+
+    /// Texture name the tiles of `tileset` are drawn from
+    pub fn texture(&self, tileset: usize) -> NameId {
+        self.textures[tileset]
+    }
+
+    /// Show or hide every layer named `name`; false if there's none
+    pub fn set_layer_visible(&mut self, name: &str, visible: bool) -> bool {
+        let mut found = false;
+        for layer in self.layers.iter_mut().filter(|layer| layer.name == name) {
+            layer.visible = visible;
+            found = true;
+        }
+        found
+    }
+
+    /// Whether a collision layer has a solid cell at world point `point`
+    pub fn is_solid(&self, point: Pos2) -> bool {
+        let mut rects = Vec::new();
+        self.solid_rects(Rect::from_min_max(point, point), &mut rects);
+        rects.iter().any(|rect| rect.contains(point))
+    }
+
+    /// Append the solid cells of every collision layer that overlap `region` (world points)
+    /// Collision layers collide whether they're visible or not
+    pub fn solid_rects(&self, region: Rect, out: &mut Vec<Rect>) {
+        for grid in self.layers.iter().filter_map(|layer| layer.collision.as_ref()) {
+            let Some(([x0, x1], [y0, y1])) = grid.cells(region) else {
+                continue;
+            };
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    if grid.solid[y * grid.width + x] {
+                        out.push(grid.cell_rect(x, y));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Texture coordinates for a tile's top-left, top-right, bottom-right and bottom-left corners
+fn uv_corners(tile: &TileDef, tileset: &TilesetDef) -> [Pos2; 4] {
+    let source = if tile.flip & FLIP_DIAGONAL != 0 { Vec2::new(tile.size.y, tile.size.x) } else { tile.size };
+    let min = tile.source.to_vec2() / tileset.image_size;
+    let max = (tile.source + source).to_vec2() / tileset.image_size;
+    let mut uv = [Pos2::new(min.x, min.y), Pos2::new(max.x, min.y), Pos2::new(max.x, max.y), Pos2::new(min.x, max.y)];
+    if tile.flip & FLIP_DIAGONAL != 0 {
+        uv.swap(1, 3);
+    }
+    if tile.flip & FLIP_X != 0 {
+        uv.swap(0, 1);
+        uv.swap(2, 3);
+    }
+    if tile.flip & FLIP_Y != 0 {
+        uv.swap(0, 3);
+        uv.swap(1, 2);
+    }
+    uv
+}
+
+fn add_tile(mesh: &mut Mesh, rect: Rect, uv: [Pos2; 4], color: Color32) {
+    let base = mesh.vertices.len() as u32;
+    let corners = [rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom()];
+    for (pos, uv) in corners.into_iter().zip(uv) {
+        mesh.vertices.push(Vertex { pos, uv, color });
+    }
+    mesh.add_triangle(base, base + 1, base + 2);
+    mesh.add_triangle(base, base + 2, base + 3);
+}
+
+/// Chunk of a tilemap resolved for this frame
+#[derive(Clone)]
+pub struct TileDraw {
+    pub map: TilemapId,
+    pub layer: usize,
+    pub texture: TextureId,
+    pub bounds: Rect,
+    pub mesh: Arc<Mesh>,
+}
+
+/// Chunks are built once and shared, so the same chunk is the same mesh
+impl PartialEq for TileDraw {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+            && self.layer == other.layer
+            && self.texture == other.texture
+            && self.bounds == other.bounds
+            && Arc::ptr_eq(&self.mesh, &other.mesh)
+    }
+}
+
+/// Cell count of a `width` x `height` grid, if it's within MAX_CELLS
+fn checked_cells(width: usize, height: usize) -> Result<usize, String> {
+    width
+        .checked_mul(height)
+        .filter(|&cells| cells <= MAX_CELLS)
+        .ok_or_else(|| format!("{}x{} cells is more than the {} a map may have", width, height, MAX_CELLS))
+}
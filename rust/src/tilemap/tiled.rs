@@ -0,0 +1,348 @@
+use egui::{Pos2, Vec2};
+
+use super::{checked_cells, GridDef, LayerDef, MapDef, SpawnDef, TileDef, TilesetDef, FLIP_DIAGONAL, FLIP_X, FLIP_Y};
+use crate::json::Value;
+use crate::xml::Element;
+
+/// Flag bits Tiled keeps in the top of a global tile id
+const GID_FLIP_X: u32 = 0x8000_0000;
+const GID_FLIP_Y: u32 = 0x4000_0000;
+const GID_FLIP_DIAGONAL: u32 = 0x2000_0000;
+/// Hexagonal 120° rotation, meaningless on orthogonal maps
+const GID_ROTATE_HEX: u32 = 0x1000_0000;
+
+struct Tileset {
+    first_gid: u32,
+    image: String,
+    image_size: Vec2,
+    tile_size: Vec2,
+    columns: u32,
+    tile_count: u32,
+    margin: f32,
+    spacing: f32,
+}
+
+struct Layer {
+    name: String,
+    visible: bool,
+    opacity: f32,
+    offset: Vec2,
+    width: usize,
+    height: usize,
+    /// Global tile ids row by row, 0 = empty
+    gids: Vec<u32>,
+    collision: bool,
+}
+
+/// What the TMX and JSON forms share, tile layers flattened out of groups bottom first
+struct Map {
+    tile_size: Vec2,
+    tilesets: Vec<Tileset>,
+    layers: Vec<Layer>,
+    /// Objects of object layers, by class (type before Tiled 1.9), else name
+    spawns: Vec<SpawnDef>,
+}
+
+/// Layer settings groups pass down to what they contain
+#[derive(Clone, Copy)]
+struct Inherited {
+    visible: bool,
+    opacity: f32,
+    offset: Vec2,
+}
+
+const TOP: Inherited = Inherited { visible: true, opacity: 1.0, offset: Vec2::ZERO };
+
+/// Collision layers are marked with a true "collision" bool property, or are named "collision"
+fn is_collision(name: &str, property: Option<bool>) -> bool {
+    property.unwrap_or_else(|| name.eq_ignore_ascii_case("collision"))
+}
+
+/// Spawn for an object at `x`,`y` of `size`; tile objects hang from their bottom-left corner,
+/// everything else from its top-left
+fn spawn(class: &str, name: &str, x: f32, y: f32, size: Vec2, tile: bool, offset: Vec2) -> Option<SpawnDef> {
+    let prefab = if class.is_empty() { name } else { class };
+    if prefab.is_empty() {
+        return None;
+    }
+    let top_left = if tile { Vec2::new(x, y - size.y) } else { Vec2::new(x, y) };
+    Some(SpawnDef { prefab: prefab.to_string(), position: (offset + top_left + size / 2.0).to_pos2() })
+}
+
+fn check_orthogonal(orientation: Option<&str>, infinite: bool) -> Result<(), String> {
+    match orientation {
+        Some("orthogonal") | None => {}
+        Some(other) => return Err(format!("{} maps aren't supported, only orthogonal", other)),
+    }
+    if infinite {
+        return Err("Infinite maps aren't supported; turn off Infinite in the map properties".to_string());
+    }
+    Ok(())
+}
+
+/// Map from Tiled's JSON format (.tmj) with embedded tilesets
+pub fn from_json(root: &Value) -> Result<MapDef, String> {
+    let number = |value: &Value, key: &str| value.get(key).and_then(Value::as_f32);
+    let required = |value: &Value, key: &str| number(value, key).ok_or_else(|| format!("Tiled map is missing '{}'", key));
+    check_orthogonal(root.get("orientation").and_then(Value::as_str), root.get("infinite").and_then(Value::as_bool) == Some(true))?;
+    let tile_size = Vec2::new(required(root, "tilewidth")?, required(root, "tileheight")?);
+
+    let mut tilesets = Vec::new();
+    for tileset in root.get("tilesets").and_then(Value::as_array).unwrap_or_default() {
+        if let Some(source) = tileset.get("source").and_then(Value::as_str) {
+            return Err(format!("External tileset '{}' must be embedded in the map", source));
+        }
+        let image = tileset.get("image").and_then(Value::as_str).ok_or("Image collection tilesets aren't supported")?;
+        tilesets.push(Tileset {
+            first_gid: required(tileset, "firstgid")? as u32,
+            image: image.to_string(),
+            image_size: Vec2::new(required(tileset, "imagewidth")?, required(tileset, "imageheight")?),
+            tile_size: Vec2::new(required(tileset, "tilewidth")?, required(tileset, "tileheight")?),
+            columns: required(tileset, "columns")? as u32,
+            tile_count: required(tileset, "tilecount")? as u32,
+            margin: number(tileset, "margin").unwrap_or(0.0),
+            spacing: number(tileset, "spacing").unwrap_or(0.0),
+        });
+    }
+
+    let mut map = Map { tile_size, tilesets, layers: Vec::new(), spawns: Vec::new() };
+    json_layers(root, TOP, &mut map)?;
+    build(map)
+}
+
+fn json_layers(parent: &Value, inherited: Inherited, out: &mut Map) -> Result<(), String> {
+    for layer in parent.get("layers").and_then(Value::as_array).unwrap_or_default() {
+        let name = layer.get("name").and_then(Value::as_str).unwrap_or_default();
+        let offset = Vec2::new(
+            layer.get("offsetx").and_then(Value::as_f32).unwrap_or(0.0),
+            layer.get("offsety").and_then(Value::as_f32).unwrap_or(0.0),
+        );
+        let inherited = Inherited {
+            visible: inherited.visible && layer.get("visible").and_then(Value::as_bool).unwrap_or(true),
+            opacity: inherited.opacity * layer.get("opacity").and_then(Value::as_f32).unwrap_or(1.0),
+            offset: inherited.offset + offset,
+        };
+        match layer.get("type").and_then(Value::as_str) {
+            Some("group") => json_layers(layer, inherited, out)?,
+            Some("tilelayer") => {
+                let width = layer.get("width").and_then(Value::as_f64).unwrap_or(0.0) as usize;
+                let height = layer.get("height").and_then(Value::as_f64).unwrap_or(0.0) as usize;
+                let gids = match (layer.get("data"), layer.get("encoding").and_then(Value::as_str)) {
+                    (Some(Value::Array(data)), _) => data.iter().map(|gid| gid.as_f64().unwrap_or(0.0) as u32).collect(),
+                    (Some(Value::String(data)), Some("base64")) => {
+                        if let Some(compression) = layer.get("compression").and_then(Value::as_str).filter(|c| !c.is_empty()) {
+                            return Err(format!("Layer '{}' is {}-compressed; save tile data uncompressed", name, compression));
+                        }
+                        base64_gids(data)?
+                    }
+                    _ => return Err(format!("Layer '{}' has no tile data", name)),
+                };
+                let property = layer
+                    .get("properties")
+                    .and_then(Value::as_array)
+                    .unwrap_or_default()
+                    .iter()
+                    .find(|p| p.get("name").and_then(Value::as_str) == Some("collision"))
+                    .and_then(|p| p.get("value"))
+                    .and_then(Value::as_bool);
+                out.layers.push(Layer {
+                    name: name.to_string(),
+                    visible: inherited.visible,
+                    opacity: inherited.opacity,
+                    offset: inherited.offset,
+                    width,
+                    height,
+                    gids,
+                    collision: is_collision(name, property),
+                });
+            }
+            Some("objectgroup") => {
+                for object in layer.get("objects").and_then(Value::as_array).unwrap_or_default() {
+                    let text = |key: &str| object.get(key).and_then(Value::as_str).unwrap_or_default();
+                    let number = |key: &str| object.get(key).and_then(Value::as_f32).unwrap_or(0.0);
+                    let class = if text("class").is_empty() { text("type") } else { text("class") };
+                    let size = Vec2::new(number("width"), number("height"));
+                    let tile = object.get("gid").is_some();
+                    out.spawns.extend(spawn(class, text("name"), number("x"), number("y"), size, tile, inherited.offset));
+                }
+            }
+            // Image layers and unknown types aren't tiles
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Map from Tiled's XML format (.tmx) with embedded tilesets and CSV or uncompressed base64 data
+pub fn from_tmx(root: &Element) -> Result<MapDef, String> {
+    if root.name != "map" {
+        return Err(format!("TMX root is <{}>, not <map>", root.name));
+    }
+    check_orthogonal(root.attribute("orientation"), root.attribute("infinite") == Some("1"))?;
+    let required = |element: &Element, key: &str| {
+        element.number(key).ok_or_else(|| format!("TMX <{}> is missing '{}'", element.name, key))
+    };
+    let tile_size = Vec2::new(required(root, "tilewidth")?, required(root, "tileheight")?);
+
+    let mut tilesets = Vec::new();
+    for tileset in root.children.iter().filter(|child| child.name == "tileset") {
+        if let Some(source) = tileset.attribute("source") {
+            return Err(format!("External tileset '{}' must be embedded in the map", source));
+        }
+        let image = tileset.child("image").ok_or("Image collection tilesets aren't supported")?;
+        tilesets.push(Tileset {
+            first_gid: required(tileset, "firstgid")? as u32,
+            image: image.attribute("source").ok_or("Tileset <image> has no source")?.to_string(),
+            image_size: Vec2::new(required(image, "width")?, required(image, "height")?),
+            tile_size: Vec2::new(required(tileset, "tilewidth")?, required(tileset, "tileheight")?),
+            columns: required(tileset, "columns")? as u32,
+            tile_count: required(tileset, "tilecount")? as u32,
+            margin: tileset.number("margin").unwrap_or(0.0),
+            spacing: tileset.number("spacing").unwrap_or(0.0),
+        });
+    }
+
+    let mut map = Map { tile_size, tilesets, layers: Vec::new(), spawns: Vec::new() };
+    tmx_layers(root, TOP, &mut map)?;
+    build(map)
+}
+
+fn tmx_layers(parent: &Element, inherited: Inherited, out: &mut Map) -> Result<(), String> {
+    for layer in &parent.children {
+        let name = layer.attribute("name").unwrap_or_default();
+        let offset = Vec2::new(layer.number("offsetx").unwrap_or(0.0), layer.number("offsety").unwrap_or(0.0));
+        let inherited = Inherited {
+            visible: inherited.visible && layer.attribute("visible") != Some("0"),
+            opacity: inherited.opacity * layer.number("opacity").unwrap_or(1.0),
+            offset: inherited.offset + offset,
+        };
+        match layer.name.as_str() {
+            "group" => tmx_layers(layer, inherited, out)?,
+            "layer" => {
+                let data = layer.child("data").ok_or_else(|| format!("Layer '{}' has no tile data", name))?;
+                if let Some(compression) = data.attribute("compression") {
+                    return Err(format!("Layer '{}' is {}-compressed; save tile data uncompressed", name, compression));
+                }
+                let gids = match data.attribute("encoding") {
+                    Some("csv") => data
+                        .text
+                        .split(',')
+                        .map(|gid| gid.trim().parse().map_err(|_| format!("Layer '{}' has a bad tile id '{}'", name, gid.trim())))
+                        .collect::<Result<_, _>>()?,
+                    Some("base64") => base64_gids(&data.text)?,
+                    _ => data.children.iter().filter(|t| t.name == "tile").map(|t| t.number("gid").unwrap_or(0.0) as u32).collect(),
+                };
+                let property = layer
+                    .child("properties")
+                    .and_then(|properties| {
+                        properties.children.iter().find(|p| p.name == "property" && p.attribute("name") == Some("collision"))
+                    })
+                    .and_then(|p| p.attribute("value"))
+                    .map(|value| value == "true");
+                out.layers.push(Layer {
+                    name: name.to_string(),
+                    visible: inherited.visible,
+                    opacity: inherited.opacity,
+                    offset: inherited.offset,
+                    width: layer.number("width").unwrap_or(0.0) as usize,
+                    height: layer.number("height").unwrap_or(0.0) as usize,
+                    gids,
+                    collision: is_collision(name, property),
+                });
+            }
+            "objectgroup" => {
+                for object in layer.children.iter().filter(|child| child.name == "object") {
+                    let text = |key: &str| object.attribute(key).unwrap_or_default();
+                    let number = |key: &str| object.number(key).unwrap_or(0.0);
+                    let class = if text("class").is_empty() { text("type") } else { text("class") };
+                    let size = Vec2::new(number("width"), number("height"));
+                    let tile = object.attribute("gid").is_some();
+                    out.spawns.extend(spawn(class, text("name"), number("x"), number("y"), size, tile, inherited.offset));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Resolve global tile ids against the tilesets into placed tiles
+fn build(map: Map) -> Result<MapDef, String> {
+    let mut tilesets = map.tilesets;
+    tilesets.sort_by_key(|tileset| tileset.first_gid);
+    let mut layers = Vec::with_capacity(map.layers.len());
+    for layer in map.layers {
+        let cells = checked_cells(layer.width, layer.height)?;
+        if layer.gids.len() != cells {
+            return Err(format!("Layer '{}' has {} tiles for {}x{} cells", layer.name, layer.gids.len(), layer.width, layer.height));
+        }
+        let mut tiles = Vec::new();
+        for (index, &gid) in layer.gids.iter().enumerate() {
+            let id = gid & !(GID_FLIP_X | GID_FLIP_Y | GID_FLIP_DIAGONAL | GID_ROTATE_HEX);
+            if id == 0 {
+                continue;
+            }
+            let tileset = tilesets.iter().rposition(|tileset| tileset.first_gid <= id);
+            let Some((tileset_index, tileset)) = tileset.map(|i| (i, &tilesets[i])) else {
+                return Err(format!("Layer '{}' uses tile {} outside every tileset", layer.name, id));
+            };
+            let local = id - tileset.first_gid;
+            if local >= tileset.tile_count || tileset.columns == 0 {
+                return Err(format!("Layer '{}' uses tile {} outside every tileset", layer.name, id));
+            }
+            let (column, row) = ((local % tileset.columns) as f32, (local / tileset.columns) as f32);
+            let source = Pos2::new(
+                tileset.margin + column * (tileset.tile_size.x + tileset.spacing),
+                tileset.margin + row * (tileset.tile_size.y + tileset.spacing),
+            );
+            // Tiles taller or wider than the grid hang off its bottom-left corner
+            let cell = Vec2::new((index % layer.width) as f32, (index / layer.width) as f32);
+            let bottom_left = layer.offset + Vec2::new(cell.x, cell.y + 1.0) * map.tile_size;
+            let flips = [(GID_FLIP_X, FLIP_X), (GID_FLIP_Y, FLIP_Y), (GID_FLIP_DIAGONAL, FLIP_DIAGONAL)];
+            tiles.push(TileDef {
+                tileset: tileset_index,
+                position: Pos2::new(bottom_left.x, bottom_left.y - tileset.tile_size.y),
+                size: tileset.tile_size,
+                source,
+                flip: flips.iter().filter(|(bit, _)| gid & bit != 0).map(|(_, flag)| flag).sum(),
+            });
+        }
+        let collision = layer.collision.then(|| GridDef {
+            origin: layer.offset.to_pos2(),
+            cell: map.tile_size,
+            width: layer.width,
+            height: layer.height,
+            solid: layer.gids.iter().map(|&gid| gid != 0).collect(),
+        });
+        layers.push(LayerDef { name: layer.name, visible: layer.visible, opacity: layer.opacity, tiles, collision });
+    }
+    let tilesets = tilesets.into_iter().map(|t| TilesetDef { image: t.image, image_size: t.image_size }).collect();
+    let spawns = map.spawns;
+    Ok(MapDef { cell: map.tile_size, tilesets, layers, spawns })
+}
+
+/// Little-endian u32 tile ids from base64 text (whitespace ignored)
+fn base64_gids(text: &str) -> Result<Vec<u32>, String> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err("Tile data isn't valid base64".to_string()),
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    if bytes.len() % 4 != 0 {
+        return Err("Base64 tile data isn't a whole number of tile ids".to_string());
+    }
+    Ok(bytes.chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+}
@@ -0,0 +1,70 @@
+/// Install the subscriber that hands the engine's `tracing` spans to the platform profiler: ATrace
+/// sections on Android (Perfetto, Android Studio's system trace) and os_log activities on iOS
+/// (Instruments), so its frames show up in captures of the host app
+/// Once per process; a subscriber the host process installed first is left in place. Without one
+/// (desktop builds, tests) a span costs a callsite check
+pub fn install() {
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    {
+        use std::sync::Once;
+
+        use tracing_subscriber::layer::SubscriberExt;
+
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            #[cfg(target_os = "android")]
+            let layer = tracing_android_trace::AndroidTraceLayer::new();
+            #[cfg(target_os = "ios")]
+            let layer = tracing_oslog::OsLogger::new("com.example.flutter_con", "PointsOfInterest");
+            if tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer)).is_err() {
+                log::info!("Trace subscriber already installed; engine spans go to it");
+            }
+        });
+    }
+}
+
+/// Name of the function a `type_name_of_val` of a nested `fn f` was taken in, e.g.
+/// "game_engine::game_render::f" -> "game_render" (closures in between are skipped)
+pub fn function_name(type_name: &'static str) -> &'static str {
+    let path = type_name.strip_suffix("::f").unwrap_or(type_name);
+    path.rsplit("::").find(|segment| !segment.starts_with('{')).unwrap_or(path)
+}
+
+/// Names of the spans `f` opened on this thread, in order; FFI spans go by their function
+#[cfg(test)]
+pub fn record(f: impl FnOnce()) -> Vec<String> {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    /// The `function` field of an FFI span
+    struct Function(Option<String>);
+
+    impl Visit for Function {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "function" {
+                self.0 = Some(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for Recorder {
+        fn on_new_span(&self, attrs: &Attributes, _: &Id, _: Context<S>) {
+            let mut function = Function(None);
+            attrs.record(&mut function);
+            let name = function.0.unwrap_or_else(|| attrs.metadata().name().to_string());
+            self.0.lock().unwrap().push(name);
+        }
+    }
+
+    let names = Arc::new(Mutex::new(Vec::new()));
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(Recorder(names.clone())), f);
+    let names = names.lock().unwrap().clone();
+    names
+}
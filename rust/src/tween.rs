@@ -0,0 +1,228 @@
+use std::f32::consts::TAU;
+
+use egui::{Color32, Vec2};
+
+use crate::arena::{Arena, Id, INVALID_ID};
+use crate::scene::{NodeId, Scene, Transform};
+use crate::sprites::{SpriteArena, SpriteId};
+
+/// Tween id handed across FFI
+pub type TweenId = Id;
+
+/// Invalid tween id returned on failure
+pub const INVALID_TWEEN: TweenId = INVALID_ID;
+
+/// Most running tweens
+pub const MAX_TWEENS: usize = 1024;
+
+/// How a tween's progress maps to the share of the change applied
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u32)]
+pub enum Easing {
+    Linear = 0,
+    /// Cubic: starts slow
+    EaseIn = 1,
+    /// Cubic: ends slow
+    EaseOut = 2,
+    /// Cubic: slow at both ends
+    EaseInOut = 3,
+    /// Overshoots and springs back before settling
+    Elastic = 4,
+    /// Drops onto the end value and bounces to rest
+    Bounce = 5,
+}
+
+impl Easing {
+    pub fn from_raw(value: u32) -> Option<Easing> {
+        match value {
+            0 => Some(Easing::Linear),
+            1 => Some(Easing::EaseIn),
+            2 => Some(Easing::EaseOut),
+            3 => Some(Easing::EaseInOut),
+            4 => Some(Easing::Elastic),
+            5 => Some(Easing::Bounce),
+            _ => None,
+        }
+    }
+
+    /// Share of the change at progress `t` (0..=1); 0 and 1 map to themselves, Elastic goes past 1
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Easing::Elastic if t <= 0.0 || t >= 1.0 => t,
+            Easing::Elastic => 2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * TAU / 3.0).sin() + 1.0,
+            Easing::Bounce => bounce(t),
+        }
+    }
+}
+
+/// Ease-out bounce: three shrinking hops after the first landing
+fn bounce(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// The entity property a tween drives; one tween per target at a time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    SpritePosition(SpriteId),
+    SpriteSize(SpriteId),
+    SpriteColor(SpriteId),
+    /// Translation, rotation and scale together
+    NodeTransform(NodeId),
+}
+
+impl Target {
+    /// The property's current value, or None if the entity is gone
+    pub fn get(self, sprites: &SpriteArena, scene: &mut Scene) -> Option<Value> {
+        Some(match self {
+            Target::SpritePosition(id) => Value::Vec2(sprites.get(id)?.position.to_vec2()),
+            Target::SpriteSize(id) => Value::Vec2(sprites.get(id)?.size),
+            Target::SpriteColor(id) => Value::Color(sprites.get(id)?.color),
+            Target::NodeTransform(id) => Value::Transform(scene.get_mut(id)?.local),
+        })
+    }
+
+    /// Write `value` to the property; false if the entity is gone
+    pub fn set(self, sprites: &mut SpriteArena, scene: &mut Scene, value: Value) -> bool {
+        match (self, value) {
+            (Target::SpritePosition(id), Value::Vec2(position)) => {
+                sprites.get_mut(id).map(|s| s.position = position.to_pos2()).is_some()
+            }
+            (Target::SpriteSize(id), Value::Vec2(size)) => sprites.get_mut(id).map(|s| s.size = size).is_some(),
+            (Target::SpriteColor(id), Value::Color(color)) => sprites.get_mut(id).map(|s| s.color = color).is_some(),
+            (Target::NodeTransform(id), Value::Transform(transform)) => {
+                scene.get_mut(id).map(|n| n.local = transform).is_some()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A value of the target's property
+#[derive(Clone, Copy, Debug)]
+pub enum Value {
+    Vec2(Vec2),
+    Color(Color32),
+    Transform(Transform),
+}
+
+impl Value {
+    /// `t` of the way from self to `to` (past `to` for t > 1); mismatched kinds jump to `to`
+    fn lerp(self, to: Value, t: f32) -> Value {
+        match (self, to) {
+            (Value::Vec2(a), Value::Vec2(b)) => Value::Vec2(a + (b - a) * t),
+            (Value::Color(a), Value::Color(b)) => Value::Color(a.lerp_to_gamma(b, t)),
+            (Value::Transform(a), Value::Transform(b)) => Value::Transform(Transform {
+                translation: a.translation + (b.translation - a.translation) * t,
+                rotation: a.rotation + (b.rotation - a.rotation) * t,
+                scale: a.scale + (b.scale - a.scale) * t,
+            }),
+            _ => to,
+        }
+    }
+}
+
+struct Tween {
+    target: Target,
+    from: Value,
+    to: Value,
+    /// Seconds
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+}
+
+/// How a tween ended (reported as EVENT_TWEEN_FINISHED)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Finished {
+    pub tween: TweenId,
+    /// False if it was cancelled, replaced by another tween on its target, or the entity went away
+    pub completed: bool,
+}
+
+/// Property animations advanced on the game tick
+#[derive(Default)]
+pub struct Tweens {
+    tweens: Arena<Tween>,
+}
+
+impl Tweens {
+    /// Animate `target` from `from` to `to` over `duration` seconds; a tween already driving the target
+    /// is interrupted and reported in `finished`
+    pub fn start(
+        &mut self,
+        target: Target,
+        from: Value,
+        to: Value,
+        duration: f32,
+        easing: Easing,
+        finished: &mut Vec<Finished>,
+    ) -> Result<TweenId, String> {
+        if !(duration.is_finite() && duration >= 0.0) {
+            return Err(format!("Invalid tween duration {}", duration));
+        }
+        let running = self.tweens.entries().find(|(_, t)| t.target == target).map(|(id, _)| id);
+        if let Some(id) = running {
+            self.tweens.remove(id);
+            finished.push(Finished { tween: id, completed: false });
+        } else if self.tweens.count() == MAX_TWEENS {
+            return Err(format!("At most {} tweens", MAX_TWEENS));
+        }
+        Ok(self.tweens.insert(Tween { target, from, to, duration, elapsed: 0.0, easing }))
+    }
+
+    /// Stop a tween where it is; false if it already finished
+    pub fn cancel(&mut self, id: TweenId, finished: &mut Vec<Finished>) -> bool {
+        let cancelled = self.tweens.remove(id).is_some();
+        if cancelled {
+            finished.push(Finished { tween: id, completed: false });
+        }
+        cancelled
+    }
+
+    pub fn count(&self) -> usize {
+        self.tweens.count()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.tweens.count() > 0
+    }
+
+    /// Advance every tween by `delta` seconds and write its value with `apply`, which returns false if
+    /// the target's entity is gone. Tweens that reached their end or lost their entity are dropped
+    pub fn update(&mut self, delta: f32, mut apply: impl FnMut(Target, Value) -> bool, finished: &mut Vec<Finished>) {
+        let mut done = Vec::new();
+        for (id, tween) in self.tweens.entries_mut() {
+            tween.elapsed += delta;
+            let t = if tween.duration > 0.0 { (tween.elapsed / tween.duration).min(1.0) } else { 1.0 };
+            let value = tween.from.lerp(tween.to, tween.easing.apply(t));
+            if !apply(tween.target, value) {
+                done.push(Finished { tween: id, completed: false });
+            } else if t >= 1.0 {
+                done.push(Finished { tween: id, completed: true });
+            }
+        }
+        for end in &done {
+            self.tweens.remove(end.tween);
+        }
+        finished.extend(done);
+    }
+}
+
@@ -0,0 +1,315 @@
+use egui::{Align, Align2, Color32, FontId, Painter, Pos2, Rect, Rounding, Shape, Vec2};
+
+use crate::arena::{Arena, Id, INVALID_ID};
+use crate::engine::TouchAction;
+use crate::text::Label;
+
+/// Id of an in-engine UI widget (game_ui_*)
+pub type WidgetId = Id;
+
+/// Invalid widget id returned on failure
+pub const INVALID_WIDGET: WidgetId = INVALID_ID;
+
+/// Space around a button's text when it's sized to fit (width or height 0)
+const BUTTON_PADDING: Vec2 = Vec2::new(16.0, 8.0);
+
+/// How much darker a button is drawn while held
+const PRESSED_SHADE: f32 = 0.75;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WidgetKind {
+    /// Text with the anchor's corner or edge of its block on the anchor point
+    Label(Label),
+    /// Text centered on a rounded `fill`
+    Button { label: Label, fill: Color32 },
+    /// A track filled `value` (0 to 1) of the way from the left in `color`
+    Progress { value: f32, color: Color32 },
+}
+
+/// A widget anchored to a corner, edge or the middle of the screen
+pub struct Widget {
+    kind: WidgetKind,
+    /// Screen point the widget is placed at, and the widget's own point put there
+    anchor: Align2,
+    /// Points in from the anchored edges (right and down from a centered anchor)
+    offset: Vec2,
+    /// Points; labels are sized by their text
+    size: Vec2,
+    visible: bool,
+}
+
+impl Widget {
+    /// Screen bounds in points
+    fn rect(&self, screen: Rect) -> Rect {
+        let pos = self.anchor_pos(screen);
+        match &self.kind {
+            WidgetKind::Label(label) => label.bounds().translate(pos.to_vec2()),
+            WidgetKind::Button { label, .. } => {
+                let fit = label.bounds().size() + BUTTON_PADDING * 2.0;
+                let size = Vec2::new(
+                    if self.size.x > 0.0 { self.size.x } else { fit.x },
+                    if self.size.y > 0.0 { self.size.y } else { fit.y },
+                );
+                self.anchor.anchor_size(pos, size)
+            }
+            WidgetKind::Progress { .. } => self.anchor.anchor_size(pos, self.size),
+        }
+    }
+
+    fn anchor_pos(&self, screen: Rect) -> Pos2 {
+        let inward = |align: Align| if align == Align::Max { -1.0 } else { 1.0 };
+        let [x, y] = self.anchor.0;
+        let offset = Vec2::new(self.offset.x * inward(x), self.offset.y * inward(y));
+        self.anchor.pos_in_rect(&screen) + offset
+    }
+
+    fn label_mut(&mut self) -> Option<&mut Label> {
+        match &mut self.kind {
+            WidgetKind::Label(label) | WidgetKind::Button { label, .. } => Some(label),
+            WidgetKind::Progress { .. } => None,
+        }
+    }
+}
+
+/// A widget resolved for this frame
+#[derive(Clone, Debug, PartialEq)]
+pub struct WidgetDraw {
+    pub widget: WidgetId,
+    /// Screen bounds in points
+    pub rect: Rect,
+    /// Buttons are shaded while held
+    pub kind: WidgetKind,
+}
+
+/// What a touch did to the UI
+#[derive(Debug, PartialEq)]
+pub enum UiTouch {
+    /// Not on a button: it goes to the game
+    Missed,
+    /// Part of a button press; the game doesn't see it
+    Consumed,
+    /// Released on the button it went down on
+    Pressed(WidgetId),
+}
+
+/// Labels, buttons and progress bars drawn above the game, in creation slot order
+#[derive(Default)]
+pub struct Ui {
+    widgets: Arena<Widget>,
+    /// Button held down, and whether the finger is still on it
+    press: Option<(WidgetId, bool)>,
+}
+
+impl Ui {
+    pub fn create_label(&mut self, label: Label, anchor: Align2, offset: Vec2) -> Result<WidgetId, String> {
+        check_text(&label)?;
+        let label = Label { align: anchor, ..label };
+        Ok(self.insert(WidgetKind::Label(label), anchor, offset, Vec2::ZERO))
+    }
+
+    /// A `size` button; a zero width or height fits the text
+    pub fn create_button(
+        &mut self,
+        label: Label,
+        fill: Color32,
+        anchor: Align2,
+        offset: Vec2,
+        size: Vec2,
+    ) -> Result<WidgetId, String> {
+        check_text(&label)?;
+        check_size(size, true)?;
+        let label = Label { align: Align2::CENTER_CENTER, ..label };
+        Ok(self.insert(WidgetKind::Button { label, fill }, anchor, offset, size))
+    }
+
+    pub fn create_progress_bar(&mut self, color: Color32, anchor: Align2, offset: Vec2, size: Vec2) -> Result<WidgetId, String> {
+        check_size(size, false)?;
+        Ok(self.insert(WidgetKind::Progress { value: 0.0, color }, anchor, offset, size))
+    }
+
+    fn insert(&mut self, kind: WidgetKind, anchor: Align2, offset: Vec2, size: Vec2) -> WidgetId {
+        let offset = if offset.is_finite() { offset } else { Vec2::ZERO };
+        self.widgets.insert(Widget { kind, anchor, offset, size, visible: true })
+    }
+
+    /// Replace a label's or button's text; false for other widgets
+    pub fn set_text(&mut self, id: WidgetId, text: &str) -> bool {
+        match self.widgets.get_mut(id).and_then(Widget::label_mut) {
+            Some(label) => {
+                if label.text != text {
+                    label.text = text.to_string();
+                    label.shaped_at = None;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set how full a progress bar is, clamped to 0..=1; false for other widgets or NaN
+    pub fn set_progress(&mut self, id: WidgetId, value: f32) -> bool {
+        match self.widgets.get_mut(id).map(|widget| &mut widget.kind) {
+            Some(WidgetKind::Progress { value: current, .. }) if !value.is_nan() => {
+                *current = value.clamp(0.0, 1.0);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Hidden widgets aren't drawn and don't take touches
+    pub fn set_visible(&mut self, id: WidgetId, visible: bool) -> bool {
+        match self.widgets.get_mut(id) {
+            Some(widget) => {
+                widget.visible = visible;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, id: WidgetId) -> bool {
+        self.widgets.remove(id).is_some()
+    }
+
+    /// Text of labels and buttons, laid out with the scene's text
+    pub fn labels(&self) -> impl Iterator<Item = &Label> {
+        self.widgets.iter().filter_map(|widget| match &widget.kind {
+            WidgetKind::Label(label) | WidgetKind::Button { label, .. } => Some(label),
+            WidgetKind::Progress { .. } => None,
+        })
+    }
+
+    pub fn labels_mut(&mut self) -> impl Iterator<Item = &mut Label> {
+        self.widgets.iter_mut().filter_map(Widget::label_mut)
+    }
+
+    /// Visible widgets on a `screen` sized screen
+    pub fn resolve(&self, screen: Rect) -> Vec<WidgetDraw> {
+        let held = match self.press {
+            Some((id, true)) => id,
+            _ => INVALID_WIDGET,
+        };
+        self.widgets
+            .entries()
+            .filter(|(_, widget)| widget.visible)
+            .map(|(id, widget)| {
+                let mut kind = widget.kind.clone();
+                if let WidgetKind::Button { fill, .. } = &mut kind {
+                    if id == held {
+                        *fill = shade(*fill);
+                    }
+                }
+                WidgetDraw { widget: id, rect: widget.rect(screen), kind }
+            })
+            .collect()
+    }
+
+    /// Route a touch at screen point `pos`: one that goes down on a button is the UI's until it's
+    /// lifted, and presses the button if lifted on it
+    pub fn touch(&mut self, screen: Rect, pos: Pos2, action: TouchAction) -> UiTouch {
+        match action {
+            TouchAction::Down => {
+                // Topmost (last drawn) first
+                let hit = self.widgets.entries().filter(|(_, widget)| is_button_at(widget, screen, pos)).last();
+                match hit {
+                    Some((id, _)) => {
+                        self.press = Some((id, true));
+                        UiTouch::Consumed
+                    }
+                    None => UiTouch::Missed,
+                }
+            }
+            TouchAction::Move => match &mut self.press {
+                Some((id, inside)) => {
+                    *inside = self.widgets.get(*id).is_some_and(|widget| is_button_at(widget, screen, pos));
+                    UiTouch::Consumed
+                }
+                None => UiTouch::Missed,
+            },
+            TouchAction::Up => match self.press.take() {
+                Some((id, _)) if self.widgets.get(id).is_some_and(|widget| is_button_at(widget, screen, pos)) => {
+                    UiTouch::Pressed(id)
+                }
+                Some(_) => UiTouch::Consumed,
+                None => UiTouch::Missed,
+            },
+        }
+    }
+}
+
+fn is_button_at(widget: &Widget, screen: Rect, pos: Pos2) -> bool {
+    widget.visible && matches!(widget.kind, WidgetKind::Button { .. }) && widget.rect(screen).contains(pos)
+}
+
+fn check_text(label: &Label) -> Result<(), String> {
+    if !(label.size > 0.0 && label.size.is_finite()) {
+        return Err(format!("Invalid text size {}", label.size));
+    }
+    Ok(())
+}
+
+fn check_size(size: Vec2, zero_fits: bool) -> Result<(), String> {
+    let valid = |v: f32| v.is_finite() && (v > 0.0 || (zero_fits && v == 0.0));
+    if !(valid(size.x) && valid(size.y)) {
+        return Err(format!("Invalid widget size {}x{}", size.x, size.y));
+    }
+    Ok(())
+}
+
+fn shade(color: Color32) -> Color32 {
+    let [r, g, b, a] = color.to_array();
+    let dim = |c: u8| (c as f32 * PRESSED_SHADE) as u8;
+    Color32::from_rgba_premultiplied(dim(r), dim(g), dim(b), a)
+}
+
+/// Shapes `paint` adds for `widgets`
+pub fn shape_count(widgets: &[WidgetDraw]) -> usize {
+    usize::from(!widgets.is_empty())
+}
+
+/// Paint widgets as one shape (the renderer splits the HUD pass off by shape count)
+pub fn paint(widgets: &[WidgetDraw], painter: &Painter) {
+    if widgets.is_empty() {
+        return;
+    }
+    let mut shapes = Vec::new();
+    for draw in widgets {
+        match &draw.kind {
+            WidgetKind::Label(label) => {
+                text_shapes(painter, label, draw.rect.min - label.bounds().min.to_vec2(), &mut shapes);
+            }
+            WidgetKind::Button { label, fill } => {
+                let rounding = Rounding::same(draw.rect.size().min_elem() / 4.0);
+                shapes.push(Shape::rect_filled(draw.rect, rounding, *fill));
+                text_shapes(painter, label, draw.rect.center(), &mut shapes);
+            }
+            &WidgetKind::Progress { value, color } => {
+                let rounding = Rounding::same(draw.rect.height() / 2.0);
+                shapes.push(Shape::rect_filled(draw.rect, rounding, color.gamma_multiply(0.3)));
+                if value > 0.0 {
+                    let filled = Rect::from_min_size(draw.rect.min, Vec2::new(draw.rect.width() * value, draw.rect.height()));
+                    shapes.push(Shape::rect_filled(filled, rounding, color));
+                }
+            }
+        }
+    }
+    painter.add(Shape::Vec(shapes));
+}
+
+/// A label's glyphs with its origin at `origin`, or egui's own text without fonts
+fn text_shapes(painter: &Painter, label: &Label, origin: Pos2, shapes: &mut Vec<Shape>) {
+    match &label.glyphs {
+        Some(glyphs) => {
+            for glyph_mesh in glyphs.iter() {
+                let mut mesh = glyph_mesh.clone();
+                mesh.translate(origin.to_vec2());
+                shapes.push(Shape::mesh(mesh));
+            }
+        }
+        None => {
+            let font = FontId::proportional(label.size);
+            shapes.push(painter.fonts(|fonts| Shape::text(fonts, origin, label.align, &label.text, font, label.color)));
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+use egui::epaint::{Mesh, Vertex, WHITE_UV};
+use egui::{Color32, Pos2, Rect};
+use lyon_tessellation::math::{point, Point};
+use lyon_tessellation::path::iterator::PathIterator;
+use lyon_tessellation::path::Event;
+use lyon_tessellation::{
+    BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator, StrokeVertex,
+    VertexBuffers,
+};
+
+use crate::scene::Affine;
+
+pub use lyon_tessellation::path::Path;
+pub use lyon_tessellation::{FillRule, LineCap, LineJoin};
+
+/// Max distance between a curve and its flattened polyline, in points
+const TOLERANCE: f32 = 0.1;
+
+/// Longest dash pattern accepted (entries alternate dash and gap lengths)
+pub const MAX_DASHES: usize = 16;
+
+/// Most color stops in a gradient
+pub const MAX_STOPS: usize = 16;
+
+/// Gradient meshes are subdivided until edges are at most this fraction of the gradient's length
+const GRADIENT_STEPS: f32 = 24.0;
+
+/// Subdivision stops before a gradient mesh grows past this many triangles
+const MAX_GRADIENT_TRIANGLES: usize = 1 << 16;
+
+/// Outline made of move/line/quad/cubic segments, in points
+/// Subpaths start at move_to; drawing without one starts at the last point (or the origin)
+#[derive(Default)]
+pub struct VectorPath {
+    builder: Option<lyon_tessellation::path::path::Builder>,
+    open: bool,
+    current: Point,
+}
+
+impl VectorPath {
+    fn builder(&mut self) -> &mut lyon_tessellation::path::path::Builder {
+        let builder = self.builder.get_or_insert_with(Path::builder);
+        if !self.open {
+            builder.begin(self.current);
+            self.open = true;
+        }
+        builder
+    }
+
+    pub fn move_to(&mut self, to: Pos2) {
+        self.end(false);
+        self.current = point(to.x, to.y);
+        self.builder();
+    }
+
+    pub fn line_to(&mut self, to: Pos2) {
+        self.builder().line_to(point(to.x, to.y));
+        self.current = point(to.x, to.y);
+    }
+
+    pub fn quad_to(&mut self, ctrl: Pos2, to: Pos2) {
+        self.builder().quadratic_bezier_to(point(ctrl.x, ctrl.y), point(to.x, to.y));
+        self.current = point(to.x, to.y);
+    }
+
+    pub fn cubic_to(&mut self, ctrl1: Pos2, ctrl2: Pos2, to: Pos2) {
+        self.builder().cubic_bezier_to(point(ctrl1.x, ctrl1.y), point(ctrl2.x, ctrl2.y), point(to.x, to.y));
+        self.current = point(to.x, to.y);
+    }
+
+    /// Close the current subpath back to its start
+    pub fn close(&mut self) {
+        self.end(true);
+    }
+
+    fn end(&mut self, close: bool) {
+        if self.open {
+            if let Some(builder) = &mut self.builder {
+                builder.end(close);
+            }
+            self.open = false;
+        }
+    }
+
+    pub fn build(mut self) -> Path {
+        self.end(false);
+        self.builder.map_or_else(|| Path::builder().build(), |builder| builder.build())
+    }
+}
+
+/// How an outline is stroked
+#[derive(Clone, Debug)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub color: Color32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Alternating dash and gap lengths in points; empty for a solid line
+    pub dashes: Vec<f32>,
+    /// Distance into the pattern at which each subpath starts
+    pub dash_offset: f32,
+}
+
+impl StrokeStyle {
+    pub fn solid(width: f32, color: Color32) -> Self {
+        StrokeStyle { width, color, cap: LineCap::Butt, join: LineJoin::Miter, dashes: Vec::new(), dash_offset: 0.0 }
+    }
+}
+
+/// Fill `path` with `color`; overlapping and self-intersecting regions follow `rule`
+pub fn fill(path: &Path, rule: FillRule, color: Color32) -> Result<Mesh, String> {
+    let mut buffers: VertexBuffers<Pos2, u32> = VertexBuffers::new();
+    let options = FillOptions::tolerance(TOLERANCE).with_fill_rule(rule);
+    FillTessellator::new()
+        .tessellate_path(path, &options, &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| to_pos(v.position())))
+        .map_err(|e| format!("Fill failed: {:?}", e))?;
+    Ok(to_mesh(buffers, color))
+}
+
+/// Stroke `path` with `style`, dashed if the style has a pattern
+pub fn stroke(path: &Path, style: &StrokeStyle) -> Result<Mesh, String> {
+    let dashed;
+    let path = if style.dashes.iter().any(|&d| d > 0.0) {
+        dashed = dash(path, &style.dashes, style.dash_offset);
+        &dashed
+    } else {
+        path
+    };
+    let mut buffers: VertexBuffers<Pos2, u32> = VertexBuffers::new();
+    let options = StrokeOptions::tolerance(TOLERANCE)
+        .with_line_width(style.width)
+        .with_line_cap(style.cap)
+        .with_line_join(style.join);
+    StrokeTessellator::new()
+        .tessellate_path(path, &options, &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| to_pos(v.position())))
+        .map_err(|e| format!("Stroke failed: {:?}", e))?;
+    Ok(to_mesh(buffers, style.color))
+}
+
+/// How a filled or stroked area is colored beyond a flat color; positions are in the path's own coordinates
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    /// Stops are (offset along start -> end, color) in ascending order; the end colors extend past them
+    Linear { start: Pos2, end: Pos2, stops: Vec<(f32, Color32)> },
+    /// Stop offsets run from the center (0) to `radius` (1)
+    Radial { center: Pos2, radius: f32, stops: Vec<(f32, Color32)> },
+    /// The texture's full image spans `rect`, multiplied by `tint`; the mesh gets its texture id from the caller
+    Texture { rect: Rect, tint: Color32 },
+}
+
+/// Color or map `mesh` (tessellated under `transform`) with `paint`
+/// Gradients subdivide the mesh evenly so per-vertex colors follow the stops closely
+pub fn apply_paint(mesh: &mut Mesh, paint: &Paint, transform: &Affine) {
+    let Some(to_paint) = transform.inverse() else {
+        mesh.clear();
+        return;
+    };
+    let local = |pos: Pos2| to_paint.apply(pos);
+    match paint {
+        Paint::Linear { start, end, stops } => {
+            let length = (transform.apply(*end) - transform.apply(*start)).length();
+            subdivide(mesh, length / GRADIENT_STEPS);
+            let axis = *end - *start;
+            let length_sq = axis.length_sq().max(f32::EPSILON);
+            for v in &mut mesh.vertices {
+                v.color = sample(stops, (local(v.pos) - *start).dot(axis) / length_sq);
+            }
+        }
+        Paint::Radial { center, radius, stops } => {
+            subdivide(mesh, radius * transform.area_scale().sqrt() / GRADIENT_STEPS);
+            let radius = radius.max(f32::EPSILON);
+            for v in &mut mesh.vertices {
+                v.color = sample(stops, (local(v.pos) - *center).length() / radius);
+            }
+        }
+        Paint::Texture { rect, tint } => {
+            for v in &mut mesh.vertices {
+                let uv = (local(v.pos) - rect.min) / rect.size();
+                v.uv = uv.to_pos2();
+                v.color = *tint;
+            }
+        }
+    }
+}
+
+/// Gradient color at `t`, interpolated between the surrounding stops
+fn sample(stops: &[(f32, Color32)], t: f32) -> Color32 {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return Color32::TRANSPARENT;
+    };
+    if t <= first.0 {
+        return first.1;
+    }
+    for pair in stops.windows(2) {
+        let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+        if t <= t1 {
+            let span = t1 - t0;
+            return if span > 0.0 { c0.lerp_to_gamma(c1, (t - t0) / span) } else { c1 };
+        }
+    }
+    last.1
+}
+
+/// Split every triangle into four (sharing edge midpoints, so no cracks open up) until no edge is
+/// longer than `max_edge`, or the triangle budget runs out
+fn subdivide(mesh: &mut Mesh, max_edge: f32) {
+    if max_edge.is_nan() || max_edge <= 0.0 {
+        return;
+    }
+    let longest = mesh
+        .indices
+        .chunks_exact(3)
+        .flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+        .map(|(a, b)| (mesh.vertices[a as usize].pos - mesh.vertices[b as usize].pos).length())
+        .fold(0.0, f32::max);
+    let mut edge = longest;
+    while edge > max_edge && mesh.indices.len() / 3 * 4 <= MAX_GRADIENT_TRIANGLES {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut indices = Vec::with_capacity(mesh.indices.len() * 4);
+        for t in mesh.indices.chunks_exact(3) {
+            let mut mid = |a: u32, b: u32| {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    let (va, vb) = (mesh.vertices[a as usize], mesh.vertices[b as usize]);
+                    mesh.vertices.push(Vertex {
+                        pos: va.pos.lerp(vb.pos, 0.5),
+                        uv: va.uv.lerp(vb.uv, 0.5),
+                        color: va.color,
+                    });
+                    mesh.vertices.len() as u32 - 1
+                })
+            };
+            let (ab, bc, ca) = (mid(t[0], t[1]), mid(t[1], t[2]), mid(t[2], t[0]));
+            indices.extend_from_slice(&[t[0], ab, ca, ab, t[1], bc, ca, bc, t[2], ab, bc, ca]);
+        }
+        mesh.indices = indices;
+        edge /= 2.0;
+    }
+}
+
+/// Bounding box of a mesh's vertices (None for an empty mesh)
+pub fn bounds(mesh: &Mesh) -> Option<Rect> {
+    (!mesh.vertices.is_empty()).then(|| Rect::from_points(&mesh.vertices.iter().map(|v| v.pos).collect::<Vec<_>>()))
+}
+
+/// The parts of `path` under the dashes of `pattern`, as open subpaths
+/// Curves are flattened first; the pattern restarts at `offset` for every subpath
+fn dash(path: &Path, pattern: &[f32], offset: f32) -> Path {
+    let pattern: Vec<f32> = pattern.iter().map(|d| d.max(0.0)).collect();
+    let period: f32 = pattern.iter().sum();
+    let mut out = Path::builder();
+    let mut state = DashState::new(&pattern, offset, period);
+    let mut drawing = false;
+    for event in path.iter().flattened(TOLERANCE) {
+        let (from, to) = match event {
+            Event::Begin { at } => {
+                state = DashState::new(&pattern, offset, period);
+                if state.on {
+                    out.begin(at);
+                    drawing = true;
+                }
+                continue;
+            }
+            Event::Line { from, to } => (from, to),
+            Event::End { last, first, close: true } => (last, first),
+            Event::End { .. } => {
+                if drawing {
+                    out.end(false);
+                    drawing = false;
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        let length = (to - from).length();
+        let mut walked = 0.0;
+        while walked < length {
+            let step = state.remaining.min(length - walked);
+            walked += step;
+            let at = from.lerp(to, walked / length);
+            if state.on {
+                out.line_to(at);
+            }
+            state.remaining -= step;
+            if state.remaining <= 0.0 {
+                state.advance(&pattern);
+                if state.on {
+                    out.begin(at);
+                } else {
+                    out.end(false);
+                }
+                drawing = state.on;
+            }
+        }
+        if matches!(event, Event::End { .. }) && drawing {
+            out.end(false);
+            drawing = false;
+        }
+    }
+    if drawing {
+        out.end(false);
+    }
+    out.build()
+}
+
+/// Position in a dash pattern
+struct DashState {
+    index: usize,
+    /// Length left in the current entry
+    remaining: f32,
+    /// In a dash rather than a gap; entries alternate, so odd-length patterns swap on every repeat
+    on: bool,
+}
+
+impl DashState {
+    fn new(pattern: &[f32], offset: f32, period: f32) -> DashState {
+        let mut state = DashState { index: 0, remaining: pattern[0], on: true };
+        if period <= 0.0 {
+            return state;
+        }
+        // Patterns of odd length repeat twice per cycle, with dashes and gaps swapped the second time
+        let cycle = if pattern.len().is_multiple_of(2) { period } else { period * 2.0 };
+        let mut skip = offset.rem_euclid(cycle);
+        while skip >= state.remaining {
+            skip -= state.remaining;
+            state.advance(pattern);
+        }
+        state.remaining -= skip;
+        state
+    }
+
+    fn advance(&mut self, pattern: &[f32]) {
+        self.index += 1;
+        self.on = !self.on;
+        self.remaining = pattern[self.index % pattern.len()];
+    }
+}
+
+fn to_pos(p: Point) -> Pos2 {
+    Pos2::new(p.x, p.y)
+}
+
+fn to_mesh(buffers: VertexBuffers<Pos2, u32>, color: Color32) -> Mesh {
+    let vertices = buffers.vertices.into_iter().map(|pos| Vertex { pos, uv: WHITE_UV, color }).collect();
+    Mesh { indices: buffers.indices, vertices, ..Default::default() }
+}
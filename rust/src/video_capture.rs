@@ -0,0 +1,208 @@
+use std::ffi::c_void;
+use std::time::Instant;
+
+use glow::HasContext;
+
+/// Receives each recorded frame: tightly packed RGBA8, top row first, `width * 4` bytes per row
+/// `pixels` is only valid during the call; copy it (or feed the encoder) before returning
+pub type FrameCallback =
+    unsafe extern "C" fn(user_data: *mut c_void, pixels: *const u8, width: u32, height: u32, timestamp_ns: u64);
+
+/// Frames in flight when the host doesn't pick a ring size
+pub const DEFAULT_RING_SIZE: usize = 3;
+
+/// Most frames in flight; each holds a full-screen RGBA buffer on the GPU
+pub const MAX_RING_SIZE: usize = 8;
+
+/// Longest wait for a frame the ring has to reuse (nanoseconds)
+const WAIT_TIMEOUT_NS: i32 = 1_000_000_000;
+
+struct Slot {
+    buffer: glow::Buffer,
+    /// Set while the slot holds a frame that hasn't been delivered
+    pending: bool,
+    /// Signals when the readback into `buffer` is done (None if fences failed; mapping then stalls)
+    fence: Option<glow::Fence>,
+    timestamp_ns: u64,
+}
+
+/// Streams rendered frames to the host (MediaCodec, AVAssetWriter) through a ring of pixel pack buffers
+/// Each frame's readback is queued on the GPU and delivered a few frames later, so recording doesn't stall
+/// rendering; frames are never dropped, a full ring waits for its oldest frame instead
+/// Needs GL ES 3 (pixel pack buffers and fences)
+pub struct VideoCapture {
+    callback: FrameCallback,
+    user_data: *mut c_void,
+    ring_size: usize,
+    slots: Vec<Slot>,
+    /// Framebuffer size the slots were allocated for
+    size: [u32; 2],
+    /// Slot the next frame goes into; also the oldest queued frame
+    next: usize,
+    start: Instant,
+    /// Flipped copy handed to the callback
+    staging: Vec<u8>,
+}
+
+impl VideoCapture {
+    /// `ring_size` 0 picks DEFAULT_RING_SIZE; others are clamped to 2..=MAX_RING_SIZE
+    pub fn new(callback: FrameCallback, user_data: *mut c_void, ring_size: usize) -> VideoCapture {
+        let ring_size = match ring_size {
+            0 => DEFAULT_RING_SIZE,
+            n => n.clamp(2, MAX_RING_SIZE),
+        };
+        VideoCapture {
+            callback,
+            user_data,
+            ring_size,
+            slots: Vec::new(),
+            size: [0, 0],
+            next: 0,
+            start: Instant::now(),
+            staging: Vec::new(),
+        }
+    }
+
+    /// True if the context has what the ring needs
+    pub fn is_supported(gl: &glow::Context) -> bool {
+        gl.version().major >= 3
+    }
+
+    /// Queue a readback of the bound framebuffer (`size` in pixels) and deliver frames that finished
+    pub fn capture_frame(&mut self, gl: &glow::Context, size: [u32; 2]) {
+        if size != self.size {
+            // Frames queued at the old size still go out
+            self.finish(gl);
+            self.size = size;
+        }
+        if size[0] == 0 || size[1] == 0 {
+            return;
+        }
+        if self.slots.is_empty() {
+            if let Err(e) = self.allocate(gl) {
+                log::warn!("Video capture buffers: {}", e);
+                self.release(gl);
+                return;
+            }
+        }
+
+        self.deliver_ready(gl);
+        if self.slots[self.next].pending {
+            // Ring full: wait for the oldest frame rather than drop it
+            self.deliver(gl, self.next);
+        }
+
+        let timestamp_ns = self.start.elapsed().as_nanos() as u64;
+        let slot = &mut self.slots[self.next];
+        unsafe {
+            gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(slot.buffer));
+            gl.read_pixels(
+                0,
+                0,
+                size[0] as i32,
+                size[1] as i32,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::BufferOffset(0),
+            );
+            gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+            slot.fence = gl.fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0).ok();
+        }
+        slot.pending = true;
+        slot.timestamp_ns = timestamp_ns;
+        self.next = (self.next + 1) % self.slots.len();
+    }
+
+    /// Deliver every queued frame, waiting for the GPU, and free the buffers
+    pub fn finish(&mut self, gl: &glow::Context) {
+        for i in 0..self.slots.len() {
+            let index = (self.next + i) % self.slots.len();
+            if self.slots[index].pending {
+                self.deliver(gl, index);
+            }
+        }
+        self.release(gl);
+    }
+
+    /// Deliver queued frames oldest first, stopping at the first the GPU hasn't finished
+    fn deliver_ready(&mut self, gl: &glow::Context) {
+        for i in 0..self.slots.len() {
+            let index = (self.next + i) % self.slots.len();
+            let slot = &self.slots[index];
+            if !slot.pending {
+                continue;
+            }
+            let ready = slot.fence.is_none_or(|fence| unsafe { gl.get_sync_status(fence) == glow::SIGNALED });
+            if !ready {
+                return;
+            }
+            self.deliver(gl, index);
+        }
+    }
+
+    /// Map one slot (waiting for its readback) and hand it to the callback
+    fn deliver(&mut self, gl: &glow::Context, index: usize) {
+        let [width, height] = self.size;
+        let row_bytes = width as usize * 4;
+        let len = row_bytes * height as usize;
+        let slot = &mut self.slots[index];
+        slot.pending = false;
+
+        unsafe {
+            if let Some(fence) = slot.fence.take() {
+                let status = gl.client_wait_sync(fence, glow::SYNC_FLUSH_COMMANDS_BIT, WAIT_TIMEOUT_NS);
+                gl.delete_sync(fence);
+                if status == glow::TIMEOUT_EXPIRED || status == glow::WAIT_FAILED {
+                    log::warn!("Video capture frame not ready, dropped");
+                    return;
+                }
+            }
+
+            gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(slot.buffer));
+            let mapped = gl.map_buffer_range(glow::PIXEL_PACK_BUFFER, 0, len as i32, glow::MAP_READ_BIT);
+            if mapped.is_null() {
+                gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+                log::warn!("Video capture buffer could not be mapped");
+                return;
+            }
+            // GL reads bottom row first; encoders expect top row first
+            let pixels = std::slice::from_raw_parts(mapped, len);
+            self.staging.clear();
+            for row in pixels.chunks_exact(row_bytes).rev() {
+                self.staging.extend_from_slice(row);
+            }
+            gl.unmap_buffer(glow::PIXEL_PACK_BUFFER);
+            gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+
+            (self.callback)(self.user_data, self.staging.as_ptr(), width, height, slot.timestamp_ns);
+        }
+    }
+
+    fn allocate(&mut self, gl: &glow::Context) -> Result<(), String> {
+        let len = self.size[0] as usize * self.size[1] as usize * 4;
+        let len = i32::try_from(len).map_err(|_| "Frame too large")?;
+        for _ in 0..self.ring_size {
+            unsafe {
+                let buffer = gl.create_buffer()?;
+                gl.bind_buffer(glow::PIXEL_PACK_BUFFER, Some(buffer));
+                gl.buffer_data_size(glow::PIXEL_PACK_BUFFER, len, glow::STREAM_READ);
+                gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+                self.slots.push(Slot { buffer, pending: false, fence: None, timestamp_ns: 0 });
+            }
+        }
+        self.next = 0;
+        Ok(())
+    }
+
+    fn release(&mut self, gl: &glow::Context) {
+        for slot in self.slots.drain(..) {
+            unsafe {
+                if let Some(fence) = slot.fence {
+                    gl.delete_sync(fence);
+                }
+                gl.delete_buffer(slot.buffer);
+            }
+        }
+        self.next = 0;
+    }
+}
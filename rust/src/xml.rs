@@ -0,0 +1,204 @@
+/// Just enough XML for TMX maps: elements, attributes and text. The prolog, comments, doctypes and
+/// processing instructions are skipped; CDATA is text
+pub struct Element {
+    pub name: String,
+    attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Text directly inside the element, entities decoded
+    pub text: String,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Attribute parsed as a number; None if missing or not a number
+    pub fn number(&self, name: &str) -> Option<f32> {
+        self.attribute(name)?.trim().parse().ok()
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+}
+
+/// Nesting limit so hostile input can't overflow the stack
+const MAX_DEPTH: usize = 64;
+
+/// Parse a document into its root element
+pub fn parse(text: &str) -> Result<Element, String> {
+    let mut parser = Parser { text, pos: 0 };
+    parser.skip_misc()?;
+    let root = parser.element(0)?;
+    parser.skip_misc()?;
+    if parser.pos < text.len() {
+        return Err(parser.error("content after the root element"));
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> String {
+        format!("XML: {} at byte {}", message, self.pos)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    /// Move past `end`, which must follow
+    fn skip_past(&mut self, end: &str) -> Result<(), String> {
+        match self.rest().find(end) {
+            Some(at) => {
+                self.pos += at + end.len();
+                Ok(())
+            }
+            None => Err(self.error(&format!("missing '{}'", end))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skip whitespace, comments, processing instructions and doctypes
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") && !self.rest().starts_with("<![CDATA[") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let rest = self.rest();
+        let end = rest.find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=')).unwrap_or(rest.len());
+        if end == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += end;
+        Ok(rest[..end].to_string())
+    }
+
+    fn element(&mut self, depth: usize) -> Result<Element, String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected an element"));
+        }
+        self.pos += 1;
+        let name = self.name()?;
+        let mut element = Element { name, attributes: Vec::new(), children: Vec::new(), text: String::new() };
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("expected '='"));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.error("expected a quoted value")),
+            };
+            self.pos += 1;
+            let end = self.rest().find(quote).ok_or_else(|| self.error("unterminated value"))?;
+            let value = unescape(&self.rest()[..end]);
+            self.pos += end + 1;
+            element.attributes.push((key, value));
+        }
+
+        loop {
+            let rest = self.rest();
+            let text_end = rest.find('<').ok_or_else(|| self.error(&format!("unclosed <{}>", element.name)))?;
+            element.text.push_str(&unescape(&rest[..text_end]));
+            self.pos += text_end;
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(self.error(&format!("</{}> closes <{}>", name, element.name)));
+                }
+                self.skip_whitespace();
+                self.skip_past(">")?;
+                return Ok(element);
+            }
+            if self.rest().starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let end = self.rest().find("]]>").ok_or_else(|| self.error("unterminated CDATA"))?;
+                element.text.push_str(&self.rest()[..end]);
+                self.pos += end + 3;
+            } else if self.rest().starts_with("<!--") || self.rest().starts_with("<?") {
+                self.skip_misc()?;
+            } else {
+                element.children.push(self.element(depth + 1)?);
+            }
+        }
+    }
+}
+
+/// Decode the predefined entities and character references; unknown ones are kept as written
+fn unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let decoded = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            entity => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,134 @@
+//! Export surface audit: every #[no_mangle] function must be listed in exports.txt and
+//! every listed symbol must exist, so nothing is exported by accident
+
+use std::collections::BTreeSet;
+use std::fs;
+
+fn allowlist() -> BTreeSet<String> {
+    fs::read_to_string("exports.txt")
+        .expect("read exports.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Names of functions marked #[no_mangle] in the crate sources, module directories included
+fn no_mangle_functions() -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for (path, source) in rust_sources("src") {
+        let mut lines = source.lines().map(str::trim);
+        while let Some(line) = lines.next() {
+            if line != "#[no_mangle]" {
+                continue;
+            }
+            let signature = lines.next().unwrap_or_default();
+            let name = signature
+                .split_once("fn ")
+                .and_then(|(_, rest)| rest.split(['(', '<']).next())
+                .unwrap_or_else(|| panic!("{}: #[no_mangle] on a non-function: {}", path.display(), signature));
+            names.insert(name.to_string());
+        }
+    }
+    names
+}
+
+#[test]
+fn exports_match_allowlist() {
+    let allowed = allowlist();
+    let exported = no_mangle_functions();
+
+    let unlisted: Vec<_> = exported.difference(&allowed).collect();
+    let missing: Vec<_> = allowed.difference(&exported).collect();
+    assert!(unlisted.is_empty(), "#[no_mangle] functions missing from exports.txt: {:?}", unlisted);
+    assert!(missing.is_empty(), "exports.txt lists symbols with no #[no_mangle] function: {:?}", missing);
+}
+
+#[test]
+fn c_symbols_are_prefixed() {
+    for symbol in allowlist() {
+        assert!(
+            symbol.starts_with("game_") || symbol.starts_with("Java_com_example_flutter_1con_GameNative_"),
+            "exported symbol '{}' is outside the game_* / GameNative namespaces",
+            symbol
+        );
+    }
+}
+
+#[test]
+fn header_declares_c_api() {
+    let header = fs::read_to_string("include/game_api.h").expect("read game_api.h");
+    for symbol in allowlist().iter().filter(|s| s.starts_with("game_")) {
+        assert!(header.contains(&format!(" {}(", symbol)), "game_api.h does not declare {}", symbol);
+    }
+}
+
+/// The generated header is valid C and C++ ($CC / $CXX, else cc / c++), and nullable callbacks are
+/// typedefs rather than the opaque Option_* structs cbindgen emits for unmapped ones (see cbindgen.toml)
+#[test]
+fn header_compiles() {
+    let header = fs::read_to_string("include/game_api.h").expect("read game_api.h");
+    let opaque: Vec<&str> = header.lines().filter(|line| line.contains("Option_")).collect();
+    assert!(opaque.is_empty(), "game_api.h passes Option<..> types by value: {:?}", opaque);
+
+    for (compiler, default, language) in [("CC", "cc", "c"), ("CXX", "c++", "c++")] {
+        let compiler = std::env::var(compiler).unwrap_or_else(|_| default.to_string());
+        let output = std::process::Command::new(&compiler)
+            .args(["-fsyntax-only", "-Wall", "-Wextra", "-Werror", "-x", language, "include/game_api.h"])
+            .output()
+            .unwrap_or_else(|e| panic!("run {}: {}", compiler, e));
+        assert!(output.status.success(), "{}:\n{}", compiler, String::from_utf8_lossy(&output.stderr));
+    }
+}
+
+/// Every struct in an extern "C" signature must have a C layout for cbindgen and the hosts
+#[test]
+fn ffi_structs_are_repr_c() {
+    let sources = rust_sources("src");
+    let lib = fs::read_to_string("src/lib.rs").expect("read lib.rs");
+
+    let mut types = BTreeSet::new();
+    for (start, _) in lib.match_indices("pub extern \"C\" fn ") {
+        let rest = &lib[start..];
+        let signature = &rest[..rest.find('{').unwrap_or(rest.len())];
+        let signature = &signature[signature.find('(').unwrap_or(0)..];
+        for word in signature.split(|c: char| !c.is_alphanumeric() && c != '_') {
+            if word.starts_with(|c: char| c.is_ascii_uppercase()) && word != "Option" {
+                types.insert(word.to_string());
+            }
+        }
+    }
+
+    for name in types {
+        // Aliases of integers, pointers and function pointers are C types already
+        if sources.iter().any(|(_, s)| s.contains(&format!("pub type {} =", name))) {
+            continue;
+        }
+        let repr_c = sources.iter().any(|(_, s)| {
+            let lines: Vec<&str> = s.lines().map(str::trim).collect();
+            lines.iter().enumerate().any(|(i, line)| {
+                line.starts_with(&format!("pub struct {} ", name))
+                    && lines[i.saturating_sub(3)..i].contains(&"#[repr(C)]")
+            })
+        });
+        assert!(repr_c, "{} crosses the FFI boundary without #[repr(C)]", name);
+    }
+}
+
+/// Path and contents of every .rs file under `dir`, including module directories
+fn rust_sources(dir: &str) -> Vec<(std::path::PathBuf, String)> {
+    let mut sources = Vec::new();
+    let mut dirs = vec![std::path::PathBuf::from(dir)];
+    while let Some(dir) = dirs.pop() {
+        for path in fs::read_dir(&dir).expect("read source dir").map(|entry| entry.expect("dir entry").path()) {
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let source = fs::read_to_string(&path).expect("read source");
+                sources.push((path, source));
+            }
+        }
+    }
+    sources
+}